@@ -0,0 +1 @@
+ALTER TYPE deploy_schema DROP ATTRIBUTE IF EXISTS token_decimals;
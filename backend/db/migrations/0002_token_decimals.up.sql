@@ -0,0 +1 @@
+ALTER TYPE deploy_schema ADD ATTRIBUTE token_decimals SMALLINT;
@@ -0,0 +1,24 @@
+CREATE TABLE user_notifications (
+    wallet TEXT PRIMARY KEY,
+    email TEXT,
+    email_verified BOOL NOT NULL DEFAULT false,
+    verification_token UUID,
+    notify_on_buy BOOL NOT NULL DEFAULT true,
+    notify_on_sell BOOL NOT NULL DEFAULT true,
+    notify_on_graduation BOOL NOT NULL DEFAULT true,
+    notify_on_dev_unlock BOOL NOT NULL DEFAULT true
+);
+
+CREATE UNIQUE INDEX idx_user_notifications_verification_token
+    ON user_notifications(verification_token);
+
+CREATE TABLE notification_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    wallet TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    success BOOL NOT NULL,
+    error TEXT
+);
+
+CREATE INDEX idx_notification_deliveries_wallet ON notification_deliveries(wallet);
@@ -0,0 +1 @@
+DROP SEQUENCE IF EXISTS project_id_seq;
@@ -0,0 +1 @@
+CREATE SEQUENCE IF NOT EXISTS project_id_seq AS BIGINT START WITH 1;
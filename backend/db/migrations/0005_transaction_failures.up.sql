@@ -0,0 +1,12 @@
+CREATE TABLE transaction_failures (
+    id BIGSERIAL PRIMARY KEY,
+    project_id UUID,
+    tx_signature TEXT NOT NULL,
+    error TEXT NOT NULL,
+    logs TEXT[] NOT NULL DEFAULT '{}',
+    slot BIGINT,
+    failed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_transaction_failures_project_id
+    ON transaction_failures(project_id, failed_at DESC);
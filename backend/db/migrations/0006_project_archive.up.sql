@@ -0,0 +1,11 @@
+ALTER TABLE project ADD COLUMN archived_at TIMESTAMPTZ;
+
+CREATE TABLE admin_audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    action TEXT NOT NULL,
+    project_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_admin_audit_log_project_id
+    ON admin_audit_log(project_id, created_at DESC);
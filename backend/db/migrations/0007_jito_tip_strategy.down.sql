@@ -0,0 +1 @@
+DROP TABLE IF EXISTS jito_tip_strategy;
@@ -0,0 +1,6 @@
+-- Runtime override of the configured jito tip strategy, shared by all backend processes.
+CREATE TABLE jito_tip_strategy (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    strategy JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,9 @@
+-- Proof that raydium LP tokens of the graduated project are burned.
+CREATE TABLE lp_burns (
+    project_id UUID PRIMARY KEY REFERENCES project(id) ON DELETE CASCADE,
+    lp_mint pubkey NOT NULL,
+    burned_amount balance NOT NULL,
+    tx_signature TEXT NOT NULL,
+    slot BIGINT NOT NULL,
+    burned_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
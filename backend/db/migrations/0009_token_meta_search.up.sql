@@ -0,0 +1,9 @@
+-- Name and symbol weigh more than description in relevance ranking.
+ALTER TABLE token_meta ADD COLUMN search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', name), 'A') ||
+        setweight(to_tsvector('english', symbol), 'A') ||
+        setweight(to_tsvector('english', description), 'B')
+    ) STORED;
+
+CREATE INDEX idx_token_meta_search_vector ON token_meta USING GIN (search_vector);
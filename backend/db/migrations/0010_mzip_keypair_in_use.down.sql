@@ -0,0 +1,39 @@
+DROP PROCEDURE IF EXISTS recycle_keypair;
+
+CREATE OR REPLACE PROCEDURE assign_project_keypair(project_uuid UUID)
+LANGUAGE plpgsql AS $$
+DECLARE
+    existing_key keypair;
+    new_key keypair;
+BEGIN
+    -- First try to get existing key
+    SELECT curve_pool_keypair INTO existing_key
+    FROM project 
+    WHERE id = project_uuid;
+
+    -- If key exists, exit early
+    IF existing_key IS NOT NULL THEN
+        RETURN;
+    END IF;
+
+    -- Otherwise get first available key from mzip_keypair and delete it
+    WITH deleted_key AS (
+        DELETE FROM mzip_keypair
+        WHERE keypair IN (
+            SELECT keypair 
+            FROM mzip_keypair 
+            LIMIT 1
+        )
+        RETURNING keypair
+    )
+    SELECT keypair INTO STRICT new_key
+    FROM deleted_key;
+
+    -- Update project with new key
+    UPDATE project
+    SET curve_pool_keypair = new_key
+    WHERE id = project_uuid;
+END;
+$$;
+
+DROP TABLE IF EXISTS mzip_keypair_in_use;
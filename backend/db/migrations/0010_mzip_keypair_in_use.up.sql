@@ -0,0 +1,40 @@
+-- Mint keypairs, handed out to projects, kept for audit.
+CREATE TABLE mzip_keypair_in_use (
+    id BIGSERIAL PRIMARY KEY,
+    kp keypair NOT NULL,
+    project_id UUID NOT NULL REFERENCES project(id) ON DELETE CASCADE,
+    assigned_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    released_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX idx_mzip_keypair_in_use_project_id
+    ON mzip_keypair_in_use(project_id) WHERE released_at IS NULL;
+
+-- Keypairs are claimed by the migrator directly, see `KeysProvider`.
+DROP PROCEDURE assign_project_keypair;
+
+-- Releases keypair, assigned to the project.
+-- Keypair goes back to the pool only if its mint was not created on-chain yet:
+-- mint address of deployed curve can't be used by another token.
+CREATE OR REPLACE PROCEDURE recycle_keypair(project_uuid UUID)
+LANGUAGE plpgsql AS $$
+DECLARE
+    released_key keypair;
+    current_stage project_stage;
+BEGIN
+    UPDATE mzip_keypair_in_use
+    SET released_at = CURRENT_TIMESTAMP
+    WHERE project_id = project_uuid AND released_at IS NULL
+    RETURNING kp INTO released_key;
+
+    IF released_key IS NULL THEN
+        RETURN;
+    END IF;
+
+    SELECT stage INTO current_stage FROM project WHERE id = project_uuid;
+    IF current_stage IN ('Created', 'Confirmed', 'OnStaticPool', 'StaticPoolClosed') THEN
+        UPDATE project SET curve_pool_keypair = NULL WHERE id = project_uuid;
+        INSERT INTO mzip_keypair VALUES (released_key) ON CONFLICT DO NOTHING;
+    END IF;
+END;
+$$;
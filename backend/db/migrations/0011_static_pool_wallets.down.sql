@@ -0,0 +1 @@
+DROP TABLE IF EXISTS static_pool_wallet_record;
@@ -0,0 +1,7 @@
+-- Wallets, which bought from the static pool, used to count participants.
+CREATE TABLE static_pool_wallet_record (
+    project_id UUID NOT NULL REFERENCES project(id) ON DELETE CASCADE,
+    wallet pubkey NOT NULL,
+    first_bought_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_id, wallet)
+);
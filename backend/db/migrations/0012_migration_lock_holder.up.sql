@@ -0,0 +1,7 @@
+-- Row locks on `project_migration_lock` are not visible to other sessions,
+-- so migrator records which backend holds the lock of the project and since when.
+CREATE TABLE project_migration_lock_holder (
+    project_id UUID PRIMARY KEY REFERENCES project_migration_lock(id) ON DELETE CASCADE,
+    pid INTEGER NOT NULL,
+    locked_since TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+ALTER TABLE project DROP COLUMN holder_count_snapshot;
+
+DROP TABLE token_holders;
@@ -0,0 +1,12 @@
+-- Balances of project's curve token, accumulated from trade events.
+-- Tokens, received outside of tracked pools, are not accounted.
+CREATE TABLE token_holders (
+    project_id UUID NOT NULL REFERENCES project(id) ON DELETE CASCADE,
+    wallet pubkey NOT NULL,
+    balance balance NOT NULL,
+    PRIMARY KEY (project_id, wallet)
+);
+
+CREATE INDEX token_holders_top_idx ON token_holders (project_id, balance DESC);
+
+ALTER TABLE project ADD COLUMN holder_count_snapshot INT NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+DROP MATERIALIZED VIEW project_stats_24h;
+DROP FUNCTION project_trade_stats;
+DROP TABLE chain_events;
+DROP TYPE chain_event_kind;
@@ -0,0 +1,57 @@
+CREATE TYPE chain_event_kind AS ENUM ('trade');
+
+-- Append-only log of applied on-chain events, used for project analytics.
+CREATE TABLE chain_events (
+    id BIGSERIAL PRIMARY KEY,
+    project_id UUID NOT NULL REFERENCES project(id) ON DELETE CASCADE,
+    kind chain_event_kind NOT NULL,
+    tx_signature TEXT NOT NULL,
+    slot BIGINT NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    payload JSONB NOT NULL
+);
+
+CREATE INDEX chain_events_project_occurred_idx ON chain_events (project_id, occurred_at DESC);
+
+-- Trade aggregates per project since the given moment, for every project if none is given.
+-- Spot price is `spot_price_numerator / spot_price_denominator` after the trade, in lamports per token.
+CREATE FUNCTION project_trade_stats(since TIMESTAMPTZ, for_project UUID DEFAULT NULL)
+RETURNS TABLE (
+    project_id UUID,
+    volume_lamports NUMERIC,
+    volume_tokens NUMERIC,
+    trade_count BIGINT,
+    unique_traders BIGINT,
+    price_open FLOAT8,
+    price_high FLOAT8,
+    price_low FLOAT8,
+    price_close FLOAT8
+) AS $$
+    SELECT
+        project_id,
+        SUM((payload->>'sols')::NUMERIC),
+        SUM((payload->>'tokens')::NUMERIC),
+        COUNT(*),
+        COUNT(DISTINCT payload->>'user'),
+        (ARRAY_AGG(price ORDER BY occurred_at, id))[1],
+        MAX(price),
+        MIN(price),
+        (ARRAY_AGG(price ORDER BY occurred_at DESC, id DESC))[1]
+    FROM (
+        SELECT
+            *,
+            (payload->>'spot_price_numerator')::FLOAT8
+                / NULLIF((payload->>'spot_price_denominator')::FLOAT8, 0) AS price
+        FROM chain_events
+        WHERE kind = 'trade'
+            AND occurred_at > since
+            AND (for_project IS NULL OR chain_events.project_id = for_project)
+    ) AS trades
+    GROUP BY project_id
+$$ LANGUAGE SQL STABLE;
+
+CREATE MATERIALIZED VIEW project_stats_24h AS
+    SELECT * FROM project_trade_stats(NOW() - INTERVAL '24 hours');
+
+-- required by concurrent refresh
+CREATE UNIQUE INDEX project_stats_24h_project_idx ON project_stats_24h (project_id);
@@ -0,0 +1,6 @@
+-- Runtime overrides of instructions config values, set by admins, take precedence over config file.
+CREATE TABLE config_overrides (
+    key TEXT PRIMARY KEY,
+    value JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
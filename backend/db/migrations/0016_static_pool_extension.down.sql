@@ -0,0 +1 @@
+ALTER TABLE project DROP COLUMN static_pool_extended_at;
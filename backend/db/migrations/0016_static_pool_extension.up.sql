@@ -0,0 +1,2 @@
+-- Set once static pool deadline is extended on-chain, so users could be notified.
+ALTER TABLE project ADD COLUMN static_pool_extended_at TIMESTAMPTZ;
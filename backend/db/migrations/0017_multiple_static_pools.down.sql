@@ -0,0 +1,35 @@
+-- Pools, opened after the first one, are dropped; enum values can't be removed from `project_stage`.
+ALTER TABLE project ADD COLUMN static_pool_pubkey pubkey;
+UPDATE project SET static_pool_pubkey = static_pool_pubkeys[1];
+ALTER TABLE project DROP COLUMN static_pool_pubkeys;
+
+ALTER TYPE deploy_schema ADD ATTRIBUTE static_pool static_pool_config;
+UPDATE project SET deploy_schema.static_pool = (deploy_schema).static_pools[1];
+ALTER TYPE deploy_schema DROP ATTRIBUTE static_pools;
+
+ALTER TYPE static_pool_config DROP ATTRIBUTE collected_lamports;
+ALTER TYPE static_pool_config DROP ATTRIBUTE max_lamports;
+ALTER TYPE static_pool_config DROP ATTRIBUTE launch_period;
+
+CREATE OR REPLACE PROCEDURE recycle_keypair(project_uuid UUID)
+LANGUAGE plpgsql AS $$
+DECLARE
+    released_key keypair;
+    current_stage project_stage;
+BEGIN
+    UPDATE mzip_keypair_in_use
+    SET released_at = CURRENT_TIMESTAMP
+    WHERE project_id = project_uuid AND released_at IS NULL
+    RETURNING kp INTO released_key;
+
+    IF released_key IS NULL THEN
+        RETURN;
+    END IF;
+
+    SELECT stage INTO current_stage FROM project WHERE id = project_uuid;
+    IF current_stage IN ('Created', 'Confirmed', 'OnStaticPool', 'StaticPoolClosed') THEN
+        UPDATE project SET curve_pool_keypair = NULL WHERE id = project_uuid;
+        INSERT INTO mzip_keypair VALUES (released_key) ON CONFLICT DO NOTHING;
+    END IF;
+END;
+$$;
@@ -0,0 +1,52 @@
+-- Static pools of multi-phase launches are opened one after another, e.g. whitelist round, then public one.
+ALTER TYPE project_stage ADD VALUE 'OnSecondStaticPool' AFTER 'StaticPoolClosed';
+ALTER TYPE project_stage ADD VALUE 'SecondStaticPoolClosed' AFTER 'OnSecondStaticPool';
+
+-- `launch_ts` of pools, opened later, is assigned only once they are opened.
+ALTER TYPE static_pool_config ADD ATTRIBUTE launch_period BIGINT;
+ALTER TYPE static_pool_config ADD ATTRIBUTE max_lamports balance;
+-- Set once the pool is graduated to open the next one: chain state follows only the current pool.
+ALTER TYPE static_pool_config ADD ATTRIBUTE collected_lamports balance;
+
+ALTER TYPE deploy_schema ADD ATTRIBUTE static_pools static_pool_config[];
+UPDATE project SET deploy_schema.static_pools = CASE
+    WHEN (deploy_schema).static_pool IS NULL THEN '{}'
+    ELSE ARRAY[(deploy_schema).static_pool]
+END;
+ALTER TYPE deploy_schema DROP ATTRIBUTE static_pool;
+
+-- Mints of opened static pools, in the order of opening.
+ALTER TABLE project ADD COLUMN static_pool_pubkeys pubkey[] NOT NULL DEFAULT '{}';
+UPDATE project SET static_pool_pubkeys = ARRAY[static_pool_pubkey]
+WHERE static_pool_pubkey IS NOT NULL;
+ALTER TABLE project DROP COLUMN static_pool_pubkey;
+
+CREATE OR REPLACE PROCEDURE recycle_keypair(project_uuid UUID)
+LANGUAGE plpgsql AS $$
+DECLARE
+    released_key keypair;
+    current_stage project_stage;
+BEGIN
+    UPDATE mzip_keypair_in_use
+    SET released_at = CURRENT_TIMESTAMP
+    WHERE project_id = project_uuid AND released_at IS NULL
+    RETURNING kp INTO released_key;
+
+    IF released_key IS NULL THEN
+        RETURN;
+    END IF;
+
+    SELECT stage INTO current_stage FROM project WHERE id = project_uuid;
+    IF current_stage IN (
+        'Created',
+        'Confirmed',
+        'OnStaticPool',
+        'StaticPoolClosed',
+        'OnSecondStaticPool',
+        'SecondStaticPoolClosed'
+    ) THEN
+        UPDATE project SET curve_pool_keypair = NULL WHERE id = project_uuid;
+        INSERT INTO mzip_keypair VALUES (released_key) ON CONFLICT DO NOTHING;
+    END IF;
+END;
+$$;
@@ -0,0 +1,2 @@
+DROP MATERIALIZED VIEW candles_1h;
+DROP FUNCTION project_candles;
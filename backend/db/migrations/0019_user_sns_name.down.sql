@@ -0,0 +1,2 @@
+DROP INDEX user_info_sns_name_idx;
+ALTER TABLE user_info DROP COLUMN sns_name;
@@ -0,0 +1,4 @@
+-- Last resolved `.sol` name of the wallet, refreshed on user info fetch.
+ALTER TABLE user_info ADD COLUMN sns_name TEXT;
+
+CREATE INDEX user_info_sns_name_idx ON user_info (sns_name) WHERE sns_name IS NOT NULL;
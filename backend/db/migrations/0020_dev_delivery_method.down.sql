@@ -0,0 +1,21 @@
+-- Linear vestings are reduced to their cliffs.
+UPDATE config_overrides
+SET
+    key = 'lock_periods',
+    value = (
+        SELECT COALESCE(JSONB_AGG(
+            CASE
+                WHEN method->>'type' = 'immediate' THEN JSONB_BUILD_OBJECT('type', 'disabled')
+                ELSE JSONB_BUILD_OBJECT(
+                    'type', 'interval',
+                    'interval', (method->>'cliffSecs')::BIGINT
+                )
+            END
+        ), '[]'::JSONB)
+        FROM JSONB_ARRAY_ELEMENTS(value) AS method
+    ),
+    updated_at = NOW()
+WHERE key = 'delivery_methods';
+
+ALTER TYPE dev_purchase DROP ATTRIBUTE vesting_frequency;
+ALTER TYPE dev_purchase DROP ATTRIBUTE vesting_period;
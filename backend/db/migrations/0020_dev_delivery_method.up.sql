@@ -0,0 +1,22 @@
+-- Zero lock period is immediate delivery and positive one is cliff vesting,
+-- so existing purchases keep their meaning. Linear vesting sets both new attributes.
+ALTER TYPE dev_purchase ADD ATTRIBUTE vesting_period BIGINT;
+ALTER TYPE dev_purchase ADD ATTRIBUTE vesting_frequency BIGINT;
+
+UPDATE config_overrides
+SET
+    key = 'delivery_methods',
+    value = (
+        SELECT COALESCE(JSONB_AGG(
+            CASE
+                WHEN period->>'type' = 'interval' THEN JSONB_BUILD_OBJECT(
+                    'type', 'vestedCliff',
+                    'cliffSecs', (period->>'interval')::BIGINT
+                )
+                ELSE JSONB_BUILD_OBJECT('type', 'immediate')
+            END
+        ), '[]'::JSONB)
+        FROM JSONB_ARRAY_ELEMENTS(value) AS period
+    ),
+    updated_at = NOW()
+WHERE key = 'lock_periods';
@@ -0,0 +1,10 @@
+-- Program upgrades, proposed on-chain and waiting for the timelock, so they could be reviewed.
+CREATE TABLE program_upgrade_proposals (
+    upgrade_hash BYTEA PRIMARY KEY,
+    proposer pubkey NOT NULL,
+    proposed_at TIMESTAMPTZ NOT NULL,
+    effective_at TIMESTAMPTZ NOT NULL,
+    executed_at TIMESTAMPTZ,
+    tx_signature TEXT NOT NULL,
+    slot BIGINT NOT NULL
+);
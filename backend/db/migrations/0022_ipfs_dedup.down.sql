@@ -0,0 +1,7 @@
+DROP TABLE ipfs_uploads;
+DROP TYPE ipfs_upload_kind;
+DROP INDEX idx_token_meta_metadata_hash;
+ALTER TABLE token_meta DROP COLUMN metadata_hash;
+DROP INDEX idx_token_image_hash;
+ALTER TABLE token_image DROP COLUMN deployed_url;
+ALTER TABLE token_image DROP COLUMN image_hash;
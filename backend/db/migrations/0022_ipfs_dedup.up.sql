@@ -0,0 +1,19 @@
+-- Hashes let the migrator reuse IPFS uploads of the same content instead of pinning it again.
+ALTER TABLE token_image ADD COLUMN image_hash TEXT;
+ALTER TABLE token_image ADD COLUMN deployed_url TEXT;
+UPDATE token_image SET image_hash = encode(sha256(image_content), 'hex');
+CREATE INDEX idx_token_image_hash ON token_image(image_hash) WHERE deployed_url IS NOT NULL;
+
+ALTER TABLE token_meta ADD COLUMN metadata_hash TEXT;
+CREATE INDEX idx_token_meta_metadata_hash ON token_meta(metadata_hash) WHERE deployed_url IS NOT NULL;
+
+CREATE TYPE ipfs_upload_kind AS ENUM ('image', 'metadata', 'pumpfun_metadata');
+
+CREATE TABLE ipfs_uploads (
+    id BIGSERIAL PRIMARY KEY,
+    kind ipfs_upload_kind NOT NULL,
+    bytes BIGINT NOT NULL,
+    -- upload was skipped, as the same content is already pinned
+    deduplicated BOOLEAN NOT NULL,
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS raydium_pool_state;
@@ -0,0 +1,9 @@
+-- Live state of raydium pools of graduated projects, chain events are not tracked there.
+CREATE TABLE raydium_pool_state (
+    project_id UUID PRIMARY KEY REFERENCES project(id) ON DELETE CASCADE,
+    amm_pool pubkey NOT NULL,
+    last_price_lamports_per_token FLOAT8,
+    last_updated_slot BIGINT NOT NULL,
+    liquidity_lamports balance NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
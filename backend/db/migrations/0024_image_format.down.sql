@@ -0,0 +1 @@
+ALTER TABLE token_image DROP COLUMN image_format;
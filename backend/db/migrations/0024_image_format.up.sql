@@ -0,0 +1,2 @@
+-- Images were uploaded as PNG before formats were detected.
+ALTER TABLE token_image ADD COLUMN image_format TEXT NOT NULL DEFAULT 'png';
@@ -0,0 +1,2 @@
+DROP TABLE curved_pool_chain_state;
+DROP TYPE curved_pool_state;
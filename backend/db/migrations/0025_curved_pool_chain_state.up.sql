@@ -0,0 +1,12 @@
+CREATE TYPE curved_pool_state AS (
+    virtual_sol_reserves balance,
+    virtual_token_reserves balance,
+    real_sol_reserves balance,
+    real_token_reserves balance
+);
+
+CREATE TABLE curved_pool_chain_state (
+    mint pubkey PRIMARY KEY NOT NULL,
+    state curved_pool_state NOT NULL,
+    last_updated_slot BIGINT NOT NULL
+);
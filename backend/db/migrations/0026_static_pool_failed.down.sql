@@ -0,0 +1 @@
+-- enum values can't be removed from `project_stage`.
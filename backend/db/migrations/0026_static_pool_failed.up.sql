@@ -0,0 +1,2 @@
+-- Static pool, which missed its goal by the deadline, refunds its buyers instead of graduating.
+ALTER TYPE project_stage ADD VALUE 'StaticPoolFailed';
@@ -0,0 +1,10 @@
+-- Lifetime trade totals of projects, kept by chain sync along with each trade.
+CREATE TABLE project_stats (
+    project_id UUID PRIMARY KEY REFERENCES project(id) ON DELETE CASCADE,
+    total_volume_lamports balance NOT NULL DEFAULT 0,
+    buy_count INT NOT NULL DEFAULT 0,
+    sell_count INT NOT NULL DEFAULT 0,
+    holder_count INT NOT NULL DEFAULT 0,
+    last_price_lamports_per_token FLOAT8,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,2 @@
+ALTER TYPE deploy_schema DROP ATTRIBUTE IF EXISTS graduation_target;
+DROP TYPE IF EXISTS graduation_target;
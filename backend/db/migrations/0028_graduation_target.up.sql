@@ -0,0 +1,4 @@
+CREATE TYPE graduation_target AS ENUM ('Raydium', 'Meteora');
+
+ALTER TYPE deploy_schema ADD ATTRIBUTE graduation_target graduation_target;
+UPDATE project SET deploy_schema.graduation_target = 'Raydium';
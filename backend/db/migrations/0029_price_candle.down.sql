@@ -0,0 +1,53 @@
+-- OHLCV candles of the given width per project, for every project if none is given.
+-- Buckets are aligned to the unix epoch, so they are stable across queries.
+CREATE FUNCTION project_candles(
+    bucket_width INTERVAL,
+    for_project UUID DEFAULT NULL,
+    since TIMESTAMPTZ DEFAULT NULL,
+    till TIMESTAMPTZ DEFAULT NULL
+)
+RETURNS TABLE (
+    project_id UUID,
+    bucket TIMESTAMPTZ,
+    open FLOAT8,
+    high FLOAT8,
+    low FLOAT8,
+    close FLOAT8,
+    volume_lamports NUMERIC,
+    trade_count BIGINT
+) AS $$
+    SELECT
+        project_id,
+        bucket,
+        (ARRAY_AGG(price ORDER BY occurred_at, id))[1],
+        MAX(price),
+        MIN(price),
+        (ARRAY_AGG(price ORDER BY occurred_at DESC, id DESC))[1],
+        SUM((payload->>'sols')::NUMERIC),
+        COUNT(*)
+    FROM (
+        SELECT
+            *,
+            date_bin(bucket_width, occurred_at, TIMESTAMPTZ 'epoch') AS bucket,
+            (payload->>'spot_price_numerator')::FLOAT8
+                / (payload->>'spot_price_denominator')::FLOAT8 AS price
+        FROM chain_events
+        WHERE kind = 'trade'
+            -- price is undefined otherwise, charts can't draw such candles
+            AND (payload->>'spot_price_denominator')::NUMERIC > 0
+            AND (for_project IS NULL OR chain_events.project_id = for_project)
+            AND (since IS NULL OR occurred_at >= date_bin(bucket_width, since, TIMESTAMPTZ 'epoch'))
+            AND (till IS NULL OR occurred_at < till)
+    ) AS trades
+    GROUP BY project_id, bucket
+$$ LANGUAGE SQL STABLE;
+
+-- Hour candles are the most requested, others are aggregated on the fly.
+CREATE MATERIALIZED VIEW candles_1h AS
+    SELECT * FROM project_candles(INTERVAL '1 hour');
+
+-- required by concurrent refresh
+CREATE UNIQUE INDEX candles_1h_project_bucket_idx ON candles_1h (project_id, bucket);
+
+DROP TABLE price_candle;
+DROP TYPE candle_interval;
@@ -0,0 +1,52 @@
+CREATE TYPE candle_interval AS ENUM ('1m', '5m', '1h', '1d');
+
+-- OHLCV candles per project, upserted by chain sync along with each trade.
+-- Price is the execution one, in lamports per token, `open_ts` is a unix timestamp
+-- of the candle start, aligned to the unix epoch.
+CREATE TABLE price_candle (
+    project_id UUID NOT NULL REFERENCES project(id) ON DELETE CASCADE,
+    "interval" candle_interval NOT NULL,
+    open_ts BIGINT NOT NULL,
+    open FLOAT8 NOT NULL,
+    high FLOAT8 NOT NULL,
+    low FLOAT8 NOT NULL,
+    close FLOAT8 NOT NULL,
+    volume_lamports balance NOT NULL,
+    trade_count INT NOT NULL,
+    PRIMARY KEY (project_id, "interval", open_ts)
+);
+
+-- minute candles are kept for a week only, the same as chain sync prunes them
+INSERT INTO price_candle
+SELECT
+    project_id,
+    intervals.name,
+    EXTRACT(EPOCH FROM date_bin(intervals.width, occurred_at, TIMESTAMPTZ 'epoch'))::BIGINT AS open_ts,
+    (ARRAY_AGG(price ORDER BY occurred_at, id))[1],
+    MAX(price),
+    MIN(price),
+    (ARRAY_AGG(price ORDER BY occurred_at DESC, id DESC))[1],
+    SUM(sols),
+    COUNT(*)
+FROM (
+    SELECT
+        id,
+        project_id,
+        occurred_at,
+        (payload->>'sols')::NUMERIC AS sols,
+        (payload->>'sols')::FLOAT8 / (payload->>'tokens')::FLOAT8 AS price
+    FROM chain_events
+    WHERE kind = 'trade' AND (payload->>'tokens')::NUMERIC > 0
+) AS trades
+CROSS JOIN (
+    VALUES
+        ('1m'::candle_interval, INTERVAL '1 minute'),
+        ('5m', INTERVAL '5 minutes'),
+        ('1h', INTERVAL '1 hour'),
+        ('1d', INTERVAL '1 day')
+) AS intervals (name, width)
+WHERE intervals.name <> '1m' OR occurred_at >= NOW() - INTERVAL '7 days'
+GROUP BY project_id, intervals.name, open_ts;
+
+DROP MATERIALIZED VIEW candles_1h;
+DROP FUNCTION project_candles;
@@ -0,0 +1,2 @@
+DROP TABLE fee_collection;
+DROP TYPE fee_source;
@@ -0,0 +1,14 @@
+CREATE TYPE fee_source AS ENUM ('extracted', 'account_taken');
+
+-- Protocol revenue, collected by fee instructions, as reported by chain sync.
+CREATE TABLE fee_collection (
+    id BIGSERIAL PRIMARY KEY,
+    recipient pubkey NOT NULL,
+    amount balance NOT NULL,
+    source fee_source NOT NULL,
+    tx_signature TEXT NOT NULL,
+    slot BIGINT NOT NULL,
+    collected_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX fee_collection_collected_at_idx ON fee_collection (collected_at);
@@ -0,0 +1 @@
+ALTER TYPE dev_purchase DROP ATTRIBUTE cliff_pct;
@@ -0,0 +1,3 @@
+-- Percent of the linear vesting, unlocked at the cliff. Absent one means nothing is unlocked,
+-- so existing purchases keep their meaning.
+ALTER TYPE dev_purchase ADD ATTRIBUTE cliff_pct SMALLINT;
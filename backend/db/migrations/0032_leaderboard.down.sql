@@ -0,0 +1,3 @@
+DROP INDEX project_stats_24h_volume_idx;
+DROP INDEX project_stats_holder_count_idx;
+DROP INDEX project_stats_total_volume_idx;
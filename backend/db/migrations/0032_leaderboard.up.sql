@@ -0,0 +1,4 @@
+-- Leaderboard ranks, ties are broken by project id.
+CREATE INDEX project_stats_total_volume_idx ON project_stats (total_volume_lamports DESC, project_id);
+CREATE INDEX project_stats_holder_count_idx ON project_stats (holder_count DESC, project_id);
+CREATE INDEX project_stats_24h_volume_idx ON project_stats_24h (volume_lamports DESC, project_id);
@@ -0,0 +1,2 @@
+-- Projects graduated to Orca Whirlpool, holding the liquidity in a burned full range position.
+ALTER TYPE graduation_target ADD VALUE 'Orca';
@@ -0,0 +1,25 @@
+-- Endpoints of users, receiving trades and state changes of projects.
+-- Projects are matched by their stage, empty filter matches all of them.
+CREATE TABLE webhook (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    owner_wallet BYTEA NOT NULL,
+    target_url TEXT NOT NULL,
+    secret_hex TEXT NOT NULL,
+    event_filter project_stage[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_webhook_owner_wallet ON webhook(owner_wallet);
+
+CREATE TABLE webhook_delivery_log (
+    id BIGSERIAL PRIMARY KEY,
+    webhook_id UUID NOT NULL REFERENCES webhook(id) ON DELETE CASCADE,
+    event_type TEXT NOT NULL,
+    attempt INT NOT NULL,
+    status_code INT,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    success BOOL NOT NULL,
+    error TEXT
+);
+
+CREATE INDEX idx_webhook_delivery_log_webhook ON webhook_delivery_log(webhook_id);
@@ -0,0 +1 @@
+ALTER TABLE token_holders DROP COLUMN last_updated_slot;
@@ -0,0 +1,2 @@
+-- Slot of the latest trade, which changed the balance; zero for balances tracked before.
+ALTER TABLE token_holders ADD COLUMN last_updated_slot BIGINT NOT NULL DEFAULT 0;
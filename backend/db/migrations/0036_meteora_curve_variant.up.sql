@@ -0,0 +1,2 @@
+-- Projects, launched right into the Meteora Dynamic AMM pool, without a bonding curve.
+ALTER TYPE curve_variant ADD VALUE 'Meteora';
@@ -0,0 +1 @@
+ALTER TABLE token_meta DROP COLUMN update_pending;
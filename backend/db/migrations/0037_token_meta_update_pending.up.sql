@@ -0,0 +1,2 @@
+-- Set by owner's update, so the metadata is uploaded again; deployed one is kept until then.
+ALTER TABLE token_meta ADD COLUMN update_pending BOOLEAN NOT NULL DEFAULT FALSE;
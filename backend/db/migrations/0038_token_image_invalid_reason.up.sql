@@ -0,0 +1,2 @@
+-- Set once the migrator fails to convert the image, so the project isn't picked every tick.
+ALTER TABLE token_image ADD COLUMN invalid_reason TEXT;
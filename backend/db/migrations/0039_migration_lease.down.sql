@@ -0,0 +1,5 @@
+DELETE FROM project_migration_lock_holder;
+ALTER TABLE project_migration_lock_holder
+    DROP COLUMN owner,
+    DROP COLUMN expires_at,
+    ADD COLUMN pid INTEGER NOT NULL;
@@ -0,0 +1,7 @@
+-- Migration locks are leased by owner tokens and expire on their own,
+-- instead of being released by terminating the backend, holding the row lock.
+DELETE FROM project_migration_lock_holder;
+ALTER TABLE project_migration_lock_holder
+    DROP COLUMN pid,
+    ADD COLUMN owner UUID NOT NULL,
+    ADD COLUMN expires_at TIMESTAMPTZ NOT NULL;
@@ -0,0 +1,9 @@
+-- Trades, already counted into project stats, so replayed transactions aren't counted twice.
+-- Holder counts are refreshed periodically instead of being counted on each trade.
+CREATE TABLE project_stats_trade (
+    tx_signature TEXT NOT NULL,
+    -- Order of the trade among trades of the transaction.
+    trade_index INT NOT NULL,
+    project_id UUID NOT NULL REFERENCES project(id) ON DELETE CASCADE,
+    PRIMARY KEY (tx_signature, trade_index)
+);
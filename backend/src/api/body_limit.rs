@@ -0,0 +1,196 @@
+use axum::{
+    extract::{DefaultBodyLimit, Request, State},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::MethodRouter,
+};
+use http::{header::CONTENT_LENGTH, StatusCode};
+use services_common::api::response::ApiError;
+
+/// Fallback for routes without their own limit.
+pub const DEFAULT_MAX_BYTES: usize = 4 * 1024;
+
+pub const CREATE_PROJECT: BodyLimit = BodyLimit {
+    max_bytes: 2 * 1024 * 1024,
+    hint: "project request with its image must fit, consider downscaling the image",
+};
+
+pub const BATCH_CREATE_PROJECT: BodyLimit = BodyLimit {
+    max_bytes: 64 * 1024,
+    hint: "batch carries project requests only, images are referred by url",
+};
+
+pub const TRADE: BodyLimit = BodyLimit {
+    max_bytes: 2 * 1024,
+    hint: "trade request is a small JSON payload",
+};
+
+pub const GET_PROJECT: BodyLimit = BodyLimit {
+    max_bytes: 2 * 1024,
+    hint: "project is requested by query parameters only",
+};
+
+pub const USERNAME: BodyLimit = BodyLimit {
+    max_bytes: 512,
+    hint: "username request is a small JSON payload",
+};
+
+/// Maximal body size of the route, with a hint for the client, what the route expects instead.
+#[derive(Debug, Clone, Copy)]
+pub struct BodyLimit {
+    pub max_bytes: usize,
+    pub hint: &'static str,
+}
+
+impl BodyLimit {
+    /// Overrides the router-wide limit for the route, so the route rejects larger bodies
+    /// with [`ApiError::RequestTooLarge`] instead of the bare 413.
+    pub fn apply<S: Clone + Send + Sync + 'static>(
+        self,
+        route: MethodRouter<S>,
+    ) -> MethodRouter<S> {
+        route
+            .layer(DefaultBodyLimit::max(self.max_bytes))
+            .layer(middleware::from_fn_with_state(self, reject_too_large))
+    }
+
+    fn rejection(self) -> Response {
+        ApiError::RequestTooLarge {
+            max_bytes: self.max_bytes,
+            hint: self.hint,
+        }
+        .into_response()
+    }
+}
+
+/// Declared length is checked upfront: multipart extractors report an exceeded limit
+/// as a malformed field. Bodies without declared length are still cut by [`DefaultBodyLimit`].
+async fn reject_too_large(
+    State(limit): State<BodyLimit>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let declared = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if declared.is_some_and(|len| len > limit.max_bytes) {
+        return limit.rejection();
+    }
+
+    let response = next.run(request).await;
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return limit.rejection();
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_utils;
+    use axum::{body::Bytes, routing::post, Router};
+    use services_common::api::response::ErrorResponse;
+
+    async fn serve_test_router() -> String {
+        test_utils::serve(
+            Router::new()
+                .route("/create", CREATE_PROJECT.apply(post(echo)))
+                .route("/buy", TRADE.apply(post(echo)))
+                .route("/upsert", USERNAME.apply(post(echo)))
+                .route("/default", post(echo))
+                .layer(DefaultBodyLimit::max(DEFAULT_MAX_BYTES)),
+        )
+        .await
+    }
+
+    async fn echo(body: Bytes) -> String {
+        body.len().to_string()
+    }
+
+    async fn post_body(url: &str, len: usize) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(url)
+            .body(vec![b'a'; len])
+            .send()
+            .await
+            .unwrap()
+    }
+
+    async fn assert_accepted(url: &str, len: usize) {
+        let response = post_body(url, len).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().await.unwrap(), len.to_string());
+    }
+
+    async fn assert_rejected(url: &str, len: usize, message: &str) {
+        let response = post_body(url, len).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.code, 17);
+        assert_eq!(error.message, message);
+    }
+
+    #[tokio::test]
+    async fn test_limits_each_route_separately() {
+        let base = serve_test_router().await;
+
+        let create = format!("{base}/create");
+        assert_accepted(&create, CREATE_PROJECT.max_bytes).await;
+        assert_rejected(
+            &create,
+            CREATE_PROJECT.max_bytes + 1,
+            "request body exceeds the 2MB limit: project request with its image must fit, \
+             consider downscaling the image",
+        )
+        .await;
+
+        let buy = format!("{base}/buy");
+        assert_accepted(&buy, TRADE.max_bytes).await;
+        assert_rejected(
+            &buy,
+            TRADE.max_bytes + 1,
+            "request body exceeds the 2KB limit: trade request is a small JSON payload",
+        )
+        .await;
+
+        let upsert = format!("{base}/upsert");
+        assert_accepted(&upsert, USERNAME.max_bytes).await;
+        assert_rejected(
+            &upsert,
+            USERNAME.max_bytes + 1,
+            "request body exceeds the 512B limit: username request is a small JSON payload",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_keeps_router_limit_for_other_routes() {
+        let base = serve_test_router().await;
+
+        let default = format!("{base}/default");
+        assert_accepted(&default, DEFAULT_MAX_BYTES).await;
+        let response = post_body(&default, DEFAULT_MAX_BYTES + 1).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_streamed_body_without_declared_length() {
+        let base = serve_test_router().await;
+
+        let chunks: Vec<Result<_, std::io::Error>> =
+            vec![Ok(vec![b'a'; TRADE.max_bytes]), Ok(vec![b'a'; 1])];
+        let response = reqwest::Client::new()
+            .post(format!("{base}/buy"))
+            .body(reqwest::Body::wrap_stream(futures_util::stream::iter(
+                chunks,
+            )))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.code, 17);
+    }
+}
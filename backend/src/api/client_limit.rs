@@ -0,0 +1,350 @@
+use super::body_limit::BodyLimit;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::MethodRouter,
+};
+use dashmap::DashMap;
+use http::{header::RETRY_AFTER, HeaderMap, HeaderValue};
+use serde::Deserialize;
+use services_common::api::response::ApiError;
+use std::{
+    net::{IpAddr, SocketAddr},
+    num::NonZeroU32,
+    sync::{Arc, Weak},
+    time::{Duration, Instant},
+};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Refilled buckets are dropped that often, so idle clients don't pile up.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Deserialize, serde_derive_default::Default)]
+pub struct RateLimitsConfig {
+    #[serde(default = "default_trade_limit")]
+    pub trade: RateLimitConfig,
+    #[serde(default = "default_create_project_limit")]
+    pub create_project: RateLimitConfig,
+    /// Header, the reverse proxy in front of the api puts client address to, e.g. `x-forwarded-for`.
+    /// Its last address is taken, as earlier ones are sent by the client itself.
+    /// Peer address of the connection is used, if absent.
+    #[serde(default)]
+    pub client_ip_header: Option<String>,
+}
+
+/// Client may send `burst` requests at once, then `max_requests_per_minute` are refilled evenly.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RateLimitConfig {
+    pub max_requests_per_minute: NonZeroU32,
+    pub burst: NonZeroU32,
+}
+
+fn default_trade_limit() -> RateLimitConfig {
+    RateLimitConfig {
+        max_requests_per_minute: NonZeroU32::new(60).unwrap(),
+        burst: NonZeroU32::new(10).unwrap(),
+    }
+}
+
+fn default_create_project_limit() -> RateLimitConfig {
+    RateLimitConfig {
+        max_requests_per_minute: NonZeroU32::new(3).unwrap(),
+        burst: NonZeroU32::new(1).unwrap(),
+    }
+}
+
+impl RateLimitConfig {
+    fn tokens_per_sec(&self) -> f64 {
+        f64::from(self.max_requests_per_minute.get()) / 60.0
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn full(config: &RateLimitConfig, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(config.burst.get()),
+            refilled_at: now,
+        }
+    }
+
+    fn refill(&mut self, config: &RateLimitConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * config.tokens_per_sec())
+            .min(f64::from(config.burst.get()));
+        self.refilled_at = now;
+    }
+
+    fn is_full(&self, config: &RateLimitConfig) -> bool {
+        self.tokens >= f64::from(config.burst.get())
+    }
+
+    /// Time to wait for the next request otherwise.
+    fn try_take(&mut self, config: &RateLimitConfig, now: Instant) -> Result<(), Duration> {
+        self.refill(config, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        Err(Duration::from_secs_f64(
+            (1.0 - self.tokens) / config.tokens_per_sec(),
+        ))
+    }
+}
+
+/// Limits requests of a single client address across all routes it's applied to.
+///
+/// Wallet, named by the request body, is chosen by the client freely,
+/// so it doesn't identify the client.
+#[derive(Debug, Clone)]
+pub struct ClientRateLimiter {
+    config: RateLimitConfig,
+    client_ip_header: Option<String>,
+    buckets: Arc<DashMap<IpAddr, TokenBucket>>,
+}
+
+impl ClientRateLimiter {
+    pub fn new(config: RateLimitConfig, client_ip_header: Option<String>) -> Self {
+        Self {
+            config,
+            client_ip_header,
+            buckets: Default::default(),
+        }
+    }
+
+    /// Stops once the limiter, together with all routes it's applied to, is dropped.
+    pub fn serve_pruning(&self) -> JoinHandle<()> {
+        let buckets = Arc::downgrade(&self.buckets);
+        let config = self.config;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+            loop {
+                interval.tick().await;
+                let Some(buckets) = Weak::upgrade(&buckets) else {
+                    return;
+                };
+                forget_refilled(&buckets, &config, Instant::now());
+            }
+        })
+    }
+
+    /// Route's body limit is applied on top of the limiter.
+    pub fn apply<S: Clone + Send + Sync + 'static>(
+        &self,
+        route: MethodRouter<S>,
+        body_limit: BodyLimit,
+    ) -> MethodRouter<S> {
+        body_limit.apply(route.layer(middleware::from_fn_with_state(self.clone(), limit_client)))
+    }
+
+    pub fn check(&self, client: IpAddr) -> Result<(), ApiError> {
+        self.check_at(client, Instant::now())
+    }
+
+    fn check_at(&self, client: IpAddr, now: Instant) -> Result<(), ApiError> {
+        let result = self
+            .buckets
+            .entry(client)
+            .or_insert_with(|| TokenBucket::full(&self.config, now))
+            .try_take(&self.config, now);
+        result.map_err(|retry_after| {
+            warn!("requests of client {client} are rate limited");
+            let retry_secs = retry_after.as_secs_f64().ceil() as u64;
+            let mut headers = HeaderMap::new();
+            headers.insert(RETRY_AFTER, HeaderValue::from(retry_secs));
+            ApiError::RateLimited(
+                anyhow::anyhow!("too many requests, retry in {retry_secs}s"),
+                headers,
+            )
+        })
+    }
+
+    /// Proxy header is trusted only once configured, otherwise the client could forge it.
+    fn client_ip(&self, request: &Request) -> Option<IpAddr> {
+        match &self.client_ip_header {
+            Some(header) => request
+                .headers()
+                .get_all(header.as_str())
+                .iter()
+                .last()?
+                .to_str()
+                .ok()?
+                .rsplit(',')
+                .next()?
+                .trim()
+                .parse()
+                .ok(),
+            None => request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip()),
+        }
+    }
+}
+
+/// Refilled bucket is the same as the absent one.
+fn forget_refilled(buckets: &DashMap<IpAddr, TokenBucket>, config: &RateLimitConfig, now: Instant) {
+    buckets.retain(|_, bucket| {
+        bucket.refill(config, now);
+        !bucket.is_full(config)
+    });
+}
+
+/// Requests without a known client address are passed as is, e.g. once the proxy header is missing.
+async fn limit_client(
+    State(limiter): State<ClientRateLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match limiter.client_ip(&request) {
+        Some(client) => match limiter.check(client) {
+            Ok(()) => next.run(request).await,
+            Err(err) => err.into_response(),
+        },
+        None => {
+            warn!("client address of {} is unknown", request.uri());
+            next.run(request).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{body_limit, test_utils};
+    use axum::{body::Bytes, routing::post, Router};
+    use http::StatusCode;
+    use services_common::api::response::ErrorResponse;
+    use std::net::Ipv4Addr;
+
+    fn config(max_requests_per_minute: u32, burst: u32) -> RateLimitConfig {
+        RateLimitConfig {
+            max_requests_per_minute: NonZeroU32::new(max_requests_per_minute).unwrap(),
+            burst: NonZeroU32::new(burst).unwrap(),
+        }
+    }
+
+    fn client(last: u8) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(10, 0, 0, last))
+    }
+
+    #[test]
+    fn test_allows_burst_then_refills_evenly() {
+        let limiter = ClientRateLimiter::new(config(60, 3), None);
+        let client = client(1);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check_at(client, now).is_ok());
+        }
+        let err = limiter.check_at(client, now).unwrap_err();
+        let ApiError::RateLimited(_, headers) = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(headers[RETRY_AFTER], "1");
+
+        assert!(limiter
+            .check_at(client, now + Duration::from_secs(1))
+            .is_ok());
+        assert!(limiter
+            .check_at(client, now + Duration::from_secs(1))
+            .is_err());
+        // refill never exceeds the burst
+        for _ in 0..3 {
+            assert!(limiter
+                .check_at(client, now + Duration::from_secs(600))
+                .is_ok());
+        }
+        assert!(limiter
+            .check_at(client, now + Duration::from_secs(600))
+            .is_err());
+    }
+
+    #[test]
+    fn test_limits_clients_separately() {
+        let limiter = ClientRateLimiter::new(config(1, 1), None);
+        let now = Instant::now();
+
+        assert!(limiter.check_at(client(1), now).is_ok());
+        assert!(limiter.check_at(client(1), now).is_err());
+        assert!(limiter.check_at(client(2), now).is_ok());
+    }
+
+    #[test]
+    fn test_forgets_refilled_buckets() {
+        let limiter = ClientRateLimiter::new(config(60, 1), None);
+        let now = Instant::now();
+        let idle = client(1);
+        let active = client(2);
+        limiter.check_at(idle, now).unwrap();
+        limiter
+            .check_at(active, now + Duration::from_secs(5))
+            .unwrap();
+
+        forget_refilled(
+            &limiter.buckets,
+            &limiter.config,
+            now + Duration::from_secs(5),
+        );
+        assert!(!limiter.buckets.contains_key(&idle));
+        assert!(limiter.buckets.contains_key(&active));
+    }
+
+    #[test]
+    fn test_takes_last_address_of_proxy_header() {
+        let limiter = ClientRateLimiter::new(config(1, 1), Some("x-forwarded-for".to_owned()));
+        let request = Request::builder()
+            .header("x-forwarded-for", "10.0.0.9, 10.0.0.1")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        assert_eq!(limiter.client_ip(&request), Some(client(1)));
+    }
+
+    #[tokio::test]
+    async fn test_limits_client_regardless_of_request_body() {
+        let limiter = ClientRateLimiter::new(config(1, 1), None);
+        let router = Router::new()
+            .route("/buy", limiter.apply(post(echo), body_limit::TRADE))
+            .route("/sell", limiter.apply(post(echo), body_limit::TRADE));
+        let base = test_utils::serve(router).await;
+
+        let client = reqwest::Client::new();
+        let body = serde_json::json!({ "user": "first", "sols": 1 });
+        let response = client
+            .post(format!("{base}/buy"))
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        // handler still gets the whole body
+        assert_eq!(
+            response.text().await.unwrap(),
+            body.to_string().len().to_string()
+        );
+
+        // another wallet in the body doesn't reset the limit
+        let other = serde_json::json!({ "user": "second", "sols": 1 });
+        let response = client
+            .post(format!("{base}/sell"))
+            .json(&other)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(RETRY_AFTER));
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.code, 16);
+    }
+
+    async fn echo(body: Bytes) -> String {
+        body.len().to_string()
+    }
+}
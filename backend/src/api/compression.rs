@@ -0,0 +1,178 @@
+use crate::metrics::RESPONSE_COMPRESSION_RATIO;
+use axum::{
+    body::{Body, HttpBody as _},
+    extract::Request,
+    middleware::{self, Next},
+    response::Response,
+    Router,
+};
+use serde::Deserialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tower_http::compression::{
+    predicate::SizeAbove, CompressionLayer, DefaultPredicate, Predicate,
+};
+use tracing::debug;
+
+/// Each N-th response is sampled for the compression ratio metric.
+const RATIO_SAMPLE_EVERY: u64 = 100;
+
+#[derive(Debug, Clone, Deserialize, serde_derive_default::Default)]
+pub struct CompressionConfig {
+    /// Responses smaller than this are sent uncompressed: overhead exceeds savings.
+    #[serde(default = "default_min_size")]
+    pub min_size: u16,
+}
+
+fn default_min_size() -> u16 {
+    1024
+}
+
+/// Compresses responses with brotli, gzip or deflate, as negotiated by `Accept-Encoding`.
+pub fn apply<S: Clone + Send + Sync + 'static>(
+    router: Router<S>,
+    config: &CompressionConfig,
+) -> Router<S> {
+    let predicate = DefaultPredicate::new().and(SizeAbove::new(config.min_size));
+    router
+        .layer(middleware::from_fn(record_uncompressed_size))
+        .layer(
+            CompressionLayer::new()
+                .br(true)
+                .gzip(true)
+                .deflate(true)
+                .compress_when(predicate),
+        )
+        .layer(middleware::from_fn(sample_compression_ratio))
+}
+
+#[derive(Clone, Copy)]
+struct RatioSampled;
+
+#[derive(Clone, Copy)]
+struct UncompressedSize(u64);
+
+async fn sample_compression_ratio(mut request: Request, next: Next) -> Response {
+    static REQUESTS: AtomicU64 = AtomicU64::new(0);
+    if REQUESTS.fetch_add(1, Ordering::Relaxed) % RATIO_SAMPLE_EVERY != 0 {
+        return next.run(request).await;
+    }
+
+    request.extensions_mut().insert(RatioSampled);
+    let response = next.run(request).await;
+    let Some(UncompressedSize(uncompressed)) = response.extensions().get().copied() else {
+        return response;
+    };
+    if uncompressed == 0
+        || !response
+            .headers()
+            .contains_key(http::header::CONTENT_ENCODING)
+    {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let compressed = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(compressed) => compressed,
+        Err(err) => {
+            debug!("failed to buffer sampled compressed response: {err}");
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    RESPONSE_COMPRESSION_RATIO.observe(compressed.len() as f64 / uncompressed as f64);
+    Response::from_parts(parts, Body::from(compressed))
+}
+
+async fn record_uncompressed_size(request: Request, next: Next) -> Response {
+    let sampled = request.extensions().get::<RatioSampled>().is_some();
+    let mut response = next.run(request).await;
+    if sampled {
+        if let Some(size) = response.body().size_hint().exact() {
+            response.extensions_mut().insert(UncompressedSize(size));
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_utils;
+    use axum::{routing::get, Json};
+    use serde::Serialize;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Payload {
+        transaction: String,
+    }
+
+    fn payload() -> Payload {
+        use base64::Engine as _;
+        let raw: Vec<u8> = (0..4096u32).map(|i| (i % 7) as u8).collect();
+        Payload {
+            transaction: base64::engine::general_purpose::STANDARD.encode(raw),
+        }
+    }
+
+    async fn serve_test_router() -> String {
+        test_utils::serve(apply(
+            Router::new()
+                .route("/large", get(|| async { Json(payload()) }))
+                .route("/small", get(|| async { "ok" })),
+            &CompressionConfig::default(),
+        ))
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_compresses_with_brotli_when_accepted() {
+        let base = serve_test_router().await;
+
+        let raw = reqwest::Client::builder()
+            .no_brotli()
+            .build()
+            .unwrap()
+            .get(format!("{base}/large"))
+            .header(http::header::ACCEPT_ENCODING, "br")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            raw.headers().get(http::header::CONTENT_ENCODING).unwrap(),
+            "br"
+        );
+
+        let decoded: Payload = reqwest::Client::builder()
+            .brotli(true)
+            .build()
+            .unwrap()
+            .get(format!("{base}/large"))
+            .header(http::header::ACCEPT_ENCODING, "br")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(decoded, payload());
+    }
+
+    #[tokio::test]
+    async fn test_skips_small_responses() {
+        let base = serve_test_router().await;
+
+        let response = reqwest::Client::builder()
+            .no_brotli()
+            .build()
+            .unwrap()
+            .get(format!("{base}/small"))
+            .header(http::header::ACCEPT_ENCODING, "br")
+            .send()
+            .await
+            .unwrap();
+        assert!(response
+            .headers()
+            .get(http::header::CONTENT_ENCODING)
+            .is_none());
+        assert_eq!(response.text().await.unwrap(), "ok");
+    }
+}
@@ -1,28 +1,58 @@
 use crate::app::exposed::{
-    ChangeUserInfoRequest, GetOwnedNFTsRequest, GetUserInformationRequest, UserInfo,
+    AcceptTransferRequest, AcceptTransferResponse, BestBuyRouteRequest, BestBuyRouteResponse,
+    CancelProjectRequest, CancelProjectResponse, CancelTransferRequest, CancelTransferResponse,
+    Candle, ClaimCreatorFeesRequest, ClaimCreatorFeesResponse, CurveState, DailyRevenue,
+    DeploymentRules, ExtendStaticPoolRequest, ExtendStaticPoolResponse, ForceUnlockRequest,
+    ForceUnlockResponse, GetCandlesRequest, GetCurveStateRequest, GetHoldersRequest,
+    GetHoldersResponse, GetLpBurnProofRequest, GetLpBurnProofResponse, GetProjectStatsRequest,
+    GetRevenueRequest, GetTxFailuresRequest, IpfsStats, LeaderboardRequest, LeaderboardResponse,
+    ListProjectsRequest, ListProjectsResponse, MigrationLock, PriceStreamEvent, PriceStreamRequest,
+    ProjectStats, QuoteRequest, QuoteResponse, RecoverProjectRequest, RecoverProjectResponse,
+    SearchProjectsRequest, SearchProjectsResponse, SetDeliveryMethodsRequest, SetFeeConfigRequest,
+    SetFeeConfigResponse, SetJitoTipStrategyRequest, SetLaunchPeriodsRequest,
+    SetProjectCreationFeeRequest, SetProjectCreationFeeResponse, StaticPoolProgress,
+    TransferOwnershipRequest, TransferOwnershipResponse, TxFailure, UpdatePoolConfigRequest,
+    UpdatePoolConfigResponse, UpdateProjectMetaRequest, UpgradeProposal,
 };
+use crate::app::exposed::{
+    ChangeUserInfoRequest, ConfirmEmailRequest, GetOwnedNFTsRequest, GetUserInformationRequest,
+    NotificationPreferences, ResolveAddressRequest, ResolveAddressResponse, ResolveSnsRequest,
+    ResolveSnsResponse, SetAllowedOwnersRequest, UpdateNotificationsRequest, UserInfo,
+};
+use crate::app::exposed::{RegisterWebhookRequest, RegisterWebhookResponse};
+use crate::app::storage::{project::ProjectId, search::InvalidListCursor, PoolStats};
 use crate::app::{
     exposed::{
-        BuyRequest, BuyResponse, CreateProjectForm, CreateProjectResponse, CreateProjectStreamData,
-        DevLockClaimRequest, DevLockClaimResponse, GetProjectRequest, GetProjectResponse,
-        SellRequest, SellResponse,
+        BuyExactTokensRequest, BuyRequest, BuyResponse, CreateProjectForm, CreateProjectRequest,
+        CreateProjectResponse, CreateProjectStreamData, DevLockClaimRequest, DevLockClaimResponse,
+        GetProjectRequest, GetProjectResponse, SellExactSolRequest, SellRequest, SellResponse,
     },
+    price_stream::stream_trades,
     App,
 };
 use axum::{
-    extract::{DefaultBodyLimit, Multipart, Query, State},
-    routing::{get, post},
+    extract::{ws::WebSocketUpgrade, DefaultBodyLimit, Multipart, Path, Query, State},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+    routing::{delete, get, post, put},
     Json, Router,
 };
-use futures_util::TryStreamExt;
-use http::Method;
+use client_limit::{ClientRateLimiter, RateLimitsConfig};
+use compression::CompressionConfig;
+use futures_util::{stream, TryStreamExt};
+use http::{header, Method};
+use services_common::api::auth::{admin::AdminAuth, User};
 use services_common::api::captcha::Captcha;
 use services_common::api::response::{ApiError, AppJson, ErrorResponse};
 use services_common::solana::helius::GetOwnedNFTsResponse;
+use std::time::Duration;
 use tokio_util::io::StreamReader;
 use tower_http::cors::{Any, CorsLayer};
 use utoipa::OpenApi;
 use utoipauto::utoipauto;
+use uuid::Uuid;
 use validator::Validate;
 
 #[utoipauto(paths = "./backend/src")]
@@ -30,9 +60,18 @@ use validator::Validate;
 #[openapi()]
 pub struct ApiDoc;
 
+pub mod body_limit;
+pub mod client_limit;
+pub mod compression;
+#[cfg(test)]
+mod test_utils;
+
 pub type BackendState = services_common::api::server::AppState<App>;
 
-pub fn router() -> Router<BackendState> {
+pub fn router(
+    compression: &CompressionConfig,
+    rate_limits: &RateLimitsConfig,
+) -> Router<BackendState> {
     let cors = CorsLayer::new()
         .allow_methods([
             Method::GET,
@@ -48,27 +87,114 @@ pub fn router() -> Router<BackendState> {
             http::header::ACCEPT,
         ]);
 
-    Router::new()
+    let client_ip_header = rate_limits.client_ip_header.clone();
+    let trade_limiter = ClientRateLimiter::new(rate_limits.trade, client_ip_header.clone());
+    let create_limiter = ClientRateLimiter::new(rate_limits.create_project, client_ip_header);
+    trade_limiter.serve_pruning();
+    create_limiter.serve_pruning();
+
+    let router = Router::new()
         .nest(
             "/project",
             Router::new()
-                .route("/create", post(create_project))
-                .route("/buy", post(buy))
-                .route("/sell", post(sell))
+                .route(
+                    "/create",
+                    create_limiter.apply(post(create_project), body_limit::CREATE_PROJECT),
+                )
+                .route(
+                    "/batch_create",
+                    body_limit::BATCH_CREATE_PROJECT.apply(post(batch_create_projects)),
+                )
+                .route("/buy", trade_limiter.apply(post(buy), body_limit::TRADE))
+                .route(
+                    "/buy_exact_tokens",
+                    trade_limiter.apply(post(buy_exact_tokens), body_limit::TRADE),
+                )
+                .route("/sell", trade_limiter.apply(post(sell), body_limit::TRADE))
+                .route(
+                    "/sell_exact_sol",
+                    trade_limiter.apply(post(sell_exact_sol), body_limit::TRADE),
+                )
+                .route("/best_buy_route", get(get_best_buy_route))
+                .route("/quote", get(get_quote))
                 .route("/claim_dev_lock", post(claim_dev_lock))
-                .route("/get", get(get_project)),
+                .route("/transfer_ownership", post(transfer_ownership))
+                .route("/accept_transfer", post(accept_transfer))
+                .route("/cancel_transfer", post(cancel_transfer))
+                .route("/claim_creator_fees", post(claim_creator_fees))
+                .route("/cancel", post(cancel_project))
+                .route("/update_meta", post(update_project_meta))
+                .route("/get", body_limit::GET_PROJECT.apply(get(get_project)))
+                .route("/list", get(list_projects))
+                .route("/search", get(search_projects))
+                .route("/leaderboard", get(get_leaderboard))
+                .route("/holders", get(get_holders))
+                .route("/stats", get(get_project_stats))
+                .route("/candles", get(get_candles))
+                .route("/curve_state", get(get_curve_state))
+                .route("/sse/{project_id}", get(static_pool_progress_sse))
+                .route("/stream", get(price_stream))
+                .route("/lp_burn_proof", get(get_lp_burn_proof)),
+        )
+        .nest(
+            "/config",
+            Router::new().route("/deployment_rules", get(get_deployment_rules)),
+        )
+        .nest(
+            "/webhook",
+            Router::new()
+                .route("/register", post(register_webhook))
+                .route("/{id}", delete(delete_webhook)),
         )
         .nest(
             "/user",
             Router::new()
                 .route("/get", get(get_user_info))
-                .route("/upsert", post(upsert_username))
-                .route("/owned-nfts", get(get_nft_owned_by_user)),
+                .route("/upsert", body_limit::USERNAME.apply(post(upsert_username)))
+                .route("/owned-nfts", get(get_nft_owned_by_user))
+                .route("/resolve_sns", get(resolve_sns))
+                .route("/resolve_address", get(resolve_address))
+                .route("/notifications", put(update_notifications))
+                .route(
+                    "/notifications/verify_email",
+                    post(verify_notifications_email),
+                )
+                .route(
+                    "/notifications/confirm_email",
+                    get(confirm_notifications_email),
+                ),
         )
         .layer(cors)
-        .layer(DefaultBodyLimit::max(1024 * 4))
+        .layer(DefaultBodyLimit::max(body_limit::DEFAULT_MAX_BYTES));
+    compression::apply(router, compression)
 }
 
+/// Served on the internal admin listener only.
+pub fn admin_router() -> Router<BackendState> {
+    Router::new()
+        .route("/metrics", get(crate::metrics::export))
+        .route("/tx_failures", get(get_tx_failures))
+        .route("/db/pool_stats", get(get_pool_stats))
+        .route("/migration_locks", get(get_migration_locks))
+        .route("/program_upgrade_proposals", get(get_upgrade_proposals))
+        .route("/ipfs/stats", get(get_ipfs_stats))
+        .route("/revenue", get(get_revenue))
+        .route("/jito/set_strategy", post(set_jito_tip_strategy))
+        .route("/fee/config", post(set_fee_config))
+        .route("/fee/project_creation", post(set_project_creation_fee))
+        .route("/config/launch_periods", put(set_launch_periods))
+        .route("/config/delivery_methods", put(set_delivery_methods))
+        .route("/config/allowed_owners", put(set_allowed_owners))
+        .route("/project/extend_static_pool", post(extend_static_pool))
+        .route("/project/force_unlock", post(force_unlock_project))
+        .route("/recover_project", post(recover_project))
+        .route("/pool/update_config", post(update_pool_config))
+        .route("/project/{id}", delete(archive_project))
+        .route("/project/{id}/restore", post(restore_project))
+}
+
+/// Request body, including the image, is limited to 2MB.
+/// Images above 512x512 or 256KB are downscaled and compressed to WebP before they are stored.
 #[utoipa::path(
     post,
     tag = "project",
@@ -111,6 +237,27 @@ pub async fn create_project(
     Ok(AppJson(state.app().create_project(request, streams).await?))
 }
 
+/// Creates up to 5 projects of distinct owners, with images already hosted by `image_url`.
+/// Responses follow the order of requests. Request body is limited to 64KB.
+#[utoipa::path(
+    post,
+    tag = "project",
+    path = "/api/project/batch_create",
+    request_body = Vec<CreateProjectRequest>,
+    responses(
+        (status = 200, description = "Successfully created projects", body = Vec<CreateProjectResponse>),
+        ErrorResponse
+    )
+)]
+pub async fn batch_create_projects(
+    State(state): State<BackendState>,
+    _captcha: Captcha,
+    Json(requests): Json<Vec<CreateProjectRequest>>,
+) -> Result<AppJson<Vec<CreateProjectResponse>>, ApiError> {
+    Ok(AppJson(state.app().batch_create_projects(requests).await?))
+}
+
+/// Request body is limited to 2KB.
 #[utoipa::path(
     post,
     tag = "project",
@@ -128,6 +275,59 @@ pub async fn buy(
     Ok(AppJson(state.app().buy(request).await?))
 }
 
+/// Moonzip curve pool only. Request body is limited to 2KB.
+#[utoipa::path(
+    post,
+    tag = "project",
+    path = "/api/project/buy_exact_tokens",
+    responses(
+        (status = 200, description = "Successfully bought exact amount of tokens from project", body = BuyResponse),
+        ErrorResponse
+    )
+)]
+pub async fn buy_exact_tokens(
+    State(state): State<BackendState>,
+    _captcha: Captcha,
+    Json(request): Json<BuyExactTokensRequest>,
+) -> Result<AppJson<BuyResponse>, ApiError> {
+    Ok(AppJson(state.app().buy_exact_tokens(request).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "project",
+    path = "/api/project/best_buy_route",
+    params(BestBuyRouteRequest),
+    responses(
+        (status = 200, description = "Route with the best expected output for the buy", body = BestBuyRouteResponse),
+        ErrorResponse
+    )
+)]
+pub async fn get_best_buy_route(
+    State(state): State<BackendState>,
+    Query(request): Query<BestBuyRouteRequest>,
+) -> Result<AppJson<BestBuyRouteResponse>, ApiError> {
+    Ok(AppJson(state.app().get_best_route(request).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "project",
+    path = "/api/project/quote",
+    params(QuoteRequest),
+    responses(
+        (status = 200, description = "Expected output of the buy or sell at the current stage", body = QuoteResponse),
+        ErrorResponse
+    )
+)]
+pub async fn get_quote(
+    State(state): State<BackendState>,
+    Query(request): Query<QuoteRequest>,
+) -> Result<AppJson<QuoteResponse>, ApiError> {
+    Ok(AppJson(state.app().quote(request).await?))
+}
+
+/// Request body is limited to 2KB.
 #[utoipa::path(
     post,
     tag = "project",
@@ -145,6 +345,24 @@ pub async fn sell(
     Ok(AppJson(state.app().sell(request).await?))
 }
 
+/// Moonzip curve pool only. Request body is limited to 2KB.
+#[utoipa::path(
+    post,
+    tag = "project",
+    path = "/api/project/sell_exact_sol",
+    responses(
+        (status = 200, description = "Successfully sold tokens to receive exact amount of sols", body = SellResponse),
+        ErrorResponse
+    )
+)]
+pub async fn sell_exact_sol(
+    State(state): State<BackendState>,
+    _captcha: Captcha,
+    Json(request): Json<SellExactSolRequest>,
+) -> Result<AppJson<SellResponse>, ApiError> {
+    Ok(AppJson(state.app().sell_exact_sol(request).await?))
+}
+
 #[utoipa::path(
     post,
     tag = "project",
@@ -161,6 +379,112 @@ pub async fn claim_dev_lock(
     Ok(AppJson(state.app().dev_lock_claim(request).await?))
 }
 
+#[utoipa::path(
+    post,
+    tag = "project",
+    path = "/api/project/transfer_ownership",
+    responses(
+        (status = 200, description = "Provided transaction to propose ownership transfer", body = TransferOwnershipResponse),
+        ErrorResponse
+    )
+)]
+pub async fn transfer_ownership(
+    State(state): State<BackendState>,
+    User { key }: User,
+    Json(request): Json<TransferOwnershipRequest>,
+) -> Result<AppJson<TransferOwnershipResponse>, ApiError> {
+    Ok(AppJson(state.app().transfer_ownership(key, request).await?))
+}
+
+/// Metadata is deployed again asynchronously by the migrator.
+#[utoipa::path(
+    post,
+    tag = "project",
+    path = "/api/project/update_meta",
+    request_body = UpdateProjectMetaRequest,
+    responses(
+        (status = 200, description = "Metadata is updated and scheduled for deployment"),
+        ErrorResponse
+    )
+)]
+pub async fn update_project_meta(
+    State(state): State<BackendState>,
+    User { key }: User,
+    Json(request): Json<UpdateProjectMetaRequest>,
+) -> Result<AppJson<()>, ApiError> {
+    state.app().update_project_meta(key, request).await?;
+    Ok(AppJson(()))
+}
+
+#[utoipa::path(
+    post,
+    tag = "project",
+    path = "/api/project/accept_transfer",
+    responses(
+        (status = 200, description = "Provided transaction to accept ownership transfer", body = AcceptTransferResponse),
+        ErrorResponse
+    )
+)]
+pub async fn accept_transfer(
+    State(state): State<BackendState>,
+    User { key }: User,
+    Json(request): Json<AcceptTransferRequest>,
+) -> Result<AppJson<AcceptTransferResponse>, ApiError> {
+    Ok(AppJson(state.app().accept_transfer(key, request).await?))
+}
+
+#[utoipa::path(
+    post,
+    tag = "project",
+    path = "/api/project/cancel_transfer",
+    responses(
+        (status = 200, description = "Provided transaction to cancel pending ownership transfer", body = CancelTransferResponse),
+        ErrorResponse
+    )
+)]
+pub async fn cancel_transfer(
+    State(state): State<BackendState>,
+    User { key }: User,
+    Json(request): Json<CancelTransferRequest>,
+) -> Result<AppJson<CancelTransferResponse>, ApiError> {
+    Ok(AppJson(state.app().cancel_transfer(key, request).await?))
+}
+
+#[utoipa::path(
+    post,
+    tag = "project",
+    path = "/api/project/claim_creator_fees",
+    responses(
+        (status = 200, description = "Provided transaction to claim creator share of curve trade fees", body = ClaimCreatorFeesResponse),
+        ErrorResponse
+    )
+)]
+pub async fn claim_creator_fees(
+    State(state): State<BackendState>,
+    User { key }: User,
+    Json(request): Json<ClaimCreatorFeesRequest>,
+) -> Result<AppJson<ClaimCreatorFeesResponse>, ApiError> {
+    Ok(AppJson(state.app().claim_creator_fees(key, request).await?))
+}
+
+#[utoipa::path(
+    post,
+    tag = "project",
+    path = "/api/project/cancel",
+    responses(
+        (status = 200, description = "Provided transaction to cancel project and refund its deposit", body = CancelProjectResponse),
+        ErrorResponse
+    )
+)]
+pub async fn cancel_project(
+    State(state): State<BackendState>,
+    User { key }: User,
+    Json(request): Json<CancelProjectRequest>,
+) -> Result<AppJson<CancelProjectResponse>, ApiError> {
+    Ok(AppJson(state.app().cancel_project(key, request).await?))
+}
+
+/// Request body is limited to 2KB, project is requested by query parameters.
 #[utoipa::path(
     get,
     tag = "project",
@@ -178,6 +502,251 @@ pub async fn get_project(
     Ok(AppJson(state.app().get_project(request).await?))
 }
 
+#[utoipa::path(
+    get,
+    tag = "project",
+    path = "/api/project/leaderboard",
+    params(LeaderboardRequest),
+    responses(
+        (status = 200, description = "Page of projects, ranked by the chosen stats", body = LeaderboardResponse),
+        ErrorResponse
+    )
+)]
+pub async fn get_leaderboard(
+    State(state): State<BackendState>,
+    Query(request): Query<LeaderboardRequest>,
+) -> Result<AppJson<LeaderboardResponse>, ApiError> {
+    Ok(AppJson(state.app().get_leaderboard(request).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "project",
+    path = "/api/project/list",
+    params(ListProjectsRequest),
+    responses(
+        (status = 200, description = "Page of projects, newest first by default", body = ListProjectsResponse),
+        ErrorResponse
+    )
+)]
+pub async fn list_projects(
+    State(state): State<BackendState>,
+    Query(request): Query<ListProjectsRequest>,
+) -> Result<AppJson<ListProjectsResponse>, ApiError> {
+    let response = state.app().list_projects(request).await.map_err(|err| {
+        if err.is::<InvalidListCursor>() {
+            ApiError::InvalidRequest(err)
+        } else {
+            ApiError::Internal(err)
+        }
+    })?;
+    Ok(AppJson(response))
+}
+
+#[utoipa::path(
+    get,
+    tag = "project",
+    path = "/api/project/search",
+    params(SearchProjectsRequest),
+    responses(
+        (status = 200, description = "Projects, matching the query by token meta", body = SearchProjectsResponse),
+        ErrorResponse
+    )
+)]
+pub async fn search_projects(
+    State(state): State<BackendState>,
+    Query(request): Query<SearchProjectsRequest>,
+) -> Result<AppJson<SearchProjectsResponse>, ApiError> {
+    Ok(AppJson(state.app().search_projects(request).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "project",
+    path = "/api/project/stats",
+    params(GetProjectStatsRequest),
+    responses(
+        (status = 200, description = "Trade statistics of the project for the period", body = ProjectStats),
+        ErrorResponse
+    )
+)]
+pub async fn get_project_stats(
+    State(state): State<BackendState>,
+    Query(request): Query<GetProjectStatsRequest>,
+) -> Result<AppJson<ProjectStats>, ApiError> {
+    Ok(AppJson(state.app().get_project_stats(request).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "project",
+    path = "/api/project/curve_state",
+    params(GetCurveStateRequest),
+    responses(
+        (status = 200, description = "Live state of the moonzip curve pool, read from chain", body = CurveState),
+        ErrorResponse
+    )
+)]
+pub async fn get_curve_state(
+    State(state): State<BackendState>,
+    Query(request): Query<GetCurveStateRequest>,
+) -> Result<AppJson<CurveState>, ApiError> {
+    Ok(AppJson(state.app().get_curve_state(request).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "project",
+    path = "/api/project/candles",
+    params(GetCandlesRequest),
+    responses(
+        (status = 200, description = "OHLCV candles of the project, ordered from the oldest", body = Vec<Candle>),
+        ErrorResponse
+    )
+)]
+pub async fn get_candles(
+    State(state): State<BackendState>,
+    Query(request): Query<GetCandlesRequest>,
+) -> Result<AppJson<Vec<Candle>>, ApiError> {
+    Ok(AppJson(state.app().get_candles(request).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "project",
+    path = "/api/project/holders",
+    params(GetHoldersRequest),
+    responses(
+        (status = 200, description = "Page of token holders of the project, largest first", body = GetHoldersResponse),
+        ErrorResponse
+    )
+)]
+pub async fn get_holders(
+    State(state): State<BackendState>,
+    Query(request): Query<GetHoldersRequest>,
+) -> Result<AppJson<GetHoldersResponse>, ApiError> {
+    Ok(AppJson(state.app().get_holders(request).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "project",
+    path = "/api/project/sse/{project_id}",
+    params(("project_id" = ProjectId, Path, description = "Project with static pool")),
+    responses(
+        (status = 200, description = "Stream of static pool progress updates", body = StaticPoolProgress, content_type = "text/event-stream"),
+        ErrorResponse
+    )
+)]
+pub async fn static_pool_progress_sse(
+    State(state): State<BackendState>,
+    Path(project_id): Path<ProjectId>,
+) -> Result<impl IntoResponse, ApiError> {
+    let receiver = state
+        .app()
+        .subscribe_static_pool_progress(project_id)
+        .await?;
+    // current progress is sent right away, then on every change
+    let events = stream::unfold((receiver, true), |(mut receiver, first)| async move {
+        if !first && receiver.changed().await.is_err() {
+            return None;
+        }
+        let event = Event::default().json_data(&*receiver.borrow_and_update());
+        Some((event, (receiver, false)))
+    });
+    let sse = Sse::new(events).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("heartbeat"),
+    );
+    Ok(([(header::CACHE_CONTROL, "no-cache")], sse))
+}
+
+#[utoipa::path(
+    get,
+    tag = "project",
+    path = "/api/project/stream",
+    params(PriceStreamRequest),
+    responses(
+        (status = 101, description = "Websocket with JSON text message per curve trade of the project", body = PriceStreamEvent),
+        ErrorResponse
+    )
+)]
+pub async fn price_stream(
+    State(state): State<BackendState>,
+    Query(request): Query<PriceStreamRequest>,
+    upgrade: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let app = state.app();
+    upgrade.on_upgrade(move |socket| stream_trades(app, request.project_id, socket))
+}
+
+#[utoipa::path(
+    get,
+    tag = "project",
+    path = "/api/project/lp_burn_proof",
+    params(GetLpBurnProofRequest),
+    responses(
+        (status = 200, description = "Burn of raydium LP tokens, received on graduation", body = GetLpBurnProofResponse),
+        ErrorResponse
+    )
+)]
+pub async fn get_lp_burn_proof(
+    State(state): State<BackendState>,
+    Query(request): Query<GetLpBurnProofRequest>,
+) -> Result<AppJson<GetLpBurnProofResponse>, ApiError> {
+    Ok(AppJson(state.app().lp_burn_proof(request).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "config",
+    path = "/api/config/deployment_rules",
+    responses(
+        (status = 200, description = "Rules applied to newly created projects", body = DeploymentRules),
+        ErrorResponse
+    )
+)]
+pub async fn get_deployment_rules(
+    State(state): State<BackendState>,
+) -> Result<AppJson<DeploymentRules>, ApiError> {
+    Ok(AppJson(state.app().deployment_rules().await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "user",
+    path = "/api/user/resolve_sns",
+    params(ResolveSnsRequest),
+    responses(
+        (status = 200, description = "`.sol` name of the wallet, if any", body = ResolveSnsResponse),
+        ErrorResponse
+    )
+)]
+pub async fn resolve_sns(
+    State(state): State<BackendState>,
+    Query(request): Query<ResolveSnsRequest>,
+) -> Result<AppJson<ResolveSnsResponse>, ApiError> {
+    Ok(AppJson(state.app().resolve_sns(request).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "user",
+    path = "/api/user/resolve_address",
+    params(ResolveAddressRequest),
+    responses(
+        (status = 200, description = "Owner of the `.sol` name, if registered", body = ResolveAddressResponse),
+        ErrorResponse
+    )
+)]
+pub async fn resolve_address(
+    State(state): State<BackendState>,
+    Query(request): Query<ResolveAddressRequest>,
+) -> Result<AppJson<ResolveAddressResponse>, ApiError> {
+    Ok(AppJson(state.app().resolve_address(request).await?))
+}
+
 #[utoipa::path(
     get,
     tag = "user",
@@ -196,6 +765,7 @@ pub async fn get_user_info(
     ))
 }
 
+/// Request body is limited to 512 bytes.
 #[utoipa::path(
     post,
     tag = "user",
@@ -234,3 +804,414 @@ pub async fn get_nft_owned_by_user(
     let response = state.app().get_owned_nfts_by_address(request).await?;
     Ok(AppJson(response))
 }
+
+#[utoipa::path(
+    put,
+    tag = "user",
+    path = "/api/user/notifications",
+    responses(
+        (status = 200, description = "Successfully updated notification preferences", body = NotificationPreferences),
+        ErrorResponse
+    )
+)]
+pub async fn update_notifications(
+    State(state): State<BackendState>,
+    User { key }: User,
+    Json(request): Json<UpdateNotificationsRequest>,
+) -> Result<AppJson<NotificationPreferences>, ApiError> {
+    request
+        .validate()
+        .map_err(|err| ApiError::InvalidRequest(anyhow::anyhow!(err)))?;
+    Ok(AppJson(
+        state
+            .app()
+            .update_notification_preferences(key, request)
+            .await?,
+    ))
+}
+
+/// Payloads are signed, see [`crate::app::exposed::WebhookPayload`].
+#[utoipa::path(
+    post,
+    tag = "webhook",
+    path = "/api/webhook/register",
+    request_body = RegisterWebhookRequest,
+    responses(
+        (status = 200, description = "Webhook is registered, its secret is shown once", body = RegisterWebhookResponse),
+        ErrorResponse
+    )
+)]
+pub async fn register_webhook(
+    State(state): State<BackendState>,
+    User { key }: User,
+    Json(request): Json<RegisterWebhookRequest>,
+) -> Result<AppJson<RegisterWebhookResponse>, ApiError> {
+    request
+        .validate()
+        .map_err(|err| ApiError::InvalidRequest(anyhow::anyhow!(err)))?;
+    Ok(AppJson(state.app().register_webhook(key, request).await?))
+}
+
+#[utoipa::path(
+    delete,
+    tag = "webhook",
+    path = "/api/webhook/{id}",
+    params(("id" = Uuid, Path, description = "Webhook to remove")),
+    responses(
+        (status = 200, description = "Webhook is removed"),
+        ErrorResponse
+    )
+)]
+pub async fn delete_webhook(
+    State(state): State<BackendState>,
+    User { key }: User,
+    Path(id): Path<Uuid>,
+) -> Result<AppJson<()>, ApiError> {
+    state.app().delete_webhook(key, id).await?;
+    Ok(AppJson(()))
+}
+
+#[utoipa::path(
+    post,
+    tag = "user",
+    path = "/api/user/notifications/verify_email",
+    responses(
+        (status = 200, description = "Verification link is sent to the notifications email"),
+        ErrorResponse
+    )
+)]
+pub async fn verify_notifications_email(
+    State(state): State<BackendState>,
+    User { key }: User,
+) -> Result<AppJson<()>, ApiError> {
+    state.app().send_email_verification(key).await?;
+    Ok(AppJson(()))
+}
+
+#[utoipa::path(
+    get,
+    tag = "user",
+    path = "/api/user/notifications/confirm_email",
+    params(ConfirmEmailRequest),
+    responses(
+        (status = 200, description = "Notifications email is verified"),
+        ErrorResponse
+    )
+)]
+pub async fn confirm_notifications_email(
+    State(state): State<BackendState>,
+    Query(request): Query<ConfirmEmailRequest>,
+) -> Result<AppJson<()>, ApiError> {
+    state.app().confirm_email(request).await?;
+    Ok(AppJson(()))
+}
+
+#[utoipa::path(
+    get,
+    tag = "admin",
+    path = "/admin/tx_failures",
+    params(GetTxFailuresRequest),
+    responses(
+        (status = 200, description = "Latest failed transactions with their logs", body = Vec<TxFailure>),
+        ErrorResponse
+    )
+)]
+pub async fn get_tx_failures(
+    State(state): State<BackendState>,
+    Query(request): Query<GetTxFailuresRequest>,
+) -> Result<AppJson<Vec<TxFailure>>, ApiError> {
+    Ok(AppJson(state.app().tx_failures(request).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "admin",
+    path = "/admin/db/pool_stats",
+    responses(
+        (status = 200, description = "Current database pool statistics", body = PoolStats),
+        ErrorResponse
+    )
+)]
+pub async fn get_pool_stats(
+    State(state): State<BackendState>,
+) -> Result<AppJson<PoolStats>, ApiError> {
+    Ok(AppJson(state.app().pool_stats()))
+}
+
+#[utoipa::path(
+    get,
+    tag = "admin",
+    path = "/admin/migration_locks",
+    responses(
+        (status = 200, description = "Projects, currently locked by migrator, oldest locks first", body = Vec<MigrationLock>),
+        ErrorResponse
+    )
+)]
+pub async fn get_migration_locks(
+    State(state): State<BackendState>,
+) -> Result<AppJson<Vec<MigrationLock>>, ApiError> {
+    Ok(AppJson(state.app().migration_locks().await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "admin",
+    path = "/admin/program_upgrade_proposals",
+    responses(
+        (status = 200, description = "Program upgrades, proposed on-chain, latest first", body = Vec<UpgradeProposal>),
+        ErrorResponse
+    )
+)]
+pub async fn get_upgrade_proposals(
+    State(state): State<BackendState>,
+) -> Result<AppJson<Vec<UpgradeProposal>>, ApiError> {
+    Ok(AppJson(state.app().upgrade_proposals().await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "admin",
+    path = "/admin/ipfs/stats",
+    responses(
+        (status = 200, description = "IPFS uploads, including ones skipped by deduplication", body = IpfsStats),
+        ErrorResponse
+    )
+)]
+pub async fn get_ipfs_stats(
+    State(state): State<BackendState>,
+) -> Result<AppJson<IpfsStats>, ApiError> {
+    Ok(AppJson(state.app().ipfs_stats().await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "admin",
+    path = "/admin/revenue",
+    params(GetRevenueRequest),
+    responses(
+        (status = 200, description = "Fee, collected by the program per UTC day, latest days first", body = Vec<DailyRevenue>),
+        ErrorResponse
+    )
+)]
+pub async fn get_revenue(
+    State(state): State<BackendState>,
+    Query(request): Query<GetRevenueRequest>,
+) -> Result<AppJson<Vec<DailyRevenue>>, ApiError> {
+    Ok(AppJson(state.app().revenue(request).await?))
+}
+
+#[utoipa::path(
+    post,
+    tag = "admin",
+    path = "/admin/fee/config",
+    request_body = SetFeeConfigRequest,
+    responses(
+        (status = 200, description = "Fee config is updated on-chain", body = SetFeeConfigResponse),
+        ErrorResponse
+    )
+)]
+pub async fn set_fee_config(
+    State(state): State<BackendState>,
+    Json(request): Json<SetFeeConfigRequest>,
+) -> Result<AppJson<SetFeeConfigResponse>, ApiError> {
+    Ok(AppJson(state.app().set_fee_config(request).await?))
+}
+
+#[utoipa::path(
+    post,
+    tag = "admin",
+    path = "/admin/fee/project_creation",
+    request_body = SetProjectCreationFeeRequest,
+    responses(
+        (status = 200, description = "Project creation fee is updated on-chain", body = SetProjectCreationFeeResponse),
+        ErrorResponse
+    )
+)]
+pub async fn set_project_creation_fee(
+    State(state): State<BackendState>,
+    Json(request): Json<SetProjectCreationFeeRequest>,
+) -> Result<AppJson<SetProjectCreationFeeResponse>, ApiError> {
+    Ok(AppJson(
+        state.app().set_project_creation_fee(request).await?,
+    ))
+}
+
+#[utoipa::path(
+    put,
+    tag = "admin",
+    path = "/admin/config/launch_periods",
+    request_body = SetLaunchPeriodsRequest,
+    responses(
+        (status = 200, description = "Launch periods are overridden for all backend instances"),
+        ErrorResponse
+    )
+)]
+pub async fn set_launch_periods(
+    State(state): State<BackendState>,
+    Json(request): Json<SetLaunchPeriodsRequest>,
+) -> Result<AppJson<()>, ApiError> {
+    state.app().set_launch_periods(request).await?;
+    Ok(AppJson(()))
+}
+
+#[utoipa::path(
+    put,
+    tag = "admin",
+    path = "/admin/config/allowed_owners",
+    request_body = SetAllowedOwnersRequest,
+    responses(
+        (status = 200, description = "Owners, bypassing projects creation limits, are overridden for all backend instances"),
+        ErrorResponse
+    )
+)]
+pub async fn set_allowed_owners(
+    State(state): State<BackendState>,
+    Json(request): Json<SetAllowedOwnersRequest>,
+) -> Result<AppJson<()>, ApiError> {
+    state.app().set_allowed_owners(request).await?;
+    Ok(AppJson(()))
+}
+
+#[utoipa::path(
+    put,
+    tag = "admin",
+    path = "/admin/config/delivery_methods",
+    request_body = SetDeliveryMethodsRequest,
+    responses(
+        (status = 200, description = "Dev purchase delivery methods are overridden for all backend instances"),
+        ErrorResponse
+    )
+)]
+pub async fn set_delivery_methods(
+    State(state): State<BackendState>,
+    Json(request): Json<SetDeliveryMethodsRequest>,
+) -> Result<AppJson<()>, ApiError> {
+    state.app().set_delivery_methods(request).await?;
+    Ok(AppJson(()))
+}
+
+#[utoipa::path(
+    post,
+    tag = "admin",
+    path = "/admin/jito/set_strategy",
+    request_body = SetJitoTipStrategyRequest,
+    responses(
+        (status = 200, description = "Tip strategy is changed for api server and migrator"),
+        ErrorResponse
+    )
+)]
+pub async fn set_jito_tip_strategy(
+    State(state): State<BackendState>,
+    Json(request): Json<SetJitoTipStrategyRequest>,
+) -> Result<AppJson<()>, ApiError> {
+    state.app().set_jito_tip_strategy(request).await?;
+    Ok(AppJson(()))
+}
+
+#[utoipa::path(
+    post,
+    tag = "admin",
+    path = "/admin/project/extend_static_pool",
+    request_body = ExtendStaticPoolRequest,
+    responses(
+        (status = 200, description = "Static pool deadline is extended on-chain", body = ExtendStaticPoolResponse),
+        ErrorResponse
+    )
+)]
+pub async fn extend_static_pool(
+    State(state): State<BackendState>,
+    Json(request): Json<ExtendStaticPoolRequest>,
+) -> Result<AppJson<ExtendStaticPoolResponse>, ApiError> {
+    Ok(AppJson(state.app().extend_static_pool(request).await?))
+}
+
+#[utoipa::path(
+    post,
+    tag = "admin",
+    path = "/admin/project/force_unlock",
+    request_body = ForceUnlockRequest,
+    responses(
+        (status = 200, description = "Stale project latch is released on-chain", body = ForceUnlockResponse),
+        ErrorResponse
+    )
+)]
+pub async fn force_unlock_project(
+    State(state): State<BackendState>,
+    Json(request): Json<ForceUnlockRequest>,
+) -> Result<AppJson<ForceUnlockResponse>, ApiError> {
+    Ok(AppJson(state.app().force_unlock_project(request).await?))
+}
+
+/// Requires `Authorization: Bearer <admin token>`.
+#[utoipa::path(
+    post,
+    tag = "admin",
+    path = "/admin/pool/update_config",
+    request_body = UpdatePoolConfigRequest,
+    responses(
+        (status = 200, description = "Config of the live curve pool is replaced on-chain", body = UpdatePoolConfigResponse),
+        ErrorResponse
+    )
+)]
+pub async fn update_pool_config(
+    State(state): State<BackendState>,
+    _admin: AdminAuth,
+    Json(request): Json<UpdatePoolConfigRequest>,
+) -> Result<AppJson<UpdatePoolConfigResponse>, ApiError> {
+    Ok(AppJson(state.app().update_pool_config(request).await?))
+}
+
+/// Requires `Authorization: Bearer <admin token>`.
+#[utoipa::path(
+    post,
+    tag = "admin",
+    path = "/admin/recover_project",
+    request_body = RecoverProjectRequest,
+    responses(
+        (status = 200, description = "Stored project is in line with the chain and migration lock is released", body = RecoverProjectResponse),
+        ErrorResponse
+    )
+)]
+pub async fn recover_project(
+    State(state): State<BackendState>,
+    _admin: AdminAuth,
+    Json(request): Json<RecoverProjectRequest>,
+) -> Result<AppJson<RecoverProjectResponse>, ApiError> {
+    Ok(AppJson(state.app().recover_project(request).await?))
+}
+
+#[utoipa::path(
+    delete,
+    tag = "admin",
+    path = "/admin/project/{id}",
+    params(("id" = ProjectId, Path, description = "Project to archive")),
+    responses(
+        (status = 200, description = "Project is archived and hidden from users"),
+        ErrorResponse
+    )
+)]
+pub async fn archive_project(
+    State(state): State<BackendState>,
+    Path(id): Path<ProjectId>,
+) -> Result<AppJson<()>, ApiError> {
+    state.app().archive_project(id).await?;
+    Ok(AppJson(()))
+}
+
+#[utoipa::path(
+    post,
+    tag = "admin",
+    path = "/admin/project/{id}/restore",
+    params(("id" = ProjectId, Path, description = "Project to restore")),
+    responses(
+        (status = 200, description = "Project is visible to users again"),
+        ErrorResponse
+    )
+)]
+pub async fn restore_project(
+    State(state): State<BackendState>,
+    Path(id): Path<ProjectId>,
+) -> Result<AppJson<()>, ApiError> {
+    state.app().restore_project(id).await?;
+    Ok(AppJson(()))
+}
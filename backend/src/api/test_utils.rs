@@ -0,0 +1,18 @@
+use axum::Router;
+use std::net::SocketAddr;
+
+/// Serves `router` on a random local port and returns its base url.
+/// Handlers see the client address, as they do behind [`services_common::api::server::serve`].
+pub async fn serve(router: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap()
+    });
+    format!("http://{addr}")
+}
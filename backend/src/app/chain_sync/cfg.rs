@@ -1,7 +1,26 @@
 use serde::Deserialize;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Deserialize, serde_derive_default::Default)]
 pub struct ChainSyncConfig {
     #[serde(default)]
     pub allowed_mint_suffix: Option<String>,
+    #[serde(with = "humantime_serde", default = "default_holder_snapshot_interval")]
+    pub holder_snapshot_interval: Duration,
+    #[serde(with = "humantime_serde", default = "default_stats_refresh_interval")]
+    pub stats_refresh_interval: Duration,
+    #[serde(with = "humantime_serde", default = "default_candles_prune_interval")]
+    pub candles_prune_interval: Duration,
+}
+
+fn default_holder_snapshot_interval() -> Duration {
+    Duration::from_secs(5 * 60)
+}
+
+fn default_stats_refresh_interval() -> Duration {
+    Duration::from_secs(5 * 60)
+}
+
+fn default_candles_prune_interval() -> Duration {
+    Duration::from_secs(60 * 60)
 }
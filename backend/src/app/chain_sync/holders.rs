@@ -0,0 +1,34 @@
+use crate::app::storage::{
+    holders::StoredTokenHolder, project_stats::StoredProjectStats, StorageClient,
+};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, error};
+
+/// Periodically stores holder counts into projects and their stats,
+/// so neither project reads nor trades count holders.
+pub struct HolderCountSnapshotter {
+    pub storage: StorageClient,
+    pub interval: Duration,
+}
+
+impl HolderCountSnapshotter {
+    pub fn serve(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = self.tick().await {
+                    error!("holder count snapshot failed: {err:#}");
+                }
+                tokio::time::sleep(self.interval).await;
+            }
+        })
+    }
+
+    async fn tick(&self) -> anyhow::Result<()> {
+        let changed = StoredTokenHolder::snapshot_counts(&self.storage.pool).await?;
+        debug!("holder count snapshot changed for {changed} projects");
+        let changed = StoredProjectStats::refresh_holder_counts(&self.storage.pool).await?;
+        debug!("holder count of stats changed for {changed} projects");
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
 pub mod cfg;
 pub mod fetcher;
 pub mod geyser;
+pub mod holders;
 pub mod parser;
+pub mod router;
+pub mod stats;
 pub mod storage;
@@ -5,11 +5,13 @@ use anchor_client::anchor_lang::{
 };
 use anyhow::{bail, Context as _};
 use moonzip::events::{
-    CurvedPoolBuyEvent, CurvedPoolSellEvent, ProjectChangedEvent, StaticPoolBuyEvent,
-    StaticPoolSellEvent,
+    CurvedPoolBuyEvent, CurvedPoolSellEvent, FeeCollectedEvent, LiquidityBurnedEvent,
+    ProjectCancelledEvent, ProjectChangedEvent, ProjectOwnershipTransferredEvent,
+    StaticPoolBuyEvent, StaticPoolDeadlineExtendedEvent, StaticPoolSellEvent, UpgradeExecutedEvent,
+    UpgradeProposedEvent,
 };
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use tokio::{
     spawn,
     sync::mpsc::{channel, Receiver, Sender},
@@ -20,18 +22,35 @@ use yellowstone_grpc_proto::prelude::{
     InnerInstruction, InnerInstructions, Transaction, TransactionStatusMeta,
 };
 
-use crate::{define_discriminator, utils::ANCHOR_DISCRIMINATOR_BYTE_SIZE};
+use crate::{
+    app::storage::project::{from_chain_project_id, ProjectId},
+    define_discriminator,
+    utils::ANCHOR_DISCRIMINATOR_BYTE_SIZE,
+};
 
-use super::cfg::ChainSyncConfig;
+use super::{
+    cfg::ChainSyncConfig,
+    router::{EventContext, EventRouter, EventRouters},
+};
 
 const BUFFER_CAPACITY: usize = 1000;
 const PROJECT_CHANGED_EVENT: &[u8] = ProjectChangedEvent::DISCRIMINATOR.as_slice();
+const PROJECT_OWNERSHIP_TRANSFERRED_EVENT: &[u8] =
+    ProjectOwnershipTransferredEvent::DISCRIMINATOR.as_slice();
+const PROJECT_CANCELLED_EVENT: &[u8] = ProjectCancelledEvent::DISCRIMINATOR.as_slice();
+const LIQUIDITY_BURNED_EVENT: &[u8] = LiquidityBurnedEvent::DISCRIMINATOR.as_slice();
 
 const CURVE_POOL_BUY_EVENT: &[u8] = CurvedPoolBuyEvent::DISCRIMINATOR.as_slice();
 const CURVE_POOL_SELL_EVENT: &[u8] = CurvedPoolSellEvent::DISCRIMINATOR.as_slice();
 
 const STATIC_POOL_SELL_EVENT: &[u8] = StaticPoolSellEvent::DISCRIMINATOR.as_slice();
 const STATIC_POOL_BUY_EVENT: &[u8] = StaticPoolBuyEvent::DISCRIMINATOR.as_slice();
+const STATIC_POOL_DEADLINE_EXTENDED_EVENT: &[u8] =
+    StaticPoolDeadlineExtendedEvent::DISCRIMINATOR.as_slice();
+
+const UPGRADE_PROPOSED_EVENT: &[u8] = UpgradeProposedEvent::DISCRIMINATOR.as_slice();
+const UPGRADE_EXECUTED_EVENT: &[u8] = UpgradeExecutedEvent::DISCRIMINATOR.as_slice();
+const FEE_COLLECTED_EVENT: &[u8] = FeeCollectedEvent::DISCRIMINATOR.as_slice();
 const TRACKED_PROGRAMS: &[Pubkey] = &[moonzip::ID_CONST, pumpfun_cpi::ID_CONST];
 
 define_discriminator!(TradeEvent, &[189, 219, 127, 211, 78, 230, 97, 238]);
@@ -44,6 +63,7 @@ pub struct ParseInput {
 
 pub struct ParseResult {
     pub slot_number: u64,
+    pub tx_signature: Signature,
     pub events: Vec<TrackedEvent>,
 }
 
@@ -51,6 +71,7 @@ pub struct ParseAggregator {
     input_receiver: Receiver<ParseInput>,
     results_sender: Option<Sender<ParseResult>>,
     config: Arc<ChainSyncConfig>,
+    routers: EventRouters,
 }
 
 impl ParseAggregator {
@@ -59,9 +80,16 @@ impl ParseAggregator {
             input_receiver: blocks,
             results_sender: None,
             config: Arc::new(cfg),
+            routers: EventRouters::default(),
         }
     }
 
+    /// Router gets every parsed event, before it's passed to the storage.
+    pub fn with_router(mut self, router: impl EventRouter + Sync + 'static) -> Self {
+        self.routers.push(router);
+        self
+    }
+
     pub fn serve(mut self) -> Receiver<ParseResult> {
         let (tx, rx) = channel(BUFFER_CAPACITY);
         self.results_sender = Some(tx);
@@ -84,6 +112,13 @@ impl ParseAggregator {
             anyhow::anyhow!("no block could be received: channel unexpectedly closed")
         })?;
         let slot = input.slot;
+        let tx_signature = input
+            .transaction
+            .signatures
+            .first()
+            .map(|signature| Signature::try_from(signature.as_slice()))
+            .transpose()?
+            .unwrap_or_default();
         let tx_to_parse = TransactionToParse {
             transaction: input.transaction,
             inner_instructions: input.meta.inner_instructions,
@@ -103,9 +138,18 @@ impl ParseAggregator {
             return Ok(());
         }
 
+        let ctx = EventContext {
+            slot_number: slot,
+            tx_signature,
+        };
+        for event in &result {
+            self.routers.route(&ctx, event).await.log(&ctx);
+        }
+
         sender
             .send(ParseResult {
                 slot_number: slot,
+                tx_signature,
                 events: result,
             })
             .await?;
@@ -162,10 +206,23 @@ impl Parser {
             moonzip::ID_CONST => {
                 let mzip_event: MoonzipEvent = match discriminator {
                     PROJECT_CHANGED_EVENT => ProjectChangedEvent::deserialize(&mut data)?.into(),
+                    PROJECT_OWNERSHIP_TRANSFERRED_EVENT => {
+                        ProjectOwnershipTransferredEvent::deserialize(&mut data)?.into()
+                    }
+                    PROJECT_CANCELLED_EVENT => {
+                        ProjectCancelledEvent::deserialize(&mut data)?.into()
+                    }
+                    LIQUIDITY_BURNED_EVENT => LiquidityBurnedEvent::deserialize(&mut data)?.into(),
                     STATIC_POOL_SELL_EVENT => StaticPoolSellEvent::deserialize(&mut data)?.into(),
                     STATIC_POOL_BUY_EVENT => StaticPoolBuyEvent::deserialize(&mut data)?.into(),
+                    STATIC_POOL_DEADLINE_EXTENDED_EVENT => {
+                        StaticPoolDeadlineExtendedEvent::deserialize(&mut data)?.into()
+                    }
                     CURVE_POOL_BUY_EVENT => CurvedPoolBuyEvent::deserialize(&mut data)?.into(),
                     CURVE_POOL_SELL_EVENT => CurvedPoolSellEvent::deserialize(&mut data)?.into(),
+                    UPGRADE_PROPOSED_EVENT => UpgradeProposedEvent::deserialize(&mut data)?.into(),
+                    UPGRADE_EXECUTED_EVENT => UpgradeExecutedEvent::deserialize(&mut data)?.into(),
+                    FEE_COLLECTED_EVENT => FeeCollectedEvent::deserialize(&mut data)?.into(),
                     _ => bail!("unsupported moonzip event discriminator: {discriminator:?}"),
                 };
                 Some(TrackedEvent::from(mzip_event))
@@ -233,15 +290,67 @@ pub enum TrackedEvent {
     Moonzip(MoonzipEvent),
 }
 
+impl TrackedEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TrackedEvent::Pumpfun(PumpfunEvent::Trade(_)) => "pumpfun_trade",
+            TrackedEvent::Moonzip(event) => match event {
+                MoonzipEvent::ProjectChanged(_) => "project_changed",
+                MoonzipEvent::ProjectOwnershipTransferred(_) => "project_ownership_transferred",
+                MoonzipEvent::ProjectCancelled(_) => "project_cancelled",
+                MoonzipEvent::LiquidityBurned(_) => "liquidity_burned",
+                MoonzipEvent::StaticPoolBuy(_) => "static_pool_buy",
+                MoonzipEvent::StaticPoolSell(_) => "static_pool_sell",
+                MoonzipEvent::StaticPoolDeadlineExtended(_) => "static_pool_deadline_extended",
+                MoonzipEvent::CurvedPoolBuy(_) => "curved_pool_buy",
+                MoonzipEvent::CurvedPoolSell(_) => "curved_pool_sell",
+                MoonzipEvent::UpgradeProposed(_) => "upgrade_proposed",
+                MoonzipEvent::UpgradeExecuted(_) => "upgrade_executed",
+                MoonzipEvent::FeeCollected(_) => "fee_collected",
+            },
+        }
+    }
+}
+
 #[derive(Debug, derive_more::From)]
 pub enum MoonzipEvent {
     ProjectChanged(ProjectChangedEvent),
+    ProjectOwnershipTransferred(ProjectOwnershipTransferredEvent),
+    ProjectCancelled(ProjectCancelledEvent),
+    LiquidityBurned(LiquidityBurnedEvent),
 
     StaticPoolBuy(StaticPoolBuyEvent),
     StaticPoolSell(StaticPoolSellEvent),
+    StaticPoolDeadlineExtended(StaticPoolDeadlineExtendedEvent),
 
     CurvedPoolBuy(CurvedPoolBuyEvent),
     CurvedPoolSell(CurvedPoolSellEvent),
+
+    UpgradeProposed(UpgradeProposedEvent),
+    UpgradeExecuted(UpgradeExecutedEvent),
+
+    FeeCollected(FeeCollectedEvent),
+}
+
+impl MoonzipEvent {
+    /// Absent for program-wide events.
+    pub fn project_id(&self) -> Option<ProjectId> {
+        let id = match self {
+            MoonzipEvent::ProjectChanged(event) => event.project_id,
+            MoonzipEvent::ProjectOwnershipTransferred(event) => event.project_id,
+            MoonzipEvent::ProjectCancelled(event) => event.project_id,
+            MoonzipEvent::LiquidityBurned(event) => event.project_id,
+            MoonzipEvent::StaticPoolBuy(event) => event.project_id,
+            MoonzipEvent::StaticPoolSell(event) => event.project_id,
+            MoonzipEvent::StaticPoolDeadlineExtended(event) => event.project_id,
+            MoonzipEvent::CurvedPoolBuy(event) => event.project_id,
+            MoonzipEvent::CurvedPoolSell(event) => event.project_id,
+            MoonzipEvent::UpgradeProposed(_)
+            | MoonzipEvent::UpgradeExecuted(_)
+            | MoonzipEvent::FeeCollected(_) => return None,
+        };
+        Some(from_chain_project_id(id))
+    }
 }
 
 #[derive(Debug, derive_more::From)]
@@ -0,0 +1,348 @@
+//! Typed subscriptions to parsed chain events.
+//!
+//! Each subscriber implements only handlers of events it's interested in,
+//! while [`EventRouters`] fans every event out to all of them concurrently.
+use futures::future::join_all;
+use moonzip::events::{
+    CurvedPoolBuyEvent, CurvedPoolSellEvent, FeeCollectedEvent, LiquidityBurnedEvent,
+    ProjectCancelledEvent, ProjectChangedEvent, ProjectOwnershipTransferredEvent,
+    StaticPoolBuyEvent, StaticPoolDeadlineExtendedEvent, StaticPoolSellEvent, UpgradeExecutedEvent,
+    UpgradeProposedEvent,
+};
+use solana_sdk::signature::Signature;
+use tracing::error;
+
+use crate::metrics::CHAIN_SYNC_EVENTS;
+
+use super::parser::{MoonzipEvent, PumpfunEvent, TrackedEvent};
+
+/// Chain transaction, the event was emitted in.
+#[derive(Debug, Clone, Copy)]
+pub struct EventContext {
+    pub slot_number: u64,
+    pub tx_signature: Signature,
+}
+
+#[async_trait::async_trait]
+pub trait EventRouter: Send {
+    /// Used to tell failed routers apart in logs.
+    fn name(&self) -> &'static str;
+
+    async fn handle_project_changed(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &ProjectChangedEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_ownership_transferred(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &ProjectOwnershipTransferredEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_project_cancelled(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &ProjectCancelledEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_liquidity_burned(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &LiquidityBurnedEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_static_pool_buy(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &StaticPoolBuyEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_static_pool_sell(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &StaticPoolSellEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_static_pool_deadline_extended(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &StaticPoolDeadlineExtendedEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_curved_pool_buy(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &CurvedPoolBuyEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_curved_pool_sell(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &CurvedPoolSellEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_upgrade_proposed(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &UpgradeProposedEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_upgrade_executed(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &UpgradeExecutedEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_fee_collected(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &FeeCollectedEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_pumpfun_trade(
+        &mut self,
+        _ctx: &EventContext,
+        _event: &pumpfun_cpi::TradeEvent,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Dispatches the event to its typed handler.
+    async fn route(&mut self, ctx: &EventContext, event: &TrackedEvent) -> anyhow::Result<()> {
+        match event {
+            TrackedEvent::Moonzip(event) => match event {
+                MoonzipEvent::ProjectChanged(event) => {
+                    self.handle_project_changed(ctx, event).await
+                }
+                MoonzipEvent::ProjectOwnershipTransferred(event) => {
+                    self.handle_ownership_transferred(ctx, event).await
+                }
+                MoonzipEvent::ProjectCancelled(event) => {
+                    self.handle_project_cancelled(ctx, event).await
+                }
+                MoonzipEvent::LiquidityBurned(event) => {
+                    self.handle_liquidity_burned(ctx, event).await
+                }
+                MoonzipEvent::StaticPoolBuy(event) => self.handle_static_pool_buy(ctx, event).await,
+                MoonzipEvent::StaticPoolSell(event) => {
+                    self.handle_static_pool_sell(ctx, event).await
+                }
+                MoonzipEvent::StaticPoolDeadlineExtended(event) => {
+                    self.handle_static_pool_deadline_extended(ctx, event).await
+                }
+                MoonzipEvent::CurvedPoolBuy(event) => self.handle_curved_pool_buy(ctx, event).await,
+                MoonzipEvent::CurvedPoolSell(event) => {
+                    self.handle_curved_pool_sell(ctx, event).await
+                }
+                MoonzipEvent::UpgradeProposed(event) => {
+                    self.handle_upgrade_proposed(ctx, event).await
+                }
+                MoonzipEvent::UpgradeExecuted(event) => {
+                    self.handle_upgrade_executed(ctx, event).await
+                }
+                MoonzipEvent::FeeCollected(event) => self.handle_fee_collected(ctx, event).await,
+            },
+            TrackedEvent::Pumpfun(PumpfunEvent::Trade(event)) => {
+                self.handle_pumpfun_trade(ctx, event).await
+            }
+        }
+    }
+}
+
+/// Routes every event to all registered routers at once.
+/// Failure of one router doesn't prevent others from handling the event.
+#[derive(Default)]
+pub struct EventRouters {
+    routers: Vec<Box<dyn EventRouter + Send + Sync>>,
+}
+
+impl EventRouters {
+    pub fn push(&mut self, router: impl EventRouter + Sync + 'static) {
+        self.routers.push(Box::new(router));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routers.is_empty()
+    }
+
+    pub async fn route(&mut self, ctx: &EventContext, event: &TrackedEvent) -> RouteResult {
+        let results = join_all(self.routers.iter_mut().map(|router| async move {
+            let name = router.name();
+            router.route(ctx, event).await.map_err(|err| (name, err))
+        }))
+        .await;
+        RouteResult {
+            errors: results.into_iter().filter_map(Result::err).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RouteResult {
+    /// Name of the failed router with its error.
+    pub errors: Vec<(&'static str, anyhow::Error)>,
+}
+
+impl RouteResult {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn log(&self, ctx: &EventContext) {
+        for (router, err) in &self.errors {
+            error!(
+                "router {router} failed to handle event of tx {}: {err:#}",
+                ctx.tx_signature
+            );
+        }
+    }
+}
+
+/// Counts handled events by their kind.
+pub struct MetricsRouter;
+
+#[async_trait::async_trait]
+impl EventRouter for MetricsRouter {
+    fn name(&self) -> &'static str {
+        "metrics"
+    }
+
+    async fn route(&mut self, _ctx: &EventContext, event: &TrackedEvent) -> anyhow::Result<()> {
+        CHAIN_SYNC_EVENTS.with_label_values(&[event.kind()]).inc();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default, Clone)]
+    struct RecordingRouter {
+        proposed: Arc<Mutex<Vec<[u8; 32]>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl EventRouter for RecordingRouter {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        async fn handle_upgrade_proposed(
+            &mut self,
+            _ctx: &EventContext,
+            event: &UpgradeProposedEvent,
+        ) -> anyhow::Result<()> {
+            self.proposed.lock().unwrap().push(event.upgrade_hash);
+            Ok(())
+        }
+    }
+
+    struct FailingRouter;
+
+    #[async_trait::async_trait]
+    impl EventRouter for FailingRouter {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        async fn handle_upgrade_proposed(
+            &mut self,
+            _ctx: &EventContext,
+            _event: &UpgradeProposedEvent,
+        ) -> anyhow::Result<()> {
+            anyhow::bail!("storage is unavailable")
+        }
+    }
+
+    fn ctx() -> EventContext {
+        EventContext {
+            slot_number: 42,
+            tx_signature: Signature::default(),
+        }
+    }
+
+    fn upgrade_proposed(hash: u8) -> TrackedEvent {
+        MoonzipEvent::from(UpgradeProposedEvent {
+            upgrade_hash: [hash; 32],
+            proposed_at: 0,
+            effective_at: 0,
+            proposer: Pubkey::default(),
+        })
+        .into()
+    }
+
+    #[tokio::test]
+    async fn test_fans_out_event_to_every_router() {
+        let first = RecordingRouter::default();
+        let second = RecordingRouter::default();
+        let mut routers = EventRouters::default();
+        routers.push(first.clone());
+        routers.push(second.clone());
+
+        let result = routers.route(&ctx(), &upgrade_proposed(1)).await;
+        assert!(result.is_ok());
+        assert_eq!(*first.proposed.lock().unwrap(), vec![[1; 32]]);
+        assert_eq!(*second.proposed.lock().unwrap(), vec![[1; 32]]);
+    }
+
+    #[tokio::test]
+    async fn test_collects_errors_without_stopping_other_routers() {
+        let recording = RecordingRouter::default();
+        let mut routers = EventRouters::default();
+        routers.push(FailingRouter);
+        routers.push(recording.clone());
+        routers.push(FailingRouter);
+
+        let result = routers.route(&ctx(), &upgrade_proposed(2)).await;
+        assert_eq!(*recording.proposed.lock().unwrap(), vec![[2; 32]]);
+        let failed: Vec<_> = result.errors.iter().map(|(name, _)| *name).collect();
+        assert_eq!(failed, vec!["failing", "failing"]);
+        assert_eq!(result.errors[0].1.to_string(), "storage is unavailable");
+    }
+
+    #[tokio::test]
+    async fn test_skips_events_without_handlers() {
+        let recording = RecordingRouter::default();
+        let mut routers = EventRouters::default();
+        routers.push(FailingRouter);
+        routers.push(recording.clone());
+
+        let event = MoonzipEvent::from(UpgradeExecutedEvent {
+            upgrade_hash: [3; 32],
+            proposed_at: 0,
+            executed_at: 0,
+        })
+        .into();
+        let result = routers.route(&ctx(), &event).await;
+        assert!(result.is_ok());
+        assert!(recording.proposed.lock().unwrap().is_empty());
+    }
+}
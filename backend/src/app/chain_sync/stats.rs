@@ -0,0 +1,54 @@
+use crate::app::storage::{
+    chain_events::{CandleInterval, StoredCandle, StoredTradeStats},
+    StorageClient,
+};
+use services_common::TZ;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, error};
+
+/// Periodically refreshes the materialized day trade stats.
+pub struct TradeStatsRefresher {
+    pub storage: StorageClient,
+    pub interval: Duration,
+}
+
+impl TradeStatsRefresher {
+    pub fn serve(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = StoredTradeStats::refresh_day_view(&self.storage.pool).await {
+                    error!("trade stats refresh failed: {err:#}");
+                }
+                tokio::time::sleep(self.interval).await;
+            }
+        })
+    }
+}
+
+/// Periodically prunes minute candles, older than [`StoredCandle::MINUTE_RETENTION`].
+pub struct CandlesPruner {
+    pub storage: StorageClient,
+    pub interval: Duration,
+}
+
+impl CandlesPruner {
+    pub fn serve(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let before = TZ::now() - StoredCandle::MINUTE_RETENTION;
+                match StoredCandle::prune(
+                    &self.storage.pool,
+                    CandleInterval::Minute,
+                    before.timestamp(),
+                )
+                .await
+                {
+                    Ok(pruned) => debug!("pruned {pruned} minute candles"),
+                    Err(err) => error!("candles prune failed: {err:#}"),
+                }
+                tokio::time::sleep(self.interval).await;
+            }
+        })
+    }
+}
@@ -1,16 +1,39 @@
 use std::ops::DerefMut as _;
 
-use moonzip::events::{ProjectChangedEvent, StaticPoolBuyEvent, StaticPoolSellEvent};
+use chrono::DateTime;
+use moonzip::{
+    events::{
+        CurvedPoolBuyEvent, CurvedPoolSellEvent, FeeCollectedEvent, LiquidityBurnedEvent,
+        ProjectCancelledEvent, ProjectChangedEvent, ProjectOwnershipTransferredEvent,
+        StaticPoolBuyEvent, StaticPoolDeadlineExtendedEvent, StaticPoolSellEvent,
+        UpgradeExecutedEvent, UpgradeProposedEvent,
+    },
+    project::ProjectStage,
+};
+use services_common::TZ;
+use solana_sdk::signature::Signature;
 use tokio::{spawn, sync::mpsc::Receiver, task::JoinHandle};
-use tracing::{debug, error, instrument};
-
-use crate::app::{
-    chain_sync::parser::{MoonzipEvent, PumpfunEvent},
-    storage::{
-        misc::{Balance, StoredPubkey},
-        project::{self, from_chain_project_id, PumpfunCurveState},
-        DBTransaction, StorageClient,
+use tracing::{debug, error, instrument, warn};
+
+use crate::{
+    app::{
+        chain_sync::router::{EventContext, EventRouter},
+        exposed::{PriceStreamEvent, WebhookEvent},
+        notifications::{Notification, NotificationEvent, NotificationQueue},
+        storage::{
+            chain_events::{self, PriceStreamNotification, StoredCandle, TradePayload},
+            fee_collection,
+            holders::StoredTokenHolder,
+            misc::{Balance, StoredPubkey},
+            notifications::StoredNotificationPreferences,
+            project::{self, from_chain_project_id, CurvedPoolState, ProjectId, PumpfunCurveState},
+            project_stats::StoredProjectStats,
+            static_pool::{self, StaticPoolWalletRecord},
+            DBTransaction, StorageClient,
+        },
+        webhooks::{WebhookQueue, WebhookTrigger},
     },
+    utils::to_hex,
 };
 
 use super::parser::{ParseResult, TrackedEvent};
@@ -18,6 +41,8 @@ use super::parser::{ParseResult, TrackedEvent};
 pub struct StorageApplier {
     storage_client: StorageClient,
     parsed_rx: Receiver<ParseResult>,
+    notifications: Option<NotificationQueue>,
+    webhooks: Option<WebhookQueue>,
 }
 
 impl StorageApplier {
@@ -25,9 +50,21 @@ impl StorageApplier {
         Self {
             storage_client,
             parsed_rx: parse_results,
+            notifications: None,
+            webhooks: None,
         }
     }
 
+    pub fn with_notifications(mut self, notifications: NotificationQueue) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    pub fn with_webhooks(mut self, webhooks: WebhookQueue) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
     pub fn serve(mut self) -> JoinHandle<()> {
         spawn(async move {
             loop {
@@ -43,11 +80,24 @@ impl StorageApplier {
             let mut tx = TransactionProcessor::new(
                 self.storage_client.serializable_tx().await?,
                 result.slot_number,
+                result.tx_signature,
             );
             for event in result.events {
                 tx.process_event(event).await?;
             }
-            tx.commit().await?
+            let pending = tx.commit().await?;
+            if let Some(queue) = self.notifications.as_ref() {
+                pending
+                    .notifications
+                    .into_iter()
+                    .for_each(|notification| queue.push(notification));
+            }
+            if let Some(queue) = self.webhooks.as_ref() {
+                pending
+                    .webhooks
+                    .into_iter()
+                    .for_each(|trigger| queue.push(trigger));
+            }
         }
         anyhow::bail!("unexpected disconnect from parser")
     }
@@ -56,49 +106,368 @@ impl StorageApplier {
 struct TransactionProcessor<'a> {
     transaction: DBTransaction<'a>,
     slot_number: u64,
+    tx_signature: Signature,
+    /// Trades of the transaction, counted into project stats so far, which orders them.
+    stats_trades: i32,
+    /// Notifications are sent only after transaction is committed.
+    pending_notifications: Vec<Notification>,
+    /// Same as notifications, webhooks are called after commit.
+    pending_webhooks: Vec<WebhookTrigger>,
+}
+
+struct PendingDeliveries {
+    notifications: Vec<Notification>,
+    webhooks: Vec<WebhookTrigger>,
 }
 
 impl<'a> TransactionProcessor<'a> {
-    fn new(tx: DBTransaction<'a>, slot_number: u64) -> Self {
+    fn new(tx: DBTransaction<'a>, slot_number: u64, tx_signature: Signature) -> Self {
         Self {
             transaction: tx,
             slot_number,
+            tx_signature,
+            stats_trades: 0,
+            pending_notifications: vec![],
+            pending_webhooks: vec![],
         }
     }
 
     #[instrument(skip(self))]
     async fn process_event(&mut self, event: TrackedEvent) -> anyhow::Result<()> {
         tracing::trace!("applying event on slot {}", self.slot_number);
-        match event {
-            super::parser::TrackedEvent::Moonzip(event) => match event {
-                MoonzipEvent::ProjectChanged(project_changed) => {
-                    apply_project_changed(&mut self.transaction, &project_changed).await?;
-                }
-                MoonzipEvent::StaticPoolBuy(event) => {
-                    apply_static_pool_buy(&mut self.transaction, &event).await?;
-                }
-                MoonzipEvent::StaticPoolSell(event) => {
-                    apply_static_pool_sell(&mut self.transaction, &event).await?;
-                }
-                _ => {
-                    error!("some mzip tracked event not implemented")
-                }
+        let project_id = match &event {
+            TrackedEvent::Moonzip(event) => event.project_id(),
+            TrackedEvent::Pumpfun(_) => None,
+        };
+        if let Some(project_id) = project_id {
+            if project::StoredProject::is_archived(self.transaction.deref_mut(), &project_id)
+                .await?
+            {
+                debug!("skipping event of archived project {project_id}");
+                return Ok(());
+            }
+        }
+        let ctx = EventContext {
+            slot_number: self.slot_number,
+            tx_signature: self.tx_signature,
+        };
+        self.route(&ctx, &event).await
+    }
+
+    async fn record_trade(
+        &mut self,
+        project_id: ProjectId,
+        payload: TradePayload,
+    ) -> anyhow::Result<()> {
+        self.push_webhook(
+            project_id,
+            WebhookEvent::Trade {
+                user: payload.user.to_string(),
+                trade: PriceStreamEvent::from_trade(&payload, TZ::now().timestamp()),
             },
-            super::parser::TrackedEvent::Pumpfun(event) => match event {
-                PumpfunEvent::Trade(event) => {
-                    apply_pumpfun_trade(&mut self.transaction, &event).await?;
-                }
+        );
+        self.record_stats_trade(
+            project_id,
+            payload.is_buy,
+            payload.sols,
+            payload.spot_price(),
+        )
+        .await?;
+        StoredCandle::record_trade(
+            self.transaction.deref_mut(),
+            project_id,
+            &payload,
+            TZ::now().timestamp(),
+        )
+        .await?;
+        chain_events::record_trade(
+            self.transaction.deref_mut(),
+            project_id,
+            &self.tx_signature.to_string(),
+            self.slot_number,
+            payload,
+        )
+        .await
+    }
+
+    /// Counted once per trade, see [`StoredProjectStats::record_trade`].
+    async fn record_stats_trade(
+        &mut self,
+        project_id: ProjectId,
+        is_buy: bool,
+        lamports: u64,
+        price_lamports_per_token: Option<f64>,
+    ) -> anyhow::Result<()> {
+        let trade_index = self.stats_trades;
+        self.stats_trades += 1;
+        StoredProjectStats::record_trade(
+            self.transaction.deref_mut(),
+            project_id,
+            &self.tx_signature.to_string(),
+            trade_index,
+            is_buy,
+            lamports,
+            price_lamports_per_token,
+        )
+        .await
+    }
+
+    /// Streamed to api clients along with recording, see [`Self::record_trade`].
+    async fn record_streamed_trade(
+        &mut self,
+        project_id: ProjectId,
+        payload: TradePayload,
+    ) -> anyhow::Result<()> {
+        let notification = PriceStreamNotification {
+            project_id,
+            event: PriceStreamEvent::from_trade(&payload, TZ::now().timestamp()),
+        };
+        self.record_trade(project_id, payload).await?;
+        chain_events::notify_price_stream(self.transaction.deref_mut(), &notification).await
+    }
+
+    fn push_webhook(&mut self, project_id: ProjectId, event: WebhookEvent) {
+        self.pending_webhooks.push(WebhookTrigger {
+            project_id,
+            tx_signature: self.tx_signature,
+            slot: self.slot_number,
+            event,
+        });
+    }
+
+    async fn commit(self) -> anyhow::Result<PendingDeliveries> {
+        debug!("commit transaction for slot {}", self.slot_number);
+        self.transaction.commit().await?;
+        Ok(PendingDeliveries {
+            notifications: self.pending_notifications,
+            webhooks: self.pending_webhooks,
+        })
+    }
+}
+
+/// Applies events to the database within the transaction of the whole chain transaction.
+#[async_trait::async_trait]
+impl EventRouter for TransactionProcessor<'_> {
+    fn name(&self) -> &'static str {
+        "storage"
+    }
+
+    async fn handle_project_changed(
+        &mut self,
+        _ctx: &EventContext,
+        event: &ProjectChangedEvent,
+    ) -> anyhow::Result<()> {
+        apply_project_changed(&mut self.transaction, event).await?;
+        self.push_webhook(
+            from_chain_project_id(event.project_id),
+            WebhookEvent::StageChanged {
+                stage: project::Stage::from_chain(event.to_stage),
             },
+        );
+        if event.to_stage == ProjectStage::SecondStaticPoolActive {
+            apply_second_static_pool_opened(&mut self.transaction, event).await?;
         }
+        if event.to_stage == ProjectStage::Graduated {
+            recycle_keypair(&mut self.transaction, event).await?;
+            self.pending_notifications
+                .extend(graduation_notification(&mut self.transaction, event).await?);
+        }
+        Ok(())
+    }
 
+    async fn handle_ownership_transferred(
+        &mut self,
+        _ctx: &EventContext,
+        event: &ProjectOwnershipTransferredEvent,
+    ) -> anyhow::Result<()> {
+        apply_ownership_transferred(&mut self.transaction, event).await?;
+        self.push_webhook(
+            from_chain_project_id(event.project_id),
+            WebhookEvent::OwnershipTransferred {
+                to: event.to.to_string(),
+            },
+        );
         Ok(())
     }
 
-    async fn commit(self) -> anyhow::Result<()> {
-        debug!("commit transaction for slot {}", self.slot_number);
-        self.transaction.commit().await?;
+    async fn handle_project_cancelled(
+        &mut self,
+        _ctx: &EventContext,
+        event: &ProjectCancelledEvent,
+    ) -> anyhow::Result<()> {
+        apply_project_cancelled(&mut self.transaction, event).await?;
+        self.push_webhook(
+            from_chain_project_id(event.project_id),
+            WebhookEvent::Cancelled,
+        );
         Ok(())
     }
+
+    async fn handle_liquidity_burned(
+        &mut self,
+        ctx: &EventContext,
+        event: &LiquidityBurnedEvent,
+    ) -> anyhow::Result<()> {
+        apply_liquidity_burned(&mut self.transaction, event, &ctx.tx_signature).await
+    }
+
+    async fn handle_static_pool_buy(
+        &mut self,
+        _ctx: &EventContext,
+        event: &StaticPoolBuyEvent,
+    ) -> anyhow::Result<()> {
+        // pool trades at the fixed rate, so it doesn't set the price
+        let project_id = from_chain_project_id(event.project_id);
+        self.record_stats_trade(project_id, true, event.request_sols, None)
+            .await?;
+        apply_static_pool_buy(&mut self.transaction, event).await
+    }
+
+    async fn handle_static_pool_sell(
+        &mut self,
+        _ctx: &EventContext,
+        event: &StaticPoolSellEvent,
+    ) -> anyhow::Result<()> {
+        let project_id = from_chain_project_id(event.project_id);
+        self.record_stats_trade(project_id, false, event.output_sols, None)
+            .await?;
+        apply_static_pool_sell(&mut self.transaction, event).await
+    }
+
+    async fn handle_static_pool_deadline_extended(
+        &mut self,
+        _ctx: &EventContext,
+        event: &StaticPoolDeadlineExtendedEvent,
+    ) -> anyhow::Result<()> {
+        apply_static_pool_deadline_extended(&mut self.transaction, event).await
+    }
+
+    async fn handle_curved_pool_buy(
+        &mut self,
+        ctx: &EventContext,
+        event: &CurvedPoolBuyEvent,
+    ) -> anyhow::Result<()> {
+        apply_curved_pool_buy(&mut self.transaction, event, ctx.slot_number).await?;
+        upsert_curved_pool_state(
+            &mut self.transaction,
+            event.project_id,
+            CurvedPoolState {
+                virtual_sol_reserves: event.new_virtual_sol_reserves.into(),
+                virtual_token_reserves: event.new_virtual_token_reserves.into(),
+                real_sol_reserves: event.new_real_sol_reserves.into(),
+                real_token_reserves: event.new_real_token_reserves.into(),
+            },
+            ctx.slot_number,
+        )
+        .await?;
+        self.record_streamed_trade(
+            from_chain_project_id(event.project_id),
+            TradePayload {
+                user: event.user,
+                is_buy: true,
+                sols: event.request_sols,
+                tokens: event.tokens_output,
+                spot_price_numerator: event.spot_price_numerator,
+                spot_price_denominator: event.spot_price_denominator,
+            },
+        )
+        .await
+    }
+
+    async fn handle_curved_pool_sell(
+        &mut self,
+        ctx: &EventContext,
+        event: &CurvedPoolSellEvent,
+    ) -> anyhow::Result<()> {
+        apply_curved_pool_sell(&mut self.transaction, event, ctx.slot_number).await?;
+        upsert_curved_pool_state(
+            &mut self.transaction,
+            event.project_id,
+            CurvedPoolState {
+                virtual_sol_reserves: event.new_virtual_sol_reserves.into(),
+                virtual_token_reserves: event.new_virtual_token_reserves.into(),
+                real_sol_reserves: event.new_real_sol_reserves.into(),
+                real_token_reserves: event.new_real_token_reserves.into(),
+            },
+            ctx.slot_number,
+        )
+        .await?;
+        self.record_streamed_trade(
+            from_chain_project_id(event.project_id),
+            TradePayload {
+                user: event.user,
+                is_buy: false,
+                sols: event.sols_output,
+                tokens: event.request_tokens,
+                spot_price_numerator: event.spot_price_numerator,
+                spot_price_denominator: event.spot_price_denominator,
+            },
+        )
+        .await
+    }
+
+    async fn handle_upgrade_proposed(
+        &mut self,
+        ctx: &EventContext,
+        event: &UpgradeProposedEvent,
+    ) -> anyhow::Result<()> {
+        apply_upgrade_proposed(
+            &mut self.transaction,
+            event,
+            &ctx.tx_signature,
+            ctx.slot_number,
+        )
+        .await
+    }
+
+    async fn handle_upgrade_executed(
+        &mut self,
+        _ctx: &EventContext,
+        event: &UpgradeExecutedEvent,
+    ) -> anyhow::Result<()> {
+        apply_upgrade_executed(&mut self.transaction, event).await
+    }
+
+    async fn handle_fee_collected(
+        &mut self,
+        ctx: &EventContext,
+        event: &FeeCollectedEvent,
+    ) -> anyhow::Result<()> {
+        fee_collection::record(
+            self.transaction.deref_mut(),
+            event.recipient.into(),
+            event.amount,
+            event.source.into(),
+            &ctx.tx_signature.to_string(),
+            ctx.slot_number,
+        )
+        .await
+    }
+
+    async fn handle_pumpfun_trade(
+        &mut self,
+        ctx: &EventContext,
+        event: &pumpfun_cpi::TradeEvent,
+    ) -> anyhow::Result<()> {
+        if let Some(project_id) =
+            apply_pumpfun_trade(&mut self.transaction, event, ctx.slot_number).await?
+        {
+            self.record_trade(project_id, pumpfun_trade_payload(event))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+async fn recycle_keypair(
+    tx: &mut DBTransaction<'_>,
+    event: &ProjectChangedEvent,
+) -> anyhow::Result<()> {
+    sqlx::query("CALL recycle_keypair($1)")
+        .bind(from_chain_project_id(event.project_id))
+        .execute(tx.deref_mut())
+        .await?;
+    Ok(())
 }
 
 async fn apply_project_changed(
@@ -122,54 +491,302 @@ async fn apply_project_changed(
     Ok(())
 }
 
-async fn apply_static_pool_buy(
+async fn apply_ownership_transferred(
     tx: &mut DBTransaction<'_>,
-    event: &StaticPoolBuyEvent,
+    event: &ProjectOwnershipTransferredEvent,
 ) -> anyhow::Result<()> {
     let project_id = from_chain_project_id(event.project_id);
-    let collected_lamports = Balance::from(event.new_collected_sols);
 
-    sqlx::query!(
+    sqlx::query("UPDATE project SET owner = $2 WHERE project.id = $1")
+        .bind(project_id)
+        .bind(StoredPubkey::from(event.to))
+        .execute(tx.deref_mut())
+        .await?;
+    Ok(())
+}
+
+/// Project account is closed on cancel, so it's archived to keep the migrator away from it.
+async fn apply_project_cancelled(
+    tx: &mut DBTransaction<'_>,
+    event: &ProjectCancelledEvent,
+) -> anyhow::Result<()> {
+    let project_id = from_chain_project_id(event.project_id);
+
+    sqlx::query("UPDATE project SET archived_at = NOW() WHERE id = $1 AND archived_at IS NULL")
+        .bind(project_id)
+        .execute(tx.deref_mut())
+        .await?;
+    Ok(())
+}
+
+async fn apply_liquidity_burned(
+    tx: &mut DBTransaction<'_>,
+    event: &LiquidityBurnedEvent,
+    tx_signature: &Signature,
+) -> anyhow::Result<()> {
+    let project_id = from_chain_project_id(event.project_id);
+
+    sqlx::query(
         "
-                UPDATE static_pool_chain_state
-                SET state.collected_lamports = $2
-                WHERE project_id = $1;
+            INSERT INTO lp_burns (project_id, lp_mint, burned_amount, tx_signature, slot)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (project_id) DO NOTHING
+        ",
+    )
+    .bind(project_id)
+    .bind(StoredPubkey::from(event.lp_mint))
+    .bind(Balance::from(event.burned_amount))
+    .bind(tx_signature.to_string())
+    .bind(i64::try_from(event.slot)?)
+    .execute(tx.deref_mut())
+    .await?;
+    Ok(())
+}
+
+async fn apply_upgrade_proposed(
+    tx: &mut DBTransaction<'_>,
+    event: &UpgradeProposedEvent,
+    tx_signature: &Signature,
+    slot: u64,
+) -> anyhow::Result<()> {
+    warn!(
+        "program upgrade {} is proposed by {}, effective at {}",
+        to_hex(&event.upgrade_hash),
+        event.proposer,
+        event.effective_at
+    );
+    sqlx::query(
+        "
+            INSERT INTO program_upgrade_proposals
+                (upgrade_hash, proposer, proposed_at, effective_at, tx_signature, slot)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (upgrade_hash) DO UPDATE SET
+                proposer = excluded.proposer,
+                proposed_at = excluded.proposed_at,
+                effective_at = excluded.effective_at,
+                executed_at = NULL,
+                tx_signature = excluded.tx_signature,
+                slot = excluded.slot
         ",
-        &project_id,
-        &collected_lamports as _
     )
+    .bind(event.upgrade_hash.as_slice())
+    .bind(StoredPubkey::from(event.proposer))
+    .bind(chain_timestamp(event.proposed_at)?)
+    .bind(chain_timestamp(event.effective_at)?)
+    .bind(tx_signature.to_string())
+    .bind(i64::try_from(slot)?)
     .execute(tx.deref_mut())
     .await?;
+    Ok(())
+}
 
+async fn apply_upgrade_executed(
+    tx: &mut DBTransaction<'_>,
+    event: &UpgradeExecutedEvent,
+) -> anyhow::Result<()> {
+    warn!(
+        "program upgrade {} is executed",
+        to_hex(&event.upgrade_hash)
+    );
+    sqlx::query(
+        "
+            UPDATE program_upgrade_proposals
+            SET executed_at = $2
+            WHERE upgrade_hash = $1
+        ",
+    )
+    .bind(event.upgrade_hash.as_slice())
+    .bind(chain_timestamp(event.executed_at)?)
+    .execute(tx.deref_mut())
+    .await?;
     Ok(())
 }
 
+fn chain_timestamp(ts: i64) -> anyhow::Result<DateTime<TZ>> {
+    DateTime::from_timestamp(ts, 0).ok_or_else(|| anyhow::anyhow!("invalid timestamp {ts}"))
+}
+
+async fn graduation_notification(
+    tx: &mut DBTransaction<'_>,
+    event: &ProjectChangedEvent,
+) -> anyhow::Result<Option<Notification>> {
+    let project_id = from_chain_project_id(event.project_id);
+    let Some(owner) =
+        sqlx::query_scalar::<_, StoredPubkey>("SELECT owner FROM project WHERE id = $1")
+            .bind(project_id)
+            .fetch_optional(tx.deref_mut())
+            .await?
+    else {
+        return Ok(None);
+    };
+    let wallet = owner.to_pubkey().to_string();
+
+    let Some(preferences) = StoredNotificationPreferences::find(tx.deref_mut(), &wallet).await?
+    else {
+        return Ok(None);
+    };
+    if !preferences.notify_on_graduation {
+        return Ok(None);
+    }
+    let Some(email) = preferences.verified_email() else {
+        return Ok(None);
+    };
+
+    Ok(Some(Notification {
+        email: email.to_owned(),
+        wallet,
+        event: NotificationEvent::Graduation,
+        subject: "Your project has graduated".to_owned(),
+        body: format!("Project {project_id} has successfully graduated."),
+    }))
+}
+
+async fn apply_static_pool_buy(
+    tx: &mut DBTransaction<'_>,
+    event: &StaticPoolBuyEvent,
+) -> anyhow::Result<()> {
+    let project_id = from_chain_project_id(event.project_id);
+    StaticPoolWalletRecord::record(tx.deref_mut(), project_id, StoredPubkey::from(event.user))
+        .await?;
+    set_static_pool_collected(tx, project_id, event.new_collected_sols).await
+}
+
 async fn apply_static_pool_sell(
     tx: &mut DBTransaction<'_>,
     event: &StaticPoolSellEvent,
 ) -> anyhow::Result<()> {
     let project_id = from_chain_project_id(event.project_id);
-    let collected_lamports = Balance::from(event.new_collected_sols);
+    set_static_pool_collected(tx, project_id, event.new_collected_sols).await
+}
 
-    sqlx::query!(
+/// Chain state follows only the current pool,
+/// so lamports of the graduated one are kept to be spent on the curve.
+async fn apply_second_static_pool_opened(
+    tx: &mut DBTransaction<'_>,
+    event: &ProjectChangedEvent,
+) -> anyhow::Result<()> {
+    let project_id = from_chain_project_id(event.project_id);
+    sqlx::query(
         "
-                UPDATE static_pool_chain_state
-                SET state.collected_lamports = $2
-                WHERE project_id = $1;
+            UPDATE project
+            SET deploy_schema.static_pools[1].collected_lamports = COALESCE(
+                (SELECT (state).collected_lamports FROM static_pool_chain_state WHERE project_id = $1),
+                0
+            )
+            WHERE id = $1
         ",
-        &project_id,
-        &collected_lamports as _
     )
+    .bind(project_id)
     .execute(tx.deref_mut())
     .await?;
+    sqlx::query("DELETE FROM static_pool_chain_state WHERE project_id = $1")
+        .bind(project_id)
+        .execute(tx.deref_mut())
+        .await?;
+    static_pool::notify_progress(tx.deref_mut(), project_id).await
+}
 
+/// Migrator closes static pool by the stored launch timestamp, so it follows the chain.
+async fn apply_static_pool_deadline_extended(
+    tx: &mut DBTransaction<'_>,
+    event: &StaticPoolDeadlineExtendedEvent,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "
+            UPDATE project
+            SET deploy_schema.static_pools[
+                    CASE WHEN stage IN ('OnSecondStaticPool', 'SecondStaticPoolClosed') THEN 2 ELSE 1 END
+                ].launch_ts = $2,
+                static_pool_extended_at = NOW()
+            WHERE id = $1
+        ",
+    )
+    .bind(from_chain_project_id(event.project_id))
+    .bind(i64::try_from(event.new_finish_ts)?)
+    .execute(tx.deref_mut())
+    .await?;
+    Ok(())
+}
+
+async fn set_static_pool_collected(
+    tx: &mut DBTransaction<'_>,
+    project_id: ProjectId,
+    collected_lamports: u64,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "
+            INSERT INTO static_pool_chain_state (project_id, state)
+            VALUES ($1, ROW($2)::static_pool_state)
+            ON CONFLICT (project_id) DO UPDATE SET state = excluded.state
+        ",
+    )
+    .bind(project_id)
+    .bind(Balance::from(collected_lamports))
+    .execute(tx.deref_mut())
+    .await?;
+    static_pool::notify_progress(tx.deref_mut(), project_id).await
+}
+
+async fn apply_curved_pool_buy(
+    tx: &mut DBTransaction<'_>,
+    event: &CurvedPoolBuyEvent,
+    slot_number: u64,
+) -> anyhow::Result<()> {
+    StoredTokenHolder::increase(
+        tx.deref_mut(),
+        from_chain_project_id(event.project_id),
+        StoredPubkey::from(event.user),
+        event.tokens_output,
+        slot_number,
+    )
+    .await
+}
+
+async fn apply_curved_pool_sell(
+    tx: &mut DBTransaction<'_>,
+    event: &CurvedPoolSellEvent,
+    slot_number: u64,
+) -> anyhow::Result<()> {
+    StoredTokenHolder::decrease(
+        tx.deref_mut(),
+        from_chain_project_id(event.project_id),
+        StoredPubkey::from(event.user),
+        event.request_tokens,
+        slot_number,
+    )
+    .await
+}
+
+/// Events of the same slot are applied in order, while older slots must not override newer state.
+async fn upsert_curved_pool_state(
+    tx: &mut DBTransaction<'_>,
+    project_id: moonzip::project::ProjectId,
+    state: CurvedPoolState,
+    slot_number: u64,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "
+            INSERT INTO curved_pool_chain_state (mint, state, last_updated_slot)
+            SELECT kp_to_pubkey(curve_pool_keypair), $2, $3 FROM project WHERE id = $1
+            ON CONFLICT (mint) DO UPDATE
+                SET state = excluded.state, last_updated_slot = excluded.last_updated_slot
+                WHERE curved_pool_chain_state.last_updated_slot <= excluded.last_updated_slot
+        ",
+    )
+    .bind(from_chain_project_id(project_id))
+    .bind(state)
+    .bind(i64::try_from(slot_number)?)
+    .execute(tx.deref_mut())
+    .await?;
     Ok(())
 }
 
+/// Returns the project, trading on this curve, if any.
 async fn apply_pumpfun_trade(
     tx: &mut DBTransaction<'_>,
     event: &pumpfun_cpi::TradeEvent,
-) -> anyhow::Result<()> {
+    slot_number: u64,
+) -> anyhow::Result<Option<ProjectId>> {
     let virtual_sol_reserves = Balance::from(event.virtual_sol_reserves);
     let virtual_token_reserves = Balance::from(event.virtual_token_reserves);
     let state = PumpfunCurveState {
@@ -184,11 +801,37 @@ async fn apply_pumpfun_trade(
                 ON CONFLICT (mint) DO UPDATE
                     SET state = excluded.state;
         ",
-        mint as _,
+        mint.clone() as _,
         state as _
     )
     .execute(tx.deref_mut())
     .await?;
 
-    Ok(())
+    let project_id: Option<ProjectId> =
+        sqlx::query_scalar("SELECT id FROM project WHERE kp_to_pubkey(curve_pool_keypair) = $1")
+            .bind(mint)
+            .fetch_optional(tx.deref_mut())
+            .await?;
+    let Some(project_id) = project_id else {
+        return Ok(None);
+    };
+    let user = StoredPubkey::from(event.user);
+    let amount = event.token_amount;
+    if event.is_buy {
+        StoredTokenHolder::increase(tx.deref_mut(), project_id, user, amount, slot_number).await?;
+    } else {
+        StoredTokenHolder::decrease(tx.deref_mut(), project_id, user, amount, slot_number).await?;
+    }
+    Ok(Some(project_id))
+}
+
+fn pumpfun_trade_payload(event: &pumpfun_cpi::TradeEvent) -> TradePayload {
+    TradePayload {
+        user: event.user,
+        is_buy: event.is_buy,
+        sols: event.sol_amount,
+        tokens: event.token_amount,
+        spot_price_numerator: event.virtual_sol_reserves,
+        spot_price_denominator: event.virtual_token_reserves,
+    }
 }
@@ -1,17 +1,42 @@
 use super::storage::{
     self,
-    misc::{StoredKeypair, StoredPubkey},
-    project::{CurveVariant, Stage, StoredDeploySchema, StoredStaticPoolConfig},
+    misc::{Balance, StoredKeypair, StoredPubkey},
+    project::{
+        CurveVariant, GraduationTarget, ProjectIdStrategy, Stage, StoredDeploySchema,
+        StoredStaticPoolConfig,
+    },
 };
+use crate::utils::to_hex;
 use anyhow::bail;
 use chrono::DateTime;
-use rust_decimal::prelude::Zero;
+use moonzip::{
+    curved_pool::{
+        stats::ProjectStats as ChainProjectStats, CurveStateView, CurvedPoolConfig,
+        CurvedPoolStatus,
+    },
+    fee::{BasisPoints, TradeFee},
+    project::MAX_STATIC_POOLS,
+    static_pool::POOL_TOKEN_DECIMALS,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
-use services_common::{utils::serialize_tx_bs64, TZ};
+use services_common::{solana::jito::JitoTipStrategy, utils::serialize_tx_bs64, TZ};
 use solana_sdk::{pubkey::Pubkey, signer::Signer as _, transaction::Transaction};
 use std::time::Duration;
-use storage::user_info::StoredUserInfo;
+use storage::{
+    chain_events::{CandleInterval, StatsPeriod, StoredCandle, StoredTradeStats, TradePayload},
+    fee_collection::StoredDailyRevenue,
+    holders::StoredTokenHolder,
+    ipfs_uploads::StoredIpfsStats,
+    lp_burns::StoredLpBurn,
+    migration_lock::StoredMigrationLock,
+    notifications::StoredNotificationPreferences,
+    project_stats::{LeaderboardSortBy, StoredLeaderboardEntry, StoredProjectStats},
+    search::{SearchMode, SearchSort, SortOrder},
+    tx_failures::StoredTxFailure,
+    upgrade_proposals::StoredUpgradeProposal,
+    user_info::StoredUserInfo,
+};
 use tokio::io::AsyncRead;
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
@@ -25,15 +50,23 @@ pub struct PublicProject {
     #[serde_as(as = "DisplayFromStr")]
     #[schema(value_type = String)]
     pub owner: Pubkey,
+    /// `.sol` name of the owner, resolved only when single project is requested.
+    pub owner_sns: Option<String>,
     pub name: String,
     pub description: String,
     pub created_at: String,
     pub stage: PublicProjectStage,
 
+    /// Mint of the current static pool, see [`PublicProject::static_pool_mints`] for all of them.
     #[serde_as(as = "Option<DisplayFromStr>")]
     #[schema(value_type = Option<String>)]
     pub static_pool_mint: Option<Pubkey>,
 
+    /// Mints of opened static pools, in the order of opening.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[schema(value_type = Vec<String>)]
+    pub static_pool_mints: Vec<Pubkey>,
+
     #[serde_as(as = "Option<DisplayFromStr>")]
     #[schema(value_type = Option<String>)]
     pub curve_pool_mint: Option<Pubkey>,
@@ -41,18 +74,52 @@ pub struct PublicProject {
     #[serde_as(as = "Option<DisplayFromStr>")]
     #[schema(value_type = Option<String>)]
     pub dev_lock_base: Option<Pubkey>,
+
+    /// Checked against the on-chain mint, only when single project is requested.
+    pub mint_authority_revoked: bool,
+
+    /// Refreshed periodically, so could lag behind recent trades.
+    pub holder_count: u64,
+
+    /// Refreshed periodically, same as [`PublicProject::holder_count`].
+    pub volume_24h_lamports: u64,
+    pub trade_count_24h: u64,
+
+    /// Static pool deadline was extended after project launch.
+    pub static_pool_deadline_extended: bool,
+
+    /// Unix timestamp of the on-chain latch lock, read only when single project is requested.
+    /// Latch is released within the same transaction, so a lasting one means project is stuck.
+    pub locked_at: Option<i64>,
+
+    /// Last price on raydium in lamports per token, only for projects graduated to raydium.
+    /// Synced periodically, so could lag behind recent trades.
+    pub raydium_price: Option<f64>,
+
+    /// Lifetime trade totals, only when single project is requested.
+    pub stats: Option<LifetimeStats>,
+
+    /// Totals of the moonzip curve pool, read from the chain if requested, see
+    /// [`GetProjectRequest::with_chain_stats`]. Unlike [`PublicProject::stats`], doesn't lag
+    /// behind the backend syncing the trades.
+    pub chain_stats: Option<ChainTradeStats>,
 }
 
+#[derive(sqlx::FromRow)]
 pub struct StoredProjectInfo {
     pub id: Uuid,
     pub owner: StoredPubkey,
     pub name: String,
     pub description: String,
     pub stage: Stage,
-    pub static_pool_pubkey: Option<StoredPubkey>,
+    pub static_pool_pubkeys: Vec<StoredPubkey>,
     pub curve_pool_keypair: Option<StoredKeypair>,
     pub dev_lock_keypair: Option<StoredKeypair>,
     pub created_at: DateTime<TZ>,
+    pub holder_count_snapshot: i32,
+    pub volume_24h_lamports: Balance,
+    pub trade_count_24h: i64,
+    pub static_pool_extended_at: Option<DateTime<TZ>>,
 }
 
 #[serde_as]
@@ -69,6 +136,7 @@ pub struct UserInfo {
     #[schema(value_type = String)]
     pub nft_address: Option<Pubkey>,
     pub last_active: Option<i64>,
+    pub sns_name: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -83,7 +151,15 @@ impl TryFrom<StoredProjectInfo> for PublicProject {
             bail!("project stage could not be exposed")
         };
 
-        let static_pool_mint = project.static_pool_pubkey.map(|pubkey| pubkey.to_pubkey());
+        let static_pool_mints: Vec<_> = project
+            .static_pool_pubkeys
+            .iter()
+            .map(StoredPubkey::to_pubkey)
+            .collect();
+        let static_pool_mint = project
+            .stage
+            .static_pool_index(static_pool_mints.len())
+            .and_then(|index| static_pool_mints.get(index).copied());
         let mut curve_pool_mint = project
             .curve_pool_keypair
             .map(|keypair| keypair.to_keypair().pubkey());
@@ -94,15 +170,26 @@ impl TryFrom<StoredProjectInfo> for PublicProject {
         Ok(PublicProject {
             id: project.id,
             owner: project.owner.to_pubkey(),
+            owner_sns: None,
             name: project.name,
             description: project.description,
             stage,
             created_at: project.created_at.to_string(),
             static_pool_mint,
+            static_pool_mints,
             curve_pool_mint,
             dev_lock_base: project
                 .dev_lock_keypair
                 .map(|key| key.to_keypair().pubkey()),
+            mint_authority_revoked: false,
+            holder_count: project.holder_count_snapshot.try_into()?,
+            volume_24h_lamports: project.volume_24h_lamports.try_into()?,
+            trade_count_24h: project.trade_count_24h.try_into()?,
+            static_pool_deadline_extended: project.static_pool_extended_at.is_some(),
+            locked_at: None,
+            raydium_price: None,
+            stats: None,
+            chain_stats: None,
         })
     }
 }
@@ -118,17 +205,21 @@ impl TryFrom<StoredUserInfo> for UserInfo {
             image_url: stored.image_url,
             nft_address: stored.nft_address.map(|pubkey| pubkey.to_pubkey()),
             last_active: stored.last_active,
+            sns_name: stored.sns_name,
             created_at: stored.created_at.unwrap().to_string(),
             updated_at: stored.updated_at.unwrap().to_string(),
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[serde(rename_all = "camelCase")]
 pub enum PublicProjectStage {
     StaticPoolActive,
     StaticPoolClosed,
+    SecondStaticPoolActive,
+    SecondStaticPoolClosed,
+    StaticPoolFailed,
     CurvePoolActive,
     CurvePoolClosed,
     Graduated,
@@ -141,9 +232,25 @@ impl PublicProjectStage {
             storage::project::Stage::Confirmed => None,
             storage::project::Stage::OnStaticPool => Some(Self::StaticPoolActive),
             storage::project::Stage::StaticPoolClosed => Some(Self::StaticPoolClosed),
+            storage::project::Stage::OnSecondStaticPool => Some(Self::SecondStaticPoolActive),
+            storage::project::Stage::SecondStaticPoolClosed => Some(Self::SecondStaticPoolClosed),
             storage::project::Stage::OnCurvePool => Some(Self::CurvePoolActive),
             storage::project::Stage::CurvePoolClosed => Some(Self::CurvePoolClosed),
             storage::project::Stage::Graduated => Some(Self::Graduated),
+            storage::project::Stage::StaticPoolFailed => Some(Self::StaticPoolFailed),
+        }
+    }
+
+    pub fn to_stored(&self) -> storage::project::Stage {
+        match self {
+            Self::StaticPoolActive => storage::project::Stage::OnStaticPool,
+            Self::StaticPoolClosed => storage::project::Stage::StaticPoolClosed,
+            Self::SecondStaticPoolActive => storage::project::Stage::OnSecondStaticPool,
+            Self::SecondStaticPoolClosed => storage::project::Stage::SecondStaticPoolClosed,
+            Self::StaticPoolFailed => storage::project::Stage::StaticPoolFailed,
+            Self::CurvePoolActive => storage::project::Stage::OnCurvePool,
+            Self::CurvePoolClosed => storage::project::Stage::CurvePoolClosed,
+            Self::Graduated => storage::project::Stage::Graduated,
         }
     }
 }
@@ -165,6 +272,10 @@ pub struct CreateProjectRequest {
     pub owner: Pubkey,
     pub meta: CreateTokenMeta,
     pub deploy_schema: DeploySchema,
+    /// Already hosted image, required by the batch creation, which carries no image content.
+    /// Single creation takes the image from the form instead.
+    #[serde(default)]
+    pub image_url: Option<String>,
 }
 
 pub struct CreateProjectStreamData<S: AsyncRead> {
@@ -175,37 +286,86 @@ pub struct CreateProjectStreamData<S: AsyncRead> {
 #[serde(rename_all = "camelCase")]
 pub struct StaticPoolSchema {
     pub launch_period: u64,
+    /// Pool closes once it collects this much, `sols_to_graduate` of the config is used if absent.
+    #[serde(default)]
+    pub max_lamports: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DeploySchema {
-    pub static_pool: Option<StaticPoolSchema>,
+    /// Ordered by activation: the next pool is opened once the previous one is graduated.
+    #[serde(default)]
+    pub static_pools: Vec<StaticPoolSchema>,
     pub curve_pool: CurveVariant,
     pub dev_purchase: Option<DevPurchase>,
+    pub token_decimals: Option<u8>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DevPurchase {
     pub value: u64,
-    pub lock: DevLockPeriod,
+    pub delivery: DeliveryMethod,
 }
 
 impl DeploySchema {
-    pub fn try_to_stored(self) -> anyhow::Result<StoredDeploySchema> {
+    /// Pumpfun curve is graduated by pumpfun itself, so `graduation_target` applies to moonzip curve only.
+    /// Meteora launch has neither static pools nor dev purchase, as there is no curve to buy from.
+    pub fn try_to_stored(
+        self,
+        graduation_target: GraduationTarget,
+    ) -> anyhow::Result<StoredDeploySchema> {
+        if let Some(decimals) = self.token_decimals {
+            if self.curve_pool != CurveVariant::Moonzip {
+                bail!("custom token decimals are supported only for moonzip curve");
+            }
+            if decimals > POOL_TOKEN_DECIMALS {
+                bail!("token decimals must not exceed {POOL_TOKEN_DECIMALS}, got {decimals}");
+            }
+        }
+        if self.curve_pool == CurveVariant::Meteora {
+            if !self.static_pools.is_empty() {
+                bail!("static pools are not supported for meteora launch");
+            }
+            if self.dev_purchase.is_some() {
+                bail!("dev purchase is not supported for meteora launch");
+            }
+        }
+        if self.static_pools.len() > usize::from(MAX_STATIC_POOLS) {
+            bail!(
+                "at most {MAX_STATIC_POOLS} static pools are supported, got {}",
+                self.static_pools.len()
+            );
+        }
         let stored = StoredDeploySchema {
-            static_pool: self
-                .static_pool
-                .map(|static_pool| {
+            static_pools: self
+                .static_pools
+                .into_iter()
+                .enumerate()
+                .map(|(index, static_pool)| {
+                    // later pools are assigned deadline once they are opened
+                    let launch_ts = if index == 0 {
+                        (TZ::now() + Duration::from_secs(static_pool.launch_period)).timestamp()
+                    } else {
+                        0
+                    };
                     Result::<_, anyhow::Error>::Ok(StoredStaticPoolConfig {
-                        launch_ts: (TZ::now() + Duration::from_secs(static_pool.launch_period))
-                            .timestamp(),
+                        launch_ts,
+                        launch_period: Some(i64::try_from(static_pool.launch_period)?),
+                        max_lamports: static_pool.max_lamports.map(Balance::from),
+                        collected_lamports: None,
                     })
                 })
-                .transpose()?,
+                .collect::<anyhow::Result<_>>()?,
             curve_pool: self.curve_pool,
             dev_purchase: self.dev_purchase.map(|balance| balance.into()),
+            token_decimals: self.token_decimals.map(i16::from),
+            graduation_target: match self.curve_pool {
+                CurveVariant::Moonzip => graduation_target,
+                CurveVariant::Pumpfun => GraduationTarget::Raydium,
+                CurveVariant::Meteora => GraduationTarget::Meteora,
+            },
         };
         Ok(stored)
     }
@@ -242,6 +402,87 @@ pub struct BuyRequest {
     pub project_id: Uuid,
     pub sols: u64,
     pub min_token_output: Option<u64>,
+    /// If unset, project's token is bought from the pool, defined by the stage.
+    #[serde(default)]
+    pub preferred_route: Option<RoutePreference>,
+    /// If set, exactly this amount of tokens is bought, while `sols` caps lamports spent,
+    /// fee included. Supported by the moonzip curve pool only.
+    #[serde(default)]
+    pub exact_tokens: Option<u64>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BuyExactTokensRequest {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user: Pubkey,
+    pub project_id: Uuid,
+    pub tokens: u64,
+    /// Fee included.
+    pub max_sol_input: u64,
+}
+
+/// Pool, which serves buys of the project's token.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BuyRoute {
+    Curve,
+    Raydium,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RoutePreference {
+    /// Route with the best expected output is chosen.
+    Auto,
+    Curve,
+    Raydium,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct BestBuyRouteRequest {
+    pub project_id: Uuid,
+    pub sols: u64,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BestBuyRouteResponse {
+    pub route: BuyRoute,
+    pub expected_tokens: u64,
+    pub price_impact_bps: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteRequest {
+    pub project_id: Uuid,
+    pub side: TradeSide,
+    /// Lamports to spend on buy, tokens to sell on sell.
+    pub amount: u64,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteResponse {
+    pub side: TradeSide,
+    pub amount: u64,
+    /// Tokens on buy, lamports on sell, after fees.
+    pub expected_output: u64,
+    /// Percents, the spot price grows by after the buy. Zero for sells.
+    pub price_impact_pct: f64,
+    /// Impact exceeds the threshold, so the client should ask the user to confirm the trade.
+    pub warn_high_impact: bool,
 }
 
 #[derive(Debug, Serialize, Clone, ToSchema)]
@@ -264,6 +505,19 @@ pub struct SellRequest {
     pub min_sol_output: Option<u64>,
 }
 
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SellExactSolRequest {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub user: Pubkey,
+    pub project_id: Uuid,
+    /// Fee excluded, received by the user.
+    pub target_sol: u64,
+    pub max_token_input: u64,
+}
+
 #[derive(Debug, Serialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SellResponse {
@@ -287,6 +541,92 @@ pub struct DevLockClaimResponse {
     pub transaction: Transaction,
 }
 
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferOwnershipRequest {
+    pub project_id: Uuid,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub new_owner: Pubkey,
+}
+
+/// Name, symbol and image are part of the token identity, so they can't be changed.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProjectMetaRequest {
+    pub project_id: Uuid,
+    /// Absent fields keep their current values.
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub twitter: Option<String>,
+    pub telegram: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelProjectRequest {
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelProjectResponse {
+    #[schema(value_type = String)]
+    #[serde(serialize_with = "serialize_tx_bs64")]
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferOwnershipResponse {
+    #[schema(value_type = String)]
+    #[serde(serialize_with = "serialize_tx_bs64")]
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptTransferRequest {
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptTransferResponse {
+    #[schema(value_type = String)]
+    #[serde(serialize_with = "serialize_tx_bs64")]
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimCreatorFeesRequest {
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimCreatorFeesResponse {
+    #[schema(value_type = String)]
+    #[serde(serialize_with = "serialize_tx_bs64")]
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelTransferRequest {
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelTransferResponse {
+    #[schema(value_type = String)]
+    #[serde(serialize_with = "serialize_tx_bs64")]
+    pub transaction: Transaction,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SlippageSettings {
@@ -298,6 +638,9 @@ pub struct SlippageSettings {
 #[serde(rename_all = "camelCase")]
 pub struct GetProjectRequest {
     pub project_id: Uuid,
+    /// Reads [`PublicProject::chain_stats`] from the chain, which takes an extra RPC call.
+    #[serde(default)]
+    pub with_chain_stats: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
@@ -306,74 +649,1049 @@ pub struct GetProjectResponse {
     pub project: Option<PublicProject>,
 }
 
-#[serde_as]
-#[derive(Deserialize, Serialize, Debug, Clone, ToSchema, PartialEq, PartialOrd)]
-#[serde(tag = "type", rename_all = "camelCase")]
-pub enum DevLockPeriod {
-    Disabled,
-    Interval { interval: u64 },
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceStreamRequest {
+    pub project_id: Uuid,
 }
 
-impl DevLockPeriod {
-    pub fn as_secs(&self) -> u64 {
-        match self {
-            DevLockPeriod::Disabled => 0,
-            DevLockPeriod::Interval { interval } => *interval,
+/// Trade on the moonzip curve of the project, sent to websocket clients as JSON text.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceStreamEvent {
+    #[serde(rename = "type")]
+    pub side: TradeSide,
+    /// Lamports, paid by user on buy or received on sell.
+    pub sols: u64,
+    pub tokens: u64,
+    /// Spot price after the trade, in lamports per token.
+    pub price: Option<f64>,
+    /// Unix timestamp of the trade sync, chain events carry no time.
+    pub timestamp: i64,
+}
+
+impl PriceStreamEvent {
+    pub fn from_trade(payload: &TradePayload, timestamp: i64) -> Self {
+        Self {
+            side: if payload.is_buy {
+                TradeSide::Buy
+            } else {
+                TradeSide::Sell
+            },
+            sols: payload.sols,
+            tokens: payload.tokens,
+            price: payload.spot_price(),
+            timestamp,
         }
     }
+}
+
+#[derive(Debug, Deserialize, Validate, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterWebhookRequest {
+    /// Must be a public https url.
+    #[validate(url(message = "Target url is malformed"))]
+    pub target_url: String,
+    /// Events of projects in these stages are delivered, of all projects if empty.
+    #[serde(default)]
+    pub event_filter: Vec<Stage>,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterWebhookResponse {
+    pub id: Uuid,
+    /// Key of the payload signature, it's never shown again.
+    pub secret_hex: String,
+}
+
+/// Body of webhook requests. Signed by the `X-Moonzip-Signature` header:
+/// `sha256=` followed by hex of HMAC-SHA256 of the body, keyed by the webhook secret.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    pub webhook_id: Uuid,
+    pub project_id: Uuid,
+    /// Stage of the project by the time of delivery.
+    pub stage: Stage,
+    pub tx_signature: String,
+    pub slot: u64,
+    pub event: WebhookEvent,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum WebhookEvent {
+    StageChanged {
+        stage: Stage,
+    },
+    Trade {
+        user: String,
+        trade: PriceStreamEvent,
+    },
+    OwnershipTransferred {
+        to: String,
+    },
+    Cancelled,
+}
 
-    pub fn from_secs(secs: u64) -> Self {
-        if secs.is_zero() {
-            return Self::Disabled;
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::StageChanged { .. } => "stage_changed",
+            WebhookEvent::Trade { .. } => "trade",
+            WebhookEvent::OwnershipTransferred { .. } => "ownership_transferred",
+            WebhookEvent::Cancelled => "cancelled",
         }
-        Self::Interval { interval: secs }
     }
 }
 
 #[serde_as]
-#[derive(Debug, Serialize, Validate, Deserialize, Clone, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
 #[serde(rename_all = "camelCase")]
-pub struct ChangeUserInfoRequest {
-    #[serde_as(as = "DisplayFromStr")]
-    #[schema(value_type = String)]
-    pub wallet_address: Pubkey,
+pub struct ListProjectsRequest {
+    /// Ignored if `cursor` is passed.
+    #[serde(default)]
+    pub page: u32,
+    #[serde(default = "default_list_page_size")]
+    pub page_size: u32,
+    pub stage: Option<PublicProjectStage>,
+    pub curve_variant: Option<CurveVariant>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    #[param(value_type = Option<String>)]
+    pub owner: Option<Pubkey>,
+    #[serde(default)]
+    pub sort: SortOrder,
+    /// `nextCursor` of the previous page.
+    pub cursor: Option<String>,
+}
 
-    #[validate(length(min = 1, message = "Username cannot be empty"))]
-    pub username: String,
+fn default_list_page_size() -> u32 {
+    20
+}
 
-    pub display_value: String,
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListProjectsResponse {
+    pub projects: Vec<PublicProject>,
+    /// Absent on the last page.
+    pub next_cursor: Option<String>,
+}
 
-    #[serde_as(as = "Option<DisplayFromStr>")]
-    #[schema(value_type = String)]
-    pub nft_address: Option<Pubkey>,
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardRequest {
+    #[serde(default)]
+    pub sort_by: LeaderboardSortBy,
+    #[serde(default)]
+    pub page: u32,
+    #[serde(default = "default_list_page_size")]
+    pub page_size: u32,
 }
 
-#[serde_as]
-#[derive(Debug, Serialize, Validate, Deserialize, Clone, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct GetUserInformationRequest {
-    #[serde_as(as = "DisplayFromStr")]
-    #[schema(value_type = String)]
-    pub wallet_address: Pubkey,
+pub struct LeaderboardResponse {
+    pub entries: Vec<LeaderboardEntry>,
 }
 
+/// Slim [`PublicProject`] along with its trade totals.
 #[serde_as]
-#[derive(Debug, Serialize, Validate, Deserialize, Clone, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct GetOwnedNFTsRequest {
+pub struct LeaderboardEntry {
+    /// Starts from 1 on the first page.
+    pub rank: u64,
+    pub id: Uuid,
     #[serde_as(as = "DisplayFromStr")]
     #[schema(value_type = String)]
-    pub owner_address: Pubkey,
-    #[validate(range(
-        min = 1,
-        max = 1000,
-        message = "Page must be greater than 0 and less than 1000"
-    ))]
-    pub page: Option<u32>,
-    #[validate(range(
-        min = 1,
-        max = 1000,
-        message = "Limit must be greater than 0 and less than 1000"
-    ))]
-    pub limit: Option<u32>,
+    pub owner: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub stage: PublicProjectStage,
+    pub created_at: String,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub curve_pool_mint: Option<Pubkey>,
+    pub volume_24h_lamports: u64,
+    pub total_volume_lamports: u64,
+    pub holder_count: u64,
+    pub last_price_lamports_per_token: Option<f64>,
+}
+
+impl LeaderboardEntry {
+    pub fn from_stored(rank: u64, entry: StoredLeaderboardEntry) -> anyhow::Result<Self> {
+        let Some(stage) = PublicProjectStage::from_stored(entry.stage) else {
+            bail!("project stage could not be exposed")
+        };
+        let curve_pool_mint = entry
+            .curve_pool_keypair
+            .filter(|_| stage >= PublicProjectStage::CurvePoolActive)
+            .map(|keypair| keypair.to_keypair().pubkey());
+        Ok(Self {
+            rank,
+            id: entry.id,
+            owner: entry.owner.to_pubkey(),
+            name: entry.name,
+            symbol: entry.symbol,
+            stage,
+            created_at: entry.created_at.to_string(),
+            curve_pool_mint,
+            volume_24h_lamports: entry.volume_24h_lamports.try_into()?,
+            total_volume_lamports: entry.total_volume_lamports.try_into()?,
+            holder_count: entry.holder_count.try_into()?,
+            last_price_lamports_per_token: entry.last_price_lamports_per_token,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct GetProjectStatsRequest {
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub period: StatsPeriod,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCurveStateRequest {
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CurvePoolStatus {
+    Active,
+    Closed,
+    Paused,
+}
+
+/// Live state of the moonzip curve pool, spot price is in lamports per token.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CurveState {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub total_token_supply: u64,
+    pub spot_price_numerator: u64,
+    pub spot_price_denominator: u64,
+    pub token_balance: u64,
+    pub sol_balance: u64,
+    pub status: CurvePoolStatus,
+}
+
+impl From<CurveStateView> for CurveState {
+    fn from(view: CurveStateView) -> Self {
+        Self {
+            virtual_token_reserves: view.virtual_token_reserves,
+            virtual_sol_reserves: view.virtual_sol_reserves,
+            real_token_reserves: view.real_token_reserves,
+            real_sol_reserves: view.real_sol_reserves,
+            total_token_supply: view.total_token_supply,
+            spot_price_numerator: view.spot_price_numerator,
+            spot_price_denominator: view.spot_price_denominator,
+            token_balance: view.token_balance,
+            sol_balance: view.sol_balance,
+            status: match view.status {
+                CurvedPoolStatus::Active => CurvePoolStatus::Active,
+                CurvedPoolStatus::Closed => CurvePoolStatus::Closed,
+                CurvedPoolStatus::Paused => CurvePoolStatus::Paused,
+            },
+        }
+    }
+}
+
+/// Trades on curve pools only, prices are in lamports per token and absent without trades.
+/// For projects graduated to raydium, close price is the last synced price of the raydium pool.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectStats {
+    pub volume_lamports: u64,
+    pub volume_tokens: u64,
+    pub trade_count: u64,
+    pub unique_traders: u64,
+    pub price_open: Option<f64>,
+    pub price_high: Option<f64>,
+    pub price_low: Option<f64>,
+    pub price_close: Option<f64>,
+    pub avg_trade_size_lamports: u64,
+}
+
+/// Totals of all tracked trades since the project launch, see [`ProjectStats`] for a period.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LifetimeStats {
+    pub total_volume_lamports: u64,
+    pub buy_count: u64,
+    pub sell_count: u64,
+    /// Approximate, as tokens could be moved outside of tracked pools.
+    pub holder_count: u64,
+    /// Spot price after the last curve trade, in lamports per token.
+    pub last_price_lamports_per_token: Option<f64>,
+    pub updated_at: String,
+}
+
+/// Counters of the on-chain stats account of the project, kept by the program on every trade.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainTradeStats {
+    pub buy_count: u64,
+    pub sell_count: u64,
+    pub total_volume_lamports: u64,
+}
+
+impl From<ChainProjectStats> for ChainTradeStats {
+    fn from(stats: ChainProjectStats) -> Self {
+        Self {
+            buy_count: stats.buy_count,
+            sell_count: stats.sell_count,
+            total_volume_lamports: stats.total_sol_volume,
+        }
+    }
+}
+
+impl TryFrom<StoredProjectStats> for LifetimeStats {
+    type Error = anyhow::Error;
+
+    fn try_from(stored: StoredProjectStats) -> Result<Self, Self::Error> {
+        Ok(LifetimeStats {
+            total_volume_lamports: stored.total_volume_lamports.try_into()?,
+            buy_count: stored.buy_count.try_into()?,
+            sell_count: stored.sell_count.try_into()?,
+            holder_count: stored.holder_count.try_into()?,
+            last_price_lamports_per_token: stored.last_price_lamports_per_token,
+            updated_at: stored.updated_at.to_string(),
+        })
+    }
+}
+
+impl TryFrom<StoredTradeStats> for ProjectStats {
+    type Error = anyhow::Error;
+
+    fn try_from(stored: StoredTradeStats) -> Result<Self, Self::Error> {
+        let volume_lamports: u64 = stored.volume_lamports.try_into()?;
+        let trade_count: u64 = stored.trade_count.try_into()?;
+        Ok(ProjectStats {
+            volume_lamports,
+            volume_tokens: stored.volume_tokens.try_into()?,
+            trade_count,
+            unique_traders: stored.unique_traders.try_into()?,
+            price_open: stored.price_open,
+            price_high: stored.price_high,
+            price_low: stored.price_low,
+            price_close: stored.price_close,
+            avg_trade_size_lamports: volume_lamports.checked_div(trade_count).unwrap_or(0),
+        })
+    }
+}
+
+/// Range bounds are unix timestamps in seconds, `to` is exclusive.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCandlesRequest {
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub interval: CandleInterval,
+    #[serde(default)]
+    pub from: Option<i64>,
+    #[serde(default)]
+    pub to: Option<i64>,
+}
+
+/// In the format of TradingView Lightweight Charts: `time` is a unix timestamp of the bucket start
+/// in seconds, prices are in lamports per token and volume is in lamports.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct Candle {
+    pub time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: u64,
+}
+
+impl TryFrom<StoredCandle> for Candle {
+    type Error = anyhow::Error;
+
+    fn try_from(stored: StoredCandle) -> Result<Self, Self::Error> {
+        Ok(Candle {
+            time: stored.open_ts,
+            open: stored.open,
+            high: stored.high,
+            low: stored.low,
+            close: stored.close,
+            volume: stored.volume_lamports.try_into()?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct GetHoldersRequest {
+    pub project_id: Uuid,
+    /// Page size.
+    #[serde(default = "default_holders_limit", alias = "pageSize")]
+    pub limit: u32,
+    /// Zero-based.
+    #[serde(default)]
+    pub page: u32,
+}
+
+fn default_holders_limit() -> u32 {
+    20
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetHoldersResponse {
+    pub holders: Vec<TokenHolder>,
+    /// Holders with non-zero balance across all pages.
+    pub total: i64,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenHolder {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub wallet: Pubkey,
+    pub balance: u64,
+    /// Slot of the latest trade, which changed the balance, zero if unknown.
+    pub last_updated_slot: u64,
+}
+
+impl TryFrom<StoredTokenHolder> for TokenHolder {
+    type Error = anyhow::Error;
+
+    fn try_from(stored: StoredTokenHolder) -> Result<Self, Self::Error> {
+        Ok(TokenHolder {
+            wallet: stored.wallet.to_pubkey(),
+            balance: stored.balance.try_into()?,
+            last_updated_slot: stored.last_updated_slot.try_into()?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchProjectsRequest {
+    pub query: String,
+    #[serde(default)]
+    pub mode: SearchMode,
+    #[serde(default)]
+    pub sort: SearchSort,
+    #[serde(default = "default_search_limit")]
+    pub limit: u32,
+    #[serde(default)]
+    pub offset: u32,
+}
+
+fn default_search_limit() -> u32 {
+    20
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchProjectsResponse {
+    pub results: Vec<ProjectSearchHit>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSearchHit {
+    pub project: PublicProject,
+    /// HTML fragment of name and description: the text is escaped,
+    /// matched words are wrapped into `<b>` tags.
+    pub highlight: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StaticPoolProgress {
+    pub collected_lamports: u64,
+    /// Pool is closed once this amount is collected.
+    pub max_lamports: u64,
+    pub percentage: f64,
+    pub participant_count: u64,
+}
+
+impl StaticPoolProgress {
+    pub fn new(collected_lamports: u64, max_lamports: u64, participant_count: u64) -> Self {
+        let percentage = if max_lamports == 0 {
+            0.0
+        } else {
+            (collected_lamports as f64 / max_lamports as f64 * 100.0).min(100.0)
+        };
+        Self {
+            collected_lamports,
+            max_lamports,
+            percentage,
+            participant_count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct GetLpBurnProofRequest {
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetLpBurnProofResponse {
+    /// Missing until the project is graduated to raydium.
+    pub proof: Option<LpBurnProof>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LpBurnProof {
+    pub project_id: Uuid,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub lp_mint: Pubkey,
+    pub burned_amount: u64,
+    pub tx_signature: String,
+    pub slot: i64,
+    pub burned_at: DateTime<TZ>,
+}
+
+impl TryFrom<StoredLpBurn> for LpBurnProof {
+    type Error = anyhow::Error;
+
+    fn try_from(stored: StoredLpBurn) -> Result<Self, Self::Error> {
+        Ok(Self {
+            project_id: stored.project_id,
+            lp_mint: stored.lp_mint.to_pubkey(),
+            burned_amount: stored.burned_amount.try_into()?,
+            tx_signature: stored.tx_signature,
+            slot: stored.slot,
+            burned_at: stored.burned_at,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLaunchPeriodsRequest {
+    /// Allowed static pool launch periods, in seconds.
+    pub periods: Vec<u64>,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAllowedOwnersRequest {
+    /// Verified creators, not limited in projects creation.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[schema(value_type = Vec<String>)]
+    pub owners: Vec<Pubkey>,
+}
+
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDeliveryMethodsRequest {
+    pub methods: Vec<DeliveryMethod>,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploymentRules {
+    /// Allowed static pool launch periods, in seconds.
+    pub allowed_launch_periods: Vec<u64>,
+    pub allowed_delivery_methods: Vec<DeliveryMethod>,
+    pub project_id_strategy: ProjectIdStrategy,
+    /// Included into the creator deposit.
+    pub project_creation_fee_lamports: u64,
+    /// Closed curve pool is graduated to raydium only with at least this liquidity.
+    pub min_graduation_liquidity_lamports: u64,
+}
+
+/// How dev purchase tokens are handed to the creator once curve pool is deployed.
+/// Vested tokens are kept in the locker escrow, counting starts from the curve pool deployment.
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DeliveryMethod {
+    Immediate,
+    /// `cliff_pct` percents are unlocked at the cliff, the rest in equal parts
+    /// every `frequency_secs` until `period_secs` pass.
+    #[serde(rename_all = "camelCase")]
+    VestedLinear {
+        cliff_secs: u64,
+        #[serde(default)]
+        cliff_pct: u8,
+        period_secs: u64,
+        frequency_secs: u64,
+    },
+    /// Everything is unlocked at once after the cliff.
+    #[serde(rename_all = "camelCase")]
+    VestedCliff {
+        cliff_secs: u64,
+    },
+}
+
+impl DeliveryMethod {
+    pub fn is_vested(&self) -> bool {
+        !matches!(self, DeliveryMethod::Immediate)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        match self {
+            DeliveryMethod::Immediate => {}
+            DeliveryMethod::VestedLinear {
+                cliff_pct,
+                period_secs,
+                frequency_secs,
+                ..
+            } => {
+                if *frequency_secs == 0 || *period_secs == 0 {
+                    bail!("vesting period and frequency must be positive");
+                }
+                if *cliff_pct > 100 {
+                    bail!("cliff unlock must not exceed 100%, got {cliff_pct}%");
+                }
+                if period_secs % frequency_secs != 0 {
+                    bail!("vesting period must be a multiple of frequency");
+                }
+            }
+            DeliveryMethod::VestedCliff { cliff_secs } => {
+                if *cliff_secs == 0 {
+                    bail!("cliff must be positive, use immediate delivery instead");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Validate, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeUserInfoRequest {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub wallet_address: Pubkey,
+
+    #[validate(length(min = 1, message = "Username cannot be empty"))]
+    pub username: String,
+
+    pub display_value: String,
+
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = String)]
+    pub nft_address: Option<Pubkey>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Validate, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUserInformationRequest {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub wallet_address: Pubkey,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+pub struct ResolveSnsRequest {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    #[param(value_type = String)]
+    pub address: Pubkey,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ResolveSnsResponse {
+    pub sns_name: Option<String>,
+}
+
+/// Name is accepted with or without `.sol` suffix.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+pub struct ResolveAddressRequest {
+    pub sns_name: String,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ResolveAddressResponse {
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[schema(value_type = Option<String>)]
+    pub address: Option<Pubkey>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Validate, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetOwnedNFTsRequest {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub owner_address: Pubkey,
+    #[validate(range(
+        min = 1,
+        max = 1000,
+        message = "Page must be greater than 0 and less than 1000"
+    ))]
+    pub page: Option<u32>,
+    #[validate(range(
+        min = 1,
+        max = 1000,
+        message = "Limit must be greater than 0 and less than 1000"
+    ))]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Validate, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNotificationsRequest {
+    #[validate(email(message = "Email is malformed"))]
+    pub email: Option<String>,
+    pub notify_on_buy: bool,
+    pub notify_on_sell: bool,
+    pub notify_on_graduation: bool,
+    pub notify_on_dev_unlock: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferences {
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub notify_on_buy: bool,
+    pub notify_on_sell: bool,
+    pub notify_on_graduation: bool,
+    pub notify_on_dev_unlock: bool,
+}
+
+impl From<StoredNotificationPreferences> for NotificationPreferences {
+    fn from(stored: StoredNotificationPreferences) -> Self {
+        Self {
+            email: stored.email,
+            email_verified: stored.email_verified,
+            notify_on_buy: stored.notify_on_buy,
+            notify_on_sell: stored.notify_on_sell,
+            notify_on_graduation: stored.notify_on_graduation,
+            notify_on_dev_unlock: stored.notify_on_dev_unlock,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmEmailRequest {
+    pub token: Uuid,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationLock {
+    pub project_id: Uuid,
+    /// Token of the migration, holding the lease.
+    pub owner: Uuid,
+    pub locked_since: DateTime<TZ>,
+    pub expires_at: DateTime<TZ>,
+    pub age_secs: i64,
+}
+
+impl From<StoredMigrationLock> for MigrationLock {
+    fn from(stored: StoredMigrationLock) -> Self {
+        Self {
+            project_id: stored.project_id,
+            owner: stored.owner,
+            age_secs: (TZ::now() - stored.locked_since).num_seconds(),
+            locked_since: stored.locked_since,
+            expires_at: stored.expires_at,
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradeProposal {
+    /// Hex SHA-256 of the proposed program binary.
+    pub upgrade_hash: String,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub proposer: Pubkey,
+    pub proposed_at: DateTime<TZ>,
+    /// Upgrade could not be executed earlier.
+    pub effective_at: DateTime<TZ>,
+    pub executed_at: Option<DateTime<TZ>>,
+    pub tx_signature: String,
+}
+
+impl From<StoredUpgradeProposal> for UpgradeProposal {
+    fn from(stored: StoredUpgradeProposal) -> Self {
+        Self {
+            upgrade_hash: to_hex(&stored.upgrade_hash),
+            proposer: stored.proposer.to_pubkey(),
+            proposed_at: stored.proposed_at,
+            effective_at: stored.effective_at,
+            executed_at: stored.executed_at,
+            tx_signature: stored.tx_signature,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IpfsStats {
+    pub total_uploads: i64,
+    /// Uploads, skipped as the same content was already uploaded.
+    pub deduplicated_uploads: i64,
+    pub bytes_saved: i64,
+}
+
+impl From<StoredIpfsStats> for IpfsStats {
+    fn from(stored: StoredIpfsStats) -> Self {
+        Self {
+            total_uploads: stored.total_uploads,
+            deduplicated_uploads: stored.deduplicated_uploads,
+            bytes_saved: stored.bytes_saved,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRevenueRequest {
+    /// Including the current day.
+    #[serde(default = "default_revenue_days")]
+    pub days: u32,
+}
+
+fn default_revenue_days() -> u32 {
+    30
+}
+
+/// Lamports, collected by fee instructions within a UTC day.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyRevenue {
+    pub day: DateTime<TZ>,
+    pub extracted: u64,
+    pub account_taken: u64,
+    pub total: u64,
+}
+
+impl TryFrom<StoredDailyRevenue> for DailyRevenue {
+    type Error = anyhow::Error;
+
+    fn try_from(stored: StoredDailyRevenue) -> Result<Self, Self::Error> {
+        let extracted: u64 = stored.extracted.try_into()?;
+        let account_taken: u64 = stored.account_taken.try_into()?;
+        Ok(Self {
+            day: stored.day,
+            extracted,
+            account_taken,
+            total: extracted.saturating_add(account_taken),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTxFailuresRequest {
+    pub project_id: Option<Uuid>,
+    #[serde(default = "default_tx_failures_limit")]
+    pub limit: u32,
+}
+
+fn default_tx_failures_limit() -> u32 {
+    50
+}
+
+/// Mirrors on-chain [`TradeFee`], basis points are hundredths of a percent.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TradeFeeSchema {
+    Percentage { bps: u16 },
+    Flat { lamports: u64 },
+    MaxOf { bps: u16, lamports: u64 },
+}
+
+impl From<TradeFeeSchema> for TradeFee {
+    fn from(schema: TradeFeeSchema) -> Self {
+        match schema {
+            TradeFeeSchema::Percentage { bps } => TradeFee::Percentage(BasisPoints(bps)),
+            TradeFeeSchema::Flat { lamports } => TradeFee::Flat(lamports),
+            TradeFeeSchema::MaxOf { bps, lamports } => TradeFee::MaxOf {
+                percentage: BasisPoints(bps),
+                flat: lamports,
+            },
+        }
+    }
+}
+
+/// Percentage of trade fees, once 30 days volume of all pools reaches `volume_lamports`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeTierSchema {
+    pub volume_lamports: u64,
+    pub bps: u16,
+}
+
+/// Wallet, receiving `share_bps` of every fee extraction.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeRecipientSchema {
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub address: Pubkey,
+    pub share_bps: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendStaticPoolRequest {
+    pub project_id: Uuid,
+    /// Unix timestamp, in seconds.
+    pub new_finish_ts: u64,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendStaticPoolResponse {
+    pub signature: String,
+}
+
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForceUnlockRequest {
+    pub project_id: Uuid,
+    /// Latch must be locked for longer than this, so the one of in-flight transaction is kept.
+    pub max_age_secs: u64,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForceUnlockResponse {
+    pub signature: String,
+}
+
+/// Whole config of the pool is replaced, unset limits are lifted.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePoolConfigRequest {
+    pub project_id: Uuid,
+    pub min_tradeable_sol: Option<u64>,
+    pub min_sol_to_close: Option<u64>,
+    pub max_wallet_tokens: Option<u64>,
+    pub anti_snipe_slots: Option<u64>,
+    /// Can't be switched on or off for the live pool, only changed.
+    pub creator_fee_share_bps: Option<u16>,
+}
+
+impl UpdatePoolConfigRequest {
+    pub fn config(&self) -> CurvedPoolConfig {
+        CurvedPoolConfig {
+            min_tradeable_sol: self.min_tradeable_sol,
+            min_sol_to_close: self.min_sol_to_close,
+            max_wallet_tokens: self.max_wallet_tokens,
+            anti_snipe_slots: self.anti_snipe_slots,
+            creator_fee_share_bps: self.creator_fee_share_bps,
+            // kept by the program as is
+            token_program: Pubkey::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePoolConfigResponse {
+    pub signature: String,
+}
+
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverProjectRequest {
+    pub project_id: Uuid,
+    /// Must match the stage, derived from the on-chain project, so operator confirms the outcome.
+    pub target_stage: Stage,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverProjectResponse {
+    pub previous_stage: Stage,
+    pub stage: Stage,
+    /// Whether the lease of a stuck migration had to be revoked to release the project.
+    pub lock_released: bool,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFeeConfigRequest {
+    pub on_buy: TradeFeeSchema,
+    pub on_sell: TradeFeeSchema,
+    /// Ascending by volume, empty keeps the fee static.
+    #[serde(default)]
+    pub volume_tiers: Vec<FeeTierSchema>,
+    /// Shares must sum up to 10000 basis points, empty sends extracted fee to a single receiver.
+    #[serde(default)]
+    pub fee_recipients: Vec<FeeRecipientSchema>,
+    #[serde_as(as = "DisplayFromStr")]
+    #[schema(value_type = String)]
+    pub fee_receiver: Pubkey,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFeeConfigResponse {
+    pub signature: String,
+}
+
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetProjectCreationFeeRequest {
+    /// Zero disables the fee.
+    pub fee_lamports: u64,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetProjectCreationFeeResponse {
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetJitoTipStrategyRequest {
+    /// One of `"random"`, `"roundRobin"` or `{"preferred": ["<tip account>", ...]}`.
+    #[schema(value_type = Object)]
+    pub strategy: JitoTipStrategy,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TxFailure {
+    pub project_id: Option<Uuid>,
+    pub tx_signature: String,
+    pub error: String,
+    pub logs: Vec<String>,
+    pub slot: Option<i64>,
+    pub failed_at: DateTime<TZ>,
+}
+
+impl From<StoredTxFailure> for TxFailure {
+    fn from(stored: StoredTxFailure) -> Self {
+        Self {
+            project_id: stored.project_id,
+            tx_signature: stored.tx_signature,
+            error: stored.error,
+            logs: stored.logs,
+            slot: stored.slot,
+            failed_at: stored.failed_at,
+        }
+    }
 }
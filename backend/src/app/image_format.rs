@@ -0,0 +1,359 @@
+use bytes::Bytes;
+use image::{imageops::FilterType, DynamicImage};
+use serde::{Deserialize, Serialize};
+use std::{io::Cursor, str::FromStr};
+
+/// Enough to cover signatures of all supported formats.
+pub const FORMAT_PROBE_LEN: usize = 32;
+
+/// Source images above it aren't decoded, as decoded ones are way larger.
+pub const MAX_CONVERTED_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+pub const WEBP_QUALITY: f32 = 85.0;
+/// Checked against the header before decoding, as a tiny file may declare gigapixels.
+/// Below the WebP limit of 16383, so decoded images are always encodable.
+const MAX_DECODED_DIMENSION: u32 = 4096;
+/// Budget of the decoder, covers RGBA buffer of the largest allowed image.
+const MAX_DECODER_ALLOC: u64 = 4 * (MAX_DECODED_DIMENSION as u64).pow(2) + 16 * 1024 * 1024;
+
+/// Limits of uploaded project images, larger ones are compressed before they're stored.
+pub const MAX_PROJECT_IMAGE_DIMENSION: u32 = 512;
+pub const MAX_PROJECT_IMAGE_BYTES: usize = 256 * 1024;
+/// Tried in order, until the encoded image fits the size limit.
+const COMPRESSION_QUALITIES: [f32; 4] = [WEBP_QUALITY, 70.0, 55.0, 40.0];
+
+/// Formats of project images, stored in `token_image.image_format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Png,
+    #[serde(alias = "jpg")]
+    Jpeg,
+    Gif,
+    WebP,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unsupported image format")]
+pub struct UnsupportedImageFormat;
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidImage {
+    #[error("image of {0} bytes exceeds the limit of {MAX_CONVERTED_IMAGE_BYTES} bytes")]
+    TooLarge(usize),
+    #[error("image format is not recognized by the content signature")]
+    Unrecognized,
+    #[error("failed to decode {format} image: {source}")]
+    Undecodable {
+        format: &'static str,
+        source: image::ImageError,
+    },
+    #[error(
+        "image of {width}x{height} exceeds the limit of {MAX_DECODED_DIMENSION}x{MAX_DECODED_DIMENSION}"
+    )]
+    TooLargeDimensions { width: u32, height: u32 },
+    #[error("failed to encode image to WebP: {0}")]
+    Unencodable(String),
+    #[error("image can't be compressed below {0} bytes, consider a simpler image")]
+    Incompressible(usize),
+}
+
+/// Images are uploaded as WebP regardless of the source format, only the first frame of animations is kept.
+/// CPU bound, so should be called off the async runtime.
+pub fn convert_to_webp(content: &[u8]) -> Result<Vec<u8>, InvalidImage> {
+    let decoded = decode(content)?;
+    let (width, height) = (decoded.width(), decoded.height());
+    let rgba = decoded.into_rgba8();
+    let encoded = webp::Encoder::from_rgba(&rgba, width, height)
+        .encode_simple(false, WEBP_QUALITY)
+        .map_err(|err| InvalidImage::Unencodable(format!("{err:?}")))?;
+    Ok(encoded.to_vec())
+}
+
+/// Image, fitting both limits, is kept as is, so animations survive.
+/// Otherwise it's downscaled to fit `max_dimension` and encoded to WebP.
+pub async fn compress_image(
+    content: Bytes,
+    max_dimension: u32,
+    max_bytes: usize,
+) -> anyhow::Result<Bytes> {
+    let compressed =
+        tokio::task::spawn_blocking(move || compress(content, max_dimension, max_bytes)).await??;
+    Ok(compressed)
+}
+
+fn compress(content: Bytes, max_dimension: u32, max_bytes: usize) -> Result<Bytes, InvalidImage> {
+    let decoded = decode(&content)?;
+    let (width, height) = (decoded.width(), decoded.height());
+    if content.len() <= max_bytes && width <= max_dimension && height <= max_dimension {
+        return Ok(content);
+    }
+    let resized = if width > max_dimension || height > max_dimension {
+        // aspect ratio is preserved, so only the larger side matches the limit
+        decoded.resize(max_dimension, max_dimension, FilterType::Lanczos3)
+    } else {
+        decoded
+    };
+    let rgba = resized.into_rgba8();
+    for quality in COMPRESSION_QUALITIES {
+        let encoded = webp::Encoder::from_rgba(&rgba, rgba.width(), rgba.height())
+            .encode_simple(false, quality)
+            .map_err(|err| InvalidImage::Unencodable(format!("{err:?}")))?;
+        if encoded.len() <= max_bytes {
+            return Ok(Bytes::copy_from_slice(&encoded));
+        }
+    }
+    Err(InvalidImage::Incompressible(max_bytes))
+}
+
+fn decode(content: &[u8]) -> Result<DynamicImage, InvalidImage> {
+    if content.len() > MAX_CONVERTED_IMAGE_BYTES {
+        return Err(InvalidImage::TooLarge(content.len()));
+    }
+    let format = ImageFormat::detect(content).ok_or(InvalidImage::Unrecognized)?;
+    let undecodable = |source| InvalidImage::Undecodable {
+        format: format.as_str(),
+        source,
+    };
+    let reader = || {
+        let mut reader = image::ImageReader::with_format(Cursor::new(content), format.into());
+        reader.limits(decoder_limits());
+        reader
+    };
+
+    // only the header is read, so nothing is allocated for pixels yet
+    let (width, height) = reader().into_dimensions().map_err(undecodable)?;
+    if width > MAX_DECODED_DIMENSION || height > MAX_DECODED_DIMENSION {
+        return Err(InvalidImage::TooLargeDimensions { width, height });
+    }
+    reader().decode().map_err(undecodable)
+}
+
+fn decoder_limits() -> image::Limits {
+    let mut limits = image::Limits::default();
+    limits.max_image_width = Some(MAX_DECODED_DIMENSION);
+    limits.max_image_height = Some(MAX_DECODED_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODER_ALLOC);
+    limits
+}
+
+impl ImageFormat {
+    pub const ALL: [ImageFormat; 4] = [
+        ImageFormat::Png,
+        ImageFormat::Jpeg,
+        ImageFormat::Gif,
+        ImageFormat::WebP,
+    ];
+
+    /// Format is told by the content signature, as clients could send anything under any name.
+    pub fn detect(head: &[u8]) -> Option<Self> {
+        match image::guess_format(head).ok()? {
+            image::ImageFormat::Png => Some(ImageFormat::Png),
+            image::ImageFormat::Jpeg => Some(ImageFormat::Jpeg),
+            image::ImageFormat::Gif => Some(ImageFormat::Gif),
+            image::ImageFormat::WebP => Some(ImageFormat::WebP),
+            _ => None,
+        }
+    }
+
+    pub fn detect_allowed(head: &[u8], allowed: &[Self]) -> Result<Self, UnsupportedImageFormat> {
+        Self::detect(head)
+            .filter(|format| allowed.contains(format))
+            .ok_or(UnsupportedImageFormat)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpeg",
+            ImageFormat::Gif => "gif",
+            ImageFormat::WebP => "webp",
+        }
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::WebP => "image/webp",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            other => other.as_str(),
+        }
+    }
+}
+
+impl From<ImageFormat> for image::ImageFormat {
+    fn from(format: ImageFormat) -> Self {
+        match format {
+            ImageFormat::Png => image::ImageFormat::Png,
+            ImageFormat::Jpeg => image::ImageFormat::Jpeg,
+            ImageFormat::Gif => image::ImageFormat::Gif,
+            ImageFormat::WebP => image::ImageFormat::WebP,
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("unknown image format: {s}"))
+    }
+}
+
+impl TryFrom<String> for ImageFormat {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    const JPEG: &[u8] = b"\xff\xd8\xff\xe0\0\x10JFIF\0";
+    const GIF: &[u8] = b"GIF89a\x01\0\x01\0";
+    const WEBP: &[u8] = b"RIFF\x24\0\0\0WEBPVP8 ";
+
+    #[test]
+    fn test_detects_supported_formats() {
+        assert_eq!(ImageFormat::detect(PNG), Some(ImageFormat::Png));
+        assert_eq!(ImageFormat::detect(JPEG), Some(ImageFormat::Jpeg));
+        assert_eq!(ImageFormat::detect(GIF), Some(ImageFormat::Gif));
+        assert_eq!(ImageFormat::detect(WEBP), Some(ImageFormat::WebP));
+    }
+
+    #[test]
+    fn test_rejects_text_disguised_as_png() {
+        // clients name files by themselves, so `token.png` may contain anything
+        let disguised = b"definitely an image, trust me".as_slice();
+        assert_eq!(ImageFormat::detect(disguised), None);
+        assert!(ImageFormat::detect_allowed(disguised, &ImageFormat::ALL).is_err());
+    }
+
+    #[test]
+    fn test_rejects_unsupported_and_disallowed_formats() {
+        let bmp = b"BM\x1e\0\0\0\0\0\0\0\x1a\0\0\0".as_slice();
+        assert_eq!(ImageFormat::detect(bmp), None);
+
+        let allowed = [ImageFormat::Png, ImageFormat::Jpeg];
+        assert_eq!(
+            ImageFormat::detect_allowed(JPEG, &allowed).unwrap(),
+            ImageFormat::Jpeg
+        );
+        let err = ImageFormat::detect_allowed(GIF, &allowed).unwrap_err();
+        assert_eq!(err.to_string(), "Unsupported image format");
+    }
+
+    #[test]
+    fn test_round_trips_stored_names() {
+        for format in ImageFormat::ALL {
+            assert_eq!(format.as_str().parse::<ImageFormat>().unwrap(), format);
+        }
+        assert!("bmp".parse::<ImageFormat>().is_err());
+
+        let configured: Vec<ImageFormat> =
+            serde_json::from_str(r#"["png", "jpg", "gif", "webp"]"#).unwrap();
+        assert_eq!(configured, ImageFormat::ALL);
+    }
+
+    fn encode(width: u32, height: u32, format: image::ImageFormat) -> Vec<u8> {
+        let image = image::RgbaImage::from_pixel(width, height, image::Rgba([200, 30, 90, 255]));
+        let mut content = Cursor::new(Vec::new());
+        image.write_to(&mut content, format).unwrap();
+        content.into_inner()
+    }
+
+    #[test]
+    fn test_converts_supported_formats_to_webp() {
+        for format in [
+            image::ImageFormat::Png,
+            image::ImageFormat::Jpeg,
+            image::ImageFormat::Gif,
+        ] {
+            let converted = convert_to_webp(&encode(4, 3, format)).unwrap();
+            assert_eq!(ImageFormat::detect(&converted), Some(ImageFormat::WebP));
+            let decoded = image::load_from_memory(&converted).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (4, 3));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compresses_images_above_limits() {
+        let small = Bytes::from(encode(4, 3, image::ImageFormat::Png));
+        let kept = compress_image(small.clone(), 512, 256 * 1024)
+            .await
+            .unwrap();
+        assert_eq!(kept, small);
+
+        let large = Bytes::from(encode(1024, 256, image::ImageFormat::Png));
+        let compressed = compress_image(large, 512, 256 * 1024).await.unwrap();
+        assert!(compressed.len() <= 256 * 1024);
+        assert_eq!(ImageFormat::detect(&compressed), Some(ImageFormat::WebP));
+        let decoded = image::load_from_memory(&compressed).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (512, 128));
+
+        let err = compress_image(Bytes::from_static(PNG), 512, 256 * 1024)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<InvalidImage>(),
+            Some(InvalidImage::Undecodable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_images_it_cannot_convert() {
+        assert!(matches!(
+            convert_to_webp(b"definitely an image, trust me"),
+            Err(InvalidImage::Unrecognized)
+        ));
+        // signature is right, but the content is cut
+        assert!(matches!(
+            convert_to_webp(PNG),
+            Err(InvalidImage::Undecodable { format: "png", .. })
+        ));
+        let oversized = vec![0; MAX_CONVERTED_IMAGE_BYTES + 1];
+        assert!(matches!(
+            convert_to_webp(&oversized),
+            Err(InvalidImage::TooLarge(_))
+        ));
+    }
+
+    /// Tiny GIF, which logical screen claims 65535x65535 pixels.
+    fn gif_bomb() -> Bytes {
+        let mut content = encode(1, 1, image::ImageFormat::Gif);
+        content[6..10].copy_from_slice(&[0xff; 4]);
+        Bytes::from(content)
+    }
+
+    #[tokio::test]
+    async fn test_refuses_to_decompress_bombs() {
+        let err = compress_image(gif_bomb(), 512, 256 * 1024)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<InvalidImage>(),
+            Some(InvalidImage::TooLargeDimensions { .. } | InvalidImage::Undecodable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_refuses_to_convert_bombs() {
+        assert!(matches!(
+            convert_to_webp(&gif_bomb()),
+            Err(InvalidImage::TooLargeDimensions { .. } | InvalidImage::Undecodable { .. })
+        ));
+    }
+}
@@ -0,0 +1,322 @@
+use super::{
+    BuyParams, InstructionsConfig, ProjectsOperations, SellParams, SlippageExceeded,
+    WRAPPED_SOL_MINT,
+};
+use anyhow::bail;
+use base64::Engine as _;
+use serde::Deserialize;
+use serde_with::{serde_as, DisplayFromStr};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use std::time::Duration;
+use tracing::warn;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Jupiter V6 swap API, routing trades of graduated projects over every DEX, Jupiter knows.
+pub struct JupiterClient {
+    client: reqwest::Client,
+    /// Quotes are requested at `{base_url}/quote`.
+    base_url: String,
+    /// Takes the quote and returns instructions of the swap, rather than a whole transaction,
+    /// so they could be merged with ours.
+    swap_url: String,
+}
+
+impl JupiterClient {
+    pub fn new(config: &InstructionsConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: config.jupiter_base_url.clone(),
+            swap_url: config.jupiter_swap_url.clone(),
+        }
+    }
+
+    /// Routes are limited to the ones, fitting a legacy transaction, as our transactions are.
+    async fn quote(
+        &self,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> anyhow::Result<Quote> {
+        let raw: serde_json::Value = self
+            .client
+            .get(format!("{}/quote", self.base_url))
+            .query(&[
+                ("inputMint", input_mint.to_string()),
+                ("outputMint", output_mint.to_string()),
+                ("amount", amount.to_string()),
+                ("slippageBps", slippage_bps.to_string()),
+                ("asLegacyTransaction", true.to_string()),
+            ])
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let amounts = serde_json::from_value(raw.clone())?;
+        Ok(Quote { raw, amounts })
+    }
+
+    async fn swap_instructions(
+        &self,
+        user: Pubkey,
+        quote: Quote,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let response: SwapInstructions = self
+            .client
+            .post(&self.swap_url)
+            .json(&serde_json::json!({
+                "userPublicKey": user.to_string(),
+                "quoteResponse": quote.raw,
+                "wrapAndUnwrapSol": true,
+                "asLegacyTransaction": true,
+            }))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if !response.address_lookup_table_addresses.is_empty() {
+            bail!("jupiter route needs lookup tables, which legacy transaction can't use");
+        }
+        let ixs = response
+            .compute_budget_instructions
+            .into_iter()
+            .chain(response.setup_instructions)
+            .chain(Some(response.swap_instruction))
+            .chain(response.cleanup_instruction)
+            .map(Instruction::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        ensure_only_user_signs(user, &ixs)?;
+        Ok(ixs)
+    }
+}
+
+/// Swap is merged into transactions, which may be co-signed by the program authority,
+/// so a route asking for any other signature than the user's one is never trusted.
+fn ensure_only_user_signs(user: Pubkey, ixs: &[Instruction]) -> anyhow::Result<()> {
+    let foreign_signer = ixs
+        .iter()
+        .flat_map(|ix| &ix.accounts)
+        .find(|account| account.is_signer && account.pubkey != user);
+    if let Some(account) = foreign_signer {
+        bail!(
+            "jupiter route requires signature of {}, only {user} is allowed to sign",
+            account.pubkey
+        );
+    }
+    Ok(())
+}
+
+/// Quote is passed back to Jupiter as is, we only check its amounts.
+struct Quote {
+    raw: serde_json::Value,
+    amounts: QuoteAmounts,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuoteAmounts {
+    #[serde_as(as = "DisplayFromStr")]
+    out_amount: u64,
+    /// Least output, the swap succeeds with, given the slippage.
+    #[serde_as(as = "DisplayFromStr")]
+    other_amount_threshold: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SwapInstructions {
+    #[serde(default)]
+    compute_budget_instructions: Vec<JupiterInstruction>,
+    #[serde(default)]
+    setup_instructions: Vec<JupiterInstruction>,
+    swap_instruction: JupiterInstruction,
+    cleanup_instruction: Option<JupiterInstruction>,
+    #[serde(default)]
+    address_lookup_table_addresses: Vec<String>,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JupiterInstruction {
+    #[serde_as(as = "DisplayFromStr")]
+    program_id: Pubkey,
+    accounts: Vec<JupiterAccount>,
+    /// Base64 encoded.
+    data: String,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JupiterAccount {
+    #[serde_as(as = "DisplayFromStr")]
+    pubkey: Pubkey,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+impl TryFrom<JupiterInstruction> for Instruction {
+    type Error = anyhow::Error;
+
+    fn try_from(instruction: JupiterInstruction) -> anyhow::Result<Self> {
+        Ok(Instruction {
+            program_id: instruction.program_id,
+            accounts: instruction
+                .accounts
+                .into_iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: base64::engine::general_purpose::STANDARD.decode(instruction.data)?,
+        })
+    }
+}
+
+impl<'a> ProjectsOperations<'a> {
+    /// `None` if Jupiter is disabled or failed, so the caller falls back to direct swaps.
+    /// Slippage is not a failure of Jupiter, so it's returned as is.
+    pub(super) async fn buy_via_jupiter(
+        &self,
+        params: &BuyParams,
+    ) -> anyhow::Result<Option<Vec<Instruction>>> {
+        if !self.config.use_jupiter {
+            return Ok(None);
+        }
+        let curve_mint = self.curve_mint()?;
+        let quote = match self
+            .jupiter
+            .quote(
+                WRAPPED_SOL_MINT,
+                curve_mint,
+                params.sols,
+                self.config.jupiter_slippage_bps,
+            )
+            .await
+        {
+            Ok(quote) => quote,
+            Err(err) => {
+                warn!("jupiter failed to quote buy of {curve_mint}, falling back: {err:#}");
+                return Ok(None);
+            }
+        };
+        if quote.amounts.other_amount_threshold < params.min_token_output {
+            return Err(SlippageExceeded {
+                expected: quote.amounts.other_amount_threshold,
+                min_token_output: params.min_token_output,
+            }
+            .into());
+        }
+        Ok(self.jupiter_swap(params.user, quote).await)
+    }
+
+    /// See [`Self::buy_via_jupiter`].
+    pub(super) async fn sell_via_jupiter(
+        &self,
+        params: &SellParams,
+    ) -> anyhow::Result<Option<Vec<Instruction>>> {
+        if !self.config.use_jupiter {
+            return Ok(None);
+        }
+        let curve_mint = self.curve_mint()?;
+        let quote = match self
+            .jupiter
+            .quote(
+                curve_mint,
+                WRAPPED_SOL_MINT,
+                params.tokens,
+                self.config.jupiter_slippage_bps,
+            )
+            .await
+        {
+            Ok(quote) => quote,
+            Err(err) => {
+                warn!("jupiter failed to quote sell of {curve_mint}, falling back: {err:#}");
+                return Ok(None);
+            }
+        };
+        if quote.amounts.other_amount_threshold < params.min_sol_output {
+            bail!(
+                "Slippage exceeded: would receive {} lamports, requested at least {}",
+                quote.amounts.other_amount_threshold,
+                params.min_sol_output
+            );
+        }
+        Ok(self.jupiter_swap(params.user, quote).await)
+    }
+
+    async fn jupiter_swap(&self, user: Pubkey, quote: Quote) -> Option<Vec<Instruction>> {
+        let out_amount = quote.amounts.out_amount;
+        match self.jupiter.swap_instructions(user, quote).await {
+            Ok(ixs) => {
+                tracing::debug!("jupiter route is expected to give {out_amount}");
+                Some(ixs)
+            }
+            Err(err) => {
+                warn!("jupiter failed to build swap, falling back: {err:#}");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_converts_swap_instructions() {
+        let response: SwapInstructions = serde_json::from_value(serde_json::json!({
+            "computeBudgetInstructions": [],
+            "setupInstructions": [],
+            "swapInstruction": {
+                "programId": "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4",
+                "accounts": [{
+                    "pubkey": "So11111111111111111111111111111111111111112",
+                    "isSigner": false,
+                    "isWritable": true,
+                }],
+                "data": "AQID",
+            },
+            "cleanupInstruction": null,
+            "addressLookupTableAddresses": [],
+        }))
+        .unwrap();
+        let instruction = Instruction::try_from(response.swap_instruction).unwrap();
+        assert_eq!(instruction.data, vec![1, 2, 3]);
+        assert_eq!(
+            instruction.accounts,
+            vec![AccountMeta::new(WRAPPED_SOL_MINT, false)]
+        );
+        assert!(response.cleanup_instruction.is_none());
+    }
+
+    #[test]
+    fn test_rejects_routes_signed_by_anyone_but_user() {
+        let user = Pubkey::new_unique();
+        let swap = |signer: Pubkey| Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![
+                AccountMeta::new(signer, true),
+                AccountMeta::new(WRAPPED_SOL_MINT, false),
+            ],
+            data: vec![],
+        };
+        ensure_only_user_signs(user, &[swap(user)]).unwrap();
+        assert!(
+            ensure_only_user_signs(user, &[swap(user), swap(moonzip::PROGRAM_AUTHORITY)]).is_err()
+        );
+    }
+}
@@ -1,6 +1,122 @@
+use crate::app::exposed::DeliveryMethod;
+use anyhow::bail;
+use locker::CreateVestingEscrowParameters;
 use solana_sdk::pubkey::Pubkey;
 
 const ESCROW_PREFIX: &[u8] = b"escrow";
 pub fn escrow_address(base: &Pubkey, program_id: &Pubkey) -> Pubkey {
     Pubkey::find_program_address(&[ESCROW_PREFIX, base.as_ref()], program_id).0
 }
+
+/// Escrow schedule of the dev tokens, counted from `now`.
+/// For linear vesting, rounding remainder is unlocked right at the cliff, along with `cliff_pct`.
+pub fn vesting_params(
+    delivery: &DeliveryMethod,
+    tokens: u64,
+    now: u64,
+) -> anyhow::Result<CreateVestingEscrowParameters> {
+    let (cliff_secs, cliff_pct, frequency, number_of_period) = match delivery {
+        DeliveryMethod::Immediate => bail!("immediate delivery must be done without locking"),
+        DeliveryMethod::VestedLinear {
+            cliff_secs,
+            cliff_pct,
+            period_secs,
+            frequency_secs,
+        } => {
+            delivery.validate()?;
+            (
+                *cliff_secs,
+                *cliff_pct,
+                *frequency_secs,
+                period_secs / frequency_secs,
+            )
+        }
+        DeliveryMethod::VestedCliff { cliff_secs } => (*cliff_secs, 0, 1, 1),
+    };
+    // bounded by `tokens`, as percent is validated
+    let cliff_amount = (u128::from(tokens) * u128::from(cliff_pct) / 100) as u64;
+    let amount_per_period = (tokens - cliff_amount) / number_of_period;
+    let cliff_time = now + cliff_secs;
+    Ok(CreateVestingEscrowParameters {
+        vesting_start_time: cliff_time,
+        cliff_time,
+        frequency,
+        cliff_unlock_amount: tokens - amount_per_period * number_of_period,
+        amount_per_period,
+        number_of_period,
+        update_recipient_mode: 0,
+        cancel_mode: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlocks_cliff_at_once() {
+        let params =
+            vesting_params(&DeliveryMethod::VestedCliff { cliff_secs: 100 }, 1000, 10).unwrap();
+        assert_eq!(params.cliff_time, 110);
+        assert_eq!(params.cliff_unlock_amount, 0);
+        assert_eq!(params.amount_per_period * params.number_of_period, 1000);
+    }
+
+    #[test]
+    fn test_splits_linear_vesting_into_periods() {
+        let delivery = DeliveryMethod::VestedLinear {
+            cliff_secs: 100,
+            cliff_pct: 0,
+            period_secs: 300,
+            frequency_secs: 100,
+        };
+        let params = vesting_params(&delivery, 1000, 10).unwrap();
+        assert_eq!(params.cliff_time, 110);
+        assert_eq!(params.frequency, 100);
+        assert_eq!(params.number_of_period, 3);
+        assert_eq!(params.amount_per_period, 333);
+        assert_eq!(params.cliff_unlock_amount, 1);
+    }
+
+    #[test]
+    fn test_unlocks_cliff_percent_then_vests_linearly() {
+        let delivery = DeliveryMethod::VestedLinear {
+            cliff_secs: 100,
+            cliff_pct: 25,
+            period_secs: 400,
+            frequency_secs: 100,
+        };
+        let params = vesting_params(&delivery, 1001, 10).unwrap();
+        assert_eq!(params.number_of_period, 4);
+        assert_eq!(params.amount_per_period, 187);
+        // 250 at the cliff plus rounding remainder
+        assert_eq!(params.cliff_unlock_amount, 253);
+        assert_eq!(
+            params.cliff_unlock_amount + params.amount_per_period * params.number_of_period,
+            1001
+        );
+
+        let everything = DeliveryMethod::VestedLinear {
+            cliff_secs: 100,
+            cliff_pct: 100,
+            period_secs: 400,
+            frequency_secs: 100,
+        };
+        let params = vesting_params(&everything, 1001, 10).unwrap();
+        assert_eq!(params.cliff_unlock_amount, 1001);
+        assert_eq!(params.amount_per_period, 0);
+
+        let invalid = DeliveryMethod::VestedLinear {
+            cliff_secs: 100,
+            cliff_pct: 101,
+            period_secs: 400,
+            frequency_secs: 100,
+        };
+        assert!(vesting_params(&invalid, 1001, 10).is_err());
+    }
+
+    #[test]
+    fn test_rejects_immediate_delivery() {
+        assert!(vesting_params(&DeliveryMethod::Immediate, 1000, 10).is_err());
+    }
+}
@@ -0,0 +1,610 @@
+use super::{
+    quote::BuyQuote,
+    utils::{account_field, anchor_discriminator},
+    ProjectsOperations, WRAPPED_SOL_MINT,
+};
+use anchor_spl::associated_token::get_associated_token_address;
+use moonzip::PROGRAM_AUTHORITY;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction::create_account_with_seed,
+    system_program, sysvar,
+};
+use std::collections::BTreeMap;
+
+/// Bins, covered by a single bin array account of DLMM.
+const MAX_BIN_PER_ARRAY: i64 = 70;
+/// Distribution of the bin liquidity is in basis points.
+const FULL_DISTRIBUTION: u16 = 10000;
+/// Position spans the active bin and this many bins on each side of it, within DLMM limit of 70:
+/// tokens are sold from the upper bins and sols buy them back in the lower ones.
+const POSITION_HALF_WIDTH: i32 = 34;
+const POSITION_WIDTH: i32 = 2 * POSITION_HALF_WIDTH + 1;
+/// Liquidity of the position is never released, the same as burned raydium LP tokens.
+const LOCK_RELEASE_POINT: u64 = u64::MAX;
+/// Swap fee of the pair is in billionths of the input amount.
+const FEE_PRECISION: u128 = 1_000_000_000;
+
+const LB_PAIR_BASE_FACTOR_OFFSET: usize = 8;
+const LB_PAIR_ACTIVE_ID_OFFSET: usize = 76;
+const LB_PAIR_BIN_STEP_OFFSET: usize = 80;
+const BIN_ARRAY_BINS_OFFSET: usize = 56;
+const BIN_SIZE: usize = 144;
+
+const BIN_ARRAY_SEED: &[u8] = b"bin_array";
+const ORACLE_SEED: &[u8] = b"oracle";
+const PRESET_PARAMETER_SEED: &[u8] = b"preset_parameter";
+const POSITION_SEED: &[u8] = b"position";
+const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+#[derive(borsh::BorshSerialize)]
+struct InitializeLbPairArgs {
+    active_id: i32,
+    bin_step: u16,
+}
+
+#[derive(borsh::BorshSerialize)]
+struct InitializeBinArrayArgs {
+    index: i64,
+}
+
+#[derive(borsh::BorshSerialize)]
+struct InitializePositionByOperatorArgs {
+    lower_bin_id: i32,
+    width: i32,
+    fee_owner: Pubkey,
+    lock_release_point: u64,
+}
+
+#[derive(borsh::BorshSerialize)]
+struct BinLiquidityDistribution {
+    bin_id: i32,
+    distribution_x: u16,
+    distribution_y: u16,
+}
+
+#[derive(borsh::BorshSerialize)]
+struct AddLiquidityArgs {
+    amount_x: u64,
+    amount_y: u64,
+    bin_liquidity_dist: Vec<BinLiquidityDistribution>,
+}
+
+/// Liquidity of the graduated pair around its active bin, as [`ProjectsOperations::fetch_meteora_pair_state`] reads it.
+#[derive(Debug, Clone)]
+pub struct MeteoraPairState {
+    pub active_id: i32,
+    pub bin_step: u16,
+    /// Base fee only, variable fee grows with volatility and is not accounted.
+    pub base_fee_rate: u64,
+    /// `(amount_x, amount_y)` of bins by their ids.
+    pub bins: BTreeMap<i32, (u64, u64)>,
+}
+
+impl MeteoraPairState {
+    /// Lamports per the smallest token unit.
+    pub fn bin_price(&self, id: i32) -> f64 {
+        (1.0 + f64::from(self.bin_step) / f64::from(FULL_DISTRIBUTION)).powi(id)
+    }
+
+    pub fn buy_quote(&self, sols: u64) -> BuyQuote {
+        let (tokens, _) = self.buy(sols);
+        BuyQuote::at_spot_price(sols, tokens, self.bin_price(self.active_id))
+    }
+
+    /// Percents, the price of the active bin moves by, once `sols` are spent.
+    pub fn buy_impact_pct(&self, sols: u64) -> f64 {
+        let (_, last_id) = self.buy(sols);
+        (self.bin_price(last_id) / self.bin_price(self.active_id) - 1.0) * 100.0
+    }
+
+    /// Sells drain lamports of the bins from the active one downwards.
+    pub fn sell_output(&self, tokens: u64) -> u64 {
+        let mut tokens_left = self.input_after_fee(tokens) as f64;
+        let mut sols = 0.0;
+        for (&id, &(_, amount_y)) in self.bins.range(..=self.active_id).rev() {
+            let price = self.bin_price(id);
+            let value = tokens_left * price;
+            if value <= amount_y as f64 {
+                sols += value;
+                break;
+            }
+            sols += amount_y as f64;
+            tokens_left -= amount_y as f64 / price;
+        }
+        sols as u64
+    }
+
+    /// Tokens, received for `sols`, and the bin, the swap stops at:
+    /// buys drain tokens of the bins from the active one upwards.
+    fn buy(&self, sols: u64) -> (u64, i32) {
+        let mut sols_left = self.input_after_fee(sols) as f64;
+        let mut tokens = 0.0;
+        let mut last_id = self.active_id;
+        for (&id, &(amount_x, _)) in self.bins.range(self.active_id..) {
+            if amount_x == 0 {
+                continue;
+            }
+            last_id = id;
+            let price = self.bin_price(id);
+            let cost = amount_x as f64 * price;
+            if sols_left <= cost {
+                tokens += sols_left / price;
+                break;
+            }
+            tokens += amount_x as f64;
+            sols_left -= cost;
+        }
+        (tokens as u64, last_id)
+    }
+
+    fn input_after_fee(&self, amount: u64) -> u64 {
+        let fee = (amount as u128 * self.base_fee_rate as u128).div_ceil(FEE_PRECISION);
+        (amount as u128).saturating_sub(fee) as u64
+    }
+}
+
+/// Meteora DLMM pair of the project: project token is X and wrapped sol is Y,
+/// so bin prices are lamports per the smallest token unit.
+///
+/// Unlike raydium, DLMM issues no LP token to burn: the liquidity is held by the position,
+/// which is locked on creation, so only its fees could be claimed by the program authority.
+impl<'a> ProjectsOperations<'a> {
+    /// Creates the pair with the active bin at the price of the deployed liquidity,
+    /// along with bin arrays and the locked position, [`Self::deploy_to_meteora`] deposits to.
+    pub fn create_meteora_pair(
+        &self,
+        tokens_amount: u64,
+        sols_amount: u64,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let curve_mint = self.curve_mint()?;
+        let lb_pair = self.meteora_lb_pair()?;
+        let active_id = active_bin_id(sols_amount, tokens_amount, self.config.meteora_bin_step)?;
+        let donor = PROGRAM_AUTHORITY;
+        let program = self.config.meteora_program;
+
+        let initialize_pair = self.meteora_instruction(
+            "initialize_lb_pair",
+            &InitializeLbPairArgs {
+                active_id,
+                bin_step: self.config.meteora_bin_step,
+            },
+            vec![
+                AccountMeta::new(lb_pair, false),
+                // bitmap extension is needed only for bins, far from zero
+                AccountMeta::new_readonly(program, false),
+                AccountMeta::new_readonly(curve_mint, false),
+                AccountMeta::new_readonly(WRAPPED_SOL_MINT, false),
+                AccountMeta::new(self.meteora_reserve(&lb_pair, &curve_mint), false),
+                AccountMeta::new(self.meteora_reserve(&lb_pair, &WRAPPED_SOL_MINT), false),
+                AccountMeta::new(self.meteora_oracle(&lb_pair), false),
+                AccountMeta::new_readonly(self.meteora_preset_parameter(), false),
+                AccountMeta::new(donor, true),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(sysvar::rent::ID, false),
+                AccountMeta::new_readonly(self.meteora_event_authority(), false),
+                AccountMeta::new_readonly(program, false),
+            ],
+        )?;
+
+        let lower_bin_id = position_lower_bin(active_id)?;
+        let (lower_index, upper_index) = position_bin_arrays(lower_bin_id);
+        let mut ixs = vec![initialize_pair];
+        for index in [lower_index, upper_index] {
+            ixs.push(self.meteora_instruction(
+                "initialize_bin_array",
+                &InitializeBinArrayArgs { index },
+                vec![
+                    AccountMeta::new_readonly(lb_pair, false),
+                    AccountMeta::new(self.meteora_bin_array(&lb_pair, index), false),
+                    AccountMeta::new(donor, true),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+            )?);
+        }
+
+        // authority is both the owner and the operator, the latter is allowed to set the lock
+        let donor_tokens = get_associated_token_address(&donor, &curve_mint);
+        ixs.push(self.meteora_instruction(
+            "initialize_position_by_operator",
+            &InitializePositionByOperatorArgs {
+                lower_bin_id,
+                width: POSITION_WIDTH,
+                fee_owner: donor,
+                lock_release_point: LOCK_RELEASE_POINT,
+            },
+            vec![
+                AccountMeta::new(donor, true),
+                AccountMeta::new_readonly(donor, true),
+                AccountMeta::new(self.meteora_position(&lb_pair, lower_bin_id), false),
+                AccountMeta::new_readonly(lb_pair, false),
+                AccountMeta::new_readonly(donor, false),
+                AccountMeta::new_readonly(donor, true),
+                AccountMeta::new_readonly(donor_tokens, false),
+                AccountMeta::new_readonly(donor_tokens, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(self.meteora_event_authority(), false),
+                AccountMeta::new_readonly(program, false),
+            ],
+        )?);
+        Ok(ixs)
+    }
+
+    /// Spreads the liquidity over the bins of the position, created by [`Self::create_meteora_pair`].
+    pub fn deploy_to_meteora(
+        &self,
+        tokens_amount: u64,
+        sols_amount: u64,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let curve_mint = self.curve_mint()?;
+        let lb_pair = self.meteora_lb_pair()?;
+        let active_id = active_bin_id(sols_amount, tokens_amount, self.config.meteora_bin_step)?;
+        let donor = PROGRAM_AUTHORITY;
+        let program = self.config.meteora_program;
+
+        let token_account_space = spl_token::state::Account::LEN;
+        let lamports = self.rent.minimum_balance(token_account_space) + sols_amount;
+        let seed = self.project_derived_seed("meteora_intermediate_holder");
+        let donor_wrapped_sol = Pubkey::create_with_seed(&donor, &seed, &spl_token::ID)?;
+
+        let create_donor_wrapped_sol = create_account_with_seed(
+            &donor,
+            &donor_wrapped_sol,
+            &donor,
+            &seed,
+            lamports,
+            token_account_space as u64,
+            &spl_token::ID,
+        );
+        let initialize_donor_wrapped_sol = spl_token::instruction::initialize_account(
+            &spl_token::ID,
+            &donor_wrapped_sol,
+            &WRAPPED_SOL_MINT,
+            &donor,
+        )?;
+        let close_donor_wrapped_sol = spl_token::instruction::close_account(
+            &spl_token::ID,
+            &donor_wrapped_sol,
+            &donor,
+            &donor,
+            &[&donor],
+        )?;
+
+        let lower_bin_id = position_lower_bin(active_id)?;
+        let (lower_index, upper_index) = position_bin_arrays(lower_bin_id);
+        let add_liquidity = self.meteora_instruction(
+            "add_liquidity",
+            &AddLiquidityArgs {
+                amount_x: tokens_amount,
+                amount_y: sols_amount,
+                bin_liquidity_dist: spread_liquidity(active_id),
+            },
+            vec![
+                AccountMeta::new(self.meteora_position(&lb_pair, lower_bin_id), false),
+                AccountMeta::new(lb_pair, false),
+                AccountMeta::new_readonly(program, false),
+                AccountMeta::new(get_associated_token_address(&donor, &curve_mint), false),
+                AccountMeta::new(donor_wrapped_sol, false),
+                AccountMeta::new(self.meteora_reserve(&lb_pair, &curve_mint), false),
+                AccountMeta::new(self.meteora_reserve(&lb_pair, &WRAPPED_SOL_MINT), false),
+                AccountMeta::new_readonly(curve_mint, false),
+                AccountMeta::new_readonly(WRAPPED_SOL_MINT, false),
+                AccountMeta::new(self.meteora_bin_array(&lb_pair, lower_index), false),
+                AccountMeta::new(self.meteora_bin_array(&lb_pair, upper_index), false),
+                AccountMeta::new_readonly(donor, true),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(self.meteora_event_authority(), false),
+                AccountMeta::new_readonly(program, false),
+            ],
+        )?;
+
+        Ok(vec![
+            create_donor_wrapped_sol,
+            initialize_donor_wrapped_sol,
+            add_liquidity,
+            close_donor_wrapped_sol,
+        ])
+    }
+
+    /// Absent until the project is deployed to meteora.
+    /// Position is narrower than a bin array, so its bins are within the neighbours of the active one.
+    pub async fn fetch_meteora_pair_state(&self) -> anyhow::Result<Option<MeteoraPairState>> {
+        let lb_pair = self.meteora_lb_pair()?;
+        let client = self.solana_pool.rpc_client().use_single().await;
+        let commitment = client.commitment();
+        let Some(pair) = client
+            .get_account_with_commitment(&lb_pair, commitment)
+            .await?
+            .value
+        else {
+            return Ok(None);
+        };
+        let active_id = i32::from_le_bytes(account_field(&pair.data, LB_PAIR_ACTIVE_ID_OFFSET)?);
+        let bin_step = u16::from_le_bytes(account_field(&pair.data, LB_PAIR_BIN_STEP_OFFSET)?);
+        let base_factor =
+            u16::from_le_bytes(account_field(&pair.data, LB_PAIR_BASE_FACTOR_OFFSET)?);
+
+        let active_index = i64::from(active_id).div_euclid(MAX_BIN_PER_ARRAY);
+        let indexes = [active_index - 1, active_index, active_index + 1];
+        let addresses: Vec<_> = indexes
+            .iter()
+            .map(|index| self.meteora_bin_array(&lb_pair, *index))
+            .collect();
+        let arrays = client
+            .get_multiple_accounts_with_commitment(&addresses, commitment)
+            .await?
+            .value;
+        let mut bins = BTreeMap::new();
+        for (index, array) in indexes.into_iter().zip(arrays) {
+            let Some(array) = array else {
+                continue;
+            };
+            for offset in 0..MAX_BIN_PER_ARRAY {
+                let at = BIN_ARRAY_BINS_OFFSET + offset as usize * BIN_SIZE;
+                let amount_x = u64::from_le_bytes(account_field(&array.data, at)?);
+                let amount_y = u64::from_le_bytes(account_field(&array.data, at + 8)?);
+                bins.insert(
+                    (index * MAX_BIN_PER_ARRAY + offset) as i32,
+                    (amount_x, amount_y),
+                );
+            }
+        }
+        Ok(Some(MeteoraPairState {
+            active_id,
+            bin_step,
+            base_fee_rate: u64::from(base_factor) * u64::from(bin_step) * 10,
+            bins,
+        }))
+    }
+
+    fn meteora_instruction<T: borsh::BorshSerialize>(
+        &self,
+        name: &str,
+        args: &T,
+        accounts: Vec<AccountMeta>,
+    ) -> anyhow::Result<Instruction> {
+        let mut data = anchor_discriminator(name).to_vec();
+        args.serialize(&mut data)?;
+        Ok(Instruction {
+            program_id: self.config.meteora_program,
+            accounts,
+            data,
+        })
+    }
+
+    fn meteora_lb_pair(&self) -> anyhow::Result<Pubkey> {
+        let curve_mint = self.curve_mint()?;
+        let (min, max) = if curve_mint < WRAPPED_SOL_MINT {
+            (curve_mint, WRAPPED_SOL_MINT)
+        } else {
+            (WRAPPED_SOL_MINT, curve_mint)
+        };
+        Ok(Pubkey::find_program_address(
+            &[
+                min.as_ref(),
+                max.as_ref(),
+                &self.config.meteora_bin_step.to_le_bytes(),
+                &self.config.meteora_base_factor.to_le_bytes(),
+            ],
+            &self.config.meteora_program,
+        )
+        .0)
+    }
+
+    fn meteora_reserve(&self, lb_pair: &Pubkey, mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[lb_pair.as_ref(), mint.as_ref()],
+            &self.config.meteora_program,
+        )
+        .0
+    }
+
+    fn meteora_oracle(&self, lb_pair: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[ORACLE_SEED, lb_pair.as_ref()],
+            &self.config.meteora_program,
+        )
+        .0
+    }
+
+    fn meteora_preset_parameter(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                PRESET_PARAMETER_SEED,
+                &self.config.meteora_bin_step.to_le_bytes(),
+                &self.config.meteora_base_factor.to_le_bytes(),
+            ],
+            &self.config.meteora_program,
+        )
+        .0
+    }
+
+    fn meteora_bin_array(&self, lb_pair: &Pubkey, index: i64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[BIN_ARRAY_SEED, lb_pair.as_ref(), &index.to_le_bytes()],
+            &self.config.meteora_program,
+        )
+        .0
+    }
+
+    fn meteora_position(&self, lb_pair: &Pubkey, lower_bin_id: i32) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                POSITION_SEED,
+                lb_pair.as_ref(),
+                PROGRAM_AUTHORITY.as_ref(),
+                &lower_bin_id.to_le_bytes(),
+                &POSITION_WIDTH.to_le_bytes(),
+            ],
+            &self.config.meteora_program,
+        )
+        .0
+    }
+
+    fn meteora_event_authority(&self) -> Pubkey {
+        Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &self.config.meteora_program).0
+    }
+}
+
+/// Bin, which price is the closest to lamports per token of the deployed liquidity.
+/// Price of the bin `id` is `(1 + bin_step / 10000) ^ id`.
+fn active_bin_id(sols: u64, tokens: u64, bin_step: u16) -> anyhow::Result<i32> {
+    if sols == 0 || tokens == 0 || bin_step == 0 {
+        anyhow::bail!("unable to price meteora pair: {sols} lamports, {tokens} tokens");
+    }
+    let price = sols as f64 / tokens as f64;
+    let base = 1.0 + f64::from(bin_step) / f64::from(FULL_DISTRIBUTION);
+    let id = (price.ln() / base.ln()).round();
+    if !(f64::from(i32::MIN)..=f64::from(i32::MAX)).contains(&id) {
+        anyhow::bail!("meteora bin for price {price} is out of range");
+    }
+    Ok(id as i32)
+}
+
+fn position_lower_bin(active_id: i32) -> anyhow::Result<i32> {
+    active_id
+        .checked_sub(POSITION_HALF_WIDTH)
+        .ok_or_else(|| anyhow::anyhow!("meteora position around bin {active_id} is out of range"))
+}
+
+/// Tokens are spread evenly over the active bin and the ones above it, sols over the active bin
+/// and the ones below it, the remainder of each side goes to the active bin.
+fn spread_liquidity(active_id: i32) -> Vec<BinLiquidityDistribution> {
+    let side_bins = POSITION_HALF_WIDTH as u16 + 1;
+    let share = FULL_DISTRIBUTION / side_bins;
+    let active_share = FULL_DISTRIBUTION - share * (side_bins - 1);
+    (-POSITION_HALF_WIDTH..=POSITION_HALF_WIDTH)
+        .map(|offset| {
+            let side_share = if offset == 0 { active_share } else { share };
+            BinLiquidityDistribution {
+                bin_id: active_id + offset,
+                distribution_x: if offset >= 0 { side_share } else { 0 },
+                distribution_y: if offset <= 0 { side_share } else { 0 },
+            }
+        })
+        .collect()
+}
+
+/// Bin arrays of the position, starting at `bin_id`: position width fits a single array,
+/// so it never spans more than two of them.
+/// DLMM requires the upper bin array to follow the lower one, even if the position fits the first.
+fn position_bin_arrays(bin_id: i32) -> (i64, i64) {
+    let lower = i64::from(bin_id).div_euclid(MAX_BIN_PER_ARRAY);
+    (lower, lower + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_picks_bin_closest_to_the_price() {
+        assert_eq!(active_bin_id(1000, 1000, 100).unwrap(), 0);
+        // 1.01 ^ 70 ~ 2.0068
+        assert_eq!(active_bin_id(2000, 1000, 100).unwrap(), 70);
+        assert_eq!(active_bin_id(1000, 2000, 100).unwrap(), -70);
+        // 79 sols against 200M tokens with 6 decimals
+        assert_eq!(
+            active_bin_id(79_000_000_000, 200_000_000_000_000, 100).unwrap(),
+            -788
+        );
+        assert!(active_bin_id(0, 1000, 100).is_err());
+        assert!(active_bin_id(1000, 0, 100).is_err());
+    }
+
+    #[test]
+    fn test_floors_bin_array_index_of_negative_bins() {
+        assert_eq!(position_bin_arrays(0), (0, 1));
+        assert_eq!(position_bin_arrays(69), (0, 1));
+        assert_eq!(position_bin_arrays(70), (1, 2));
+        assert_eq!(position_bin_arrays(-1), (-1, 0));
+        assert_eq!(position_bin_arrays(-70), (-1, 0));
+        assert_eq!(position_bin_arrays(-71), (-2, -1));
+    }
+
+    #[test]
+    fn test_spreads_liquidity_over_the_position() {
+        let active_id = -788;
+        let spread = spread_liquidity(active_id);
+        assert_eq!(spread.len(), POSITION_WIDTH as usize);
+
+        let lower_bin_id = position_lower_bin(active_id).unwrap();
+        assert_eq!(spread.first().unwrap().bin_id, lower_bin_id);
+        assert_eq!(
+            spread.last().unwrap().bin_id,
+            lower_bin_id + POSITION_WIDTH - 1
+        );
+
+        let total_x: u16 = spread.iter().map(|bin| bin.distribution_x).sum();
+        let total_y: u16 = spread.iter().map(|bin| bin.distribution_y).sum();
+        assert_eq!((total_x, total_y), (FULL_DISTRIBUTION, FULL_DISTRIBUTION));
+        for bin in &spread {
+            // tokens are never offered below the price, nor sols above it
+            assert!(bin.bin_id >= active_id || bin.distribution_x == 0);
+            assert!(bin.bin_id <= active_id || bin.distribution_y == 0);
+            assert!(bin.distribution_x > 0 || bin.distribution_y > 0);
+        }
+    }
+
+    #[test]
+    fn test_fits_position_into_its_bin_arrays() {
+        for active_id in [-788, -35, 0, 34, 69, 443_000] {
+            let lower_bin_id = position_lower_bin(active_id).unwrap();
+            let upper_bin_id = lower_bin_id + POSITION_WIDTH - 1;
+            let (lower_index, upper_index) = position_bin_arrays(lower_bin_id);
+            let covered = lower_index * MAX_BIN_PER_ARRAY..(upper_index + 1) * MAX_BIN_PER_ARRAY;
+            assert!(covered.contains(&i64::from(upper_bin_id)));
+        }
+        assert!(position_lower_bin(i32::MIN).is_err());
+    }
+
+    fn pair_state(base_fee_rate: u64) -> MeteoraPairState {
+        // 1000 tokens above the active bin, 1000 lamports below it, price 1 in the active bin
+        let bins = (-2..=2)
+            .map(|id: i32| {
+                let amount_x = if id >= 0 { 1000 } else { 0 };
+                let amount_y = if id <= 0 { 1000 } else { 0 };
+                (id, (amount_x, amount_y))
+            })
+            .collect();
+        MeteoraPairState {
+            active_id: 0,
+            bin_step: 100,
+            base_fee_rate,
+            bins,
+        }
+    }
+
+    #[test]
+    fn test_quotes_pair_within_the_active_bin() {
+        let state = pair_state(0);
+        assert_eq!(state.buy_quote(500).expected_tokens, 500);
+        assert_eq!(state.buy_quote(500).price_impact_bps, 0);
+        assert_eq!(state.buy_impact_pct(500), 0.0);
+        assert_eq!(state.sell_output(500), 500);
+    }
+
+    #[test]
+    fn test_quotes_pair_across_bins() {
+        let state = pair_state(0);
+        // the active bin is drained by 1000 lamports, the rest buys at 1.01
+        assert_eq!(state.buy_quote(1505).expected_tokens, 1500);
+        assert!((state.buy_impact_pct(1505) - 1.0).abs() < 1e-9);
+        // 1000 tokens drain the active bin, the rest sells at 1 / 1.01
+        assert_eq!(state.sell_output(1051), 1050);
+        // liquidity beyond the fetched bins is not quoted
+        assert_eq!(state.buy_quote(u64::MAX).expected_tokens, 3000);
+    }
+
+    #[test]
+    fn test_takes_pair_fee_from_input() {
+        // 1% of the input
+        let state = pair_state(10_000_000);
+        assert_eq!(state.buy_quote(500).expected_tokens, 495);
+        assert_eq!(state.sell_output(500), 495);
+    }
+}
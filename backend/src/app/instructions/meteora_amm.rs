@@ -0,0 +1,326 @@
+use super::{
+    create_token_metadata, mpl, utils::anchor_discriminator, CurveCreate, InstructionsConfig,
+    ProjectsOperations, WRAPPED_SOL_MINT,
+};
+use anchor_spl::associated_token::{
+    get_associated_token_address,
+    spl_associated_token_account::instruction::{
+        create_associated_token_account, create_associated_token_account_idempotent,
+    },
+};
+use moonzip::PROGRAM_AUTHORITY;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey,
+    pubkey::Pubkey,
+    system_instruction, system_program, sysvar,
+};
+
+/// Base of vaults, created permissionlessly, the same for every mint.
+const VAULT_BASE: Pubkey = pubkey!("HWzXGcGHy4tcpYfaRDCyLNzXqBTv3E6BttpCH2vJxArv");
+/// Owner of protocol fee accounts of permissionless pools.
+const AMM_FEE_OWNER: Pubkey = pubkey!("6WaLrrRfReGKBYUSkmx2K6AuT21ida4j8at2SUiZdXu8");
+
+const VAULT_SEED: &[u8] = b"vault";
+const TOKEN_VAULT_SEED: &[u8] = b"token_vault";
+const LP_MINT_SEED: &[u8] = b"lp_mint";
+const FEE_SEED: &[u8] = b"fee";
+
+#[derive(borsh::BorshSerialize)]
+enum CurveType {
+    ConstantProduct,
+}
+
+#[derive(borsh::BorshSerialize)]
+struct InitializePermissionlessPoolArgs {
+    curve_type: CurveType,
+    token_a_amount: u64,
+    token_b_amount: u64,
+}
+
+/// Meteora Dynamic AMM pool of [`super::CurveVariant::Meteora`] launch:
+/// project token is A and wrapped sol is B, both are held by dynamic vaults.
+///
+/// LP tokens of the initial deposit are burned in the same transaction the pool is created,
+/// the same as raydium ones, so the liquidity can't be withdrawn.
+impl<'a> ProjectsOperations<'a> {
+    /// Instructions of two transactions, as they don't fit a single one:
+    /// the first mints the whole supply to the authority and prepares the token vault,
+    /// the second creates the pool with all of it against `sols_to_graduate`,
+    /// burns the LP tokens and graduates the project.
+    ///
+    /// Pool creation takes the initial deposit itself, so no separate liquidity is added.
+    pub fn init_meteora_pool(
+        &mut self,
+        action: CurveCreate,
+    ) -> anyhow::Result<(Vec<Instruction>, Vec<Instruction>)> {
+        let meta = self.mzip_meta.clone().get()?;
+        let config = &meta.global_account.config;
+        let tokens_amount = config.curve.total_token_supply;
+        let sols_amount = self.config.sols_to_graduate;
+        let authority_ata = get_associated_token_address(&PROGRAM_AUTHORITY, &action.mint);
+
+        let mint_ixs = vec![
+            system_instruction::create_account(
+                &PROGRAM_AUTHORITY,
+                &action.mint,
+                self.rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &action.mint,
+                &PROGRAM_AUTHORITY,
+                None,
+                config.token_decimals,
+            )?,
+            create_associated_token_account(
+                &PROGRAM_AUTHORITY,
+                &PROGRAM_AUTHORITY,
+                &action.mint,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::mint_to(
+                &spl_token::ID,
+                &action.mint,
+                &authority_ata,
+                &PROGRAM_AUTHORITY,
+                &[],
+                tokens_amount,
+            )?,
+            // the project graduates right away, so metadata is never updated
+            create_token_metadata(&action, false)?,
+            // metadata creation needs the mint authority, so it's revoked only afterwards
+            spl_token::instruction::set_authority(
+                &spl_token::ID,
+                &action.mint,
+                None,
+                spl_token::instruction::AuthorityType::MintTokens,
+                &PROGRAM_AUTHORITY,
+                &[],
+            )?,
+            initialize_meteora_vault(self.config, &action.mint),
+        ];
+
+        let donor_wrapped_sol = get_associated_token_address(&PROGRAM_AUTHORITY, &WRAPPED_SOL_MINT);
+        let mut pool_ixs = vec![
+            create_associated_token_account_idempotent(
+                &PROGRAM_AUTHORITY,
+                &PROGRAM_AUTHORITY,
+                &WRAPPED_SOL_MINT,
+                &spl_token::ID,
+            ),
+            system_instruction::transfer(&PROGRAM_AUTHORITY, &donor_wrapped_sol, sols_amount),
+            spl_token::instruction::sync_native(&spl_token::ID, &donor_wrapped_sol)?,
+            initialize_permissionless_pool(
+                self.config,
+                &action.mint,
+                InitializePermissionlessPoolArgs {
+                    curve_type: CurveType::ConstantProduct,
+                    token_a_amount: tokens_amount,
+                    token_b_amount: sols_amount,
+                },
+                authority_ata,
+                donor_wrapped_sol,
+            )?,
+            spl_token::instruction::close_account(
+                &spl_token::ID,
+                &donor_wrapped_sol,
+                &PROGRAM_AUTHORITY,
+                &PROGRAM_AUTHORITY,
+                &[],
+            )?,
+        ];
+        let lp_mint = meteora_amm_lp_mint(self.config, &action.mint);
+        pool_ixs.append(&mut self.emit_liquidity_burn(lp_mint)?);
+        pool_ixs.append(&mut self.burn_and_close(PROGRAM_AUTHORITY, lp_mint)?);
+        pool_ixs.append(&mut self.manual_project_graduate()?);
+
+        Ok((mint_ixs, pool_ixs))
+    }
+}
+
+/// Vault of wrapped sol is already there, the one of the fresh mint is created by us.
+fn initialize_meteora_vault(config: &InstructionsConfig, mint: &Pubkey) -> Instruction {
+    let vault = meteora_vault(config, mint);
+    Instruction {
+        program_id: config.meteora_vault_program,
+        accounts: vec![
+            AccountMeta::new(vault, false),
+            AccountMeta::new(PROGRAM_AUTHORITY, true),
+            AccountMeta::new(meteora_vault_pda(config, TOKEN_VAULT_SEED, &vault), false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(meteora_vault_pda(config, LP_MINT_SEED, &vault), false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: anchor_discriminator("initialize").to_vec(),
+    }
+}
+
+/// LP tokens of the initial deposit are minted to the authority ATA of [`meteora_amm_lp_mint`].
+fn initialize_permissionless_pool(
+    config: &InstructionsConfig,
+    mint: &Pubkey,
+    args: InitializePermissionlessPoolArgs,
+    donor_tokens: Pubkey,
+    donor_wrapped_sol: Pubkey,
+) -> anyhow::Result<Instruction> {
+    let program = config.meteora_amm_program;
+    let pool = meteora_amm_pool(config, mint);
+    let lp_mint = meteora_amm_lp_mint(config, mint);
+    let a_vault = meteora_vault(config, mint);
+    let b_vault = meteora_vault(config, &WRAPPED_SOL_MINT);
+    let a_vault_lp_mint = meteora_vault_pda(config, LP_MINT_SEED, &a_vault);
+    let b_vault_lp_mint = meteora_vault_pda(config, LP_MINT_SEED, &b_vault);
+    let vault_lp =
+        |vault: &Pubkey| Pubkey::find_program_address(&[vault.as_ref(), pool.as_ref()], &program).0;
+    let protocol_fee = |mint: &Pubkey| {
+        Pubkey::find_program_address(&[FEE_SEED, mint.as_ref(), pool.as_ref()], &program).0
+    };
+
+    let mut data = anchor_discriminator("initialize_permissionless_pool").to_vec();
+    borsh::BorshSerialize::serialize(&args, &mut data)?;
+    Ok(Instruction {
+        program_id: program,
+        accounts: vec![
+            AccountMeta::new(pool, false),
+            AccountMeta::new(lp_mint, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(WRAPPED_SOL_MINT, false),
+            AccountMeta::new(a_vault, false),
+            AccountMeta::new(b_vault, false),
+            AccountMeta::new(a_vault_lp_mint, false),
+            AccountMeta::new(b_vault_lp_mint, false),
+            AccountMeta::new(vault_lp(&a_vault), false),
+            AccountMeta::new(vault_lp(&b_vault), false),
+            AccountMeta::new(donor_tokens, false),
+            AccountMeta::new(donor_wrapped_sol, false),
+            AccountMeta::new(
+                get_associated_token_address(&PROGRAM_AUTHORITY, &lp_mint),
+                false,
+            ),
+            AccountMeta::new(protocol_fee(mint), false),
+            AccountMeta::new(protocol_fee(&WRAPPED_SOL_MINT), false),
+            AccountMeta::new(PROGRAM_AUTHORITY, true),
+            AccountMeta::new_readonly(AMM_FEE_OWNER, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new(mpl::metadata_account(lp_mint), false),
+            AccountMeta::new_readonly(*mpl::PROGRAM, false),
+            AccountMeta::new_readonly(config.meteora_vault_program, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(anchor_spl::associated_token::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data,
+    })
+}
+
+/// Permissionless pools are derived from the curve type and both mints, the greater one first.
+fn meteora_amm_pool(config: &InstructionsConfig, mint: &Pubkey) -> Pubkey {
+    let (first, second) = if *mint > WRAPPED_SOL_MINT {
+        (*mint, WRAPPED_SOL_MINT)
+    } else {
+        (WRAPPED_SOL_MINT, *mint)
+    };
+    let constant_product = 0u8;
+    Pubkey::find_program_address(
+        &[&[constant_product], first.as_ref(), second.as_ref()],
+        &config.meteora_amm_program,
+    )
+    .0
+}
+
+fn meteora_amm_lp_mint(config: &InstructionsConfig, mint: &Pubkey) -> Pubkey {
+    let pool = meteora_amm_pool(config, mint);
+    Pubkey::find_program_address(&[LP_MINT_SEED, pool.as_ref()], &config.meteora_amm_program).0
+}
+
+fn meteora_vault(config: &InstructionsConfig, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[VAULT_SEED, mint.as_ref(), VAULT_BASE.as_ref()],
+        &config.meteora_vault_program,
+    )
+    .0
+}
+
+fn meteora_vault_pda(config: &InstructionsConfig, seed: &[u8], vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seed, vault.as_ref()], &config.meteora_vault_program).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool_creation(mint: &Pubkey) -> Instruction {
+        let donor_tokens = get_associated_token_address(&PROGRAM_AUTHORITY, mint);
+        let donor_wrapped_sol = get_associated_token_address(&PROGRAM_AUTHORITY, &WRAPPED_SOL_MINT);
+        initialize_permissionless_pool(
+            &InstructionsConfig::default(),
+            mint,
+            InitializePermissionlessPoolArgs {
+                curve_type: CurveType::ConstantProduct,
+                token_a_amount: 1_000,
+                token_b_amount: 79,
+            },
+            donor_tokens,
+            donor_wrapped_sol,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mints_lp_tokens_to_the_burned_account() {
+        let config = InstructionsConfig::default();
+        let mint = Pubkey::new_unique();
+        let ix = pool_creation(&mint);
+
+        let lp_mint = meteora_amm_lp_mint(&config, &mint);
+        assert_eq!(ix.accounts[1].pubkey, lp_mint);
+        // the same account is emptied by `burn_and_close` of the authority
+        assert_eq!(
+            ix.accounts[12].pubkey,
+            get_associated_token_address(&PROGRAM_AUTHORITY, &lp_mint)
+        );
+        assert!(ix.accounts[12].is_writable);
+    }
+
+    #[test]
+    fn test_deposits_whole_amounts_of_both_sides() {
+        let ix = pool_creation(&Pubkey::new_unique());
+        let mut expected = anchor_discriminator("initialize_permissionless_pool").to_vec();
+        expected.push(0);
+        expected.extend(1_000u64.to_le_bytes());
+        expected.extend(79u64.to_le_bytes());
+        assert_eq!(ix.data, expected);
+        assert_eq!(
+            ix.program_id,
+            InstructionsConfig::default().meteora_amm_program
+        );
+    }
+
+    #[test]
+    fn test_derives_pool_regardless_of_mint_order() {
+        let config = InstructionsConfig::default();
+        let lesser = Pubkey::new_from_array([0; 32]);
+        let greater = Pubkey::new_from_array([255; 32]);
+        let expected = |first: &Pubkey, second: &Pubkey| {
+            Pubkey::find_program_address(
+                &[&[0], first.as_ref(), second.as_ref()],
+                &config.meteora_amm_program,
+            )
+            .0
+        };
+        assert_eq!(
+            meteora_amm_pool(&config, &lesser),
+            expected(&WRAPPED_SOL_MINT, &lesser)
+        );
+        assert_eq!(
+            meteora_amm_pool(&config, &greater),
+            expected(&greater, &WRAPPED_SOL_MINT)
+        );
+    }
+}
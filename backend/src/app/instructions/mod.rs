@@ -1,7 +1,12 @@
 use super::{
-    exposed::DevLockPeriod,
-    storage::project::{project_id, CurveVariant, FullProjectState, Stage, StoredTokenMeta},
+    exposed::DeliveryMethod,
+    image_format::ImageFormat,
+    storage::project::{
+        project_id, CurveVariant, FullProjectState, GraduationTarget, ProjectId, Stage,
+        StoredDevPurchase, StoredStaticPoolConfig, StoredTokenMeta,
+    },
 };
+use anchor_client::anchor_lang::{AccountDeserialize as _, Discriminator as _};
 use anchor_spl::associated_token::{
     self, get_associated_token_address,
     spl_associated_token_account::instruction::{
@@ -10,17 +15,26 @@ use anchor_spl::associated_token::{
 };
 use anyhow::bail;
 use moonzip::{
-    accounts::BaseInitTransmuterAccounts,
+    accounts::{BaseInitTransmuterAccounts, ProgramAuthorityAccounts},
+    authority::authority_config_address,
     common::PoolCloseConditions,
     fee::fee_address,
     instruction::{BuyFromPump, GraduateStaticPool},
+    metadata::{metadata_authority_address, LockProjectMetadataData, UpdateProjectMetadataData},
     moonzip::{
-        curve::CalcBuy as _, curved_pool_address, static_pool_address, BuyFromCurvedPoolData,
-        BuyFromStaticPoolData, CreateCurvedPoolData, CreateStaticPoolData, CurvedPool,
-        GraduateCurvedPoolData, SellFromCurvedPoolData, SellToStaticPoolData, StaticPool,
-        StaticPoolConfig, Transmuter, CURVED_POOL_PREFIX, TRANSMUTER_PREFIX,
+        creator_fee_wallet_address, curve::CalcBuy as _, curved_pool_address,
+        project_stats_address, static_pool_address, wallet_record_address, BuyExactTokensData,
+        BuyFromCurvedPoolData, BuyFromStaticPoolData, CreateCurvedPoolData, CreateStaticPoolData,
+        CurveStateView, CurvedPool, CurvedPoolConfig, ExtendStaticPoolDeadlineData,
+        GraduateCurvedPoolData, InitTransmuterData, ProjectStats, RefundFromStaticPoolData,
+        RevokeFreezeAuthorityData, SellExactSolData, SellFromCurvedPoolData, SellToStaticPoolData,
+        SetTransmuterExpiryData, StaticPool, StaticPoolConfig, StaticPoolWalletRecord,
+        TransmuteIdempotentData, Transmuter, CURVED_POOL_PREFIX, TRANSMUTER_PREFIX,
+    },
+    project::{
+        ownership_transfer_address, project_address, CreateProjectData, ForceUnlockStaleLatchData,
+        MigrateProjectData, Project, ProposeTransferOwnershipData,
     },
-    project::{project_address, CreateProjectData},
     pumpfun::BuyFromPumpData,
     PROGRAM_AUTHORITY,
 };
@@ -28,9 +42,14 @@ use mpl::SampleMetadata;
 use mpl_token_metadata::instructions::CreateV1Builder;
 use mzip::{FEE_ACCOUNT, MOONZIP_EVENT_AUTHORITY};
 use serde::Deserialize;
-use serde_with::{serde_as, DurationSeconds};
+use serde_with::{serde_as, DisplayFromStr, DurationSeconds};
 use services_common::{solana::pool::SolanaPool, utils::period_fetch::DataReceiver, TZ};
 use sha2::{Digest, Sha256};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
 use solana_sdk::{
     instruction::Instruction,
     native_token::{sol_to_lamports, LAMPORTS_PER_SOL},
@@ -44,11 +63,16 @@ use solana_sdk::{
 use std::{str::FromStr, sync::Arc, time::Duration};
 use utils::anchor_event_authority;
 
+pub mod jupiter;
 pub mod lock;
+pub mod meteora;
+pub mod meteora_amm;
 pub mod mpl;
 pub mod mzip;
 pub mod openbook;
+pub mod orca;
 pub mod pumpfun;
+pub mod quote;
 pub mod raydium;
 pub mod solana;
 pub mod utils;
@@ -65,24 +89,115 @@ pub struct InstructionsConfig {
     #[serde(default = "default_raydium_program")]
     pub raydium_program: Pubkey,
 
+    #[serde(default = "default_meteora_program")]
+    pub meteora_program: Pubkey,
+    /// Meteora pair is created from the preset, existing for this bin step and base factor.
+    #[serde(default = "default_meteora_bin_step")]
+    pub meteora_bin_step: u16,
+    #[serde(default = "default_meteora_base_factor")]
+    pub meteora_base_factor: u16,
+
+    /// Meteora Dynamic AMM, tokens of [`CurveVariant::Meteora`] are launched to.
+    #[serde(default = "default_meteora_amm_program")]
+    pub meteora_amm_program: Pubkey,
+    /// Dynamic vaults, holding the liquidity of Dynamic AMM pools.
+    #[serde(default = "default_meteora_vault_program")]
+    pub meteora_vault_program: Pubkey,
+    /// Rents of the Dynamic AMM pool, its vaults and LP accounts.
+    #[serde(default = "default_meteora_amm_init_price")]
+    pub meteora_amm_init_price: u64,
+
+    #[serde(default = "default_orca_program")]
+    pub orca_program: Pubkey,
+    #[serde(default = "default_orca_whirlpools_config")]
+    pub orca_whirlpools_config: Pubkey,
+    /// Whirlpool is created in the fee tier of this tick spacing.
+    #[serde(default = "default_orca_tick_spacing")]
+    pub orca_tick_spacing: u16,
+
     #[serde(default = "default_memo_program")]
     pub memo_program: Pubkey,
 
+    /// Graduated projects are traded via Jupiter routes, falling back to direct swaps on its failures.
+    #[serde(default)]
+    pub use_jupiter: bool,
+    #[serde(default = "default_jupiter_base_url")]
+    pub jupiter_base_url: String,
+    #[serde(default = "default_jupiter_swap_url")]
+    pub jupiter_swap_url: String,
+    #[serde(default = "default_jupiter_slippage_bps")]
+    pub jupiter_slippage_bps: u16,
+
+    /// Label backend transactions with memos, so operations are told apart in explorers.
+    #[serde(default = "default_attach_memos")]
+    pub attach_memos: bool,
+
     #[serde(default = "default_sols_to_graduate")]
     pub sols_to_graduate: u64,
+    /// Lamports, a single wallet could put into each static pool. Unlimited if unset.
+    #[serde(default)]
+    pub static_pool_max_purchase_per_wallet: Option<u64>,
+    /// Lamports, each static pool must collect by its deadline, otherwise buyers are refunded.
+    #[serde(default)]
+    pub static_pool_min_lamports: Option<u64>,
+    /// DEX, projects created from now on are graduated to.
+    #[serde(default)]
+    pub graduation_target: GraduationTarget,
+    /// Lamports of liquidity, deployed to the DEX of `graduation_target` on graduation.
     #[serde(default = "default_raydium_liquidity")]
     pub raydium_liquidity: u64,
+    /// Closed curve pool with less sols is not graduated to raydium, as the price impact there
+    /// would be terrible. Unlike `min_sol_to_close` of the curve pool config,
+    /// which makes the pool close, this one only holds the closed pool back from raydium.
+    #[serde(default = "default_min_graduation_liquidity_lamports")]
+    pub min_graduation_liquidity_lamports: u64,
+    /// Minimal amount of tokens, deployed to raydium along with the liquidity.
+    #[serde(default)]
+    pub min_token_supply_for_graduation: u64,
+    /// Quotes warn about buys, moving the spot price by more percents.
+    #[serde(default = "default_high_price_impact_pct")]
+    pub high_price_impact_pct: f64,
     #[serde(default = "default_creator_graduate_reward")]
     pub creator_graduate_reward: u64,
+    /// Static pool tokens could be transmuted for this long after graduation.
+    #[serde(default = "default_transmuter_lifetime_after_graduation")]
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub transmuter_lifetime_after_graduation: Duration,
     #[serde(default = "default_pumpfun_init_price")]
     pub pumpfun_init_price: u64,
 
+    #[serde(default = "default_min_dev_purchase_lamports")]
+    pub min_dev_purchase_lamports: u64,
+    #[serde(default = "default_max_dev_purchase_lamports")]
+    pub max_dev_purchase_lamports: u64,
+    /// Creator deposit is never less than this, even if computed rents are lower.
+    #[serde(default)]
+    pub min_creator_deposit_lamports: u64,
+
     #[serde(default = "default_allowed_launch_periods")]
     #[serde_as(as = "Vec<DurationSeconds<u64>>")]
     pub allowed_launch_periods: Vec<Duration>,
 
-    #[serde(default = "default_allowed_lock_periods")]
-    pub allowed_lock_periods: Vec<DevLockPeriod>,
+    #[serde(default = "default_allowed_delivery_methods")]
+    pub allowed_delivery_methods: Vec<DeliveryMethod>,
+
+    /// Checked against the image content on project creation, not against its file name.
+    #[serde(default = "default_allowed_image_formats")]
+    pub allowed_image_formats: Vec<ImageFormat>,
+
+    /// Rejected in token names in addition to the `rustrict` dictionary.
+    #[serde(default)]
+    pub extra_blocked_words: Vec<String>,
+
+    #[serde(default = "default_max_projects_per_owner_per_hour")]
+    pub max_projects_per_owner_per_hour: u32,
+    /// Projects, which are neither graduated nor archived.
+    #[serde(default = "default_max_active_projects_per_owner")]
+    pub max_active_projects_per_owner: u32,
+    /// Verified creators, not limited in projects creation.
+    #[serde(default)]
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub allowed_owners: Vec<Pubkey>,
 }
 
 fn default_allowed_launch_periods() -> Vec<Duration> {
@@ -93,18 +208,22 @@ fn default_allowed_launch_periods() -> Vec<Duration> {
     ]
 }
 
-fn default_allowed_lock_periods() -> Vec<DevLockPeriod> {
+fn default_allowed_image_formats() -> Vec<ImageFormat> {
+    ImageFormat::ALL.to_vec()
+}
+
+fn default_allowed_delivery_methods() -> Vec<DeliveryMethod> {
     let hour = 60 * 60;
     vec![
-        DevLockPeriod::Disabled,
-        DevLockPeriod::Interval {
-            interval: hour * 24,
+        DeliveryMethod::Immediate,
+        DeliveryMethod::VestedCliff {
+            cliff_secs: hour * 24,
         },
-        DevLockPeriod::Interval {
-            interval: hour * 24 * 7,
+        DeliveryMethod::VestedCliff {
+            cliff_secs: hour * 24 * 7,
         },
-        DevLockPeriod::Interval {
-            interval: hour * 24 * 30,
+        DeliveryMethod::VestedCliff {
+            cliff_secs: hour * 24 * 30,
         },
     ]
 }
@@ -113,6 +232,54 @@ fn default_raydium_program() -> Pubkey {
     Pubkey::from_str("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8").unwrap()
 }
 
+fn default_meteora_program() -> Pubkey {
+    Pubkey::from_str("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo").unwrap()
+}
+
+fn default_meteora_bin_step() -> u16 {
+    100
+}
+
+fn default_meteora_base_factor() -> u16 {
+    10000
+}
+
+fn default_meteora_amm_program() -> Pubkey {
+    Pubkey::from_str("Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB").unwrap()
+}
+
+fn default_meteora_vault_program() -> Pubkey {
+    Pubkey::from_str("24Uqj9JCLxUeoC3hGfh5W3s9FM9uCHDS2SG3LYwBpyTi").unwrap()
+}
+
+fn default_meteora_amm_init_price() -> u64 {
+    sol_to_lamports(0.05)
+}
+
+fn default_jupiter_base_url() -> String {
+    "https://quote-api.jup.ag/v6".to_string()
+}
+
+fn default_jupiter_swap_url() -> String {
+    "https://quote-api.jup.ag/v6/swap-instructions".to_string()
+}
+
+fn default_jupiter_slippage_bps() -> u16 {
+    100
+}
+
+fn default_orca_program() -> Pubkey {
+    Pubkey::from_str("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc").unwrap()
+}
+
+fn default_orca_whirlpools_config() -> Pubkey {
+    Pubkey::from_str("2LecshUwdy9xi7meFgHtFJQNSKk4KdTrcpvaB56dP2NQ").unwrap()
+}
+
+fn default_orca_tick_spacing() -> u16 {
+    64
+}
+
 fn default_locker_program() -> Pubkey {
     Pubkey::from_str("LocpQgucEQHbqNABEYvBvwoxCPsSbG91A1QaQhQQqjn").unwrap()
 }
@@ -121,6 +288,10 @@ fn default_serum_openbook_program() -> Pubkey {
     Pubkey::from_str("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX").unwrap()
 }
 
+fn default_attach_memos() -> bool {
+    true
+}
+
 fn default_memo_program() -> Pubkey {
     Pubkey::from_str("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr").unwrap()
 }
@@ -133,6 +304,18 @@ fn default_raydium_liquidity() -> u64 {
     LAMPORTS_PER_SOL * 79
 }
 
+fn default_min_graduation_liquidity_lamports() -> u64 {
+    LAMPORTS_PER_SOL * 10
+}
+
+fn default_high_price_impact_pct() -> f64 {
+    15.0
+}
+
+fn default_transmuter_lifetime_after_graduation() -> Duration {
+    Duration::from_secs(90 * 24 * 60 * 60)
+}
+
 fn default_creator_graduate_reward() -> u64 {
     sol_to_lamports(0.5)
 }
@@ -141,6 +324,52 @@ fn default_pumpfun_init_price() -> u64 {
     sol_to_lamports(0.022)
 }
 
+fn default_min_dev_purchase_lamports() -> u64 {
+    sol_to_lamports(0.01)
+}
+
+fn default_max_dev_purchase_lamports() -> u64 {
+    sol_to_lamports(10.0)
+}
+
+fn default_max_projects_per_owner_per_hour() -> u32 {
+    5
+}
+
+fn default_max_active_projects_per_owner() -> u32 {
+    10
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum GraduationCheckError {
+    #[error("curve pool holds {sol_balance} lamports, at least {min} are required to graduate")]
+    InsufficientLiquidity { sol_balance: u64, min: u64 },
+    #[error("{tokens} tokens would be deployed, at least {min} are required to graduate")]
+    InsufficientTokens { tokens: u64, min: u64 },
+}
+
+impl InstructionsConfig {
+    pub fn check_graduation(
+        &self,
+        sol_balance: u64,
+        tokens_amount: u64,
+    ) -> Result<(), GraduationCheckError> {
+        if sol_balance < self.min_graduation_liquidity_lamports {
+            return Err(GraduationCheckError::InsufficientLiquidity {
+                sol_balance,
+                min: self.min_graduation_liquidity_lamports,
+            });
+        }
+        if tokens_amount < self.min_token_supply_for_graduation {
+            return Err(GraduationCheckError::InsufficientTokens {
+                tokens: tokens_amount,
+                min: self.min_token_supply_for_graduation,
+            });
+        }
+        Ok(())
+    }
+}
+
 const WRAPPED_SOL_MINT: Pubkey = solana_sdk::pubkey!("So11111111111111111111111111111111111111112");
 
 #[derive(Clone)]
@@ -150,6 +379,8 @@ pub struct InstructionsBuilder {
     pub pump_meta: DataReceiver<pumpfun::Meta>,
     pub mzip_meta: DataReceiver<mzip::Meta>,
     pub config: Arc<InstructionsConfig>,
+    pub pumpfun_curves: Arc<pumpfun::LiveCurveCache>,
+    pub jupiter: Arc<jupiter::JupiterClient>,
 }
 
 impl InstructionsBuilder {
@@ -164,10 +395,47 @@ impl InstructionsBuilder {
 
             pump_meta: self.pump_meta.clone(),
             mzip_meta: self.mzip_meta.clone(),
+            pumpfun_curves: &self.pumpfun_curves,
+            jupiter: &self.jupiter,
 
             rent: self.solana_meta.clone().get()?.rent,
         })
     }
+
+    /// Absent until project creation lands on-chain.
+    pub async fn fetch_project_account(&self, id: &ProjectId) -> anyhow::Result<Option<Project>> {
+        let client = self.solana_pool.rpc_client().use_single().await;
+        let commitment = client.commitment();
+        let Some(account) = client
+            .get_account_with_commitment(&project_address(&project_id(id)), commitment)
+            .await?
+            .value
+        else {
+            return Ok(None);
+        };
+        Ok(Some(Project::try_deserialize(
+            &mut account.data.as_slice(),
+        )?))
+    }
+
+    /// Absent until the first trade on the moonzip curve pool.
+    pub async fn fetch_project_stats(
+        &self,
+        id: &ProjectId,
+    ) -> anyhow::Result<Option<ProjectStats>> {
+        let client = self.solana_pool.rpc_client().use_single().await;
+        let commitment = client.commitment();
+        let Some(account) = client
+            .get_account_with_commitment(&project_stats_address(&project_id(id)), commitment)
+            .await?
+            .value
+        else {
+            return Ok(None);
+        };
+        Ok(Some(ProjectStats::try_deserialize(
+            &mut account.data.as_slice(),
+        )?))
+    }
 }
 
 #[derive(Clone)]
@@ -178,6 +446,8 @@ pub struct ProjectsOperations<'a> {
 
     pump_meta: DataReceiver<pumpfun::Meta>,
     mzip_meta: DataReceiver<mzip::Meta>,
+    pumpfun_curves: &'a pumpfun::LiveCurveCache,
+    jupiter: &'a jupiter::JupiterClient,
 
     rent: Rent,
 }
@@ -192,18 +462,52 @@ impl<'a> ProjectsOperations<'a> {
 
         let project_id = project_id(&self.project_state.project.id);
         let project_address = project_address(&project_id);
+        let creator_deposit = self.creator_deposit(&metadata)?;
+
+        let ix = program
+            .request()
+            .accounts(moonzip::accounts::CreateProjectAccounts {
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+                event_authority: *MOONZIP_EVENT_AUTHORITY,
+                creator: self.project_state.project.owner.clone().into(),
+                project: project_address,
+                global: *mzip::GLOBAL_ACCOUNT,
+                fee: *FEE_ACCOUNT,
+                system_program: solana_sdk::system_program::ID,
+                program: moonzip::ID,
+            })
+            .args(moonzip::instruction::CreateProject {
+                data: CreateProjectData {
+                    id: project_id,
+                    schema: self.project_state.project.deploy_schema.to_project_schema(),
+                    creator_deposit,
+                },
+            })
+            .instructions()?;
+
+        Ok(self
+            .memo("create_project")
+            .map(|memo| spl_memo::build_memo(memo.as_bytes(), &[]))
+            .into_iter()
+            .chain(ix)
+            .collect())
+    }
+
+    /// Label of the project's operation, `None` if memos are disabled.
+    pub fn memo(&self, operation: &str) -> Option<String> {
+        self.config
+            .attach_memos
+            .then(|| format!("moonzip:{operation}:{}", self.project_state.project.id))
+    }
 
+    /// Lamports, which creator transfers to the authority to cover project deployment,
+    /// and the project creation fee on top.
+    pub fn creator_deposit(&self, metadata: &SampleMetadata) -> anyhow::Result<u64> {
         // will certainly need for main token
         let mut creator_deposit = 0;
 
-        // if static pool, will need for pool mint and static pool itself
-        if self
-            .project_state
-            .project
-            .deploy_schema
-            .static_pool
-            .is_some()
-        {
+        // for each static pool, will need for pool mint and static pool itself
+        for _ in &self.project_state.project.deploy_schema.static_pools {
             creator_deposit += self.rent.minimum_balance(StaticPool::ACCOUNT_SIZE);
             creator_deposit += self.rent.minimum_balance(Transmuter::ACCOUNT_SIZE);
             creator_deposit += self.rent.minimum_balance(spl_token::state::Account::LEN) * 2;
@@ -220,6 +524,14 @@ impl<'a> ProjectsOperations<'a> {
             CurveVariant::Pumpfun => {
                 creator_deposit += self.config.pumpfun_init_price;
             }
+            // creator seeds the pool with the liquidity, the curve would have collected
+            CurveVariant::Meteora => {
+                creator_deposit += self.rent.minimum_balance(spl_token::state::Mint::LEN);
+                creator_deposit += self.rent.minimum_balance(spl_token::state::Account::LEN);
+                creator_deposit += metadata.estimate_price(&self.rent)?;
+                creator_deposit += self.config.meteora_amm_init_price;
+                creator_deposit += self.config.sols_to_graduate;
+            }
         }
 
         if let Some(purchase) = self
@@ -232,31 +544,22 @@ impl<'a> ProjectsOperations<'a> {
             creator_deposit += u64::try_from(purchase.amount)?;
         }
 
-        let ix = program
-            .request()
-            .accounts(moonzip::accounts::CreateProjectAccounts {
-                authority: moonzip::PROGRAM_AUTHORITY,
-                event_authority: *MOONZIP_EVENT_AUTHORITY,
-                creator: self.project_state.project.owner.clone().into(),
-                project: project_address,
-                system_program: solana_sdk::system_program::ID,
-                program: moonzip::ID,
-            })
-            .args(moonzip::instruction::CreateProject {
-                data: CreateProjectData {
-                    id: project_id,
-                    schema: self.project_state.project.deploy_schema.to_project_schema(),
-                    creator_deposit,
-                },
-            })
-            .instructions()?;
-
-        Ok(ix)
+        let creation_fee = self
+            .mzip_meta
+            .clone()
+            .get()?
+            .global_account
+            .config
+            .project_creation_fee_lamports;
+        Ok(creator_deposit.max(self.config.min_creator_deposit_lamports) + creation_fee)
     }
 
+    /// Pool must be already assigned its deadline,
+    /// see [`super::storage::project::StoredProject::open_static_pool`].
     pub fn init_static_pool(
         &mut self,
         static_pool_mint: &Keypair,
+        static_pool: &StoredStaticPoolConfig,
     ) -> anyhow::Result<Vec<Instruction>> {
         let client = self.solana_pool.builder();
         let program = client.program(moonzip::ID)?;
@@ -269,19 +572,18 @@ impl<'a> ProjectsOperations<'a> {
         let pool_mint_account =
             get_associated_token_address(&pool_address, &static_pool_mint.pubkey());
 
-        let static_pool = self
-            .project_state
-            .project
-            .deploy_schema
-            .static_pool
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("invariant: static pool config missing"))?;
         let finish_ts = static_pool.launch_ts;
+        let max_lamports = static_pool
+            .max_lamports
+            .clone()
+            .map(u64::try_from)
+            .transpose()?
+            .unwrap_or(self.config.sols_to_graduate);
 
         let ix = program
             .request()
             .accounts(moonzip::accounts::CreateStaticPoolAccounts {
-                authority: moonzip::PROGRAM_AUTHORITY,
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
                 event_authority: *MOONZIP_EVENT_AUTHORITY,
                 project: project_address,
                 mint: static_pool_mint.pubkey(),
@@ -300,8 +602,11 @@ impl<'a> ProjectsOperations<'a> {
                         min_purchase_lamports: None,
                         close_conditions: PoolCloseConditions {
                             finish_ts: Some(finish_ts as u64),
-                            max_lamports: Some(self.config.sols_to_graduate),
+                            max_lamports: Some(max_lamports),
+                            grace_period_secs: None,
+                            min_lamports: self.config.static_pool_min_lamports,
                         },
+                        max_purchase_per_wallet: self.config.static_pool_max_purchase_per_wallet,
                     },
                 },
             })
@@ -317,7 +622,7 @@ impl<'a> ProjectsOperations<'a> {
         let ix = program
             .request()
             .accounts(moonzip::accounts::GraduateStaticPoolAccounts {
-                authority: moonzip::PROGRAM_AUTHORITY,
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
                 event_authority: *MOONZIP_EVENT_AUTHORITY,
                 funds_receiver: moonzip::PROGRAM_AUTHORITY,
                 project: project_address(&project_id(&self.project_state.project.id)),
@@ -334,6 +639,101 @@ impl<'a> ProjectsOperations<'a> {
         Ok(ix)
     }
 
+    pub fn extend_static_pool_deadline(
+        &self,
+        new_finish_ts: u64,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        let project_id = project_id(&self.project_state.project.id);
+        let ix = program
+            .request()
+            .accounts(moonzip::accounts::ExtendStaticPoolDeadlineAccounts {
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+                event_authority: *MOONZIP_EVENT_AUTHORITY,
+                project: project_address(&project_id),
+                pool: self.static_pool_address()?,
+                global: *mzip::GLOBAL_ACCOUNT,
+                program: moonzip::ID,
+            })
+            .args(moonzip::instruction::ExtendStaticPoolDeadline {
+                data: ExtendStaticPoolDeadlineData {
+                    project_id,
+                    new_finish_ts,
+                },
+            })
+            .instructions()?;
+
+        Ok(ix)
+    }
+
+    /// Wallets, which bought from the static pool of `pool_mint` and still keep their records.
+    pub async fn fetch_wallet_records(&self, pool_mint: Pubkey) -> anyhow::Result<Vec<Pubkey>> {
+        let pool = static_pool_address(pool_mint);
+        let project_id = project_id(&self.project_state.project.id);
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    0,
+                    StaticPoolWalletRecord::DISCRIMINATOR.to_vec(),
+                )),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(8, project_id.to_bytes().to_vec())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let accounts = self
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .get_program_accounts_with_config(&moonzip::ID, config)
+            .await?;
+
+        let mut wallets = Vec::with_capacity(accounts.len());
+        for (address, account) in accounts {
+            let record = StaticPoolWalletRecord::try_deserialize(&mut account.data.as_slice())?;
+            // records of the other static pool of the same project are skipped
+            if address == wallet_record_address(pool, record.wallet) {
+                wallets.push(record.wallet);
+            }
+        }
+        Ok(wallets)
+    }
+
+    /// Pool of `pool_mint` must be already graduated.
+    pub fn close_wallet_records(
+        &self,
+        pool_mint: Pubkey,
+        wallets: &[Pubkey],
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+        let pool = static_pool_address(pool_mint);
+
+        let mut instructions = Vec::with_capacity(wallets.len());
+        for wallet in wallets {
+            instructions.append(
+                &mut program
+                    .request()
+                    .accounts(moonzip::accounts::CloseStaticPoolWalletRecordAccounts {
+                        authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+                        mint: pool_mint,
+                        pool,
+                        wallet_record: wallet_record_address(pool, *wallet),
+                        wallet: *wallet,
+                    })
+                    .args(moonzip::instruction::CloseStaticPoolWalletRecord {})
+                    .instructions()?,
+            );
+        }
+        Ok(instructions)
+    }
+
     pub fn reward_creator_on_graduate(&self) -> anyhow::Result<Vec<Instruction>> {
         Ok(vec![system_instruction::transfer(
             &PROGRAM_AUTHORITY,
@@ -342,21 +742,11 @@ impl<'a> ProjectsOperations<'a> {
         )])
     }
 
-    pub fn init_transmuter(&mut self) -> anyhow::Result<Vec<Instruction>> {
-        if self
-            .project_state
-            .project
-            .deploy_schema
-            .static_pool
-            .is_none()
-        {
-            return Ok(vec![]);
-        }
-        let static_pool_mint = self
-            .project_state
-            .project
-            .static_pool_mint()
-            .ok_or_else(|| anyhow::anyhow!("invariant: static pool mint is not already stored"))?;
+    /// Transmuter takes all curve tokens, held by the authority.
+    pub fn init_transmuter(
+        &mut self,
+        static_pool_mint: Pubkey,
+    ) -> anyhow::Result<Vec<Instruction>> {
         let curve_mint = self.curve_mint()?;
         Ok(match self.project_state.project.deploy_schema.curve_pool {
             CurveVariant::Moonzip => self.add_transmuter_for_moonzip(TransmuterInitArgs {
@@ -369,9 +759,37 @@ impl<'a> ProjectsOperations<'a> {
                 to_mint: curve_mint,
                 donor: PROGRAM_AUTHORITY,
             })?,
+            CurveVariant::Meteora => bail!("meteora launch has no static pools"),
         })
     }
 
+    /// Buys curve tokens for the authority with lamports, collected by one of later static pools,
+    /// so they could be given out by the transmuter of that pool.
+    pub fn buy_for_static_pool(&mut self, sols: u64) -> anyhow::Result<Vec<Instruction>> {
+        let curve_mint = self.curve_mint()?;
+        let params = BuyParams {
+            user: PROGRAM_AUTHORITY,
+            sols,
+            min_token_output: 0,
+        };
+        // account is closed by the previous transmuter
+        let mut ixs = vec![create_associated_token_account_idempotent(
+            &PROGRAM_AUTHORITY,
+            &PROGRAM_AUTHORITY,
+            &curve_mint,
+            &anchor_spl::token::ID,
+        )];
+        match self.project_state.project.deploy_schema.curve_pool {
+            CurveVariant::Moonzip => {
+                let creator_fee_wallet = self.new_pool_creator_fee_wallet()?;
+                ixs.append(&mut self.buy_from_curve_pool(params, creator_fee_wallet)?)
+            }
+            CurveVariant::Pumpfun => ixs.append(&mut self.buy_from_pumpfun(params)?),
+            CurveVariant::Meteora => bail!("meteora launch has no static pools"),
+        }
+        Ok(ixs)
+    }
+
     pub fn add_transmuter_for_moonzip(
         &mut self,
         args: TransmuterInitArgs,
@@ -384,7 +802,9 @@ impl<'a> ProjectsOperations<'a> {
         let ix = program
             .request()
             .accounts(moonzip::accounts::InitTransmuterForCurveAccounts { base, curved_pool })
-            .args(moonzip::instruction::InitTransmuterForCurve {})
+            .args(moonzip::instruction::InitTransmuterForCurve {
+                data: Self::transmuter_init_data(),
+            })
             .instructions()?;
 
         Ok(ix)
@@ -404,12 +824,64 @@ impl<'a> ProjectsOperations<'a> {
                 base: self.base_transmuter_init_accounts(&args),
                 bonding_curve,
             })
-            .args(moonzip::instruction::InitTransmuterForPumpfunCurve {})
+            .args(moonzip::instruction::InitTransmuterForPumpfunCurve {
+                data: Self::transmuter_init_data(),
+            })
             .instructions()?;
 
         Ok(ix)
     }
 
+    /// Transmuter is initialized along with the curve pool, expiry is set on graduation,
+    /// see [`Self::expire_transmuters`].
+    fn transmuter_init_data() -> InitTransmuterData {
+        InitTransmuterData { expiry_ts: None }
+    }
+
+    /// Sets expiry of transmuters, still serving static pool tokens of the project.
+    pub async fn expire_transmuters(&self, expiry_ts: u64) -> anyhow::Result<Vec<Instruction>> {
+        let curve_mint = self.curve_mint()?;
+        let transmuters: Vec<_> = self
+            .project_state
+            .project
+            .static_pool_mints()
+            .map(|static_pool_mint| get_transmuter_address(static_pool_mint, curve_mint))
+            .collect();
+        if transmuters.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let client = self.solana_pool.rpc_client().use_single().await;
+        let accounts = client
+            .get_multiple_accounts_with_commitment(&transmuters, client.commitment())
+            .await?
+            .value;
+        let program = self.solana_pool.builder().program(moonzip::ID)?;
+        let mut ixs = vec![];
+        // transmuter is closed once all of its tokens are claimed
+        for (transmuter, _) in transmuters
+            .into_iter()
+            .zip(accounts)
+            .filter(|(_, account)| account.is_some())
+        {
+            ixs.append(
+                &mut program
+                    .request()
+                    .accounts(moonzip::accounts::SetTransmuterExpiryAccounts {
+                        authority: program_authority_accounts(PROGRAM_AUTHORITY),
+                        transmuter,
+                    })
+                    .args(moonzip::instruction::SetTransmuterExpiry {
+                        data: SetTransmuterExpiryData {
+                            expiry_ts: Some(expiry_ts),
+                        },
+                    })
+                    .instructions()?,
+            );
+        }
+        Ok(ixs)
+    }
+
     fn base_transmuter_init_accounts(
         &self,
         args: &TransmuterInitArgs,
@@ -417,7 +889,7 @@ impl<'a> ProjectsOperations<'a> {
         let transmuter = get_transmuter_address(args.from_mint, args.to_mint);
 
         BaseInitTransmuterAccounts {
-            authority: moonzip::PROGRAM_AUTHORITY,
+            authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
             from_mint: args.from_mint,
             to_mint: args.to_mint,
             donor_to_mint_account: get_associated_token_address(&args.donor, &args.to_mint),
@@ -529,7 +1001,7 @@ impl<'a> ProjectsOperations<'a> {
             .request()
             .accounts(moonzip::accounts::GraduateProjectAccounts {
                 event_authority: *MOONZIP_EVENT_AUTHORITY,
-                authority: moonzip::PROGRAM_AUTHORITY,
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
                 project,
                 program: moonzip::ID,
             })
@@ -549,19 +1021,24 @@ impl<'a> ProjectsOperations<'a> {
         let pool_address = get_curved_pool_address(action.mint);
         let project = self.get_project_address();
         let pool_token_account = get_associated_token_address(&pool_address, &action.mint);
+        let creator_fee_wallet = self.new_pool_creator_fee_wallet()?;
 
         let buy = |user: Pubkey, sols: u64| {
             Result::<_, anyhow::Error>::Ok(
                 program
                     .request()
                     .accounts(moonzip::accounts::BuyFromCurvedPoolAccounts {
-                        authority: moonzip::PROGRAM_AUTHORITY,
+                        authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
                         fee: fee_address(),
                         project,
                         mint: action.mint,
 
                         pool_token_account,
                         pool: pool_address,
+                        creator_fee_wallet,
+                        project_stats: project_stats_address(&project_id(
+                            &self.project_state.project.id,
+                        )),
 
                         user_token_account: get_associated_token_address(&user, &action.mint),
                         user,
@@ -583,29 +1060,68 @@ impl<'a> ProjectsOperations<'a> {
             )
         };
 
-        let mut ix = program
-            .request()
-            .accounts(moonzip::accounts::CreateCurvedPoolAccounts {
-                authority: moonzip::PROGRAM_AUTHORITY,
-                project,
-
-                global: *mzip::GLOBAL_ACCOUNT,
-                mint: action.mint,
-                pool_token_account,
-                pool: pool_address,
-
-                event_authority: *MOONZIP_EVENT_AUTHORITY,
-                program: moonzip::ID,
-                system_program: solana_sdk::system_program::ID,
-                token_program: anchor_spl::token::ID,
-                associated_token_program: anchor_spl::associated_token::ID,
-            })
-            .args(moonzip::instruction::CreateCurvedPool {
-                data: CreateCurvedPoolData {
-                    project_id: project_id(&self.project_state.project.id),
-                },
-            })
-            .instructions()?;
+        let token_decimals = self
+            .project_state
+            .project
+            .deploy_schema
+            .token_decimals
+            .map(u8::try_from)
+            .transpose()?;
+        let global_decimals = self
+            .mzip_meta
+            .clone()
+            .get()?
+            .global_account
+            .config
+            .token_decimals;
+        // the pool takes a mint, initialized beforehand, and mints the whole supply itself
+        let mut ix = vec![
+            system_instruction::create_account(
+                &PROGRAM_AUTHORITY,
+                &action.mint,
+                self.rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &action.mint,
+                &PROGRAM_AUTHORITY,
+                Some(&PROGRAM_AUTHORITY),
+                token_decimals.unwrap_or(global_decimals),
+            )?,
+        ];
+        ix.append(
+            &mut program
+                .request()
+                .accounts(moonzip::accounts::CreateCurvedPoolAccounts {
+                    authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+                    project,
+
+                    global: *mzip::GLOBAL_ACCOUNT,
+                    mint: action.mint,
+                    pool_token_account,
+                    pool: pool_address,
+                    creator_fee_wallet,
+
+                    event_authority: *MOONZIP_EVENT_AUTHORITY,
+                    program: moonzip::ID,
+                    system_program: solana_sdk::system_program::ID,
+                    token_program: anchor_spl::token::ID,
+                    associated_token_program: anchor_spl::associated_token::ID,
+                })
+                .args(moonzip::instruction::CreateCurvedPool {
+                    data: CreateCurvedPoolData {
+                        project_id: project_id(&self.project_state.project.id),
+                        token_decimals_override: token_decimals,
+                        curve_kind_override: None,
+                        max_wallet_tokens_override: None,
+                        anti_snipe_slots_override: None,
+                        creator_fee_share_bps_override: None,
+                    },
+                })
+                .instructions()?,
+        );
 
         if let Some(purchase) = action.dev_purchase {
             let sols = purchase.sols;
@@ -617,31 +1133,15 @@ impl<'a> ProjectsOperations<'a> {
             ix.append(&mut buy(PROGRAM_AUTHORITY, sols)?)
         };
 
-        ix.push(
-            CreateV1Builder::new()
-                .metadata(mpl::metadata_account(action.mint))
-                .mint(action.mint, true)
-                .authority(moonzip::PROGRAM_AUTHORITY)
-                .payer(moonzip::PROGRAM_AUTHORITY)
-                .update_authority(moonzip::PROGRAM_AUTHORITY, true)
-                .is_mutable(false)
-                .primary_sale_happened(false)
-                .name(action.metadata.name.clone())
-                .uri(action.metadata.deployed_url()?)
-                .seller_fee_basis_points(0)
-                .token_standard(mpl_token_metadata::types::TokenStandard::Fungible)
-                .instruction(),
-        );
+        // updatable by owners until graduation, see `update_project_metadata`
+        ix.push(create_token_metadata(&action, true)?);
 
         Ok(ix)
     }
 
     pub fn graduate_curve_pool(&self) -> anyhow::Result<Vec<Instruction>> {
-        if matches!(
-            self.project_state.project.deploy_schema.curve_pool,
-            CurveVariant::Pumpfun
-        ) {
-            bail!("pumpfun curve pools could not be graduated");
+        if self.project_state.project.deploy_schema.curve_pool != CurveVariant::Moonzip {
+            bail!("only moonzip curve pools could be graduated");
         }
 
         let client = self.solana_pool.builder();
@@ -650,12 +1150,15 @@ impl<'a> ProjectsOperations<'a> {
         let pool_address = curved_pool_address(curve_mint);
         let project_id = project_id(&self.project_state.project.id);
 
-        let ix = program
+        // the pool, binding metadata to the project, is closed by the graduation
+        let mut ixs = self.lock_project_metadata()?;
+        let mut ix = program
             .request()
             .accounts(moonzip::accounts::GraduateCurvedPoolAccounts {
                 event_authority: *MOONZIP_EVENT_AUTHORITY,
 
-                authority: moonzip::PROGRAM_AUTHORITY,
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+
                 project: project_address(&project_id),
                 fee: fee_address(),
                 mint: curve_mint,
@@ -678,12 +1181,167 @@ impl<'a> ProjectsOperations<'a> {
                 _data: GraduateCurvedPoolData { project_id },
             })
             .instructions()?;
+        ixs.append(&mut ix);
 
-        Ok(ix)
+        Ok(ixs)
     }
 
-    pub fn lock_dev(&self) -> anyhow::Result<Vec<Instruction>> {
-        self._lock_dev(self.dev_tokens_amount()?)
+    /// Incident response: stops trading on the moonzip curve pool.
+    pub fn pause_curve_pool(&self) -> anyhow::Result<Vec<Instruction>> {
+        let accounts = self.pause_curve_pool_accounts()?;
+        let client = self.solana_pool.builder();
+        Ok(client
+            .program(moonzip::ID)?
+            .request()
+            .accounts(accounts)
+            .args(moonzip::instruction::PauseCurvedPool {})
+            .instructions()?)
+    }
+
+    pub fn resume_curve_pool(&self) -> anyhow::Result<Vec<Instruction>> {
+        let accounts = self.pause_curve_pool_accounts()?;
+        let client = self.solana_pool.builder();
+        Ok(client
+            .program(moonzip::ID)?
+            .request()
+            .accounts(accounts)
+            .args(moonzip::instruction::ResumeCurvedPool {})
+            .instructions()?)
+    }
+
+    fn pause_curve_pool_accounts(
+        &self,
+    ) -> anyhow::Result<moonzip::accounts::PauseCurvedPoolAccounts> {
+        if self.project_state.project.deploy_schema.curve_pool != CurveVariant::Moonzip {
+            bail!("only moonzip curve pools could be paused");
+        }
+        let curve_mint = self.curve_mint()?;
+        Ok(moonzip::accounts::PauseCurvedPoolAccounts {
+            authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+            mint: curve_mint,
+            pool: curved_pool_address(curve_mint),
+        })
+    }
+
+    /// Replaces config of the live moonzip curve pool, its curve is kept as is.
+    pub fn update_curve_pool_config(
+        &self,
+        new_config: CurvedPoolConfig,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        if self.project_state.project.deploy_schema.curve_pool != CurveVariant::Moonzip {
+            bail!("only moonzip curve pools could be reconfigured");
+        }
+        let curve_mint = self.curve_mint()?;
+        let client = self.solana_pool.builder();
+        Ok(client
+            .program(moonzip::ID)?
+            .request()
+            .accounts(moonzip::accounts::UpdateCurvedPoolConfigAccounts {
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+                mint: curve_mint,
+                pool: curved_pool_address(curve_mint),
+            })
+            .args(moonzip::instruction::UpdateCurvedPoolConfig { new_config })
+            .instructions()?)
+    }
+
+    pub fn revoke_mint_authority(&self) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+        let curve_mint = self.curve_mint()?;
+
+        let ix = program
+            .request()
+            .accounts(moonzip::accounts::RevokeMintAuthorityAccounts {
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+                mint: curve_mint,
+                pool: curved_pool_address(curve_mint),
+                project: self.get_project_address(),
+                token_program: anchor_spl::token::ID,
+            })
+            .args(moonzip::instruction::RevokeMintAuthority {})
+            .instructions()?;
+
+        Ok(ix)
+    }
+
+    /// Points the curve mint metadata to the newly uploaded one,
+    /// allowed by the program only while the curve pool is active.
+    pub fn update_project_metadata(
+        &self,
+        metadata: &StoredTokenMeta,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        Ok(program
+            .request()
+            .accounts(self.project_metadata_accounts()?)
+            .args(moonzip::instruction::UpdateProjectMetadata {
+                data: UpdateProjectMetadataData {
+                    project_id: project_id(&self.project_state.project.id),
+                    uri: metadata.deployed_url()?,
+                },
+            })
+            .instructions()?)
+    }
+
+    /// Should precede the graduation of the closed curve pool, so metadata is never changed afterwards.
+    fn lock_project_metadata(&self) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        Ok(program
+            .request()
+            .accounts(self.project_metadata_accounts()?)
+            .args(moonzip::instruction::LockProjectMetadata {
+                _data: LockProjectMetadataData {
+                    project_id: project_id(&self.project_state.project.id),
+                },
+            })
+            .instructions()?)
+    }
+
+    fn project_metadata_accounts(
+        &self,
+    ) -> anyhow::Result<moonzip::accounts::UpdateProjectMetadataAccounts> {
+        let curve_mint = self.curve_mint()?;
+        Ok(moonzip::accounts::UpdateProjectMetadataAccounts {
+            authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+            project: self.get_project_address(),
+            pool: curved_pool_address(curve_mint),
+            metadata: mpl::metadata_account(curve_mint),
+            metadata_authority: metadata_authority_address(),
+            token_metadata_program: *mpl::PROGRAM,
+        })
+    }
+
+    /// Should precede the graduation of the curve pool, as the pool is closed by it.
+    pub fn revoke_freeze_authority(&self) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+        let project_id = project_id(&self.project_state.project.id);
+        let curve_mint = self.curve_mint()?;
+
+        let ix = program
+            .request()
+            .accounts(moonzip::accounts::RevokeFreezeAuthorityAccounts {
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+                project: project_address(&project_id),
+                mint: curve_mint,
+                pool: curved_pool_address(curve_mint),
+                token_program: anchor_spl::token::ID,
+            })
+            .args(moonzip::instruction::RevokeFreezeAuthority {
+                _data: RevokeFreezeAuthorityData { project_id },
+            })
+            .instructions()?;
+
+        Ok(ix)
+    }
+
+    pub fn lock_dev(&self) -> anyhow::Result<Vec<Instruction>> {
+        self._lock_dev(self.dev_tokens_amount()?)
     }
 
     fn _lock_dev(&self, tokens: u64) -> anyhow::Result<Vec<Instruction>> {
@@ -692,19 +1350,16 @@ impl<'a> ProjectsOperations<'a> {
         let sender_ata = get_associated_token_address(&sender, &curve_mint);
         let owner = self.project_state.project.owner.to_pubkey();
 
-        let Some(period) = self
-            .project_state
-            .project
-            .deploy_schema
-            .dev_purchase
-            .as_ref()
-            .map(|purchase| Duration::from_secs(purchase.lock_period as u64))
-        else {
-            bail!("invariant: dev purchase is not enabled for project")
-        };
-        if period.is_zero() {
-            bail!("zero period must be delivered immediately, without locking");
-        }
+        let delivery = self.dev_delivery()?;
+        let params = lock::vesting_params(&delivery, tokens, TZ::now().timestamp() as u64)?;
+        tracing::debug!(
+            "would unlock {} of {tokens} at {}, then {} per {} periods by {:?}",
+            params.cliff_unlock_amount,
+            params.cliff_time,
+            params.amount_per_period,
+            params.number_of_period,
+            delivery
+        );
 
         let client = self.solana_pool.builder();
         let program_id = self.config.locker_program;
@@ -721,11 +1376,6 @@ impl<'a> ProjectsOperations<'a> {
         let escrow_addr = lock::escrow_address(&base.pubkey(), &self.config.locker_program);
         let escrow_ata = get_associated_token_address(&escrow_addr, &curve_mint);
 
-        let cliff_time = (TZ::now() + period).timestamp() as u64;
-        tracing::debug!("would unlock {tokens} after {cliff_time}");
-
-        let frequency = 1;
-
         let mut ixs = vec![create_associated_token_account(
             &sender,
             &escrow_addr,
@@ -749,16 +1399,7 @@ impl<'a> ProjectsOperations<'a> {
                 token_program: anchor_spl::token::ID,
             })
             .args(locker::instruction::CreateVestingEscrowV2 {
-                params: locker::CreateVestingEscrowParameters {
-                    vesting_start_time: cliff_time,
-                    cliff_time,
-                    frequency,
-                    cliff_unlock_amount: 0,
-                    amount_per_period: tokens,
-                    number_of_period: 1,
-                    update_recipient_mode: 0,
-                    cancel_mode: 0,
-                },
+                params,
                 remaining_accounts_info: None,
             })
             .instructions()?;
@@ -766,23 +1407,28 @@ impl<'a> ProjectsOperations<'a> {
         Ok(ixs)
     }
 
-    pub fn claim_dev_lock(&self) -> anyhow::Result<Vec<Instruction>> {
-        let curve_mint = self.curve_mint()?;
-        let owner = self.project_state.project.owner.to_pubkey();
-
-        let Some(period) = self
+    /// Vested delivery of the dev purchase, the only one requiring the locker.
+    fn dev_delivery(&self) -> anyhow::Result<DeliveryMethod> {
+        let Some(delivery) = self
             .project_state
             .project
             .deploy_schema
             .dev_purchase
             .as_ref()
-            .map(|purchase| Duration::from_secs(purchase.lock_period as u64))
+            .map(StoredDevPurchase::delivery)
         else {
             bail!("invariant: dev purchase is not enabled for project")
         };
-        if period.is_zero() {
-            bail!("zero period must be delivered immediately, without locking");
+        if !delivery.is_vested() {
+            bail!("immediate delivery must be done without locking");
         }
+        Ok(delivery)
+    }
+
+    pub fn claim_dev_lock(&self) -> anyhow::Result<Vec<Instruction>> {
+        let curve_mint = self.curve_mint()?;
+        let owner = self.project_state.project.owner.to_pubkey();
+        self.dev_delivery()?;
 
         let client = self.solana_pool.builder();
         let program_id = self.config.locker_program;
@@ -829,6 +1475,111 @@ impl<'a> ProjectsOperations<'a> {
         Ok(ixs)
     }
 
+    pub fn propose_transfer_ownership(
+        &self,
+        new_owner: Pubkey,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        let project = self.get_project_address();
+        let ix = program
+            .request()
+            .accounts(moonzip::accounts::ProposeTransferOwnershipAccounts {
+                owner: self.project_state.project.owner.to_pubkey(),
+                project,
+                proposal: ownership_transfer_address(&project),
+                system_program: solana_sdk::system_program::ID,
+            })
+            .args(moonzip::instruction::ProposeTransferOwnership {
+                data: ProposeTransferOwnershipData { new_owner },
+            })
+            .instructions()?;
+
+        Ok(ix)
+    }
+
+    pub fn accept_transfer_ownership(&self, new_owner: Pubkey) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        let project = self.get_project_address();
+        let ix = program
+            .request()
+            .accounts(moonzip::accounts::AcceptTransferOwnershipAccounts {
+                new_owner,
+                project,
+                previous_owner: self.project_state.project.owner.to_pubkey(),
+                proposal: ownership_transfer_address(&project),
+                event_authority: *MOONZIP_EVENT_AUTHORITY,
+                program: moonzip::ID,
+            })
+            .args(moonzip::instruction::AcceptTransferOwnership {})
+            .instructions()?;
+
+        Ok(ix)
+    }
+
+    pub fn cancel_transfer_ownership(&self) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        let project = self.get_project_address();
+        let ix = program
+            .request()
+            .accounts(moonzip::accounts::CancelTransferOwnershipAccounts {
+                owner: self.project_state.project.owner.to_pubkey(),
+                project,
+                proposal: ownership_transfer_address(&project),
+            })
+            .args(moonzip::instruction::CancelTransferOwnership {})
+            .instructions()?;
+
+        Ok(ix)
+    }
+
+    pub fn claim_creator_fees(&self) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        let project = self.get_project_address();
+        let ix = program
+            .request()
+            .accounts(moonzip::accounts::ClaimCreatorFeesAccounts {
+                creator: self.project_state.project.owner.to_pubkey(),
+                project,
+                creator_fee_wallet: creator_fee_wallet_address(project),
+                system_program: solana_sdk::system_program::ID,
+            })
+            .args(moonzip::instruction::ClaimCreatorFees {})
+            .instructions()?;
+
+        Ok(ix)
+    }
+
+    /// Must be signed by the authority as well, as it gives the deployment deposit back.
+    pub fn cancel_project(&self) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        let project = self.get_project_address();
+        let ix = program
+            .request()
+            .accounts(moonzip::accounts::CancelProjectAccounts {
+                authority: program_authority_accounts(PROGRAM_AUTHORITY),
+                creator: self.project_state.project.owner.to_pubkey(),
+                project,
+                proposal: ownership_transfer_address(&project),
+                system_program: solana_sdk::system_program::ID,
+                event_authority: *MOONZIP_EVENT_AUTHORITY,
+                program: moonzip::ID,
+            })
+            .args(moonzip::instruction::CancelProject {})
+            .instructions()?;
+
+        Ok(ix)
+    }
+
     pub fn deliver_dev_tokens(&self) -> anyhow::Result<Vec<Instruction>> {
         let curve_mint = self.curve_mint()?;
         let sender = PROGRAM_AUTHORITY;
@@ -872,15 +1623,19 @@ impl<'a> ProjectsOperations<'a> {
                     .fixed_sols(sols)
                     .tokens
             }
+            CurveVariant::Meteora => bail!("meteora launch has no dev purchase"),
             CurveVariant::Moonzip => {
                 let meta = self.mzip_meta.clone().get()?;
-                let initial = moonzip::curved_pool::curve::CurveState::from_cfg(
+                let mut curve = moonzip::curved_pool::curve::CurveState::from_cfg(
                     &meta.global_account.config.curve,
                 );
-                let result = moonzip::curved_pool::curve::BuyCalculator::new(&initial)
-                    .with_fee(meta.fee_account.config.on_buy)
-                    .fixed_sols(sols);
-                result
+                // pool may have been traded before the dev purchase is delivered
+                if let Some(live) = &self.project_state.curved_pool_state {
+                    curve = live.apply_to(curve)?;
+                }
+                moonzip::curved_pool::curve::BuyCalculator::new(&curve)
+                    .with_fee(meta.fee_account.on_buy(chrono::Utc::now().timestamp()))
+                    .fixed_sols(sols)
             }
         };
         Ok(tokens)
@@ -893,7 +1648,7 @@ impl<'a> ProjectsOperations<'a> {
         let ix = program
             .request()
             .accounts(moonzip::accounts::ProjectLockLatchAccounts {
-                authority: moonzip::PROGRAM_AUTHORITY,
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
                 project: self.get_project_address(),
             })
             .args(moonzip::instruction::ProjectLockLatch {})
@@ -912,7 +1667,7 @@ impl<'a> ProjectsOperations<'a> {
         let ix = program
             .request()
             .accounts(moonzip::accounts::ProjectUnlockLatchAccounts {
-                authority: moonzip::PROGRAM_AUTHORITY,
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
                 project: project_address,
             })
             .args(moonzip::instruction::ProjectUnlockLatch {})
@@ -921,7 +1676,52 @@ impl<'a> ProjectsOperations<'a> {
         Ok(ix)
     }
 
-    pub fn buy(
+    /// Fails on-chain, unless the latch is locked for longer than `max_age`.
+    pub fn force_unlock_stale_latch(&self, max_age: Duration) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        let ix = program
+            .request()
+            .accounts(moonzip::accounts::ForceUnlockStaleLatchAccounts {
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+                project: self.get_project_address(),
+            })
+            .args(moonzip::instruction::ForceUnlockStaleLatch {
+                data: ForceUnlockStaleLatchData {
+                    max_age_secs: max_age.as_secs(),
+                },
+            })
+            .instructions()?;
+
+        Ok(ix)
+    }
+
+    /// Rewrites project account of the legacy layout, creator is taken from the stored owner.
+    /// Migrated latch has no lock time, so if it was left locked, it's stale right away.
+    pub fn migrate_project(&self) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        let ix = program
+            .request()
+            .accounts(moonzip::accounts::MigrateProjectAccounts {
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+                project: self.get_project_address(),
+                system_program: solana_sdk::system_program::ID,
+            })
+            .args(moonzip::instruction::MigrateProject {
+                data: MigrateProjectData {
+                    id: project_id(&self.project_state.project.id),
+                    creator: self.project_state.project.owner.to_pubkey(),
+                },
+            })
+            .instructions()?;
+
+        Ok(ix)
+    }
+
+    pub async fn buy(
         &mut self,
         user: Pubkey,
         sols: u64,
@@ -929,31 +1729,34 @@ impl<'a> ProjectsOperations<'a> {
     ) -> anyhow::Result<Vec<Instruction>> {
         let mut ixs = vec![];
 
-        let has_static_pool = self
-            .project_state
-            .project
-            .deploy_schema
-            .static_pool
-            .is_some();
+        let has_static_pool = self.project_state.project.deploy_schema.has_static_pool();
         let buy_params = BuyParams {
             user,
             sols,
             min_token_output: min_token_output.unwrap_or(0),
         };
         match self.project_state.project.stage {
-            Stage::OnStaticPool => ixs.append(&mut self.buy_from_static_pool(buy_params)?),
+            Stage::OnStaticPool | Stage::OnSecondStaticPool => {
+                ixs.append(&mut self.buy_from_static_pool(buy_params)?)
+            }
             Stage::OnCurvePool => {
                 if has_static_pool {
-                    ixs.append(&mut self.transmute_idempotent(user)?);
+                    ixs.append(&mut self.transmute_idempotent(user, None)?);
                 }
-                ixs.append(&mut self.buy_from_curve_pool(buy_params)?);
+                let creator_fee_wallet = self.curve_creator_fee_wallet().await?;
+                ixs.append(&mut self.buy_from_curve_pool(buy_params, creator_fee_wallet)?);
             }
             Stage::Graduated => {
                 if has_static_pool {
-                    ixs.append(&mut self.transmute_idempotent(user)?);
+                    ixs.append(&mut self.transmute_idempotent(user, None)?);
                 }
                 if self.project_state.project.deploy_schema.curve_pool == CurveVariant::Pumpfun {
+                    self.check_pumpfun_slippage(&buy_params).await?;
                     ixs.append(&mut self.buy_from_pumpfun(buy_params)?);
+                } else if let Some(mut swap) = self.buy_via_jupiter(&buy_params).await? {
+                    ixs.append(&mut swap);
+                } else if let Some(target) = self.unrouted_graduation_target() {
+                    bail!("project is graduated to {target:?}, trade it there directly");
                 } else {
                     ixs.append(&mut self.buy_from_raydium(buy_params)?);
                 }
@@ -967,6 +1770,47 @@ impl<'a> ProjectsOperations<'a> {
         Ok(ixs)
     }
 
+    /// Exact token output is supported by the moonzip curve pool only,
+    /// `max_sol_input` includes the fee.
+    pub async fn buy_exact_tokens(
+        &mut self,
+        user: Pubkey,
+        tokens: u64,
+        max_sol_input: u64,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let project = &self.project_state.project;
+        if project.stage != Stage::OnCurvePool
+            || project.deploy_schema.curve_pool != CurveVariant::Moonzip
+        {
+            return Err(ExactTokensUnsupported {
+                stage: project.stage,
+            }
+            .into());
+        }
+
+        let mut ixs = vec![];
+        if project.deploy_schema.has_static_pool() {
+            ixs.append(&mut self.transmute_idempotent(user, None)?);
+        }
+        let creator_fee_wallet = self.curve_creator_fee_wallet().await?;
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+        ixs.append(
+            &mut program
+                .request()
+                .accounts(self.curve_pool_buy_accounts(user, creator_fee_wallet)?)
+                .args(moonzip::instruction::BuyExactTokens {
+                    data: BuyExactTokensData {
+                        project_id: project_id(&self.project_state.project.id),
+                        tokens,
+                        max_sol_input,
+                    },
+                })
+                .instructions()?,
+        );
+        Ok(ixs)
+    }
+
     fn buy_from_static_pool(&self, params: BuyParams) -> anyhow::Result<Vec<Instruction>> {
         let client = self.solana_pool.builder();
         let program = client.program(moonzip::ID)?;
@@ -984,7 +1828,7 @@ impl<'a> ProjectsOperations<'a> {
         Ok(program
             .request()
             .accounts(moonzip::accounts::BuyFromStaticPoolAccounts {
-                authority: moonzip::PROGRAM_AUTHORITY,
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
 
                 fee: *FEE_ACCOUNT,
                 project: project_address,
@@ -992,6 +1836,7 @@ impl<'a> ProjectsOperations<'a> {
                 mint: static_pool_mint,
                 user_mint_account: get_associated_token_address(&params.user, &static_pool_mint),
                 pool_mint_account: get_associated_token_address(&pool, &static_pool_mint),
+                wallet_record: wallet_record_address(pool, params.user),
                 pool,
 
                 event_authority: *MOONZIP_EVENT_AUTHORITY,
@@ -1009,37 +1854,20 @@ impl<'a> ProjectsOperations<'a> {
             .instructions()?)
     }
 
-    fn buy_from_curve_pool(&self, params: BuyParams) -> anyhow::Result<Vec<Instruction>> {
+    fn buy_from_curve_pool(
+        &self,
+        params: BuyParams,
+        creator_fee_wallet: Option<Pubkey>,
+    ) -> anyhow::Result<Vec<Instruction>> {
         let client = self.solana_pool.builder();
         let program = client.program(moonzip::ID)?;
 
-        let curve_mint = self.curve_mint()?;
-        let curve_pool = get_curved_pool_address(curve_mint);
-
-        let project_id = project_id(&self.project_state.project.id);
-        let project_address = project_address(&project_id);
-
         Ok(program
             .request()
-            .accounts(moonzip::accounts::BuyFromCurvedPoolAccounts {
-                event_authority: *MOONZIP_EVENT_AUTHORITY,
-                authority: moonzip::PROGRAM_AUTHORITY,
-                project: project_address,
-                fee: fee_address(),
-                user: params.user,
-                mint: curve_mint,
-                user_token_account: get_associated_token_address(&params.user, &curve_mint),
-                pool_token_account: get_associated_token_address(&curve_pool, &curve_mint),
-                pool: curve_pool,
-
-                program: moonzip::ID,
-                system_program: solana_sdk::system_program::ID,
-                token_program: anchor_spl::token::ID,
-                associated_token_program: anchor_spl::associated_token::ID,
-            })
+            .accounts(self.curve_pool_buy_accounts(params.user, creator_fee_wallet)?)
             .args(moonzip::instruction::BuyFromCurvedPool {
                 data: BuyFromCurvedPoolData {
-                    project_id,
+                    project_id: project_id(&self.project_state.project.id),
                     sols: params.sols,
                     min_token_output: params.min_token_output,
                 },
@@ -1047,6 +1875,56 @@ impl<'a> ProjectsOperations<'a> {
             .instructions()?)
     }
 
+    fn curve_pool_buy_accounts(
+        &self,
+        user: Pubkey,
+        creator_fee_wallet: Option<Pubkey>,
+    ) -> anyhow::Result<moonzip::accounts::BuyFromCurvedPoolAccounts> {
+        let curve_mint = self.curve_mint()?;
+        let curve_pool = get_curved_pool_address(curve_mint);
+        let project_id = project_id(&self.project_state.project.id);
+
+        Ok(moonzip::accounts::BuyFromCurvedPoolAccounts {
+            event_authority: *MOONZIP_EVENT_AUTHORITY,
+            authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+            project: project_address(&project_id),
+            fee: fee_address(),
+            user,
+            mint: curve_mint,
+            user_token_account: get_associated_token_address(&user, &curve_mint),
+            pool_token_account: get_associated_token_address(&curve_pool, &curve_mint),
+            pool: curve_pool,
+            creator_fee_wallet,
+            project_stats: project_stats_address(&project_id),
+
+            program: moonzip::ID,
+            system_program: solana_sdk::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+        })
+    }
+
+    /// Pumpfun curve moves with every trade, so output is estimated against live reserves:
+    /// otherwise user would only learn about slippage from the failed transaction.
+    async fn check_pumpfun_slippage(&self, params: &BuyParams) -> anyhow::Result<()> {
+        let curve_mint = self.curve_mint()?;
+        let live_curve = self
+            .pumpfun_curves
+            .fetch(self.solana_pool, &curve_mint)
+            .await?;
+        let tokens = moonzip::pumpfun::BuyCalculator::from_cpi_curve(&live_curve)
+            .fixed_sols(params.sols)
+            .tokens;
+        if tokens < params.min_token_output {
+            return Err(SlippageExceeded {
+                expected: tokens,
+                min_token_output: params.min_token_output,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
     fn buy_from_pumpfun(&mut self, params: BuyParams) -> anyhow::Result<Vec<Instruction>> {
         let client = self.solana_pool.builder();
         let program = client.program(moonzip::ID)?;
@@ -1086,18 +1964,13 @@ impl<'a> ProjectsOperations<'a> {
         Ok(ixs)
     }
 
-    pub fn sell(
+    pub async fn sell(
         &mut self,
         user: Pubkey,
         tokens: u64,
         min_sol_output: Option<u64>,
     ) -> anyhow::Result<Vec<Instruction>> {
-        let has_static_pool = self
-            .project_state
-            .project
-            .deploy_schema
-            .static_pool
-            .is_some();
+        let has_static_pool = self.project_state.project.deploy_schema.has_static_pool();
 
         let mut ixs = vec![];
         let sell_params = SellParams {
@@ -1107,21 +1980,29 @@ impl<'a> ProjectsOperations<'a> {
         };
 
         match self.project_state.project.stage {
-            Stage::OnStaticPool => {
+            Stage::OnStaticPool | Stage::OnSecondStaticPool => {
                 ixs.append(&mut self.sell_to_static_pool(sell_params)?);
             }
+            Stage::StaticPoolFailed => {
+                ixs.append(&mut self.refund_from_static_pool(sell_params)?);
+            }
             Stage::OnCurvePool => {
                 if has_static_pool {
-                    ixs.append(&mut self.transmute_idempotent(user)?);
+                    ixs.append(&mut self.transmute_idempotent(user, None)?);
                 }
-                ixs.append(&mut self.sell_to_curve_pool(sell_params)?);
+                let creator_fee_wallet = self.curve_creator_fee_wallet().await?;
+                ixs.append(&mut self.sell_to_curve_pool(sell_params, creator_fee_wallet)?);
             }
             Stage::Graduated => {
                 if has_static_pool {
-                    ixs.append(&mut self.transmute_idempotent(user)?);
+                    ixs.append(&mut self.transmute_idempotent(user, None)?);
                 }
                 if self.project_state.project.deploy_schema.curve_pool == CurveVariant::Pumpfun {
                     ixs.append(&mut self.sell_to_pumpfun(sell_params)?);
+                } else if let Some(mut swap) = self.sell_via_jupiter(&sell_params).await? {
+                    ixs.append(&mut swap);
+                } else if let Some(target) = self.unrouted_graduation_target() {
+                    bail!("project is graduated to {target:?}, trade it there directly");
                 } else {
                     ixs.append(&mut self.sell_to_raydium(sell_params)?);
                 }
@@ -1153,7 +2034,7 @@ impl<'a> ProjectsOperations<'a> {
         Ok(program
             .request()
             .accounts(moonzip::accounts::SellToStaticPoolAccounts {
-                authority: moonzip::PROGRAM_AUTHORITY,
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
 
                 fee: *FEE_ACCOUNT,
                 project: project_address,
@@ -1178,38 +2059,99 @@ impl<'a> ProjectsOperations<'a> {
             .instructions()?)
     }
 
-    fn sell_to_curve_pool(&self, params: SellParams) -> anyhow::Result<Vec<Instruction>> {
+    /// Refund of the failed pool doesn't charge fees, so slippage isn't checked.
+    fn refund_from_static_pool(&self, params: SellParams) -> anyhow::Result<Vec<Instruction>> {
         let client = self.solana_pool.builder();
         let program = client.program(moonzip::ID)?;
 
         let project_id = project_id(&self.project_state.project.id);
-        let project_address = project_address(&project_id);
-
-        let curve_mint = self.curve_mint()?;
-        let curve_pool = get_curved_pool_address(curve_mint);
-
+        let static_pool_mint = self
+            .project_state
+            .project
+            .static_pool_mint()
+            .ok_or_else(|| anyhow::anyhow!("invariant: no static pool mint"))?;
+        let pool = static_pool_address(static_pool_mint);
         Ok(program
             .request()
-            .accounts(moonzip::accounts::SellFromCurvedPoolAccounts {
+            .accounts(moonzip::accounts::RefundFromStaticPoolAccounts {
                 authority: moonzip::PROGRAM_AUTHORITY,
+                authority_config: authority_config_address(),
 
-                fee: fee_address(),
-                project: project_address,
+                project: project_address(&project_id),
                 user: params.user,
-                mint: curve_mint,
-                user_token_account: get_associated_token_address(&params.user, &curve_mint),
-                pool_token_account: get_associated_token_address(&curve_pool, &curve_mint),
-                pool: curve_pool,
+                mint: static_pool_mint,
+                user_token_account: get_associated_token_address(&params.user, &static_pool_mint),
+                pool_token_account: get_associated_token_address(&pool, &static_pool_mint),
+                pool,
 
                 event_authority: *MOONZIP_EVENT_AUTHORITY,
                 program: moonzip::ID,
-                system_program: solana_sdk::system_program::ID,
                 token_program: anchor_spl::token::ID,
-                associated_token_program: anchor_spl::associated_token::ID,
             })
+            .args(moonzip::instruction::RefundFromStaticPool {
+                data: RefundFromStaticPoolData {
+                    project_id,
+                    tokens: params.tokens,
+                },
+            })
+            .instructions()?)
+    }
+
+    /// Exact sol output is supported by the moonzip curve pool only,
+    /// `target_sol` excludes the fee.
+    pub async fn sell_exact_sol(
+        &mut self,
+        user: Pubkey,
+        target_sol: u64,
+        max_token_input: u64,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let project = &self.project_state.project;
+        if project.stage != Stage::OnCurvePool
+            || project.deploy_schema.curve_pool != CurveVariant::Moonzip
+        {
+            return Err(ExactSolUnsupported {
+                stage: project.stage,
+            }
+            .into());
+        }
+
+        let mut ixs = vec![];
+        if project.deploy_schema.has_static_pool() {
+            ixs.append(&mut self.transmute_idempotent(user, None)?);
+        }
+        let creator_fee_wallet = self.curve_creator_fee_wallet().await?;
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+        ixs.append(
+            &mut program
+                .request()
+                .accounts(self.curve_pool_sell_accounts(user, creator_fee_wallet)?)
+                .args(moonzip::instruction::SellExactSol {
+                    data: SellExactSolData {
+                        project_id: project_id(&self.project_state.project.id),
+                        target_sol,
+                        max_token_input,
+                    },
+                })
+                .instructions()?,
+        );
+        Ok(ixs)
+    }
+
+    fn sell_to_curve_pool(
+        &self,
+        params: SellParams,
+        creator_fee_wallet: Option<Pubkey>,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        Ok(program
+            .request()
+            .accounts(self.curve_pool_sell_accounts(params.user, creator_fee_wallet)?)
             .args(moonzip::instruction::SellFromCurvedPool {
                 data: SellFromCurvedPoolData {
-                    project_id,
+                    project_id: project_id(&self.project_state.project.id),
                     tokens: params.tokens,
                     min_sol_output: params.min_sol_output,
                 },
@@ -1217,6 +2159,36 @@ impl<'a> ProjectsOperations<'a> {
             .instructions()?)
     }
 
+    fn curve_pool_sell_accounts(
+        &self,
+        user: Pubkey,
+        creator_fee_wallet: Option<Pubkey>,
+    ) -> anyhow::Result<moonzip::accounts::SellFromCurvedPoolAccounts> {
+        let curve_mint = self.curve_mint()?;
+        let curve_pool = get_curved_pool_address(curve_mint);
+        let project_id = project_id(&self.project_state.project.id);
+
+        Ok(moonzip::accounts::SellFromCurvedPoolAccounts {
+            authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+
+            fee: fee_address(),
+            project: project_address(&project_id),
+            user,
+            mint: curve_mint,
+            user_token_account: get_associated_token_address(&user, &curve_mint),
+            pool_token_account: get_associated_token_address(&curve_pool, &curve_mint),
+            pool: curve_pool,
+            creator_fee_wallet,
+            project_stats: project_stats_address(&project_id),
+
+            event_authority: *MOONZIP_EVENT_AUTHORITY,
+            program: moonzip::ID,
+            system_program: solana_sdk::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+        })
+    }
+
     fn sell_to_pumpfun(&mut self, params: SellParams) -> anyhow::Result<Vec<Instruction>> {
         let client = self.solana_pool.builder();
         let program = client.program(pumpfun_cpi::ID)?;
@@ -1253,18 +2225,36 @@ impl<'a> ProjectsOperations<'a> {
         Ok(ixs)
     }
 
-    fn transmute_idempotent(&self, user: Pubkey) -> anyhow::Result<Vec<Instruction>> {
+    /// User could hold tokens of every static pool of the project.
+    /// `max_tokens` limits each of them, leaving token accounts open for later transmutations.
+    fn transmute_idempotent(
+        &self,
+        user: Pubkey,
+        max_tokens: Option<u64>,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let mut ixs = vec![];
+        for static_pool_mint in self.project_state.project.static_pool_mints() {
+            ixs.append(&mut self.transmute_idempotent_from(user, static_pool_mint, max_tokens)?);
+        }
+        Ok(ixs)
+    }
+
+    fn transmute_idempotent_from(
+        &self,
+        user: Pubkey,
+        static_pool_mint: Pubkey,
+        max_tokens: Option<u64>,
+    ) -> anyhow::Result<Vec<Instruction>> {
         let client = self.solana_pool.builder();
         let program = client.program(moonzip::ID)?;
 
         let curve_mint = self.curve_mint()?;
-        let static_pool_mint = self.static_pool_mint()?;
         let transmuter = get_transmuter_address(static_pool_mint, curve_mint);
 
         Ok(program
             .request()
             .accounts(moonzip::accounts::TransmuteIdempotentAccounts {
-                authority: moonzip::PROGRAM_AUTHORITY,
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
                 user,
                 from_mint: static_pool_mint,
                 to_mint: curve_mint,
@@ -1277,7 +2267,9 @@ impl<'a> ProjectsOperations<'a> {
                 associated_token_program: anchor_spl::associated_token::ID,
                 moonzip_program: moonzip::ID,
             })
-            .args(moonzip::instruction::TransmuteIdempotent {})
+            .args(moonzip::instruction::TransmuteIdempotent {
+                data: TransmuteIdempotentData { max_tokens },
+            })
             .instructions()?)
     }
 
@@ -1294,6 +2286,72 @@ impl<'a> ProjectsOperations<'a> {
         ))
     }
 
+    /// Current static pool, absent once it's graduated.
+    pub async fn fetch_static_pool(&self) -> anyhow::Result<Option<StaticPool>> {
+        let client = self.solana_pool.rpc_client().use_single().await;
+        let commitment = client.commitment();
+        let Some(account) = client
+            .get_account_with_commitment(&self.static_pool_address()?, commitment)
+            .await?
+            .value
+        else {
+            return Ok(None);
+        };
+        Ok(Some(StaticPool::try_deserialize(
+            &mut account.data.as_slice(),
+        )?))
+    }
+
+    /// Same view, as `get_curve_state` instruction returns.
+    /// Absent for pumpfun curves and until the curve pool is created.
+    pub async fn fetch_curve_state(&self) -> anyhow::Result<Option<CurveStateView>> {
+        Ok(self.fetch_curved_pool().await?.map(|pool| pool.view()))
+    }
+
+    /// Creator fee wallet must be passed to trades iff the live pool takes a creator fee share.
+    async fn curve_creator_fee_wallet(&self) -> anyhow::Result<Option<Pubkey>> {
+        let pool = self
+            .fetch_curved_pool()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("invariant: no curve pool to trade"))?;
+        Ok(pool
+            .config
+            .creator_fee_share_bps
+            .map(|_| creator_fee_wallet_address(self.get_project_address())))
+    }
+
+    /// Pool, created by the migrator, copies the pool config of the global account,
+    /// so trades, built along with its creation, can't fetch it yet.
+    fn new_pool_creator_fee_wallet(&self) -> anyhow::Result<Option<Pubkey>> {
+        let meta = self.mzip_meta.clone().get()?;
+        Ok(meta
+            .global_account
+            .config
+            .pool
+            .creator_fee_share_bps
+            .map(|_| creator_fee_wallet_address(self.get_project_address())))
+    }
+
+    async fn fetch_curved_pool(&self) -> anyhow::Result<Option<CurvedPool>> {
+        if self.project_state.project.deploy_schema.curve_pool != CurveVariant::Moonzip
+            || self.project_state.project.curve_pool_keypair.is_none()
+        {
+            return Ok(None);
+        }
+        let client = self.solana_pool.rpc_client().use_single().await;
+        let commitment = client.commitment();
+        let Some(account) = client
+            .get_account_with_commitment(&curved_pool_address(self.curve_mint()?), commitment)
+            .await?
+            .value
+        else {
+            return Ok(None);
+        };
+        Ok(Some(CurvedPool::try_deserialize(
+            &mut account.data.as_slice(),
+        )?))
+    }
+
     fn curve_mint(&self) -> anyhow::Result<Pubkey> {
         Ok(self
             .project_state
@@ -1305,32 +2363,16 @@ impl<'a> ProjectsOperations<'a> {
             .pubkey())
     }
 
-    fn static_pool_mint(&self) -> anyhow::Result<Pubkey> {
-        let static_pool_mint = self
-            .project_state
-            .project
-            .static_pool_mint()
-            .ok_or_else(|| anyhow::anyhow!("invariant: no static pool mint"))?;
-        Ok(static_pool_mint)
+    fn project_derived_seed(&self, extra_seed: &str) -> String {
+        project_derived_seed(&self.project_state.project.id, extra_seed)
     }
 
-    /// Seed is needed for deriving pubkeys.
-    fn project_seed(&self) -> String {
-        self.project_state.project.id.simple().to_string()
-    }
-
-    /// It allows generating unique solana-conforming seeds,
-    /// which are practically unique as long as combination (project_id, extra_seed) is unique.
-    ///
-    /// Lets say you want to generate market account that is bound to specific project.
-    /// You decide that seed would be "openbook_market" - it would be extra_seed parameter.
-    /// Then resulting String is unique across all projects.
-    fn project_derived_seed(&self, extra_seed: &str) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(format!("{}__{extra_seed}", self.project_seed()).into_bytes());
-        let mut result = format!("{:x}", hasher.finalize());
-        result.truncate(32);
-        result
+    /// DEX of the graduated project, trades there are not routed by backend yet.
+    fn unrouted_graduation_target(&self) -> Option<GraduationTarget> {
+        let schema = &self.project_state.project.deploy_schema;
+        (schema.curve_pool != CurveVariant::Pumpfun
+            && schema.graduation_target != GraduationTarget::Raydium)
+            .then_some(schema.graduation_target)
     }
 }
 
@@ -1341,6 +2383,27 @@ pub struct BuyParams {
     min_token_output: u64,
 }
 
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Slippage exceeded: would receive {expected} tokens, requested at least {min_token_output}"
+)]
+pub struct SlippageExceeded {
+    pub expected: u64,
+    pub min_token_output: u64,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Exact token output is available on the moonzip curve pool only, project is at {stage:?}")]
+pub struct ExactTokensUnsupported {
+    pub stage: Stage,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Exact sol output is available on the moonzip curve pool only, project is at {stage:?}")]
+pub struct ExactSolUnsupported {
+    pub stage: Stage,
+}
+
 #[derive(Debug, Clone)]
 pub struct SellParams {
     user: Pubkey,
@@ -1348,6 +2411,24 @@ pub struct SellParams {
     min_sol_output: u64,
 }
 
+/// Metadata of the curve mint, created by the authority as its mint authority.
+/// Mutable one is updated only through the program, which holds its update authority.
+fn create_token_metadata(action: &CurveCreate, is_mutable: bool) -> anyhow::Result<Instruction> {
+    Ok(CreateV1Builder::new()
+        .metadata(mpl::metadata_account(action.mint))
+        .mint(action.mint, true)
+        .authority(moonzip::PROGRAM_AUTHORITY)
+        .payer(moonzip::PROGRAM_AUTHORITY)
+        .update_authority(metadata_authority_address(), false)
+        .is_mutable(is_mutable)
+        .primary_sale_happened(false)
+        .name(action.metadata.name.clone())
+        .uri(action.metadata.deployed_url()?)
+        .seller_fee_basis_points(0)
+        .token_standard(mpl_token_metadata::types::TokenStandard::Fungible)
+        .instruction())
+}
+
 #[derive(Debug, Clone)]
 pub struct CurveCreate {
     pub mint: Pubkey,
@@ -1382,6 +2463,122 @@ fn get_transmuter_address(from_mint: Pubkey, to_mint: Pubkey) -> Pubkey {
     .0
 }
 
+/// Accounts of the program authority, signing privileged instructions.
+pub fn program_authority_accounts(authority: Pubkey) -> ProgramAuthorityAccounts {
+    ProgramAuthorityAccounts {
+        authority,
+        authority_config: authority_config_address(),
+    }
+}
+
 fn get_curved_pool_address(mint: Pubkey) -> Pubkey {
     Pubkey::find_program_address(&[CURVED_POOL_PREFIX, mint.as_ref()], &moonzip::ID).0
 }
+
+/// It allows generating unique solana-conforming seeds,
+/// which are practically unique as long as combination (project_id, extra_seed) is unique.
+///
+/// Lets say you want to generate market account that is bound to specific project.
+/// You decide that seed would be "openbook_market" - it would be extra_seed parameter.
+/// Then resulting String is unique across all projects.
+///
+/// Format is the first 32 hex chars of `SHA256("{project_uuid_simple}__{extra_seed}")`,
+/// so it fits into the max seed length. It must never change:
+/// accounts, already created with the seed, would become unreachable.
+pub fn project_derived_seed(project_id: &ProjectId, extra_seed: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}__{extra_seed}", project_id.simple()).into_bytes());
+    let mut result = format!("{:x}", hasher.finalize());
+    result.truncate(32);
+    result
+}
+
+/// PDA of the program, bound to the project by the derived seed, followed by `seeds`.
+pub fn project_derived_address(
+    project_id: &ProjectId,
+    extra_seed: &str,
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+) -> Pubkey {
+    let derived_seed = project_derived_seed(project_id, extra_seed);
+    let all_seeds: Vec<&[u8]> = std::iter::once(derived_seed.as_bytes())
+        .chain(seeds.iter().copied())
+        .collect();
+    Pubkey::find_program_address(&all_seeds, program_id).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_project_id() -> ProjectId {
+        ProjectId::parse_str("6f1c2a3b-4d5e-4f60-a1b2-c3d4e5f60718").unwrap()
+    }
+
+    #[test]
+    fn test_checks_graduation_thresholds() {
+        let config = InstructionsConfig {
+            min_token_supply_for_graduation: 1000,
+            ..Default::default()
+        };
+        let min_sols = config.min_graduation_liquidity_lamports;
+        assert_eq!(min_sols, 10 * LAMPORTS_PER_SOL);
+
+        config.check_graduation(min_sols, 1000).unwrap();
+        assert_eq!(
+            config.check_graduation(min_sols - 1, 1000),
+            Err(GraduationCheckError::InsufficientLiquidity {
+                sol_balance: min_sols - 1,
+                min: min_sols,
+            })
+        );
+        assert_eq!(
+            config.check_graduation(min_sols, 999),
+            Err(GraduationCheckError::InsufficientTokens {
+                tokens: 999,
+                min: 1000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_keeps_derived_seeds_stable() {
+        assert_eq!(
+            project_derived_seed(&fixed_project_id(), "openbook_market"),
+            "f477cf608975abe35fb01d049d1ef53c"
+        );
+        assert_eq!(
+            project_derived_seed(&fixed_project_id(), "openbook_queue_event"),
+            "371f2ba8c33c81eb62a4aa9edffd3c8e"
+        );
+    }
+
+    #[test]
+    fn test_derives_distinct_addresses_per_seed() {
+        let program_id = Pubkey::new_unique();
+        let market =
+            project_derived_address(&fixed_project_id(), "openbook_market", &[], &program_id);
+        assert_eq!(
+            market,
+            project_derived_address(&fixed_project_id(), "openbook_market", &[], &program_id)
+        );
+        assert_ne!(
+            market,
+            project_derived_address(
+                &fixed_project_id(),
+                "openbook_market",
+                &[b"vault"],
+                &program_id
+            )
+        );
+        assert_ne!(
+            market,
+            project_derived_address(
+                &fixed_project_id(),
+                "raydium_intermediate_holder",
+                &[],
+                &program_id
+            )
+        );
+    }
+}
@@ -1,29 +1,38 @@
 use anchor_client::anchor_lang::AccountDeserialize as _;
 use anchor_spl::associated_token::get_associated_token_address;
-use anyhow::Context as _;
+use anyhow::{bail, Context as _};
 use moonzip::{
-    fee::{FeeAccount, FEE_ACCOUNT_PREFIX},
-    moonzip::{GlobalCurvedPoolAccount, GLOBAL_ACCOUNT_PREFIX},
+    fee::{FeeAccount, FeeConfig},
+    moonzip::{GlobalCurvedPoolAccount, SetProjectCreationFeeData, GLOBAL_ACCOUNT_PREFIX},
 };
 use once_cell::sync::Lazy;
 use services_common::{solana::pool::SolanaPool, utils::period_fetch::FetchExecutor};
 use solana_sdk::{commitment_config::CommitmentConfig, instruction::Instruction, pubkey::Pubkey};
 
-use super::{utils::anchor_event_authority, ProjectsOperations};
+use super::{
+    program_authority_accounts, utils::anchor_event_authority_with_bump, InstructionsBuilder,
+    ProjectsOperations,
+};
+use crate::app::storage::project::project_id;
 
 impl<'a> ProjectsOperations<'a> {
+    /// Burns the whole balance of the authority ATA, closes it and revokes mint authorities,
+    /// held by the authority. Only the program authority is allowed to burn.
     pub fn burn_and_close(
         &self,
         authority: Pubkey,
         mint: Pubkey,
     ) -> anyhow::Result<Vec<Instruction>> {
+        if authority != moonzip::PROGRAM_AUTHORITY {
+            bail!("only program authority could burn tokens, got {authority}");
+        }
         let client = self.solana_pool.builder();
         let program = client.program(moonzip::ID)?;
 
         Ok(program
             .request()
             .accounts(moonzip::accounts::BurnAndCloseAccounts {
-                authority,
+                authority: program_authority_accounts(authority),
                 mint,
                 token_account: get_associated_token_address(&authority, &mint),
                 token_program: anchor_spl::token::ID,
@@ -32,10 +41,95 @@ impl<'a> ProjectsOperations<'a> {
             .args(moonzip::instruction::BurnAndClose {})
             .instructions()?)
     }
+
+    /// Should be placed right before `burn_and_close` of the LP tokens.
+    pub fn emit_liquidity_burn(&self, lp_mint: Pubkey) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        Ok(program
+            .request()
+            .accounts(moonzip::accounts::EmitLiquidityBurnAccounts {
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+                project: self.get_project_address(),
+                lp_mint,
+                lp_token_account: get_associated_token_address(
+                    &moonzip::PROGRAM_AUTHORITY,
+                    &lp_mint,
+                ),
+                event_authority: *MOONZIP_EVENT_AUTHORITY,
+                program: moonzip::ID,
+            })
+            .args(moonzip::instruction::EmitLiquidityBurn {
+                _data: moonzip::misc::EmitLiquidityBurnData {
+                    id: project_id(&self.project_state.project.id),
+                },
+            })
+            .instructions()?)
+    }
 }
 
+impl InstructionsBuilder {
+    pub fn set_fee_config(
+        &self,
+        config: FeeConfig,
+        fee_receiver: Pubkey,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        Ok(program
+            .request()
+            .accounts(moonzip::accounts::SetFeeConfigAccounts {
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+                fee: *FEE_ACCOUNT,
+                fee_receiver,
+                system_program: solana_sdk::system_program::ID,
+            })
+            .args(moonzip::instruction::SetFeeConfig { config })
+            .instructions()?)
+    }
+
+    pub fn set_project_creation_fee(&self, fee_lamports: u64) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        Ok(program
+            .request()
+            .accounts(moonzip::accounts::SetProjectCreationFeeAccounts {
+                authority: program_authority_accounts(moonzip::PROGRAM_AUTHORITY),
+                global: *GLOBAL_ACCOUNT,
+                system_program: solana_sdk::system_program::ID,
+            })
+            .args(moonzip::instruction::SetProjectCreationFee {
+                data: SetProjectCreationFeeData { fee_lamports },
+            })
+            .instructions()?)
+    }
+
+    pub fn sweep_fee(&self, fee_receiver: Pubkey) -> anyhow::Result<Vec<Instruction>> {
+        let client = self.solana_pool.builder();
+        let program = client.program(moonzip::ID)?;
+
+        Ok(program
+            .request()
+            .accounts(moonzip::accounts::SweepFeeAccounts {
+                fee: *FEE_ACCOUNT,
+                fee_receiver,
+            })
+            .args(moonzip::instruction::SweepFee {})
+            .instructions()?)
+    }
+}
+
+static MOONZIP_EVENT_AUTHORITY_WITH_BUMP: Lazy<(Pubkey, u8)> =
+    Lazy::new(|| anchor_event_authority_with_bump(&moonzip::ID));
+
+/// Derived from the compiled-in program id, so test deployments get their own one.
 pub static MOONZIP_EVENT_AUTHORITY: Lazy<Pubkey> =
-    Lazy::new(|| anchor_event_authority(&moonzip::ID));
+    Lazy::new(|| MOONZIP_EVENT_AUTHORITY_WITH_BUMP.0);
+pub static MOONZIP_EVENT_AUTHORITY_BUMP: Lazy<u8> =
+    Lazy::new(|| MOONZIP_EVENT_AUTHORITY_WITH_BUMP.1);
 
 pub struct MetaFetcher {
     pub pool: SolanaPool,
@@ -106,12 +200,8 @@ impl PartialOrd for Meta {
 }
 
 pub static GLOBAL_ACCOUNT: Lazy<Pubkey> = Lazy::new(global_account_address);
-pub static FEE_ACCOUNT: Lazy<Pubkey> = Lazy::new(fee_account_address);
+pub static FEE_ACCOUNT: Lazy<Pubkey> = Lazy::new(FeeAccount::address);
 
 fn global_account_address() -> Pubkey {
     Pubkey::find_program_address(&[GLOBAL_ACCOUNT_PREFIX], &moonzip::ID).0
 }
-
-fn fee_account_address() -> Pubkey {
-    Pubkey::find_program_address(&[FEE_ACCOUNT_PREFIX], &moonzip::ID).0
-}
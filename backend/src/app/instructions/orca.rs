@@ -0,0 +1,569 @@
+use super::{
+    quote::ConstantProduct,
+    utils::{account_field, anchor_discriminator},
+    ProjectsOperations, WRAPPED_SOL_MINT,
+};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use moonzip::PROGRAM_AUTHORITY;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer as _,
+    system_instruction::create_account_with_seed,
+    system_program, sysvar,
+};
+
+/// Ticks, covered by a single tick array account of Whirlpool.
+const TICK_ARRAY_SIZE: i32 = 88;
+const MAX_TICK_INDEX: i32 = 443636;
+/// Liquidity is computed with floats, so it's shrunk to never require more than deposited.
+const LIQUIDITY_MARGIN: f64 = 1e-6;
+
+/// Fee rate of the pool is in hundredths of a basis point.
+const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+const WHIRLPOOL_FEE_RATE_OFFSET: usize = 45;
+const WHIRLPOOL_LIQUIDITY_OFFSET: usize = 49;
+const WHIRLPOOL_SQRT_PRICE_OFFSET: usize = 65;
+
+const WHIRLPOOL_SEED: &[u8] = b"whirlpool";
+const FEE_TIER_SEED: &[u8] = b"fee_tier";
+const TICK_ARRAY_SEED: &[u8] = b"tick_array";
+const POSITION_SEED: &[u8] = b"position";
+
+#[derive(borsh::BorshSerialize)]
+struct InitializePoolArgs {
+    whirlpool_bump: u8,
+    tick_spacing: u16,
+    initial_sqrt_price: u128,
+}
+
+#[derive(borsh::BorshSerialize)]
+struct InitializeTickArrayArgs {
+    start_tick_index: i32,
+}
+
+#[derive(borsh::BorshSerialize)]
+struct OpenPositionArgs {
+    position_bump: u8,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+}
+
+#[derive(borsh::BorshSerialize)]
+struct IncreaseLiquidityArgs {
+    liquidity_amount: u128,
+    token_max_a: u64,
+    token_max_b: u64,
+}
+
+/// Accounts, Whirlpool requires to be created by signers, so they are generated per graduation.
+pub struct WhirlpoolKeys {
+    pub vault_a: Keypair,
+    pub vault_b: Keypair,
+    pub position_mint: Keypair,
+}
+
+impl WhirlpoolKeys {
+    pub fn generate() -> Self {
+        Self {
+            vault_a: Keypair::new(),
+            vault_b: Keypair::new(),
+            position_mint: Keypair::new(),
+        }
+    }
+}
+
+/// Whirlpool orders mints, so the project token is either A or B,
+/// and the price is amount of B per the smallest unit of A.
+struct WhirlpoolMints {
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    token_is_a: bool,
+}
+
+impl WhirlpoolMints {
+    fn new(curve_mint: Pubkey) -> Self {
+        let token_is_a = curve_mint < WRAPPED_SOL_MINT;
+        let (mint_a, mint_b) = if token_is_a {
+            (curve_mint, WRAPPED_SOL_MINT)
+        } else {
+            (WRAPPED_SOL_MINT, curve_mint)
+        };
+        Self {
+            mint_a,
+            mint_b,
+            token_is_a,
+        }
+    }
+
+    /// Lamports per token in terms of the pool.
+    fn price(&self, lamports: u64, tokens: u64) -> f64 {
+        if self.token_is_a {
+            lamports as f64 / tokens as f64
+        } else {
+            tokens as f64 / lamports as f64
+        }
+    }
+
+    fn amounts(&self, tokens: u64, lamports: u64) -> (u64, u64) {
+        if self.token_is_a {
+            (tokens, lamports)
+        } else {
+            (lamports, tokens)
+        }
+    }
+}
+
+/// Orca Whirlpool of the project, holding the liquidity in a single full range position,
+/// so it behaves the same as the constant product pool of raydium.
+///
+/// Position is represented by the NFT, which is burned once liquidity is deposited:
+/// nobody could withdraw it afterwards, the same as with burned raydium LP tokens.
+impl<'a> ProjectsOperations<'a> {
+    /// Creates the pool at the price of the deployed liquidity, so neither side of the deposit
+    /// is left behind, along with tick arrays of the full range position,
+    /// [`Self::deploy_to_whirlpool`] deposits to.
+    pub fn create_whirlpool(
+        &self,
+        tokens_amount: u64,
+        sols_amount: u64,
+        keys: &WhirlpoolKeys,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let mints = WhirlpoolMints::new(self.curve_mint()?);
+        let (whirlpool, whirlpool_bump) = self.whirlpool_address(&mints);
+        let sqrt_price = sqrt_price_x64(mints.price(sols_amount, tokens_amount))?;
+        let tick_spacing = self.config.orca_tick_spacing;
+        let funder = PROGRAM_AUTHORITY;
+
+        let mut ixs = vec![self.whirlpool_instruction(
+            "initialize_pool",
+            &InitializePoolArgs {
+                whirlpool_bump,
+                tick_spacing,
+                initial_sqrt_price: sqrt_price,
+            },
+            vec![
+                AccountMeta::new_readonly(self.config.orca_whirlpools_config, false),
+                AccountMeta::new_readonly(mints.mint_a, false),
+                AccountMeta::new_readonly(mints.mint_b, false),
+                AccountMeta::new(funder, true),
+                AccountMeta::new(whirlpool, false),
+                AccountMeta::new(keys.vault_a.pubkey(), true),
+                AccountMeta::new(keys.vault_b.pubkey(), true),
+                AccountMeta::new_readonly(self.whirlpool_fee_tier(), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(sysvar::rent::ID, false),
+            ],
+        )?];
+
+        // swaps start from the array of the current tick, so it must exist as well
+        let current_tick = tick_at_sqrt_price(sqrt_price);
+        let (lower_tick, upper_tick) = full_range_ticks(tick_spacing);
+        let mut starts: Vec<_> = [lower_tick, current_tick, upper_tick]
+            .into_iter()
+            .map(|tick| tick_array_start(tick, tick_spacing))
+            .collect();
+        starts.dedup();
+        for start_tick_index in starts {
+            ixs.push(self.whirlpool_instruction(
+                "initialize_tick_array",
+                &InitializeTickArrayArgs { start_tick_index },
+                vec![
+                    AccountMeta::new_readonly(whirlpool, false),
+                    AccountMeta::new(funder, true),
+                    AccountMeta::new(
+                        self.whirlpool_tick_array(&whirlpool, start_tick_index),
+                        false,
+                    ),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+            )?);
+        }
+        Ok(ixs)
+    }
+
+    /// Opens the full range position in the pool, created by [`Self::create_whirlpool`],
+    /// deposits the liquidity there and burns the position NFT.
+    pub fn deploy_to_whirlpool(
+        &self,
+        tokens_amount: u64,
+        sols_amount: u64,
+        keys: &WhirlpoolKeys,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let curve_mint = self.curve_mint()?;
+        let mints = WhirlpoolMints::new(curve_mint);
+        let (whirlpool, _) = self.whirlpool_address(&mints);
+        let sqrt_price = sqrt_price_x64(mints.price(sols_amount, tokens_amount))?;
+        let tick_spacing = self.config.orca_tick_spacing;
+        let (lower_tick, upper_tick) = full_range_ticks(tick_spacing);
+        let donor = PROGRAM_AUTHORITY;
+        let program = self.config.orca_program;
+
+        let position_mint = keys.position_mint.pubkey();
+        let (position, position_bump) =
+            Pubkey::find_program_address(&[POSITION_SEED, position_mint.as_ref()], &program);
+        let position_token_account = get_associated_token_address(&donor, &position_mint);
+
+        let open_position = self.whirlpool_instruction(
+            "open_position",
+            &OpenPositionArgs {
+                position_bump,
+                tick_lower_index: lower_tick,
+                tick_upper_index: upper_tick,
+            },
+            vec![
+                AccountMeta::new(donor, true),
+                AccountMeta::new_readonly(donor, false),
+                AccountMeta::new(position, false),
+                AccountMeta::new(position_mint, true),
+                AccountMeta::new(position_token_account, false),
+                AccountMeta::new_readonly(whirlpool, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(sysvar::rent::ID, false),
+                AccountMeta::new_readonly(associated_token::ID, false),
+            ],
+        )?;
+
+        let token_account_space = spl_token::state::Account::LEN;
+        let lamports = self.rent.minimum_balance(token_account_space) + sols_amount;
+        let seed = self.project_derived_seed("orca_intermediate_holder");
+        let donor_wrapped_sol = Pubkey::create_with_seed(&donor, &seed, &spl_token::ID)?;
+        let donor_token = get_associated_token_address(&donor, &curve_mint);
+
+        let create_donor_wrapped_sol = create_account_with_seed(
+            &donor,
+            &donor_wrapped_sol,
+            &donor,
+            &seed,
+            lamports,
+            token_account_space as u64,
+            &spl_token::ID,
+        );
+        let initialize_donor_wrapped_sol = spl_token::instruction::initialize_account(
+            &spl_token::ID,
+            &donor_wrapped_sol,
+            &WRAPPED_SOL_MINT,
+            &donor,
+        )?;
+        let close_donor_wrapped_sol = spl_token::instruction::close_account(
+            &spl_token::ID,
+            &donor_wrapped_sol,
+            &donor,
+            &donor,
+            &[&donor],
+        )?;
+
+        let (amount_a, amount_b) = mints.amounts(tokens_amount, sols_amount);
+        let (owner_account_a, owner_account_b) = if mints.token_is_a {
+            (donor_token, donor_wrapped_sol)
+        } else {
+            (donor_wrapped_sol, donor_token)
+        };
+        let increase_liquidity = self.whirlpool_instruction(
+            "increase_liquidity",
+            &IncreaseLiquidityArgs {
+                liquidity_amount: full_range_liquidity(
+                    sqrt_price,
+                    tick_spacing,
+                    amount_a,
+                    amount_b,
+                )?,
+                token_max_a: amount_a,
+                token_max_b: amount_b,
+            },
+            vec![
+                AccountMeta::new(whirlpool, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(donor, true),
+                AccountMeta::new(position, false),
+                AccountMeta::new_readonly(position_token_account, false),
+                AccountMeta::new(owner_account_a, false),
+                AccountMeta::new(owner_account_b, false),
+                AccountMeta::new(keys.vault_a.pubkey(), false),
+                AccountMeta::new(keys.vault_b.pubkey(), false),
+                AccountMeta::new(
+                    self.whirlpool_tick_array(
+                        &whirlpool,
+                        tick_array_start(lower_tick, tick_spacing),
+                    ),
+                    false,
+                ),
+                AccountMeta::new(
+                    self.whirlpool_tick_array(
+                        &whirlpool,
+                        tick_array_start(upper_tick, tick_spacing),
+                    ),
+                    false,
+                ),
+            ],
+        )?;
+
+        let burn_position = spl_token::instruction::burn(
+            &spl_token::ID,
+            &position_token_account,
+            &position_mint,
+            &donor,
+            &[&donor],
+            1,
+        )?;
+        let close_position_token_account = spl_token::instruction::close_account(
+            &spl_token::ID,
+            &position_token_account,
+            &donor,
+            &donor,
+            &[&donor],
+        )?;
+
+        Ok(vec![
+            open_position,
+            create_donor_wrapped_sol,
+            initialize_donor_wrapped_sol,
+            increase_liquidity,
+            close_donor_wrapped_sol,
+            burn_position,
+            close_position_token_account,
+        ])
+    }
+
+    /// Absent until the project is deployed to orca.
+    pub async fn fetch_whirlpool_reserves(&self) -> anyhow::Result<Option<ConstantProduct>> {
+        let mints = WhirlpoolMints::new(self.curve_mint()?);
+        let (whirlpool, _) = self.whirlpool_address(&mints);
+        let client = self.solana_pool.rpc_client().use_single().await;
+        let commitment = client.commitment();
+        let Some(account) = client
+            .get_account_with_commitment(&whirlpool, commitment)
+            .await?
+            .value
+        else {
+            return Ok(None);
+        };
+        let data = account.data.as_slice();
+        Ok(Some(whirlpool_reserves(
+            &mints,
+            u128::from_le_bytes(account_field(data, WHIRLPOOL_LIQUIDITY_OFFSET)?),
+            u128::from_le_bytes(account_field(data, WHIRLPOOL_SQRT_PRICE_OFFSET)?),
+            u16::from_le_bytes(account_field(data, WHIRLPOOL_FEE_RATE_OFFSET)?),
+        )))
+    }
+
+    fn whirlpool_instruction<T: borsh::BorshSerialize>(
+        &self,
+        name: &str,
+        args: &T,
+        accounts: Vec<AccountMeta>,
+    ) -> anyhow::Result<Instruction> {
+        let mut data = anchor_discriminator(name).to_vec();
+        args.serialize(&mut data)?;
+        Ok(Instruction {
+            program_id: self.config.orca_program,
+            accounts,
+            data,
+        })
+    }
+
+    fn whirlpool_address(&self, mints: &WhirlpoolMints) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                WHIRLPOOL_SEED,
+                self.config.orca_whirlpools_config.as_ref(),
+                mints.mint_a.as_ref(),
+                mints.mint_b.as_ref(),
+                &self.config.orca_tick_spacing.to_le_bytes(),
+            ],
+            &self.config.orca_program,
+        )
+    }
+
+    fn whirlpool_fee_tier(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                FEE_TIER_SEED,
+                self.config.orca_whirlpools_config.as_ref(),
+                &self.config.orca_tick_spacing.to_le_bytes(),
+            ],
+            &self.config.orca_program,
+        )
+        .0
+    }
+
+    /// Unlike other seeds, start index is encoded as a decimal string.
+    fn whirlpool_tick_array(&self, whirlpool: &Pubkey, start_tick_index: i32) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                TICK_ARRAY_SEED,
+                whirlpool.as_ref(),
+                start_tick_index.to_string().as_bytes(),
+            ],
+            &self.config.orca_program,
+        )
+        .0
+    }
+}
+
+/// Square root of the price in Q64.64, as Whirlpool keeps it.
+fn sqrt_price_x64(price: f64) -> anyhow::Result<u128> {
+    if !price.is_finite() || price <= 0.0 {
+        anyhow::bail!("unable to price whirlpool: {price}");
+    }
+    let tick = (price.ln() / 1.0001f64.ln()).floor();
+    if tick.abs() > f64::from(MAX_TICK_INDEX) {
+        anyhow::bail!("whirlpool price {price} is out of range");
+    }
+    Ok((price.sqrt() * 2f64.powi(64)) as u128)
+}
+
+/// Full range liquidity trades as the constant product of `L / sqrt(P)` of A and `L * sqrt(P)` of B.
+fn whirlpool_reserves(
+    mints: &WhirlpoolMints,
+    liquidity: u128,
+    sqrt_price_x64: u128,
+    fee_rate: u16,
+) -> ConstantProduct {
+    let sqrt_price = sqrt_price_x64 as f64 / 2f64.powi(64);
+    let liquidity = liquidity as f64;
+    let (reserve_a, reserve_b) = if sqrt_price > 0.0 {
+        (
+            (liquidity / sqrt_price) as u64,
+            (liquidity * sqrt_price) as u64,
+        )
+    } else {
+        (0, 0)
+    };
+    let (token_reserves, sol_reserves) = if mints.token_is_a {
+        (reserve_a, reserve_b)
+    } else {
+        (reserve_b, reserve_a)
+    };
+    ConstantProduct {
+        sol_reserves,
+        token_reserves,
+        fee_numerator: u64::from(fee_rate),
+        fee_denominator: FEE_RATE_DENOMINATOR,
+    }
+}
+
+fn sqrt_price_at_tick(tick: i32) -> f64 {
+    1.0001f64.powf(f64::from(tick) / 2.0)
+}
+
+/// Tick, which range contains the price.
+fn tick_at_sqrt_price(sqrt_price_x64: u128) -> i32 {
+    let sqrt_price = sqrt_price_x64 as f64 / 2f64.powi(64);
+    (2.0 * sqrt_price.ln() / 1.0001f64.ln()).floor() as i32
+}
+
+/// The widest range, aligned to the tick spacing.
+fn full_range_ticks(tick_spacing: u16) -> (i32, i32) {
+    let spacing = i32::from(tick_spacing);
+    let upper = MAX_TICK_INDEX / spacing * spacing;
+    (-upper, upper)
+}
+
+fn tick_array_start(tick: i32, tick_spacing: u16) -> i32 {
+    let ticks_in_array = i32::from(tick_spacing) * TICK_ARRAY_SIZE;
+    tick.div_euclid(ticks_in_array) * ticks_in_array
+}
+
+/// The most liquidity, both amounts are enough for at the given price.
+fn full_range_liquidity(
+    sqrt_price_x64: u128,
+    tick_spacing: u16,
+    amount_a: u64,
+    amount_b: u64,
+) -> anyhow::Result<u128> {
+    let (lower_tick, upper_tick) = full_range_ticks(tick_spacing);
+    let lower = sqrt_price_at_tick(lower_tick);
+    let upper = sqrt_price_at_tick(upper_tick);
+    let current = sqrt_price_x64 as f64 / 2f64.powi(64);
+    if current <= lower || current >= upper {
+        anyhow::bail!("whirlpool price is out of the full range");
+    }
+    let by_a = amount_a as f64 * current * upper / (upper - current);
+    let by_b = amount_b as f64 / (current - lower);
+    Ok((by_a.min(by_b) * (1.0 - LIQUIDITY_MARGIN)) as u128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aligns_full_range_to_tick_arrays() {
+        assert_eq!(full_range_ticks(64), (-443584, 443584));
+        assert_eq!(full_range_ticks(1), (-443636, 443636));
+        assert_eq!(tick_array_start(-443584, 64), -444928);
+        assert_eq!(tick_array_start(443584, 64), 439296);
+        assert_eq!(tick_array_start(0, 64), 0);
+        assert_eq!(tick_array_start(-1, 64), -5632);
+    }
+
+    #[test]
+    fn test_prices_pool_in_q64() {
+        assert_eq!(sqrt_price_x64(1.0).unwrap(), 1 << 64);
+        assert_eq!(sqrt_price_x64(4.0).unwrap(), 2 << 64);
+        assert_eq!(tick_at_sqrt_price(1 << 64), 0);
+        // 1.0001 ^ -138163 ~ 1e-6
+        assert_eq!(tick_at_sqrt_price(sqrt_price_x64(1e-6).unwrap()), -138163);
+        assert!(sqrt_price_x64(0.0).is_err());
+        assert!(sqrt_price_x64(f64::INFINITY).is_err());
+        assert!(sqrt_price_x64(1e-30).is_err());
+    }
+
+    #[test]
+    fn test_never_requires_more_than_deposited() {
+        // 79 sols against 200M tokens with 6 decimals, token being B
+        let mints = WhirlpoolMints {
+            mint_a: WRAPPED_SOL_MINT,
+            mint_b: Pubkey::new_unique(),
+            token_is_a: false,
+        };
+        let (sols, tokens) = (79_000_000_000, 200_000_000_000_000);
+        let sqrt_price = sqrt_price_x64(mints.price(sols, tokens)).unwrap();
+        let (amount_a, amount_b) = mints.amounts(tokens, sols);
+        assert_eq!((amount_a, amount_b), (sols, tokens));
+        let liquidity = full_range_liquidity(sqrt_price, 64, amount_a, amount_b).unwrap() as f64;
+
+        let (lower_tick, upper_tick) = full_range_ticks(64);
+        let current = sqrt_price as f64 / 2f64.powi(64);
+        let upper = sqrt_price_at_tick(upper_tick);
+        let lower = sqrt_price_at_tick(lower_tick);
+        let required_a = liquidity * (upper - current) / (current * upper);
+        let required_b = liquidity * (current - lower);
+        assert!(required_a <= amount_a as f64);
+        assert!(required_b <= amount_b as f64);
+        // the price matches the deposit, so neither side is left behind
+        assert!(required_a > amount_a as f64 * 0.999);
+        assert!(required_b > amount_b as f64 * 0.999);
+    }
+
+    #[test]
+    fn test_reads_full_range_liquidity_as_reserves() {
+        let mints = WhirlpoolMints {
+            mint_a: WRAPPED_SOL_MINT,
+            mint_b: Pubkey::new_unique(),
+            token_is_a: false,
+        };
+        let (sols, tokens) = (79_000_000_000u64, 200_000_000_000_000u64);
+        let sqrt_price = sqrt_price_x64(mints.price(sols, tokens)).unwrap();
+        let (amount_a, amount_b) = mints.amounts(tokens, sols);
+        let liquidity = full_range_liquidity(sqrt_price, 64, amount_a, amount_b).unwrap();
+
+        let reserves = whirlpool_reserves(&mints, liquidity, sqrt_price, 3000);
+        assert!(reserves.sol_reserves.abs_diff(sols) < sols / 1000);
+        assert!(reserves.token_reserves.abs_diff(tokens) < tokens / 1000);
+        assert_eq!(
+            (reserves.fee_numerator, reserves.fee_denominator),
+            (3000, FEE_RATE_DENOMINATOR)
+        );
+        assert_eq!(
+            whirlpool_reserves(&mints, liquidity, 0, 3000).sol_reserves,
+            0
+        );
+    }
+}
@@ -1,8 +1,12 @@
+use crate::metrics::PUMPFUN_LIVE_FETCH;
 use anchor_client::anchor_lang::AccountDeserialize;
+use dashmap::DashMap;
 use once_cell::sync::Lazy;
+use pumpfun_cpi::BondingCurve;
 use services_common::{solana::pool::SolanaPool, utils::period_fetch::FetchExecutor};
 use solana_program::pubkey;
 use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::time::{Duration, Instant};
 
 pub static MINT_AUTHORITY: Lazy<Pubkey> = Lazy::new(get_mint_authority);
 pub static GLOBAL: Lazy<Pubkey> = Lazy::new(get_global);
@@ -20,6 +24,33 @@ pub fn get_bonding_curve(mint: &Pubkey) -> Pubkey {
     Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &pumpfun_cpi::ID).0
 }
 
+const LIVE_CURVE_TTL: Duration = Duration::from_secs(2);
+
+/// Bonding curves, fetched right before building trades, so slippage is checked against
+/// current reserves. Short TTL keeps bursts of requests from hitting RPC on each call.
+#[derive(Default)]
+pub struct LiveCurveCache {
+    curves: DashMap<Pubkey, (BondingCurve, Instant)>,
+}
+
+impl LiveCurveCache {
+    pub async fn fetch(&self, pool: &SolanaPool, mint: &Pubkey) -> anyhow::Result<BondingCurve> {
+        if let Some(cached) = self.curves.get(mint) {
+            let (curve, fetched_at) = cached.value();
+            if fetched_at.elapsed() < LIVE_CURVE_TTL {
+                return Ok(curve.clone());
+            }
+        }
+
+        PUMPFUN_LIVE_FETCH.inc();
+        let client = pool.rpc_client().use_single().await;
+        let account = client.get_account(&get_bonding_curve(mint)).await?;
+        let curve = BondingCurve::try_deserialize(&mut account.data.as_slice())?;
+        self.curves.insert(*mint, (curve.clone(), Instant::now()));
+        Ok(curve)
+    }
+}
+
 pub struct MetaFetcher {
     pub pool: SolanaPool,
 }
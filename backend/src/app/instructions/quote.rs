@@ -0,0 +1,479 @@
+use super::{meteora::MeteoraPairState, pumpfun, ProjectsOperations};
+use crate::app::{
+    exposed::BuyRoute,
+    storage::project::{CurveVariant, GraduationTarget, Stage},
+};
+use anchor_client::anchor_lang::AccountDeserialize;
+use anyhow::bail;
+use moonzip::{
+    curved_pool::{
+        curve::{BuyCalculator, CalcBuy as _, CalcSell as _, SellCalculator},
+        curved_pool_address, CurvedPool,
+    },
+    fee::TradeFee,
+};
+
+const BPS: u128 = 10_000;
+
+/// Expected outcome of spending given amount of lamports in a pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuyQuote {
+    pub expected_tokens: u64,
+    pub price_impact_bps: u64,
+}
+
+impl BuyQuote {
+    /// Impact is measured against the spot price of reserves, so pool fees are included.
+    pub fn against_reserves(
+        sols: u64,
+        expected_tokens: u64,
+        sol_reserves: u64,
+        token_reserves: u64,
+    ) -> Self {
+        let spot_tokens = sols as u128 * token_reserves as u128 / sol_reserves.max(1) as u128;
+        let price_impact_bps = if spot_tokens == 0 {
+            0
+        } else {
+            spot_tokens.saturating_sub(expected_tokens as u128) * BPS / spot_tokens
+        };
+        Self {
+            expected_tokens,
+            price_impact_bps: price_impact_bps as u64,
+        }
+    }
+
+    /// Same as [`Self::against_reserves`] for pools, which price is not a ratio of reserves.
+    pub fn at_spot_price(sols: u64, expected_tokens: u64, lamports_per_token: f64) -> Self {
+        let spot_tokens = if lamports_per_token > 0.0 {
+            sols as f64 / lamports_per_token
+        } else {
+            0.0
+        };
+        let price_impact_bps = if spot_tokens < 1.0 {
+            0.0
+        } else {
+            (spot_tokens - expected_tokens as f64).max(0.0) * BPS as f64 / spot_tokens
+        };
+        Self {
+            expected_tokens,
+            price_impact_bps: price_impact_bps as u64,
+        }
+    }
+}
+
+/// Reserves of the pool, keeping their product, with the swap fee taken from the input amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstantProduct {
+    pub sol_reserves: u64,
+    pub token_reserves: u64,
+    pub fee_numerator: u64,
+    pub fee_denominator: u64,
+}
+
+impl ConstantProduct {
+    pub fn buy_output(&self, sols: u64) -> u64 {
+        let sols_in = self.input_after_fee(sols);
+        (self.token_reserves as u128 * sols_in / (self.sol_reserves as u128 + sols_in).max(1))
+            as u64
+    }
+
+    pub fn sell_output(&self, tokens: u64) -> u64 {
+        let tokens_in = self.input_after_fee(tokens);
+        (self.sol_reserves as u128 * tokens_in / (self.token_reserves as u128 + tokens_in).max(1))
+            as u64
+    }
+
+    pub fn buy_quote(&self, sols: u64) -> BuyQuote {
+        BuyQuote::against_reserves(
+            sols,
+            self.buy_output(sols),
+            self.sol_reserves,
+            self.token_reserves,
+        )
+    }
+
+    pub fn buy_impact_pct(&self, sols: u64) -> f64 {
+        let sols_in = self.input_after_fee(sols) as u64;
+        price_impact_pct(
+            (self.sol_reserves, self.token_reserves),
+            (
+                self.sol_reserves.saturating_add(sols_in),
+                self.token_reserves.saturating_sub(self.buy_output(sols)),
+            ),
+        )
+    }
+
+    fn input_after_fee(&self, amount: u64) -> u128 {
+        let fee = (amount as u128 * self.fee_numerator as u128)
+            .div_ceil(self.fee_denominator.max(1) as u128);
+        (amount as u128).saturating_sub(fee)
+    }
+}
+
+/// DEX pool, the moonzip curve is graduated to.
+enum GraduatedPool {
+    ConstantProduct(ConstantProduct),
+    Meteora(MeteoraPairState),
+}
+
+impl GraduatedPool {
+    fn buy_quote(&self, sols: u64) -> BuyQuote {
+        match self {
+            Self::ConstantProduct(pool) => pool.buy_quote(sols),
+            Self::Meteora(pair) => pair.buy_quote(sols),
+        }
+    }
+
+    fn sell_output(&self, tokens: u64) -> u64 {
+        match self {
+            Self::ConstantProduct(pool) => pool.sell_output(tokens),
+            Self::Meteora(pair) => pair.sell_output(tokens),
+        }
+    }
+
+    fn buy_impact_pct(&self, sols: u64) -> f64 {
+        match self {
+            Self::ConstantProduct(pool) => pool.buy_impact_pct(sols),
+            Self::Meteora(pair) => pair.buy_impact_pct(sols),
+        }
+    }
+}
+
+/// Change of the spot price in percents, prices are lamports per token as `(numerator, denominator)`.
+pub fn price_impact_pct(before: (u64, u64), after: (u64, u64)) -> f64 {
+    let price = |(numerator, denominator): (u64, u64)| numerator as f64 / denominator.max(1) as f64;
+    let before = price(before);
+    if before == 0.0 {
+        return 0.0;
+    }
+    (price(after) - before) / before * 100.0
+}
+
+/// Static pool trades tokens for lamports one to one, fee is taken from lamports.
+fn static_pool_output(amount: u64, fee: TradeFee) -> u64 {
+    amount.saturating_sub(fee.compute(amount))
+}
+
+impl<'a> ProjectsOperations<'a> {
+    /// Tokens, received for `sols` by [`Self::buy`] at the current stage, without building a transaction.
+    pub async fn quote_buy(&self, sols: u64) -> anyhow::Result<u64> {
+        let project = &self.project_state.project;
+        match project.stage {
+            Stage::OnStaticPool | Stage::OnSecondStaticPool => {
+                let fee = self
+                    .mzip_meta
+                    .clone()
+                    .get()?
+                    .fee_account
+                    .on_buy(chrono::Utc::now().timestamp());
+                Ok(static_pool_output(sols, fee))
+            }
+            Stage::OnCurvePool => Ok(self.quote_curve_buy(sols).await?.expected_tokens),
+            Stage::Graduated => match project.deploy_schema.curve_pool {
+                CurveVariant::Pumpfun => Ok(self.quote_curve_buy(sols).await?.expected_tokens),
+                CurveVariant::Moonzip => match project.deploy_schema.graduation_target {
+                    GraduationTarget::Raydium => {
+                        Ok(self.quote_raydium_buy(sols).await?.expected_tokens)
+                    }
+                    GraduationTarget::Meteora | GraduationTarget::Orca => Ok(self
+                        .fetch_graduated_pool()
+                        .await?
+                        .buy_quote(sols)
+                        .expected_tokens),
+                },
+                CurveVariant::Meteora => {
+                    bail!("meteora pools are not quoted, trade there directly")
+                }
+            },
+            stage => bail!("unable to quote buy from project: stage mismatch: {stage:?}"),
+        }
+    }
+
+    /// Lamports, received for `tokens` by [`Self::sell`] at the current stage, without building a transaction.
+    pub async fn quote_sell(&self, tokens: u64) -> anyhow::Result<u64> {
+        let project = &self.project_state.project;
+        match project.stage {
+            Stage::OnStaticPool | Stage::OnSecondStaticPool => {
+                let fee = self
+                    .mzip_meta
+                    .clone()
+                    .get()?
+                    .fee_account
+                    .on_sell(chrono::Utc::now().timestamp());
+                Ok(static_pool_output(tokens, fee))
+            }
+            Stage::OnCurvePool => self.quote_curve_sell(tokens).await,
+            Stage::Graduated => match project.deploy_schema.curve_pool {
+                CurveVariant::Pumpfun => self.quote_curve_sell(tokens).await,
+                CurveVariant::Moonzip => Ok(self.fetch_graduated_pool().await?.sell_output(tokens)),
+                CurveVariant::Meteora => {
+                    bail!("meteora pools are not quoted, trade there directly")
+                }
+            },
+            stage => bail!("unable to quote sell to project: stage mismatch: {stage:?}"),
+        }
+    }
+
+    /// Percents, the spot price moves by, once `sols` are spent by [`Self::buy`] at the current stage.
+    /// Static pool sells at the fixed price, so it has no impact.
+    pub async fn calculate_price_impact(&self, sols: u64) -> anyhow::Result<f64> {
+        let project = &self.project_state.project;
+        let curve_mint = self.curve_mint()?;
+        match (project.stage, project.deploy_schema.curve_pool) {
+            (Stage::OnStaticPool | Stage::OnSecondStaticPool, _) => Ok(0.0),
+            (Stage::OnCurvePool, CurveVariant::Moonzip) => {
+                let client = self.solana_pool.rpc_client().use_single().await;
+                let account = client.get_account(&curved_pool_address(curve_mint)).await?;
+                let pool = CurvedPool::try_deserialize(&mut account.data.as_slice())?;
+                let fee = self
+                    .mzip_meta
+                    .clone()
+                    .get()?
+                    .fee_account
+                    .on_buy(chrono::Utc::now().timestamp());
+                let tokens = BuyCalculator::new(&pool.curve)
+                    .with_fee(fee)
+                    .fixed_sols(sols)
+                    .min(pool.curve.real_token_reserves);
+                let mut after = pool.curve;
+                after.commit_buy(sols.saturating_sub(fee.compute(sols)), tokens);
+                Ok(price_impact_pct(
+                    pool.curve.spot_price(),
+                    after.spot_price(),
+                ))
+            }
+            (Stage::OnCurvePool | Stage::Graduated, CurveVariant::Pumpfun) => {
+                let curve = self
+                    .pumpfun_curves
+                    .fetch(self.solana_pool, &curve_mint)
+                    .await?;
+                let curve = moonzip::pumpfun::CurveWrapper::from(curve);
+                let tokens = moonzip::pumpfun::BuyCalculator::from_curve_wrapper(&curve)
+                    .fixed_sols(sols)
+                    .tokens;
+                Ok(price_impact_pct(
+                    (curve.virtual_sol_reserves, curve.virtual_token_reserves),
+                    (
+                        curve.virtual_sol_reserves + moonzip::pumpfun::BUY_FEE.accounting(sols),
+                        curve.virtual_token_reserves.saturating_sub(tokens),
+                    ),
+                ))
+            }
+            (Stage::Graduated, CurveVariant::Moonzip) => {
+                Ok(self.fetch_graduated_pool().await?.buy_impact_pct(sols))
+            }
+            (stage, _) => bail!("unable to estimate price impact: stage mismatch: {stage:?}"),
+        }
+    }
+
+    /// Pool of the graduation target, the closed moonzip curve is deployed to.
+    async fn fetch_graduated_pool(&self) -> anyhow::Result<GraduatedPool> {
+        let project = &self.project_state.project;
+        let target = project.deploy_schema.graduation_target;
+        let pool = match target {
+            GraduationTarget::Raydium => self
+                .fetch_raydium_pool_state()
+                .await?
+                .map(|state| GraduatedPool::ConstantProduct((&state).into())),
+            GraduationTarget::Orca => self
+                .fetch_whirlpool_reserves()
+                .await?
+                .map(GraduatedPool::ConstantProduct),
+            GraduationTarget::Meteora => self
+                .fetch_meteora_pair_state()
+                .await?
+                .map(GraduatedPool::Meteora),
+        };
+        pool.ok_or_else(|| anyhow::anyhow!("{target:?} pool of project {} is missing", project.id))
+    }
+
+    async fn quote_curve_sell(&self, tokens: u64) -> anyhow::Result<u64> {
+        let curve_mint = self.curve_mint()?;
+        match self.project_state.project.deploy_schema.curve_pool {
+            CurveVariant::Moonzip => {
+                let client = self.solana_pool.rpc_client().use_single().await;
+                let account = client.get_account(&curved_pool_address(curve_mint)).await?;
+                let pool = CurvedPool::try_deserialize(&mut account.data.as_slice())?;
+                let fee = self
+                    .mzip_meta
+                    .clone()
+                    .get()?
+                    .fee_account
+                    .on_sell(chrono::Utc::now().timestamp());
+                Ok(SellCalculator::new(&pool.curve)
+                    .with_fee(fee)
+                    .fixed_tokens(tokens))
+            }
+            CurveVariant::Pumpfun => {
+                let curve = self
+                    .pumpfun_curves
+                    .fetch(self.solana_pool, &curve_mint)
+                    .await?;
+                let curve = moonzip::pumpfun::CurveWrapper::from(curve);
+                Ok(moonzip::pumpfun::SellCalculator::new(&curve).fixed_tokens(tokens))
+            }
+            CurveVariant::Meteora => bail!("meteora launch has no curve to sell to"),
+        }
+    }
+
+    /// Pools, accepting buys at the current stage, in the same way [`Self::buy`] dispatches them.
+    /// Curve pool is closed on graduation, so graduated moonzip curve is traded on raydium only.
+    pub fn buy_routes(&self) -> Vec<BuyRoute> {
+        match (
+            self.project_state.project.stage,
+            self.project_state.project.deploy_schema.curve_pool,
+        ) {
+            (Stage::OnCurvePool, _) | (Stage::Graduated, CurveVariant::Pumpfun) => {
+                vec![BuyRoute::Curve]
+            }
+            (Stage::Graduated, CurveVariant::Moonzip)
+                if self.project_state.project.deploy_schema.graduation_target
+                    == GraduationTarget::Raydium =>
+            {
+                vec![BuyRoute::Raydium]
+            }
+            _ => vec![],
+        }
+    }
+
+    pub async fn quote_buy_via(&self, route: BuyRoute, sols: u64) -> anyhow::Result<BuyQuote> {
+        match route {
+            BuyRoute::Curve => self.quote_curve_buy(sols).await,
+            BuyRoute::Raydium => self.quote_raydium_buy(sols).await,
+        }
+    }
+
+    pub async fn quote_curve_buy(&self, sols: u64) -> anyhow::Result<BuyQuote> {
+        let curve_mint = self.curve_mint()?;
+        let client = self.solana_pool.rpc_client().use_single().await;
+
+        match self.project_state.project.deploy_schema.curve_pool {
+            CurveVariant::Moonzip => {
+                let account = client.get_account(&curved_pool_address(curve_mint)).await?;
+                let pool = CurvedPool::try_deserialize(&mut account.data.as_slice())?;
+                let fee = self
+                    .mzip_meta
+                    .clone()
+                    .get()?
+                    .fee_account
+                    .on_buy(chrono::Utc::now().timestamp());
+                let tokens = BuyCalculator::new(&pool.curve)
+                    .with_fee(fee)
+                    .fixed_sols(sols);
+                let (price_numerator, price_denominator) = pool.curve.spot_price();
+                Ok(BuyQuote::against_reserves(
+                    sols,
+                    tokens,
+                    price_numerator,
+                    price_denominator,
+                ))
+            }
+            CurveVariant::Pumpfun => {
+                let account = client
+                    .get_account(&pumpfun::get_bonding_curve(&curve_mint))
+                    .await?;
+                let curve =
+                    pumpfun_cpi::BondingCurve::try_deserialize(&mut account.data.as_slice())?;
+                let tokens = moonzip::pumpfun::BuyCalculator::from_cpi_curve(&curve)
+                    .fixed_sols(sols)
+                    .tokens;
+                Ok(BuyQuote::against_reserves(
+                    sols,
+                    tokens,
+                    curve.virtual_sol_reserves,
+                    curve.virtual_token_reserves,
+                ))
+            }
+            CurveVariant::Meteora => bail!("meteora launch has no curve to buy from"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measures_spot_price_change_in_percents() {
+        assert_eq!(price_impact_pct((1, 100), (1, 100)), 0.0);
+        // doubled lamports and halved tokens make the price 4 times higher
+        assert_eq!(price_impact_pct((30, 1000), (60, 500)), 300.0);
+        assert_eq!(price_impact_pct((0, 1000), (60, 500)), 0.0);
+    }
+
+    #[test]
+    fn test_has_no_impact_at_spot_price() {
+        let quote = BuyQuote::against_reserves(1_000, 2_000, 30_000, 60_000);
+        assert_eq!(quote.price_impact_bps, 0);
+        assert_eq!(quote.expected_tokens, 2_000);
+    }
+
+    #[test]
+    fn test_measures_impact_against_spot_price() {
+        // constant product: 60_000 - 30_000 * 60_000 / 31_000
+        let quote = BuyQuote::against_reserves(1_000, 1_935, 30_000, 60_000);
+        assert_eq!(quote.price_impact_bps, 325);
+    }
+
+    #[test]
+    fn test_tolerates_empty_reserves() {
+        let quote = BuyQuote::against_reserves(1_000, 0, 0, 0);
+        assert_eq!(quote.price_impact_bps, 0);
+    }
+
+    #[test]
+    fn test_measures_impact_against_spot_price_of_bins() {
+        assert_eq!(
+            BuyQuote::at_spot_price(1_000, 2_000, 0.5).price_impact_bps,
+            0
+        );
+        assert_eq!(
+            BuyQuote::at_spot_price(1_000, 1_900, 0.5).price_impact_bps,
+            500
+        );
+        assert_eq!(BuyQuote::at_spot_price(1_000, 0, 0.0).price_impact_bps, 0);
+    }
+
+    #[test]
+    fn test_quotes_constant_product_both_ways() {
+        let pool = ConstantProduct {
+            sol_reserves: 30_000,
+            token_reserves: 60_000,
+            fee_numerator: 0,
+            fee_denominator: 10_000,
+        };
+        // 60_000 - 30_000 * 60_000 / 31_000
+        assert_eq!(pool.buy_output(1_000), 1_935);
+        assert_eq!(pool.buy_quote(1_000).price_impact_bps, 325);
+        // 31_000 / 58_065 against 30_000 / 60_000
+        assert!((pool.buy_impact_pct(1_000) - 6.777).abs() < 1e-3);
+        assert_eq!(pool.sell_output(2_000), 967);
+    }
+
+    #[test]
+    fn test_quotes_static_pool_with_fee() {
+        use moonzip::fee::BasisPoints;
+
+        assert_eq!(static_pool_output(10_000, BasisPoints(100).into()), 9_900);
+        assert_eq!(static_pool_output(10_000, TradeFee::Flat(250)), 9_750);
+        assert_eq!(static_pool_output(100, TradeFee::Flat(250)), 0);
+    }
+
+    #[test]
+    fn test_quotes_raydium_sell_with_input_fee() {
+        use crate::app::instructions::raydium::RaydiumPoolState;
+        use solana_sdk::pubkey::Pubkey;
+
+        let state = RaydiumPoolState {
+            amm_pool: Pubkey::default(),
+            sol_reserves: 30_000,
+            token_reserves: 60_000,
+            slot: 0,
+            swap_fee_numerator: 25,
+            swap_fee_denominator: 10_000,
+        };
+        // 25 bps of 4_000 tokens is 10, then 30_000 * 3_990 / 63_990
+        assert_eq!(state.sell_output(4_000), 1_870);
+        assert_eq!(state.sell_output(0), 0);
+    }
+}
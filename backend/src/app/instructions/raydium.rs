@@ -1,8 +1,12 @@
-use super::{BuyParams, ProjectsOperations, SellParams, WRAPPED_SOL_MINT};
+use super::{
+    quote::{BuyQuote, ConstantProduct},
+    BuyParams, ProjectsOperations, SellParams, WRAPPED_SOL_MINT,
+};
 use anchor_spl::associated_token::{
     get_associated_token_address,
     spl_associated_token_account::instruction::create_associated_token_account_idempotent,
 };
+use anyhow::bail;
 use moonzip::PROGRAM_AUTHORITY;
 use once_cell::sync::OnceCell;
 use raydium_amm::{
@@ -11,17 +15,59 @@ use raydium_amm::{
         get_associated_address_and_bump_seed, AMM_CONFIG_SEED, AUTHORITY_AMM,
         COIN_VAULT_ASSOCIATED_SEED, PC_VAULT_ASSOCIATED_SEED,
     },
+    state::{AmmInfo, Loadable as _},
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_config::{
+    RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
 };
 use solana_sdk::{
+    account::Account,
     instruction::Instruction,
     program_pack::Pack,
     pubkey::Pubkey,
     system_instruction::{self, create_account_with_seed},
+    transaction::Transaction,
 };
 
 static AMM_AUTHORITY: OnceCell<(Pubkey, u8)> = OnceCell::new();
 static AMM_CONFIG: OnceCell<(Pubkey, u8)> = OnceCell::new();
 
+/// Reserves of the graduated project pool, sol is the coin and project token is the pc side.
+#[derive(Debug, Clone)]
+pub struct RaydiumPoolState {
+    pub amm_pool: Pubkey,
+    pub sol_reserves: u64,
+    pub token_reserves: u64,
+    pub slot: u64,
+    /// Swap fee is taken from the input amount.
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+}
+
+impl RaydiumPoolState {
+    /// Absent for the drained pool.
+    pub fn price_lamports_per_token(&self) -> Option<f64> {
+        (self.token_reserves > 0).then(|| self.sol_reserves as f64 / self.token_reserves as f64)
+    }
+
+    /// Lamports, received for selling `tokens` to the pool, as raydium computes `swap_base_in`.
+    pub fn sell_output(&self, tokens: u64) -> u64 {
+        ConstantProduct::from(self).sell_output(tokens)
+    }
+}
+
+impl From<&RaydiumPoolState> for ConstantProduct {
+    fn from(state: &RaydiumPoolState) -> Self {
+        Self {
+            sol_reserves: state.sol_reserves,
+            token_reserves: state.token_reserves,
+            fee_numerator: state.swap_fee_numerator,
+            fee_denominator: state.swap_fee_denominator,
+        }
+    }
+}
+
 impl<'a> ProjectsOperations<'a> {
     pub fn deploy_to_raydium(
         &self,
@@ -117,6 +163,8 @@ impl<'a> ProjectsOperations<'a> {
             close_user_wrapped_sol_account,
         ];
 
+        let mut emit_burn = self.emit_liquidity_burn(amm_lp_mint)?;
+        ixs.append(&mut emit_burn);
         let mut burn_and_close_lp = self.burn_and_close(PROGRAM_AUTHORITY, amm_lp_mint)?;
         ixs.append(&mut burn_and_close_lp);
 
@@ -179,6 +227,71 @@ impl<'a> ProjectsOperations<'a> {
         ])
     }
 
+    /// Simulates the swap on behalf of the authority, as quoting user may have no funds at all.
+    pub async fn quote_raydium_buy(&self, sols: u64) -> anyhow::Result<BuyQuote> {
+        let curve_mint = self.curve_mint()?;
+        let quoter = PROGRAM_AUTHORITY;
+        let destination = get_associated_token_address(&quoter, &curve_mint);
+        let ixs = self.buy_from_raydium(BuyParams {
+            user: quoter,
+            sols,
+            min_token_output: 0,
+        })?;
+        let tx = Transaction::new_with_payer(&ixs, Some(&quoter));
+
+        let client = self.solana_pool.rpc_client().use_single().await;
+        let commitment = client.commitment();
+        let balance_before = client
+            .get_account_with_commitment(&destination, commitment)
+            .await?
+            .value
+            .map(|account| token_amount(&account))
+            .transpose()?
+            .unwrap_or(0);
+        let simulation = client
+            .simulate_transaction_with_config(
+                &tx,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    commitment: Some(commitment),
+                    accounts: Some(RpcSimulateTransactionAccountsConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        addresses: vec![destination.to_string()],
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await?
+            .value;
+        if let Some(err) = simulation.err {
+            bail!("raydium swap simulation failed: {err}");
+        }
+        let balance_after = simulation
+            .accounts
+            .and_then(|accounts| accounts.into_iter().next().flatten())
+            .and_then(|account| account.decode::<Account>())
+            .ok_or_else(|| anyhow::anyhow!("no destination account in simulation result"))
+            .and_then(|account| token_amount(&account))?;
+
+        let sol_reserves = client
+            .get_token_account_balance(&self.amm_coin_vault())
+            .await?
+            .amount
+            .parse()?;
+        let token_reserves = client
+            .get_token_account_balance(&self.amm_pc_vault())
+            .await?
+            .amount
+            .parse()?;
+        Ok(BuyQuote::against_reserves(
+            sols,
+            balance_after.saturating_sub(balance_before),
+            sol_reserves,
+            token_reserves,
+        ))
+    }
+
     pub fn sell_to_raydium(&self, params: SellParams) -> anyhow::Result<Vec<Instruction>> {
         let curve_mint = self.curve_mint()?;
         let market = self.openbook_market_address().key;
@@ -240,6 +353,38 @@ impl<'a> ProjectsOperations<'a> {
         ])
     }
 
+    /// Absent until the project is deployed to raydium.
+    /// Vault balances include pnl, not yet taken by the pool, so it's subtracted from reserves.
+    pub async fn fetch_raydium_pool_state(&self) -> anyhow::Result<Option<RaydiumPoolState>> {
+        let amm_pool = self.amm_pool();
+        let client = self.solana_pool.rpc_client().use_single().await;
+        let commitment = client.commitment();
+        let Some(pool_account) = client
+            .get_account_with_commitment(&amm_pool, commitment)
+            .await?
+            .value
+        else {
+            return Ok(None);
+        };
+        let amm = AmmInfo::load_from_bytes(&pool_account.data)?;
+
+        let vaults = client
+            .get_multiple_accounts_with_commitment(&[amm.coin_vault, amm.pc_vault], commitment)
+            .await?;
+        let [Some(coin_vault), Some(pc_vault)] = vaults.value.as_slice() else {
+            bail!("vaults of raydium pool {amm_pool} are missing");
+        };
+        Ok(Some(RaydiumPoolState {
+            amm_pool,
+            sol_reserves: token_amount(coin_vault)?
+                .saturating_sub(amm.state_data.need_take_pnl_coin),
+            token_reserves: token_amount(pc_vault)?.saturating_sub(amm.state_data.need_take_pnl_pc),
+            slot: vaults.context.slot,
+            swap_fee_numerator: amm.fees.swap_fee_numerator,
+            swap_fee_denominator: amm.fees.swap_fee_denominator,
+        }))
+    }
+
     fn amm_coin_vault(&self) -> Pubkey {
         let (associated_token_address, _) = get_associated_address_and_bump_seed(
             &self.config.raydium_program,
@@ -317,3 +462,7 @@ impl<'a> ProjectsOperations<'a> {
         })
     }
 }
+
+fn token_amount(account: &Account) -> anyhow::Result<u64> {
+    Ok(spl_token::state::Account::unpack(&account.data)?.amount)
+}
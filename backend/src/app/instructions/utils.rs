@@ -1,7 +1,68 @@
+use sha2::{Digest, Sha256};
 use solana_sdk::pubkey::Pubkey;
 
 const EVENT_AUTHORTIY_PREFIX: &[u8] = b"__event_authority";
 
+/// Address of the anchor `#[event_cpi]` signer for the given program.
 pub fn anchor_event_authority(program_id: &Pubkey) -> Pubkey {
-    Pubkey::find_program_address(&[EVENT_AUTHORTIY_PREFIX], program_id).0
+    anchor_event_authority_with_bump(program_id).0
+}
+
+pub fn anchor_event_authority_with_bump(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EVENT_AUTHORTIY_PREFIX], program_id)
+}
+
+/// Instruction discriminator of anchor programs, which crates are not depended on.
+pub fn anchor_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("global:{name}").as_bytes());
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+/// Bytes of the fixed offset field of an account, which program crate is not depended on.
+pub fn account_field<const N: usize>(data: &[u8], offset: usize) -> anyhow::Result<[u8; N]> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| anyhow::anyhow!("account of {} bytes has no field at {offset}", data.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::instructions::pumpfun;
+    use solana_program::pubkey;
+
+    #[test]
+    fn test_matches_known_pumpfun_event_authority() {
+        assert_eq!(
+            anchor_event_authority(&pumpfun_cpi::ID),
+            pumpfun::EVENT_AUTHORITY
+        );
+    }
+
+    #[test]
+    fn test_derives_mainnet_moonzip_event_authority() {
+        let mainnet_program = pubkey!("544hmhQ5N72wv8aJFz92sgRMnDEqwmSuzGtG8T8CPgNb");
+        assert_eq!(
+            anchor_event_authority_with_bump(&mainnet_program),
+            (pubkey!("3NWrmHT5PvMSbs2uYT9z4GuZkxPkSKmJn5iZ1Cbf5d34"), 255)
+        );
+    }
+
+    #[test]
+    fn test_reads_account_fields_within_bounds() {
+        let data = [1, 2, 3, 4];
+        assert_eq!(account_field::<2>(&data, 2).unwrap(), [3, 4]);
+        assert!(account_field::<2>(&data, 3).is_err());
+    }
+
+    #[test]
+    fn test_derives_anchor_discriminators() {
+        // sha256("global:initialize_lb_pair")[..8]
+        assert_eq!(
+            anchor_discriminator("initialize_lb_pair"),
+            [45, 154, 237, 210, 221, 15, 166, 92]
+        );
+    }
 }
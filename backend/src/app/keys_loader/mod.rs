@@ -1,4 +1,4 @@
-use super::storage::misc::StoredKeypair;
+use super::{migrator::keys_provider::KeysProvider, storage::misc::StoredKeypair};
 use crate::app::storage::StorageClient;
 use serde::{Deserialize, Serialize};
 use solana_sdk::{signature::Keypair, signer::Signer};
@@ -61,6 +61,9 @@ impl KeysLoader {
             }
         }
 
+        KeysProvider::new(self.storage_client.clone())
+            .refresh_available()
+            .await?;
         Ok(())
     }
 
@@ -0,0 +1,102 @@
+use super::{
+    exposed::DeliveryMethod,
+    instructions::InstructionsConfig,
+    storage::{
+        config_overrides::{load_override, ConfigKey},
+        StorageClient,
+    },
+};
+use solana_sdk::pubkey::Pubkey;
+use std::{str::FromStr, sync::Arc, time::Duration};
+use tokio::{
+    sync::{RwLock, RwLockReadGuard},
+    task::JoinHandle,
+};
+use tracing::{error, info};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Instructions config from the config file with overrides, set by admins at runtime.
+#[derive(Clone)]
+pub struct LiveConfig {
+    base: Arc<InstructionsConfig>,
+    current: Arc<RwLock<InstructionsConfig>>,
+}
+
+impl LiveConfig {
+    pub async fn load(
+        storage: &StorageClient,
+        base: Arc<InstructionsConfig>,
+    ) -> anyhow::Result<Self> {
+        let config = Self {
+            current: Arc::new(RwLock::new(base.as_ref().clone())),
+            base,
+        };
+        config.refresh(storage).await?;
+        Ok(config)
+    }
+
+    pub async fn read(&self) -> RwLockReadGuard<'_, InstructionsConfig> {
+        self.current.read().await
+    }
+
+    /// Overrides, removed from the database, fall back to config file values.
+    pub async fn refresh(&self, storage: &StorageClient) -> anyhow::Result<()> {
+        let launch_periods = load_override::<Vec<u64>, _>(&storage.pool, ConfigKey::LaunchPeriods)
+            .await?
+            .map(|periods| periods.into_iter().map(Duration::from_secs).collect())
+            .unwrap_or_else(|| self.base.allowed_launch_periods.clone());
+        let delivery_methods =
+            load_override::<Vec<DeliveryMethod>, _>(&storage.pool, ConfigKey::DeliveryMethods)
+                .await?
+                .unwrap_or_else(|| self.base.allowed_delivery_methods.clone());
+        let allowed_owners =
+            load_override::<Vec<String>, _>(&storage.pool, ConfigKey::AllowedOwners)
+                .await?
+                .map(|owners| {
+                    owners
+                        .iter()
+                        .map(|owner| Pubkey::from_str(owner))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()?
+                .unwrap_or_else(|| self.base.allowed_owners.clone());
+
+        let mut current = self.current.write().await;
+        if current.allowed_launch_periods != launch_periods {
+            info!(
+                "allowed launch periods changed from {:?} to {:?}",
+                current.allowed_launch_periods, launch_periods
+            );
+            current.allowed_launch_periods = launch_periods;
+        }
+        if current.allowed_delivery_methods != delivery_methods {
+            info!(
+                "allowed delivery methods changed from {:?} to {:?}",
+                current.allowed_delivery_methods, delivery_methods
+            );
+            current.allowed_delivery_methods = delivery_methods;
+        }
+        if current.allowed_owners != allowed_owners {
+            info!(
+                "allowed owners changed from {:?} to {:?}",
+                current.allowed_owners, allowed_owners
+            );
+            current.allowed_owners = allowed_owners;
+        }
+        Ok(())
+    }
+
+    /// Picks up overrides, changed by other backend instances.
+    pub fn serve_refresh(&self, storage: StorageClient) -> JoinHandle<()> {
+        let config = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(REFRESH_INTERVAL).await;
+                if let Err(err) = config.refresh(&storage).await {
+                    error!("config overrides refresh failed: {err:#}");
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,79 @@
+use super::{txs::TransactionRequest, Tools};
+use crate::{app::instructions::mzip::FEE_ACCOUNT, metrics::FEE_SWEPT_LAMPORTS};
+use moonzip::fee::FeeAccount;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+/// Periodically moves collected fee to the receiver, configured in the fee account.
+pub(super) struct FeeSweeper {
+    pub(super) tools: Tools,
+    pub(super) interval: Duration,
+    pub(super) min_sweep_lamports: u64,
+}
+
+impl FeeSweeper {
+    pub(super) fn serve(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = self.tick().await {
+                    error!("fee sweep tick failed: {err:#}");
+                }
+                tokio::time::sleep(self.interval).await;
+            }
+        })
+    }
+
+    async fn tick(&self) -> anyhow::Result<()> {
+        let balance = self
+            .tools
+            .instructions_builder
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .get_balance(&FEE_ACCOUNT)
+            .await?;
+        let rent = self
+            .tools
+            .instructions_builder
+            .solana_meta
+            .clone()
+            .get()?
+            .rent
+            .minimum_balance(FeeAccount::ACCOUNT_SIZE);
+        let sweepable = balance.saturating_sub(rent);
+        if sweepable < self.min_sweep_lamports {
+            debug!("fee account holds {sweepable} sweepable lamports, below threshold");
+            return Ok(());
+        }
+
+        let fee_receiver = self
+            .tools
+            .moonzip_meta_rx
+            .clone()
+            .get()?
+            .fee_account
+            .fee_receiver;
+        let authority = self.tools.solana_keys.authority_keypair().to_keypair();
+        self.tools
+            .tx_executor
+            .execute_single(TransactionRequest {
+                instructions: self.tools.instructions_builder.sweep_fee(fee_receiver)?,
+                signers: vec![authority.insecure_clone()],
+                payer: authority,
+                project_id: None,
+                memo: self
+                    .tools
+                    .instructions_builder
+                    .config
+                    .attach_memos
+                    .then(|| "moonzip:sweep_fee".to_owned()),
+            })
+            .await?;
+
+        info!("swept {sweepable} lamports of fee to {fee_receiver}");
+        FEE_SWEPT_LAMPORTS.inc_by(sweepable);
+        Ok(())
+    }
+}
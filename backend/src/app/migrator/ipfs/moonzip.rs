@@ -1,4 +1,4 @@
-use crate::app::storage::project::ImageStream;
+use crate::app::{image_format::ImageFormat, storage::project::ImageStream};
 use derive_more::Into;
 
 use serde::Deserialize;
@@ -70,14 +70,15 @@ impl IpfsClient {
     pub async fn upload_image(
         &self,
         image_content: ImageStream<'_>,
+        image_format: ImageFormat,
         name: &str,
     ) -> anyhow::Result<String> {
         let form = Form::new()
             .part(
                 "file",
                 Part::stream(image_content)
-                    .file_name(format!("{}.png", name))
-                    .mime_str("image/png")?,
+                    .file_name(format!("{}.{}", name, image_format.extension()))
+                    .mime_str(image_format.mime())?,
             )
             .part(
                 "pinataMetadata",
@@ -170,7 +171,9 @@ pub(crate) mod tests {
         let client = client().await;
 
         let image_content = ImageStream::from_file(&path)?;
-        let result = client.upload_image(image_content, "moon").await?;
+        let result = client
+            .upload_image(image_content, ImageFormat::Png, "moon")
+            .await?;
         println!("{:?}", result);
         let response = reqwest::get(result).await;
         assert!(
@@ -1,4 +1,4 @@
-use crate::app::storage::project::ImageStream;
+use crate::app::{image_format::ImageFormat, storage::project::ImageStream};
 use reqwest::multipart::{Form, Part};
 use serde::{Deserialize, Serialize};
 use services_common::utils::decode_response_type_or_raw;
@@ -22,6 +22,7 @@ pub struct CreateTokenMetadata<'a> {
     /// Description of the token
     pub description: String,
     pub image_content: ImageStream<'a>,
+    pub image_format: ImageFormat,
     /// Optional Twitter handle
     pub twitter: Option<String>,
     /// Optional Telegram group
@@ -76,7 +77,7 @@ impl PumpfunIpfsClient {
         // Add the image file part
         let image_part = Part::stream(metadata.image_content)
             .file_name("file")
-            .mime_str("application/octet-stream")?;
+            .mime_str(metadata.image_format.mime())?;
         form = form.part("file", image_part);
 
         // Send the request
@@ -0,0 +1,66 @@
+use crate::{
+    app::storage::{misc::StoredKeypair, project::ProjectId, DBTransaction, StorageClient},
+    metrics::MZIP_KEYPAIR_AVAILABLE,
+};
+use anyhow::anyhow;
+use solana_sdk::signature::Keypair;
+use std::ops::DerefMut;
+use tracing::warn;
+
+/// Hands out pre-generated mint keypairs, loaded by the keys loader,
+/// so every mint used by projects is tracked in `mzip_keypair_in_use`.
+#[derive(Clone)]
+pub struct KeysProvider {
+    storage: StorageClient,
+}
+
+impl KeysProvider {
+    pub fn new(storage: StorageClient) -> Self {
+        Self { storage }
+    }
+
+    /// Claims a free keypair and assigns it to the project within the given transaction,
+    /// so keypair returns to the pool if the transaction is rolled back.
+    pub async fn next(
+        &self,
+        tx: &mut DBTransaction<'_>,
+        project_id: ProjectId,
+    ) -> anyhow::Result<Keypair> {
+        let claimed: Option<StoredKeypair> = sqlx::query_scalar(
+            "
+                DELETE FROM mzip_keypair
+                WHERE keypair = (SELECT keypair FROM mzip_keypair LIMIT 1 FOR UPDATE SKIP LOCKED)
+                RETURNING keypair
+            ",
+        )
+        .fetch_optional(tx.deref_mut())
+        .await?;
+        let claimed = claimed.ok_or_else(|| {
+            anyhow!("no free mint keypairs left, add more into the keys loader directory")
+        })?;
+
+        sqlx::query("INSERT INTO mzip_keypair_in_use (kp, project_id) VALUES ($1, $2)")
+            .bind(&claimed)
+            .bind(project_id)
+            .execute(tx.deref_mut())
+            .await?;
+        sqlx::query("UPDATE project SET curve_pool_keypair = $1 WHERE id = $2")
+            .bind(&claimed)
+            .bind(project_id)
+            .execute(tx.deref_mut())
+            .await?;
+
+        if let Err(err) = self.refresh_available().await {
+            warn!("failed to refresh available mint keypairs: {err:#}");
+        }
+        Ok(claimed.to_keypair())
+    }
+
+    pub async fn refresh_available(&self) -> anyhow::Result<()> {
+        let available: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM mzip_keypair")
+            .fetch_one(&self.storage.pool)
+            .await?;
+        MZIP_KEYPAIR_AVAILABLE.set(available);
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{
+    app::storage::{migration_lock::StoredMigrationLock, StorageClient},
+    metrics::MIGRATION_LOCK_STUCK,
+};
+use services_common::TZ;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+/// Removes expired leases of hung migrations, so they never commit and projects are retried.
+pub(super) struct LockCleaner {
+    pub(super) storage: StorageClient,
+    pub(super) interval: Duration,
+}
+
+impl LockCleaner {
+    pub(super) fn serve(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = self.tick().await {
+                    error!("migration lock cleanup failed: {err:#}");
+                }
+                tokio::time::sleep(self.interval).await;
+            }
+        })
+    }
+
+    async fn tick(&self) -> anyhow::Result<()> {
+        let released = StoredMigrationLock::release_expired(&self.storage.pool).await?;
+        for lock in released {
+            warn!(
+                "released migration lease of project {}, held by {} for {}s",
+                lock.project_id,
+                lock.owner,
+                (TZ::now() - lock.locked_since).num_seconds()
+            );
+            MIGRATION_LOCK_STUCK.inc();
+        }
+        Ok(())
+    }
+}
@@ -1,33 +1,72 @@
 use super::{
-    instructions::{mzip, pumpfun, CurveCreate, InitialPurchase, InstructionsBuilder},
+    image_format::{self, ImageFormat, InvalidImage},
+    instructions::{
+        mzip, orca::WhirlpoolKeys, pumpfun, CurveCreate, InitialPurchase, InstructionsBuilder,
+    },
     storage::{
-        project::{self, CurveVariant, FullProjectState, ImageStream, ProjectId, StoredTokenMeta},
+        ipfs_uploads::{self, IpfsUploadKind, StoredImageDigest},
+        migration_lock::{AdvisoryLock, StoredMigrationLock},
+        project::{
+            self, CurveVariant, FullProjectState, GraduationTarget, ImageStream, ProjectId,
+            SortDir, StoredProject, StoredTokenMeta,
+        },
         DBTransaction, StorageClient,
     },
 };
-use crate::solana::SolanaKeys;
-use anyhow::bail;
+use crate::{
+    metrics::{
+        IPFS_DEDUP_SAVED, JITO_TIP_KEY_USED, MIGRATOR_ADVISORY_LOCK_CONTENTION,
+        MIGRATOR_GRACEFUL_SHUTDOWN_DURATION,
+    },
+    solana::SolanaKeys,
+    utils::to_hex,
+};
+use anyhow::{bail, Context as _};
 use chrono::DateTime;
-use const_format::concatcp;
 use derive_more::derive::Deref;
-use moonzip::PROGRAM_AUTHORITY;
-use rust_decimal::prelude::Zero;
+use fee_sweep::FeeSweeper;
+use keys_provider::KeysProvider;
+use lock_cleanup::LockCleaner;
+use moonzip::{
+    static_pool::{static_pool_address, StaticPoolState},
+    PROGRAM_AUTHORITY,
+};
+use raydium_sync::RaydiumStateSyncer;
 use serde::{Deserialize, Serialize};
 use services_common::{
     solana::{jito, pool::SolanaPool},
     utils::period_fetch::{DataReceiver, PeriodicFetcher, PeriodicFetcherConfig},
     TZ,
 };
-use solana_sdk::signer::Signer;
-use sqlx::{query, query_as};
-use std::{ops::DerefMut, sync::Arc, time::Duration};
-use tokio::{spawn, task::JoinHandle};
+use sha2::{Digest, Sha256};
+use solana_sdk::{
+    commitment_config::CommitmentLevel, instruction::Instruction, native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey, signature::Keypair, signer::Signer,
+};
+use sqlx::query_as;
+use std::{
+    iter,
+    ops::DerefMut,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tip_strategy::TipStrategyWatcher;
+use tokio::task::{JoinError, JoinHandle, JoinSet};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, instrument, warn};
 use txs::{TransactionRequest, TxExecutor, TxExecutorConfig};
+use uuid::Uuid;
 
 const DEV_WEBSITE: &str = "https://moon.zip";
+/// Each record adds the record and its wallet to the transaction.
+const WALLET_RECORDS_PER_TX: usize = 10;
 
+mod fee_sweep;
 pub mod ipfs;
+pub mod keys_provider;
+mod lock_cleanup;
+mod raydium_sync;
+mod tip_strategy;
 pub mod txs;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,19 +76,72 @@ pub struct MigratorConfig {
     pub mzip_ipfs: ipfs::moonzip::IpfsClientConfig,
     pub pumpfun_ipfs: ipfs::pumpfun::PumpfunIpfsClientConfig,
     pub tx_exec: TxExecutorConfig,
+    #[serde(with = "humantime_serde", default = "default_fee_sweep_interval")]
+    pub fee_sweep_interval: Duration,
+    /// Fee is swept only when fee account holds at least this amount above rent.
+    #[serde(default = "default_min_sweep_lamports")]
+    pub min_sweep_lamports: u64,
+    /// How often jito tip strategy, set via admin api, is reloaded.
+    #[serde(with = "humantime_serde", default = "default_tip_strategy_refresh")]
+    pub tip_strategy_refresh: Duration,
+    #[serde(with = "humantime_serde", default = "default_lock_cleanup_interval")]
+    pub lock_cleanup_interval: Duration,
+    /// Lease of the project lock, migration must commit within it, otherwise it's retried.
+    #[serde(with = "humantime_serde", default = "default_max_lock_age")]
+    pub max_lock_age: Duration,
+    /// On-chain project latch, locked for longer, is left by a failed transaction and forced open.
+    #[serde(with = "humantime_serde", default = "default_max_latch_age")]
+    pub max_latch_age: Duration,
+    #[serde(with = "humantime_serde", default = "default_raydium_sync_interval")]
+    pub raydium_sync_interval: Duration,
+    /// Several replicas are running, so projects are locked by advisory locks in addition
+    /// to row locks, and projects locked by other replicas are skipped silently.
+    #[serde(default)]
+    pub horizontal_scaling: bool,
 }
 
 pub fn default_tick_interval() -> Duration {
     Duration::from_secs(3)
 }
 
+pub fn default_tip_strategy_refresh() -> Duration {
+    Duration::from_secs(10)
+}
+
+pub fn default_lock_cleanup_interval() -> Duration {
+    Duration::from_secs(5 * 60)
+}
+
+pub fn default_max_lock_age() -> Duration {
+    Duration::from_secs(10 * 60)
+}
+
+pub fn default_max_latch_age() -> Duration {
+    Duration::from_secs(10 * 60)
+}
+
+pub fn default_fee_sweep_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
+pub fn default_raydium_sync_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
+pub fn default_min_sweep_lamports() -> u64 {
+    LAMPORTS_PER_SOL
+}
+
 pub struct Migrator {
     tools: Tools,
+    in_flight: JoinSet<anyhow::Result<()>>,
 }
 
 impl Migrator {
     /// Limited by max number of accounts for get_multiple_accounts rpc call
     const PAGE_SIZE: usize = 100;
+    /// In-flight migrations still running after this period on shutdown are aborted.
+    const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
 
     pub async fn serve(
         solana_pool: SolanaPool,
@@ -57,6 +149,7 @@ impl Migrator {
         storage: StorageClient,
         instructions_builder: InstructionsBuilder,
         config: MigratorConfig,
+        cancellation: CancellationToken,
     ) -> anyhow::Result<JoinHandle<()>> {
         let jito_meta_rx = PeriodicFetcher::new(
             jito::JitoTipStateFetcher::default(),
@@ -80,53 +173,138 @@ impl Migrator {
                 tx_executor: TxExecutor::new(
                     solana_pool.clone(),
                     instructions_builder.solana_meta.clone(),
+                    storage.clone(),
                     config.tx_exec,
                 ),
+                keys_provider: KeysProvider::new(storage.clone()),
                 solana_keys,
                 instructions_builder,
+                max_latch_age: config.max_latch_age,
+                max_lock_age: config.max_lock_age,
+                horizontal_scaling: config.horizontal_scaling,
             }),
         };
 
-        let migrator = Migrator { tools };
+        TipStrategyWatcher {
+            storage: storage.clone(),
+            solana_pool: solana_pool.clone(),
+            interval: config.tip_strategy_refresh,
+        }
+        .serve();
+
+        LockCleaner {
+            storage: storage.clone(),
+            interval: config.lock_cleanup_interval,
+        }
+        .serve();
+
+        FeeSweeper {
+            tools: tools.clone(),
+            interval: config.fee_sweep_interval,
+            min_sweep_lamports: config.min_sweep_lamports,
+        }
+        .serve();
+
+        RaydiumStateSyncer {
+            tools: tools.clone(),
+            interval: config.raydium_sync_interval,
+        }
+        .serve();
+
+        let mut migrator = Migrator {
+            tools,
+            in_flight: JoinSet::new(),
+        };
 
         Ok(tokio::spawn(async move {
             loop {
-                if let Err(err) = migrator.tick().await {
-                    error!("migration tick failed: {err:#}");
-                } else {
-                    debug!("migrator tick completed successfully");
+                tokio::select! {
+                    _ = cancellation.cancelled() => break,
+                    _ = async {
+                        if let Err(err) = migrator.tick().await {
+                            error!("migration tick failed: {err:#}");
+                        } else {
+                            debug!("migrator tick completed successfully");
+                        }
+                        migrator.reap_finished();
+
+                        tokio::time::sleep(config.tick_interval).await;
+                    } => {}
                 }
-
-                tokio::time::sleep(config.tick_interval).await;
             }
+            migrator.shutdown().await;
         }))
     }
 
-    async fn tick(&self) -> anyhow::Result<()> {
-        let mut after =
-            DateTime::<TZ>::from_timestamp(0, 0).expect("invariant: unix epoch timestamp");
+    /// Stops picking new projects, letting already started migrations reach their end,
+    /// so project is not left locked in the middle of deploy.
+    async fn shutdown(mut self) {
+        info!(
+            "migrator is shutting down, waiting for {} in-flight migrations",
+            self.in_flight.len()
+        );
+        let started = Instant::now();
+        let aborted = drain_in_flight(&mut self.in_flight, Self::SHUTDOWN_TIMEOUT).await;
+        if aborted > 0 {
+            warn!("aborted {aborted} migrations, which didn't complete in time");
+        }
+        MIGRATOR_GRACEFUL_SHUTDOWN_DURATION.observe(started.elapsed().as_secs_f64());
+        info!("migrator shut down in {:?}", started.elapsed());
+    }
+
+    fn reap_finished(&mut self) {
+        while let Some(result) = self.in_flight.try_join_next() {
+            report_migration_result(result);
+        }
+    }
+
+    async fn tick(&mut self) -> anyhow::Result<()> {
+        let mut after = None;
         while let Some(new_after) = self.tick_page(after).await? {
-            after = new_after;
+            after = Some(new_after);
         }
+        self.spawn_metadata_updates().await?;
 
         Ok(())
     }
 
-    async fn tick_page<'a>(&self, after: DateTime<TZ>) -> anyhow::Result<Option<DateTime<TZ>>> {
+    /// Live curves are out of the regular migration, so updated metadata is picked separately.
+    async fn spawn_metadata_updates(&mut self) -> anyhow::Result<()> {
+        let outdated = StoredTokenMeta::outdated_on_curve(&self.tools.storage.pool).await?;
+        for project_id in outdated {
+            let tools = self.tools.clone();
+            self.in_flight.spawn(async move {
+                update_project_metadata(tools, project_id)
+                    .await
+                    .with_context(|| {
+                        format!("failed to update metadata of project({project_id:?})")
+                    })
+            });
+        }
+        Ok(())
+    }
+
+    async fn tick_page(
+        &mut self,
+        after: Option<(DateTime<TZ>, ProjectId)>,
+    ) -> anyhow::Result<Option<(DateTime<TZ>, ProjectId)>> {
         let eligible_stages = [
             project::Stage::Confirmed,
             project::Stage::OnStaticPool,
             project::Stage::StaticPoolClosed,
+            project::Stage::OnSecondStaticPool,
+            project::Stage::SecondStaticPoolClosed,
             project::Stage::CurvePoolClosed,
         ];
-        let projects:Vec<FullProjectState> = query_as(
-            concatcp!(
-                FullProjectState::QUERY_BODY,
-                "WHERE stage = ANY($1::project_stage[]) AND created_at > $2 ORDER BY created_at ASC LIMIT $3"
-            )
-        ).bind(eligible_stages).bind(after).bind(Self::PAGE_SIZE as i64)
-        .fetch_all(&*self.tools.storage)
-        .await?;
+        let mut query = FullProjectState::query_builder()
+            .where_stage(&eligible_stages)
+            .where_image_valid()
+            .order_by_created_at(SortDir::Asc)
+            .limit(Self::PAGE_SIZE as i64);
+        if let Some((after_ts, after_id)) = after {
+            query = query.cursor(after_ts, after_id);
+        }
+        let projects = query.fetch_all(&*self.tools.storage).await?;
         debug!(
             "received projects for migration: {:?}",
             projects
@@ -135,18 +313,21 @@ impl Migrator {
                 .collect::<Vec<_>>()
         );
         let received_projects = projects.len();
-        let last_timemark = projects.last().map(|project| project.project.created_at);
+        let last_timemark = projects
+            .last()
+            .map(|project| (project.project.created_at, project.project.id));
 
         for project in projects.into_iter() {
             let executor = ProjectMigrationExecutor {
                 tools: self.tools.clone(),
                 project_state: project,
             };
-            spawn(async move {
+            self.in_flight.spawn(async move {
                 let id = executor.project_state.project.id;
-                if let Err(err) = executor.migrate().await {
-                    warn!("failed to execute migration for project({:?}): {err:?}", id);
-                }
+                executor
+                    .migrate()
+                    .await
+                    .with_context(|| format!("failed to execute migration for project({id:?})"))
             });
         }
 
@@ -158,6 +339,54 @@ impl Migrator {
     }
 }
 
+/// Waits for all in-flight migrations up to the timeout, aborting the rest.
+/// Returns number of aborted migrations.
+async fn drain_in_flight(in_flight: &mut JoinSet<anyhow::Result<()>>, timeout: Duration) -> usize {
+    let drained = tokio::time::timeout(timeout, async {
+        while let Some(result) = in_flight.join_next().await {
+            report_migration_result(result);
+        }
+    })
+    .await;
+    if drained.is_ok() {
+        return 0;
+    }
+
+    let aborted = in_flight.len();
+    in_flight.abort_all();
+    while in_flight.join_next().await.is_some() {}
+    aborted
+}
+
+fn report_migration_result(result: Result<anyhow::Result<()>, JoinError>) {
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) if err.is::<ProjectLockedElsewhere>() => debug!("{err:#}"),
+        Ok(Err(err)) => warn!("{err:?}"),
+        Err(err) if err.is_cancelled() => {}
+        Err(err) => error!("migration task panicked: {err}"),
+    }
+}
+
+#[instrument(skip(tools))]
+async fn update_project_metadata(tools: Tools, project_id: ProjectId) -> anyhow::Result<()> {
+    let project_state = FullProjectState::query(&tools.storage.pool, &project_id).await?;
+    if !matches!(
+        project_state.project.deploy_schema.curve_pool,
+        CurveVariant::Moonzip
+    ) {
+        bail!("invariant: only moonzip curve metadata could be updated");
+    }
+    let lock = tools.lock_project(&project_id).await?;
+    PrepareCurveDeploy {
+        tools: tools.clone(),
+        project_state: &project_state,
+        lock,
+    }
+    .update_metadata()
+    .await
+}
+
 struct ProjectMigrationExecutor {
     tools: Tools,
     project_state: FullProjectState,
@@ -166,34 +395,43 @@ struct ProjectMigrationExecutor {
 impl ProjectMigrationExecutor {
     #[instrument(skip(self), fields(project_id = %self.project_state.project.id))]
     async fn migrate(self) -> anyhow::Result<()> {
+        self.release_stale_latch().await?;
         match self.project_state.project.stage {
             // project is created with graduation straight to curve pool
             project::Stage::Confirmed => {
-                if self
-                    .project_state
-                    .project
-                    .deploy_schema
-                    .static_pool
-                    .is_some()
-                {
+                if self.project_state.project.deploy_schema.has_static_pool() {
                     bail!("invariant: project must have begun straight to static pool");
                 }
                 info!("would deploy curve, avoiding static pool");
                 self.deploy_curve().await?;
             }
-            // we need to migrate static pool to curve pool
-            project::Stage::StaticPoolClosed => {
-                info!("would deploy curve, static pool is already closed");
-                self.deploy_curve().await?;
+            // we need to migrate static pool to the next one or to curve pool
+            project::Stage::StaticPoolClosed | project::Stage::SecondStaticPoolClosed => {
+                info!("static pool is already closed");
+                self.leave_static_pool().await?;
             }
-            project::Stage::OnStaticPool => {
-                if self.project_state.should_close_static_pool() {
-                    info!("would deploy curve, static pool should be closed by time");
-                    self.deploy_curve().await?;
+            project::Stage::OnStaticPool | project::Stage::OnSecondStaticPool => {
+                if !self.project_state.should_close_static_pool() {
+                    return Ok(());
+                }
+                match self.static_pool_closing().await? {
+                    // e.g. sells are still accepted within the grace period after its limit
+                    None => debug!("static pool is not closed on chain yet"),
+                    Some(StaticPoolState::Failed) => {
+                        info!("static pool missed its goal, would switch it to refunds");
+                        self.fail_static_pool().await?;
+                    }
+                    Some(_) => {
+                        info!("static pool should be closed by time or its limit");
+                        self.leave_static_pool().await?;
+                    }
                 }
             }
             project::Stage::CurvePoolClosed => {
-                info!("curve pool closed, need to deploy on raydium");
+                info!(
+                    "curve pool closed, need to deploy on {:?}",
+                    self.project_state.project.deploy_schema.graduation_target
+                );
                 let mut lock = self
                     .tools
                     .lock_project(&self.project_state.project.id)
@@ -203,7 +441,7 @@ impl ProjectMigrationExecutor {
                     lock: &mut lock,
                     project_state: &self.project_state,
                 };
-                deployer.graduate_to_raydium().await?;
+                deployer.graduate_to_dex().await?;
             }
             _ => {
                 bail!("invariant: other stage must not propagate to the migrator");
@@ -212,6 +450,223 @@ impl ProjectMigrationExecutor {
         Ok(())
     }
 
+    /// Every migration transaction locks the latch, so one, left locked, would block them all.
+    async fn release_stale_latch(&self) -> anyhow::Result<()> {
+        let ix_builder = self
+            .tools
+            .instructions_builder
+            .for_project(&self.project_state)?;
+        let Some(project) = self
+            .tools
+            .instructions_builder
+            .fetch_project_account(&self.project_state.project.id)
+            .await?
+        else {
+            return Ok(());
+        };
+        if !project.latch.is_locked() {
+            return Ok(());
+        }
+        // same as on-chain, latch without timestamp is locked before it was recorded.
+        let age = TZ::now()
+            .timestamp()
+            .saturating_sub(project.latch.locked_at.unwrap_or(0));
+        if age <= self.tools.max_latch_age.as_secs() as i64 {
+            return Ok(());
+        }
+
+        warn!("project latch is locked for {age}s, forcing it open");
+        let authority = self.tools.solana_keys.authority_keypair().to_keypair();
+        self.tools
+            .tx_executor
+            .execute_single(TransactionRequest {
+                instructions: ix_builder.force_unlock_stale_latch(self.tools.max_latch_age)?,
+                signers: vec![authority.insecure_clone()],
+                payer: authority,
+                project_id: Some(self.project_state.project.id),
+                memo: ix_builder.memo("force_unlock_stale_latch"),
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Chain tells, whether the pool is closed and whether it fails once closed,
+    /// as the stored state could lag behind. Missing pool is left to its graduation to report.
+    async fn static_pool_closing(&self) -> anyhow::Result<Option<StaticPoolState>> {
+        let ix_builder = self
+            .tools
+            .instructions_builder
+            .for_project(&self.project_state)?;
+        let Some(mut pool) = ix_builder.fetch_static_pool().await? else {
+            return Ok(Some(StaticPoolState::Closed));
+        };
+        if !pool.close_if_needed_at(TZ::now().timestamp() as u64) {
+            return Ok(None);
+        }
+        Ok(Some(pool.state))
+    }
+
+    /// Failed pool is left to buyers for refunds, neither next pool nor curve is deployed.
+    async fn fail_static_pool(&self) -> anyhow::Result<()> {
+        let lock = self
+            .tools
+            .lock_project(&self.project_state.project.id)
+            .await?;
+        let mut ix_builder = self
+            .tools
+            .instructions_builder
+            .for_project(&self.project_state)?;
+        let authority = self.tools.solana_keys.authority_keypair().to_keypair();
+        let mut instructions = ix_builder.lock_project()?;
+        instructions.append(&mut ix_builder.graduate_static_pool()?);
+        instructions.append(&mut ix_builder.unlock_project()?);
+        self.tools
+            .tx_executor
+            .execute_single(TransactionRequest {
+                instructions,
+                signers: vec![authority.insecure_clone()],
+                payer: authority,
+                project_id: Some(self.project_state.project.id),
+                memo: ix_builder.memo("fail_static_pool"),
+            })
+            .await?;
+        info!("static pool failed, buyers could refund now");
+
+        lock.commit().await
+    }
+
+    /// Static pools are opened one after another, the last one is followed by curve pool.
+    async fn leave_static_pool(&self) -> anyhow::Result<()> {
+        let left_mint = self.project_state.project.static_pool_mint();
+        match self.project_state.project.next_static_pool_index() {
+            Some(index) => {
+                info!("would open static pool #{index}");
+                self.open_static_pool(index).await?;
+            }
+            None => {
+                info!("would deploy curve");
+                self.deploy_curve().await?;
+            }
+        }
+        if let Some(mint) = left_mint {
+            // rent is owed to buyers, but it's not a reason to hold the project back
+            if let Err(err) = self.close_wallet_records(mint).await {
+                warn!("failed to close wallet records of static pool {mint}: {err:#}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns rent of wallet records to buyers, once the static pool is graduated.
+    async fn close_wallet_records(&self, pool_mint: Pubkey) -> anyhow::Result<()> {
+        let pool_balance = self
+            .tools
+            .instructions_builder
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .get_balance(&static_pool_address(pool_mint))
+            .await?;
+        if pool_balance > 0 {
+            debug!("static pool {pool_mint} is not graduated yet, keeping wallet records");
+            return Ok(());
+        }
+
+        let ix_builder = self
+            .tools
+            .instructions_builder
+            .for_project(&self.project_state)?;
+        let wallets = ix_builder.fetch_wallet_records(pool_mint).await?;
+        for chunk in wallets.chunks(WALLET_RECORDS_PER_TX) {
+            let authority = self.tools.solana_keys.authority_keypair().to_keypair();
+            self.tools
+                .tx_executor
+                .execute_single(TransactionRequest {
+                    instructions: ix_builder.close_wallet_records(pool_mint, chunk)?,
+                    signers: vec![authority.insecure_clone()],
+                    payer: authority,
+                    project_id: Some(self.project_state.project.id),
+                    memo: ix_builder.memo("close_wallet_records"),
+                })
+                .await?;
+        }
+        if !wallets.is_empty() {
+            info!(
+                "closed {} wallet records of static pool {pool_mint}",
+                wallets.len()
+            );
+        }
+        Ok(())
+    }
+
+    async fn open_static_pool(&self, index: usize) -> anyhow::Result<()> {
+        let project = &self.project_state.project;
+        // previous attempt could have landed, while chain syncer is not caught up yet.
+        if let Some(assigned) = project.static_pool_pubkeys.get(index) {
+            let pool_balance = self
+                .tools
+                .instructions_builder
+                .solana_pool
+                .rpc_client()
+                .use_single()
+                .await
+                .get_balance(&static_pool_address(assigned.to_pubkey()))
+                .await?;
+            if pool_balance > 0 {
+                debug!("static pool #{index} is already created, waiting for chain sync");
+                return Ok(());
+            }
+        }
+
+        let mut pool = project
+            .deploy_schema
+            .static_pools
+            .get(index)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("invariant: no static pool #{index} in schema"))?;
+        let launch_period = pool.launch_period.ok_or_else(|| {
+            anyhow::anyhow!("invariant: no launch period for static pool #{index}")
+        })?;
+        pool.launch_ts = TZ::now().timestamp() + launch_period;
+        let mint = Keypair::new();
+
+        let lock = self.tools.lock_project(&project.id).await?;
+        // stored before submission: mint keypair is not kept, so failed attempt is replaced.
+        StoredProject::open_static_pool(
+            &self.tools.storage.pool,
+            &project.id,
+            index,
+            mint.pubkey().into(),
+            pool.launch_ts,
+        )
+        .await?;
+
+        let mut ix_builder = self
+            .tools
+            .instructions_builder
+            .for_project(&self.project_state)?;
+        let authority = self.tools.solana_keys.authority_keypair().to_keypair();
+        let mut instructions = ix_builder.lock_project()?;
+        instructions.append(&mut ix_builder.graduate_static_pool()?);
+        instructions.append(&mut ix_builder.init_static_pool(&mint, &pool)?);
+        instructions.append(&mut ix_builder.unlock_project()?);
+        let mint_pubkey = mint.pubkey();
+        self.tools
+            .tx_executor
+            .execute_single(TransactionRequest {
+                instructions,
+                signers: vec![authority.insecure_clone(), mint],
+                payer: authority,
+                project_id: Some(project.id),
+                memo: ix_builder.memo("open_static_pool"),
+            })
+            .await?;
+        info!("opened static pool #{index} with mint {mint_pubkey}");
+
+        lock.commit().await
+    }
+
     async fn deploy_curve<'a>(&self) -> anyhow::Result<()> {
         let mut lock = self
             .tools
@@ -260,28 +715,30 @@ impl<'a, 'b> Deployer<'a, 'b> {
             .map(|purchase| purchase.amount.try_into())
             .transpose()?;
 
-        let post_dev_purchase = if self
-            .project_state
-            .project
-            .deploy_schema
-            .static_pool
-            .is_some()
-        {
-            let collected: u64 = self
-                .project_state
-                .static_pool_state
-                .as_ref()
-                .map(|state| state.collected_lamports.clone())
-                .unwrap_or_default()
-                .try_into()?;
-            if collected == 0 {
-                None
-            } else {
-                Some(collected)
-            }
-        } else {
-            None
-        };
+        // the last pool is tracked on chain, previous ones are recorded when the next one opens.
+        let static_pools = &self.project_state.project.deploy_schema.static_pools;
+        let static_pool_lamports = static_pools
+            .iter()
+            .enumerate()
+            .map(|(index, pool)| {
+                let collected = if index + 1 == static_pools.len() {
+                    self.project_state
+                        .static_pool_state
+                        .as_ref()
+                        .map(|state| state.collected_lamports.clone())
+                        .unwrap_or_default()
+                } else {
+                    pool.collected_lamports.clone().ok_or_else(|| {
+                        anyhow::anyhow!("invariant: no collected lamports for static pool #{index}")
+                    })?
+                };
+                Result::<u64, anyhow::Error>::Ok(collected.try_into()?)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let post_dev_purchase = static_pool_lamports
+            .first()
+            .copied()
+            .filter(|collected| *collected > 0);
 
         let mut ix_builder = self
             .tools
@@ -309,7 +766,7 @@ impl<'a, 'b> Deployer<'a, 'b> {
             .deploy_schema
             .dev_purchase
             .as_ref()
-            .map(|purchase| !purchase.lock_period.is_zero())
+            .map(|purchase| purchase.delivery().is_vested())
             .unwrap_or(false);
 
         let token_meta = token_meta(&mut self.lock.tx, self.project_state.project.id).await?;
@@ -333,29 +790,30 @@ impl<'a, 'b> Deployer<'a, 'b> {
                 curve_mint_keypair,
             ],
             payer: self.tools.solana_keys.authority_keypair().to_keypair(),
+            project_id: Some(self.project_state.project.id),
+            memo: ix_builder.memo("deploy_curve"),
         };
 
         first_tx
             .instructions
             .append(&mut ix_builder.lock_project()?);
 
-        if self
-            .project_state
-            .project
-            .deploy_schema
-            .static_pool
-            .is_some()
-        {
+        if self.project_state.project.deploy_schema.has_static_pool() {
             first_tx
                 .instructions
                 .append(&mut ix_builder.graduate_static_pool()?);
         }
 
+        let mut meteora_pool = None;
         match self.project_state.project.deploy_schema.curve_pool {
             CurveVariant::Moonzip => {
                 first_tx
                     .instructions
                     .append(&mut ix_builder.init_moonzip_pool(curve_create)?);
+                // whole supply is already minted and metadata is created.
+                first_tx
+                    .instructions
+                    .append(&mut ix_builder.revoke_mint_authority()?);
             }
             CurveVariant::Pumpfun => {
                 let pumpfun_meta = self.tools.pumpfun_meta_rx.clone().get()?;
@@ -363,21 +821,36 @@ impl<'a, 'b> Deployer<'a, 'b> {
                     .instructions
                     .append(&mut ix_builder.init_pumpfun_pool(curve_create, pumpfun_meta)?);
             }
+            CurveVariant::Meteora => {
+                let (mut mint_ixs, pool_ixs) = ix_builder.init_meteora_pool(curve_create)?;
+                first_tx.instructions.append(&mut mint_ixs);
+                meteora_pool = Some(pool_ixs);
+            }
         };
         first_tx
             .instructions
             .append(&mut ix_builder.unlock_project()?);
 
-        // second transaction is for tokens delivery mainly.
+        // second transaction is for tokens delivery mainly, meteora launch has none to deliver.
         let mut second_tx = TransactionRequest {
             instructions: vec![],
             signers: vec![self.tools.solana_keys.authority_keypair().to_keypair()],
             payer: self.tools.solana_keys.authority_keypair().to_keypair(),
+            project_id: Some(self.project_state.project.id),
+            memo: ix_builder.memo(if meteora_pool.is_some() {
+                "init_meteora_pool"
+            } else if should_lock {
+                "dev_lock"
+            } else {
+                "deliver_dev_tokens"
+            }),
         };
         second_tx
             .instructions
             .append(&mut ix_builder.lock_project()?);
-        if should_lock {
+        if let Some(mut pool_ixs) = meteora_pool {
+            second_tx.instructions.append(&mut pool_ixs);
+        } else if should_lock {
             second_tx.instructions.append(&mut ix_builder.lock_dev()?);
             second_tx.signers.push(
                 dev_lock_keypair
@@ -388,44 +861,69 @@ impl<'a, 'b> Deployer<'a, 'b> {
                 .instructions
                 .append(&mut ix_builder.deliver_dev_tokens()?);
         }
-        second_tx
-            .instructions
-            .append(&mut ix_builder.init_transmuter()?);
+        let static_pool_mints: Vec<_> = self.project_state.project.static_pool_mints().collect();
+        if let Some(first_mint) = static_pool_mints.first() {
+            second_tx
+                .instructions
+                .append(&mut ix_builder.init_transmuter(*first_mint)?);
+        }
         second_tx
             .instructions
             .append(&mut ix_builder.unlock_project()?);
 
+        let mut txs = vec![first_tx, second_tx];
+        // each further static pool buys its own share from the curve.
+        for (mint, lamports) in static_pool_mints
+            .iter()
+            .zip(static_pool_lamports.iter())
+            .skip(1)
+            .filter(|(_, lamports)| **lamports > 0)
+        {
+            let mut instructions = ix_builder.lock_project()?;
+            instructions.append(&mut ix_builder.buy_for_static_pool(*lamports)?);
+            instructions.append(&mut ix_builder.init_transmuter(*mint)?);
+            instructions.append(&mut ix_builder.unlock_project()?);
+            txs.push(TransactionRequest {
+                instructions,
+                signers: vec![self.tools.solana_keys.authority_keypair().to_keypair()],
+                payer: self.tools.solana_keys.authority_keypair().to_keypair(),
+                project_id: Some(self.project_state.project.id),
+                memo: ix_builder.memo("deliver_static_pool_tokens"),
+            });
+        }
+
         // it's ok to add jito tip *after* project's bank is unlocked -
         // we don't really track it as it's dynamic.
-        let jito_meta = self.tools.jito_meta_rx.clone().get()?;
-        second_tx
+        let tip = self.tools.jito_tip_ix().await?;
+        txs.last_mut()
+            .expect("at least two transactions")
             .instructions
-            .push(jito_meta.tip_ix(&PROGRAM_AUTHORITY));
+            .push(tip);
 
-        let txs = vec![first_tx, second_tx];
-        self.tools.tx_executor.execute_batch(txs).await?;
+        // tokens distribution relies on the curve pool, so wait until it's certainly landed.
+        let confirmation = self
+            .tools
+            .tx_executor
+            .execute_bundle_with_confirmation(
+                txs,
+                CommitmentLevel::Confirmed,
+                Duration::from_secs(60),
+            )
+            .await?;
+        info!(
+            "curve pool deployed in bundle {} at slot {}: {:?}",
+            confirmation.bundle_id, confirmation.slot, confirmation.signatures
+        );
 
         Ok(())
     }
 
-    async fn graduate_to_raydium(&self) -> anyhow::Result<()> {
+    async fn graduate_to_dex(&self) -> anyhow::Result<()> {
         let ix_builder = self
             .tools
             .instructions_builder
             .for_project(self.project_state)?;
 
-        let mut first_tx = vec![];
-        first_tx.append(&mut ix_builder.graduate_curve_pool()?);
-        first_tx.append(&mut ix_builder.prepare_openbook_market_vaults()?);
-        first_tx.append(&mut ix_builder.reward_creator_on_graduate()?);
-
-        let jito_meta = self.tools.jito_meta_rx.clone().get()?;
-        first_tx.push(jito_meta.tip_ix(&PROGRAM_AUTHORITY));
-
-        let mut second_tx = vec![];
-        let mut openbook_market_ix = ix_builder.initialize_openbook_market()?;
-        second_tx.append(&mut openbook_market_ix);
-
         let curve_config = self
             .tools
             .moonzip_meta_rx
@@ -436,12 +934,66 @@ impl<'a, 'b> Deployer<'a, 'b> {
             .curve;
         let tokens_amount =
             curve_config.total_token_supply - curve_config.initial_real_token_reserves;
+        let curve_state = ix_builder
+            .fetch_curve_state()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("invariant: no curve pool of closed project"))?;
+        self.tools
+            .instructions_builder
+            .config
+            .check_graduation(curve_state.sol_balance, tokens_amount)?;
+
+        let sols_amount = self.tools.instructions_builder.config.raydium_liquidity;
+        let target = self.project_state.project.deploy_schema.graduation_target;
 
-        let mut third_tx = vec![];
-        third_tx.append(&mut ix_builder.deploy_to_raydium(
-            tokens_amount,
-            self.tools.instructions_builder.config.raydium_liquidity,
-        )?);
+        let mut first_tx = vec![];
+        first_tx.append(&mut ix_builder.revoke_freeze_authority()?);
+        first_tx.append(&mut ix_builder.graduate_curve_pool()?);
+        if target == GraduationTarget::Raydium {
+            first_tx.append(&mut ix_builder.prepare_openbook_market_vaults()?);
+        }
+        first_tx.append(&mut ix_builder.reward_creator_on_graduate()?);
+        let transmuter_expiry = TZ::now()
+            + self
+                .tools
+                .instructions_builder
+                .config
+                .transmuter_lifetime_after_graduation;
+        first_tx.append(
+            &mut ix_builder
+                .expire_transmuters(transmuter_expiry.timestamp() as u64)
+                .await?,
+        );
+
+        first_tx.push(self.tools.jito_tip_ix().await?);
+
+        // some DEX accounts are created by keypairs, which must sign the transaction as well
+        let (second_tx, second_signers, third_tx, third_signers) = match target {
+            GraduationTarget::Raydium => (
+                ix_builder.initialize_openbook_market()?,
+                vec![],
+                ix_builder.deploy_to_raydium(tokens_amount, sols_amount)?,
+                vec![],
+            ),
+            GraduationTarget::Meteora => (
+                ix_builder.create_meteora_pair(tokens_amount, sols_amount)?,
+                vec![],
+                ix_builder.deploy_to_meteora(tokens_amount, sols_amount)?,
+                vec![],
+            ),
+            GraduationTarget::Orca => {
+                let keys = WhirlpoolKeys::generate();
+                let create_ixs = ix_builder.create_whirlpool(tokens_amount, sols_amount, &keys)?;
+                let deploy_ixs =
+                    ix_builder.deploy_to_whirlpool(tokens_amount, sols_amount, &keys)?;
+                (
+                    create_ixs,
+                    vec![keys.vault_a, keys.vault_b],
+                    deploy_ixs,
+                    vec![keys.position_mint],
+                )
+            }
+        };
 
         let signer = self.tools.solana_keys.authority_keypair().to_keypair();
 
@@ -452,16 +1004,26 @@ impl<'a, 'b> Deployer<'a, 'b> {
                     instructions: first_tx,
                     signers: vec![signer.insecure_clone()],
                     payer: signer.insecure_clone(),
+                    project_id: Some(self.project_state.project.id),
+                    memo: ix_builder.memo("graduate"),
                 },
                 TransactionRequest {
                     instructions: second_tx,
-                    signers: vec![signer.insecure_clone()],
+                    signers: iter::once(signer.insecure_clone())
+                        .chain(second_signers)
+                        .collect(),
                     payer: signer.insecure_clone(),
+                    project_id: Some(self.project_state.project.id),
+                    memo: ix_builder.memo("graduate"),
                 },
                 TransactionRequest {
                     instructions: third_tx,
-                    signers: vec![signer.insecure_clone()],
+                    signers: iter::once(signer.insecure_clone())
+                        .chain(third_signers)
+                        .collect(),
                     payer: signer.insecure_clone(),
+                    project_id: Some(self.project_state.project.id),
+                    memo: ix_builder.memo("graduate"),
                 },
             ])
             .await?;
@@ -478,12 +1040,12 @@ struct PrepareCurveDeploy<'a> {
 
 impl<'a> PrepareCurveDeploy<'a> {
     async fn prepare(mut self) -> anyhow::Result<()> {
-        query!(
-            r#"call assign_project_keypair($1)"#,
-            self.project_state.project.id as _,
-        )
-        .execute(&mut *self.lock.tx)
-        .await?;
+        let keypair = self
+            .tools
+            .keys_provider
+            .next(&mut self.lock.tx, self.project_state.project.id)
+            .await?;
+        info!("assigned curve mint {}", keypair.pubkey());
         self.deploy_metadata(self.project_state.project.deploy_schema.curve_pool)
             .await?;
         self.lock.commit().await?;
@@ -491,62 +1053,118 @@ impl<'a> PrepareCurveDeploy<'a> {
         Ok(())
     }
 
+    /// Metadata is deployed again, while the lock keeps owner from changing it until committed.
+    /// New url is committed only with the on-chain update, so the previous one stays in use
+    /// and the update is retried, if either fails.
+    async fn update_metadata(mut self) -> anyhow::Result<()> {
+        let project_id = self.project_state.project.id;
+        self.deploy_metadata(CurveVariant::Moonzip).await?;
+        let meta = token_meta(&mut self.lock.tx, project_id).await?;
+
+        let ix_builder = self
+            .tools
+            .instructions_builder
+            .for_project(self.project_state)?;
+        let authority = self.tools.solana_keys.authority_keypair().to_keypair();
+        self.tools
+            .tx_executor
+            .execute_single(TransactionRequest {
+                instructions: ix_builder.update_project_metadata(&meta)?,
+                signers: vec![authority.insecure_clone()],
+                payer: authority,
+                project_id: Some(project_id),
+                memo: ix_builder.memo("update_project_metadata"),
+            })
+            .await?;
+        info!("updated metadata to {}", meta.deployed_url()?);
+
+        self.lock.commit().await
+    }
+
     async fn deploy_metadata(&mut self, curve_variant: CurveVariant) -> anyhow::Result<String> {
-        let meta = token_meta(&mut self.lock.tx, self.project_state.project.id).await?;
-        if let Some(deployed_url) = meta.deployed_url {
-            return Ok(deployed_url);
+        let project_id = self.project_state.project.id;
+        let meta = token_meta(&mut self.lock.tx, project_id).await?;
+        if let Some(deployed_url) = &meta.deployed_url {
+            if !StoredTokenMeta::is_update_pending(self.lock.tx.deref_mut(), project_id).await? {
+                return Ok(deployed_url.clone());
+            }
         }
 
-        let metadata_uri = {
-            let image = token_image(&mut self.lock.tx, self.project_state.project.id).await?;
-
-            match curve_variant {
-                CurveVariant::Moonzip => {
-                    Self::deploy_moonzip_metadata(&self.tools.mzip_ipfs, meta, image).await?
-                }
-                CurveVariant::Pumpfun => {
-                    Self::deploy_pumpfun_metadata(&self.tools.pumpfun_ipfs, meta, image).await?
-                }
+        let digest = ipfs_uploads::image_digest(self.lock.tx.deref_mut(), project_id).await?;
+        let (metadata_uri, metadata_hash) = match curve_variant {
+            // metadata account of meteora launch is created by us, the same way as for moonzip
+            CurveVariant::Moonzip | CurveVariant::Meteora => {
+                self.deploy_moonzip_metadata(meta, digest).await?
             }
+            CurveVariant::Pumpfun => self.deploy_pumpfun_metadata(meta, digest).await?,
         };
 
-        sqlx::query!(
-            "UPDATE token_meta SET deployed_url = $1 WHERE project_id = $2",
-            metadata_uri,
-            self.project_state.project.id as _,
+        sqlx::query(
+            "
+                UPDATE token_meta SET deployed_url = $1, metadata_hash = $2, update_pending = FALSE
+                WHERE project_id = $3
+            ",
         )
+        .bind(&metadata_uri)
+        .bind(metadata_hash)
+        .bind(project_id)
         .execute(self.lock.tx.deref_mut())
         .await?;
 
         Ok(metadata_uri)
     }
 
+    /// Pumpfun uploads image and metadata at once, so both are deduplicated by the single hash.
     async fn deploy_pumpfun_metadata(
-        ipfs: &ipfs::pumpfun::PumpfunIpfsClient,
+        &mut self,
         meta: StoredTokenMeta,
-        image: ImageStream<'_>,
-    ) -> anyhow::Result<String> {
+        digest: StoredImageDigest,
+    ) -> anyhow::Result<(String, Option<String>)> {
+        // keys of the json object are sorted, so the hash is stable
+        let metadata_hash = digest.image_hash.as_ref().map(|image_hash| {
+            let identity = serde_json::json!({
+                "variant": "pumpfun",
+                "name": meta.name,
+                "symbol": meta.symbol,
+                "description": meta.description,
+                "image_hash": image_hash,
+                "telegram": meta.telegram,
+                "website": meta.website,
+                "twitter": meta.twitter,
+            });
+            content_hash(identity.to_string().as_bytes())
+        });
+        if let Some(url) = self.find_metadata_url(metadata_hash.as_deref()).await? {
+            self.record_upload(IpfsUploadKind::PumpfunMetadata, digest.bytes, true)
+                .await;
+            return Ok((url, metadata_hash));
+        }
+
+        let image = self.webp_image(&digest).await?;
         let metadata = ipfs::pumpfun::CreateTokenMetadata {
             name: meta.name,
             symbol: meta.symbol,
             description: meta.description,
-            image_content: image,
+            image_content: ImageStream::from_bytes(image),
+            image_format: ImageFormat::WebP,
             telegram: meta.telegram,
             website: meta.website,
             twitter: meta.twitter,
         };
 
-        let response = ipfs.deploy_metadata(metadata).await?;
+        let response = self.tools.pumpfun_ipfs.deploy_metadata(metadata).await?;
+        self.record_upload(IpfsUploadKind::PumpfunMetadata, digest.bytes, false)
+            .await;
 
-        Ok(response.metadata_uri)
+        Ok((response.metadata_uri, metadata_hash))
     }
 
     async fn deploy_moonzip_metadata(
-        ipfs: &ipfs::moonzip::IpfsClient,
+        &mut self,
         meta: StoredTokenMeta,
-        image: ImageStream<'_>,
-    ) -> anyhow::Result<String> {
-        let image_url = ipfs.upload_image(image, &meta.name).await?;
+        digest: StoredImageDigest,
+    ) -> anyhow::Result<(String, Option<String>)> {
+        let image_url = self.deploy_moonzip_image(&meta.name, digest).await?;
         let token_name = meta.name.clone();
 
         let metadata = OffchainMetadata {
@@ -561,11 +1179,133 @@ impl<'a> PrepareCurveDeploy<'a> {
             twitter: meta.twitter,
         };
 
-        let meta_url = ipfs.upload_json(&metadata, &token_name).await?;
-        Ok(meta_url)
+        let serialized = serde_json::to_vec(&metadata)?;
+        let metadata_hash = content_hash(&serialized);
+        let bytes = i64::try_from(serialized.len())?;
+        if let Some(url) = self.find_metadata_url(Some(&metadata_hash)).await? {
+            self.record_upload(IpfsUploadKind::Metadata, bytes, true)
+                .await;
+            return Ok((url, Some(metadata_hash)));
+        }
+
+        let meta_url = self
+            .tools
+            .mzip_ipfs
+            .upload_json(&metadata, &token_name)
+            .await?;
+        self.record_upload(IpfsUploadKind::Metadata, bytes, false)
+            .await;
+        Ok((meta_url, Some(metadata_hash)))
+    }
+
+    async fn deploy_moonzip_image(
+        &mut self,
+        token_name: &str,
+        digest: StoredImageDigest,
+    ) -> anyhow::Result<String> {
+        let project_id = self.project_state.project.id;
+        if let Some(url) = digest.deployed_url {
+            return Ok(url);
+        }
+        if let Some(image_hash) = digest.image_hash.as_deref() {
+            let found = ipfs_uploads::find_image_url(self.lock.tx.deref_mut(), image_hash).await?;
+            if let Some(url) = found {
+                debug!("image {image_hash} is already uploaded to {url}");
+                ipfs_uploads::set_image_url(self.lock.tx.deref_mut(), project_id, &url).await?;
+                self.record_upload(IpfsUploadKind::Image, digest.bytes, true)
+                    .await;
+                return Ok(url);
+            }
+        }
+
+        let image = self.webp_image(&digest).await?;
+        let bytes = i64::try_from(image.len())?;
+        let url = self
+            .tools
+            .mzip_ipfs
+            .upload_image(
+                ImageStream::from_bytes(image),
+                ImageFormat::WebP,
+                token_name,
+            )
+            .await?;
+        ipfs_uploads::set_image_url(self.lock.tx.deref_mut(), project_id, &url).await?;
+        self.record_upload(IpfsUploadKind::Image, bytes, false)
+            .await;
+        Ok(url)
+    }
+
+    /// Stored image is the upload, compressed only if it exceeded the limits.
+    /// Conversion is deterministic, so deduplication by the hash of the stored content still holds.
+    async fn webp_image(&mut self, digest: &StoredImageDigest) -> anyhow::Result<Vec<u8>> {
+        let project_id = self.project_state.project.id;
+        let stored_bytes = usize::try_from(digest.bytes)?;
+        let converted = if stored_bytes > image_format::MAX_CONVERTED_IMAGE_BYTES {
+            Err(InvalidImage::TooLarge(stored_bytes))
+        } else {
+            let content: Vec<u8> =
+                sqlx::query_scalar("SELECT image_content FROM token_image WHERE project_id = $1")
+                    .bind(project_id)
+                    .fetch_one(self.lock.tx.deref_mut())
+                    .await?;
+            tokio::task::spawn_blocking(move || image_format::convert_to_webp(&content)).await?
+        };
+        match converted {
+            Ok(converted) => Ok(converted),
+            Err(source) => {
+                self.mark_image_invalid(&source).await;
+                Err(MigratorError::InvalidImageFormat { project_id, source }.into())
+            }
+        }
+    }
+
+    /// Written outside of the project lock, as the migration is rolled back on this failure.
+    async fn mark_image_invalid(&self, reason: &InvalidImage) {
+        let project_id = self.project_state.project.id;
+        if let Err(err) = ipfs_uploads::mark_image_invalid(
+            &self.tools.storage.pool,
+            project_id,
+            &reason.to_string(),
+        )
+        .await
+        {
+            warn!("failed to mark image of project({project_id:?}) invalid: {err:#}");
+        }
+    }
+
+    async fn find_metadata_url(
+        &mut self,
+        metadata_hash: Option<&str>,
+    ) -> anyhow::Result<Option<String>> {
+        let Some(metadata_hash) = metadata_hash else {
+            return Ok(None);
+        };
+        let found =
+            ipfs_uploads::find_metadata_url(self.lock.tx.deref_mut(), metadata_hash).await?;
+        if let Some(url) = &found {
+            debug!("metadata {metadata_hash} is already deployed to {url}");
+        }
+        Ok(found)
+    }
+
+    /// Written outside of the project lock, as the upload is done regardless of the migration outcome.
+    /// Failures are only logged: stats must not block the migration.
+    async fn record_upload(&self, kind: IpfsUploadKind, bytes: i64, deduplicated: bool) {
+        if deduplicated {
+            IPFS_DEDUP_SAVED.with_label_values(&[kind.as_str()]).inc();
+        }
+        if let Err(err) =
+            ipfs_uploads::record_upload(&self.tools.storage.pool, kind, bytes, deduplicated).await
+        {
+            warn!("failed to record ipfs upload: {err:#}");
+        }
     }
 }
 
+fn content_hash(content: &[u8]) -> String {
+    to_hex(&Sha256::digest(content))
+}
+
 #[derive(Clone, Deref)]
 struct Tools {
     internal: Arc<ToolsInternal>,
@@ -582,13 +1322,55 @@ struct ToolsInternal {
     mzip_ipfs: ipfs::moonzip::IpfsClient,
     pumpfun_ipfs: ipfs::pumpfun::PumpfunIpfsClient,
     tx_executor: TxExecutor,
+    keys_provider: KeysProvider,
     instructions_builder: InstructionsBuilder,
+    max_latch_age: Duration,
+    max_lock_age: Duration,
+    horizontal_scaling: bool,
 }
 
 impl Tools {
+    async fn jito_tip_ix(&self) -> anyhow::Result<Instruction> {
+        let tip = self.jito_meta_rx.clone().get()?;
+        let jito = self.instructions_builder.solana_pool.jito_client();
+        let tip_account = jito.tip_account().await;
+        JITO_TIP_KEY_USED
+            .with_label_values(&[&tip_account.to_string()])
+            .inc();
+        Ok(tip.tip_ix(&PROGRAM_AUTHORITY, &tip_account, jito.tip_multiplier()))
+    }
+
     async fn lock_project<'a>(&self, project_id: &ProjectId) -> anyhow::Result<ProjectLock<'_>> {
+        let advisory = if self.horizontal_scaling {
+            let Some(lock) = AdvisoryLock::try_acquire(&self.storage, project_id).await? else {
+                MIGRATOR_ADVISORY_LOCK_CONTENTION.inc();
+                return Err(ProjectLockedElsewhere(*project_id).into());
+            };
+            Some(lock)
+        } else {
+            None
+        };
+        let Some(owner) =
+            StoredMigrationLock::acquire(&self.storage.pool, *project_id, self.max_lock_age)
+                .await?
+        else {
+            return Err(ProjectLockedElsewhere(*project_id).into());
+        };
+        // given up on drop, unless finished by the commit
+        let lease = MigrationLease {
+            storage: self.storage.clone(),
+            project_id: *project_id,
+            owner,
+        };
         let mut tx = self.storage.serializable_tx().await?;
 
+        // postgres closes the session, stuck past its lease, so the row lock is released as well
+        sqlx::query(&format!(
+            "SET LOCAL idle_in_transaction_session_timeout = {}",
+            self.max_lock_age.as_millis()
+        ))
+        .execute(tx.deref_mut())
+        .await?;
         sqlx::query!(
             "SELECT id FROM project_migration_lock WHERE id = $1 FOR UPDATE NOWAIT;",
             project_id
@@ -596,28 +1378,72 @@ impl Tools {
         .fetch_one(tx.deref_mut())
         .await?;
 
-        Ok(ProjectLock { tx })
+        Ok(ProjectLock {
+            tx,
+            lease,
+            _advisory: advisory,
+        })
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+#[error("project {0} is locked by another migration")]
+struct ProjectLockedElsewhere(ProjectId);
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigratorError {
+    #[error("invalid image of project {project_id}: {source}")]
+    InvalidImageFormat {
+        project_id: ProjectId,
+        source: InvalidImage,
+    },
+}
+
 struct ProjectLock<'a> {
     tx: DBTransaction<'a>,
+    lease: MigrationLease,
+    /// Released after the transaction, as it's declared later.
+    _advisory: Option<AdvisoryLock>,
 }
 
 impl<'a> ProjectLock<'a> {
-    async fn commit(self) -> anyhow::Result<()> {
+    /// Commits only while the lease is still owned, otherwise the project is retried.
+    async fn commit(mut self) -> anyhow::Result<()> {
+        StoredMigrationLock::finish(self.tx.deref_mut(), self.lease.project_id, self.lease.owner)
+            .await?;
         self.tx.commit().await?;
         Ok(())
     }
 }
 
+struct MigrationLease {
+    storage: StorageClient,
+    project_id: ProjectId,
+    owner: Uuid,
+}
+
+impl Drop for MigrationLease {
+    /// Lease of the failed migration is given up, so the project is retried without waiting
+    /// for the expiry. It's a no-op once the lease is finished by the commit.
+    fn drop(&mut self) {
+        let storage = self.storage.clone();
+        let (project_id, owner) = (self.project_id, self.owner);
+        tokio::spawn(async move {
+            if let Err(err) = StoredMigrationLock::give_up(&storage.pool, project_id, owner).await {
+                error!("failed to give up migration lease of project {project_id}: {err:#}");
+            }
+        });
+    }
+}
+
 async fn token_meta(
     tx: &mut DBTransaction<'_>,
     project_id: ProjectId,
 ) -> anyhow::Result<StoredTokenMeta> {
     let metadata = query_as!(
         StoredTokenMeta,
-        "SELECT * FROM token_meta WHERE project_id = $1",
+        "SELECT project_id, name, symbol, description, website, twitter, telegram, deployed_url
+        FROM token_meta WHERE project_id = $1",
         project_id as _,
     )
     .fetch_one(tx.deref_mut())
@@ -626,18 +1452,6 @@ async fn token_meta(
     Ok(metadata)
 }
 
-async fn token_image<'a, 'b>(
-    tx: &'a mut DBTransaction<'b>,
-    project_id: ProjectId,
-) -> anyhow::Result<ImageStream<'a>> {
-    let query = format!(
-        "COPY (SELECT image_content FROM token_image WHERE project_id = '{}') TO STDOUT WITH (FORMAT binary)",
-        project_id
-    );
-    let copy_out = tx.copy_out_raw(&query).await?;
-    Ok(ImageStream(copy_out))
-}
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct OffchainMetadata {
@@ -654,3 +1468,53 @@ struct OffchainMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     twitter: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_waits_for_in_flight_migrations() {
+        let completed = Arc::new(AtomicBool::new(false));
+        let mut in_flight = JoinSet::new();
+        in_flight.spawn({
+            let completed = completed.clone();
+            async move {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                completed.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+        in_flight.spawn(async { bail!("migration failed") });
+
+        let aborted = drain_in_flight(&mut in_flight, Duration::from_secs(30)).await;
+        assert_eq!(aborted, 0);
+        assert!(completed.load(Ordering::SeqCst));
+        assert!(in_flight.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_aborts_migrations_exceeding_timeout() {
+        let completed = Arc::new(AtomicBool::new(false));
+        let mut in_flight = JoinSet::new();
+        in_flight.spawn(async { Ok(()) });
+        in_flight.spawn({
+            let completed = completed.clone();
+            async move {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                completed.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+
+        let started = tokio::time::Instant::now();
+        let aborted = drain_in_flight(&mut in_flight, Duration::from_secs(30)).await;
+        assert_eq!(aborted, 1);
+        assert_eq!(started.elapsed(), Duration::from_secs(30));
+        assert!(in_flight.is_empty());
+
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert!(!completed.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,78 @@
+use super::Tools;
+use crate::{
+    app::storage::{
+        project::{CurveVariant, FullProjectState, GraduationTarget, Stage},
+        raydium_pool_state::StoredRaydiumPoolState,
+    },
+    metrics::RAYDIUM_STATE_SYNC_LATENCY,
+};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, error};
+
+/// Periodically syncs raydium pools of graduated projects,
+/// as chain events of trades there are not tracked.
+pub(super) struct RaydiumStateSyncer {
+    pub(super) tools: Tools,
+    pub(super) interval: Duration,
+}
+
+impl RaydiumStateSyncer {
+    pub(super) fn serve(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = self.tick().await {
+                    error!("raydium state sync tick failed: {err:#}");
+                }
+                tokio::time::sleep(self.interval).await;
+            }
+        })
+    }
+
+    async fn tick(&self) -> anyhow::Result<()> {
+        let _timer = RAYDIUM_STATE_SYNC_LATENCY.start_timer();
+        let projects = FullProjectState::query_builder()
+            .where_stage(&[Stage::Graduated])
+            .fetch_all(&self.tools.storage.pool)
+            .await?;
+        // pumpfun projects graduate to the pool, created by pumpfun itself
+        for project in projects.iter().filter(|project| {
+            let schema = &project.project.deploy_schema;
+            schema.curve_pool == CurveVariant::Moonzip
+                && schema.graduation_target == GraduationTarget::Raydium
+        }) {
+            if let Err(err) = self.sync_project(project).await {
+                error!(
+                    "failed to sync raydium pool of project {}: {err:#}",
+                    project.project.id
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn sync_project(&self, project: &FullProjectState) -> anyhow::Result<()> {
+        let Some(state) = self
+            .tools
+            .instructions_builder
+            .for_project(project)?
+            .fetch_raydium_pool_state()
+            .await?
+        else {
+            debug!(
+                "project {} is not deployed to raydium yet",
+                project.project.id
+            );
+            return Ok(());
+        };
+        StoredRaydiumPoolState::upsert(
+            &self.tools.storage.pool,
+            &project.project.id,
+            &state.amm_pool,
+            state.price_lamports_per_token(),
+            state.slot,
+            state.sol_reserves,
+        )
+        .await
+    }
+}
@@ -0,0 +1,36 @@
+use crate::app::storage::{jito::load_tip_strategy, StorageClient};
+use services_common::solana::pool::SolanaPool;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+/// Picks up jito tip strategy, changed by admins, without migrator restart.
+pub(super) struct TipStrategyWatcher {
+    pub(super) storage: StorageClient,
+    pub(super) solana_pool: SolanaPool,
+    pub(super) interval: Duration,
+}
+
+impl TipStrategyWatcher {
+    pub(super) fn serve(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = self.tick().await {
+                    error!("jito tip strategy refresh failed: {err:#}");
+                }
+                tokio::time::sleep(self.interval).await;
+            }
+        })
+    }
+
+    async fn tick(&self) -> anyhow::Result<()> {
+        let Some(strategy) = load_tip_strategy(&self.storage.pool).await? else {
+            return Ok(());
+        };
+        if self.solana_pool.jito_client().tip_strategy().await != strategy {
+            info!("switching jito tip strategy to {strategy:?}");
+            self.solana_pool.set_jito_tip_strategy(strategy).await?;
+        }
+        Ok(())
+    }
+}
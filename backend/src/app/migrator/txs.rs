@@ -1,88 +1,373 @@
-use crate::app::instructions::solana;
+use crate::{
+    app::{
+        instructions::solana,
+        storage::{project::ProjectId, tx_failures::StoredTxFailure, StorageClient},
+    },
+    metrics::{
+        COMPUTE_UNITS_ACTUAL, COMPUTE_UNITS_ESTIMATED, COMPUTE_UNIT_ESTIMATION_ERROR_RATIO,
+        JITO_BUNDLE_CONFIRM_DURATION, RPC_SUBMISSION_LATENCY, TX_DEDUPLICATED, TX_FAILURES,
+    },
+};
 use anyhow::{bail, Context as _};
+use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use services_common::{
-    solana::{any_tx::AnyTx, pool::SolanaPool},
+    solana::{any_tx::AnyTx, jito::BundleStatus, pool::SolanaPool},
     utils::period_fetch::DataReceiver,
 };
+use solana_client::{
+    rpc_client::SerializableTransaction,
+    rpc_config::{RpcSimulateTransactionConfig, RpcTransactionConfig},
+};
 use solana_sdk::{
     commitment_config::{CommitmentConfig, CommitmentLevel},
+    compute_budget::ComputeBudgetInstruction,
     hash::Hash,
-    instruction::Instruction,
+    instruction::{Instruction, InstructionError},
     signature::{Keypair, Signature},
     signer::Signer,
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
-use std::{sync::Arc, time::Duration};
+use solana_transaction_status::UiTransactionEncoding;
+use std::{future::Future, sync::Arc, time::Duration};
 use tokio::time::Instant;
 use tracing::{debug, info, instrument, warn};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct TxExecutorConfig {
-    pub max_tries: u32,
-    #[serde(with = "humantime_serde")]
-    pub err_retry_interval: Duration,
+    /// Retries of a failed submission, on top of the first try.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Delay before a retry, doubled on each network error, see [`MAX_BACKOFF_EXPONENT`].
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+    #[serde(default = "default_refresh_blockhash_on_expiry")]
+    pub refresh_blockhash_on_expiry: bool,
+    /// Times a single try re-signs with the latest blockhash, before it's counted as failed.
+    #[serde(default = "default_max_blockhash_refreshes")]
+    pub max_blockhash_refreshes: u32,
+    /// Simulate each transaction to set compute unit limit close to the consumed amount.
+    /// Costs an extra rpc round-trip per transaction.
+    #[serde(default)]
+    pub auto_simulate_compute_units: bool,
+    #[serde(default)]
+    pub compute_budget: ComputeBudgetConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, serde_derive_default::Default)]
+pub struct ComputeBudgetConfig {
+    /// Used when simulation is disabled or failed, runtime default applies if unset.
+    #[serde(default)]
+    pub compute_unit_limit: Option<u32>,
+    /// Upper bound for the simulated limit.
+    #[serde(default = "default_max_compute_units")]
+    pub max_compute_units: u32,
+}
+
+impl TxExecutorConfig {
+    fn retry_delay(&self) -> Duration {
+        Duration::from_millis(self.retry_delay_ms)
+    }
+}
+
+fn default_max_compute_units() -> u32 {
+    1_400_000
+}
+
+/// Safety margin over the simulated consumption, as state may change before landing.
+const COMPUTE_UNITS_BUFFER: f64 = 1.15;
+
+fn default_max_retries() -> u32 {
+    3
 }
 
-pub fn default_err_retry_interval() -> Duration {
-    Duration::from_millis(200)
+fn default_retry_delay_ms() -> u64 {
+    200
 }
 
+fn default_refresh_blockhash_on_expiry() -> bool {
+    true
+}
+
+fn default_max_blockhash_refreshes() -> u32 {
+    3
+}
+
+/// Delay between tries, failed on network errors, stops doubling at `retry_delay_ms * 2^6`.
+const MAX_BACKOFF_EXPONENT: u32 = 6;
+
+/// Blockhash is valid for 150 slots, so the same transaction couldn't be signed again later.
+const SENT_SIGNATURE_TTL: Duration = Duration::from_secs(150);
+
 pub struct TxExecutor {
     solana_pool: SolanaPool,
     solana_meta: DataReceiver<solana::Meta>,
+    storage: StorageClient,
     config: Arc<TxExecutorConfig>,
+    sent_signatures: SentSignatures,
 }
 
 impl TxExecutor {
     pub fn new(
         solana_pool: SolanaPool,
         solana_meta: DataReceiver<solana::Meta>,
+        storage: StorageClient,
         config: TxExecutorConfig,
     ) -> Self {
         Self {
             solana_pool,
             solana_meta,
+            storage,
             config: Arc::new(config),
+            sent_signatures: SentSignatures::default(),
         }
     }
 
     pub async fn execute_single(&self, request: TransactionRequest) -> anyhow::Result<()> {
         let mut solana_meta = self.solana_meta.clone();
+        let compute_units = self.compute_units(&request).await;
         let mut tries = 0;
-        while tries <= self.config.max_tries {
+        loop {
             tries += 1;
-            let result = self.execute_single_tick(&mut solana_meta, &request).await;
+            let result = self
+                .execute_single_tick(&mut solana_meta, &request, &compute_units)
+                .await;
             match result {
                 Ok(Ok(())) => return Ok(()),
                 Ok(Err(err)) => {
                     bail!("fatal error, stop execution: {err:?}");
                 }
                 Err(err) => {
-                    warn!("transaction submission failed, retrying: {err:?}");
-                    tokio::time::sleep(self.config.err_retry_interval).await;
+                    if tries > self.config.max_retries {
+                        bail!(
+                            "transaction submission failed after {} retries: {err:?}",
+                            self.config.max_retries
+                        );
+                    }
+                    let delay = retry_delay(self.config.retry_delay(), tries, &err);
+                    warn!(
+                        "transaction submission failed on try {tries}, retrying in {delay:?}: {err:?}"
+                    );
+                    tokio::time::sleep(delay).await;
                 }
             }
         }
-        bail!(
-            "transaction submission failed after {} tries",
-            self.config.max_tries
-        );
     }
 
     async fn execute_single_tick(
         &self,
         meta: &mut DataReceiver<solana::Meta>,
         request: &TransactionRequest,
+        compute_units: &ComputeUnits,
     ) -> anyhow::Result<anyhow::Result<()>> {
         let blockhash = meta.get()?.recent_blockhash;
-        let tx = request.signed(blockhash)?;
-        let signature = self.solana_pool.jito_client().submit_single_tx(&tx).await?;
-        self.wait_by_signature(&signature).await?;
+        let submit = |blockhash: Hash| async move {
+            let tx = request.signed(blockhash, compute_units.limit)?;
+            if self.already_landed(&[*tx.get_signature()]).await? {
+                return Ok(());
+            }
+            let signature = self.solana_pool.jito_client().submit_single_tx(&tx).await?;
+            self.sent_signatures.mark(&[signature], SentStatus::Pending);
+            let result = self.wait_by_signature(&signature).await;
+            match &result {
+                Ok(()) => {
+                    self.sent_signatures
+                        .mark(&[signature], SentStatus::Confirmed);
+                    self.observe_compute_units(&signature, compute_units).await
+                }
+                Err(err) => {
+                    if let Some(error_type) = onchain_error_type(err) {
+                        self.capture_failure(request.project_id, &signature, err, &error_type)
+                            .await;
+                    }
+                }
+            }
+            result
+        };
+        submit_with_blockhash_refresh(
+            self.blockhash_refreshes(),
+            blockhash,
+            || self.fetch_latest_blockhash(),
+            submit,
+        )
+        .await?;
 
         Ok(Ok(()))
     }
 
+    async fn compute_units(&self, request: &TransactionRequest) -> ComputeUnits {
+        let estimated = if self.config.auto_simulate_compute_units {
+            self.estimate_compute_units(request).await
+        } else {
+            None
+        };
+        ComputeUnits {
+            estimated,
+            limit: estimated.or(self.config.compute_budget.compute_unit_limit),
+        }
+    }
+
+    async fn compute_units_all(&self, requests: &[TransactionRequest]) -> Vec<ComputeUnits> {
+        let mut compute_units = Vec::with_capacity(requests.len());
+        for request in requests {
+            compute_units.push(self.compute_units(request).await);
+        }
+        compute_units
+    }
+
+    /// Returns `None` if simulation failed, e.g. transaction relies on accounts,
+    /// which are created by previous transactions of the same bundle.
+    async fn estimate_compute_units(&self, request: &TransactionRequest) -> Option<u32> {
+        let max_compute_units = self.config.compute_budget.max_compute_units;
+        // left unsigned with zeroed signatures, so keypairs sign only the submitted transaction
+        let tx = Transaction::new_with_payer(
+            &request.instructions_with_limit(Some(max_compute_units)),
+            Some(&request.payer.pubkey()),
+        );
+        let timer = RPC_SUBMISSION_LATENCY
+            .with_label_values(&["simulate"])
+            .start_timer();
+        let simulation = self
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .simulate_transaction_with_config(
+                &tx,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    ..Default::default()
+                },
+            )
+            .await;
+        timer.observe_duration();
+
+        let consumed = match simulation {
+            Ok(response) => match response.value.err {
+                None => response.value.units_consumed,
+                Some(err) => {
+                    debug!("transaction simulation failed: {err:?}");
+                    None
+                }
+            },
+            Err(err) => {
+                debug!("transaction simulation request failed: {err:#}");
+                None
+            }
+        };
+        if consumed.is_none() {
+            info!("compute units are not estimated, falling back to configured limit");
+        }
+        consumed.map(|consumed| buffered_compute_units(consumed, max_compute_units))
+    }
+
+    /// Compares estimation with units, consumed by the landed transaction.
+    async fn observe_compute_units(&self, signature: &Signature, compute_units: &ComputeUnits) {
+        let Some(estimated) = compute_units.estimated else {
+            return;
+        };
+        let tx_info = self
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await;
+        let actual = match tx_info {
+            Ok(tx_info) => tx_info
+                .transaction
+                .meta
+                .and_then(|meta| Option::<u64>::from(meta.compute_units_consumed)),
+            Err(err) => {
+                debug!("failed to fetch landed transaction {signature}: {err:#}");
+                None
+            }
+        };
+        let Some(actual) = actual else {
+            return;
+        };
+
+        info!("transaction {signature} consumed {actual} compute units, estimated {estimated}");
+        COMPUTE_UNITS_ESTIMATED.observe(estimated as f64);
+        COMPUTE_UNITS_ACTUAL.observe(actual as f64);
+        if actual > 0 {
+            COMPUTE_UNIT_ESTIMATION_ERROR_RATIO
+                .observe((estimated as f64 - actual as f64).abs() / actual as f64);
+        }
+    }
+
+    fn blockhash_refreshes(&self) -> u32 {
+        if self.config.refresh_blockhash_on_expiry {
+            self.config.max_blockhash_refreshes
+        } else {
+            0
+        }
+    }
+
+    async fn fetch_latest_blockhash(&self) -> anyhow::Result<Hash> {
+        let timer = RPC_SUBMISSION_LATENCY
+            .with_label_values(&["get_blockhash"])
+            .start_timer();
+        let blockhash = self
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .get_latest_blockhash()
+            .await?;
+        timer.observe_duration();
+        Ok(blockhash)
+    }
+
+    /// Retry after a timeout may sign exactly the same transactions, if blockhash is not changed.
+    /// Bundles land atomically, so any landed signature stands for the whole submission.
+    async fn already_landed(&self, signatures: &[Signature]) -> anyhow::Result<bool> {
+        for signature in signatures {
+            match self.sent_signatures.status(signature) {
+                None => continue,
+                Some(SentStatus::Confirmed) => {}
+                Some(SentStatus::Pending) => {
+                    // previous submission could land after the wait has timed out
+                    let status = self
+                        .solana_pool
+                        .rpc_client()
+                        .use_single()
+                        .await
+                        .get_signature_status_with_commitment(
+                            signature,
+                            CommitmentConfig::confirmed(),
+                        )
+                        .await?;
+                    match status {
+                        None => continue,
+                        Some(Ok(())) => {
+                            self.sent_signatures.mark(signatures, SentStatus::Confirmed)
+                        }
+                        Some(Err(err)) => {
+                            return Err(
+                                anyhow::Error::from(err).context("transaction returned error")
+                            )
+                        }
+                    }
+                }
+            }
+            info!("transaction {signature} has already landed, submission is skipped");
+            TX_DEDUPLICATED.inc();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
     #[instrument(skip(self))]
     async fn wait_by_signature(&self, signature: &Signature) -> anyhow::Result<()> {
         let wait_commitment = CommitmentConfig::confirmed();
@@ -105,7 +390,7 @@ impl TxExecutor {
                 continue;
             };
             if let Err(err) = result {
-                return Err(anyhow::anyhow!("transaction returned error: {err:?}"));
+                return Err(anyhow::Error::from(err).context("transaction returned error"));
             }
 
             info!("transaction confirmed successfully");
@@ -117,52 +402,275 @@ impl TxExecutor {
 
     pub async fn execute_batch(&self, requests: Vec<TransactionRequest>) -> anyhow::Result<()> {
         let mut solana_meta = self.solana_meta.clone();
+        let compute_units = self.compute_units_all(&requests).await;
         let mut tries = 0;
-        while tries <= self.config.max_tries {
+        loop {
             tries += 1;
-            let result = self.execute_batch_tick(&mut solana_meta, &requests).await;
+            let result = self
+                .execute_batch_tick(&mut solana_meta, &requests, &compute_units)
+                .await;
             match result {
                 Ok(Ok(())) => return Ok(()),
                 Ok(Err(err)) => {
                     bail!("fatal error, stop execution: {err:?}");
                 }
                 Err(err) => {
-                    warn!("transaction batch submission failed, retrying: {err:?}");
-                    tokio::time::sleep(self.config.err_retry_interval).await;
+                    if tries > self.config.max_retries {
+                        bail!(
+                            "transaction batch submission failed after {} retries: {err:?}",
+                            self.config.max_retries
+                        );
+                    }
+                    let delay = retry_delay(self.config.retry_delay(), tries, &err);
+                    warn!(
+                        "transaction batch submission failed on try {tries}, retrying in {delay:?}: {err:?}"
+                    );
+                    tokio::time::sleep(delay).await;
                 }
             }
         }
-        bail!(
-            "transaction batch submission failed after {} tries",
-            self.config.max_tries
-        );
     }
 
     async fn execute_batch_tick(
         &self,
         meta: &mut DataReceiver<solana::Meta>,
         requests: &[TransactionRequest],
+        compute_units: &[ComputeUnits],
     ) -> anyhow::Result<anyhow::Result<()>> {
         let blockhash = meta.get()?.recent_blockhash;
+        let submit = |blockhash: Hash| async move {
+            let txs = sign_all(requests, compute_units, blockhash)?;
+            let signatures: Vec<_> = txs.iter().map(|tx| *tx.get_signature()).collect();
+            if self.already_landed(&signatures).await? {
+                return Ok(());
+            }
 
-        let txs = requests
-            .iter()
-            .enumerate()
-            .map(|(idx, request)| {
-                request
-                    .signed(blockhash)
-                    .with_context(|| format!("signing #{idx} transaction"))
-            })
-            .collect::<anyhow::Result<Vec<_>>>()?;
-
-        let bundle_id = self.solana_pool.jito_client().submit_bundle(txs).await?;
-        self.watch_by_bundle_id(bundle_id).await?;
+            let bundle_id = self.solana_pool.jito_client().submit_bundle(txs).await?;
+            self.sent_signatures.mark(&signatures, SentStatus::Pending);
+            self.watch_by_bundle_id(bundle_id, bundle_project_id(requests))
+                .await?;
+            self.sent_signatures
+                .mark(&signatures, SentStatus::Confirmed);
+            for (signature, compute_units) in signatures.iter().zip(compute_units) {
+                self.observe_compute_units(signature, compute_units).await;
+            }
+            Ok(())
+        };
+        submit_with_blockhash_refresh(
+            self.blockhash_refreshes(),
+            blockhash,
+            || self.fetch_latest_blockhash(),
+            submit,
+        )
+        .await?;
 
         Ok(Ok(()))
     }
 
+    async fn capture_bundle_failure(
+        &self,
+        project_id: Option<ProjectId>,
+        status: &BundleStatus,
+        err: &anyhow::Error,
+    ) {
+        let signatures = match status.signatures() {
+            Ok(signatures) => signatures,
+            Err(decode_err) => {
+                warn!("failed bundle signatures are not captured: {decode_err:#}");
+                TX_FAILURES.with_label_values(&["bundle"]).inc();
+                return;
+            }
+        };
+        for signature in signatures.iter() {
+            self.capture_failure(project_id, signature, err, "bundle")
+                .await;
+        }
+    }
+
+    /// Stores failed transaction together with its logs for later review.
+    /// Best-effort: capture errors are only logged, original error is propagated by caller.
+    async fn capture_failure(
+        &self,
+        project_id: Option<ProjectId>,
+        signature: &Signature,
+        err: &anyhow::Error,
+        error_type: &str,
+    ) {
+        TX_FAILURES.with_label_values(&[error_type]).inc();
+
+        let mut logs = vec![];
+        let mut slot = None;
+        let tx_info = self
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::JsonParsed),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await;
+        if let Ok(tx_info) = tx_info {
+            slot = i64::try_from(tx_info.slot).ok();
+            logs = tx_info
+                .transaction
+                .meta
+                .and_then(|meta| Option::<Vec<String>>::from(meta.log_messages))
+                .unwrap_or_default();
+        }
+
+        if let Err(store_err) = StoredTxFailure::insert(
+            &self.storage.pool,
+            project_id,
+            &signature.to_string(),
+            &format!("{err:#}"),
+            &logs,
+            slot,
+        )
+        .await
+        {
+            warn!("failed to store transaction {signature} failure: {store_err:#}");
+        }
+    }
+
+    /// Submits transactions as a single bundle and waits until it reaches `confirmation`.
+    pub async fn execute_bundle_with_confirmation(
+        &self,
+        requests: Vec<TransactionRequest>,
+        confirmation: CommitmentLevel,
+        timeout: Duration,
+    ) -> anyhow::Result<BundleConfirmation> {
+        let mut solana_meta = self.solana_meta.clone();
+        let compute_units = self.compute_units_all(&requests).await;
+        let mut tries = 0;
+        loop {
+            tries += 1;
+            let blockhash = solana_meta.get()?.recent_blockhash;
+            let submit = |blockhash: Hash| {
+                let requests = &requests;
+                let compute_units = &compute_units;
+                async move {
+                    let txs = sign_all(requests, compute_units, blockhash)?;
+                    let signatures: Vec<_> = txs.iter().map(|tx| *tx.get_signature()).collect();
+                    if self.already_landed(&signatures).await? {
+                        // still waited for, as requested commitment may be above the landed one
+                        let bundle_id = self
+                            .sent_signatures
+                            .bundle_id(&signatures)
+                            .context("landed bundle id is not remembered")?;
+                        return self
+                            .wait_bundle_confirmation(
+                                bundle_id,
+                                bundle_project_id(requests),
+                                confirmation,
+                                timeout,
+                            )
+                            .await;
+                    }
+
+                    let bundle_id = self.solana_pool.jito_client().submit_bundle(txs).await?;
+                    self.sent_signatures.mark(&signatures, SentStatus::Pending);
+                    self.sent_signatures.mark_bundle(&signatures, &bundle_id);
+                    let confirmed = self
+                        .wait_bundle_confirmation(
+                            bundle_id,
+                            bundle_project_id(requests),
+                            confirmation,
+                            timeout,
+                        )
+                        .await?;
+                    self.sent_signatures
+                        .mark(&signatures, SentStatus::Confirmed);
+                    for (signature, compute_units) in confirmed.signatures.iter().zip(compute_units)
+                    {
+                        self.observe_compute_units(signature, compute_units).await;
+                    }
+                    Ok(confirmed)
+                }
+            };
+            let result = submit_with_blockhash_refresh(
+                self.blockhash_refreshes(),
+                blockhash,
+                || self.fetch_latest_blockhash(),
+                submit,
+            )
+            .await;
+            match result {
+                Ok(confirmed) => return Ok(confirmed),
+                Err(err) => {
+                    if tries > self.config.max_retries {
+                        bail!(
+                            "bundle submission failed after {} retries: {err:?}",
+                            self.config.max_retries
+                        );
+                    }
+                    let delay = retry_delay(self.config.retry_delay(), tries, &err);
+                    warn!(
+                        "bundle submission failed on try {tries}, retrying in {delay:?}: {err:?}"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn wait_bundle_confirmation(
+        &self,
+        bundle_id: String,
+        project_id: Option<ProjectId>,
+        confirmation: CommitmentLevel,
+        timeout: Duration,
+    ) -> anyhow::Result<BundleConfirmation> {
+        let poll_interval = Duration::from_millis(500);
+        let timer = JITO_BUNDLE_CONFIRM_DURATION.start_timer();
+        let before = Instant::now();
+
+        while before.elapsed() < timeout {
+            let status = match self
+                .solana_pool
+                .jito_client()
+                .get_bundle_status(&bundle_id)
+                .await
+            {
+                Ok(status) => status,
+                Err(err) => {
+                    debug!("bundle status is not available yet: {err:#}");
+                    tokio::time::sleep(poll_interval).await;
+                    continue;
+                }
+            };
+            if let Err(err) = &status.err {
+                timer.stop_and_discard();
+                let err = anyhow::anyhow!("bundle {bundle_id} resulted in error: {err:?}");
+                self.capture_bundle_failure(project_id, &status, &err).await;
+                return Err(err);
+            }
+            if commitment_rank(status.confirmation_status) >= commitment_rank(confirmation) {
+                timer.observe_duration();
+                info!("bundle reached {confirmation:?} commitment");
+                return Ok(BundleConfirmation {
+                    signatures: status.signatures()?,
+                    slot: status.slot,
+                    bundle_id,
+                });
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+        timer.stop_and_discard();
+        bail!("bundle {bundle_id} was not confirmed in {timeout:?}")
+    }
+
     #[instrument(skip(self))]
-    async fn watch_by_bundle_id(&self, bundle_id: String) -> anyhow::Result<()> {
+    async fn watch_by_bundle_id(
+        &self,
+        bundle_id: String,
+        project_id: Option<ProjectId>,
+    ) -> anyhow::Result<()> {
         let wait_commitment = CommitmentLevel::Confirmed;
         let confirm_timeout = Duration::from_secs(2);
 
@@ -178,8 +686,10 @@ impl TxExecutor {
                 info!("bundle completed successfully");
                 return Ok(());
             }
-            if let Err(err) = status.err {
-                bail!("transaction batch resulted in error: {err:?}");
+            if let Err(err) = &status.err {
+                let err = anyhow::anyhow!("transaction batch resulted in error: {err:?}");
+                self.capture_bundle_failure(project_id, &status, &err).await;
+                return Err(err);
             }
             tokio::time::sleep(Duration::from_millis(300)).await;
         }
@@ -187,19 +697,205 @@ impl TxExecutor {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SentStatus {
+    /// Submitted, but not seen confirmed yet.
+    Pending,
+    Confirmed,
+}
+
+/// Signatures of submitted transactions, kept in memory, as they're useful for retries only.
+#[derive(Default)]
+struct SentSignatures {
+    signatures: DashMap<Signature, (SentStatus, Instant)>,
+    /// Bundle, the signature was submitted in, so the landed bundle is confirmed by its id.
+    bundles: DashMap<Signature, String>,
+}
+
+impl SentSignatures {
+    fn status(&self, signature: &Signature) -> Option<SentStatus> {
+        self.signatures
+            .get(signature)
+            .filter(|sent| sent.value().1.elapsed() < SENT_SIGNATURE_TTL)
+            .map(|sent| sent.value().0)
+    }
+
+    fn mark(&self, signatures: &[Signature], status: SentStatus) {
+        self.signatures
+            .retain(|_, (_, sent_at)| sent_at.elapsed() < SENT_SIGNATURE_TTL);
+        let now = Instant::now();
+        for signature in signatures {
+            self.signatures.insert(*signature, (status, now));
+        }
+    }
+
+    fn mark_bundle(&self, signatures: &[Signature], bundle_id: &str) {
+        self.bundles
+            .retain(|signature, _| self.signatures.contains_key(signature));
+        for signature in signatures {
+            self.bundles.insert(*signature, bundle_id.to_string());
+        }
+    }
+
+    fn bundle_id(&self, signatures: &[Signature]) -> Option<String> {
+        signatures
+            .iter()
+            .filter(|signature| self.status(signature).is_some())
+            .find_map(|signature| self.bundles.get(signature).map(|id| id.value().clone()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BundleConfirmation {
+    pub bundle_id: String,
+    pub signatures: Vec<Signature>,
+    pub slot: u64,
+}
+
+struct ComputeUnits {
+    /// Set only when limit is derived from simulation.
+    estimated: Option<u32>,
+    limit: Option<u32>,
+}
+
+fn buffered_compute_units(consumed: u64, max_compute_units: u32) -> u32 {
+    let buffered = (consumed as f64 * COMPUTE_UNITS_BUFFER).ceil() as u64;
+    buffered.min(max_compute_units as u64) as u32
+}
+
+fn sign_all(
+    requests: &[TransactionRequest],
+    compute_units: &[ComputeUnits],
+    blockhash: Hash,
+) -> anyhow::Result<Vec<AnyTx>> {
+    requests
+        .iter()
+        .zip(compute_units)
+        .enumerate()
+        .map(|(idx, (request, compute_units))| {
+            request
+                .signed(blockhash, compute_units.limit)
+                .with_context(|| format!("signing #{idx} transaction"))
+        })
+        .collect()
+}
+
+/// Failures are attributed to the project of the first transaction in bundle.
+fn bundle_project_id(requests: &[TransactionRequest]) -> Option<ProjectId> {
+    requests.iter().find_map(|request| request.project_id)
+}
+
+/// Returns error type if transaction was processed and failed on-chain.
+fn onchain_error_type(err: &anyhow::Error) -> Option<String> {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<TransactionError>())
+        .map(|err| {
+            let name = format!("{err:?}");
+            name.split(['(', ' ', '{'])
+                .next()
+                .unwrap_or_default()
+                .to_owned()
+        })
+}
+
+fn commitment_rank(level: CommitmentLevel) -> u8 {
+    match level {
+        CommitmentLevel::Processed => 0,
+        CommitmentLevel::Confirmed => 1,
+        CommitmentLevel::Finalized => 2,
+    }
+}
+
+/// Submits transactions signed with given blockhash.
+/// While submission fails because blockhash is expired, fetches the latest one,
+/// re-signs and retries, up to `max_refreshes` times.
+async fn submit_with_blockhash_refresh<T, F, FFut, S, SFut>(
+    max_refreshes: u32,
+    mut blockhash: Hash,
+    fetch_latest: F,
+    submit: S,
+) -> anyhow::Result<T>
+where
+    F: Fn() -> FFut,
+    FFut: Future<Output = anyhow::Result<Hash>>,
+    S: Fn(Hash) -> SFut,
+    SFut: Future<Output = anyhow::Result<T>>,
+{
+    let mut refreshes = 0;
+    loop {
+        match submit(blockhash).await {
+            Err(err) if refreshes < max_refreshes && is_blockhash_expired(&err) => {
+                refreshes += 1;
+                warn!(
+                    "blockhash {blockhash} expired, re-signing with the latest one \
+                     ({refreshes}/{max_refreshes}): {err:#}"
+                );
+                blockhash = fetch_latest().await?;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// On-chain errors are retried at the fixed interval, as they don't depend on the node,
+/// while network ones back off exponentially, giving the node time to recover.
+fn retry_delay(interval: Duration, failed_tries: u32, err: &anyhow::Error) -> Duration {
+    if onchain_error_type(err).is_some() {
+        return interval;
+    }
+    interval.saturating_mul(1 << failed_tries.saturating_sub(1).min(MAX_BACKOFF_EXPONENT))
+}
+
+fn is_blockhash_expired(err: &anyhow::Error) -> bool {
+    let typed = err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<TransactionError>(),
+            Some(TransactionError::BlockhashNotFound)
+                | Some(TransactionError::InstructionError(
+                    _,
+                    InstructionError::InvalidArgument
+                ))
+        )
+    });
+    // bundle statuses and rpc responses report errors as plain strings
+    typed || format!("{err:#}").contains("BlockhashNotFound")
+}
+
 pub struct TransactionRequest {
     pub instructions: Vec<Instruction>,
     pub signers: Vec<Keypair>,
     pub payer: Keypair,
+    /// Project, on behalf of which transaction is sent, used for failures tracking.
+    pub project_id: Option<ProjectId>,
+    /// Attached via memo program, signed by the payer.
+    pub memo: Option<String>,
 }
 
 impl TransactionRequest {
-    fn signed(&self, recent_blockhash: Hash) -> anyhow::Result<AnyTx> {
-        let mut tx = Transaction::new_with_payer(&self.instructions, Some(&self.payer.pubkey()));
+    fn instructions_with_limit(&self, compute_unit_limit: Option<u32>) -> Vec<Instruction> {
+        let payer = self.payer.pubkey();
+        compute_unit_limit
+            .map(ComputeBudgetInstruction::set_compute_unit_limit)
+            .into_iter()
+            .chain(
+                self.memo
+                    .as_ref()
+                    .map(|memo| spl_memo::build_memo(memo.as_bytes(), &[&payer])),
+            )
+            .chain(self.instructions.iter().cloned())
+            .collect()
+    }
+
+    fn signed(
+        &self,
+        recent_blockhash: Hash,
+        compute_unit_limit: Option<u32>,
+    ) -> anyhow::Result<AnyTx> {
+        let instructions = self.instructions_with_limit(compute_unit_limit);
+        let mut tx = Transaction::new_with_payer(&instructions, Some(&self.payer.pubkey()));
         tx.try_sign(&self.signers.iter().collect::<Vec<_>>(), recent_blockhash)
             .map_err(|err| {
-                let signers: Vec<_> = self
-                    .instructions
+                let signers: Vec<_> = instructions
                     .iter()
                     .enumerate()
                     .flat_map(|(ix_idx, instruction)| {
@@ -219,3 +915,191 @@ impl TransactionRequest {
         Ok(AnyTx::from(tx))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_retries_with_fresh_blockhash_on_expiry() {
+        let expired = Hash::new_unique();
+        let fresh = Hash::new_unique();
+        let attempts = Mutex::new(vec![]);
+
+        let result = submit_with_blockhash_refresh(
+            3,
+            expired,
+            || async { Ok(fresh) },
+            |blockhash| {
+                attempts.lock().unwrap().push(blockhash);
+                async move {
+                    if blockhash == expired {
+                        return Err(anyhow::Error::from(TransactionError::BlockhashNotFound)
+                            .context("transaction returned error"));
+                    }
+                    Ok(())
+                }
+            },
+        )
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(*attempts.lock().unwrap(), vec![expired, fresh]);
+    }
+
+    #[tokio::test]
+    async fn test_stops_refreshing_blockhash_after_limit() {
+        let attempts = Mutex::new(0);
+
+        let result: anyhow::Result<()> = submit_with_blockhash_refresh(
+            2,
+            Hash::new_unique(),
+            || async { Ok(Hash::new_unique()) },
+            |_| {
+                *attempts.lock().unwrap() += 1;
+                async { Err(anyhow::Error::from(TransactionError::BlockhashNotFound)) }
+            },
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(*attempts.lock().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_backs_off_on_network_errors_only() {
+        let interval = Duration::from_millis(200);
+        let network = anyhow::anyhow!("connection reset");
+        let delays: Vec<_> = (1..=4)
+            .map(|tries| retry_delay(interval, tries, &network).as_millis())
+            .collect();
+        assert_eq!(delays, vec![200, 400, 800, 1600]);
+        assert_eq!(
+            retry_delay(interval, 100, &network),
+            interval * (1 << MAX_BACKOFF_EXPONENT)
+        );
+
+        let onchain = anyhow::Error::from(TransactionError::AccountInUse)
+            .context("transaction returned error");
+        assert_eq!(retry_delay(interval, 4, &onchain), interval);
+    }
+
+    #[test]
+    fn test_extracts_onchain_error_type() {
+        let err = anyhow::Error::from(TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(6001),
+        ))
+        .context("transaction returned error");
+        assert_eq!(
+            onchain_error_type(&err).as_deref(),
+            Some("InstructionError")
+        );
+        assert_eq!(
+            onchain_error_type(&anyhow::anyhow!("timeout elapsed")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_buffers_simulated_compute_units() {
+        assert_eq!(buffered_compute_units(100_000, 1_400_000), 115_000);
+        assert_eq!(buffered_compute_units(1_300_000, 1_400_000), 1_400_000);
+    }
+
+    #[test]
+    fn test_prepends_compute_unit_limit() {
+        let payer = Keypair::new();
+        let request = TransactionRequest {
+            instructions: vec![Instruction::new_with_bytes(
+                solana_sdk::system_program::ID,
+                &[],
+                vec![],
+            )],
+            signers: vec![payer.insecure_clone()],
+            payer,
+            project_id: None,
+            memo: None,
+        };
+        assert_eq!(request.instructions_with_limit(None), request.instructions);
+
+        let instructions = request.instructions_with_limit(Some(50_000));
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(
+            instructions[0],
+            ComputeBudgetInstruction::set_compute_unit_limit(50_000)
+        );
+    }
+
+    #[test]
+    fn test_places_memo_after_compute_unit_limit() {
+        let payer = Keypair::new();
+        let memo = "moonzip:graduate:ünïcode";
+        let request = TransactionRequest {
+            instructions: vec![Instruction::new_with_bytes(
+                solana_sdk::system_program::ID,
+                &[],
+                vec![],
+            )],
+            signers: vec![payer.insecure_clone()],
+            payer: payer.insecure_clone(),
+            project_id: None,
+            memo: Some(memo.to_owned()),
+        };
+
+        let instructions = request.instructions_with_limit(Some(50_000));
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[1].program_id, spl_memo::id());
+        assert_eq!(instructions[1].data, memo.as_bytes());
+        assert_eq!(instructions[1].accounts[0].pubkey, payer.pubkey());
+        assert!(instructions[1].accounts[0].is_signer);
+        assert_eq!(instructions[2], request.instructions[0]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_remembers_sent_signatures_until_blockhash_expires() {
+        let sent = SentSignatures::default();
+        let (first, second) = (Signature::new_unique(), Signature::new_unique());
+        assert_eq!(sent.status(&first), None);
+
+        sent.mark(&[first, second], SentStatus::Pending);
+        assert_eq!(sent.status(&first), Some(SentStatus::Pending));
+        sent.mark(&[first], SentStatus::Confirmed);
+        assert_eq!(sent.status(&first), Some(SentStatus::Confirmed));
+        assert_eq!(sent.status(&second), Some(SentStatus::Pending));
+
+        tokio::time::advance(SENT_SIGNATURE_TTL).await;
+        assert_eq!(sent.status(&first), None);
+        sent.mark(&[Signature::new_unique()], SentStatus::Pending);
+        assert_eq!(sent.signatures.len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_remembers_bundle_of_sent_signatures() {
+        let sent = SentSignatures::default();
+        let signatures = [Signature::new_unique(), Signature::new_unique()];
+        assert_eq!(sent.bundle_id(&signatures), None);
+
+        sent.mark(&signatures, SentStatus::Pending);
+        sent.mark_bundle(&signatures, "bundle");
+        assert_eq!(sent.bundle_id(&signatures[1..]), Some("bundle".to_string()));
+
+        tokio::time::advance(SENT_SIGNATURE_TTL).await;
+        assert_eq!(sent.bundle_id(&signatures), None);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_when_refresh_disabled() {
+        let result: anyhow::Result<()> = submit_with_blockhash_refresh(
+            0,
+            Hash::new_unique(),
+            || async { Err(anyhow::anyhow!("blockhash must not be refetched")) },
+            |_| async { Err(anyhow::Error::from(TransactionError::BlockhashNotFound)) },
+        )
+        .await;
+
+        let err = result.unwrap_err();
+        assert!(err.downcast_ref::<TransactionError>().is_some());
+    }
+}
@@ -1,79 +1,341 @@
 use crate::app::exposed::{ChangeUserInfoRequest, GetUserInformationRequest};
+use crate::app::exposed::{
+    ConfirmEmailRequest, NotificationPreferences, UpdateNotificationsRequest,
+};
 use crate::app::exposed::{GetOwnedNFTsRequest, UserInfo};
+use crate::app::notifications::{Notification, NotificationEvent, NotificationQueue};
 use crate::app::storage::misc::StoredPubkey;
+use crate::app::storage::notifications::StoredNotificationPreferences;
+use crate::metrics::{PROJECT_ARCHIVED, SEARCH_CACHE_HITS, SEARCH_QUERIES};
 use crate::solana::SolanaKeys;
 use anyhow::bail;
+use exposed::{
+    AcceptTransferRequest, AcceptTransferResponse, CancelProjectRequest, CancelProjectResponse,
+    CancelTransferRequest, CancelTransferResponse, ClaimCreatorFeesRequest,
+    ClaimCreatorFeesResponse, DeploymentRules, GetLpBurnProofRequest, GetLpBurnProofResponse,
+    GetTxFailuresRequest, LpBurnProof, MigrationLock, ProjectSearchHit, SearchProjectsRequest,
+    SearchProjectsResponse, SetFeeConfigRequest, SetFeeConfigResponse, SetJitoTipStrategyRequest,
+    SetProjectCreationFeeRequest, SetProjectCreationFeeResponse, StaticPoolProgress,
+    TransferOwnershipRequest, TransferOwnershipResponse, TxFailure, UpdateProjectMetaRequest,
+};
+use exposed::{
+    BestBuyRouteRequest, BestBuyRouteResponse, BuyExactTokensRequest, BuyRoute, RoutePreference,
+};
 use exposed::{
     BuyRequest, BuyResponse, CreateProjectRequest, CreateProjectResponse, CreateProjectStreamData,
-    DevLockClaimRequest, DevLockClaimResponse, DevLockPeriod, GetProjectRequest,
-    GetProjectResponse, PublicProject, SellRequest, SellResponse, StoredProjectInfo,
+    DevLockClaimRequest, DevLockClaimResponse, GetProjectRequest, GetProjectResponse,
+    PublicProject, PublicProjectStage, SellExactSolRequest, SellRequest, SellResponse,
+    StoredProjectInfo,
+};
+use exposed::{Candle, GetCandlesRequest, GetProjectStatsRequest, ProjectStats};
+use exposed::{
+    ChainTradeStats, LeaderboardEntry, LeaderboardRequest, LeaderboardResponse, LifetimeStats,
+    ListProjectsRequest, ListProjectsResponse,
+};
+use exposed::{CurveState, GetCurveStateRequest};
+use exposed::{DailyRevenue, GetRevenueRequest};
+use exposed::{
+    ExtendStaticPoolRequest, ExtendStaticPoolResponse, ForceUnlockRequest, ForceUnlockResponse,
+    IpfsStats, RecoverProjectRequest, RecoverProjectResponse, UpdatePoolConfigRequest,
+    UpdatePoolConfigResponse, UpgradeProposal,
+};
+use exposed::{GetHoldersRequest, GetHoldersResponse, TokenHolder};
+use exposed::{QuoteRequest, QuoteResponse, TradeSide};
+use exposed::{RegisterWebhookRequest, RegisterWebhookResponse};
+use exposed::{
+    ResolveAddressRequest, ResolveAddressResponse, ResolveSnsRequest, ResolveSnsResponse,
+};
+use exposed::{SetAllowedOwnersRequest, SetDeliveryMethodsRequest, SetLaunchPeriodsRequest};
+use http::HeaderMap;
+use image_format::{
+    compress_image, ImageFormat, InvalidImage, UnsupportedImageFormat, FORMAT_PROBE_LEN,
+    MAX_CONVERTED_IMAGE_BYTES, MAX_PROJECT_IMAGE_BYTES, MAX_PROJECT_IMAGE_DIMENSION,
 };
 use instructions::{
     mpl::{SampleMetadata, SAMPLE_MPL_URI},
-    InstructionsBuilder,
+    quote::BuyQuote,
+    ExactSolUnsupported, ExactTokensUnsupported, InstructionsBuilder, ProjectsOperations,
+    SlippageExceeded,
 };
+use live_config::LiveConfig;
+use price_stream::PriceStreamHub;
 use rustrict::CensorStr;
+use search_cache::{SearchCache, SearchKey};
 use services_common::api::response::ApiError;
 use services_common::solana::helius::{GetAssetNFTsResponse, GetOwnedNFTsResponse};
 use services_common::solana::pool::SolanaPool;
 use services_common::utils::period_fetch::DataReceiver;
-use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use sns::SnsResolver;
+use solana_sdk::{
+    hash::Hash, instruction::Instruction, program_pack::Pack as _, pubkey::Pubkey,
+    signature::Keypair, signer::Signer, transaction::Transaction,
+};
 use sqlx::query_as;
-use std::{pin::pin, time::Duration};
-use storage::project::FullProjectState;
+use static_pool_progress::StaticPoolProgressHub;
+use std::{collections::HashMap, io::Cursor, pin::pin, time::Duration};
+use storage::audit::{record_admin_action, AdminAction};
+use storage::chain_events::{StoredCandle, StoredTradeStats};
+use storage::config_overrides::{store_override, ConfigKey};
+use storage::fee_collection::StoredDailyRevenue;
+use storage::holders::StoredTokenHolder;
+use storage::ipfs_uploads::StoredIpfsStats;
+use storage::jito::store_tip_strategy;
+use storage::lp_burns::StoredLpBurn;
+use storage::migration_lock::StoredMigrationLock;
+use storage::project::{
+    CurveVariant, FullProjectState, ProjectId, ProjectIdStrategy, Stage, StoredProject,
+    StoredTokenMeta,
+};
+use storage::project_stats::{StoredLeaderboardEntry, StoredProjectStats};
+use storage::raydium_pool_state::StoredRaydiumPoolState;
+use storage::search::{
+    escape_highlight, list_projects, search_projects, ListCursor, ProjectListFilter,
+};
+use storage::static_pool::StoredStaticPoolProgress;
+use storage::tx_failures::StoredTxFailure;
+use storage::upgrade_proposals::StoredUpgradeProposal;
 use storage::user_info::StoredUserInfo;
-use storage::StorageClient;
-use tokio::io::AsyncRead;
-use tracing::debug;
+use storage::webhooks::StoredWebhook;
+use storage::{PoolStats, StorageClient};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt as _},
+    sync::watch,
+};
+use tracing::{debug, warn};
 use uuid::Uuid;
+use validation::{
+    check_projects_limits, validate_batch_create, validate_token_meta, validate_token_meta_update,
+    validate_webhook_url, InsufficientCreatorDeposit, ProjectsLimitExceeded,
+};
 
 pub mod chain_sync;
 pub mod exposed;
+pub mod image_format;
 pub mod instructions;
 pub mod keys_loader;
+pub mod live_config;
 pub mod migrator;
+pub mod notifications;
+pub mod price_stream;
+pub mod search_cache;
+pub mod sns;
+pub mod static_pool_progress;
 pub mod storage;
+pub mod validation;
+pub mod webhooks;
+
+/// Image of the new project: either uploaded along with the request, or already hosted.
+enum ProjectImage<S: AsyncRead> {
+    Content(CreateProjectStreamData<S>),
+    Hosted(String),
+}
 
 pub struct App {
     pub storage: StorageClient,
     pub instructions_builder: InstructionsBuilder,
+    /// Source of project validation rules, could be changed at runtime.
+    pub live_config: LiveConfig,
     pub keys: SolanaKeys,
     pub solana_meta: DataReceiver<instructions::solana::Meta>,
     pub solana_pool: SolanaPool,
+    pub notifications: Option<NotificationQueue>,
+    pub project_id_strategy: ProjectIdStrategy,
+    /// Search is not cached, unless configured.
+    pub search_cache: Option<SearchCache>,
+    pub static_pool_progress: StaticPoolProgressHub,
+    pub price_stream: PriceStreamHub,
+    /// `.sol` names resolution is disabled if not configured.
+    pub sns: Option<SnsResolver>,
 }
 
 impl App {
+    const MAX_TX_FAILURES_LIMIT: u32 = 500;
+    const MAX_SEARCH_LIMIT: u32 = 100;
+    const MAX_LIST_PAGE_SIZE: u32 = 50;
+    const MAX_HOLDERS_LIMIT: u32 = 100;
+    const MAX_CANDLES: i64 = 1000;
+    const MAX_UPGRADE_PROPOSALS: i64 = 100;
+    const MAX_REVENUE_DAYS: u32 = 366;
+
     pub async fn create_project(
         &self,
         request: CreateProjectRequest,
         streams: CreateProjectStreamData<impl AsyncRead>,
-    ) -> anyhow::Result<CreateProjectResponse> {
-        if let Some(static_pool) = &request.deploy_schema.static_pool {
-            if !self
-                .instructions_builder
-                .config
+    ) -> Result<CreateProjectResponse, ApiError> {
+        if request.image_url.is_some() {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "image url is accepted by batch creation only, upload the image instead"
+            )));
+        }
+        self.validate_create_request(&request)
+            .await
+            .map_err(ApiError::InvalidRequest)?;
+        self.ensure_projects_limits(&request.owner).await?;
+
+        let mut content = Vec::new();
+        pin!(streams.image_content)
+            .take(MAX_CONVERTED_IMAGE_BYTES as u64 + 1)
+            .read_to_end(&mut content)
+            .await
+            .map_err(|err| {
+                ApiError::InvalidRequest(anyhow::anyhow!("failed to read image content: {err}"))
+            })?;
+        let allowed_formats = self.live_config.read().await.allowed_image_formats.clone();
+        ImageFormat::detect_allowed(&content, &allowed_formats)
+            .map_err(|err| ApiError::InvalidRequest(err.into()))?;
+        let content = compress_image(
+            content.into(),
+            MAX_PROJECT_IMAGE_DIMENSION,
+            MAX_PROJECT_IMAGE_BYTES,
+        )
+        .await
+        .map_err(Self::store_project_error)?;
+
+        let recent_blockhash = self.solana_meta.clone().get()?.recent_blockhash;
+        let streams = CreateProjectStreamData {
+            image_content: Cursor::new(content),
+        };
+        self.store_project(request, ProjectImage::Content(streams), recent_blockhash)
+            .await
+            .map_err(Self::store_project_error)
+    }
+
+    /// Whole batch is validated first, but projects are stored one by one,
+    /// so ones before a failed project stay created, just like unsigned single creations.
+    pub async fn batch_create_projects(
+        &self,
+        requests: Vec<CreateProjectRequest>,
+    ) -> Result<Vec<CreateProjectResponse>, ApiError> {
+        validate_batch_create(&requests).map_err(ApiError::InvalidRequest)?;
+        for request in &requests {
+            self.validate_create_request(request)
+                .await
+                .map_err(ApiError::InvalidRequest)?;
+            self.ensure_projects_limits(&request.owner).await?;
+        }
+
+        let mut responses = Vec::with_capacity(requests.len());
+        for mut request in requests {
+            let image_url = request
+                .image_url
+                .take()
+                .ok_or_else(|| anyhow::anyhow!("invariant: image url is validated"))?;
+            // signing of the whole batch may take a while, so cached blockhash could expire
+            let recent_blockhash = self
+                .solana_pool
+                .rpc_client()
+                .use_single()
+                .await
+                .get_latest_blockhash()
+                .await
+                .map_err(anyhow::Error::from)?;
+            let response = self
+                .store_project(
+                    request,
+                    ProjectImage::<tokio::io::Empty>::Hosted(image_url),
+                    recent_blockhash,
+                )
+                .await
+                .map_err(Self::store_project_error)?;
+            responses.push(response);
+        }
+        Ok(responses)
+    }
+
+    fn store_project_error(err: anyhow::Error) -> ApiError {
+        let err = match err.downcast::<ProjectsLimitExceeded>() {
+            Ok(err) => return Self::projects_limit_error(err),
+            Err(err) => err,
+        };
+        if err.is::<InsufficientCreatorDeposit>()
+            || err.is::<UnsupportedImageFormat>()
+            || err.is::<InvalidImage>()
+        {
+            ApiError::InvalidRequest(err)
+        } else {
+            ApiError::Internal(err)
+        }
+    }
+
+    /// Per hour and active projects limits of the owner, absent for allowed owners.
+    async fn projects_limits(&self, owner: &Pubkey) -> Option<(u32, u32)> {
+        let config = self.live_config.read().await;
+        if config.allowed_owners.contains(owner) {
+            return None;
+        }
+        Some((
+            config.max_projects_per_owner_per_hour,
+            config.max_active_projects_per_owner,
+        ))
+    }
+
+    /// Rejects requests early, before the image is processed,
+    /// limits are enforced once again by [`Self::store_project`] within its transaction.
+    async fn ensure_projects_limits(&self, owner: &Pubkey) -> Result<(), ApiError> {
+        let Some((max_per_hour, max_active)) = self.projects_limits(owner).await else {
+            return Ok(());
+        };
+        let counts = StoredProject::count_by_owner(&self.storage.pool, &(*owner).into()).await?;
+        check_projects_limits(counts, max_per_hour, max_active).map_err(|err| {
+            warn!("projects creation by {owner} is rate limited: {err}");
+            Self::projects_limit_error(err)
+        })
+    }
+
+    fn projects_limit_error(err: ProjectsLimitExceeded) -> ApiError {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-projects-this-hour", err.counts.this_hour.into());
+        headers.insert("x-active-projects", err.counts.active.into());
+        ApiError::RateLimited(err.into(), headers)
+    }
+
+    async fn validate_create_request(&self, request: &CreateProjectRequest) -> anyhow::Result<()> {
+        let config = self.live_config.read().await;
+        validate_token_meta(&request.meta, &config.extra_blocked_words)?;
+
+        for static_pool in &request.deploy_schema.static_pools {
+            if !config
                 .allowed_launch_periods
                 .contains(&Duration::from_secs(static_pool.launch_period))
             {
                 bail!("Invalid launch period: {}", static_pool.launch_period);
             }
-        };
+            if static_pool.max_lamports == Some(0) {
+                bail!("Static pool max lamports must be positive");
+            }
+        }
 
         if let Some(dev_purchase) = &request.deploy_schema.dev_purchase {
-            if !self
-                .instructions_builder
-                .config
-                .allowed_lock_periods
-                .contains(&dev_purchase.lock)
+            if !config
+                .allowed_delivery_methods
+                .contains(&dev_purchase.delivery)
             {
-                bail!("Invalid dev lock period: {:?}", &dev_purchase.lock)
+                bail!("Invalid dev delivery method: {:?}", &dev_purchase.delivery)
+            }
+            let bounds = config.min_dev_purchase_lamports..=config.max_dev_purchase_lamports;
+            if !bounds.contains(&dev_purchase.value) {
+                bail!(
+                    "Dev purchase must be within {}-{} lamports, got {}",
+                    bounds.start(),
+                    bounds.end(),
+                    dev_purchase.value
+                );
             }
         }
+        Ok(())
+    }
+
+    async fn store_project(
+        &self,
+        request: CreateProjectRequest,
+        image: ProjectImage<impl AsyncRead>,
+        recent_blockhash: Hash,
+    ) -> anyhow::Result<CreateProjectResponse> {
         let dev_lock_needed = request
             .deploy_schema
             .dev_purchase
             .as_ref()
-            .map(|purchase| purchase.lock != DevLockPeriod::Disabled)
+            .map(|purchase| purchase.delivery.is_vested())
             .unwrap_or(false);
         let dev_lock_keypair = if dev_lock_needed {
             Some(Keypair::new().into())
@@ -81,48 +343,91 @@ impl App {
             None
         };
 
-        let deploy_schema = request.deploy_schema.try_to_stored()?;
+        let deploy_schema = request
+            .deploy_schema
+            .try_to_stored(self.instructions_builder.config.graduation_target)?;
 
-        let static_pool_keypair = if deploy_schema.static_pool.is_some() {
+        // later pools are created by migrator, once previous ones are graduated.
+        let static_pool_keypair = if deploy_schema.has_static_pool() {
             Some(Keypair::new())
         } else {
             None
         };
 
         let project = storage::project::StoredProject {
-            id: Uuid::new_v4(),
+            id: self
+                .project_id_strategy
+                .generate(&self.storage.pool)
+                .await?,
             owner: request.owner.into(),
             deploy_schema: deploy_schema.clone(),
             stage: storage::project::Stage::Created,
-            static_pool_pubkey: static_pool_keypair
-                .as_ref()
-                .map(|keypair| keypair.pubkey().into()),
+            static_pool_pubkeys: static_pool_keypair
+                .iter()
+                .map(|keypair| keypair.pubkey().into())
+                .collect(),
             dev_lock_keypair,
             curve_pool_keypair: None,
             created_at: chrono::Utc::now(),
         };
         let full_project_state = FullProjectState::only_project(project);
         let mut builder = self.instructions_builder.for_project(&full_project_state)?;
-        let mut ixs = vec![];
-        ixs.extend(builder.create_project(SampleMetadata {
+        let sample_metadata = SampleMetadata {
             name: &request.meta.name,
             symbol: &request.meta.symbol,
             uri: SAMPLE_MPL_URI,
-        })?);
+        };
+
+        // otherwise creator gets a confusing on-chain error on transaction submission.
+        let need = builder.creator_deposit(&sample_metadata)?;
+        let got = self
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .get_balance(&request.owner)
+            .await?;
+        if got < need {
+            return Err(InsufficientCreatorDeposit { need, got }.into());
+        }
+
+        let mut ixs = vec![];
+        ixs.extend(builder.create_project(sample_metadata)?);
         if let Some(keypair) = static_pool_keypair.as_ref() {
-            ixs.extend(builder.init_static_pool(keypair)?);
+            ixs.extend(builder.init_static_pool(keypair, &deploy_schema.static_pools[0])?);
         }
 
         let project = full_project_state.project;
-        let mut tx = self.storage.serializable_tx().await?;
+        let limits = self.projects_limits(&request.owner).await;
+        // read committed, so counts, taken after the lock, see projects of its previous holder,
+        // while serializable snapshot would be taken before the lock is acquired.
+        let mut tx = self.storage.tx().await?;
+
+        // concurrent creations could pass the early check together, so it's repeated under lock
+        if let Some((max_per_hour, max_active)) = limits {
+            StoredProject::lock_owner(&mut tx, &project.owner).await?;
+            let counts = StoredProject::count_by_owner(&mut *tx, &project.owner).await?;
+            check_projects_limits(counts, max_per_hour, max_active).inspect_err(|err| {
+                warn!(
+                    "projects creation by {} is rate limited: {err}",
+                    request.owner
+                )
+            })?;
+        }
 
         sqlx::query!(
-            "INSERT INTO project VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            "
+                INSERT INTO project (
+                    id, owner, deploy_schema, stage, static_pool_pubkeys,
+                    curve_pool_keypair, dev_lock_keypair, created_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ",
             project.id,
             project.owner as _,
             project.deploy_schema as _,
             project.stage as _,
-            project.static_pool_pubkey as _,
+            project.static_pool_pubkeys as _,
             project.curve_pool_keypair as _,
             project.dev_lock_keypair as _,
             project.created_at
@@ -147,19 +452,55 @@ impl App {
         .execute(&mut *tx)
         .await?;
 
-        let mut copy_in = tx
-            .copy_in_raw(
-                "COPY token_image (project_id, image_content) FROM STDIN WITH (FORMAT text)",
-            )
-            .await?;
-        copy_in.send(project.id.to_string().as_bytes()).await?;
-        copy_in.send(b"\t".as_slice()).await?;
-        copy_in.read_from(pin!(streams.image_content)).await?;
-        copy_in.finish().await?;
+        match image {
+            ProjectImage::Content(streams) => {
+                let mut copy_in = tx
+                    .copy_in_raw(
+                        "COPY token_image (project_id, image_content) FROM STDIN WITH (FORMAT text)",
+                    )
+                    .await?;
+                copy_in.send(project.id.to_string().as_bytes()).await?;
+                copy_in.send(b"\t".as_slice()).await?;
+                copy_in.read_from(pin!(streams.image_content)).await?;
+                copy_in.finish().await?;
+                // allowed formats are checked against the upload, stored one may be WebP
+                let head: Vec<u8> = sqlx::query_scalar(
+                    "SELECT substring(image_content FROM 1 FOR $2) FROM token_image WHERE project_id = $1",
+                )
+                .bind(project.id)
+                .bind(FORMAT_PROBE_LEN as i32)
+                .fetch_one(&mut *tx)
+                .await?;
+                let image_format = ImageFormat::detect(&head).ok_or(UnsupportedImageFormat)?;
+                sqlx::query(
+                    "
+                        UPDATE token_image
+                        SET image_hash = encode(sha256(image_content), 'hex'), image_format = $2
+                        WHERE project_id = $1
+                    ",
+                )
+                .bind(project.id)
+                .bind(image_format.as_str())
+                .execute(&mut *tx)
+                .await?;
+            }
+            ProjectImage::Hosted(url) => {
+                // there is nothing to upload, so migrator takes it as already deployed
+                sqlx::query(
+                    "
+                        INSERT INTO token_image (project_id, image_content, deployed_url)
+                        VALUES ($1, '', $2)
+                    ",
+                )
+                .bind(project.id)
+                .bind(url)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
 
         tx.commit().await?;
 
-        let recent_blockhash = self.solana_meta.clone().get()?.recent_blockhash;
         let mut transaction = Transaction::new_with_payer(&ixs, Some(&request.owner));
         let authority = self.keys.authority_keypair().to_keypair();
         let mut signers = vec![&authority];
@@ -173,11 +514,28 @@ impl App {
         })
     }
 
-    pub async fn buy(&self, request: BuyRequest) -> anyhow::Result<BuyResponse> {
+    pub async fn buy(&self, request: BuyRequest) -> Result<BuyResponse, ApiError> {
         let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
 
         let mut builder = self.instructions_builder.for_project(&project)?;
-        let ixs = builder.buy(request.user, request.sols, request.min_token_output)?;
+        if let Some(preference) = request.preferred_route {
+            // every stage exposes routes, which match the buy dispatch by stage.
+            let route = Self::resolve_buy_route(&builder, preference, request.sols).await?;
+            debug!("buying project {} via {route:?}", request.project_id);
+        }
+        let ixs = match request.exact_tokens {
+            Some(tokens) => {
+                builder
+                    .buy_exact_tokens(request.user, tokens, request.sols)
+                    .await
+            }
+            None => {
+                builder
+                    .buy(request.user, request.sols, request.min_token_output)
+                    .await
+            }
+        }
+        .map_err(Self::buy_error)?;
         let mut tx = Transaction::new_with_payer(&ixs, Some(&request.user));
         let recent_blockhash = self.solana_meta.clone().get()?.recent_blockhash;
         tx.partial_sign(&[&self.keys.authority_keypair()], recent_blockhash);
@@ -185,14 +543,165 @@ impl App {
         Ok(BuyResponse { transaction: tx })
     }
 
+    pub async fn buy_exact_tokens(
+        &self,
+        request: BuyExactTokensRequest,
+    ) -> Result<BuyResponse, ApiError> {
+        self.buy(BuyRequest {
+            user: request.user,
+            project_id: request.project_id,
+            sols: request.max_sol_input,
+            min_token_output: None,
+            preferred_route: None,
+            exact_tokens: Some(request.tokens),
+        })
+        .await
+    }
+
+    fn buy_error(err: anyhow::Error) -> ApiError {
+        if err.is::<SlippageExceeded>() || err.is::<ExactTokensUnsupported>() {
+            ApiError::InvalidRequest(err)
+        } else {
+            ApiError::Internal(err)
+        }
+    }
+
+    pub async fn get_best_route(
+        &self,
+        request: BestBuyRouteRequest,
+    ) -> Result<BestBuyRouteResponse, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+        let builder = self.instructions_builder.for_project(&project)?;
+        let (route, quote) = Self::best_buy_route(&builder, &builder.buy_routes(), request.sols)
+            .await?
+            .ok_or_else(|| {
+                ApiError::InvalidRequest(anyhow::anyhow!(
+                    "no buy route is available for project at stage {:?}",
+                    project.project.stage
+                ))
+            })?;
+        Ok(BestBuyRouteResponse {
+            route,
+            expected_tokens: quote.expected_tokens,
+            price_impact_bps: quote.price_impact_bps,
+        })
+    }
+
+    /// Same dispatch as [`Self::buy`] and [`Self::sell`], but only the output is computed.
+    pub async fn quote(&self, request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+        let builder = self.instructions_builder.for_project(&project)?;
+        let (expected_output, price_impact_pct) = match request.side {
+            TradeSide::Buy => (
+                builder.quote_buy(request.amount).await?,
+                builder.calculate_price_impact(request.amount).await?,
+            ),
+            TradeSide::Sell => (builder.quote_sell(request.amount).await?, 0.0),
+        };
+        Ok(QuoteResponse {
+            side: request.side,
+            amount: request.amount,
+            expected_output,
+            price_impact_pct,
+            warn_high_impact: price_impact_pct
+                > self.instructions_builder.config.high_price_impact_pct,
+        })
+    }
+
+    async fn best_buy_route(
+        builder: &ProjectsOperations<'_>,
+        routes: &[BuyRoute],
+        sols: u64,
+    ) -> anyhow::Result<Option<(BuyRoute, BuyQuote)>> {
+        let mut quotes = Vec::with_capacity(routes.len());
+        for route in routes {
+            quotes.push((*route, builder.quote_buy_via(*route, sols).await?));
+        }
+        Ok(quotes
+            .into_iter()
+            .max_by_key(|(_, quote)| quote.expected_tokens))
+    }
+
+    pub async fn get_curve_state(
+        &self,
+        request: GetCurveStateRequest,
+    ) -> Result<CurveState, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+        let builder = self.instructions_builder.for_project(&project)?;
+        let view = builder.fetch_curve_state().await?.ok_or_else(|| {
+            ApiError::InvalidRequest(anyhow::anyhow!(
+                "project {} has no moonzip curve pool",
+                request.project_id
+            ))
+        })?;
+        Ok(view.into())
+    }
+
+    /// Quotes are requested only if there is a real choice between routes.
+    async fn resolve_buy_route(
+        builder: &ProjectsOperations<'_>,
+        preference: RoutePreference,
+        sols: u64,
+    ) -> Result<BuyRoute, ApiError> {
+        let routes = builder.buy_routes();
+        let route = match preference {
+            RoutePreference::Auto if routes.len() > 1 => {
+                Self::best_buy_route(builder, &routes, sols)
+                    .await?
+                    .map(|(route, _)| route)
+            }
+            RoutePreference::Auto => routes.first().copied(),
+            RoutePreference::Curve => Some(BuyRoute::Curve),
+            RoutePreference::Raydium => Some(BuyRoute::Raydium),
+        };
+        match route {
+            Some(route) if routes.contains(&route) => Ok(route),
+            _ => Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "preferred route {preference:?} is not available, available: {routes:?}"
+            ))),
+        }
+    }
+
     pub async fn sell(&self, request: SellRequest) -> anyhow::Result<SellResponse> {
         let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
 
         let mut builder = self.instructions_builder.for_project(&project)?;
-        let ixs = builder.sell(request.user, request.tokens, request.min_sol_output)?;
-        let mut tx = Transaction::new_with_payer(&ixs, Some(&request.user));
+        let ixs = builder
+            .sell(request.user, request.tokens, request.min_sol_output)
+            .await?;
+        self.sell_response(&ixs, &request.user)
+    }
+
+    pub async fn sell_exact_sol(
+        &self,
+        request: SellExactSolRequest,
+    ) -> Result<SellResponse, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+
+        let mut builder = self.instructions_builder.for_project(&project)?;
+        let ixs = builder
+            .sell_exact_sol(request.user, request.target_sol, request.max_token_input)
+            .await
+            .map_err(|err| {
+                if err.is::<ExactSolUnsupported>() {
+                    ApiError::InvalidRequest(err)
+                } else {
+                    ApiError::Internal(err)
+                }
+            })?;
+        Ok(self.sell_response(&ixs, &request.user)?)
+    }
+
+    fn sell_response(&self, ixs: &[Instruction], user: &Pubkey) -> anyhow::Result<SellResponse> {
+        let mut tx = Transaction::new_with_payer(ixs, Some(user));
         let recent_blockhash = self.solana_meta.clone().get()?.recent_blockhash;
-        tx.partial_sign(&[&self.keys.authority_keypair()], recent_blockhash);
+        // refunds of failed static pools don't need the authority
+        let authority = self.keys.authority_keypair();
+        if tx.message.signer_keys().contains(&&authority.pubkey()) {
+            tx.partial_sign(&[&authority], recent_blockhash);
+        } else {
+            tx.message.recent_blockhash = recent_blockhash;
+        }
         Ok(SellResponse { transaction: tx })
     }
 
@@ -209,10 +718,607 @@ impl App {
         Ok(DevLockClaimResponse { transaction: tx })
     }
 
+    pub async fn transfer_ownership(
+        &self,
+        user: Pubkey,
+        request: TransferOwnershipRequest,
+    ) -> Result<TransferOwnershipResponse, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+        if project.project.owner.to_pubkey() != user {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "only project owner could transfer ownership"
+            )));
+        }
+        if request.new_owner == user {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "project is already owned by {user}"
+            )));
+        }
+
+        let builder = self.instructions_builder.for_project(&project)?;
+        let ixs = builder.propose_transfer_ownership(request.new_owner)?;
+        let tx = Transaction::new_with_payer(&ixs, Some(&user));
+
+        Ok(TransferOwnershipResponse { transaction: tx })
+    }
+
+    /// Project could be cancelled only until the migrator deploys anything for it.
+    pub async fn cancel_project(
+        &self,
+        user: Pubkey,
+        request: CancelProjectRequest,
+    ) -> Result<CancelProjectResponse, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+        if project.project.owner.to_pubkey() != user {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "only project owner could cancel it"
+            )));
+        }
+        // confirmed project is being deployed by migrator, so the chain rejects its cancellation
+        if project.project.stage != Stage::Created {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "project {} is already deployed",
+                request.project_id
+            )));
+        }
+
+        let builder = self.instructions_builder.for_project(&project)?;
+        let ixs = builder.cancel_project()?;
+        let mut tx = Transaction::new_with_payer(&ixs, Some(&user));
+        let recent_blockhash = self.solana_meta.clone().get()?.recent_blockhash;
+        tx.partial_sign(&[&self.keys.authority_keypair()], recent_blockhash);
+
+        Ok(CancelProjectResponse { transaction: tx })
+    }
+
+    /// Metadata is re-uploaded by the migrator: with the curve deploy or, for live moonzip curves,
+    /// along with the on-chain update. It's locked in between, once the curve mint is assigned.
+    pub async fn update_project_meta(
+        &self,
+        user: Pubkey,
+        request: UpdateProjectMetaRequest,
+    ) -> Result<(), ApiError> {
+        validate_token_meta_update(&request).map_err(ApiError::InvalidRequest)?;
+
+        let mut tx = self.storage.tx().await?;
+        if !StoredMigrationLock::try_hold(&mut *tx, request.project_id).await? {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "project {} is being migrated, retry later",
+                request.project_id
+            )));
+        }
+        let project = FullProjectState::query(&mut *tx, &request.project_id).await?;
+        if project.project.owner.to_pubkey() != user {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "only project owner could update its metadata"
+            )));
+        }
+        match (
+            project.project.stage,
+            project.project.curve_pool_mint(),
+            &project.project.deploy_schema.curve_pool,
+        ) {
+            (
+                Stage::Created
+                | Stage::Confirmed
+                | Stage::OnStaticPool
+                | Stage::StaticPoolClosed
+                | Stage::OnSecondStaticPool
+                | Stage::SecondStaticPoolClosed,
+                None,
+                _,
+            ) => {}
+            (Stage::OnCurvePool, Some(mint), CurveVariant::Moonzip) => {
+                if !self.is_metadata_updatable(&mint).await? {
+                    return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                        "metadata of {mint} is immutable"
+                    )));
+                }
+            }
+            (stage, _, _) => {
+                return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                    "metadata could not be updated at {stage:?} stage"
+                )));
+            }
+        }
+
+        StoredTokenMeta::update(&mut *tx, &request).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Curves, deployed before metadata updates were introduced, have immutable metadata
+    /// or the one, which the program can't update.
+    async fn is_metadata_updatable(&self, mint: &Pubkey) -> anyhow::Result<bool> {
+        let account = self
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .get_account(&instructions::mpl::metadata_account(*mint))
+            .await?;
+        let metadata = mpl_token_metadata::accounts::Metadata::safe_deserialize(&account.data)?;
+        Ok(metadata.is_mutable
+            && metadata.update_authority == moonzip::metadata::metadata_authority_address())
+    }
+
+    pub async fn accept_transfer(
+        &self,
+        user: Pubkey,
+        request: AcceptTransferRequest,
+    ) -> Result<AcceptTransferResponse, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+
+        let builder = self.instructions_builder.for_project(&project)?;
+        let ixs = builder.accept_transfer_ownership(user)?;
+        let tx = Transaction::new_with_payer(&ixs, Some(&user));
+
+        Ok(AcceptTransferResponse { transaction: tx })
+    }
+
+    /// Nomination could be withdrawn by the owner until the new owner accepts it.
+    pub async fn cancel_transfer(
+        &self,
+        user: Pubkey,
+        request: CancelTransferRequest,
+    ) -> Result<CancelTransferResponse, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+        if project.project.owner.to_pubkey() != user {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "only project owner could cancel ownership transfer"
+            )));
+        }
+
+        let builder = self.instructions_builder.for_project(&project)?;
+        let ixs = builder.cancel_transfer_ownership()?;
+        let tx = Transaction::new_with_payer(&ixs, Some(&user));
+
+        Ok(CancelTransferResponse { transaction: tx })
+    }
+
+    /// Creator share of curve trade fees is collected in the wallet, derived from the project.
+    pub async fn claim_creator_fees(
+        &self,
+        user: Pubkey,
+        request: ClaimCreatorFeesRequest,
+    ) -> Result<ClaimCreatorFeesResponse, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+        if project.project.owner.to_pubkey() != user {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "only project owner could claim creator fees"
+            )));
+        }
+
+        let builder = self.instructions_builder.for_project(&project)?;
+        let ixs = builder.claim_creator_fees()?;
+        let tx = Transaction::new_with_payer(&ixs, Some(&user));
+
+        Ok(ClaimCreatorFeesResponse { transaction: tx })
+    }
+
+    pub async fn deployment_rules(&self) -> anyhow::Result<DeploymentRules> {
+        let project_creation_fee_lamports = self
+            .instructions_builder
+            .mzip_meta
+            .clone()
+            .get()?
+            .global_account
+            .config
+            .project_creation_fee_lamports;
+        let config = self.live_config.read().await;
+        Ok(DeploymentRules {
+            allowed_launch_periods: config
+                .allowed_launch_periods
+                .iter()
+                .map(Duration::as_secs)
+                .collect(),
+            allowed_delivery_methods: config.allowed_delivery_methods.clone(),
+            project_id_strategy: self.project_id_strategy.clone(),
+            project_creation_fee_lamports,
+            min_graduation_liquidity_lamports: config.min_graduation_liquidity_lamports,
+        })
+    }
+
+    pub fn pool_stats(&self) -> PoolStats {
+        self.storage.pool_stats()
+    }
+
+    pub async fn tx_failures(
+        &self,
+        request: GetTxFailuresRequest,
+    ) -> anyhow::Result<Vec<TxFailure>> {
+        let limit = request.limit.min(Self::MAX_TX_FAILURES_LIMIT);
+        let failures =
+            StoredTxFailure::list(&self.storage.pool, request.project_id, i64::from(limit)).await?;
+        Ok(failures.into_iter().map(TxFailure::from).collect())
+    }
+
+    pub async fn upgrade_proposals(&self) -> anyhow::Result<Vec<UpgradeProposal>> {
+        let proposals =
+            StoredUpgradeProposal::list(&self.storage.pool, Self::MAX_UPGRADE_PROPOSALS).await?;
+        Ok(proposals.into_iter().map(UpgradeProposal::from).collect())
+    }
+
+    pub async fn ipfs_stats(&self) -> anyhow::Result<IpfsStats> {
+        Ok(StoredIpfsStats::query(&self.storage.pool).await?.into())
+    }
+
+    /// Latest days first, days without collected fee are omitted.
+    pub async fn revenue(&self, request: GetRevenueRequest) -> anyhow::Result<Vec<DailyRevenue>> {
+        let days = request.days.clamp(1, Self::MAX_REVENUE_DAYS);
+        let since = chrono::Utc::now() - chrono::Duration::days(i64::from(days - 1));
+        StoredDailyRevenue::query(&self.storage.pool, since)
+            .await?
+            .into_iter()
+            .map(DailyRevenue::try_from)
+            .collect()
+    }
+
+    pub async fn migration_locks(&self) -> anyhow::Result<Vec<MigrationLock>> {
+        let locks = StoredMigrationLock::list_held(&self.storage.pool).await?;
+        Ok(locks.into_iter().map(MigrationLock::from).collect())
+    }
+
+    pub async fn set_fee_config(
+        &self,
+        request: SetFeeConfigRequest,
+    ) -> anyhow::Result<SetFeeConfigResponse> {
+        let config = moonzip::fee::FeeConfig {
+            on_buy: request.on_buy.into(),
+            on_sell: request.on_sell.into(),
+            dynamic: moonzip::fee::DynamicFeeConfig {
+                volume_thresholds: request
+                    .volume_tiers
+                    .iter()
+                    .map(|tier| moonzip::fee::FeeTier {
+                        volume: tier.volume_lamports,
+                        bps: moonzip::fee::BasisPoints(tier.bps),
+                    })
+                    .collect(),
+            },
+            recipients: request
+                .fee_recipients
+                .iter()
+                .map(|recipient| moonzip::fee::FeeRecipient {
+                    address: recipient.address,
+                    share_bps: recipient.share_bps,
+                })
+                .collect(),
+        };
+        let ixs = self
+            .instructions_builder
+            .set_fee_config(config, request.fee_receiver)?;
+        let authority = self.keys.authority_keypair().to_keypair();
+        let recent_blockhash = self.solana_meta.clone().get()?.recent_blockhash;
+        let tx = Transaction::new_signed_with_payer(
+            &ixs,
+            Some(&authority.pubkey()),
+            &[&authority],
+            recent_blockhash,
+        );
+        let signature = self
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .send_and_confirm_transaction(&tx)
+            .await?;
+        Ok(SetFeeConfigResponse {
+            signature: signature.to_string(),
+        })
+    }
+
+    pub async fn set_project_creation_fee(
+        &self,
+        request: SetProjectCreationFeeRequest,
+    ) -> anyhow::Result<SetProjectCreationFeeResponse> {
+        let ixs = self
+            .instructions_builder
+            .set_project_creation_fee(request.fee_lamports)?;
+        let authority = self.keys.authority_keypair().to_keypair();
+        let recent_blockhash = self.solana_meta.clone().get()?.recent_blockhash;
+        let tx = Transaction::new_signed_with_payer(
+            &ixs,
+            Some(&authority.pubkey()),
+            &[&authority],
+            recent_blockhash,
+        );
+        let signature = self
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .send_and_confirm_transaction(&tx)
+            .await?;
+        record_admin_action(&self.storage.pool, AdminAction::SetProjectCreationFee, None).await?;
+        Ok(SetProjectCreationFeeResponse {
+            signature: signature.to_string(),
+        })
+    }
+
+    /// Persisted, so the migrator picks it up too, and applied to own clients right away.
+    pub async fn set_jito_tip_strategy(
+        &self,
+        request: SetJitoTipStrategyRequest,
+    ) -> Result<(), ApiError> {
+        request
+            .strategy
+            .validate()
+            .map_err(ApiError::InvalidRequest)?;
+        store_tip_strategy(&self.storage.pool, &request.strategy).await?;
+        self.solana_pool
+            .set_jito_tip_strategy(request.strategy)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_launch_periods(
+        &self,
+        request: SetLaunchPeriodsRequest,
+    ) -> Result<(), ApiError> {
+        if request.periods.is_empty() || request.periods.contains(&0) {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "launch periods must be non-empty and positive"
+            )));
+        }
+        let mut tx = self.storage.tx().await?;
+        store_override(&mut *tx, ConfigKey::LaunchPeriods, &request.periods).await?;
+        record_admin_action(&mut *tx, AdminAction::SetLaunchPeriods, None).await?;
+        tx.commit().await?;
+        self.live_config.refresh(&self.storage).await?;
+        Ok(())
+    }
+
+    pub async fn set_allowed_owners(
+        &self,
+        request: SetAllowedOwnersRequest,
+    ) -> Result<(), ApiError> {
+        let mut tx = self.storage.tx().await?;
+        let owners: Vec<_> = request.owners.iter().map(Pubkey::to_string).collect();
+        store_override(&mut *tx, ConfigKey::AllowedOwners, &owners).await?;
+        record_admin_action(&mut *tx, AdminAction::SetAllowedOwners, None).await?;
+        tx.commit().await?;
+        self.live_config.refresh(&self.storage).await?;
+        Ok(())
+    }
+
+    pub async fn set_delivery_methods(
+        &self,
+        request: SetDeliveryMethodsRequest,
+    ) -> Result<(), ApiError> {
+        if request.methods.is_empty() {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "delivery methods must be non-empty"
+            )));
+        }
+        for method in &request.methods {
+            method.validate().map_err(ApiError::InvalidRequest)?;
+        }
+        let mut tx = self.storage.tx().await?;
+        store_override(&mut *tx, ConfigKey::DeliveryMethods, &request.methods).await?;
+        record_admin_action(&mut *tx, AdminAction::SetDeliveryMethods, None).await?;
+        tx.commit().await?;
+        self.live_config.refresh(&self.storage).await?;
+        Ok(())
+    }
+
+    pub async fn extend_static_pool(
+        &self,
+        request: ExtendStaticPoolRequest,
+    ) -> Result<ExtendStaticPoolResponse, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+        if !matches!(
+            project.project.stage,
+            storage::project::Stage::OnStaticPool | storage::project::Stage::OnSecondStaticPool
+        ) {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "project {} has no active static pool",
+                request.project_id
+            )));
+        }
+        let ixs = self
+            .instructions_builder
+            .for_project(&project)?
+            .extend_static_pool_deadline(request.new_finish_ts)?;
+        let authority = self.keys.authority_keypair().to_keypair();
+        let recent_blockhash = self.solana_meta.clone().get()?.recent_blockhash;
+        let tx = Transaction::new_signed_with_payer(
+            &ixs,
+            Some(&authority.pubkey()),
+            &[&authority],
+            recent_blockhash,
+        );
+        let signature = self
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .send_and_confirm_transaction(&tx)
+            .await
+            .map_err(anyhow::Error::from)?;
+        record_admin_action(
+            &self.storage.pool,
+            AdminAction::ExtendStaticPool,
+            Some(request.project_id),
+        )
+        .await?;
+        Ok(ExtendStaticPoolResponse {
+            signature: signature.to_string(),
+        })
+    }
+
+    pub async fn force_unlock_project(
+        &self,
+        request: ForceUnlockRequest,
+    ) -> Result<ForceUnlockResponse, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+        let locked = self
+            .instructions_builder
+            .fetch_project_account(&request.project_id)
+            .await?
+            .is_some_and(|project| project.latch.is_locked());
+        if !locked {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "project {} latch is not locked",
+                request.project_id
+            )));
+        }
+        let ixs = self
+            .instructions_builder
+            .for_project(&project)?
+            .force_unlock_stale_latch(Duration::from_secs(request.max_age_secs))?;
+        let authority = self.keys.authority_keypair().to_keypair();
+        let recent_blockhash = self.solana_meta.clone().get()?.recent_blockhash;
+        let tx = Transaction::new_signed_with_payer(
+            &ixs,
+            Some(&authority.pubkey()),
+            &[&authority],
+            recent_blockhash,
+        );
+        let signature = self
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .send_and_confirm_transaction(&tx)
+            .await
+            .map_err(anyhow::Error::from)?;
+        record_admin_action(
+            &self.storage.pool,
+            AdminAction::ForceUnlockLatch,
+            Some(request.project_id),
+        )
+        .await?;
+        Ok(ForceUnlockResponse {
+            signature: signature.to_string(),
+        })
+    }
+
+    pub async fn update_pool_config(
+        &self,
+        request: UpdatePoolConfigRequest,
+    ) -> Result<UpdatePoolConfigResponse, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+        if project.project.stage != Stage::OnCurvePool {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "project {} has no active curve pool",
+                request.project_id
+            )));
+        }
+        let config = request.config();
+        if !config.is_creator_fee_share_valid() {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "creator fee share must not exceed 10000 bps"
+            )));
+        }
+        let ixs = self
+            .instructions_builder
+            .for_project(&project)?
+            .update_curve_pool_config(config)
+            .map_err(ApiError::InvalidRequest)?;
+        let authority = self.keys.authority_keypair().to_keypair();
+        let recent_blockhash = self.solana_meta.clone().get()?.recent_blockhash;
+        let tx = Transaction::new_signed_with_payer(
+            &ixs,
+            Some(&authority.pubkey()),
+            &[&authority],
+            recent_blockhash,
+        );
+        let signature = self
+            .solana_pool
+            .rpc_client()
+            .use_single()
+            .await
+            .send_and_confirm_transaction(&tx)
+            .await
+            .map_err(anyhow::Error::from)?;
+        record_admin_action(
+            &self.storage.pool,
+            AdminAction::UpdatePoolConfig,
+            Some(request.project_id),
+        )
+        .await?;
+        Ok(UpdatePoolConfigResponse {
+            signature: signature.to_string(),
+        })
+    }
+
+    /// Brings stored stage of the project in line with the chain, once events are missed,
+    /// and releases the migration lock, so migrator handles the project as usual.
+    pub async fn recover_project(
+        &self,
+        request: RecoverProjectRequest,
+    ) -> Result<RecoverProjectResponse, ApiError> {
+        let project = FullProjectState::query(&self.storage.pool, &request.project_id).await?;
+        let onchain = self
+            .instructions_builder
+            .fetch_project_account(&request.project_id)
+            .await?
+            .ok_or_else(|| {
+                ApiError::InvalidRequest(anyhow::anyhow!(
+                    "project {} is not deployed on-chain",
+                    request.project_id
+                ))
+            })?;
+        let stage = Stage::from_chain(onchain.stage);
+        if stage != request.target_stage {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "on-chain project {} is at {stage:?}, not {:?}",
+                request.project_id,
+                request.target_stage
+            )));
+        }
+        // lease of the stuck migration is revoked, so it never commits over the recovered stage
+        let lock_released =
+            StoredMigrationLock::release(&self.storage.pool, request.project_id).await?;
+        let mut tx = self.storage.tx().await?;
+        StoredProject::set_stage(&mut *tx, &request.project_id, stage).await?;
+        record_admin_action(
+            &mut *tx,
+            AdminAction::RecoverProject,
+            Some(request.project_id),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(RecoverProjectResponse {
+            previous_stage: project.project.stage,
+            stage,
+            lock_released,
+        })
+    }
+
+    pub async fn archive_project(&self, project_id: ProjectId) -> Result<(), ApiError> {
+        let mut tx = self.storage.tx().await?;
+        if !StoredProject::archive(&mut *tx, &project_id).await? {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "project {project_id} is missing, already archived or not in terminal stage"
+            )));
+        }
+        record_admin_action(&mut *tx, AdminAction::ArchiveProject, Some(project_id)).await?;
+        tx.commit().await?;
+        PROJECT_ARCHIVED.inc();
+        Ok(())
+    }
+
+    pub async fn restore_project(&self, project_id: ProjectId) -> Result<(), ApiError> {
+        let mut tx = self.storage.tx().await?;
+        if !StoredProject::restore(&mut *tx, &project_id).await? {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "project {project_id} is missing or not archived"
+            )));
+        }
+        record_admin_action(&mut *tx, AdminAction::RestoreProject, Some(project_id)).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
     pub async fn get_project(
         &self,
         request: GetProjectRequest,
     ) -> anyhow::Result<GetProjectResponse> {
+        if StoredProject::is_archived(&self.storage.pool, &request.project_id).await? {
+            return Ok(GetProjectResponse { project: None });
+        }
         let stored_project = query_as!(
             StoredProjectInfo,
             r#"SELECT
@@ -221,17 +1327,24 @@ impl App {
                 token_meta.name,
                 token_meta.description,
                 project.stage AS "stage: _",
-                project.static_pool_pubkey AS "static_pool_pubkey?: _",
+                project.static_pool_pubkeys AS "static_pool_pubkeys: _",
                 project.curve_pool_keypair AS "curve_pool_keypair?: _",
                 project.dev_lock_keypair AS "dev_lock_keypair?: _",
-                project.created_at AS "created_at: _"
-            FROM project, token_meta WHERE project.id = $1 AND token_meta.project_id = $1"#,
+                project.created_at AS "created_at: _",
+                project.holder_count_snapshot,
+                COALESCE(stats.volume_lamports, 0)::balance AS "volume_24h_lamports!: _",
+                COALESCE(stats.trade_count, 0) AS "trade_count_24h!",
+                project.static_pool_extended_at AS "static_pool_extended_at: _"
+            FROM project
+            JOIN token_meta ON token_meta.project_id = project.id
+            LEFT JOIN project_stats_24h AS stats ON stats.project_id = project.id
+            WHERE project.id = $1"#,
             request.project_id as _,
         )
         .fetch_one(&self.storage.pool)
         .await?;
 
-        let project = match PublicProject::try_from(stored_project) {
+        let mut project = match PublicProject::try_from(stored_project) {
             Ok(project) => project,
             Err(err) => {
                 debug!(
@@ -241,12 +1354,304 @@ impl App {
                 return Ok(GetProjectResponse { project: None });
             }
         };
+        if let Some(mint) = project.curve_pool_mint {
+            project.mint_authority_revoked = self.is_mint_authority_revoked(&mint).await?;
+        }
+        project.owner_sns = self.sns_name_or_none(&project.owner).await;
+        project.locked_at = self
+            .instructions_builder
+            .fetch_project_account(&project.id)
+            .await?
+            .and_then(|project| project.latch.locked_at);
+        if project.stage == PublicProjectStage::Graduated {
+            project.raydium_price = StoredRaydiumPoolState::find(&self.storage.pool, &project.id)
+                .await?
+                .and_then(|state| state.last_price_lamports_per_token);
+        }
+        project.stats = StoredProjectStats::find(&self.storage.pool, project.id)
+            .await?
+            .map(LifetimeStats::try_from)
+            .transpose()?;
+        if request.with_chain_stats {
+            project.chain_stats = self
+                .instructions_builder
+                .fetch_project_stats(&project.id)
+                .await?
+                .map(ChainTradeStats::from);
+        }
 
         Ok(GetProjectResponse {
             project: Some(project),
         })
     }
 
+    /// Mint is assigned before the curve is deployed, so it may be missing on-chain yet.
+    async fn is_mint_authority_revoked(&self, mint: &Pubkey) -> anyhow::Result<bool> {
+        let client = self.solana_pool.rpc_client().use_single().await;
+        let Some(account) = client
+            .get_account_with_commitment(mint, client.commitment())
+            .await?
+            .value
+        else {
+            return Ok(false);
+        };
+        let mint = spl_token::state::Mint::unpack(&account.data)?;
+        Ok(mint.mint_authority.is_none())
+    }
+
+    pub async fn list_projects(
+        &self,
+        request: ListProjectsRequest,
+    ) -> anyhow::Result<ListProjectsResponse> {
+        let page_size = request.page_size.clamp(1, Self::MAX_LIST_PAGE_SIZE);
+        let cursor = request
+            .cursor
+            .as_deref()
+            .map(ListCursor::decode)
+            .transpose()?;
+        let filter = ProjectListFilter {
+            stage: request.stage.map(|stage| stage.to_stored()),
+            curve_variant: request.curve_variant,
+            owner: request.owner.map(StoredPubkey::from),
+        };
+        let stored = list_projects(
+            &self.storage.pool,
+            &filter,
+            request.sort,
+            cursor,
+            i64::from(page_size),
+            i64::from(request.page) * i64::from(page_size),
+        )
+        .await?;
+
+        let next_cursor = (stored.len() == page_size as usize)
+            .then(|| stored.last())
+            .flatten()
+            .map(|last| {
+                ListCursor {
+                    created_at: last.created_at,
+                    id: last.id,
+                }
+                .encode()
+            });
+        let mut projects: Vec<_> = stored
+            .into_iter()
+            .filter_map(|project| PublicProject::try_from(project).ok())
+            .collect();
+        let graduated: Vec<_> = projects
+            .iter()
+            .filter(|project| project.stage == PublicProjectStage::Graduated)
+            .map(|project| project.id)
+            .collect();
+        if !graduated.is_empty() {
+            let prices: HashMap<_, _> =
+                StoredRaydiumPoolState::prices(&self.storage.pool, &graduated)
+                    .await?
+                    .into_iter()
+                    .collect();
+            for project in &mut projects {
+                project.raydium_price = prices.get(&project.id).copied();
+            }
+        }
+        Ok(ListProjectsResponse {
+            projects,
+            next_cursor,
+        })
+    }
+
+    pub async fn get_leaderboard(
+        &self,
+        request: LeaderboardRequest,
+    ) -> anyhow::Result<LeaderboardResponse> {
+        let page_size = request.page_size.clamp(1, Self::MAX_LIST_PAGE_SIZE);
+        let offset = u64::from(request.page) * u64::from(page_size);
+        let stored = StoredLeaderboardEntry::query(
+            &self.storage.pool,
+            request.sort_by,
+            i64::from(page_size),
+            offset as i64,
+        )
+        .await?;
+        let entries = stored
+            .into_iter()
+            .zip(offset + 1..)
+            .filter_map(|(entry, rank)| LeaderboardEntry::from_stored(rank, entry).ok())
+            .collect();
+        Ok(LeaderboardResponse { entries })
+    }
+
+    pub async fn search_projects(
+        &self,
+        request: SearchProjectsRequest,
+    ) -> anyhow::Result<SearchProjectsResponse> {
+        SEARCH_QUERIES
+            .with_label_values(&[request.mode.as_str()])
+            .inc();
+        let key = SearchKey {
+            query: request.query.trim().to_owned(),
+            mode: request.mode,
+            sort: request.sort,
+            limit: request.limit.min(Self::MAX_SEARCH_LIMIT),
+            offset: request.offset,
+        };
+        if let Some(cache) = &self.search_cache {
+            if let Some(cached) = cache.get(&key).await {
+                SEARCH_CACHE_HITS.inc();
+                return Ok(cached);
+            }
+        }
+
+        let hits = search_projects(
+            &self.storage.pool,
+            &key.query,
+            key.mode,
+            key.sort,
+            i64::from(key.limit),
+            i64::from(key.offset),
+        )
+        .await?;
+        let mut results: Vec<_> = hits
+            .into_iter()
+            .filter_map(|hit| {
+                let project = PublicProject::try_from(hit.project).ok()?;
+                Some(ProjectSearchHit {
+                    project,
+                    highlight: hit.highlight.as_deref().map(escape_highlight),
+                })
+            })
+            .collect();
+        let graduated: Vec<_> = results
+            .iter()
+            .filter(|hit| hit.project.stage == PublicProjectStage::Graduated)
+            .map(|hit| hit.project.id)
+            .collect();
+        if !graduated.is_empty() {
+            let prices: HashMap<_, _> =
+                StoredRaydiumPoolState::prices(&self.storage.pool, &graduated)
+                    .await?
+                    .into_iter()
+                    .collect();
+            for hit in &mut results {
+                hit.project.raydium_price = prices.get(&hit.project.id).copied();
+            }
+        }
+        let response = SearchProjectsResponse { results };
+        if let Some(cache) = &self.search_cache {
+            cache.insert(&key, &response).await;
+        }
+        Ok(response)
+    }
+
+    pub async fn get_project_stats(
+        &self,
+        request: GetProjectStatsRequest,
+    ) -> anyhow::Result<ProjectStats> {
+        let mut stats: ProjectStats =
+            StoredTradeStats::query(&self.storage.pool, request.project_id, request.period)
+                .await?
+                .try_into()?;
+        // trades on raydium are not tracked, so its pool is the only source of the current price
+        if let Some(state) =
+            StoredRaydiumPoolState::find(&self.storage.pool, &request.project_id).await?
+        {
+            stats.price_close = state.last_price_lamports_per_token.or(stats.price_close);
+        }
+        Ok(stats)
+    }
+
+    /// Latest candles in the range, so charts could be paged back by `to`.
+    pub async fn get_candles(&self, request: GetCandlesRequest) -> Result<Vec<Candle>, ApiError> {
+        let timestamp = |ts: i64| {
+            chrono::DateTime::from_timestamp(ts, 0)
+                .ok_or_else(|| ApiError::InvalidRequest(anyhow::anyhow!("invalid timestamp: {ts}")))
+        };
+        let from = request.from.map(timestamp).transpose()?;
+        let to = request.to.map(timestamp).transpose()?;
+        if let (Some(from), Some(to)) = (from, to) {
+            if from >= to {
+                return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                    "range start must precede its end"
+                )));
+            }
+        }
+        StoredCandle::query(
+            &self.storage.pool,
+            request.project_id,
+            request.interval,
+            from,
+            to,
+            Self::MAX_CANDLES,
+        )
+        .await?
+        .into_iter()
+        .map(Candle::try_from)
+        .collect::<anyhow::Result<_>>()
+        .map_err(ApiError::from)
+    }
+
+    pub async fn get_holders(
+        &self,
+        request: GetHoldersRequest,
+    ) -> anyhow::Result<GetHoldersResponse> {
+        let limit = request.limit.min(Self::MAX_HOLDERS_LIMIT);
+        let holders = StoredTokenHolder::top(
+            &self.storage.pool,
+            request.project_id,
+            limit.into(),
+            i64::from(request.page) * i64::from(limit),
+        )
+        .await?
+        .into_iter()
+        .map(TokenHolder::try_from)
+        .collect::<anyhow::Result<_>>()?;
+        let total = StoredTokenHolder::count(&self.storage.pool, request.project_id).await?;
+        Ok(GetHoldersResponse { holders, total })
+    }
+
+    pub async fn load_static_pool_progress(
+        &self,
+        project_id: ProjectId,
+    ) -> anyhow::Result<Option<StaticPoolProgress>> {
+        let Some(stored) = StoredStaticPoolProgress::load(&self.storage.pool, project_id).await?
+        else {
+            return Ok(None);
+        };
+        // pools without their own limit were created with the configured one
+        let max_lamports = match stored.max_lamports {
+            Some(max_lamports) => max_lamports.try_into()?,
+            None => self.instructions_builder.config.sols_to_graduate,
+        };
+        Ok(Some(StaticPoolProgress::new(
+            stored.collected_lamports.try_into()?,
+            max_lamports,
+            stored.participant_count.try_into()?,
+        )))
+    }
+
+    pub async fn subscribe_static_pool_progress(
+        &self,
+        project_id: ProjectId,
+    ) -> Result<watch::Receiver<StaticPoolProgress>, ApiError> {
+        let progress = self
+            .load_static_pool_progress(project_id)
+            .await?
+            .ok_or_else(|| {
+                ApiError::InvalidRequest(anyhow::anyhow!("project has no static pool state"))
+            })?;
+        Ok(self.static_pool_progress.subscribe(project_id, progress))
+    }
+
+    pub async fn lp_burn_proof(
+        &self,
+        request: GetLpBurnProofRequest,
+    ) -> anyhow::Result<GetLpBurnProofResponse> {
+        let proof = StoredLpBurn::find(&self.storage.pool, &request.project_id)
+            .await?
+            .map(LpBurnProof::try_from)
+            .transpose()?;
+        Ok(GetLpBurnProofResponse { proof })
+    }
+
     pub async fn upsert_user_info(
         &self,
         request: ChangeUserInfoRequest,
@@ -282,6 +1687,7 @@ impl App {
             image_url: Some(image_url),
             nft_address: stored_nft_address,
             last_active: None,
+            sns_name: None,
             created_at: Some(chrono::Utc::now()),
             updated_at: Some(chrono::Utc::now()),
         };
@@ -304,7 +1710,7 @@ impl App {
                         image_url = EXCLUDED.image_url,
                         nft_address = EXCLUDED.nft_address,
                         updated_at = now()
-                    RETURNING wallet_address as "wallet_address: _", username, image_url, nft_address as "nft_address?: _", display_name, last_active, created_at, updated_at
+                    RETURNING wallet_address as "wallet_address: _", username, image_url, nft_address as "nft_address?: _", display_name, last_active, sns_name, created_at, updated_at
                 "#,
                 stored_user_info.wallet_address as _,
                 stored_user_info.username as _,
@@ -336,7 +1742,7 @@ impl App {
     ) -> anyhow::Result<UserInfo, ApiError> {
         let stored_pub_key: StoredPubkey = request.wallet_address.into();
 
-        let user = query_as!(
+        let mut user = query_as!(
             StoredUserInfo,
             r#"select  u.wallet_address as "wallet_address: _",
                        u.username,
@@ -344,6 +1750,7 @@ impl App {
                        u.image_url,
                        u.nft_address AS "nft_address?: _",
                        u.last_active,
+                       u.sns_name,
                        u.created_at,
                        u.updated_at
                 from user_info u
@@ -354,6 +1761,19 @@ impl App {
         .await
         .map_err(|_e| ApiError::NotFoundUser(anyhow::anyhow!("Not found user by address",)))?;
 
+        if self.sns.is_some() {
+            let sns_name = self.sns_name_or_none(&request.wallet_address).await;
+            if sns_name != user.sns_name {
+                StoredUserInfo::set_sns_name(
+                    &self.storage.pool,
+                    &user.wallet_address,
+                    sns_name.as_deref(),
+                )
+                .await?;
+                user.sns_name = sns_name;
+            }
+        }
+
         let user_info = UserInfo::try_from(user).map_err(|e| {
             ApiError::Internal(anyhow::anyhow!("Failed to convert stored user info: {}", e))
         })?;
@@ -361,6 +1781,168 @@ impl App {
         Ok(user_info)
     }
 
+    pub async fn resolve_sns(
+        &self,
+        request: ResolveSnsRequest,
+    ) -> Result<ResolveSnsResponse, ApiError> {
+        let sns = self.sns_resolver()?;
+        Ok(ResolveSnsResponse {
+            sns_name: sns
+                .resolve_name(&self.solana_pool, &request.address)
+                .await?,
+        })
+    }
+
+    /// Name registry is the source of truth, as domains change hands,
+    /// names, stored for users, who fetched their info, are used only once it's unavailable.
+    pub async fn resolve_address(
+        &self,
+        request: ResolveAddressRequest,
+    ) -> Result<ResolveAddressResponse, ApiError> {
+        let sns = self.sns_resolver()?;
+        let Some(sns_name) = sns::canonical_name(&request.sns_name) else {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "sns name is empty"
+            )));
+        };
+        let address = match sns.resolve_address(&self.solana_pool, &sns_name).await {
+            Ok(address) => address,
+            Err(err) => {
+                warn!("failed to read name registry for {sns_name}: {err:#}");
+                StoredUserInfo::find_by_sns_name(&self.storage.pool, &sns_name)
+                    .await?
+                    .map(|address| address.to_pubkey())
+            }
+        };
+        Ok(ResolveAddressResponse { address })
+    }
+
+    fn sns_resolver(&self) -> Result<&SnsResolver, ApiError> {
+        self.sns
+            .as_ref()
+            .ok_or_else(|| ApiError::InvalidRequest(anyhow::anyhow!("sns resolution is disabled")))
+    }
+
+    /// Name is only a decoration, so resolution failures are not propagated.
+    async fn sns_name_or_none(&self, address: &Pubkey) -> Option<String> {
+        let sns = self.sns.as_ref()?;
+        match sns.resolve_name(&self.solana_pool, address).await {
+            Ok(name) => name,
+            Err(err) => {
+                warn!("failed to resolve sns name of {address}: {err:#}");
+                None
+            }
+        }
+    }
+
+    pub async fn update_notification_preferences(
+        &self,
+        user: Pubkey,
+        request: UpdateNotificationsRequest,
+    ) -> anyhow::Result<NotificationPreferences> {
+        let stored = StoredNotificationPreferences::upsert(
+            &self.storage.pool,
+            &StoredNotificationPreferences {
+                wallet: user.to_string(),
+                email: request.email,
+                email_verified: false,
+                verification_token: None,
+                notify_on_buy: request.notify_on_buy,
+                notify_on_sell: request.notify_on_sell,
+                notify_on_graduation: request.notify_on_graduation,
+                notify_on_dev_unlock: request.notify_on_dev_unlock,
+            },
+        )
+        .await?;
+        Ok(stored.into())
+    }
+
+    pub async fn send_email_verification(&self, user: Pubkey) -> Result<(), ApiError> {
+        let Some(queue) = self.notifications.as_ref() else {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "email notifications are disabled"
+            )));
+        };
+        let wallet = user.to_string();
+        let preferences = StoredNotificationPreferences::find(&self.storage.pool, &wallet)
+            .await?
+            .filter(|preferences| preferences.email.is_some())
+            .ok_or_else(|| {
+                ApiError::InvalidRequest(anyhow::anyhow!("no email set for notifications"))
+            })?;
+        if preferences.email_verified {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "email is already verified"
+            )));
+        }
+
+        let token = Uuid::new_v4();
+        StoredNotificationPreferences::set_verification_token(&self.storage.pool, &wallet, token)
+            .await?;
+        queue.push(Notification {
+            email: preferences.email.unwrap_or_default(),
+            wallet,
+            event: NotificationEvent::EmailVerification,
+            subject: "Verify your email".to_owned(),
+            body: format!(
+                "Follow the link to receive Moonzip notifications: {}",
+                queue.verify_email_link(&token)
+            ),
+        });
+        Ok(())
+    }
+
+    pub async fn register_webhook(
+        &self,
+        user: Pubkey,
+        request: RegisterWebhookRequest,
+    ) -> Result<RegisterWebhookResponse, ApiError> {
+        const MAX_WEBHOOKS_PER_WALLET: i64 = 10;
+
+        let target_url =
+            validate_webhook_url(&request.target_url).map_err(ApiError::InvalidRequest)?;
+        if StoredWebhook::count_by_owner(&self.storage.pool, &user).await?
+            >= MAX_WEBHOOKS_PER_WALLET
+        {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "at most {MAX_WEBHOOKS_PER_WALLET} webhooks could be registered per wallet"
+            )));
+        }
+        let secret_hex = webhooks::generate_secret();
+        let webhook = StoredWebhook::insert(
+            &self.storage.pool,
+            &user,
+            target_url.as_str(),
+            &secret_hex,
+            &request.event_filter,
+        )
+        .await?;
+        Ok(RegisterWebhookResponse {
+            id: webhook.id,
+            secret_hex,
+        })
+    }
+
+    pub async fn delete_webhook(&self, user: Pubkey, id: Uuid) -> Result<(), ApiError> {
+        if !StoredWebhook::delete(&self.storage.pool, id, &user).await? {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "webhook {id} is missing or owned by another wallet"
+            )));
+        }
+        Ok(())
+    }
+
+    pub async fn confirm_email(&self, request: ConfirmEmailRequest) -> Result<(), ApiError> {
+        let confirmed =
+            StoredNotificationPreferences::confirm_email(&self.storage.pool, request.token).await?;
+        if confirmed.is_none() {
+            return Err(ApiError::InvalidRequest(anyhow::anyhow!(
+                "verification token is invalid or already used"
+            )));
+        }
+        Ok(())
+    }
+
     pub async fn get_owned_nfts_by_address(
         &self,
         request: GetOwnedNFTsRequest,
@@ -0,0 +1,184 @@
+use super::storage::{notifications::record_delivery, StorageClient};
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport as _, Message, Tokio1Executor,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::{
+    sync::mpsc::{channel, error::TrySendError, Receiver, Sender},
+    task::JoinHandle,
+};
+use tracing::{debug, error, warn};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotificationsConfig {
+    pub smtp: SmtpConfig,
+    /// Link sent for email verification, token is appended as query parameter.
+    pub verify_email_url: String,
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+}
+
+fn default_queue_capacity() -> usize {
+    1000
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SmtpConfig {
+    pub relay: String,
+    pub username: String,
+    pub password: String,
+    pub from: String,
+}
+
+pub struct EmailSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl EmailSender {
+    pub fn from_cfg(config: &SmtpConfig) -> anyhow::Result<Self> {
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::relay(&config.relay)?
+            .credentials(Credentials::new(
+                config.username.clone(),
+                config.password.clone(),
+            ))
+            .build();
+        Ok(Self {
+            transport,
+            from: config.from.parse()?,
+        })
+    }
+
+    pub async fn send(&self, to: &str, subject: &str, body: String) -> anyhow::Result<()> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to.parse()?)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body)?;
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationEvent {
+    EmailVerification,
+    Buy,
+    Sell,
+    Graduation,
+    DevUnlock,
+}
+
+impl NotificationEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationEvent::EmailVerification => "email_verification",
+            NotificationEvent::Buy => "buy",
+            NotificationEvent::Sell => "sell",
+            NotificationEvent::Graduation => "graduation",
+            NotificationEvent::DevUnlock => "dev_unlock",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub wallet: String,
+    pub email: String,
+    pub event: NotificationEvent,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Entry point for notifications, emails are sent asynchronously by [`NotificationWorker`].
+#[derive(Clone)]
+pub struct NotificationQueue {
+    tx: Sender<Notification>,
+    config: Arc<NotificationsConfig>,
+}
+
+impl NotificationQueue {
+    pub fn push(&self, notification: Notification) {
+        match self.tx.try_send(notification) {
+            Ok(()) => {}
+            Err(TrySendError::Full(notification)) => {
+                warn!(
+                    "notification queue is full, dropping {} notification for {}",
+                    notification.event.as_str(),
+                    notification.wallet
+                );
+            }
+            Err(TrySendError::Closed(_)) => {
+                error!("notification worker is terminated, notification is lost");
+            }
+        }
+    }
+
+    pub fn verify_email_link(&self, token: &uuid::Uuid) -> String {
+        format!("{}?token={token}", self.config.verify_email_url)
+    }
+}
+
+pub struct NotificationWorker {
+    sender: EmailSender,
+    storage: StorageClient,
+    rx: Receiver<Notification>,
+}
+
+impl NotificationWorker {
+    pub fn serve(
+        config: NotificationsConfig,
+        storage: StorageClient,
+    ) -> anyhow::Result<(NotificationQueue, JoinHandle<()>)> {
+        let (tx, rx) = channel(config.queue_capacity);
+        let mut worker = Self {
+            sender: EmailSender::from_cfg(&config.smtp)?,
+            storage,
+            rx,
+        };
+        let handle = tokio::spawn(async move {
+            while let Some(notification) = worker.rx.recv().await {
+                worker.deliver(notification).await;
+            }
+            debug!("notification queue closed, worker stopped");
+        });
+        let queue = NotificationQueue {
+            tx,
+            config: Arc::new(config),
+        };
+        Ok((queue, handle))
+    }
+
+    async fn deliver(&self, notification: Notification) {
+        let result = self
+            .sender
+            .send(
+                &notification.email,
+                &notification.subject,
+                notification.body.clone(),
+            )
+            .await;
+        let error = result.err().map(|err| {
+            warn!(
+                "failed to deliver {} notification to {}: {err:#}",
+                notification.event.as_str(),
+                notification.wallet
+            );
+            format!("{err:#}")
+        });
+        if let Err(err) = record_delivery(
+            &self.storage.pool,
+            &notification.wallet,
+            notification.event.as_str(),
+            error,
+        )
+        .await
+        {
+            error!("failed to record notification delivery: {err:#}");
+        }
+    }
+}
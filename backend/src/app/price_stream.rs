@@ -0,0 +1,204 @@
+use super::{
+    exposed::PriceStreamEvent,
+    storage::{
+        chain_events::{PriceStreamNotification, PRICE_STREAM_CHANNEL},
+        project::ProjectId,
+    },
+    App,
+};
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket};
+use dashmap::DashMap;
+use sqlx::postgres::PgListener;
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    task::JoinHandle,
+};
+use tracing::{debug, error, warn};
+
+/// Fans out curve trades to websocket clients, watching the project.
+#[derive(Default)]
+pub struct PriceStreamHub {
+    senders: DashMap<ProjectId, broadcast::Sender<PriceStreamEvent>>,
+}
+
+impl PriceStreamHub {
+    /// Subscriber, lagging behind by more events, is evicted.
+    pub const CAPACITY: usize = 64;
+
+    pub fn subscribe(&self, project_id: ProjectId) -> broadcast::Receiver<PriceStreamEvent> {
+        self.senders
+            .entry(project_id)
+            .or_insert_with(|| broadcast::channel(Self::CAPACITY).0)
+            .subscribe()
+    }
+
+    pub fn is_watched(&self, project_id: &ProjectId) -> bool {
+        self.senders.contains_key(project_id)
+    }
+
+    pub fn publish(&self, project_id: ProjectId, event: PriceStreamEvent) {
+        self.forget_idle(&project_id);
+        if let Some(sender) = self.senders.get(&project_id) {
+            // receivers could leave in between, it's fine to drop the event then
+            let _ = sender.send(event);
+        }
+    }
+
+    /// Called once subscriber leaves, as projects without trades are never published to.
+    pub fn forget_idle(&self, project_id: &ProjectId) {
+        self.senders
+            .remove_if(project_id, |_, sender| sender.receiver_count() == 0);
+    }
+}
+
+/// Sends trades of the project to the client until either side leaves.
+/// Client is disconnected once it lags behind by [`PriceStreamHub::CAPACITY`] events
+/// or doesn't accept a single one in time, so slow clients don't hold the hub.
+pub async fn stream_trades(app: Arc<App>, project_id: ProjectId, mut socket: WebSocket) {
+    const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+    let mut receiver = app.price_stream.subscribe(project_id);
+    loop {
+        tokio::select! {
+            event = receiver.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        debug!("evicting price stream subscriber of {project_id}, lagged by {skipped}");
+                        let close = Message::Close(Some(CloseFrame {
+                            code: close_code::POLICY,
+                            reason: "subscriber is too slow".into(),
+                        }));
+                        let _ = tokio::time::timeout(SEND_TIMEOUT, socket.send(close)).await;
+                        break;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                let text = match serde_json::to_string(&event) {
+                    Ok(text) => text,
+                    Err(err) => {
+                        error!("failed to serialize price stream event: {err}");
+                        break;
+                    }
+                };
+                match tokio::time::timeout(SEND_TIMEOUT, socket.send(Message::Text(text.into()))).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(_)) => break,
+                    Err(_) => {
+                        debug!("evicting price stream subscriber of {project_id}, send timed out");
+                        break;
+                    }
+                }
+            }
+            // clients only listen, pings are answered by axum itself
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    drop(receiver);
+    app.price_stream.forget_idle(&project_id);
+}
+
+/// Trades are persisted by chain syncer, which notifies api servers via postgres.
+pub struct PriceStreamListener {
+    pub app: Arc<App>,
+}
+
+impl PriceStreamListener {
+    const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn serve(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = self.listen().await {
+                    error!("price stream listener failed: {err:#}");
+                }
+                tokio::time::sleep(Self::RECONNECT_INTERVAL).await;
+            }
+        })
+    }
+
+    async fn listen(&self) -> anyhow::Result<()> {
+        let mut listener = PgListener::connect_with(&self.app.storage.pool).await?;
+        listener.listen(PRICE_STREAM_CHANNEL).await?;
+
+        loop {
+            let notification = listener.recv().await?;
+            let notification: PriceStreamNotification =
+                match serde_json::from_str(notification.payload()) {
+                    Ok(notification) => notification,
+                    Err(err) => {
+                        warn!(
+                            "invalid price stream notification {:?}: {err}",
+                            notification.payload()
+                        );
+                        continue;
+                    }
+                };
+            if self.app.price_stream.is_watched(&notification.project_id) {
+                self.app
+                    .price_stream
+                    .publish(notification.project_id, notification.event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::exposed::TradeSide;
+
+    fn event(sols: u64) -> PriceStreamEvent {
+        PriceStreamEvent {
+            side: TradeSide::Buy,
+            sols,
+            tokens: sols * 2,
+            price: Some(0.5),
+            timestamp: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fans_out_trades_of_the_project_only() {
+        let hub = PriceStreamHub::default();
+        let project = ProjectId::new_v4();
+        let other = ProjectId::new_v4();
+        let mut first = hub.subscribe(project);
+        let mut second = hub.subscribe(project);
+        let mut unrelated = hub.subscribe(other);
+
+        hub.publish(project, event(1));
+        assert_eq!(first.recv().await.unwrap(), event(1));
+        assert_eq!(second.recv().await.unwrap(), event(1));
+        assert!(unrelated.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reports_lag_of_slow_subscriber() {
+        let hub = PriceStreamHub::default();
+        let project = ProjectId::new_v4();
+        let mut slow = hub.subscribe(project);
+
+        for sols in 0..=PriceStreamHub::CAPACITY as u64 {
+            hub.publish(project, event(sols));
+        }
+        assert!(matches!(slow.recv().await, Err(RecvError::Lagged(1))));
+    }
+
+    #[test]
+    fn test_forgets_projects_without_subscribers() {
+        let hub = PriceStreamHub::default();
+        let project = ProjectId::new_v4();
+        let receiver = hub.subscribe(project);
+        hub.forget_idle(&project);
+        assert!(hub.is_watched(&project));
+
+        drop(receiver);
+        hub.forget_idle(&project);
+        assert!(!hub.is_watched(&project));
+    }
+}
@@ -0,0 +1,132 @@
+use super::{
+    exposed::SearchProjectsResponse,
+    storage::search::{SearchMode, SearchSort},
+};
+use crate::utils::to_hex;
+use redis::{aio::ConnectionManager, AsyncCommands as _};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tracing::warn;
+
+const REDIS_KEY_PREFIX: &str = "moonzip:search:";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchCacheConfig {
+    pub redis_url: String,
+    #[serde(with = "humantime_serde", default = "default_search_cache_ttl")]
+    pub ttl: Duration,
+}
+
+pub fn default_search_cache_ttl() -> Duration {
+    Duration::from_secs(60)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct SearchKey {
+    pub query: String,
+    pub mode: SearchMode,
+    pub sort: SearchSort,
+    pub limit: u32,
+    pub offset: u32,
+}
+
+impl SearchKey {
+    /// Query is user input of any length, so it's hashed along with other parameters.
+    fn redis_key(&self) -> String {
+        let params = serde_json::to_vec(self).expect("invariant: search key is serializable");
+        format!("{REDIS_KEY_PREFIX}{}", to_hex(&Sha256::digest(params)))
+    }
+}
+
+/// Keeps results of search queries in Redis for a short period, shared by all API replicas.
+/// Redis evicts them on its own, once expired or, being full, by its `maxmemory-policy`:
+/// `allkeys-lfu` is expected, so the least requested queries give place to new ones.
+#[derive(Clone)]
+pub struct SearchCache {
+    redis: ConnectionManager,
+    ttl: Duration,
+}
+
+impl SearchCache {
+    pub async fn connect(config: SearchCacheConfig) -> anyhow::Result<Self> {
+        let redis = redis::Client::open(config.redis_url)?
+            .get_connection_manager()
+            .await?;
+        Ok(Self {
+            redis,
+            ttl: config.ttl,
+        })
+    }
+
+    /// Failures are logged only, search is served from the database then.
+    pub async fn get(&self, key: &SearchKey) -> Option<SearchProjectsResponse> {
+        let cached: Option<String> = match self.redis.clone().get(key.redis_key()).await {
+            Ok(cached) => cached,
+            Err(err) => {
+                warn!("failed to read search cache: {err:#}");
+                return None;
+            }
+        };
+        serde_json::from_str(&cached?)
+            .inspect_err(|err| warn!("failed to decode cached search: {err:#}"))
+            .ok()
+    }
+
+    /// See [`Self::get`].
+    pub async fn insert(&self, key: &SearchKey, response: &SearchProjectsResponse) {
+        let response = match serde_json::to_string(response) {
+            Ok(response) => response,
+            Err(err) => {
+                warn!("failed to encode search for cache: {err:#}");
+                return;
+            }
+        };
+        let stored: redis::RedisResult<()> = self
+            .redis
+            .clone()
+            .set_ex(key.redis_key(), response, self.ttl.as_secs().max(1))
+            .await;
+        if let Err(err) = stored {
+            warn!("failed to write search cache: {err:#}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(query: &str) -> SearchKey {
+        SearchKey {
+            query: query.into(),
+            mode: SearchMode::Simple,
+            sort: SearchSort::Relevance,
+            limit: 20,
+            offset: 0,
+        }
+    }
+
+    #[test]
+    fn test_derives_redis_key_from_every_parameter() {
+        assert_eq!(key("moon").redis_key(), key("moon").redis_key());
+        assert_ne!(key("moon").redis_key(), key("sun").redis_key());
+        let next_page = SearchKey {
+            offset: 20,
+            ..key("moon")
+        };
+        assert_ne!(key("moon").redis_key(), next_page.redis_key());
+        let phrase = SearchKey {
+            mode: SearchMode::Phrase,
+            ..key("moon")
+        };
+        assert_ne!(key("moon").redis_key(), phrase.redis_key());
+    }
+
+    #[test]
+    fn test_bounds_redis_key_length() {
+        let long = key(&"moon ".repeat(1000)).redis_key();
+        assert!(long.starts_with(REDIS_KEY_PREFIX));
+        assert_eq!(long.len(), REDIS_KEY_PREFIX.len() + 64);
+    }
+}
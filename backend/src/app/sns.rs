@@ -0,0 +1,158 @@
+use moka::sync::Cache;
+use serde::Deserialize;
+use serde_with::{serde_as, DisplayFromStr};
+use services_common::solana::{helius::NFTItem, pool::SolanaPool};
+use solana_sdk::{hash::hashv, pubkey, pubkey::Pubkey};
+use std::time::Duration;
+
+/// Program of the Solana Name Service registry.
+const NAME_PROGRAM_ID: Pubkey = pubkey!("namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX");
+/// Parent of every `.sol` domain.
+const SOL_TLD: Pubkey = pubkey!("58PwtjSDuFHuUkYjH9BYnnQKHfwo9reZhC2zMJv9JPkx");
+const HASH_PREFIX: &str = "SPL Name Service";
+const SOL_SUFFIX: &str = ".sol";
+/// Name record header is `parent`, `owner` and `class`.
+const OWNER_OFFSET: usize = 32;
+const OWNED_ASSETS_LIMIT: u32 = 1000;
+
+#[serde_as]
+#[derive(Debug, Clone, Deserialize)]
+pub struct SnsConfig {
+    /// Collection of tokenized `.sol` domains.
+    #[serde_as(as = "DisplayFromStr")]
+    pub collection: Pubkey,
+    #[serde(with = "humantime_serde", default = "default_cache_ttl")]
+    pub cache_ttl: Duration,
+    /// Wallets, which names are kept, least recently used ones are evicted first.
+    #[serde(default = "default_cache_capacity")]
+    pub cache_capacity: u64,
+}
+
+fn default_cache_ttl() -> Duration {
+    Duration::from_secs(10 * 60)
+}
+
+fn default_cache_capacity() -> u64 {
+    100_000
+}
+
+/// Resolves wallets to their `.sol` names via Helius DAS.
+/// Absent names are cached as well, so wallets without domains don't hit the API on each request.
+pub struct SnsResolver {
+    config: SnsConfig,
+    names: Cache<Pubkey, Option<String>>,
+}
+
+impl SnsResolver {
+    pub fn new(config: SnsConfig) -> Self {
+        let names = Cache::builder()
+            .max_capacity(config.cache_capacity)
+            .time_to_live(config.cache_ttl)
+            .build();
+        Self { config, names }
+    }
+
+    pub async fn resolve_name(
+        &self,
+        pool: &SolanaPool,
+        address: &Pubkey,
+    ) -> anyhow::Result<Option<String>> {
+        if let Some(name) = self.names.get(address) {
+            return Ok(name);
+        }
+
+        let assets = pool
+            .helius_client()
+            .get_owned_nfts(address.to_string(), Some(1), Some(OWNED_ASSETS_LIMIT))
+            .await?;
+        let name = assets
+            .result
+            .into_iter()
+            .flat_map(|result| result.items)
+            .find(|item| self.is_domain(item))
+            .and_then(|item| item.content.metadata.name)
+            .and_then(|name| canonical_name(&name));
+        self.names.insert(*address, name.clone());
+        Ok(name)
+    }
+
+    /// Owner of the domain, as recorded in the name registry.
+    pub async fn resolve_address(
+        &self,
+        pool: &SolanaPool,
+        name: &str,
+    ) -> anyhow::Result<Option<Pubkey>> {
+        let Some(name) = normalize_name(name) else {
+            return Ok(None);
+        };
+        let client = pool.rpc_client().use_single().await;
+        let commitment = client.commitment();
+        let Some(account) = client
+            .get_account_with_commitment(&name_account(&name), commitment)
+            .await?
+            .value
+        else {
+            return Ok(None);
+        };
+        let owner = account
+            .data
+            .get(OWNER_OFFSET..OWNER_OFFSET + 32)
+            .ok_or_else(|| anyhow::anyhow!("name record is too short"))?;
+        Ok(Some(Pubkey::try_from(owner)?))
+    }
+
+    fn is_domain(&self, item: &NFTItem) -> bool {
+        let collection = self.config.collection.to_string();
+        item.grouping.iter().any(|group| {
+            group.group_key.as_deref() == Some("collection")
+                && group.group_value.as_deref() == Some(collection.as_str())
+        })
+    }
+}
+
+/// Lowercase domain with the `.sol` suffix, as it's exposed and stored.
+pub fn canonical_name(name: &str) -> Option<String> {
+    normalize_name(name).map(|name| format!("{name}{SOL_SUFFIX}"))
+}
+
+/// Domain without the `.sol` suffix, as it's hashed by the registry.
+fn normalize_name(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    let name = name.strip_suffix(SOL_SUFFIX).unwrap_or(&name);
+    (!name.is_empty()).then(|| name.to_owned())
+}
+
+fn name_account(name: &str) -> Pubkey {
+    let hashed = hashv(&[HASH_PREFIX.as_bytes(), name.as_bytes()]);
+    Pubkey::find_program_address(
+        &[
+            hashed.as_ref(),
+            Pubkey::default().as_ref(),
+            SOL_TLD.as_ref(),
+        ],
+        &NAME_PROGRAM_ID,
+    )
+    .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalizes_names() {
+        assert_eq!(normalize_name("Bonfida.sol"), Some("bonfida".to_owned()));
+        assert_eq!(normalize_name(" bonfida "), Some("bonfida".to_owned()));
+        assert_eq!(normalize_name(".sol"), None);
+        assert_eq!(normalize_name(""), None);
+        assert_eq!(canonical_name("Bonfida"), Some("bonfida.sol".to_owned()));
+    }
+
+    #[test]
+    fn test_derives_same_account_regardless_of_suffix() {
+        assert_eq!(
+            name_account(&normalize_name("bonfida.sol").unwrap()),
+            name_account(&normalize_name("Bonfida").unwrap())
+        );
+    }
+}
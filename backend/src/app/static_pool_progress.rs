@@ -0,0 +1,94 @@
+use super::{
+    exposed::StaticPoolProgress,
+    storage::{project::ProjectId, static_pool::STATIC_POOL_PROGRESS_CHANNEL},
+    App,
+};
+use dashmap::DashMap;
+use sqlx::postgres::PgListener;
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::watch, task::JoinHandle};
+use tracing::{debug, error, warn};
+
+/// Fans out static pool progress to api clients, watching the project.
+#[derive(Default)]
+pub struct StaticPoolProgressHub {
+    senders: DashMap<ProjectId, watch::Sender<StaticPoolProgress>>,
+}
+
+impl StaticPoolProgressHub {
+    pub fn subscribe(
+        &self,
+        project_id: ProjectId,
+        current: StaticPoolProgress,
+    ) -> watch::Receiver<StaticPoolProgress> {
+        let sender = self
+            .senders
+            .entry(project_id)
+            .or_insert_with(|| watch::channel(current.clone()).0);
+        // channel could be left without receivers and miss some updates
+        sender.send_if_modified(|progress| {
+            let modified = *progress != current;
+            *progress = current;
+            modified
+        });
+        sender.subscribe()
+    }
+
+    pub fn is_watched(&self, project_id: &ProjectId) -> bool {
+        self.senders.contains_key(project_id)
+    }
+
+    pub fn publish(&self, project_id: ProjectId, progress: StaticPoolProgress) {
+        self.senders
+            .remove_if(&project_id, |_, sender| sender.receiver_count() == 0);
+        if let Some(sender) = self.senders.get(&project_id) {
+            sender.send_replace(progress);
+        }
+    }
+}
+
+/// Static pool state is persisted by chain syncer, which notifies api servers via postgres.
+pub struct StaticPoolProgressListener {
+    pub app: Arc<App>,
+}
+
+impl StaticPoolProgressListener {
+    const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn serve(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = self.listen().await {
+                    error!("static pool progress listener failed: {err:#}");
+                }
+                tokio::time::sleep(Self::RECONNECT_INTERVAL).await;
+            }
+        })
+    }
+
+    async fn listen(&self) -> anyhow::Result<()> {
+        let mut listener = PgListener::connect_with(&self.app.storage.pool).await?;
+        listener.listen(STATIC_POOL_PROGRESS_CHANNEL).await?;
+
+        loop {
+            let notification = listener.recv().await?;
+            let project_id: ProjectId = match notification.payload().parse() {
+                Ok(project_id) => project_id,
+                Err(err) => {
+                    warn!(
+                        "invalid static pool progress notification {:?}: {err}",
+                        notification.payload()
+                    );
+                    continue;
+                }
+            };
+            if !self.app.static_pool_progress.is_watched(&project_id) {
+                continue;
+            }
+            match self.app.load_static_pool_progress(project_id).await? {
+                Some(progress) => self.app.static_pool_progress.publish(project_id, progress),
+                None => debug!("static pool state of project {project_id} is gone"),
+            }
+        }
+    }
+}
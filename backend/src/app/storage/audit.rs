@@ -0,0 +1,45 @@
+use super::{project::ProjectId, DB};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminAction {
+    ArchiveProject,
+    RestoreProject,
+    SetLaunchPeriods,
+    SetDeliveryMethods,
+    SetAllowedOwners,
+    ExtendStaticPool,
+    ForceUnlockLatch,
+    SetProjectCreationFee,
+    RecoverProject,
+    UpdatePoolConfig,
+}
+
+impl AdminAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdminAction::ArchiveProject => "archive_project",
+            AdminAction::RestoreProject => "restore_project",
+            AdminAction::SetLaunchPeriods => "set_launch_periods",
+            AdminAction::SetDeliveryMethods => "set_delivery_methods",
+            AdminAction::SetAllowedOwners => "set_allowed_owners",
+            AdminAction::ExtendStaticPool => "extend_static_pool",
+            AdminAction::ForceUnlockLatch => "force_unlock_latch",
+            AdminAction::SetProjectCreationFee => "set_project_creation_fee",
+            AdminAction::RecoverProject => "recover_project",
+            AdminAction::UpdatePoolConfig => "update_pool_config",
+        }
+    }
+}
+
+pub async fn record_admin_action<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    action: AdminAction,
+    project_id: Option<ProjectId>,
+) -> anyhow::Result<()> {
+    sqlx::query("INSERT INTO admin_audit_log (action, project_id) VALUES ($1, $2)")
+        .bind(action.as_str())
+        .bind(project_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,387 @@
+use super::{misc::Balance, project::ProjectId, DB};
+use crate::app::exposed::PriceStreamEvent;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use services_common::TZ;
+use solana_sdk::pubkey::Pubkey;
+use sqlx::types::Json;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "chain_event_kind", rename_all = "snake_case")]
+pub enum ChainEventKind {
+    Trade,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TradePayload {
+    #[serde_as(as = "DisplayFromStr")]
+    pub user: Pubkey,
+    pub is_buy: bool,
+    /// Lamports, paid by user on buy or received on sell.
+    pub sols: u64,
+    pub tokens: u64,
+    /// Price after the trade, in lamports per token.
+    pub spot_price_numerator: u64,
+    pub spot_price_denominator: u64,
+}
+
+impl TradePayload {
+    pub fn spot_price(&self) -> Option<f64> {
+        (self.spot_price_denominator > 0)
+            .then(|| self.spot_price_numerator as f64 / self.spot_price_denominator as f64)
+    }
+}
+
+pub const PRICE_STREAM_CHANNEL: &str = "price_stream";
+
+/// Curve trade, passed from chain syncer to api servers.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceStreamNotification {
+    pub project_id: ProjectId,
+    pub event: PriceStreamEvent,
+}
+
+/// Delivered only once the transaction is committed, so listeners never see rolled back trades.
+pub async fn notify_price_stream<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    notification: &PriceStreamNotification,
+) -> anyhow::Result<()> {
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(PRICE_STREAM_CHANNEL)
+        .bind(serde_json::to_string(notification)?)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+pub async fn record_trade<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    project_id: ProjectId,
+    tx_signature: &str,
+    slot: u64,
+    payload: TradePayload,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "
+            INSERT INTO chain_events (project_id, kind, tx_signature, slot, payload)
+            VALUES ($1, $2, $3, $4, $5)
+        ",
+    )
+    .bind(project_id)
+    .bind(ChainEventKind::Trade)
+    .bind(tx_signature)
+    .bind(i64::try_from(slot)?)
+    .bind(Json(payload))
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+pub enum StatsPeriod {
+    #[serde(rename = "1h")]
+    Hour,
+    #[default]
+    #[serde(rename = "24h")]
+    Day,
+    #[serde(rename = "7d")]
+    Week,
+}
+
+impl StatsPeriod {
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            StatsPeriod::Hour => chrono::Duration::hours(1),
+            StatsPeriod::Day => chrono::Duration::hours(24),
+            StatsPeriod::Week => chrono::Duration::days(7),
+        }
+    }
+}
+
+/// Prices are absent if there were no trades in the period.
+#[derive(Debug, Clone, sqlx::FromRow, Default)]
+pub struct StoredTradeStats {
+    pub volume_lamports: Balance,
+    pub volume_tokens: Balance,
+    pub trade_count: i64,
+    pub unique_traders: i64,
+    pub price_open: Option<f64>,
+    pub price_high: Option<f64>,
+    pub price_low: Option<f64>,
+    pub price_close: Option<f64>,
+}
+
+impl StoredTradeStats {
+    /// Day stats are read from the periodically refreshed view, others are aggregated on the fly.
+    pub async fn query<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+        period: StatsPeriod,
+    ) -> anyhow::Result<StoredTradeStats> {
+        const COLUMNS: &str = "
+            volume_lamports::balance,
+            volume_tokens::balance,
+            trade_count,
+            unique_traders,
+            price_open,
+            price_high,
+            price_low,
+            price_close
+        ";
+        let stats = if period == StatsPeriod::Day {
+            sqlx::query_as(&format!(
+                "SELECT {COLUMNS} FROM project_stats_24h WHERE project_id = $1"
+            ))
+            .bind(project_id)
+            .fetch_optional(executor)
+            .await?
+        } else {
+            let since: DateTime<TZ> = TZ::now() - period.duration();
+            sqlx::query_as(&format!(
+                "SELECT {COLUMNS} FROM project_trade_stats($2, $1)"
+            ))
+            .bind(project_id)
+            .bind(since)
+            .fetch_optional(executor)
+            .await?
+        };
+        Ok(stats.unwrap_or_default())
+    }
+
+    pub async fn refresh_day_view<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+    ) -> anyhow::Result<()> {
+        sqlx::query("REFRESH MATERIALIZED VIEW CONCURRENTLY project_stats_24h")
+            .execute(executor)
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, sqlx::Type, PartialEq, Eq,
+)]
+#[sqlx(type_name = "candle_interval")]
+pub enum CandleInterval {
+    #[serde(rename = "1m")]
+    #[sqlx(rename = "1m")]
+    Minute,
+    #[serde(rename = "5m")]
+    #[sqlx(rename = "5m")]
+    FiveMinutes,
+    #[default]
+    #[serde(rename = "1h")]
+    #[sqlx(rename = "1h")]
+    Hour,
+    #[serde(rename = "1d")]
+    #[sqlx(rename = "1d")]
+    Day,
+}
+
+impl CandleInterval {
+    pub const ALL: [CandleInterval; 4] = [
+        CandleInterval::Minute,
+        CandleInterval::FiveMinutes,
+        CandleInterval::Hour,
+        CandleInterval::Day,
+    ];
+
+    /// Start of the candle, containing the unix timestamp, aligned to the unix epoch.
+    pub fn open_ts(&self, ts: i64) -> i64 {
+        ts - ts.rem_euclid(self.duration().num_seconds())
+    }
+
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            CandleInterval::Minute => chrono::Duration::minutes(1),
+            CandleInterval::FiveMinutes => chrono::Duration::minutes(5),
+            CandleInterval::Hour => chrono::Duration::hours(1),
+            CandleInterval::Day => chrono::Duration::days(1),
+        }
+    }
+}
+
+/// Prices are in lamports per token, volume is in lamports.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoredCandle {
+    /// Unix timestamp of the candle start in seconds.
+    pub open_ts: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume_lamports: Balance,
+    pub trade_count: i32,
+}
+
+impl StoredCandle {
+    /// Minute candles are the most numerous, so they are pruned after this age.
+    pub const MINUTE_RETENTION: chrono::Duration = chrono::Duration::days(7);
+
+    /// Upserts current candles of every interval with the execution price of the trade.
+    pub async fn record_trade<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+        payload: &TradePayload,
+        ts: i64,
+    ) -> anyhow::Result<()> {
+        // price is undefined otherwise, charts can't draw such candles
+        if payload.tokens == 0 {
+            return Ok(());
+        }
+        let price = payload.sols as f64 / payload.tokens as f64;
+        let candles = (0..CandleInterval::ALL.len())
+            .map(|idx| {
+                format!(
+                    "(${}::candle_interval, ${}::BIGINT)",
+                    4 + idx * 2,
+                    5 + idx * 2
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "
+                INSERT INTO price_candle AS candle (
+                    project_id,
+                    \"interval\",
+                    open_ts,
+                    open,
+                    high,
+                    low,
+                    close,
+                    volume_lamports,
+                    trade_count
+                )
+                SELECT $1, current.interval, current.open_ts, $2, $2, $2, $2, $3, 1
+                FROM (VALUES {candles}) AS current (\"interval\", open_ts)
+                ON CONFLICT (project_id, \"interval\", open_ts) DO UPDATE SET
+                    high = GREATEST(candle.high, excluded.high),
+                    low = LEAST(candle.low, excluded.low),
+                    close = excluded.close,
+                    volume_lamports = candle.volume_lamports + excluded.volume_lamports,
+                    trade_count = candle.trade_count + 1
+            "
+        );
+        let mut query = sqlx::query(&sql)
+            .bind(project_id)
+            .bind(price)
+            .bind(Balance::from(payload.sols));
+        for interval in CandleInterval::ALL {
+            query = query.bind(interval).bind(interval.open_ts(ts));
+        }
+        query.execute(executor).await?;
+        Ok(())
+    }
+
+    /// Latest `limit` candles in the range, ordered from the oldest.
+    pub async fn query<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+        interval: CandleInterval,
+        from: Option<DateTime<TZ>>,
+        to: Option<DateTime<TZ>>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<StoredCandle>> {
+        let mut candles: Vec<StoredCandle> = sqlx::query_as(
+            "
+                SELECT open_ts, open, high, low, close, volume_lamports, trade_count
+                FROM price_candle
+                WHERE project_id = $1
+                    AND \"interval\" = $2
+                    AND ($3::BIGINT IS NULL OR open_ts >= $3)
+                    AND ($4::BIGINT IS NULL OR open_ts < $4)
+                ORDER BY open_ts DESC
+                LIMIT $5
+            ",
+        )
+        .bind(project_id)
+        .bind(interval)
+        // candle, containing the range start, is included
+        .bind(from.map(|from| interval.open_ts(from.timestamp())))
+        .bind(to.map(|to| to.timestamp()))
+        .bind(limit)
+        .fetch_all(executor)
+        .await?;
+        candles.reverse();
+        Ok(candles)
+    }
+
+    /// Removes candles of the interval, started before the unix timestamp.
+    pub async fn prune<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        interval: CandleInterval,
+        before_ts: i64,
+    ) -> anyhow::Result<u64> {
+        Ok(
+            sqlx::query("DELETE FROM price_candle WHERE \"interval\" = $1 AND open_ts < $2")
+                .bind(interval)
+                .bind(before_ts)
+                .execute(executor)
+                .await?
+                .rows_affected(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_stats_period() {
+        for (raw, period) in [
+            ("\"1h\"", StatsPeriod::Hour),
+            ("\"24h\"", StatsPeriod::Day),
+            ("\"7d\"", StatsPeriod::Week),
+        ] {
+            assert_eq!(serde_json::from_str::<StatsPeriod>(raw).unwrap(), period);
+        }
+        assert!(serde_json::from_str::<StatsPeriod>("\"30d\"").is_err());
+    }
+
+    #[test]
+    fn test_parses_candle_interval() {
+        for (raw, interval) in [
+            ("\"1m\"", CandleInterval::Minute),
+            ("\"5m\"", CandleInterval::FiveMinutes),
+            ("\"1h\"", CandleInterval::Hour),
+            ("\"1d\"", CandleInterval::Day),
+        ] {
+            assert_eq!(
+                serde_json::from_str::<CandleInterval>(raw).unwrap(),
+                interval
+            );
+        }
+        assert!(serde_json::from_str::<CandleInterval>("\"15m\"").is_err());
+    }
+
+    #[test]
+    fn test_aligns_candles_to_epoch() {
+        let ts = 1_700_000_290;
+        assert_eq!(CandleInterval::Minute.open_ts(ts), 1_700_000_280);
+        assert_eq!(CandleInterval::FiveMinutes.open_ts(ts), 1_700_000_100);
+        assert_eq!(CandleInterval::Hour.open_ts(ts), 1_699_999_200);
+        assert_eq!(CandleInterval::Day.open_ts(ts), 1_699_920_000);
+        assert_eq!(CandleInterval::Hour.open_ts(-1), -3600);
+    }
+
+    #[test]
+    fn test_stores_user_as_string() {
+        let user = Pubkey::new_unique();
+        let payload = serde_json::to_value(TradePayload {
+            user,
+            is_buy: true,
+            sols: 1,
+            tokens: 2,
+            spot_price_numerator: 3,
+            spot_price_denominator: 4,
+        })
+        .unwrap();
+        assert_eq!(payload["user"], user.to_string());
+    }
+}
@@ -0,0 +1,51 @@
+use super::DB;
+use serde::{de::DeserializeOwned, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigKey {
+    LaunchPeriods,
+    DeliveryMethods,
+    AllowedOwners,
+}
+
+impl ConfigKey {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigKey::LaunchPeriods => "launch_periods",
+            ConfigKey::DeliveryMethods => "delivery_methods",
+            ConfigKey::AllowedOwners => "allowed_owners",
+        }
+    }
+}
+
+pub async fn load_override<'c, T: DeserializeOwned, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    key: ConfigKey,
+) -> anyhow::Result<Option<T>> {
+    let stored: Option<String> =
+        sqlx::query_scalar("SELECT value::TEXT FROM config_overrides WHERE key = $1")
+            .bind(key.as_str())
+            .fetch_optional(executor)
+            .await?;
+    stored
+        .map(|value| Ok(serde_json::from_str(&value)?))
+        .transpose()
+}
+
+pub async fn store_override<'c, T: Serialize, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    key: ConfigKey,
+    value: &T,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "
+            INSERT INTO config_overrides (key, value) VALUES ($1, $2::JSONB)
+            ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, updated_at = NOW()
+        ",
+    )
+    .bind(key.as_str())
+    .bind(serde_json::to_string(value)?)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
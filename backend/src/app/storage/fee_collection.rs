@@ -0,0 +1,81 @@
+use super::{
+    misc::{Balance, StoredPubkey},
+    DB,
+};
+use chrono::DateTime;
+use moonzip::events::FeeSource;
+use services_common::TZ;
+
+#[derive(Debug, Clone, Copy, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "fee_source", rename_all = "snake_case")]
+pub enum StoredFeeSource {
+    Extracted,
+    AccountTaken,
+}
+
+impl From<FeeSource> for StoredFeeSource {
+    fn from(source: FeeSource) -> Self {
+        match source {
+            FeeSource::Extracted => StoredFeeSource::Extracted,
+            FeeSource::AccountTaken => StoredFeeSource::AccountTaken,
+        }
+    }
+}
+
+pub async fn record<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    recipient: StoredPubkey,
+    amount: u64,
+    source: StoredFeeSource,
+    tx_signature: &str,
+    slot: u64,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "
+            INSERT INTO fee_collection (recipient, amount, source, tx_signature, slot)
+            VALUES ($1, $2, $3, $4, $5)
+        ",
+    )
+    .bind(recipient)
+    .bind(Balance::from(amount))
+    .bind(source)
+    .bind(tx_signature)
+    .bind(i64::try_from(slot)?)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Lamports, collected within a UTC day.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoredDailyRevenue {
+    pub day: DateTime<TZ>,
+    pub extracted: Balance,
+    pub account_taken: Balance,
+}
+
+impl StoredDailyRevenue {
+    /// Days without collections are omitted, latest days first.
+    pub async fn query<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        since: DateTime<TZ>,
+    ) -> anyhow::Result<Vec<StoredDailyRevenue>> {
+        Ok(sqlx::query_as(
+            "
+                SELECT
+                    date_bin(INTERVAL '1 day', collected_at, TIMESTAMPTZ 'epoch') AS day,
+                    COALESCE(SUM(amount) FILTER (WHERE source = 'extracted'), 0)::balance
+                        AS extracted,
+                    COALESCE(SUM(amount) FILTER (WHERE source = 'account_taken'), 0)::balance
+                        AS account_taken
+                FROM fee_collection
+                WHERE collected_at >= date_bin(INTERVAL '1 day', $1, TIMESTAMPTZ 'epoch')
+                GROUP BY day
+                ORDER BY day DESC
+            ",
+        )
+        .bind(since)
+        .fetch_all(executor)
+        .await?)
+    }
+}
@@ -0,0 +1,132 @@
+use super::{
+    misc::{Balance, StoredPubkey},
+    project::ProjectId,
+    DB,
+};
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct StoredTokenHolder {
+    pub wallet: StoredPubkey,
+    pub balance: Balance,
+    pub last_updated_slot: i64,
+}
+
+impl StoredTokenHolder {
+    pub async fn increase<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+        wallet: StoredPubkey,
+        amount: u64,
+        slot: u64,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "
+                INSERT INTO token_holders (project_id, wallet, balance, last_updated_slot)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (project_id, wallet) DO UPDATE
+                    SET balance = token_holders.balance + excluded.balance,
+                        last_updated_slot = GREATEST(
+                            token_holders.last_updated_slot, excluded.last_updated_slot
+                        )
+            ",
+        )
+        .bind(project_id)
+        .bind(wallet)
+        .bind(Balance::from(amount))
+        .bind(i64::try_from(slot)?)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Balance is floored at zero: tokens could be received outside of tracked pools.
+    /// Holder is removed once nothing is left.
+    pub async fn decrease<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+        wallet: StoredPubkey,
+        amount: u64,
+        slot: u64,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "
+                WITH decreased AS (
+                    UPDATE token_holders SET
+                        balance = GREATEST(balance - $3, 0),
+                        last_updated_slot = GREATEST(last_updated_slot, $4)
+                    WHERE project_id = $1 AND wallet = $2
+                    RETURNING project_id, wallet, balance
+                )
+                DELETE FROM token_holders USING decreased
+                WHERE token_holders.project_id = decreased.project_id
+                    AND token_holders.wallet = decreased.wallet
+                    AND decreased.balance = 0
+            ",
+        )
+        .bind(project_id)
+        .bind(wallet)
+        .bind(Balance::from(amount))
+        .bind(i64::try_from(slot)?)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn count<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+    ) -> anyhow::Result<i64> {
+        Ok(sqlx::query_scalar(
+            "SELECT COUNT(*) FROM token_holders WHERE project_id = $1 AND balance > 0",
+        )
+        .bind(project_id)
+        .fetch_one(executor)
+        .await?)
+    }
+
+    /// Largest holders first, wallet breaks ties, so pages are stable.
+    pub async fn top<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<StoredTokenHolder>> {
+        Ok(sqlx::query_as(
+            "
+                SELECT wallet, balance, last_updated_slot FROM token_holders
+                WHERE project_id = $1 AND balance > 0
+                ORDER BY balance DESC, wallet
+                LIMIT $2 OFFSET $3
+            ",
+        )
+        .bind(project_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(executor)
+        .await?)
+    }
+
+    /// Stores holder counts into `project.holder_count_snapshot`, returns number of changed projects.
+    pub async fn snapshot_counts<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+    ) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            "
+                UPDATE project SET holder_count_snapshot = counted.holders
+                FROM (
+                    SELECT project.id, COUNT(token_holders.wallet)::INT AS holders
+                    FROM project
+                    LEFT JOIN token_holders
+                        ON token_holders.project_id = project.id AND token_holders.balance > 0
+                    WHERE project.archived_at IS NULL
+                    GROUP BY project.id
+                ) AS counted
+                WHERE project.id = counted.id
+                    AND project.holder_count_snapshot != counted.holders
+            ",
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
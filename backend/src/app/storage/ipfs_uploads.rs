@@ -0,0 +1,154 @@
+use super::{project::ProjectId, DB};
+use crate::app::image_format::ImageFormat;
+
+#[derive(Debug, Clone, Copy, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "ipfs_upload_kind", rename_all = "snake_case")]
+pub enum IpfsUploadKind {
+    Image,
+    Metadata,
+    PumpfunMetadata,
+}
+
+impl IpfsUploadKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IpfsUploadKind::Image => "image",
+            IpfsUploadKind::Metadata => "metadata",
+            IpfsUploadKind::PumpfunMetadata => "pumpfun_metadata",
+        }
+    }
+}
+
+/// Hash, size and format of the project image, computed on project creation.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoredImageDigest {
+    pub image_hash: Option<String>,
+    pub bytes: i64,
+    #[sqlx(try_from = "String")]
+    pub image_format: ImageFormat,
+    /// Set once uploaded, or on creation if the image was already hosted.
+    pub deployed_url: Option<String>,
+}
+
+pub async fn image_digest<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    project_id: ProjectId,
+) -> anyhow::Result<StoredImageDigest> {
+    Ok(sqlx::query_as(
+        "
+            SELECT image_hash, octet_length(image_content)::BIGINT AS bytes, image_format, deployed_url
+            FROM token_image WHERE project_id = $1
+        ",
+    )
+    .bind(project_id)
+    .fetch_one(executor)
+    .await?)
+}
+
+/// Image content is replaced, e.g. by a converted one, so the format must follow.
+pub async fn set_image_format<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    project_id: ProjectId,
+    format: ImageFormat,
+) -> anyhow::Result<()> {
+    sqlx::query("UPDATE token_image SET image_format = $1 WHERE project_id = $2")
+        .bind(format.as_str())
+        .bind(project_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Image content never changes, so its conversion failure is permanent.
+pub async fn mark_image_invalid<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    project_id: ProjectId,
+    reason: &str,
+) -> anyhow::Result<()> {
+    sqlx::query("UPDATE token_image SET invalid_reason = $1 WHERE project_id = $2")
+        .bind(reason)
+        .bind(project_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// URL of the same image, already uploaded for any project.
+pub async fn find_image_url<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    image_hash: &str,
+) -> anyhow::Result<Option<String>> {
+    Ok(sqlx::query_scalar(
+        "SELECT deployed_url FROM token_image WHERE image_hash = $1 AND deployed_url IS NOT NULL LIMIT 1",
+    )
+    .bind(image_hash)
+    .fetch_optional(executor)
+    .await?)
+}
+
+pub async fn set_image_url<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    project_id: ProjectId,
+    url: &str,
+) -> anyhow::Result<()> {
+    sqlx::query("UPDATE token_image SET deployed_url = $1 WHERE project_id = $2")
+        .bind(url)
+        .bind(project_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// URL of the same metadata, already deployed for any project.
+pub async fn find_metadata_url<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    metadata_hash: &str,
+) -> anyhow::Result<Option<String>> {
+    Ok(sqlx::query_scalar(
+        "SELECT deployed_url FROM token_meta WHERE metadata_hash = $1 AND deployed_url IS NOT NULL LIMIT 1",
+    )
+    .bind(metadata_hash)
+    .fetch_optional(executor)
+    .await?)
+}
+
+pub async fn record_upload<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    kind: IpfsUploadKind,
+    bytes: i64,
+    deduplicated: bool,
+) -> anyhow::Result<()> {
+    sqlx::query("INSERT INTO ipfs_uploads (kind, bytes, deduplicated) VALUES ($1, $2, $3)")
+        .bind(kind)
+        .bind(bytes)
+        .bind(deduplicated)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoredIpfsStats {
+    pub total_uploads: i64,
+    pub deduplicated_uploads: i64,
+    /// Size of the content, which wasn't uploaded thanks to deduplication.
+    pub bytes_saved: i64,
+}
+
+impl StoredIpfsStats {
+    pub async fn query<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+    ) -> anyhow::Result<StoredIpfsStats> {
+        Ok(sqlx::query_as(
+            "
+                SELECT
+                    COUNT(*) AS total_uploads,
+                    COUNT(*) FILTER (WHERE deduplicated) AS deduplicated_uploads,
+                    COALESCE(SUM(bytes) FILTER (WHERE deduplicated), 0)::BIGINT AS bytes_saved
+                FROM ipfs_uploads
+            ",
+        )
+        .fetch_one(executor)
+        .await?)
+    }
+}
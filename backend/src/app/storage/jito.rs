@@ -0,0 +1,30 @@
+use super::DB;
+use services_common::solana::jito::JitoTipStrategy;
+
+/// Strategy set by admins at runtime, overrides the one from config.
+pub async fn load_tip_strategy<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+) -> anyhow::Result<Option<JitoTipStrategy>> {
+    let stored: Option<String> = sqlx::query_scalar("SELECT strategy::TEXT FROM jito_tip_strategy")
+        .fetch_optional(executor)
+        .await?;
+    stored
+        .map(|strategy| Ok(serde_json::from_str(&strategy)?))
+        .transpose()
+}
+
+pub async fn store_tip_strategy<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    strategy: &JitoTipStrategy,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "
+            INSERT INTO jito_tip_strategy (strategy) VALUES ($1::JSONB)
+            ON CONFLICT (id) DO UPDATE SET strategy = EXCLUDED.strategy, updated_at = NOW()
+        ",
+    )
+    .bind(serde_json::to_string(strategy)?)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use super::{
+    misc::{Balance, StoredPubkey},
+    project::ProjectId,
+    DB,
+};
+use chrono::DateTime;
+use services_common::TZ;
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct StoredLpBurn {
+    pub project_id: ProjectId,
+    pub lp_mint: StoredPubkey,
+    pub burned_amount: Balance,
+    pub tx_signature: String,
+    pub slot: i64,
+    pub burned_at: DateTime<TZ>,
+}
+
+impl StoredLpBurn {
+    pub async fn find<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: &ProjectId,
+    ) -> anyhow::Result<Option<StoredLpBurn>> {
+        Ok(sqlx::query_as(
+            "
+                SELECT lp_burns.* FROM lp_burns
+                JOIN project ON project.id = lp_burns.project_id
+                WHERE lp_burns.project_id = $1 AND project.archived_at IS NULL
+            ",
+        )
+        .bind(project_id)
+        .fetch_optional(executor)
+        .await?)
+    }
+}
@@ -0,0 +1,188 @@
+use super::{project::ProjectId, StorageClient, DB};
+use anyhow::bail;
+use chrono::DateTime;
+use services_common::TZ;
+use sqlx::pool::PoolConnection;
+use std::time::Duration;
+use tracing::error;
+use uuid::Uuid;
+
+/// Lease of the project, taken by the migration along with the row lock.
+/// Lease expires on its own and the migration commits only while it still owns the lease,
+/// so stuck migrations are released without touching their sessions.
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct StoredMigrationLock {
+    pub project_id: ProjectId,
+    /// Token of the migration, holding the lease.
+    pub owner: Uuid,
+    pub locked_since: DateTime<TZ>,
+    pub expires_at: DateTime<TZ>,
+}
+
+impl StoredMigrationLock {
+    /// Returns the owner token, absent if the project is leased and the lease is not expired yet.
+    /// Must be called outside of the locking transaction, so the lease is visible to others.
+    pub async fn acquire<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+        ttl: Duration,
+    ) -> anyhow::Result<Option<Uuid>> {
+        Ok(sqlx::query_scalar(
+            "
+                INSERT INTO project_migration_lock_holder AS holder (project_id, owner, expires_at)
+                VALUES ($1, $2, NOW() + make_interval(secs => $3))
+                ON CONFLICT (project_id) DO UPDATE
+                    SET owner = EXCLUDED.owner,
+                        locked_since = NOW(),
+                        expires_at = EXCLUDED.expires_at
+                    WHERE holder.expires_at <= NOW()
+                RETURNING owner
+            ",
+        )
+        .bind(project_id)
+        .bind(Uuid::new_v4())
+        .bind(ttl.as_secs_f64())
+        .fetch_optional(executor)
+        .await?)
+    }
+
+    /// Ends the lease within the locking transaction.
+    /// Fails if the lease is taken over or revoked, so the transaction is never committed.
+    pub async fn finish<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+        owner: Uuid,
+    ) -> anyhow::Result<()> {
+        let finished = Self::give_up(executor, project_id, owner).await?;
+        if !finished {
+            bail!("migration lease of project {project_id} is lost");
+        }
+        Ok(())
+    }
+
+    /// Ends the lease without committing anything, returns `false` if the lease is already lost.
+    pub async fn give_up<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+        owner: Uuid,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM project_migration_lock_holder WHERE project_id = $1 AND owner = $2",
+        )
+        .bind(project_id)
+        .bind(owner)
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Oldest leases first.
+    pub async fn list_held<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+    ) -> anyhow::Result<Vec<StoredMigrationLock>> {
+        Ok(sqlx::query_as(
+            "
+                SELECT * FROM project_migration_lock_holder
+                WHERE expires_at > NOW()
+                ORDER BY locked_since ASC
+            ",
+        )
+        .fetch_all(executor)
+        .await?)
+    }
+
+    /// Removes expired leases, so their migrations could never commit and projects are retried.
+    pub async fn release_expired<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+    ) -> anyhow::Result<Vec<StoredMigrationLock>> {
+        Ok(sqlx::query_as(
+            "DELETE FROM project_migration_lock_holder WHERE expires_at <= NOW() RETURNING *",
+        )
+        .fetch_all(executor)
+        .await?)
+    }
+
+    /// Revokes the lease of the project, so its migration never commits and migrator picks
+    /// the project up again. Returns `false` if the project is not leased.
+    pub async fn release<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+    ) -> anyhow::Result<bool> {
+        let released: Option<bool> = sqlx::query_scalar(
+            "
+                DELETE FROM project_migration_lock_holder WHERE project_id = $1
+                RETURNING expires_at > NOW()
+            ",
+        )
+        .bind(project_id)
+        .fetch_optional(executor)
+        .await?;
+        Ok(released.unwrap_or(false))
+    }
+
+    /// Keeps the migrator off the project until the transaction ends.
+    /// Returns `false` if the migrator is already working on it.
+    pub async fn try_hold<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+    ) -> anyhow::Result<bool> {
+        let held: Option<ProjectId> = sqlx::query_scalar(
+            "SELECT id FROM project_migration_lock WHERE id = $1 FOR UPDATE SKIP LOCKED",
+        )
+        .bind(project_id)
+        .fetch_optional(executor)
+        .await?;
+        Ok(held.is_some())
+    }
+}
+
+/// Session-level advisory lock of the project, so replicas don't migrate the same project.
+/// Held by the dedicated connection and released once dropped.
+pub struct AdvisoryLock {
+    conn: Option<PoolConnection<DB>>,
+    key: i64,
+}
+
+impl AdvisoryLock {
+    /// Absent if the project is already locked, most likely by another replica.
+    pub async fn try_acquire(
+        storage: &StorageClient,
+        project_id: &ProjectId,
+    ) -> anyhow::Result<Option<AdvisoryLock>> {
+        let key = advisory_lock_key(project_id);
+        let mut conn = storage.pool.acquire().await?;
+        let acquired: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+            .bind(key)
+            .fetch_one(&mut *conn)
+            .await?;
+        Ok(acquired.then_some(AdvisoryLock {
+            conn: Some(conn),
+            key,
+        }))
+    }
+}
+
+impl Drop for AdvisoryLock {
+    fn drop(&mut self) {
+        let Some(mut conn) = self.conn.take() else {
+            return;
+        };
+        let key = self.key;
+        tokio::spawn(async move {
+            if let Err(err) = sqlx::query("SELECT pg_advisory_unlock($1)")
+                .bind(key)
+                .execute(&mut *conn)
+                .await
+            {
+                error!("failed to release advisory lock {key}: {err:#}");
+                // closing the session releases the lock as well
+                drop(conn.detach());
+            }
+        });
+    }
+}
+
+/// Lower half of the project id, collisions only make unrelated projects wait for each other.
+fn advisory_lock_key(project_id: &ProjectId) -> i64 {
+    project_id.to_u128_le() as i64
+}
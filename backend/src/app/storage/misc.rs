@@ -1,7 +1,15 @@
+use super::DB;
 use derive_more::derive::{From, Into};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer as _};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef},
+};
+use std::fmt;
+use zeroize::{Zeroize as _, Zeroizing};
 
 #[derive(
     Debug, Serialize, Deserialize, sqlx::Type, From, Into, Clone, PartialEq, Eq, PartialOrd, Ord,
@@ -27,31 +35,76 @@ impl From<StoredPubkey> for Pubkey {
     }
 }
 
-#[derive(
-    Debug, Serialize, Deserialize, sqlx::Type, From, Into, Clone, PartialEq, Eq, PartialOrd, Ord,
-)]
-#[sqlx(transparent, type_name = "keypair")]
-pub struct StoredKeypair(Vec<u8>);
+const KEYPAIR_LENGTH: usize = 64;
+
+/// Secret key bytes are wiped from memory once value is dropped.
+#[derive(Clone, PartialEq, Eq)]
+pub struct StoredKeypair(Zeroizing<[u8; KEYPAIR_LENGTH]>);
 
 impl StoredKeypair {
     pub fn to_keypair(&self) -> Keypair {
-        Keypair::from_bytes(self.0.as_slice()).expect("invariant: invalid stored secret key")
+        Keypair::from_bytes(self.expose_secret()).expect("invariant: invalid stored secret key")
     }
 
     pub fn from_keypair(keypair: &Keypair) -> Self {
-        Self(keypair.to_bytes().to_vec())
+        let mut bytes = keypair.to_bytes();
+        let stored = Self(Zeroizing::new(bytes));
+        bytes.zeroize();
+        stored
+    }
+
+    pub fn expose_secret(&self) -> &[u8; KEYPAIR_LENGTH] {
+        &self.0
+    }
+}
+
+impl Drop for StoredKeypair {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for StoredKeypair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StoredKeypair")
+            .field(&self.to_keypair().pubkey())
+            .finish()
     }
 }
 
 impl From<Keypair> for StoredKeypair {
     fn from(value: Keypair) -> Self {
-        Self(value.to_bytes().to_vec())
+        Self::from_keypair(&value)
     }
 }
 
 impl From<StoredKeypair> for Keypair {
     fn from(value: StoredKeypair) -> Self {
-        Keypair::from_bytes(value.0.as_slice()).expect("invariant: invalid stored keypair")
+        value.to_keypair()
+    }
+}
+
+impl sqlx::Type<DB> for StoredKeypair {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("keypair")
+    }
+}
+
+impl sqlx::Encode<'_, DB> for StoredKeypair {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&[u8] as sqlx::Encode<DB>>::encode(self.expose_secret().as_slice(), buf)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, DB> for StoredKeypair {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let bytes = <&[u8] as sqlx::Decode<DB>>::decode(value)?;
+        let mut secret = Zeroizing::new([0u8; KEYPAIR_LENGTH]);
+        if bytes.len() != KEYPAIR_LENGTH {
+            return Err(format!("invalid keypair length: {}", bytes.len()).into());
+        }
+        secret.copy_from_slice(bytes);
+        Ok(Self(secret))
     }
 }
 
@@ -80,3 +133,45 @@ impl TryFrom<Balance> for u64 {
         Ok(value.0.try_into()?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::ManuallyDrop;
+
+    #[test]
+    fn test_roundtrips_keypair() {
+        let keypair = Keypair::new();
+        let stored = StoredKeypair::from_keypair(&keypair);
+        assert_eq!(stored.expose_secret(), &keypair.to_bytes());
+        assert_eq!(stored.to_keypair().pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn test_zeroes_secret_on_drop() {
+        let mut stored = ManuallyDrop::new(StoredKeypair::from_keypair(&Keypair::new()));
+        let secret = stored.expose_secret().as_ptr();
+        assert!(stored.expose_secret().iter().any(|byte| *byte != 0));
+
+        unsafe {
+            ManuallyDrop::drop(&mut stored);
+            // memory is still owned by `ManuallyDrop`, so it's valid to read it
+            let leftover = std::slice::from_raw_parts(secret, KEYPAIR_LENGTH);
+            assert!(leftover.iter().all(|byte| *byte == 0));
+        }
+    }
+
+    #[test]
+    fn test_zeroes_cloned_secret_on_drop() {
+        let stored = StoredKeypair::from_keypair(&Keypair::new());
+        let mut cloned = ManuallyDrop::new(stored.clone());
+        let secret = cloned.expose_secret().as_ptr();
+
+        unsafe {
+            ManuallyDrop::drop(&mut cloned);
+            let leftover = std::slice::from_raw_parts(secret, KEYPAIR_LENGTH);
+            assert!(leftover.iter().all(|byte| *byte == 0));
+        }
+        assert!(stored.expose_secret().iter().any(|byte| *byte != 0));
+    }
+}
@@ -1,10 +1,32 @@
+use crate::metrics::{
+    POOL_ACQUIRE_TIMEOUTS, POOL_CONNECTIONS_ACTIVE, POOL_CONNECTIONS_IDLE, POOL_CONNECTION_LIFETIME,
+};
 use derive_more::derive::Deref;
 use serde::{Deserialize, Serialize};
-use sqlx::query;
+use sqlx::{postgres::PgPoolOptions, query};
+use std::time::{Duration, Instant};
+use tracing::warn;
 
+pub mod audit;
+pub mod chain_events;
+pub mod config_overrides;
+pub mod fee_collection;
+pub mod holders;
+pub mod ipfs_uploads;
+pub mod jito;
+pub mod lp_burns;
+pub mod migration_lock;
 pub mod misc;
+pub mod notifications;
 pub mod project;
+pub mod project_stats;
+pub mod raydium_pool_state;
+pub mod search;
+pub mod static_pool;
+pub mod tx_failures;
+pub mod upgrade_proposals;
 pub mod user_info;
+pub mod webhooks;
 
 pub type DB = sqlx::Postgres;
 pub type DBTransaction<'a> = sqlx::Transaction<'a, DB>;
@@ -14,12 +36,33 @@ pub struct StorageConfig {
     pub url: String,
     #[serde(default = "default_max_connections")]
     pub max_connections: u32,
+    #[serde(default = "default_min_connections")]
+    pub min_connections: u32,
+    #[serde(default = "default_acquire_timeout_ms")]
+    pub acquire_timeout_ms: u64,
+    #[serde(default = "default_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
 }
 
 pub fn default_max_connections() -> u32 {
     5
 }
 
+pub fn default_min_connections() -> u32 {
+    2
+}
+
+pub fn default_acquire_timeout_ms() -> u64 {
+    5000
+}
+
+pub fn default_idle_timeout_secs() -> u64 {
+    600
+}
+
+const SLOW_ACQUIRE: Duration = Duration::from_millis(1000);
+const POOL_STATS_INTERVAL: Duration = Duration::from_secs(5);
+
 #[derive(Deref, Clone)]
 pub struct StorageClient {
     pub pool: sqlx::Pool<sqlx::Postgres>,
@@ -31,23 +74,80 @@ impl StorageClient {
     }
 
     pub async fn from_config(config: StorageConfig) -> anyhow::Result<Self> {
-        let pool = sqlx::postgres::PgPoolOptions::new()
+        let pool = PgPoolOptions::new()
             .max_connections(config.max_connections)
+            .min_connections(config.min_connections)
+            .acquire_timeout(Duration::from_millis(config.acquire_timeout_ms))
+            .idle_timeout(Duration::from_secs(config.idle_timeout_secs))
+            .after_release(|_, meta| {
+                Box::pin(async move {
+                    POOL_CONNECTION_LIFETIME.observe(meta.age.as_secs_f64());
+                    Ok(true)
+                })
+            })
             .connect(&config.url)
             .await?;
-        Ok(Self::new(pool))
+        let client = Self::new(pool);
+        client.clone().serve_pool_stats();
+        Ok(client)
+    }
+
+    /// Periodically exports pool occupancy: constantly high active count is a sign of leaked connections.
+    fn serve_pool_stats(self) {
+        tokio::spawn(async move {
+            loop {
+                let stats = self.pool_stats();
+                POOL_CONNECTIONS_ACTIVE.set(i64::from(stats.active));
+                POOL_CONNECTIONS_IDLE.set(stats.idle as i64);
+                tokio::time::sleep(POOL_STATS_INTERVAL).await;
+            }
+        });
+    }
+
+    pub fn pool_stats(&self) -> PoolStats {
+        let size = self.pool.size();
+        let idle = self.pool.num_idle();
+        PoolStats {
+            size,
+            idle,
+            active: size.saturating_sub(idle as u32),
+            max_connections: self.pool.options().get_max_connections(),
+            min_connections: self.pool.options().get_min_connections(),
+        }
     }
 
     pub async fn tx(&self) -> anyhow::Result<sqlx::Transaction<'_, sqlx::Postgres>> {
-        let tx = self.pool.begin().await?;
+        let tx = self.pool.begin().await.inspect_err(|err| {
+            if matches!(err, sqlx::Error::PoolTimedOut) {
+                POOL_ACQUIRE_TIMEOUTS.inc();
+            }
+        })?;
         Ok(tx)
     }
 
     pub async fn serializable_tx(&self) -> anyhow::Result<sqlx::Transaction<'_, sqlx::Postgres>> {
+        let before = Instant::now();
         let mut tx = self.tx().await?;
+        let elapsed = before.elapsed();
+        if elapsed > SLOW_ACQUIRE {
+            warn!(
+                "slow db connection acquisition: {elapsed:?}, pool stats: {:?}",
+                self.pool_stats()
+            );
+        }
         query!("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
             .execute(&mut *tx)
             .await?;
         Ok(tx)
     }
 }
+
+#[derive(Debug, Clone, Copy, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: usize,
+    pub active: u32,
+    pub max_connections: u32,
+    pub min_connections: u32,
+}
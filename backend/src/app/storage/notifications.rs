@@ -0,0 +1,120 @@
+use super::DB;
+use uuid::Uuid;
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct StoredNotificationPreferences {
+    pub wallet: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub verification_token: Option<Uuid>,
+    pub notify_on_buy: bool,
+    pub notify_on_sell: bool,
+    pub notify_on_graduation: bool,
+    pub notify_on_dev_unlock: bool,
+}
+
+impl StoredNotificationPreferences {
+    pub async fn upsert<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        preferences: &StoredNotificationPreferences,
+    ) -> anyhow::Result<StoredNotificationPreferences> {
+        // Changing email always drops its verification.
+        let stored = sqlx::query_as(
+            "
+                INSERT INTO user_notifications VALUES ($1, $2, false, NULL, $3, $4, $5, $6)
+                ON CONFLICT (wallet) DO UPDATE SET
+                    email = excluded.email,
+                    email_verified = user_notifications.email_verified
+                        AND user_notifications.email IS NOT DISTINCT FROM excluded.email,
+                    verification_token = CASE
+                        WHEN user_notifications.email IS NOT DISTINCT FROM excluded.email
+                        THEN user_notifications.verification_token
+                    END,
+                    notify_on_buy = excluded.notify_on_buy,
+                    notify_on_sell = excluded.notify_on_sell,
+                    notify_on_graduation = excluded.notify_on_graduation,
+                    notify_on_dev_unlock = excluded.notify_on_dev_unlock
+                RETURNING *
+            ",
+        )
+        .bind(&preferences.wallet)
+        .bind(&preferences.email)
+        .bind(preferences.notify_on_buy)
+        .bind(preferences.notify_on_sell)
+        .bind(preferences.notify_on_graduation)
+        .bind(preferences.notify_on_dev_unlock)
+        .fetch_one(executor)
+        .await?;
+        Ok(stored)
+    }
+
+    pub async fn find<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        wallet: &str,
+    ) -> anyhow::Result<Option<StoredNotificationPreferences>> {
+        Ok(
+            sqlx::query_as("SELECT * FROM user_notifications WHERE wallet = $1")
+                .bind(wallet)
+                .fetch_optional(executor)
+                .await?,
+        )
+    }
+
+    pub async fn set_verification_token<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        wallet: &str,
+        token: Uuid,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "UPDATE user_notifications SET verification_token = $2, email_verified = false WHERE wallet = $1",
+        )
+        .bind(wallet)
+        .bind(token)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns wallet, which email was confirmed, if token matched.
+    pub async fn confirm_email<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        token: Uuid,
+    ) -> anyhow::Result<Option<String>> {
+        Ok(sqlx::query_scalar(
+            "
+                UPDATE user_notifications
+                SET email_verified = true, verification_token = NULL
+                WHERE verification_token = $1 AND email IS NOT NULL
+                RETURNING wallet
+            ",
+        )
+        .bind(token)
+        .fetch_optional(executor)
+        .await?)
+    }
+
+    pub fn verified_email(&self) -> Option<&str> {
+        if !self.email_verified {
+            return None;
+        }
+        self.email.as_deref()
+    }
+}
+
+pub async fn record_delivery<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    wallet: &str,
+    event_type: &str,
+    error: Option<String>,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "INSERT INTO notification_deliveries (wallet, event_type, success, error) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(wallet)
+    .bind(event_type)
+    .bind(error.is_none())
+    .bind(error)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::app::exposed::{DevLockPeriod, DevPurchase};
+use crate::app::exposed::{DeliveryMethod, DevPurchase, UpdateProjectMetaRequest};
 
 use super::{
     misc::{Balance, StoredKeypair, StoredPubkey},
@@ -6,13 +6,17 @@ use super::{
 };
 use bytes::Bytes;
 use chrono::DateTime;
-use const_format::concatcp;
 use futures_util::stream::BoxStream;
-use moonzip::project::{CurvePoolVariant, ProjectSchema, ProjectStage};
+use moonzip::{
+    curved_pool::curve::CurveState,
+    project::{CurvePoolVariant, ProjectSchema, ProjectStage},
+};
 use serde::{Deserialize, Serialize};
 use services_common::{utils::SyncStream, TZ};
+use sha2::{Digest, Sha256};
 use solana_sdk::{pubkey::Pubkey, signer::Signer};
-use sqlx::types::Uuid;
+use sqlx::{postgres::PgArguments, query::QueryAs, types::Uuid};
+use std::{collections::HashSet, fmt::Write as _};
 use utoipa::ToSchema;
 
 pub fn project_id(id: &Uuid) -> moonzip::project::ProjectId {
@@ -25,19 +29,168 @@ pub fn from_chain_project_id(id: moonzip::project::ProjectId) -> ProjectId {
 
 pub type ProjectId = Uuid;
 
+/// Class of advisory locks, taken by [`StoredProject::lock_owner`].
+const OWNER_LOCK_CLASS: i32 = 1;
+
+pub type ProjectIdSet = HashSet<ProjectId>;
+
+/// Defines how backend assigns ids to new projects.
+/// On-chain id is always derived from the backend one via [`project_id`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProjectIdStrategy {
+    #[default]
+    Random,
+    /// Ids are taken from `project_id_seq`, so they are ordered by creation.
+    Sequential,
+    /// First 16 bytes of `SHA256(prefix || random uuid)`.
+    PrefixedHash(String),
+}
+
+impl ProjectIdStrategy {
+    pub async fn generate<'c, E: sqlx::Executor<'c, Database = DB>>(
+        &self,
+        executor: E,
+    ) -> anyhow::Result<ProjectId> {
+        Ok(match self {
+            ProjectIdStrategy::Random => Uuid::new_v4(),
+            ProjectIdStrategy::Sequential => {
+                let next: i64 = sqlx::query_scalar("SELECT nextval('project_id_seq')")
+                    .fetch_one(executor)
+                    .await?;
+                Uuid::from_u128(u128::try_from(next)?)
+            }
+            ProjectIdStrategy::PrefixedHash(prefix) => {
+                let mut hasher = Sha256::new();
+                hasher.update(prefix.as_bytes());
+                hasher.update(Uuid::new_v4().as_bytes());
+                let hash = hasher.finalize();
+                let mut bytes = [0u8; 16];
+                bytes.copy_from_slice(&hash[..16]);
+                Uuid::from_bytes(bytes)
+            }
+        })
+    }
+}
+
 #[derive(Debug, sqlx::FromRow, Clone)]
 pub struct StoredProject {
     pub id: ProjectId,
     pub owner: StoredPubkey,
     pub deploy_schema: StoredDeploySchema,
     pub stage: Stage,
-    pub static_pool_pubkey: Option<StoredPubkey>,
+    /// Mints of opened static pools, in the order of opening.
+    pub static_pool_pubkeys: Vec<StoredPubkey>,
     pub curve_pool_keypair: Option<StoredKeypair>,
     pub dev_lock_keypair: Option<StoredKeypair>,
     pub created_at: DateTime<TZ>,
 }
 
+#[derive(Debug, Clone, Copy, Default, sqlx::FromRow)]
+pub struct OwnerProjectCounts {
+    pub this_hour: i64,
+    /// Neither graduated nor archived.
+    pub active: i64,
+}
+
 impl StoredProject {
+    /// Archiving is allowed only for projects, which finished their lifecycle.
+    pub const ARCHIVABLE_STAGES: [Stage; 1] = [Stage::Graduated];
+
+    pub async fn count_by_owner<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        owner: &StoredPubkey,
+    ) -> anyhow::Result<OwnerProjectCounts> {
+        Ok(sqlx::query_as(
+            "
+                SELECT
+                    COUNT(*) FILTER (WHERE created_at > NOW() - INTERVAL '1 hour') AS this_hour,
+                    COUNT(*) FILTER (WHERE stage != 'Graduated' AND archived_at IS NULL) AS active
+                FROM project
+                WHERE owner = $1
+            ",
+        )
+        .bind(owner)
+        .fetch_one(executor)
+        .await?)
+    }
+
+    /// Serializes project creations of the owner till the end of transaction,
+    /// so [`Self::count_by_owner`] stays accurate until the project is inserted.
+    pub async fn lock_owner(
+        tx: &mut sqlx::Transaction<'_, DB>,
+        owner: &StoredPubkey,
+    ) -> anyhow::Result<()> {
+        let owner = owner.to_pubkey().to_bytes();
+        // two keys form doesn't overlap with single key locks of migrations,
+        // collisions only make unrelated owners wait for each other.
+        let key = i32::from_le_bytes(owner[..4].try_into()?);
+        sqlx::query("SELECT pg_advisory_xact_lock($1, $2)")
+            .bind(OWNER_LOCK_CLASS)
+            .bind(key)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn is_archived<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: &ProjectId,
+    ) -> anyhow::Result<bool> {
+        let archived: Option<bool> =
+            sqlx::query_scalar("SELECT archived_at IS NOT NULL FROM project WHERE id = $1")
+                .bind(project_id)
+                .fetch_optional(executor)
+                .await?;
+        Ok(archived.unwrap_or(false))
+    }
+
+    /// Returns `false` if project is missing, already archived or not in archivable stage.
+    pub async fn archive<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: &ProjectId,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            "
+                UPDATE project SET archived_at = NOW()
+                WHERE id = $1 AND archived_at IS NULL AND stage = ANY($2::project_stage[])
+            ",
+        )
+        .bind(project_id)
+        .bind(Self::ARCHIVABLE_STAGES)
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns `false` if project is missing or not archived.
+    pub async fn restore<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: &ProjectId,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            "UPDATE project SET archived_at = NULL WHERE id = $1 AND archived_at IS NOT NULL",
+        )
+        .bind(project_id)
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns `false` if project is missing or already in the stage.
+    pub async fn set_stage<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: &ProjectId,
+        stage: Stage,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query("UPDATE project SET stage = $2 WHERE id = $1 AND stage <> $2")
+            .bind(project_id)
+            .bind(stage)
+            .execute(executor)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     pub fn apply_from_chain(&mut self, project: moonzip::project::Project) -> bool {
         let stage = Stage::from_chain(project.stage);
         let changed = self.stage != stage;
@@ -45,8 +198,77 @@ impl StoredProject {
         changed
     }
 
+    /// Mint of the first pool, which is still active: earlier ones are already graduated.
+    /// Once all of them are graduated, it's the mint of the last one.
     pub fn static_pool_mint(&self) -> Option<Pubkey> {
-        self.static_pool_pubkey.as_ref().map(|key| key.to_pubkey())
+        self.static_pool_pubkeys
+            .get(self.current_static_pool_index()?)
+            .map(StoredPubkey::to_pubkey)
+    }
+
+    pub fn static_pool_mints(&self) -> impl Iterator<Item = Pubkey> + '_ {
+        self.static_pool_pubkeys.iter().map(StoredPubkey::to_pubkey)
+    }
+
+    /// Config of the pool, which mint is [`Self::static_pool_mint`].
+    pub fn current_static_pool(&self) -> Option<&StoredStaticPoolConfig> {
+        self.deploy_schema
+            .static_pools
+            .get(self.current_static_pool_index()?)
+    }
+
+    /// Limit of the current pool, absent for pools created before it was configured per pool.
+    pub fn static_pool_max_lamports(&self) -> anyhow::Result<Option<u64>> {
+        Ok(self
+            .current_static_pool()
+            .and_then(|pool| pool.max_lamports.clone())
+            .map(u64::try_from)
+            .transpose()?)
+    }
+
+    /// Pool to open once the current one is closed, if any.
+    pub fn next_static_pool_index(&self) -> Option<usize> {
+        if !self.stage.is_static_pool() {
+            return None;
+        }
+        let next = self.current_static_pool_index()? + 1;
+        (next < self.deploy_schema.static_pools.len()).then_some(next)
+    }
+
+    fn current_static_pool_index(&self) -> Option<usize> {
+        let pool_count = if self.stage == Stage::StaticPoolFailed {
+            self.static_pool_pubkeys.len()
+        } else {
+            self.deploy_schema.static_pools.len()
+        };
+        self.stage.static_pool_index(pool_count)
+    }
+
+    /// Assigns mint and deadline to the pool at `index`, replacing ones of previous attempt, if any.
+    pub async fn open_static_pool<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: &ProjectId,
+        index: usize,
+        mint: StoredPubkey,
+        launch_ts: i64,
+    ) -> anyhow::Result<()> {
+        // postgres arrays are 1-based
+        let position = i32::try_from(index)? + 1;
+        sqlx::query(
+            "
+                UPDATE project
+                SET static_pool_pubkeys = static_pool_pubkeys[1:$2 - 1] || ARRAY[$3::pubkey],
+                    deploy_schema.static_pools[$2].launch_ts = $4
+                WHERE id = $1
+            ",
+        )
+        .bind(project_id)
+        .bind(position)
+        .bind(mint)
+        .bind(launch_ts)
+        .execute(executor)
+        .await?;
+        Ok(())
     }
 
     pub fn curve_pool_mint(&self) -> Option<Pubkey> {
@@ -56,16 +278,33 @@ impl StoredProject {
     }
 }
 
-#[derive(Debug, sqlx::Type, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    sqlx::Type,
+    ToSchema,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+#[serde(rename_all = "camelCase")]
 #[sqlx(type_name = "project_stage")]
 pub enum Stage {
     Created,
     Confirmed,
     OnStaticPool,
     StaticPoolClosed,
+    OnSecondStaticPool,
+    SecondStaticPoolClosed,
     OnCurvePool,
     CurvePoolClosed,
     Graduated,
+    StaticPoolFailed,
 }
 
 impl Stage {
@@ -77,8 +316,38 @@ impl Stage {
             ProjectStage::CurvePoolActive => Stage::OnCurvePool,
             ProjectStage::CurvePoolClosed => Stage::CurvePoolClosed,
             ProjectStage::Graduated => Stage::Graduated,
+            ProjectStage::SecondStaticPoolActive => Stage::OnSecondStaticPool,
+            ProjectStage::SecondStaticPoolClosed => Stage::SecondStaticPoolClosed,
+            ProjectStage::StaticPoolFailed => Stage::StaticPoolFailed,
+        }
+    }
+
+    /// Index of the current static pool out of `pool_count` ones.
+    /// Derived from the stage, as stored mints could outrun it,
+    /// while opening of the next pool is not yet confirmed.
+    pub fn static_pool_index(&self, pool_count: usize) -> Option<usize> {
+        match self {
+            Stage::Created | Stage::Confirmed | Stage::OnStaticPool | Stage::StaticPoolClosed => {
+                Some(0)
+            }
+            Stage::OnSecondStaticPool | Stage::SecondStaticPoolClosed => Some(1),
+            Stage::OnCurvePool | Stage::CurvePoolClosed | Stage::Graduated => {
+                pool_count.checked_sub(1)
+            }
+            // no pool is opened after the failed one, so `pool_count` must be of opened ones
+            Stage::StaticPoolFailed => pool_count.checked_sub(1),
         }
     }
+
+    pub fn is_static_pool(&self) -> bool {
+        matches!(
+            self,
+            Stage::OnStaticPool
+                | Stage::StaticPoolClosed
+                | Stage::OnSecondStaticPool
+                | Stage::SecondStaticPoolClosed
+        )
+    }
 }
 
 #[derive(Debug, sqlx::FromRow, Clone)]
@@ -100,10 +369,73 @@ impl StoredTokenMeta {
             .cloned()
             .ok_or_else(|| anyhow::anyhow!("invariant: token meta is not deployed"))
     }
+
+    /// Absent fields are left as is. Deployed metadata stays in use,
+    /// until the migrator uploads the new one.
+    pub async fn update<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        update: &UpdateProjectMetaRequest,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "
+                UPDATE token_meta SET
+                    description = COALESCE($2, description),
+                    website = COALESCE($3, website),
+                    twitter = COALESCE($4, twitter),
+                    telegram = COALESCE($5, telegram),
+                    update_pending = TRUE
+                WHERE project_id = $1
+            ",
+        )
+        .bind(update.project_id)
+        .bind(&update.description)
+        .bind(&update.website)
+        .bind(&update.twitter)
+        .bind(&update.telegram)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn is_update_pending<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+    ) -> anyhow::Result<bool> {
+        Ok(
+            sqlx::query_scalar("SELECT update_pending FROM token_meta WHERE project_id = $1")
+                .bind(project_id)
+                .fetch_one(executor)
+                .await?,
+        )
+    }
+
+    /// Live curves, which metadata is updated by owners and not yet deployed again.
+    pub async fn outdated_on_curve<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+    ) -> anyhow::Result<Vec<ProjectId>> {
+        Ok(sqlx::query_scalar(
+            "
+                SELECT project.id FROM project
+                JOIN token_meta ON token_meta.project_id = project.id
+                WHERE project.stage = 'OnCurvePool' AND project.archived_at IS NULL
+                    AND token_meta.update_pending
+            ",
+        )
+        .fetch_all(executor)
+        .await?)
+    }
 }
 
 pub struct ImageStream<'a>(pub BoxStream<'a, Result<Bytes, sqlx::Error>>);
 
+impl ImageStream<'_> {
+    pub fn from_bytes(content: Vec<u8>) -> Self {
+        Self(Box::pin(futures_util::stream::once(async move {
+            sqlx::Result::Ok(Bytes::from(content))
+        })))
+    }
+}
+
 impl<'a> From<ImageStream<'a>> for reqwest::Body {
     fn from(stream: ImageStream<'a>) -> Self {
         // TODO: avoid unsafe cast, maybe some lifetimes expansion.
@@ -118,21 +450,68 @@ impl<'a> From<ImageStream<'a>> for reqwest::Body {
 #[derive(Debug, Clone, sqlx::Type)]
 #[sqlx(type_name = "static_pool_config")]
 pub struct StoredStaticPoolConfig {
+    /// Deadline of the pool, zero until the pool is opened.
     pub launch_ts: i64,
+    /// Absent for projects, created before pools were opened sequentially.
+    pub launch_period: Option<i64>,
+    /// If absent, pool is limited by `sols_to_graduate` of instructions config.
+    pub max_lamports: Option<Balance>,
+    /// Set once the pool is graduated to open the next one.
+    pub collected_lamports: Option<Balance>,
 }
 
 #[derive(Debug, Clone, sqlx::Type)]
 #[sqlx(type_name = "dev_purchase")]
 pub struct StoredDevPurchase {
     pub amount: Balance,
+    /// Vesting cliff, zero for immediate delivery.
     pub lock_period: i64,
+    /// Present for linear vesting only.
+    pub vesting_period: Option<i64>,
+    pub vesting_frequency: Option<i64>,
+    /// Percent of the linear vesting, unlocked at the cliff.
+    pub cliff_pct: Option<i16>,
+}
+
+impl StoredDevPurchase {
+    pub fn delivery(&self) -> DeliveryMethod {
+        let cliff_secs = self.lock_period as u64;
+        match (self.vesting_period, self.vesting_frequency) {
+            (Some(period), Some(frequency)) => DeliveryMethod::VestedLinear {
+                cliff_secs,
+                cliff_pct: self.cliff_pct.unwrap_or_default() as u8,
+                period_secs: period as u64,
+                frequency_secs: frequency as u64,
+            },
+            _ if cliff_secs == 0 => DeliveryMethod::Immediate,
+            _ => DeliveryMethod::VestedCliff { cliff_secs },
+        }
+    }
 }
 
 impl From<DevPurchase> for StoredDevPurchase {
     fn from(purchase: DevPurchase) -> Self {
+        let (lock_period, vesting_period, vesting_frequency, cliff_pct) = match purchase.delivery {
+            DeliveryMethod::Immediate => (0, None, None, None),
+            DeliveryMethod::VestedLinear {
+                cliff_secs,
+                cliff_pct,
+                period_secs,
+                frequency_secs,
+            } => (
+                cliff_secs as i64,
+                Some(period_secs as i64),
+                Some(frequency_secs as i64),
+                Some(cliff_pct.into()),
+            ),
+            DeliveryMethod::VestedCliff { cliff_secs } => (cliff_secs as i64, None, None, None),
+        };
         Self {
             amount: purchase.value.into(),
-            lock_period: purchase.lock.as_secs() as i64,
+            lock_period,
+            vesting_period,
+            vesting_frequency,
+            cliff_pct,
         }
     }
 }
@@ -142,8 +521,8 @@ impl TryFrom<StoredDevPurchase> for DevPurchase {
 
     fn try_from(purchase: StoredDevPurchase) -> Result<Self, Self::Error> {
         Ok(Self {
+            delivery: purchase.delivery(),
             value: purchase.amount.try_into()?,
-            lock: DevLockPeriod::from_secs(purchase.lock_period as u64),
         })
     }
 }
@@ -151,18 +530,28 @@ impl TryFrom<StoredDevPurchase> for DevPurchase {
 #[derive(Debug, Clone, sqlx::Type)]
 #[sqlx(type_name = "deploy_schema")]
 pub struct StoredDeploySchema {
-    pub static_pool: Option<StoredStaticPoolConfig>,
+    /// Ordered by activation.
+    pub static_pools: Vec<StoredStaticPoolConfig>,
     pub curve_pool: CurveVariant,
     pub dev_purchase: Option<StoredDevPurchase>,
+    pub token_decimals: Option<i16>,
+    /// Fixed on creation, so changed config doesn't move projects, already launched.
+    pub graduation_target: GraduationTarget,
 }
 
 impl StoredDeploySchema {
+    pub fn has_static_pool(&self) -> bool {
+        !self.static_pools.is_empty()
+    }
+
     pub fn to_project_schema(&self) -> ProjectSchema {
         ProjectSchema {
-            use_static_pool: self.static_pool.is_some(),
+            // bounded by `MAX_STATIC_POOLS` on creation
+            static_pool_count: self.static_pools.len() as u8,
             curve_pool: match self.curve_pool {
                 CurveVariant::Moonzip => CurvePoolVariant::Moonzip,
                 CurveVariant::Pumpfun => CurvePoolVariant::Pumpfun,
+                CurveVariant::Meteora => CurvePoolVariant::Meteora,
             },
             dev_purchase: None,
         }
@@ -170,13 +559,38 @@ impl StoredDeploySchema {
 }
 
 #[derive(
-    Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, ToSchema, PartialEq, PartialOrd,
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    sqlx::Type,
+    ToSchema,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
 )]
 #[serde(rename_all = "camelCase")]
 #[sqlx(type_name = "curve_variant")]
 pub enum CurveVariant {
     Moonzip,
     Pumpfun,
+    /// Token is launched right into the Meteora Dynamic AMM pool, there is no bonding curve.
+    Meteora,
+}
+
+/// DEX, the closed moonzip curve pool is deployed to.
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, Default, sqlx::Type, ToSchema, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(type_name = "graduation_target")]
+pub enum GraduationTarget {
+    #[default]
+    Raydium,
+    Meteora,
+    Orca,
 }
 
 #[derive(Debug, Clone, sqlx::Type)]
@@ -192,30 +606,61 @@ pub struct PumpfunCurveState {
     pub virtual_token_reserves: Balance,
 }
 
+/// Moonzip curve pool as of its last trade.
+#[derive(Debug, Clone, sqlx::Type)]
+#[sqlx(type_name = "curved_pool_state")]
+pub struct CurvedPoolState {
+    pub virtual_sol_reserves: Balance,
+    pub virtual_token_reserves: Balance,
+    pub real_sol_reserves: Balance,
+    pub real_token_reserves: Balance,
+}
+
+impl CurvedPoolState {
+    /// Reserves are taken from the chain, everything else stays as configured on creation.
+    pub fn apply_to(&self, initial: CurveState) -> anyhow::Result<CurveState> {
+        let real_token_reserves = u64::try_from(self.real_token_reserves.clone())?;
+        Ok(CurveState {
+            virtual_sol_reserves: self.virtual_sol_reserves.clone().try_into()?,
+            virtual_token_reserves: self.virtual_token_reserves.clone().try_into()?,
+            real_sol_reserves: self.real_sol_reserves.clone().try_into()?,
+            real_token_reserves,
+            tokens_sold: initial
+                .real_token_reserves
+                .saturating_sub(real_token_reserves),
+            ..initial
+        })
+    }
+}
+
 #[derive(sqlx::FromRow, Clone)]
 pub struct FullProjectState {
     #[sqlx(flatten)]
     pub project: StoredProject,
     pub static_pool_state: Option<StaticPoolState>,
     pub pumpfun_curve_state: Option<PumpfunCurveState>,
+    /// Absent until the first trade on the moonzip curve pool.
+    pub curved_pool_state: Option<CurvedPoolState>,
 }
 
 impl FullProjectState {
-    pub const QUERY_BODY: &str = r#"
+    const QUERY_BODY: &str = r#"
             SELECT
                 project.id AS id,
                 project.owner AS owner,
                 project.deploy_schema AS deploy_schema,
                 project.stage AS stage,
-                project.static_pool_pubkey AS static_pool_pubkey,
+                project.static_pool_pubkeys AS static_pool_pubkeys,
                 project.curve_pool_keypair AS curve_pool_keypair,
                 project.dev_lock_keypair AS dev_lock_keypair,
                 project.created_at AS created_at,
                 static_pool_chain_state.state AS static_pool_state,
-                pumpfun_chain_state.state AS pumpfun_curve_state
-            FROM project
+                pumpfun_chain_state.state AS pumpfun_curve_state,
+                curved_pool_chain_state.state AS curved_pool_state
+            FROM (SELECT * FROM project WHERE archived_at IS NULL) AS project
             LEFT JOIN static_pool_chain_state ON project.id = static_pool_chain_state.project_id
             LEFT JOIN pumpfun_chain_state ON pumpfun_chain_state.mint = kp_to_pubkey(project.curve_pool_keypair)
+            LEFT JOIN curved_pool_chain_state ON curved_pool_chain_state.mint = kp_to_pubkey(project.curve_pool_keypair)
     "#;
 
     pub fn only_project(project: StoredProject) -> Self {
@@ -223,37 +668,247 @@ impl FullProjectState {
             project,
             static_pool_state: None,
             pumpfun_curve_state: None,
+            curved_pool_state: None,
         }
     }
 
+    pub fn query_builder() -> FullProjectStateQuery {
+        FullProjectStateQuery::default()
+    }
+
     pub async fn query<'c, E: sqlx::Executor<'c, Database = DB>>(
         executor: E,
         project_id: &ProjectId,
     ) -> anyhow::Result<Self> {
-        Ok(sqlx::query_as(concatcp!(
-            FullProjectState::QUERY_BODY,
-            "WHERE project.id = $1"
-        ))
-        .bind(project_id)
-        .fetch_one(executor)
-        .await?)
+        Self::query_builder()
+            .where_id(project_id)
+            .fetch_one(executor)
+            .await
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortDir {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortDir {
+    fn as_sql(self) -> &'static str {
+        match self {
+            SortDir::Asc => "ASC",
+            SortDir::Desc => "DESC",
+        }
+    }
+
+    fn cursor_cmp(self) -> &'static str {
+        match self {
+            SortDir::Asc => ">",
+            SortDir::Desc => "<",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    Stages(Vec<Stage>),
+    Owner(StoredPubkey),
+    Id(ProjectId),
+    ValidImage,
+}
+
+/// Selection of [`FullProjectState`] with arbitrary filters and keyset pagination.
+/// All values are passed as bind arguments, never interpolated into the query.
+#[derive(Debug, Clone, Default)]
+pub struct FullProjectStateQuery {
+    conditions: Vec<Condition>,
+    order: Option<SortDir>,
+    cursor: Option<(DateTime<TZ>, ProjectId)>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+impl FullProjectStateQuery {
+    pub fn where_stage(mut self, stages: &[Stage]) -> Self {
+        self.conditions.push(Condition::Stages(stages.to_vec()));
+        self
+    }
+
+    pub fn where_owner(mut self, owner: &Pubkey) -> Self {
+        self.conditions.push(Condition::Owner((*owner).into()));
+        self
+    }
+
+    pub fn where_id(mut self, id: &ProjectId) -> Self {
+        self.conditions.push(Condition::Id(*id));
+        self
+    }
+
+    /// Skips projects, which image is known to be unconvertible.
+    pub fn where_image_valid(mut self) -> Self {
+        self.conditions.push(Condition::ValidImage);
+        self
+    }
+
+    pub fn order_by_created_at(mut self, dir: SortDir) -> Self {
+        self.order = Some(dir);
+        self
+    }
+
+    /// Skips everything up to and including the given position in `(created_at, id)` order.
+    /// Implies ordering by creation time, ascending unless specified otherwise.
+    pub fn cursor(mut self, after_ts: DateTime<TZ>, after_id: ProjectId) -> Self {
+        self.cursor = Some((after_ts, after_id));
+        self
+    }
+
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: i64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Placeholders are numbered in the same order, as [`Self::bind_args`] binds values.
+    pub fn sql(&self) -> String {
+        let mut sql = String::from(FullProjectState::QUERY_BODY);
+        let mut arg = 0;
+        let mut next_arg = || {
+            arg += 1;
+            arg
+        };
+
+        let mut conditions = self
+            .conditions
+            .iter()
+            .map(|condition| match condition {
+                Condition::Stages(_) => {
+                    format!("project.stage = ANY(${}::project_stage[])", next_arg())
+                }
+                Condition::Owner(_) => format!("project.owner = ${}", next_arg()),
+                Condition::Id(_) => format!("project.id = ${}", next_arg()),
+                Condition::ValidImage => "NOT EXISTS (SELECT 1 FROM token_image \
+                     WHERE token_image.project_id = project.id \
+                     AND token_image.invalid_reason IS NOT NULL)"
+                    .to_owned(),
+            })
+            .collect::<Vec<_>>();
+        let order = self.order_dir();
+        if self.cursor.is_some() {
+            conditions.push(format!(
+                "(project.created_at, project.id) {} (${}, ${})",
+                order.unwrap_or_default().cursor_cmp(),
+                next_arg(),
+                next_arg()
+            ));
+        }
+
+        if !conditions.is_empty() {
+            write!(sql, " WHERE {}", conditions.join(" AND ")).expect("invariant: write to string");
+        }
+        if let Some(order) = order {
+            let dir = order.as_sql();
+            write!(sql, " ORDER BY project.created_at {dir}, project.id {dir}")
+                .expect("invariant: write to string");
+        }
+        if self.limit.is_some() {
+            write!(sql, " LIMIT ${}", next_arg()).expect("invariant: write to string");
+        }
+        if self.offset.is_some() {
+            write!(sql, " OFFSET ${}", next_arg()).expect("invariant: write to string");
+        }
+        sql
+    }
+
+    pub async fn fetch_all<'c, E: sqlx::Executor<'c, Database = DB>>(
+        &self,
+        executor: E,
+    ) -> anyhow::Result<Vec<FullProjectState>> {
+        let sql = self.sql();
+        Ok(self
+            .bind_args(sqlx::query_as(&sql))
+            .fetch_all(executor)
+            .await?)
+    }
+
+    pub async fn fetch_optional<'c, E: sqlx::Executor<'c, Database = DB>>(
+        &self,
+        executor: E,
+    ) -> anyhow::Result<Option<FullProjectState>> {
+        let sql = self.sql();
+        Ok(self
+            .bind_args(sqlx::query_as(&sql))
+            .fetch_optional(executor)
+            .await?)
+    }
+
+    pub async fn fetch_one<'c, E: sqlx::Executor<'c, Database = DB>>(
+        &self,
+        executor: E,
+    ) -> anyhow::Result<FullProjectState> {
+        let sql = self.sql();
+        Ok(self
+            .bind_args(sqlx::query_as(&sql))
+            .fetch_one(executor)
+            .await?)
+    }
+
+    fn order_dir(&self) -> Option<SortDir> {
+        match (self.order, self.cursor) {
+            (Some(order), _) => Some(order),
+            (None, Some(_)) => Some(SortDir::Asc),
+            (None, None) => None,
+        }
+    }
+
+    fn bind_args<'q>(
+        &self,
+        mut query: QueryAs<'q, DB, FullProjectState, PgArguments>,
+    ) -> QueryAs<'q, DB, FullProjectState, PgArguments> {
+        for condition in &self.conditions {
+            query = match condition {
+                Condition::Stages(stages) => query.bind(stages.clone()),
+                Condition::Owner(owner) => query.bind(owner.clone()),
+                Condition::Id(id) => query.bind(*id),
+                Condition::ValidImage => query,
+            };
+        }
+        if let Some((after_ts, after_id)) = self.cursor {
+            query = query.bind(after_ts).bind(after_id);
+        }
+        if let Some(limit) = self.limit {
+            query = query.bind(limit);
+        }
+        if let Some(offset) = self.offset {
+            query = query.bind(offset);
+        }
+        query
     }
 }
 
 impl FullProjectState {
+    /// Pool closes by its deadline or once it collects its own limit,
+    /// chain has the final say, e.g. the limit may be followed by a grace period.
     pub fn should_close_static_pool(&self) -> bool {
         let pool_schema = self
             .project
-            .deploy_schema
-            .static_pool
-            .as_ref()
+            .current_static_pool()
             .expect("no static pool in project deploy schema");
 
         let current_ts = TZ::now().timestamp();
-
-        let mut is_closed = false;
-        is_closed = is_closed || (current_ts >= pool_schema.launch_ts);
-        is_closed
+        if current_ts >= pool_schema.launch_ts {
+            return true;
+        }
+        let max_lamports = self.project.static_pool_max_lamports().ok().flatten();
+        let collected = self
+            .static_pool_state
+            .as_ref()
+            .and_then(|state| u64::try_from(state.collected_lamports.clone()).ok());
+        matches!((collected, max_lamports), (Some(collected), Some(max)) if collected >= max)
     }
 }
 
@@ -262,12 +917,164 @@ pub mod tests {
     use super::*;
     use std::path::Path;
 
-    impl<'a> ImageStream<'a> {
+    fn clauses(query: &FullProjectStateQuery) -> String {
+        let sql = query.sql();
+        sql.strip_prefix(FullProjectState::QUERY_BODY)
+            .expect("query body prefix")
+            .trim()
+            .to_owned()
+    }
+
+    #[test]
+    fn test_stores_delivery_methods() {
+        for delivery in [
+            DeliveryMethod::Immediate,
+            DeliveryMethod::VestedCliff { cliff_secs: 60 },
+            DeliveryMethod::VestedLinear {
+                cliff_secs: 0,
+                cliff_pct: 0,
+                period_secs: 600,
+                frequency_secs: 60,
+            },
+            DeliveryMethod::VestedLinear {
+                cliff_secs: 60,
+                cliff_pct: 25,
+                period_secs: 600,
+                frequency_secs: 60,
+            },
+        ] {
+            let stored = StoredDevPurchase::from(DevPurchase {
+                value: 1,
+                delivery: delivery.clone(),
+            });
+            assert_eq!(stored.delivery(), delivery);
+        }
+    }
+
+    #[test]
+    fn test_maps_stages_to_static_pools() {
+        assert_eq!(Stage::OnStaticPool.static_pool_index(2), Some(0));
+        assert_eq!(Stage::SecondStaticPoolClosed.static_pool_index(2), Some(1));
+        assert_eq!(Stage::OnCurvePool.static_pool_index(2), Some(1));
+        assert_eq!(Stage::OnCurvePool.static_pool_index(1), Some(0));
+        assert_eq!(Stage::Graduated.static_pool_index(0), None);
+        assert_eq!(Stage::StaticPoolFailed.static_pool_index(1), Some(0));
+        assert!(!Stage::StaticPoolFailed.is_static_pool());
+    }
+
+    #[test]
+    fn test_applies_live_curve_state() {
+        let initial = CurveState::from_cfg(&Default::default());
+        let live = CurvedPoolState {
+            virtual_sol_reserves: Balance::from(initial.virtual_sol_reserves + 1_000),
+            virtual_token_reserves: Balance::from(initial.virtual_token_reserves - 500),
+            real_sol_reserves: Balance::from(1_000),
+            real_token_reserves: Balance::from(initial.real_token_reserves - 500),
+        };
+        let curve = live.apply_to(initial).unwrap();
+        assert_eq!(
+            curve.virtual_sol_reserves,
+            initial.virtual_sol_reserves + 1_000
+        );
+        assert_eq!(curve.real_sol_reserves, 1_000);
+        assert_eq!(curve.tokens_sold, 500);
+        assert_eq!(curve.total_token_supply, initial.total_token_supply);
+        assert_eq!(curve.kind, initial.kind);
+    }
+
+    #[test]
+    fn test_selects_everything_without_clauses() {
+        assert_eq!(clauses(&FullProjectState::query_builder()), "");
+    }
+
+    #[test]
+    fn test_filters_by_stage() {
+        let query = FullProjectState::query_builder().where_stage(&[Stage::Confirmed]);
+        assert_eq!(
+            clauses(&query),
+            "WHERE project.stage = ANY($1::project_stage[])"
+        );
+    }
+
+    #[test]
+    fn test_filters_by_owner() {
+        let query = FullProjectState::query_builder().where_owner(&Pubkey::new_unique());
+        assert_eq!(clauses(&query), "WHERE project.owner = $1");
+    }
+
+    #[test]
+    fn test_filters_by_id() {
+        let query = FullProjectState::query_builder().where_id(&Uuid::new_v4());
+        assert_eq!(clauses(&query), "WHERE project.id = $1");
+    }
+
+    #[test]
+    fn test_filters_by_image_validity_without_args() {
+        let query = FullProjectState::query_builder()
+            .where_image_valid()
+            .where_id(&Uuid::new_v4());
+        assert_eq!(
+            clauses(&query),
+            "WHERE NOT EXISTS (SELECT 1 FROM token_image \
+             WHERE token_image.project_id = project.id \
+             AND token_image.invalid_reason IS NOT NULL) AND project.id = $1"
+        );
+    }
+
+    #[test]
+    fn test_orders_by_created_at() {
+        let query = FullProjectState::query_builder().order_by_created_at(SortDir::Desc);
+        assert_eq!(
+            clauses(&query),
+            "ORDER BY project.created_at DESC, project.id DESC"
+        );
+    }
+
+    #[test]
+    fn test_limits_and_offsets() {
+        let query = FullProjectState::query_builder().limit(10);
+        assert_eq!(clauses(&query), "LIMIT $1");
+
+        let query = query.offset(20);
+        assert_eq!(clauses(&query), "LIMIT $1 OFFSET $2");
+    }
+
+    #[test]
+    fn test_paginates_by_cursor_in_order_direction() {
+        let query = FullProjectState::query_builder().cursor(TZ::now(), Uuid::new_v4());
+        assert_eq!(
+            clauses(&query),
+            "WHERE (project.created_at, project.id) > ($1, $2) \
+             ORDER BY project.created_at ASC, project.id ASC"
+        );
+
+        let query = query.order_by_created_at(SortDir::Desc);
+        assert_eq!(
+            clauses(&query),
+            "WHERE (project.created_at, project.id) < ($1, $2) \
+             ORDER BY project.created_at DESC, project.id DESC"
+        );
+    }
+
+    #[test]
+    fn test_numbers_placeholders_across_clauses() {
+        let query = FullProjectState::query_builder()
+            .where_stage(&[Stage::Confirmed, Stage::OnStaticPool])
+            .where_owner(&Pubkey::new_unique())
+            .cursor(TZ::now(), Uuid::new_v4())
+            .limit(50)
+            .offset(100);
+        assert_eq!(
+            clauses(&query),
+            "WHERE project.stage = ANY($1::project_stage[]) AND project.owner = $2 \
+             AND (project.created_at, project.id) > ($3, $4) \
+             ORDER BY project.created_at ASC, project.id ASC LIMIT $5 OFFSET $6"
+        );
+    }
+
+    impl ImageStream<'_> {
         pub fn from_file(path: &Path) -> anyhow::Result<Self> {
-            let image = std::fs::read(path)?;
-            Ok(Self(Box::pin(futures_util::stream::once(async move {
-                sqlx::Result::Ok(Bytes::from(image))
-            }))))
+            Ok(Self::from_bytes(std::fs::read(path)?))
         }
     }
 }
@@ -0,0 +1,215 @@
+use super::{
+    misc::{Balance, StoredKeypair, StoredPubkey},
+    project::{ProjectId, Stage},
+    DB,
+};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use services_common::TZ;
+use utoipa::ToSchema;
+
+/// Lifetime trade totals of the project, unlike windowed `project_stats_24h`.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoredProjectStats {
+    pub total_volume_lamports: Balance,
+    pub buy_count: i32,
+    pub sell_count: i32,
+    pub holder_count: i32,
+    pub last_price_lamports_per_token: Option<f64>,
+    pub updated_at: DateTime<TZ>,
+}
+
+impl StoredProjectStats {
+    /// Trade is identified by its transaction and its order among trades of the transaction,
+    /// so replayed transactions leave the totals as is.
+    pub async fn record_trade<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+        tx_signature: &str,
+        trade_index: i32,
+        is_buy: bool,
+        lamports: u64,
+        price_lamports_per_token: Option<f64>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "
+                WITH counted AS (
+                    INSERT INTO project_stats_trade (tx_signature, trade_index, project_id)
+                    VALUES ($2, $3, $1)
+                    ON CONFLICT DO NOTHING
+                    RETURNING project_id
+                )
+                INSERT INTO project_stats AS stats (
+                    project_id,
+                    total_volume_lamports,
+                    buy_count,
+                    sell_count,
+                    last_price_lamports_per_token,
+                    updated_at
+                )
+                SELECT project_id, $4, $5::INT, (NOT $5)::INT, $6, NOW() FROM counted
+                ON CONFLICT (project_id) DO UPDATE SET
+                    total_volume_lamports = stats.total_volume_lamports + excluded.total_volume_lamports,
+                    buy_count = stats.buy_count + excluded.buy_count,
+                    sell_count = stats.sell_count + excluded.sell_count,
+                    last_price_lamports_per_token = COALESCE(
+                        excluded.last_price_lamports_per_token,
+                        stats.last_price_lamports_per_token
+                    ),
+                    updated_at = excluded.updated_at
+            ",
+        )
+        .bind(project_id)
+        .bind(tx_signature)
+        .bind(trade_index)
+        .bind(Balance::from(lamports))
+        .bind(is_buy)
+        .bind(price_lamports_per_token)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Holder count is approximate: static pool participants are counted by their first buy,
+    /// curve holders by tracked balances, the larger of both is kept.
+    /// Returns number of changed projects.
+    pub async fn refresh_holder_counts<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+    ) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            "
+                UPDATE project_stats AS stats SET holder_count = counted.holders
+                FROM (
+                    SELECT
+                        project_stats.project_id,
+                        GREATEST(
+                            (
+                                SELECT COUNT(*) FROM token_holders
+                                WHERE token_holders.project_id = project_stats.project_id
+                                    AND token_holders.balance > 0
+                            ),
+                            (
+                                SELECT COUNT(*) FROM static_pool_wallet_record
+                                WHERE static_pool_wallet_record.project_id = project_stats.project_id
+                            )
+                        )::INT AS holders
+                    FROM project_stats
+                ) AS counted
+                WHERE stats.project_id = counted.project_id
+                    AND stats.holder_count != counted.holders
+            ",
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+    ) -> anyhow::Result<Option<StoredProjectStats>> {
+        Ok(sqlx::query_as(
+            "
+                SELECT
+                    total_volume_lamports,
+                    buy_count,
+                    sell_count,
+                    holder_count,
+                    last_price_lamports_per_token,
+                    updated_at
+                FROM project_stats WHERE project_id = $1
+            ",
+        )
+        .bind(project_id)
+        .fetch_optional(executor)
+        .await?)
+    }
+}
+
+/// Ranking of the leaderboard, the largest value comes first.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardSortBy {
+    /// Refreshed periodically, so could lag behind recent trades.
+    #[default]
+    Volume24h,
+    TotalVolume,
+    HolderCount,
+    /// Newest projects first.
+    LaunchDate,
+}
+
+impl LeaderboardSortBy {
+    fn order_column(self) -> &'static str {
+        match self {
+            LeaderboardSortBy::Volume24h => "stats_24h.volume_lamports",
+            LeaderboardSortBy::TotalVolume => "stats.total_volume_lamports",
+            LeaderboardSortBy::HolderCount => "stats.holder_count",
+            LeaderboardSortBy::LaunchDate => "project.created_at",
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoredLeaderboardEntry {
+    pub id: ProjectId,
+    pub owner: StoredPubkey,
+    pub name: String,
+    pub symbol: String,
+    pub stage: Stage,
+    pub curve_pool_keypair: Option<StoredKeypair>,
+    pub created_at: DateTime<TZ>,
+    pub volume_24h_lamports: Balance,
+    pub total_volume_lamports: Balance,
+    pub holder_count: i32,
+    pub last_price_lamports_per_token: Option<f64>,
+}
+
+impl StoredLeaderboardEntry {
+    pub async fn query<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        sort_by: LeaderboardSortBy,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<StoredLeaderboardEntry>> {
+        // projects without trades are ranked by launch date only,
+        // so the rest are served by indexes of the ordered stats
+        let (stats_join, stats_24h_join) = match sort_by {
+            LeaderboardSortBy::Volume24h => ("LEFT JOIN", "JOIN"),
+            LeaderboardSortBy::TotalVolume | LeaderboardSortBy::HolderCount => {
+                ("JOIN", "LEFT JOIN")
+            }
+            LeaderboardSortBy::LaunchDate => ("LEFT JOIN", "LEFT JOIN"),
+        };
+        let order = sort_by.order_column();
+        let sql = format!(
+            "
+                SELECT
+                    project.id,
+                    project.owner,
+                    token_meta.name,
+                    token_meta.symbol,
+                    project.stage,
+                    project.curve_pool_keypair,
+                    project.created_at,
+                    COALESCE(stats_24h.volume_lamports, 0)::balance AS volume_24h_lamports,
+                    COALESCE(stats.total_volume_lamports, 0)::balance AS total_volume_lamports,
+                    COALESCE(stats.holder_count, 0) AS holder_count,
+                    stats.last_price_lamports_per_token
+                FROM project
+                JOIN token_meta ON token_meta.project_id = project.id
+                {stats_join} project_stats AS stats ON stats.project_id = project.id
+                {stats_24h_join} project_stats_24h AS stats_24h ON stats_24h.project_id = project.id
+                WHERE project.archived_at IS NULL
+                    AND project.stage NOT IN ('Created', 'Confirmed')
+                ORDER BY {order} DESC, project.id
+                LIMIT $1 OFFSET $2
+            "
+        );
+        Ok(sqlx::query_as(&sql)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(executor)
+            .await?)
+    }
+}
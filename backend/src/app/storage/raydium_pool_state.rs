@@ -0,0 +1,81 @@
+use super::{
+    misc::{Balance, StoredPubkey},
+    project::ProjectId,
+    DB,
+};
+use chrono::DateTime;
+use services_common::TZ;
+use solana_sdk::pubkey::Pubkey;
+
+/// Live state of the raydium pool of graduated project, synced periodically by the migrator.
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct StoredRaydiumPoolState {
+    pub project_id: ProjectId,
+    pub amm_pool: StoredPubkey,
+    pub last_price_lamports_per_token: Option<f64>,
+    pub last_updated_slot: i64,
+    pub liquidity_lamports: Balance,
+    pub updated_at: DateTime<TZ>,
+}
+
+impl StoredRaydiumPoolState {
+    pub async fn upsert<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: &ProjectId,
+        amm_pool: &Pubkey,
+        price_lamports_per_token: Option<f64>,
+        slot: u64,
+        liquidity_lamports: u64,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "
+                INSERT INTO raydium_pool_state
+                    (project_id, amm_pool, last_price_lamports_per_token, last_updated_slot, liquidity_lamports, updated_at)
+                VALUES ($1, $2, $3, $4, $5, NOW())
+                ON CONFLICT (project_id) DO UPDATE SET
+                    amm_pool = excluded.amm_pool,
+                    last_price_lamports_per_token = excluded.last_price_lamports_per_token,
+                    last_updated_slot = excluded.last_updated_slot,
+                    liquidity_lamports = excluded.liquidity_lamports,
+                    updated_at = excluded.updated_at
+                WHERE raydium_pool_state.last_updated_slot <= excluded.last_updated_slot
+            ",
+        )
+        .bind(project_id)
+        .bind(StoredPubkey::from(*amm_pool))
+        .bind(price_lamports_per_token)
+        .bind(i64::try_from(slot)?)
+        .bind(Balance::from(liquidity_lamports))
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: &ProjectId,
+    ) -> anyhow::Result<Option<StoredRaydiumPoolState>> {
+        Ok(
+            sqlx::query_as("SELECT * FROM raydium_pool_state WHERE project_id = $1")
+                .bind(project_id)
+                .fetch_optional(executor)
+                .await?,
+        )
+    }
+
+    /// Last prices of the projects, projects without synced pools are omitted.
+    pub async fn prices<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_ids: &[ProjectId],
+    ) -> anyhow::Result<Vec<(ProjectId, f64)>> {
+        Ok(sqlx::query_as(
+            "
+                SELECT project_id, last_price_lamports_per_token FROM raydium_pool_state
+                WHERE project_id = ANY($1) AND last_price_lamports_per_token IS NOT NULL
+            ",
+        )
+        .bind(project_ids)
+        .fetch_all(executor)
+        .await?)
+    }
+}
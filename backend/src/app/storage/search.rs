@@ -0,0 +1,342 @@
+use super::{
+    misc::StoredPubkey,
+    project::{CurveVariant, ProjectId, Stage},
+    DB,
+};
+use crate::app::exposed::StoredProjectInfo;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use services_common::TZ;
+use utoipa::ToSchema;
+
+/// Control characters, `ts_headline` marks matched words with instead of HTML tags,
+/// so user text could be escaped before tags are added, see [`escape_highlight`].
+const HIGHLIGHT_START: char = '\u{2}';
+const HIGHLIGHT_STOP: char = '\u{3}';
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// Every word must match, `AND` / `OR` between words are respected.
+    #[default]
+    Simple,
+    /// Words must follow each other in the given order.
+    Phrase,
+    /// Like simple, but words may be prefixes, suitable for search-as-you-type.
+    Prefix,
+}
+
+impl SearchMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchMode::Simple => "simple",
+            SearchMode::Phrase => "phrase",
+            SearchMode::Prefix => "prefix",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSort {
+    #[default]
+    Relevance,
+    CreatedAt,
+    /// By SOL, collected in project pools: trades are not tracked separately.
+    Volume,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Newest,
+    Oldest,
+}
+
+impl SortOrder {
+    fn as_sql(self) -> &'static str {
+        match self {
+            SortOrder::Newest => "DESC",
+            SortOrder::Oldest => "ASC",
+        }
+    }
+
+    fn cursor_cmp(self) -> &'static str {
+        match self {
+            SortOrder::Newest => "<",
+            SortOrder::Oldest => ">",
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct StoredSearchHit {
+    #[sqlx(flatten)]
+    pub project: StoredProjectInfo,
+    /// Raw user text, see [`escape_highlight`].
+    pub highlight: Option<String>,
+}
+
+/// Escapes the headline as HTML text, then wraps matched words into `<b>` tags.
+pub fn escape_highlight(headline: &str) -> String {
+    let mut html = String::with_capacity(headline.len());
+    for c in headline.chars() {
+        match c {
+            HIGHLIGHT_START => html.push_str("<b>"),
+            HIGHLIGHT_STOP => html.push_str("</b>"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '&' => html.push_str("&amp;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#x27;"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+/// Searches exposed projects by their token meta, see [`SearchMode`] for the query syntax.
+pub async fn search_projects<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    query: &str,
+    mode: SearchMode,
+    sort: SearchSort,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<StoredSearchHit>> {
+    let (tsquery, query) = match mode {
+        SearchMode::Phrase => ("phraseto_tsquery('english', $1)", Some(query.to_owned())),
+        SearchMode::Simple => ("to_tsquery('english', $1)", tsquery_terms(query, false)),
+        SearchMode::Prefix => ("to_tsquery('english', $1)", tsquery_terms(query, true)),
+    };
+    let Some(query) = query else {
+        return Ok(vec![]);
+    };
+    let order = match sort {
+        // cover density with normalization into 0..1 range
+        SearchSort::Relevance => "ts_rank_cd(token_meta.search_vector, search.query, 32) DESC",
+        SearchSort::CreatedAt => "project.created_at DESC",
+        SearchSort::Volume => {
+            "COALESCE((static_pool_chain_state.state).collected_lamports, 0)
+                + COALESCE((pumpfun_chain_state.state).virtual_sol_reserves, 0) DESC"
+        }
+    };
+
+    let sql = format!(
+        "
+            SELECT
+                project.id,
+                project.owner,
+                token_meta.name,
+                token_meta.description,
+                project.stage,
+                project.static_pool_pubkeys,
+                project.curve_pool_keypair,
+                project.dev_lock_keypair,
+                project.created_at,
+                project.holder_count_snapshot,
+                COALESCE(stats.volume_lamports, 0)::balance AS volume_24h_lamports,
+                COALESCE(stats.trade_count, 0) AS trade_count_24h,
+                project.static_pool_extended_at,
+                ts_headline(
+                    'english',
+                    -- markers are stripped from user text, so only ts_headline places them
+                    translate(token_meta.name || ' ' || token_meta.description, chr(2) || chr(3), ''),
+                    search.query,
+                    'MaxWords=15, MinWords=5, StartSel=' || chr(2) || ', StopSel=' || chr(3)
+                ) AS highlight
+            FROM token_meta
+            JOIN project ON project.id = token_meta.project_id
+            LEFT JOIN static_pool_chain_state ON project.id = static_pool_chain_state.project_id
+            LEFT JOIN pumpfun_chain_state ON pumpfun_chain_state.mint = kp_to_pubkey(project.curve_pool_keypair)
+            LEFT JOIN project_stats_24h AS stats ON stats.project_id = project.id
+            CROSS JOIN {tsquery} AS search(query)
+            WHERE project.archived_at IS NULL
+                AND project.stage NOT IN ('Created', 'Confirmed')
+                AND token_meta.search_vector @@ search.query
+            ORDER BY {order}, project.id
+            LIMIT $2 OFFSET $3
+        "
+    );
+    Ok(sqlx::query_as(&sql)
+        .bind(query)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(executor)
+        .await?)
+}
+
+/// Filters of [`list_projects`], unset ones match everything.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectListFilter {
+    pub stage: Option<Stage>,
+    pub curve_variant: Option<CurveVariant>,
+    pub owner: Option<StoredPubkey>,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid project list cursor")]
+pub struct InvalidListCursor;
+
+/// Position of the last listed project in `(created_at, id)` order, opaque for clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListCursor {
+    pub created_at: DateTime<TZ>,
+    pub id: ProjectId,
+}
+
+impl ListCursor {
+    pub fn encode(&self) -> String {
+        format!("{}_{}", self.created_at.timestamp_micros(), self.id)
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, InvalidListCursor> {
+        let (micros, id) = cursor.split_once('_').ok_or(InvalidListCursor)?;
+        let micros = micros.parse().map_err(|_| InvalidListCursor)?;
+        Ok(Self {
+            created_at: DateTime::from_timestamp_micros(micros).ok_or(InvalidListCursor)?,
+            id: id.parse().map_err(|_| InvalidListCursor)?,
+        })
+    }
+}
+
+/// Browses exposed projects by creation time.
+/// Keyset pagination is used if `cursor` is passed, `offset` is applied otherwise.
+pub async fn list_projects<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    filter: &ProjectListFilter,
+    sort: SortOrder,
+    cursor: Option<ListCursor>,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<StoredProjectInfo>> {
+    let dir = sort.as_sql();
+    let cmp = sort.cursor_cmp();
+    let sql = format!(
+        "
+            SELECT
+                project.id,
+                project.owner,
+                token_meta.name,
+                token_meta.description,
+                project.stage,
+                project.static_pool_pubkeys,
+                project.curve_pool_keypair,
+                project.dev_lock_keypair,
+                project.created_at,
+                project.holder_count_snapshot,
+                COALESCE(stats.volume_lamports, 0)::balance AS volume_24h_lamports,
+                COALESCE(stats.trade_count, 0) AS trade_count_24h,
+                project.static_pool_extended_at
+            FROM project
+            JOIN token_meta ON token_meta.project_id = project.id
+            LEFT JOIN project_stats_24h AS stats ON stats.project_id = project.id
+            WHERE project.archived_at IS NULL
+                AND project.stage NOT IN ('Created', 'Confirmed')
+                AND ($1::project_stage IS NULL OR project.stage = $1)
+                AND ($2::curve_variant IS NULL OR (project.deploy_schema).curve_pool = $2)
+                AND ($3::pubkey IS NULL OR project.owner = $3)
+                AND ($4::timestamptz IS NULL OR (project.created_at, project.id) {cmp} ($4, $5::uuid))
+            ORDER BY project.created_at {dir}, project.id {dir}
+            LIMIT $6 OFFSET $7
+        "
+    );
+    Ok(sqlx::query_as(&sql)
+        .bind(filter.stage)
+        .bind(filter.curve_variant)
+        .bind(filter.owner.clone())
+        .bind(cursor.map(|cursor| cursor.created_at))
+        .bind(cursor.map(|cursor| cursor.id))
+        .bind(limit)
+        .bind(if cursor.is_some() { 0 } else { offset })
+        .fetch_all(executor)
+        .await?)
+}
+
+/// Builds `to_tsquery` input from user query, `AND` is implied between words.
+/// Everything except letters and digits is dropped, so user can't break tsquery syntax.
+fn tsquery_terms(query: &str, prefix: bool) -> Option<String> {
+    let mut terms = String::new();
+    let mut operator = "&";
+    for word in query.split_whitespace() {
+        match word {
+            "AND" => operator = "&",
+            "OR" => operator = "|",
+            word => {
+                let term: String = word.chars().filter(|c| c.is_alphanumeric()).collect();
+                if term.is_empty() {
+                    continue;
+                }
+                if !terms.is_empty() {
+                    terms.push_str(&format!(" {operator} "));
+                }
+                terms.push_str(&term);
+                if prefix {
+                    terms.push_str(":*");
+                }
+                operator = "&";
+            }
+        }
+    }
+    (!terms.is_empty()).then_some(terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_joins_words_with_and_by_default() {
+        assert_eq!(
+            tsquery_terms("moon  token", false).as_deref(),
+            Some("moon & token")
+        );
+    }
+
+    #[test]
+    fn test_respects_boolean_operators() {
+        assert_eq!(
+            tsquery_terms("moon OR sun AND star", false).as_deref(),
+            Some("moon | sun & star")
+        );
+        assert_eq!(tsquery_terms("OR moon", false).as_deref(), Some("moon"));
+    }
+
+    #[test]
+    fn test_adds_prefix_marks() {
+        assert_eq!(
+            tsquery_terms("moo OR su", true).as_deref(),
+            Some("moo:* | su:*")
+        );
+    }
+
+    #[test]
+    fn test_strips_tsquery_syntax() {
+        assert_eq!(
+            tsquery_terms("moon:* & !(sun)", false).as_deref(),
+            Some("moon & sun")
+        );
+        assert_eq!(tsquery_terms("& | !", false), None);
+    }
+
+    #[test]
+    fn test_escapes_highlighted_text() {
+        assert_eq!(
+            escape_highlight("\u{2}moon\u{3} <script>alert('x' & \"y\")</script>"),
+            "<b>moon</b> &lt;script&gt;alert(&#x27;x&#x27; &amp; &quot;y&quot;)&lt;/script&gt;"
+        );
+        assert_eq!(escape_highlight("<b>moon</b>"), "&lt;b&gt;moon&lt;/b&gt;");
+    }
+
+    #[test]
+    fn test_round_trips_list_cursor() {
+        let cursor = ListCursor {
+            created_at: DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap(),
+            id: ProjectId::new_v4(),
+        };
+        assert_eq!(ListCursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(ListCursor::decode("1700000000").is_err());
+        assert!(ListCursor::decode("soon_later").is_err());
+    }
+}
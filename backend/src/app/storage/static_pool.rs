@@ -0,0 +1,86 @@
+use super::{
+    misc::{Balance, StoredPubkey},
+    project::ProjectId,
+    DB,
+};
+use chrono::DateTime;
+use services_common::TZ;
+
+/// Postgres channel, notified with project id once its static pool state is changed.
+pub const STATIC_POOL_PROGRESS_CHANNEL: &str = "static_pool_progress";
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct StaticPoolWalletRecord {
+    pub project_id: ProjectId,
+    pub wallet: StoredPubkey,
+    pub first_bought_at: DateTime<TZ>,
+}
+
+impl StaticPoolWalletRecord {
+    pub async fn record<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+        wallet: StoredPubkey,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO static_pool_wallet_record (project_id, wallet) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(project_id)
+        .bind(wallet)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct StoredStaticPoolProgress {
+    pub collected_lamports: Balance,
+    /// Limit of the last opened pool, which the chain state follows,
+    /// absent for pools created before it was configured per pool.
+    pub max_lamports: Option<Balance>,
+    pub participant_count: i64,
+}
+
+impl StoredStaticPoolProgress {
+    /// Returns `None` if the project has no static pool state yet.
+    pub async fn load<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+    ) -> anyhow::Result<Option<StoredStaticPoolProgress>> {
+        Ok(sqlx::query_as(
+            "
+                SELECT
+                    (static_pool_chain_state.state).collected_lamports AS collected_lamports,
+                    (
+                        (project.deploy_schema).static_pools[cardinality(project.static_pool_pubkeys)]
+                    ).max_lamports AS max_lamports,
+                    (
+                        SELECT COUNT(*) FROM static_pool_wallet_record
+                        WHERE static_pool_wallet_record.project_id = static_pool_chain_state.project_id
+                    ) AS participant_count
+                FROM static_pool_chain_state
+                JOIN project ON project.id = static_pool_chain_state.project_id
+                WHERE static_pool_chain_state.project_id = $1
+                    AND static_pool_chain_state.state IS NOT NULL
+                    AND project.archived_at IS NULL
+            ",
+        )
+        .bind(project_id)
+        .fetch_optional(executor)
+        .await?)
+    }
+}
+
+/// Notification is delivered to listeners only once the transaction is committed.
+pub async fn notify_progress<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    project_id: ProjectId,
+) -> anyhow::Result<()> {
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(STATIC_POOL_PROGRESS_CHANNEL)
+        .bind(project_id.to_string())
+        .execute(executor)
+        .await?;
+    Ok(())
+}
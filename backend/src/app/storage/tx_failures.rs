@@ -0,0 +1,57 @@
+use super::{project::ProjectId, DB};
+use chrono::DateTime;
+use services_common::TZ;
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct StoredTxFailure {
+    pub id: i64,
+    pub project_id: Option<ProjectId>,
+    pub tx_signature: String,
+    pub error: String,
+    pub logs: Vec<String>,
+    pub slot: Option<i64>,
+    pub failed_at: DateTime<TZ>,
+}
+
+impl StoredTxFailure {
+    pub async fn insert<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: Option<ProjectId>,
+        tx_signature: &str,
+        error: &str,
+        logs: &[String],
+        slot: Option<i64>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO transaction_failures (project_id, tx_signature, error, logs, slot) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(project_id)
+        .bind(tx_signature)
+        .bind(error)
+        .bind(logs)
+        .bind(slot)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Latest failures first, optionally only for the given project.
+    pub async fn list<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: Option<ProjectId>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<StoredTxFailure>> {
+        Ok(sqlx::query_as(
+            "
+                SELECT * FROM transaction_failures
+                WHERE $1::UUID IS NULL OR project_id = $1
+                ORDER BY failed_at DESC
+                LIMIT $2
+            ",
+        )
+        .bind(project_id)
+        .bind(limit)
+        .fetch_all(executor)
+        .await?)
+    }
+}
@@ -0,0 +1,29 @@
+use super::{misc::StoredPubkey, DB};
+use chrono::DateTime;
+use services_common::TZ;
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct StoredUpgradeProposal {
+    pub upgrade_hash: Vec<u8>,
+    pub proposer: StoredPubkey,
+    pub proposed_at: DateTime<TZ>,
+    pub effective_at: DateTime<TZ>,
+    pub executed_at: Option<DateTime<TZ>>,
+    pub tx_signature: String,
+    pub slot: i64,
+}
+
+impl StoredUpgradeProposal {
+    /// Latest proposals first.
+    pub async fn list<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        limit: i64,
+    ) -> anyhow::Result<Vec<StoredUpgradeProposal>> {
+        Ok(sqlx::query_as(
+            "SELECT * FROM program_upgrade_proposals ORDER BY proposed_at DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(executor)
+        .await?)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::app::storage::misc::StoredPubkey;
+use crate::app::storage::{misc::StoredPubkey, DB};
 use chrono::DateTime;
 use serde::Serialize;
 use services_common::TZ;
@@ -11,6 +11,35 @@ pub struct StoredUserInfo {
     pub image_url: Option<String>,
     pub nft_address: Option<StoredPubkey>,
     pub last_active: Option<i64>,
+    pub sns_name: Option<String>,
     pub created_at: Option<DateTime<TZ>>,
     pub updated_at: Option<DateTime<TZ>>,
 }
+
+impl StoredUserInfo {
+    pub async fn set_sns_name<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        wallet_address: &StoredPubkey,
+        sns_name: Option<&str>,
+    ) -> anyhow::Result<()> {
+        sqlx::query("UPDATE user_info SET sns_name = $2 WHERE wallet_address = $1")
+            .bind(wallet_address)
+            .bind(sns_name)
+            .execute(executor)
+            .await?;
+        Ok(())
+    }
+
+    /// Wallet, which name was resolved last time its user info was fetched.
+    pub async fn find_by_sns_name<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        sns_name: &str,
+    ) -> anyhow::Result<Option<StoredPubkey>> {
+        Ok(sqlx::query_scalar(
+            "SELECT wallet_address FROM user_info WHERE sns_name = $1 ORDER BY updated_at DESC LIMIT 1",
+        )
+        .bind(sns_name)
+        .fetch_optional(executor)
+        .await?)
+    }
+}
@@ -0,0 +1,125 @@
+use super::{
+    project::{ProjectId, Stage},
+    DB,
+};
+use chrono::DateTime;
+use services_common::TZ;
+use solana_sdk::pubkey::Pubkey;
+use uuid::Uuid;
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct StoredWebhook {
+    pub id: Uuid,
+    pub owner_wallet: Vec<u8>,
+    pub target_url: String,
+    pub secret_hex: String,
+    pub event_filter: Vec<Stage>,
+    pub created_at: DateTime<TZ>,
+}
+
+impl StoredWebhook {
+    pub async fn insert<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        owner: &Pubkey,
+        target_url: &str,
+        secret_hex: &str,
+        event_filter: &[Stage],
+    ) -> anyhow::Result<StoredWebhook> {
+        Ok(sqlx::query_as(
+            "
+                INSERT INTO webhook (owner_wallet, target_url, secret_hex, event_filter)
+                VALUES ($1, $2, $3, $4)
+                RETURNING *
+            ",
+        )
+        .bind(owner.as_ref())
+        .bind(target_url)
+        .bind(secret_hex)
+        .bind(event_filter)
+        .fetch_one(executor)
+        .await?)
+    }
+
+    pub async fn count_by_owner<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        owner: &Pubkey,
+    ) -> anyhow::Result<i64> {
+        Ok(
+            sqlx::query_scalar("SELECT COUNT(*) FROM webhook WHERE owner_wallet = $1")
+                .bind(owner.as_ref())
+                .fetch_one(executor)
+                .await?,
+        )
+    }
+
+    /// Returns `false` if webhook is missing or owned by another wallet.
+    pub async fn delete<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        id: Uuid,
+        owner: &Pubkey,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query("DELETE FROM webhook WHERE id = $1 AND owner_wallet = $2")
+            .bind(id)
+            .bind(owner.as_ref())
+            .execute(executor)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Webhooks, interested in the project, along with its current stage.
+    /// Empty if the project is missing.
+    pub async fn matching<'c, E: sqlx::Executor<'c, Database = DB>>(
+        executor: E,
+        project_id: ProjectId,
+    ) -> anyhow::Result<Vec<(StoredWebhook, Stage)>> {
+        let rows: Vec<MatchedWebhook> = sqlx::query_as(
+            "
+                SELECT webhook.*, project.stage AS project_stage
+                FROM webhook, project
+                WHERE project.id = $1
+                    AND (cardinality(webhook.event_filter) = 0
+                        OR project.stage = ANY(webhook.event_filter))
+            ",
+        )
+        .bind(project_id)
+        .fetch_all(executor)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.webhook, row.project_stage))
+            .collect())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct MatchedWebhook {
+    #[sqlx(flatten)]
+    webhook: StoredWebhook,
+    project_stage: Stage,
+}
+
+/// Every attempt is recorded, so failed ones are visible even if a retry succeeded.
+pub async fn record_delivery<'c, E: sqlx::Executor<'c, Database = DB>>(
+    executor: E,
+    webhook_id: Uuid,
+    event_type: &str,
+    attempt: u32,
+    status_code: Option<u16>,
+    error: Option<String>,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "
+            INSERT INTO webhook_delivery_log (webhook_id, event_type, attempt, status_code, success, error)
+            VALUES ($1, $2, $3, $4, $5, $6)
+        ",
+    )
+    .bind(webhook_id)
+    .bind(event_type)
+    .bind(i32::try_from(attempt)?)
+    .bind(status_code.map(i32::from))
+    .bind(error.is_none())
+    .bind(error)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
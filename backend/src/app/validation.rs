@@ -0,0 +1,456 @@
+use super::{
+    exposed::{CreateProjectRequest, CreateTokenMeta, UpdateProjectMetaRequest},
+    storage::project::{CurveVariant, OwnerProjectCounts},
+};
+use rustrict::CensorStr;
+use std::{
+    collections::HashSet,
+    fmt,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+use url::{Host, Url};
+
+const NAME_MAX_LEN: usize = 32;
+const SYMBOL_MAX_LEN: usize = 10;
+const DESCRIPTION_MAX_LEN: usize = 500;
+
+pub const MAX_BATCH_PROJECTS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub reason: String,
+}
+
+/// Lists every failed field, so user could fix all of them at once.
+#[derive(Debug, thiserror::Error)]
+pub struct InvalidTokenMeta {
+    pub fields: Vec<FieldError>,
+}
+
+impl fmt::Display for InvalidTokenMeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid token meta:")?;
+        for (idx, error) in self.fields.iter().enumerate() {
+            let separator = if idx == 0 { " " } else { "; " };
+            write!(f, "{separator}{}: {}", error.field, error.reason)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Insufficient creator deposit: need {need}, got {got}")]
+pub struct InsufficientCreatorDeposit {
+    pub need: u64,
+    pub got: u64,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{reason}")]
+pub struct ProjectsLimitExceeded {
+    pub reason: String,
+    pub counts: OwnerProjectCounts,
+}
+
+pub fn check_projects_limits(
+    counts: OwnerProjectCounts,
+    max_per_hour: u32,
+    max_active: u32,
+) -> Result<(), ProjectsLimitExceeded> {
+    let reason = if counts.this_hour >= max_per_hour.into() {
+        format!("at most {max_per_hour} projects could be created per hour")
+    } else if counts.active >= max_active.into() {
+        format!("at most {max_active} projects could be active at once")
+    } else {
+        return Ok(());
+    };
+    Err(ProjectsLimitExceeded { reason, counts })
+}
+
+pub fn validate_token_meta(
+    meta: &CreateTokenMeta,
+    extra_blocked_words: &[String],
+) -> anyhow::Result<()> {
+    let mut fields = vec![];
+    let mut fail = |field, reason: &str| {
+        fields.push(FieldError {
+            field,
+            reason: reason.to_owned(),
+        })
+    };
+
+    let name_len = meta.name.chars().count();
+    if name_len == 0 || name_len > NAME_MAX_LEN {
+        fail("name", "must be 1-32 characters long");
+    }
+    if is_blocked(&meta.name, extra_blocked_words) {
+        fail("name", "contains inappropriate words");
+    }
+
+    let symbol_len = meta.symbol.chars().count();
+    if symbol_len == 0 || symbol_len > SYMBOL_MAX_LEN {
+        fail("symbol", "must be 1-10 characters long");
+    }
+    if !meta
+        .symbol
+        .chars()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    {
+        fail("symbol", "must contain only uppercase letters and digits");
+    }
+
+    check_description(&meta.description, &mut fail);
+    check_links(
+        [
+            ("website", &meta.website),
+            ("twitter", &meta.twitter),
+            ("telegram", &meta.telegram),
+        ],
+        &mut fail,
+    );
+
+    if fields.is_empty() {
+        Ok(())
+    } else {
+        Err(InvalidTokenMeta { fields }.into())
+    }
+}
+
+/// Only the fields, present in the update, are checked.
+pub fn validate_token_meta_update(update: &UpdateProjectMetaRequest) -> anyhow::Result<()> {
+    let mut fields = vec![];
+    let mut fail = |field, reason: &str| {
+        fields.push(FieldError {
+            field,
+            reason: reason.to_owned(),
+        })
+    };
+
+    if let Some(description) = &update.description {
+        check_description(description, &mut fail);
+    }
+    check_links(
+        [
+            ("website", &update.website),
+            ("twitter", &update.twitter),
+            ("telegram", &update.telegram),
+        ],
+        &mut fail,
+    );
+
+    if fields.is_empty() {
+        Ok(())
+    } else {
+        Err(InvalidTokenMeta { fields }.into())
+    }
+}
+
+fn check_description(description: &str, fail: &mut impl FnMut(&'static str, &str)) {
+    if description.chars().count() > DESCRIPTION_MAX_LEN {
+        fail("description", "must be at most 500 characters long");
+    }
+}
+
+fn check_links(
+    links: [(&'static str, &Option<String>); 3],
+    fail: &mut impl FnMut(&'static str, &str),
+) {
+    for (field, link) in links {
+        // frontend sends empty strings for unset links
+        let Some(link) = link.as_deref().filter(|link| !link.is_empty()) else {
+            continue;
+        };
+        if url::Url::parse(link).is_err() {
+            fail(field, "must be a valid url");
+        }
+    }
+}
+
+/// Webhooks are called by backend itself, so they must not point into the internal network.
+/// Names resolving to internal addresses are not caught, it's only the first line of defence.
+pub fn validate_webhook_url(target_url: &str) -> anyhow::Result<Url> {
+    let url = Url::parse(target_url)?;
+    if url.scheme() != "https" {
+        anyhow::bail!("webhook url must use https");
+    }
+    let internal = match url.host() {
+        None => true,
+        Some(Host::Domain(domain)) => {
+            domain.eq_ignore_ascii_case("localhost") || domain.ends_with(".localhost")
+        }
+        Some(Host::Ipv4(ip)) => is_internal_ipv4(ip),
+        Some(Host::Ipv6(ip)) => is_internal_ipv6(ip),
+    };
+    if internal {
+        anyhow::bail!("webhook url must point to a public host");
+    }
+    Ok(url)
+}
+
+/// Batch carries no images, so every project refers to an already hosted one.
+/// Pumpfun uploads the image content itself, so it can't be batched.
+pub fn validate_batch_create(requests: &[CreateProjectRequest]) -> anyhow::Result<()> {
+    if requests.is_empty() || requests.len() > MAX_BATCH_PROJECTS {
+        anyhow::bail!(
+            "batch must contain 1-{MAX_BATCH_PROJECTS} projects, got {}",
+            requests.len()
+        );
+    }
+    let mut owners = HashSet::new();
+    for request in requests {
+        if !owners.insert(request.owner) {
+            anyhow::bail!("owner {} is repeated in the batch", request.owner);
+        }
+        if request.deploy_schema.curve_pool == CurveVariant::Pumpfun {
+            anyhow::bail!("pumpfun projects can't be created in a batch");
+        }
+        let Some(image_url) = &request.image_url else {
+            anyhow::bail!("image url of the project of {} is missing", request.owner);
+        };
+        if Url::parse(image_url)?.scheme() != "https" {
+            anyhow::bail!("image url must use https");
+        }
+    }
+    Ok(())
+}
+
+fn is_internal_ipv4(ip: Ipv4Addr) -> bool {
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+}
+
+fn is_internal_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        // unique local fc00::/7 and link local fe80::/10
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || ip.to_ipv4_mapped().is_some_and(is_internal_ipv4)
+}
+
+fn is_blocked(text: &str, extra_blocked_words: &[String]) -> bool {
+    if text.is_inappropriate() {
+        return true;
+    }
+    let text = text.to_lowercase();
+    extra_blocked_words
+        .iter()
+        .any(|word| text.contains(&word.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use uuid::Uuid;
+
+    fn valid_meta() -> CreateTokenMeta {
+        CreateTokenMeta {
+            name: "Moon Token".into(),
+            symbol: "MOON2".into(),
+            description: "Going to the moon".into(),
+            website: Some("https://moon.zip".into()),
+            twitter: Some("https://x.com/moonzip".into()),
+            telegram: None,
+        }
+    }
+
+    fn failed_fields(meta: &CreateTokenMeta, extra_blocked_words: &[String]) -> Vec<&'static str> {
+        let err = validate_token_meta(meta, extra_blocked_words).unwrap_err();
+        err.downcast_ref::<InvalidTokenMeta>()
+            .expect("invalid token meta error")
+            .fields
+            .iter()
+            .map(|error| error.field)
+            .collect()
+    }
+
+    #[test]
+    fn test_accepts_valid_meta() {
+        validate_token_meta(&valid_meta(), &[]).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_inappropriate_name() {
+        let mut meta = valid_meta();
+        meta.name = "fuck".into();
+        assert_eq!(failed_fields(&meta, &[]), vec!["name"]);
+    }
+
+    #[test]
+    fn test_rejects_extra_blocked_words() {
+        let mut meta = valid_meta();
+        meta.name = "Some RugPull".into();
+        validate_token_meta(&meta, &[]).unwrap();
+        assert_eq!(failed_fields(&meta, &["rugpull".into()]), vec!["name"]);
+    }
+
+    #[test]
+    fn test_checks_name_length() {
+        let mut meta = valid_meta();
+        meta.name = String::new();
+        assert_eq!(failed_fields(&meta, &[]), vec!["name"]);
+
+        meta.name = "a".repeat(NAME_MAX_LEN);
+        validate_token_meta(&meta, &[]).unwrap();
+        meta.name.push('a');
+        assert_eq!(failed_fields(&meta, &[]), vec!["name"]);
+    }
+
+    #[test]
+    fn test_checks_symbol() {
+        let mut meta = valid_meta();
+        meta.symbol = String::new();
+        assert_eq!(failed_fields(&meta, &[]), vec!["symbol"]);
+
+        meta.symbol = "A".repeat(SYMBOL_MAX_LEN + 1);
+        assert_eq!(failed_fields(&meta, &[]), vec!["symbol"]);
+
+        meta.symbol = "moon".into();
+        assert_eq!(failed_fields(&meta, &[]), vec!["symbol"]);
+
+        meta.symbol = "MO-ON".into();
+        assert_eq!(failed_fields(&meta, &[]), vec!["symbol"]);
+    }
+
+    #[test]
+    fn test_checks_description_length() {
+        let mut meta = valid_meta();
+        meta.description = "a".repeat(DESCRIPTION_MAX_LEN);
+        validate_token_meta(&meta, &[]).unwrap();
+        meta.description.push('a');
+        assert_eq!(failed_fields(&meta, &[]), vec!["description"]);
+    }
+
+    #[test]
+    fn test_checks_links() {
+        let mut meta = valid_meta();
+        meta.telegram = Some(String::new());
+        validate_token_meta(&meta, &[]).unwrap();
+
+        meta.website = Some("moon zip".into());
+        meta.telegram = Some("t.me/moonzip".into());
+        assert_eq!(failed_fields(&meta, &[]), vec!["website", "telegram"]);
+    }
+
+    #[test]
+    fn test_checks_present_fields_of_update_only() {
+        let mut update = UpdateProjectMetaRequest {
+            project_id: Uuid::new_v4(),
+            description: None,
+            website: None,
+            twitter: Some(String::new()),
+            telegram: None,
+        };
+        validate_token_meta_update(&update).unwrap();
+
+        update.description = Some("a".repeat(DESCRIPTION_MAX_LEN + 1));
+        update.website = Some("moon zip".into());
+        let err = validate_token_meta_update(&update).unwrap_err();
+        let fields: Vec<_> = err
+            .downcast_ref::<InvalidTokenMeta>()
+            .expect("invalid token meta error")
+            .fields
+            .iter()
+            .map(|error| error.field)
+            .collect();
+        assert_eq!(fields, vec!["description", "website"]);
+    }
+
+    #[test]
+    fn test_limits_projects_per_owner() {
+        let counts = |this_hour, active| OwnerProjectCounts { this_hour, active };
+        check_projects_limits(counts(4, 9), 5, 10).unwrap();
+
+        let err = check_projects_limits(counts(5, 5), 5, 10).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "at most 5 projects could be created per hour"
+        );
+
+        let err = check_projects_limits(counts(0, 10), 5, 10).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "at most 10 projects could be active at once"
+        );
+    }
+
+    #[test]
+    fn test_accepts_public_webhook_urls_only() {
+        validate_webhook_url("https://hooks.example.com/moonzip").unwrap();
+        validate_webhook_url("https://8.8.8.8/hook").unwrap();
+        for url in [
+            "http://hooks.example.com",
+            "not a url",
+            "https://localhost:18000/admin",
+            "https://127.0.0.1/hook",
+            "https://10.1.2.3/hook",
+            "https://192.168.0.1/hook",
+            "https://169.254.169.254/latest",
+            "https://[::1]/hook",
+            "https://[fd00::1]/hook",
+            "https://[::ffff:127.0.0.1]/hook",
+        ] {
+            assert!(validate_webhook_url(url).is_err(), "{url} is accepted");
+        }
+    }
+
+    fn batch_request(owner: Pubkey) -> CreateProjectRequest {
+        serde_json::from_value(serde_json::json!({
+            "owner": owner.to_string(),
+            "meta": valid_meta(),
+            "deploySchema": {"curvePool": "moonzip", "devPurchase": null, "tokenDecimals": null},
+            "imageUrl": "https://ipfs.io/ipfs/image",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validates_batch_create() {
+        let requests: Vec<_> = (0..MAX_BATCH_PROJECTS)
+            .map(|_| batch_request(Pubkey::new_unique()))
+            .collect();
+        validate_batch_create(&requests).unwrap();
+
+        assert!(validate_batch_create(&[]).is_err());
+        let mut too_many = requests.clone();
+        too_many.push(batch_request(Pubkey::new_unique()));
+        assert!(validate_batch_create(&too_many).is_err());
+
+        let owner = Pubkey::new_unique();
+        assert!(validate_batch_create(&[batch_request(owner), batch_request(owner)]).is_err());
+
+        let mut without_image = batch_request(Pubkey::new_unique());
+        without_image.image_url = None;
+        assert!(validate_batch_create(&[without_image]).is_err());
+
+        let mut plain_http = batch_request(Pubkey::new_unique());
+        plain_http.image_url = Some("http://ipfs.io/ipfs/image".into());
+        assert!(validate_batch_create(&[plain_http]).is_err());
+    }
+
+    #[test]
+    fn test_lists_all_failed_fields() {
+        let meta = CreateTokenMeta {
+            name: String::new(),
+            symbol: "moon".into(),
+            description: "a".repeat(DESCRIPTION_MAX_LEN + 1),
+            website: None,
+            twitter: Some("not a url".into()),
+            telegram: None,
+        };
+        let err = validate_token_meta(&meta, &[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid token meta: name: must be 1-32 characters long; \
+             symbol: must contain only uppercase letters and digits; \
+             description: must be at most 500 characters long; \
+             twitter: must be a valid url"
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use super::{
+    exposed::{WebhookEvent, WebhookPayload},
+    storage::{
+        project::ProjectId,
+        webhooks::{record_delivery, StoredWebhook},
+        StorageClient,
+    },
+};
+use crate::utils::to_hex;
+use hmac::{Hmac, Mac as _};
+use http::header::CONTENT_TYPE;
+use serde::Deserialize;
+use sha2::Sha256;
+use solana_sdk::signature::Signature;
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    sync::{
+        mpsc::{channel, error::TrySendError, Receiver, Sender},
+        Semaphore,
+    },
+    task::JoinHandle,
+};
+use tracing::{debug, error, warn};
+
+pub const SIGNATURE_HEADER: &str = "X-Moonzip-Signature";
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 5;
+
+#[derive(Debug, Clone, Deserialize, serde_derive_default::Default)]
+pub struct WebhooksConfig {
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+    /// Worker stops taking events once that many deliveries are in flight.
+    #[serde(default = "default_max_concurrent_deliveries")]
+    pub max_concurrent_deliveries: usize,
+    /// Delay before the first retry, doubled after every failed attempt.
+    #[serde(with = "humantime_serde", default = "default_initial_backoff")]
+    pub initial_backoff: Duration,
+    #[serde(with = "humantime_serde", default = "default_request_timeout")]
+    pub request_timeout: Duration,
+}
+
+fn default_queue_capacity() -> usize {
+    1000
+}
+
+fn default_max_concurrent_deliveries() -> usize {
+    64
+}
+
+fn default_initial_backoff() -> Duration {
+    Duration::from_secs(1)
+}
+
+fn default_request_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+/// Chain event of the project, delivered to every webhook interested in the project.
+#[derive(Debug, Clone)]
+pub struct WebhookTrigger {
+    pub project_id: ProjectId,
+    pub tx_signature: Signature,
+    pub slot: u64,
+    pub event: WebhookEvent,
+}
+
+/// Entry point for webhooks, they're called asynchronously by [`WebhookWorker`].
+#[derive(Clone)]
+pub struct WebhookQueue {
+    tx: Sender<WebhookTrigger>,
+}
+
+impl WebhookQueue {
+    pub fn push(&self, trigger: WebhookTrigger) {
+        match self.tx.try_send(trigger) {
+            Ok(()) => {}
+            Err(TrySendError::Full(trigger)) => {
+                warn!(
+                    "webhook queue is full, dropping {} event of {}",
+                    trigger.event.as_str(),
+                    trigger.project_id
+                );
+            }
+            Err(TrySendError::Closed(_)) => {
+                error!("webhook worker is terminated, event is lost");
+            }
+        }
+    }
+}
+
+pub struct WebhookWorker {
+    delivery: Delivery,
+    deliveries: Arc<Semaphore>,
+    storage: StorageClient,
+    rx: Receiver<WebhookTrigger>,
+}
+
+impl WebhookWorker {
+    pub fn serve(
+        config: WebhooksConfig,
+        storage: StorageClient,
+    ) -> anyhow::Result<(WebhookQueue, JoinHandle<()>)> {
+        let (tx, rx) = channel(config.queue_capacity);
+        let client = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            // redirects could lead into the internal network, bypassing url validation
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+        let mut worker = Self {
+            delivery: Delivery {
+                client,
+                storage: storage.clone(),
+                initial_backoff: config.initial_backoff,
+            },
+            deliveries: Arc::new(Semaphore::new(config.max_concurrent_deliveries)),
+            storage,
+            rx,
+        };
+        let handle = tokio::spawn(async move {
+            while let Some(trigger) = worker.rx.recv().await {
+                if let Err(err) = worker.dispatch(trigger).await {
+                    error!("failed to dispatch webhook event: {err:#}");
+                }
+            }
+            debug!("webhook queue closed, worker stopped");
+        });
+        Ok((WebhookQueue { tx }, handle))
+    }
+
+    /// Every webhook is delivered separately, so slow endpoints don't hold others.
+    async fn dispatch(&self, trigger: WebhookTrigger) -> anyhow::Result<()> {
+        let matched = StoredWebhook::matching(&self.storage.pool, trigger.project_id).await?;
+        for (webhook, stage) in matched {
+            let payload = WebhookPayload {
+                webhook_id: webhook.id,
+                project_id: trigger.project_id,
+                stage,
+                tx_signature: trigger.tx_signature.to_string(),
+                slot: trigger.slot,
+                event: trigger.event.clone(),
+            };
+            let permit = self.deliveries.clone().acquire_owned().await?;
+            let delivery = self.delivery.clone();
+            tokio::spawn(async move {
+                delivery.deliver(webhook, payload).await;
+                drop(permit);
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct Delivery {
+    client: reqwest::Client,
+    storage: StorageClient,
+    initial_backoff: Duration,
+}
+
+impl Delivery {
+    async fn deliver(&self, webhook: StoredWebhook, payload: WebhookPayload) {
+        let event_type = payload.event.as_str();
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(err) => {
+                error!("failed to serialize webhook payload: {err}");
+                return;
+            }
+        };
+        let signature = sign(&webhook.secret_hex, &body);
+
+        for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+            let result = self
+                .client
+                .post(&webhook.target_url)
+                .header(CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .body(body.clone())
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            let (status_code, error) = match result {
+                Ok(response) => (Some(response.status().as_u16()), None),
+                Err(err) => (
+                    err.status().map(|status| status.as_u16()),
+                    Some(format!("{err:#}")),
+                ),
+            };
+            let delivered = error.is_none();
+            if let Some(error) = &error {
+                warn!(
+                    "attempt {attempt} to deliver {event_type} to webhook {} failed: {error}",
+                    webhook.id
+                );
+            }
+            if let Err(err) = record_delivery(
+                &self.storage.pool,
+                webhook.id,
+                event_type,
+                attempt,
+                status_code,
+                error,
+            )
+            .await
+            {
+                error!("failed to record webhook delivery: {err:#}");
+            }
+            if delivered {
+                return;
+            }
+            if attempt < MAX_DELIVERY_ATTEMPTS {
+                tokio::time::sleep(backoff(self.initial_backoff, attempt)).await;
+            }
+        }
+        warn!(
+            "giving up on delivery of {event_type} to webhook {} after {MAX_DELIVERY_ATTEMPTS} attempts",
+            webhook.id
+        );
+    }
+}
+
+/// Value of [`SIGNATURE_HEADER`], the secret is used as is, without decoding the hex.
+pub fn sign(secret_hex: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret_hex.as_bytes())
+        .expect("hmac accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", to_hex(&mac.finalize().into_bytes()))
+}
+
+/// Delay after the failed `attempt`, counting from 1.
+fn backoff(initial: Duration, attempt: u32) -> Duration {
+    initial.saturating_mul(1 << (attempt - 1).min(16))
+}
+
+/// Random enough for a signing key: 244 random bits of two v4 uuids.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(uuid::Uuid::new_v4().as_bytes());
+    bytes[16..].copy_from_slice(uuid::Uuid::new_v4().as_bytes());
+    to_hex(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signs_with_hmac_sha256() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_doubles_backoff_between_attempts() {
+        let initial = Duration::from_secs(1);
+        let delays: Vec<_> = (1..MAX_DELIVERY_ATTEMPTS)
+            .map(|attempt| backoff(initial, attempt).as_secs())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 8]);
+    }
+
+    #[test]
+    fn test_generates_distinct_secrets() {
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 64);
+        assert_ne!(secret, generate_secret());
+    }
+}
@@ -1,8 +1,16 @@
 use backend::{
-    api::router,
+    api::{admin_router, client_limit::RateLimitsConfig, compression::CompressionConfig, router},
     app::{
-        instructions::{self, mzip, pumpfun, InstructionsBuilder, InstructionsConfig},
-        storage::{StorageClient, StorageConfig},
+        instructions::{
+            self, jupiter::JupiterClient, mzip, pumpfun, InstructionsBuilder, InstructionsConfig,
+        },
+        live_config::LiveConfig,
+        notifications::{NotificationWorker, NotificationsConfig},
+        price_stream::PriceStreamListener,
+        search_cache::{SearchCache, SearchCacheConfig},
+        sns::{SnsConfig, SnsResolver},
+        static_pool_progress::StaticPoolProgressListener,
+        storage::{project::ProjectIdStrategy, StorageClient, StorageConfig},
         App,
     },
     cfg::FetchersConfig,
@@ -28,6 +36,18 @@ struct Config {
     #[serde(default)]
     instructions: InstructionsConfig,
     fetchers: FetchersConfig,
+    #[serde(default)]
+    notifications: Option<NotificationsConfig>,
+    #[serde(default)]
+    project_id_strategy: ProjectIdStrategy,
+    #[serde(default)]
+    compression: CompressionConfig,
+    #[serde(default)]
+    rate_limits: RateLimitsConfig,
+    #[serde(default)]
+    sns: Option<SnsConfig>,
+    #[serde(default)]
+    search_cache: Option<SearchCacheConfig>,
 }
 
 #[tokio::main]
@@ -58,23 +78,55 @@ pub async fn main() -> anyhow::Result<()> {
     )
     .serve();
 
+    let jupiter = JupiterClient::new(&cfg.instructions);
     let instructions_builder = InstructionsBuilder {
         solana_pool: solana_pool.clone(),
         solana_meta: solana_meta.clone(),
         mzip_meta: moonzip_meta_rx,
         pump_meta: pumpfun_meta_rx,
         config: cfg.instructions.into(),
+        pumpfun_curves: Default::default(),
+        jupiter: jupiter.into(),
+    };
+
+    let notifications = cfg
+        .notifications
+        .map(|config| NotificationWorker::serve(config, storage_client.clone()))
+        .transpose()?
+        .map(|(queue, _)| queue);
+
+    let search_cache = match cfg.search_cache {
+        Some(config) => Some(SearchCache::connect(config).await?),
+        None => None,
     };
 
+    let live_config =
+        LiveConfig::load(&storage_client, instructions_builder.config.clone()).await?;
+    live_config.serve_refresh(storage_client.clone());
+
     let app = Arc::new(App {
         storage: storage_client,
         instructions_builder,
+        live_config,
         keys,
         solana_meta,
         solana_pool,
+        notifications,
+        project_id_strategy: cfg.project_id_strategy,
+        search_cache,
+        static_pool_progress: Default::default(),
+        price_stream: Default::default(),
+        sns: cfg.sns.map(SnsResolver::new),
     });
+    StaticPoolProgressListener { app: app.clone() }.serve();
+    PriceStreamListener { app: app.clone() }.serve();
     let api_state = AppState::new(app, cfg.api);
     info!("Starting API server");
-    serve::<_, backend::api::ApiDoc>(api_state, router()).await?;
+    serve::<_, backend::api::ApiDoc>(
+        api_state,
+        router(&cfg.compression, &cfg.rate_limits),
+        admin_router(),
+    )
+    .await?;
     anyhow::bail!("API server unexpectedly terminated")
 }
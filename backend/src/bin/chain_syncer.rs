@@ -4,15 +4,21 @@ use backend::{
             cfg::ChainSyncConfig,
             fetcher::ChainFetcher,
             geyser::{GeyserClient, GeyserClientConfig},
+            holders::HolderCountSnapshotter,
             parser::ParseAggregator,
+            router::MetricsRouter,
+            stats::{CandlesPruner, TradeStatsRefresher},
             storage::StorageApplier,
         },
+        notifications::{NotificationWorker, NotificationsConfig},
         storage::{StorageClient, StorageConfig},
+        webhooks::{WebhookWorker, WebhooksConfig},
     },
     log::setup_log,
+    metrics,
 };
 use serde::Deserialize;
-use services_common::cfg::load_config;
+use services_common::{api::server::ListenConfig, cfg::load_config};
 
 #[derive(Deserialize, Debug, Clone)]
 struct Config {
@@ -20,20 +26,55 @@ struct Config {
     geyser: GeyserClientConfig,
     #[serde(default)]
     algo: ChainSyncConfig,
+    #[serde(default)]
+    notifications: Option<NotificationsConfig>,
+    #[serde(default)]
+    webhooks: Option<WebhooksConfig>,
+    /// Prometheus `/metrics` is exposed, once configured.
+    #[serde(default)]
+    metrics: Option<ListenConfig>,
 }
 
 #[tokio::main]
 pub async fn main() -> anyhow::Result<()> {
     setup_log();
     let cfg = load_config::<Config>();
+    if let Some(listen) = cfg.metrics.clone() {
+        metrics::serve_exporter(listen);
+    }
     let storage_client = StorageClient::from_config(cfg.db).await?;
     let geyser = GeyserClient::from_cfg(cfg.geyser).await?;
 
+    HolderCountSnapshotter {
+        storage: storage_client.clone(),
+        interval: cfg.algo.holder_snapshot_interval,
+    }
+    .serve();
+    TradeStatsRefresher {
+        storage: storage_client.clone(),
+        interval: cfg.algo.stats_refresh_interval,
+    }
+    .serve();
+    CandlesPruner {
+        storage: storage_client.clone(),
+        interval: cfg.algo.candles_prune_interval,
+    }
+    .serve();
+
     let blocks_rx = ChainFetcher::new(geyser).serve();
-    let parsed_blocks_rx = ParseAggregator::new(blocks_rx, cfg.algo).serve();
+    let parsed_blocks_rx = ParseAggregator::new(blocks_rx, cfg.algo)
+        .with_router(MetricsRouter)
+        .serve();
 
-    StorageApplier::new(storage_client, parsed_blocks_rx)
-        .serve()
-        .await?;
+    let mut applier = StorageApplier::new(storage_client.clone(), parsed_blocks_rx);
+    if let Some(config) = cfg.notifications {
+        let (queue, _) = NotificationWorker::serve(config, storage_client.clone())?;
+        applier = applier.with_notifications(queue);
+    }
+    if let Some(config) = cfg.webhooks {
+        let (queue, _) = WebhookWorker::serve(config, storage_client)?;
+        applier = applier.with_webhooks(queue);
+    }
+    applier.serve().await?;
     panic!("storage applier unexpectedly terminated")
 }
@@ -1,20 +1,27 @@
 use backend::{
     app::{
-        instructions::{self, mzip, pumpfun, InstructionsBuilder, InstructionsConfig},
+        instructions::{
+            self, jupiter::JupiterClient, mzip, pumpfun, InstructionsBuilder, InstructionsConfig,
+        },
         keys_loader::{self, KeysLoader},
         migrator::{Migrator, MigratorConfig},
         storage::{StorageClient, StorageConfig},
     },
     cfg::FetchersConfig,
     log::setup_log,
+    metrics,
     solana::{SolanaKeys, SolanaKeysConfig},
 };
 use serde::Deserialize;
 use services_common::{
+    api::server::ListenConfig,
     cfg::load_config,
     solana::pool::{SolanaPool, SolanaPoolConfig},
     utils::period_fetch::{PeriodicFetcher, PeriodicFetcherConfig},
 };
+use tokio::signal::unix::{signal, SignalKind};
+use tokio_util::sync::CancellationToken;
+use tracing::info;
 
 #[derive(Deserialize, Debug, Clone)]
 struct Config {
@@ -26,12 +33,18 @@ struct Config {
     #[serde(default)]
     instructions: InstructionsConfig,
     fetchers: FetchersConfig,
+    /// Prometheus `/metrics` is exposed, once configured.
+    #[serde(default)]
+    metrics: Option<ListenConfig>,
 }
 
 #[tokio::main]
 pub async fn main() -> anyhow::Result<()> {
     setup_log();
     let cfg = load_config::<Config>();
+    if let Some(listen) = cfg.metrics.clone() {
+        metrics::serve_exporter(listen);
+    }
     let storage_client = StorageClient::from_config(cfg.db).await?;
     let solana_pool = SolanaPool::from_cfg(cfg.solana_pool)?;
     KeysLoader::new(cfg.token_keys_loader, storage_client.clone()).serve();
@@ -57,22 +70,42 @@ pub async fn main() -> anyhow::Result<()> {
     )
     .serve();
 
+    let jupiter = JupiterClient::new(&cfg.instructions);
     let instructions_builder = InstructionsBuilder {
         solana_pool: solana_pool.clone(),
         solana_meta: solana_meta.clone(),
         pump_meta,
         mzip_meta,
         config: cfg.instructions.into(),
+        pumpfun_curves: Default::default(),
+        jupiter: jupiter.into(),
     };
 
+    let cancellation = CancellationToken::new();
     let handle = Migrator::serve(
         solana_pool.clone(),
         keys.clone(),
         storage_client.clone(),
         instructions_builder.clone(),
         cfg.migrator,
+        cancellation.clone(),
     )
     .await?;
+    tokio::spawn(cancel_on_signal(cancellation.clone()));
+
     handle.await?;
-    anyhow::bail!("migrator unexpectedly terminated the loop")
+    if !cancellation.is_cancelled() {
+        anyhow::bail!("migrator unexpectedly terminated the loop")
+    }
+    Ok(())
+}
+
+async fn cancel_on_signal(cancellation: CancellationToken) -> anyhow::Result<()> {
+    let mut terminate = signal(SignalKind::terminate())?;
+    tokio::select! {
+        _ = terminate.recv() => info!("received SIGTERM"),
+        _ = tokio::signal::ctrl_c() => info!("received SIGINT"),
+    }
+    cancellation.cancel();
+    Ok(())
 }
@@ -1,6 +1,7 @@
 pub mod app;
 pub mod cfg;
 pub mod log;
+pub mod metrics;
 pub mod solana;
 pub mod utils;
 
@@ -0,0 +1,248 @@
+use axum::{response::IntoResponse, routing::get, Router};
+use http::{header, StatusCode};
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_histogram, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge, Encoder, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    TextEncoder,
+};
+use services_common::api::server::ListenConfig;
+use tokio::task::JoinHandle;
+use tracing::{debug, error};
+
+/// Renders all registered metrics in prometheus text format.
+pub async fn export() -> impl IntoResponse {
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    if let Err(err) = encoder.encode(&prometheus::gather(), &mut buffer) {
+        error!("failed to encode metrics: {err:#}");
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    (
+        [(header::CONTENT_TYPE, encoder.format_type().to_owned())],
+        buffer,
+    )
+        .into_response()
+}
+
+/// Serves `/metrics` for binaries, which have no api server to attach it to.
+pub fn serve_exporter(listen: ListenConfig) -> JoinHandle<anyhow::Result<()>> {
+    tokio::spawn(async move {
+        let listener = tokio::net::TcpListener::bind(listen.as_bind()).await?;
+        debug!("listening metrics on {}", listener.local_addr()?);
+        axum::serve(listener, Router::new().route("/metrics", get(export))).await?;
+        Ok(())
+    })
+}
+
+pub static RPC_SUBMISSION_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "moonzip_rpc_submission_latency_seconds",
+        "Latency of solana rpc calls made during transactions submission",
+        &["operation"]
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static FEE_SWEPT_LAMPORTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "moonzip_fee_swept_lamports_total",
+        "Lamports moved from the fee account to the fee receiver"
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static JITO_BUNDLE_CONFIRM_DURATION: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "jito_bundle_confirm_duration_seconds",
+        "Time from jito bundle submission until it reaches requested commitment"
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static TX_FAILURES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "moonzip_tx_failures_total",
+        "Transactions submitted by backend, which failed on-chain",
+        &["error_type"]
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static RESPONSE_COMPRESSION_RATIO: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "moonzip_response_compression_ratio",
+        "Compressed to uncompressed size ratio of sampled api responses",
+        vec![0.05, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static POOL_CONNECTIONS_ACTIVE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "pool_connections_active",
+        "Database connections currently checked out of the pool"
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static POOL_CONNECTIONS_IDLE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "pool_connections_idle",
+        "Database connections idling in the pool"
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static POOL_ACQUIRE_TIMEOUTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "pool_acquire_timeout_total",
+        "Database connection acquisitions, which timed out"
+    )
+    .expect("invariant: metric is registered once")
+});
+
+/// Together with the active connections gauge helps to spot leaked connections.
+pub static POOL_CONNECTION_LIFETIME: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "pool_connection_lifetime_seconds",
+        "Age of database connections at the moment they are returned to the pool",
+        vec![0.1, 0.5, 1.0, 5.0, 15.0, 30.0, 60.0, 300.0, 600.0]
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static PROJECT_ARCHIVED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("project_archived_total", "Projects archived by admins")
+        .expect("invariant: metric is registered once")
+});
+
+/// Compared with landed bundles, shows which tip accounts land better.
+pub static JITO_TIP_KEY_USED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "jito_tip_key_used_total",
+        "Jito tips sent, by tip account",
+        &["key"]
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static MIGRATOR_GRACEFUL_SHUTDOWN_DURATION: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "migrator_graceful_shutdown_duration_seconds",
+        "Time spent by migrator on waiting for in-flight migrations at shutdown",
+        vec![0.1, 0.5, 1.0, 5.0, 10.0, 20.0, 30.0, 35.0]
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static COMPUTE_UNITS_ESTIMATED: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "compute_units_estimated",
+        "Compute unit limits, estimated by transaction simulation",
+        prometheus::exponential_buckets(10_000.0, 2.0, 8).expect("invariant: valid buckets")
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static COMPUTE_UNITS_ACTUAL: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "compute_units_actual",
+        "Compute units consumed by landed transactions with estimated limit",
+        prometheus::exponential_buckets(10_000.0, 2.0, 8).expect("invariant: valid buckets")
+    )
+    .expect("invariant: metric is registered once")
+});
+
+/// Relative difference between estimated and consumed compute units, helps to tune the buffer.
+pub static COMPUTE_UNIT_ESTIMATION_ERROR_RATIO: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "compute_unit_estimation_error_ratio",
+        "Relative difference between estimated and consumed compute units",
+        vec![0.01, 0.05, 0.1, 0.15, 0.2, 0.3, 0.5, 1.0]
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static SEARCH_QUERIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "moonzip_search_queries_total",
+        "Project search queries, by search mode",
+        &["mode"]
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static SEARCH_CACHE_HITS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "moonzip_search_cache_hits_total",
+        "Project search queries, served from cache"
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static MZIP_KEYPAIR_AVAILABLE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "mzip_keypair_available",
+        "Pre-generated mint keypairs, left for new projects"
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static MIGRATION_LOCK_STUCK: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "migration_lock_stuck_total",
+        "Project migration leases, expired before their migrations committed"
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static IPFS_DEDUP_SAVED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "ipfs_dedup_saved_total",
+        "IPFS uploads, skipped as the same content is already uploaded, by content kind",
+        &["kind"]
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static PUMPFUN_LIVE_FETCH: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "pumpfun_live_fetch_total",
+        "Pumpfun bonding curves, fetched from chain to build trades"
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static CHAIN_SYNC_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "chain_sync_events_total",
+        "Chain events, routed by chain syncer, by event kind",
+        &["event"]
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static RAYDIUM_STATE_SYNC_LATENCY: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "raydium_state_sync_latency_seconds",
+        "Time to sync raydium pools of all graduated projects"
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static MIGRATOR_ADVISORY_LOCK_CONTENTION: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "migrator_advisory_lock_contention_total",
+        "Project migrations, skipped as the project is locked by another replica"
+    )
+    .expect("invariant: metric is registered once")
+});
+
+pub static TX_DEDUPLICATED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "moonzip_tx_deduplicated_total",
+        "Transaction submissions, skipped as the same transaction has already landed"
+    )
+    .expect("invariant: metric is registered once")
+});
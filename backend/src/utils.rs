@@ -32,6 +32,11 @@ macro_rules! define_discriminator {
 
 pub const ANCHOR_DISCRIMINATOR_BYTE_SIZE: usize = 8;
 
+/// Lowercase hex, same as `sha256sum` prints hashes.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
 pub fn find_program_address_with_u64_nonce(
     seeds: &[&[u8]],
     program_id: &Pubkey,
@@ -51,3 +56,13 @@ pub fn find_program_address_with_u64_nonce(
     }
     anyhow::bail!("unable to find valid program address for seed {seeds:?}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats_bytes_as_hex() {
+        assert_eq!(to_hex(&[0x00, 0x0f, 0xab, 0xff]), "000fabff");
+    }
+}
@@ -0,0 +1,191 @@
+//! Program authority could be rotated, e.g. if its key is compromised or moved to a multisig.
+//!
+//! Until the [`AuthorityConfig`] is initialized, the authority is [`PROGRAM_AUTHORITY`],
+//! compiled into the program. Afterwards it's [`AuthorityConfig::current`], which is replaced
+//! only after [`AUTHORITY_ROTATION_DELAY`] since the proposal, and only with the new
+//! authority's signature, so a typo in the proposed key can't lock the program.
+use crate::{ensure_account_size, utils::Sizable, PROGRAM_AUTHORITY};
+use anchor_lang::prelude::*;
+use std::ops::Deref;
+
+pub const AUTHORITY_CONFIG_PREFIX: &[u8] = b"authority_config";
+
+/// Minimal delay between the proposal and the rotation.
+pub const AUTHORITY_ROTATION_DELAY: i64 = 7 * 24 * 60 * 60;
+
+pub fn authority_config_address() -> Pubkey {
+    let (address, _) = Pubkey::find_program_address(&[AUTHORITY_CONFIG_PREFIX], &crate::ID);
+    address
+}
+
+/// Check of the `authority` signer of every privileged instruction,
+/// `authority_config` must be derived from [`AUTHORITY_CONFIG_PREFIX`].
+///
+/// The config is missing until the authority is rotated, so it's passed unchecked with only
+/// its address constrained: if it were optional, the compiled authority could omit it afterwards.
+pub fn is_program_authority(key: &Pubkey, authority_config: &AccountInfo) -> bool {
+    if authority_config.data_is_empty() {
+        return key == &PROGRAM_AUTHORITY;
+    }
+    if authority_config.owner != &crate::ID {
+        return false;
+    }
+    let Ok(data) = authority_config.try_borrow_data() else {
+        return false;
+    };
+    AuthorityConfig::try_deserialize(&mut &data[..])
+        .map(|config| key == &config.current)
+        .unwrap_or(false)
+}
+
+pub fn init_authority_config(ctx: Context<InitAuthorityConfigAccounts>) -> Result<()> {
+    ctx.accounts.authority_config.set_inner(AuthorityConfig {
+        current: ctx.accounts.authority.key(),
+        pending: None,
+        effective_at: 0,
+        bump: ctx.bumps.authority_config,
+    });
+    Ok(())
+}
+
+/// Replaces the pending rotation if there is one, so the mistaken proposal could be fixed.
+pub fn propose_authority_rotation(
+    ctx: Context<ProposeAuthorityRotationAccounts>,
+    data: ProposeAuthorityRotationData,
+) -> Result<()> {
+    let config = &mut ctx.accounts.authority_config;
+    if data.new_authority == config.current {
+        return err!(AuthorityError::SameAuthority);
+    }
+    let effective_at = Clock::get()?
+        .unix_timestamp
+        .saturating_add(AUTHORITY_ROTATION_DELAY);
+    config.pending = Some(data.new_authority);
+    config.effective_at = effective_at;
+    msg!(
+        "authority rotation to {} is proposed, effective at {}",
+        data.new_authority,
+        effective_at
+    );
+    Ok(())
+}
+
+pub fn finalize_authority_rotation(ctx: Context<FinalizeAuthorityRotationAccounts>) -> Result<()> {
+    let config = &mut ctx.accounts.authority_config;
+    if Clock::get()?.unix_timestamp < config.effective_at {
+        msg!(
+            "authority could be rotated only after {}",
+            config.effective_at
+        );
+        return err!(AuthorityError::RotationDelayNotPassed);
+    }
+    msg!(
+        "authority is rotated from {} to {}",
+        config.current,
+        ctx.accounts.new_authority.key
+    );
+    config.current = ctx.accounts.new_authority.key();
+    config.pending = None;
+    config.effective_at = 0;
+    Ok(())
+}
+
+/// Authority of the program, replacing [`PROGRAM_AUTHORITY`] once initialized.
+#[account]
+#[derive(Debug)]
+pub struct AuthorityConfig {
+    pub current: Pubkey,
+    /// Proposed authority, which could take over after `effective_at`.
+    pub pending: Option<Pubkey>,
+    pub effective_at: i64,
+    pub bump: u8,
+}
+
+impl Sizable for AuthorityConfig {
+    fn longest() -> Self {
+        AuthorityConfig {
+            current: Pubkey::default(),
+            pending: Some(Pubkey::default()),
+            effective_at: Sizable::longest(),
+            bump: Sizable::longest(),
+        }
+    }
+}
+
+ensure_account_size!(AuthorityConfig, 82);
+
+#[derive(Accounts)]
+pub struct InitAuthorityConfigAccounts<'info> {
+    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AuthorityConfig::ACCOUNT_SIZE,
+        seeds = [AUTHORITY_CONFIG_PREFIX], bump,
+    )]
+    pub authority_config: Account<'info, AuthorityConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Program authority, signing a privileged instruction.
+///
+/// Derefs to the signer, so instructions could use it as one.
+#[derive(Accounts)]
+pub struct ProgramAuthorityAccounts<'info> {
+    #[account(mut, constraint = is_program_authority(authority.key, &authority_config))]
+    pub authority: Signer<'info>,
+
+    /// CHECK: see [`is_program_authority`]
+    #[account(seeds = [AUTHORITY_CONFIG_PREFIX], bump)]
+    pub authority_config: UncheckedAccount<'info>,
+}
+
+impl<'info> Deref for ProgramAuthorityAccounts<'info> {
+    type Target = Signer<'info>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.authority
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ProposeAuthorityRotationData {
+    pub new_authority: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthorityRotationAccounts<'info> {
+    #[account(mut, constraint = authority.key == &authority_config.current)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [AUTHORITY_CONFIG_PREFIX], bump = authority_config.bump)]
+    pub authority_config: Account<'info, AuthorityConfig>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeAuthorityRotationAccounts<'info> {
+    #[account(
+        mut,
+        constraint = authority_config.pending.as_ref() == Some(new_authority.key)
+            @ AuthorityError::NotPendingAuthority
+    )]
+    pub new_authority: Signer<'info>,
+
+    #[account(mut, seeds = [AUTHORITY_CONFIG_PREFIX], bump = authority_config.bump)]
+    pub authority_config: Account<'info, AuthorityConfig>,
+}
+
+#[error_code]
+pub enum AuthorityError {
+    #[msg("Proposed authority is already the current one")]
+    SameAuthority,
+
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+
+    #[msg("Authority rotation delay has not passed yet")]
+    RotationDelayNotPassed,
+}
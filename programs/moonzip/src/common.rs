@@ -13,6 +13,10 @@ const ALLOWED_TIME_DRIFT_SECONDS: u64 = 1;
 pub struct PoolCloseConditions {
     pub max_lamports: Option<u64>,
     pub finish_ts: Option<u64>,
+    /// Once `max_lamports` is reached, pool still accepts sells for this long, but no buys.
+    pub grace_period_secs: Option<u64>,
+    /// Pool, which collected less by `finish_ts`, fails and refunds its buyers.
+    pub min_lamports: Option<u64>,
 }
 
 impl Sizable for PoolCloseConditions {
@@ -20,20 +24,30 @@ impl Sizable for PoolCloseConditions {
         Self {
             max_lamports: Some(Sizable::longest()),
             finish_ts: Some(Sizable::longest()),
+            grace_period_secs: Some(Sizable::longest()),
+            min_lamports: Some(Sizable::longest()),
         }
     }
 }
 
 impl PoolCloseConditions {
     pub fn should_be_closed(&self, balance: u64, current_ts: u64) -> bool {
-        let mut is_closed = false;
-        if let Some(max_lamports) = self.max_lamports {
-            is_closed = is_closed || (balance == max_lamports);
-        }
-        if let Some(finish_ts) = self.finish_ts {
-            is_closed = is_closed || (current_ts >= finish_ts - ALLOWED_TIME_DRIFT_SECONDS)
-        }
-        is_closed
+        self.limit_reached(balance) || self.deadline_passed(current_ts)
+    }
+
+    pub fn limit_reached(&self, balance: u64) -> bool {
+        self.max_lamports
+            .is_some_and(|max_lamports| balance == max_lamports)
+    }
+
+    pub fn goal_missed(&self, balance: u64) -> bool {
+        self.min_lamports
+            .is_some_and(|min_lamports| balance < min_lamports)
+    }
+
+    pub fn deadline_passed(&self, current_ts: u64) -> bool {
+        self.finish_ts
+            .is_some_and(|finish_ts| current_ts >= finish_ts - ALLOWED_TIME_DRIFT_SECONDS)
     }
 
     pub fn validate(&self) -> Result<()> {
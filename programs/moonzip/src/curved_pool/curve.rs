@@ -1,12 +1,72 @@
-use crate::{fee::BasisPoints, utils::Sizable};
+use crate::{fee::TradeFee, utils::Sizable};
 use anchor_lang::{AnchorDeserialize, AnchorSerialize};
 
+/// Prices of the linear curve are in lamports per this amount of token base units,
+/// as a single base unit is worth a fraction of lamport.
+pub const LINEAR_PRICE_SCALE: u128 = 1_000_000_000;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, PartialOrd, AnchorSerialize, AnchorDeserialize, Default,
+)]
+pub enum CurveKind {
+    /// Product of virtual reserves stays the same on every trade.
+    #[default]
+    ConstantProduct,
+    /// Price is `base_price + slope_numerator / slope_denominator * tokens_sold`,
+    /// see [`LINEAR_PRICE_SCALE`] for units.
+    Linear {
+        base_price: u64,
+        slope_numerator: u64,
+        slope_denominator: u64,
+    },
+}
+
+impl CurveKind {
+    /// Linear curve must not divide by zero or give tokens away for free.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            CurveKind::ConstantProduct => true,
+            CurveKind::Linear {
+                base_price,
+                slope_numerator,
+                slope_denominator,
+            } => *slope_denominator > 0 && (*base_price > 0 || *slope_numerator > 0),
+        }
+    }
+
+    fn linear(&self) -> Option<LinearCurve> {
+        match *self {
+            CurveKind::ConstantProduct => None,
+            CurveKind::Linear {
+                base_price,
+                slope_numerator,
+                slope_denominator,
+            } => Some(LinearCurve {
+                base_price: base_price as u128,
+                slope_numerator: slope_numerator as u128,
+                slope_denominator: slope_denominator as u128,
+            }),
+        }
+    }
+}
+
+impl Sizable for CurveKind {
+    fn longest() -> Self {
+        CurveKind::Linear {
+            base_price: Sizable::longest(),
+            slope_numerator: Sizable::longest(),
+            slope_denominator: Sizable::longest(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, AnchorSerialize, AnchorDeserialize)]
 pub struct CurveConfig {
     pub initial_virtual_sol_reserves: u64,
     pub initial_virtual_token_reserves: u64,
     pub initial_real_token_reserves: u64,
     pub total_token_supply: u64,
+    pub kind: CurveKind,
 }
 
 impl Default for CurveConfig {
@@ -16,6 +76,7 @@ impl Default for CurveConfig {
             initial_virtual_sol_reserves: 30000000000,
             initial_real_token_reserves: 793100000000000,
             total_token_supply: 1000000000000000,
+            kind: CurveKind::ConstantProduct,
         }
     }
 }
@@ -27,6 +88,7 @@ impl Sizable for CurveConfig {
             initial_virtual_token_reserves: Sizable::longest(),
             initial_real_token_reserves: Sizable::longest(),
             total_token_supply: Sizable::longest(),
+            kind: Sizable::longest(),
         }
     }
 }
@@ -40,6 +102,9 @@ pub struct CurveState {
     pub real_token_reserves: u64,
     pub real_sol_reserves: u64,
     pub total_token_supply: u64,
+    pub kind: CurveKind,
+    /// Tokens bought from the curve and not sold back, moves the price of the linear curve.
+    pub tokens_sold: u64,
 }
 
 impl Sizable for CurveState {
@@ -50,6 +115,34 @@ impl Sizable for CurveState {
             real_token_reserves: Sizable::longest(),
             real_sol_reserves: Sizable::longest(),
             total_token_supply: Sizable::longest(),
+            kind: Sizable::longest(),
+            tokens_sold: Sizable::longest(),
+        }
+    }
+}
+
+/// Layout of [`CurveState`] before curve kinds were introduced, stored by legacy accounts.
+#[derive(AnchorDeserialize, Debug, Clone, Copy)]
+pub(crate) struct LegacyCurveState {
+    virtual_token_reserves: u64,
+    virtual_sol_reserves: u64,
+    real_token_reserves: u64,
+    real_sol_reserves: u64,
+    total_token_supply: u64,
+}
+
+/// Legacy curves are constant product ones, which aren't priced by `tokens_sold`,
+/// so it's started from zero.
+impl From<LegacyCurveState> for CurveState {
+    fn from(legacy: LegacyCurveState) -> Self {
+        Self {
+            virtual_token_reserves: legacy.virtual_token_reserves,
+            virtual_sol_reserves: legacy.virtual_sol_reserves,
+            real_token_reserves: legacy.real_token_reserves,
+            real_sol_reserves: legacy.real_sol_reserves,
+            total_token_supply: legacy.total_token_supply,
+            kind: CurveKind::ConstantProduct,
+            tokens_sold: 0,
         }
     }
 }
@@ -62,6 +155,8 @@ impl CurveState {
             real_token_reserves: cfg.initial_real_token_reserves,
             real_sol_reserves: 0,
             total_token_supply: cfg.total_token_supply,
+            kind: cfg.kind,
+            tokens_sold: 0,
         }
     }
 
@@ -79,6 +174,8 @@ impl CurveState {
 
         self.real_sol_reserves += sols;
         self.virtual_sol_reserves += sols;
+
+        self.tokens_sold += tokens;
     }
 
     pub fn commit_sell(&mut self, tokens: u64, sols: u64) {
@@ -87,6 +184,17 @@ impl CurveState {
 
         self.real_sol_reserves -= sols;
         self.virtual_sol_reserves -= sols;
+
+        // pools, migrated from constant product layout, start counting from zero
+        self.tokens_sold = self.tokens_sold.saturating_sub(tokens);
+    }
+
+    /// Lamports per token as `(numerator, denominator)`.
+    pub fn spot_price(&self) -> (u64, u64) {
+        match self.kind.linear() {
+            None => (self.virtual_sol_reserves, self.virtual_token_reserves),
+            Some(linear) => linear.spot_price(self.tokens_sold as u128),
+        }
     }
 
     /// Calculate the product of virtual reserves using u128 to avoid overflow
@@ -98,7 +206,8 @@ impl CurveState {
 pub trait CalcBuy {
     /// Shows how much tokens would be received for given fixed amount of sols
     fn fixed_sols(&self, sols: u64) -> u64;
-    /// Shows how much sols are needed to buy a given fixed amount of tokens
+    /// Shows how much sols are needed to buy a given fixed amount of tokens,
+    /// `u64::MAX` if the curve can't give that much
     fn fixed_tokens(&self, tokens: u64) -> u64;
 }
 
@@ -111,12 +220,17 @@ impl<'a> BuyCalculator<'a> {
         Self { curve }
     }
 
-    pub fn with_fee(self, fee: BasisPoints) -> impl CalcBuy + 'a {
+    pub fn with_fee(self, fee: TradeFee) -> BuyCalculatorWithFee<'a> {
         BuyCalculatorWithFee::new(self, fee)
     }
 }
 impl<'a> CalcBuy for BuyCalculator<'a> {
     fn fixed_sols(&self, sols: u64) -> u64 {
+        if let Some(linear) = self.curve.kind.linear() {
+            let tokens = linear.tokens_for_sols(self.curve.tokens_sold as u128, sols as u128);
+            return saturating_u64(tokens);
+        }
+
         let constant = self.curve.constant();
 
         // Calculate the new virtual sol reserves after the purchase
@@ -133,29 +247,37 @@ impl<'a> CalcBuy for BuyCalculator<'a> {
     }
 
     fn fixed_tokens(&self, tokens: u64) -> u64 {
-        let constant = self.curve.constant();
-        let new_tokens_reserves = self.curve.virtual_token_reserves as u128 + tokens as u128;
-        let new_sol_reserves = constant / new_tokens_reserves + 1;
-        self.curve
-            .virtual_sol_reserves
-            .saturating_sub(new_sol_reserves as u64)
+        if let Some(linear) = self.curve.kind.linear() {
+            let sols = linear
+                .scaled_cost(self.curve.tokens_sold as u128, tokens as u128)
+                .div_ceil(linear.scale());
+            return saturating_u64(sols);
+        }
+
+        if tokens >= self.curve.virtual_token_reserves {
+            return u64::MAX;
+        }
+        // rounded up, so the buyer never pays less, than the curve asks
+        let new_token_reserves = (self.curve.virtual_token_reserves - tokens) as u128;
+        let new_sol_reserves = self.curve.constant().div_ceil(new_token_reserves);
+        saturating_u64(new_sol_reserves.saturating_sub(self.curve.virtual_sol_reserves as u128))
     }
 }
 
 pub struct BuyCalculatorWithFee<'a> {
     calculator: BuyCalculator<'a>,
-    fee: BasisPoints,
+    fee: TradeFee,
 }
 
 impl<'a> BuyCalculatorWithFee<'a> {
-    pub fn new(calculator: BuyCalculator<'a>, fee: BasisPoints) -> Self {
+    pub fn new(calculator: BuyCalculator<'a>, fee: TradeFee) -> Self {
         Self { calculator, fee }
     }
 }
 
 impl<'a> CalcBuy for BuyCalculatorWithFee<'a> {
     fn fixed_sols(&self, sols: u64) -> u64 {
-        let fee = self.fee.part_of(sols);
+        let fee = self.fee.compute(sols);
         let resulting_sol = sols.saturating_sub(fee);
         self.calculator.fixed_sols(resulting_sol)
     }
@@ -184,13 +306,19 @@ impl<'a> SellCalculator<'a> {
         Self { curve }
     }
 
-    pub fn with_fee(self, fee: BasisPoints) -> impl CalcSell + 'a {
+    pub fn with_fee(self, fee: TradeFee) -> SellCalculatorWithFee<'a> {
         SellCalculatorWithFee::new(self, fee)
     }
 }
 
 impl<'a> CalcSell for SellCalculator<'a> {
     fn fixed_tokens(&self, tokens: u64) -> u64 {
+        if let Some(linear) = self.curve.kind.linear() {
+            let tokens = tokens.min(self.curve.tokens_sold) as u128;
+            let sold_after = self.curve.tokens_sold as u128 - tokens;
+            return saturating_u64(linear.scaled_cost(sold_after, tokens) / linear.scale());
+        }
+
         let constant = self.curve.constant();
         let new_token_reserves = self.curve.virtual_token_reserves as u128 + tokens as u128;
         let new_sol_reserves = constant / new_token_reserves + 1;
@@ -200,6 +328,17 @@ impl<'a> CalcSell for SellCalculator<'a> {
     }
 
     fn fixed_sols(&self, sols: u64) -> u64 {
+        if let Some(linear) = self.curve.kind.linear() {
+            let sold = self.curve.tokens_sold as u128;
+            let tokens = linear.tokens_to_receive(sold, sols as u128);
+            // root beyond sold tokens would mean selling below the curve start
+            return if tokens > sold {
+                u64::MAX
+            } else {
+                tokens as u64
+            };
+        }
+
         let constant = self.curve.constant();
         let new_token_reserves =
             constant / (self.curve.virtual_sol_reserves as u128 - sols as u128) + 1;
@@ -209,11 +348,11 @@ impl<'a> CalcSell for SellCalculator<'a> {
 
 pub struct SellCalculatorWithFee<'a> {
     calculator: SellCalculator<'a>,
-    fee: BasisPoints,
+    fee: TradeFee,
 }
 
 impl<'a> SellCalculatorWithFee<'a> {
-    pub fn new(calculator: SellCalculator<'a>, fee: BasisPoints) -> Self {
+    pub fn new(calculator: SellCalculator<'a>, fee: TradeFee) -> Self {
         Self { calculator, fee }
     }
 }
@@ -221,17 +360,115 @@ impl<'a> SellCalculatorWithFee<'a> {
 impl<'a> CalcSell for SellCalculatorWithFee<'a> {
     fn fixed_tokens(&self, tokens: u64) -> u64 {
         let result = self.calculator.fixed_tokens(tokens);
-        let fee = self.fee.part_of(result);
+        let fee = self.fee.compute(result);
         result.saturating_sub(fee)
     }
 
     fn fixed_sols(&self, sols: u64) -> u64 {
-        let fee = self.fee.part_of(sols);
+        let fee = self.fee.compute(sols);
         let resulting_sols = sols.saturating_sub(fee);
         self.calculator.fixed_sols(resulting_sols)
     }
 }
 
+/// Cost of tokens is the area under the price line, so all of it is computed
+/// in `u128` and multiplied by `2 * slope_denominator * LINEAR_PRICE_SCALE` to stay integer.
+struct LinearCurve {
+    base_price: u128,
+    slope_numerator: u128,
+    slope_denominator: u128,
+}
+
+impl LinearCurve {
+    fn scale(&self) -> u128 {
+        2u128
+            .saturating_mul(self.slope_denominator)
+            .saturating_mul(LINEAR_PRICE_SCALE)
+    }
+
+    /// Price at `sold`, multiplied by `slope_denominator`.
+    fn scaled_price(&self, sold: u128) -> u128 {
+        self.base_price
+            .saturating_mul(self.slope_denominator)
+            .saturating_add(self.slope_numerator.saturating_mul(sold))
+    }
+
+    /// Lamports for `tokens` on top of `sold`, multiplied by [`Self::scale`].
+    fn scaled_cost(&self, sold: u128, tokens: u128) -> u128 {
+        let base = 2u128
+            .saturating_mul(self.base_price)
+            .saturating_mul(self.slope_denominator)
+            .saturating_mul(tokens);
+        let slope = self
+            .slope_numerator
+            .saturating_mul(tokens)
+            .saturating_mul(sold.saturating_mul(2).saturating_add(tokens));
+        base.saturating_add(slope)
+    }
+
+    /// Most tokens on top of `sold`, which cost at most `sols`, rounded down.
+    /// Positive root of `num * t^2 + 2 * price(sold) * t - scale * sols = 0`.
+    fn tokens_for_sols(&self, sold: u128, sols: u128) -> u128 {
+        let half_b = self.scaled_price(sold);
+        let c = self.scale().saturating_mul(sols);
+        if self.slope_numerator == 0 {
+            return c / half_b.saturating_mul(2).max(1);
+        }
+        let discriminant = half_b
+            .saturating_mul(half_b)
+            .saturating_add(self.slope_numerator.saturating_mul(c));
+        (isqrt(discriminant).saturating_sub(half_b)) / self.slope_numerator
+    }
+
+    /// Fewest tokens below `sold`, which return at least `sols`, rounded up.
+    /// Smaller root of `num * t^2 - 2 * price(sold) * t + scale * sols = 0`.
+    fn tokens_to_receive(&self, sold: u128, sols: u128) -> u128 {
+        let half_b = self.scaled_price(sold);
+        let c = self.scale().saturating_mul(sols);
+        if self.slope_numerator == 0 {
+            return c.div_ceil(half_b.saturating_mul(2).max(1));
+        }
+        let Some(discriminant) = half_b
+            .saturating_mul(half_b)
+            .checked_sub(self.slope_numerator.saturating_mul(c))
+        else {
+            // even all the sold tokens don't return that much
+            return u128::MAX;
+        };
+        (half_b - isqrt(discriminant)).div_ceil(self.slope_numerator)
+    }
+
+    fn spot_price(&self, sold: u128) -> (u64, u64) {
+        let mut numerator = self.scaled_price(sold);
+        let mut denominator = self.slope_denominator.saturating_mul(LINEAR_PRICE_SCALE);
+        // both are shifted, so the ratio is kept with the highest precision fitting u64
+        while numerator > u64::MAX as u128 || denominator > u64::MAX as u128 {
+            numerator >>= 1;
+            denominator >>= 1;
+        }
+        (numerator as u64, denominator.max(1) as u64)
+    }
+}
+
+fn saturating_u64(value: u128) -> u64 {
+    value.min(u64::MAX as u128) as u64
+}
+
+/// Floor of the square root, `u128::isqrt` isn't available on the program toolchain.
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = 1u128 << ((128 - value.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + value / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -244,7 +481,209 @@ mod tests {
                 real_token_reserves: 793100000000000,
                 real_sol_reserves: 0,
                 total_token_supply: 1000000000000000,
+                kind: CurveKind::ConstantProduct,
+                tokens_sold: 0,
             }
         }
+
+        fn linear(base_price: u64, slope_numerator: u64, slope_denominator: u64) -> Self {
+            Self::from_cfg(&CurveConfig {
+                kind: CurveKind::Linear {
+                    base_price,
+                    slope_numerator,
+                    slope_denominator,
+                },
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_keeps_constant_product_math() {
+        let curve = CurveState::intial_pumpfun();
+        let tokens = BuyCalculator::new(&curve).fixed_sols(1_000_000_000);
+        // 1073e12 - 30e9 * 1073e12 / 31e9 - 1
+        assert_eq!(tokens, 34_612_903_225_806);
+        assert_eq!(curve.spot_price(), (30000000000, 1073000000000000));
+    }
+
+    #[test]
+    fn test_prices_exact_tokens_as_fixed_sols() {
+        let curve = CurveState::intial_pumpfun();
+        // from a lamport up to ~1000 SOL
+        for sols in [
+            1,
+            1_000,
+            999_999,
+            1_000_000_000,
+            85_005_359_057,
+            (1 << 40) - 1,
+        ] {
+            let tokens = BuyCalculator::new(&curve).fixed_sols(sols);
+            let cost = BuyCalculator::new(&curve).fixed_tokens(tokens);
+            assert!(cost <= sols, "{tokens} tokens cost {cost} > {sols}");
+            assert!(sols - cost <= 2, "{tokens} tokens cost {cost} << {sols}");
+        }
+    }
+
+    #[test]
+    fn test_prices_large_exact_tokens() {
+        let curve = CurveState::intial_pumpfun();
+        let tokens = curve.real_token_reserves;
+        let cost = BuyCalculator::new(&curve).fixed_tokens(tokens);
+        // ceil(30e9 * 1073e12 / (1073e12 - 793.1e12)) - 30e9
+        assert_eq!(cost, 85_005_359_057);
+        assert!(BuyCalculator::new(&curve).fixed_sols(cost) + 1 >= tokens);
+
+        let mut bought = curve;
+        bought.commit_buy(cost, tokens);
+        let returned = SellCalculator::new(&bought).fixed_tokens(tokens);
+        assert!(returned <= cost, "round trip returns {returned} > {cost}");
+
+        let all = curve.virtual_token_reserves;
+        assert_eq!(BuyCalculator::new(&curve).fixed_tokens(all), u64::MAX);
+        assert_eq!(BuyCalculator::new(&curve).fixed_tokens(all + 1), u64::MAX);
+    }
+
+    #[test]
+    fn test_computes_integer_square_root() {
+        for value in [
+            0u128,
+            1,
+            2,
+            3,
+            4,
+            15,
+            16,
+            17,
+            1 << 64,
+            u64::MAX as u128,
+            u128::MAX,
+        ] {
+            let root = isqrt(value);
+            assert!(root * root <= value);
+            assert!(root
+                .checked_add(1)
+                .and_then(|next| next.checked_mul(next))
+                .map_or(true, |next| next > value));
+        }
+    }
+
+    #[test]
+    fn test_buys_at_flat_price() {
+        // 2 lamports per 1e9 base units
+        let curve = CurveState::linear(2, 0, 1);
+        assert_eq!(BuyCalculator::new(&curve).fixed_sols(10), 5_000_000_000);
+        assert_eq!(BuyCalculator::new(&curve).fixed_tokens(5_000_000_000), 10);
+        assert_eq!(curve.spot_price(), (2, 1_000_000_000));
+    }
+
+    #[test]
+    fn test_raises_linear_price_with_sold_tokens() {
+        let mut curve = CurveState::linear(10, 1, 1_000_000_000);
+        let (before, _) = curve.spot_price();
+
+        let sols = 1_000_000_000;
+        let tokens = BuyCalculator::new(&curve).fixed_sols(sols);
+        assert!(BuyCalculator::new(&curve).fixed_tokens(tokens) <= sols);
+        assert!(BuyCalculator::new(&curve).fixed_tokens(tokens + 1) > sols);
+        curve.commit_buy(sols, tokens);
+        assert_eq!(curve.tokens_sold, tokens);
+
+        let (after, _) = curve.spot_price();
+        assert!(after > before);
+        // the next lamport buys fewer tokens
+        let next = BuyCalculator::new(&curve).fixed_sols(sols);
+        assert!(next < tokens);
+    }
+
+    #[test]
+    fn test_sells_back_without_profit() {
+        let mut curve = CurveState::linear(10, 3, 1_000_000_000);
+        let sols = 5_000_000_000;
+        let tokens = BuyCalculator::new(&curve).fixed_sols(sols);
+        curve.commit_buy(sols, tokens);
+
+        let returned = SellCalculator::new(&curve).fixed_tokens(tokens);
+        assert!(returned <= sols);
+        assert!(sols - returned <= 1);
+
+        let half = SellCalculator::new(&curve).fixed_tokens(tokens / 2);
+        let to_sell = SellCalculator::new(&curve).fixed_sols(half);
+        assert!(to_sell <= tokens / 2 + 1);
+        assert!(SellCalculator::new(&curve).fixed_tokens(to_sell) >= half);
+
+        // more than sold can't be returned
+        assert_eq!(
+            SellCalculator::new(&curve).fixed_tokens(tokens + 1_000),
+            returned
+        );
+        assert_eq!(SellCalculator::new(&curve).fixed_sols(sols + 1), u64::MAX);
+    }
+
+    #[test]
+    fn test_never_gives_more_with_fee() {
+        use crate::fee::BasisPoints;
+
+        let curves = [
+            CurveState::intial_pumpfun(),
+            CurveState::linear(2, 0, 1),
+            CurveState::linear(10, 3, 1_000_000_000),
+        ];
+        let fees = [
+            TradeFee::Percentage(BasisPoints(100)),
+            TradeFee::Percentage(BasisPoints(9_999)),
+            TradeFee::Flat(5_000),
+            TradeFee::MaxOf {
+                percentage: BasisPoints(100),
+                flat: 5_000,
+            },
+        ];
+        // up to ~1000 SOL, small amounts are the most sensitive to rounding
+        let amounts = [0, 1, 99, 10_000, 1_000_000_000, (1 << 40) - 1];
+        let cases = curves
+            .iter()
+            .flat_map(|curve| fees.iter().map(move |fee| (curve, *fee)))
+            .flat_map(|(curve, fee)| amounts.iter().map(move |sols| (curve, fee, *sols)));
+        for (curve, fee, sols) in cases {
+            let without_fee = BuyCalculator::new(curve).fixed_sols(sols);
+            let with_fee = BuyCalculator::new(curve).with_fee(fee).fixed_sols(sols);
+            assert!(
+                with_fee <= without_fee,
+                "{fee:?} gives {with_fee} > {without_fee} tokens for {sols} sols"
+            );
+
+            let tokens = without_fee;
+            let cost = BuyCalculator::new(curve).fixed_tokens(tokens);
+            let cost_with_fee = BuyCalculator::new(curve).with_fee(fee).fixed_tokens(tokens);
+            assert!(
+                cost_with_fee >= cost,
+                "{fee:?} makes {tokens} tokens cheaper: {cost_with_fee} < {cost}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_saturates_instead_of_overflow() {
+        let mut curve = CurveState::linear(u64::MAX, u64::MAX, 1);
+        curve.tokens_sold = u64::MAX / 2;
+        assert_eq!(BuyCalculator::new(&curve).fixed_sols(u64::MAX), 0);
+        assert_eq!(BuyCalculator::new(&curve).fixed_tokens(u64::MAX), u64::MAX);
+        let (numerator, denominator) = curve.spot_price();
+        assert!(numerator > 0 && denominator > 0);
+    }
+
+    #[test]
+    fn test_validates_curve_kind() {
+        assert!(CurveKind::ConstantProduct.is_valid());
+        let linear = |base_price, slope_numerator, slope_denominator| CurveKind::Linear {
+            base_price,
+            slope_numerator,
+            slope_denominator,
+        };
+        assert!(linear(1, 0, 1).is_valid());
+        assert!(linear(0, 1, 1).is_valid());
+        assert!(!linear(1, 1, 0).is_valid());
+        assert!(!linear(0, 0, 1).is_valid());
     }
 }
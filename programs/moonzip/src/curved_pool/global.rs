@@ -1,6 +1,12 @@
-use super::{curve::CurveConfig, CurvedPoolConfig};
-use crate::{ensure_account_size, utils::Sizable, PROGRAM_AUTHORITY};
-use anchor_lang::prelude::*;
+use super::{
+    curve::{CurveConfig, CurveKind},
+    CurvedPoolConfig, CurvedPoolError,
+};
+use crate::{
+    authority::*, ensure_account_size, static_pool::DEFAULT_MAX_DEADLINE_EXTENSION_SECS,
+    utils::Sizable,
+};
+use anchor_lang::{prelude::*, system_program};
 
 pub const GLOBAL_ACCOUNT_PREFIX: &[u8] = b"curved-pool-global-account";
 
@@ -8,6 +14,12 @@ pub fn set_global_config(
     ctx: Context<SetCurvedPoolGlobalConfigAccounts>,
     config: GlobalCurvedPoolConfig,
 ) -> Result<()> {
+    if !config.curve.kind.is_valid() {
+        return err!(CurvedPoolError::InvalidCurveKind);
+    }
+    if !config.pool.is_creator_fee_share_valid() {
+        return err!(CurvedPoolError::InvalidCreatorFeeShare);
+    }
     ctx.accounts.global.set_inner(GlobalCurvedPoolAccount {
         config,
         bump: ctx.bumps.global,
@@ -15,14 +27,92 @@ pub fn set_global_config(
     Ok(())
 }
 
+/// Global account of any earlier layout, e.g. created before the creation fee, curve kinds,
+/// pool trading limits, token programs or the deadline extension window were introduced,
+/// is rewritten into the current layout first, so it must be called once right after the upgrade.
+pub fn set_project_creation_fee(
+    ctx: Context<SetProjectCreationFeeAccounts>,
+    data: SetProjectCreationFeeData,
+) -> Result<()> {
+    let global_info = ctx.accounts.global.to_account_info();
+    let mut global = {
+        let data = global_info.try_borrow_data()?;
+        if data[..8] != GlobalCurvedPoolAccount::DISCRIMINATOR {
+            return err!(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+        }
+        match global_info.data_len() {
+            LegacyGlobalCurvedPoolAccount::ACCOUNT_SIZE => {
+                LegacyGlobalCurvedPoolAccount::deserialize(&mut &data[8..])?.into()
+            }
+            LegacyConstantCurveGlobalAccount::ACCOUNT_SIZE => {
+                LegacyConstantCurveGlobalAccount::deserialize(&mut &data[8..])?.into()
+            }
+            LegacyCurveKindGlobalAccount::ACCOUNT_SIZE => {
+                LegacyCurveKindGlobalAccount::deserialize(&mut &data[8..])?.into()
+            }
+            LegacyWalletLimitGlobalAccount::ACCOUNT_SIZE => {
+                LegacyWalletLimitGlobalAccount::deserialize(&mut &data[8..])?.into()
+            }
+            LegacyAntiSnipeGlobalAccount::ACCOUNT_SIZE => {
+                LegacyAntiSnipeGlobalAccount::deserialize(&mut &data[8..])?.into()
+            }
+            LegacySplTokenGlobalAccount::ACCOUNT_SIZE => {
+                LegacySplTokenGlobalAccount::deserialize(&mut &data[8..])?.into()
+            }
+            LegacyDeadlineGlobalAccount::ACCOUNT_SIZE => {
+                LegacyDeadlineGlobalAccount::deserialize(&mut &data[8..])?.into()
+            }
+            _ => GlobalCurvedPoolAccount::try_deserialize(&mut &data[..])?,
+        }
+    };
+
+    if global_info.data_len() != GlobalCurvedPoolAccount::ACCOUNT_SIZE {
+        let rent_minimum = Rent::get()?.minimum_balance(GlobalCurvedPoolAccount::ACCOUNT_SIZE);
+        let missing = rent_minimum.saturating_sub(global_info.lamports());
+        if missing > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: global_info.clone(),
+                    },
+                ),
+                missing,
+            )?;
+        }
+        global_info.realloc(GlobalCurvedPoolAccount::ACCOUNT_SIZE, false)?;
+    }
+
+    global.config.project_creation_fee_lamports = data.fee_lamports;
+    let mut data = global_info.try_borrow_mut_data()?;
+    global.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetProjectCreationFeeAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    /// CHECK: legacy layout can't be deserialized as [`GlobalCurvedPoolAccount`], so it's checked manually
+    #[account(mut, owner = crate::ID, seeds = [GLOBAL_ACCOUNT_PREFIX], bump)]
+    pub global: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SetProjectCreationFeeData {
+    pub fee_lamports: u64,
+}
+
 #[derive(Accounts)]
 pub struct SetCurvedPoolGlobalConfigAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(
         init_if_needed,
-        payer = authority,
+        payer = authority.authority,
         space = GlobalCurvedPoolAccount::ACCOUNT_SIZE, seeds = [GLOBAL_ACCOUNT_PREFIX], bump
     )]
     pub global: Account<'info, GlobalCurvedPoolAccount>,
@@ -46,13 +136,249 @@ impl Sizable for GlobalCurvedPoolAccount {
     }
 }
 
-ensure_account_size!(GlobalCurvedPoolAccount, 60);
+ensure_account_size!(GlobalCurvedPoolAccount, 154);
+
+/// Layout of [`CurveConfig`] before curve kinds, all curves were constant product.
+#[derive(AnchorDeserialize, AnchorSerialize, Default)]
+struct LegacyCurveConfig {
+    initial_virtual_sol_reserves: u64,
+    initial_virtual_token_reserves: u64,
+    initial_real_token_reserves: u64,
+    total_token_supply: u64,
+}
+
+impl From<LegacyCurveConfig> for CurveConfig {
+    fn from(legacy: LegacyCurveConfig) -> Self {
+        Self {
+            initial_virtual_sol_reserves: legacy.initial_virtual_sol_reserves,
+            initial_virtual_token_reserves: legacy.initial_virtual_token_reserves,
+            initial_real_token_reserves: legacy.initial_real_token_reserves,
+            total_token_supply: legacy.total_token_supply,
+            kind: CurveKind::ConstantProduct,
+        }
+    }
+}
+
+/// Layout of [`CurvedPoolConfig`] before per-wallet limits.
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub(super) struct LegacyCurvedPoolConfig {
+    min_tradeable_sol: Option<u64>,
+    min_sol_to_close: Option<u64>,
+}
+
+impl LegacyCurvedPoolConfig {
+    #[cfg(test)]
+    fn longest() -> Self {
+        Self {
+            min_tradeable_sol: Some(u64::MAX),
+            min_sol_to_close: Some(u64::MAX),
+        }
+    }
+}
+
+impl From<LegacyCurvedPoolConfig> for CurvedPoolConfig {
+    fn from(legacy: LegacyCurvedPoolConfig) -> Self {
+        Self {
+            min_tradeable_sol: legacy.min_tradeable_sol,
+            min_sol_to_close: legacy.min_sol_to_close,
+            max_wallet_tokens: None,
+            anti_snipe_slots: None,
+            creator_fee_share_bps: None,
+            token_program: anchor_spl::token::ID,
+        }
+    }
+}
+
+/// Layout of [`CurvedPoolConfig`] before anti-snipe cooldown.
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub(super) struct LegacyWalletLimitCurvedPoolConfig {
+    min_tradeable_sol: Option<u64>,
+    min_sol_to_close: Option<u64>,
+    max_wallet_tokens: Option<u64>,
+}
+
+impl From<LegacyWalletLimitCurvedPoolConfig> for CurvedPoolConfig {
+    fn from(legacy: LegacyWalletLimitCurvedPoolConfig) -> Self {
+        Self {
+            min_tradeable_sol: legacy.min_tradeable_sol,
+            min_sol_to_close: legacy.min_sol_to_close,
+            max_wallet_tokens: legacy.max_wallet_tokens,
+            anti_snipe_slots: None,
+            creator_fee_share_bps: None,
+            token_program: anchor_spl::token::ID,
+        }
+    }
+}
+
+/// Layout of [`CurvedPoolConfig`] before creator fee share.
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub(super) struct LegacyAntiSnipeCurvedPoolConfig {
+    min_tradeable_sol: Option<u64>,
+    min_sol_to_close: Option<u64>,
+    max_wallet_tokens: Option<u64>,
+    anti_snipe_slots: Option<u64>,
+}
+
+impl From<LegacyAntiSnipeCurvedPoolConfig> for CurvedPoolConfig {
+    fn from(legacy: LegacyAntiSnipeCurvedPoolConfig) -> Self {
+        Self {
+            min_tradeable_sol: legacy.min_tradeable_sol,
+            min_sol_to_close: legacy.min_sol_to_close,
+            max_wallet_tokens: legacy.max_wallet_tokens,
+            anti_snipe_slots: legacy.anti_snipe_slots,
+            creator_fee_share_bps: None,
+            token_program: anchor_spl::token::ID,
+        }
+    }
+}
+
+/// Layout of [`CurvedPoolConfig`] before the token program, all mints were of SPL Token.
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub(super) struct LegacySplTokenCurvedPoolConfig {
+    min_tradeable_sol: Option<u64>,
+    min_sol_to_close: Option<u64>,
+    max_wallet_tokens: Option<u64>,
+    anti_snipe_slots: Option<u64>,
+    creator_fee_share_bps: Option<u16>,
+}
+
+impl From<LegacySplTokenCurvedPoolConfig> for CurvedPoolConfig {
+    fn from(legacy: LegacySplTokenCurvedPoolConfig) -> Self {
+        Self {
+            min_tradeable_sol: legacy.min_tradeable_sol,
+            min_sol_to_close: legacy.min_sol_to_close,
+            max_wallet_tokens: legacy.max_wallet_tokens,
+            anti_snipe_slots: legacy.anti_snipe_slots,
+            creator_fee_share_bps: legacy.creator_fee_share_bps,
+            token_program: anchor_spl::token::ID,
+        }
+    }
+}
+
+/// Layout of [`GlobalCurvedPoolAccount`] before the project creation fee.
+#[derive(AnchorDeserialize)]
+struct LegacyGlobalCurvedPoolAccount {
+    curve: LegacyCurveConfig,
+    token_decimals: u8,
+    pool: LegacyCurvedPoolConfig,
+    bump: u8,
+}
+
+impl LegacyGlobalCurvedPoolAccount {
+    const ACCOUNT_SIZE: usize = 60;
+}
+
+impl From<LegacyGlobalCurvedPoolAccount> for GlobalCurvedPoolAccount {
+    fn from(legacy: LegacyGlobalCurvedPoolAccount) -> Self {
+        Self {
+            config: GlobalCurvedPoolConfig {
+                curve: legacy.curve.into(),
+                token_decimals: legacy.token_decimals,
+                pool: legacy.pool.into(),
+                project_creation_fee_lamports: 0,
+                max_deadline_extension_secs: DEFAULT_MAX_DEADLINE_EXTENSION_SECS,
+            },
+            bump: legacy.bump,
+        }
+    }
+}
+
+/// Layout of [`GlobalCurvedPoolAccount`] before curve kinds and pool trading limits.
+#[derive(AnchorDeserialize)]
+struct LegacyConstantCurveGlobalAccount {
+    curve: LegacyCurveConfig,
+    token_decimals: u8,
+    pool: LegacyCurvedPoolConfig,
+    project_creation_fee_lamports: u64,
+    bump: u8,
+}
+
+impl LegacyConstantCurveGlobalAccount {
+    const ACCOUNT_SIZE: usize = 68;
+}
+
+impl From<LegacyConstantCurveGlobalAccount> for GlobalCurvedPoolAccount {
+    fn from(legacy: LegacyConstantCurveGlobalAccount) -> Self {
+        Self {
+            config: GlobalCurvedPoolConfig {
+                curve: legacy.curve.into(),
+                token_decimals: legacy.token_decimals,
+                pool: legacy.pool.into(),
+                project_creation_fee_lamports: legacy.project_creation_fee_lamports,
+                max_deadline_extension_secs: DEFAULT_MAX_DEADLINE_EXTENSION_SECS,
+            },
+            bump: legacy.bump,
+        }
+    }
+}
+
+/// Layout of [`GlobalCurvedPoolAccount`] with curve kinds, but an earlier pool config `P`.
+#[derive(AnchorDeserialize)]
+struct LegacyPoolConfigGlobalAccount<P> {
+    curve: CurveConfig,
+    token_decimals: u8,
+    pool: P,
+    project_creation_fee_lamports: u64,
+    bump: u8,
+}
+
+/// Before per-wallet limits.
+type LegacyCurveKindGlobalAccount = LegacyPoolConfigGlobalAccount<LegacyCurvedPoolConfig>;
+/// Before anti-snipe cooldown.
+type LegacyWalletLimitGlobalAccount =
+    LegacyPoolConfigGlobalAccount<LegacyWalletLimitCurvedPoolConfig>;
+/// Before creator fee share.
+type LegacyAntiSnipeGlobalAccount = LegacyPoolConfigGlobalAccount<LegacyAntiSnipeCurvedPoolConfig>;
+/// Before the token program of the pool.
+type LegacySplTokenGlobalAccount = LegacyPoolConfigGlobalAccount<LegacySplTokenCurvedPoolConfig>;
+/// Before the deadline extension window of static pools.
+type LegacyDeadlineGlobalAccount = LegacyPoolConfigGlobalAccount<CurvedPoolConfig>;
+
+impl LegacyCurveKindGlobalAccount {
+    const ACCOUNT_SIZE: usize = 93;
+}
+
+impl LegacyWalletLimitGlobalAccount {
+    const ACCOUNT_SIZE: usize = 102;
+}
+
+impl LegacyAntiSnipeGlobalAccount {
+    const ACCOUNT_SIZE: usize = 111;
+}
+
+impl LegacySplTokenGlobalAccount {
+    const ACCOUNT_SIZE: usize = 114;
+}
+
+impl LegacyDeadlineGlobalAccount {
+    const ACCOUNT_SIZE: usize = 146;
+}
+
+impl<P: Into<CurvedPoolConfig>> From<LegacyPoolConfigGlobalAccount<P>> for GlobalCurvedPoolAccount {
+    fn from(legacy: LegacyPoolConfigGlobalAccount<P>) -> Self {
+        Self {
+            config: GlobalCurvedPoolConfig {
+                curve: legacy.curve,
+                token_decimals: legacy.token_decimals,
+                pool: legacy.pool.into(),
+                project_creation_fee_lamports: legacy.project_creation_fee_lamports,
+                max_deadline_extension_secs: DEFAULT_MAX_DEADLINE_EXTENSION_SECS,
+            },
+            bump: legacy.bump,
+        }
+    }
+}
 
 #[derive(AnchorDeserialize, AnchorSerialize, Clone, PartialEq, PartialOrd, Debug)]
 pub struct GlobalCurvedPoolConfig {
     pub curve: CurveConfig,
     pub token_decimals: u8,
     pub pool: CurvedPoolConfig,
+    /// Taken from the creator on project creation, no fee if zero.
+    pub project_creation_fee_lamports: u64,
+    /// Static pool deadline could be pushed at most this far from the current one
+    /// (or from now, if unset).
+    pub max_deadline_extension_secs: u64,
 }
 
 impl Default for GlobalCurvedPoolConfig {
@@ -61,6 +387,8 @@ impl Default for GlobalCurvedPoolConfig {
             curve: Default::default(),
             token_decimals: 6,
             pool: Default::default(),
+            project_creation_fee_lamports: 0,
+            max_deadline_extension_secs: DEFAULT_MAX_DEADLINE_EXTENSION_SECS,
         }
     }
 }
@@ -71,6 +399,180 @@ impl Sizable for GlobalCurvedPoolConfig {
             curve: Sizable::longest(),
             token_decimals: Sizable::longest(),
             pool: Sizable::longest(),
+            project_creation_fee_lamports: Sizable::longest(),
+            max_deadline_extension_secs: Sizable::longest(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_knows_legacy_size() {
+        let legacy = (
+            LegacyCurveConfig::default(),
+            6u8,
+            LegacyCurvedPoolConfig::longest(),
+            0u8,
+        );
+        let mut serialized = GlobalCurvedPoolAccount::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut serialized).unwrap();
+        assert_eq!(
+            serialized.len(),
+            LegacyGlobalCurvedPoolAccount::ACCOUNT_SIZE
+        );
+    }
+
+    #[test]
+    fn test_migrates_constant_curve_layout() {
+        let legacy = (
+            LegacyCurveConfig {
+                initial_virtual_sol_reserves: 1,
+                initial_virtual_token_reserves: 2,
+                initial_real_token_reserves: 3,
+                total_token_supply: 4,
+            },
+            6u8,
+            LegacyCurvedPoolConfig::longest(),
+            500u64,
+            255u8,
+        );
+        let mut serialized = GlobalCurvedPoolAccount::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut serialized).unwrap();
+        assert_eq!(
+            serialized.len(),
+            LegacyConstantCurveGlobalAccount::ACCOUNT_SIZE
+        );
+
+        let migrated: GlobalCurvedPoolAccount =
+            LegacyConstantCurveGlobalAccount::deserialize(&mut &serialized[8..])
+                .unwrap()
+                .into();
+        assert_eq!(migrated.config.curve.kind, CurveKind::ConstantProduct);
+        assert_eq!(migrated.config.curve.total_token_supply, 4);
+        assert_eq!(migrated.config.project_creation_fee_lamports, 500);
+        assert_eq!(migrated.config.pool.max_wallet_tokens, None);
+        assert_eq!(migrated.config.pool.anti_snipe_slots, None);
+        assert_eq!(migrated.config.pool.creator_fee_share_bps, None);
+        assert_eq!(migrated.config.pool.token_program, anchor_spl::token::ID);
+        assert_eq!(migrated.bump, 255);
+    }
+
+    #[test]
+    fn test_migrates_pool_limits_layouts() {
+        let mut serialized = GlobalCurvedPoolAccount::DISCRIMINATOR.to_vec();
+        (
+            CurveConfig::longest(),
+            6u8,
+            LegacyCurvedPoolConfig::longest(),
+            500u64,
+            255u8,
+        )
+            .serialize(&mut serialized)
+            .unwrap();
+        assert_eq!(serialized.len(), LegacyCurveKindGlobalAccount::ACCOUNT_SIZE);
+
+        let mut serialized = GlobalCurvedPoolAccount::DISCRIMINATOR.to_vec();
+        (
+            CurveConfig::longest(),
+            6u8,
+            LegacyWalletLimitCurvedPoolConfig {
+                min_tradeable_sol: Some(u64::MAX),
+                min_sol_to_close: Some(u64::MAX),
+                max_wallet_tokens: Some(u64::MAX),
+            },
+            500u64,
+            255u8,
+        )
+            .serialize(&mut serialized)
+            .unwrap();
+        assert_eq!(
+            serialized.len(),
+            LegacyWalletLimitGlobalAccount::ACCOUNT_SIZE
+        );
+
+        let mut serialized = GlobalCurvedPoolAccount::DISCRIMINATOR.to_vec();
+        (
+            CurveConfig::longest(),
+            6u8,
+            LegacyAntiSnipeCurvedPoolConfig {
+                min_tradeable_sol: Some(u64::MAX),
+                min_sol_to_close: Some(u64::MAX),
+                max_wallet_tokens: Some(1_000),
+                anti_snipe_slots: Some(u64::MAX),
+            },
+            500u64,
+            255u8,
+        )
+            .serialize(&mut serialized)
+            .unwrap();
+        assert_eq!(serialized.len(), LegacyAntiSnipeGlobalAccount::ACCOUNT_SIZE);
+
+        let migrated: GlobalCurvedPoolAccount =
+            LegacyAntiSnipeGlobalAccount::deserialize(&mut &serialized[8..])
+                .unwrap()
+                .into();
+        assert_eq!(migrated.config.pool.max_wallet_tokens, Some(1_000));
+        assert_eq!(migrated.config.pool.anti_snipe_slots, Some(u64::MAX));
+        assert_eq!(migrated.config.pool.creator_fee_share_bps, None);
+        assert_eq!(migrated.config.pool.token_program, anchor_spl::token::ID);
+        assert_eq!(migrated.config.project_creation_fee_lamports, 500);
+        assert_eq!(migrated.bump, 255);
+    }
+
+    #[test]
+    fn test_migrates_spl_token_layout() {
+        let legacy = (
+            CurveConfig::longest(),
+            6u8,
+            LegacySplTokenCurvedPoolConfig {
+                min_tradeable_sol: Some(u64::MAX),
+                min_sol_to_close: Some(u64::MAX),
+                max_wallet_tokens: Some(u64::MAX),
+                anti_snipe_slots: Some(u64::MAX),
+                creator_fee_share_bps: Some(2_500),
+            },
+            500u64,
+            255u8,
+        );
+        let mut serialized = GlobalCurvedPoolAccount::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), LegacySplTokenGlobalAccount::ACCOUNT_SIZE);
+
+        let migrated: GlobalCurvedPoolAccount =
+            LegacySplTokenGlobalAccount::deserialize(&mut &serialized[8..])
+                .unwrap()
+                .into();
+        assert_eq!(migrated.config.pool.creator_fee_share_bps, Some(2_500));
+        assert_eq!(migrated.config.pool.token_program, anchor_spl::token::ID);
+        assert_eq!(migrated.config.project_creation_fee_lamports, 500);
+        assert_eq!(migrated.bump, 255);
+    }
+
+    #[test]
+    fn test_migrates_deadline_layout() {
+        let legacy = (
+            CurveConfig::longest(),
+            6u8,
+            CurvedPoolConfig::longest(),
+            500u64,
+            255u8,
+        );
+        let mut serialized = GlobalCurvedPoolAccount::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), LegacyDeadlineGlobalAccount::ACCOUNT_SIZE);
+
+        let migrated: GlobalCurvedPoolAccount =
+            LegacyDeadlineGlobalAccount::deserialize(&mut &serialized[8..])
+                .unwrap()
+                .into();
+        assert_eq!(
+            migrated.config.max_deadline_extension_secs,
+            DEFAULT_MAX_DEADLINE_EXTENSION_SECS
+        );
+        assert_eq!(migrated.config.project_creation_fee_lamports, 500);
+        assert_eq!(migrated.bump, 255);
+    }
+}
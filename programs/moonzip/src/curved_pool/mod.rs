@@ -1,32 +1,73 @@
 use crate::{
+    authority::*,
     ensure_account_size,
     events::{CurvedPoolBuyEvent, CurvedPoolSellEvent},
     fee::{take_fee, FeeAccount, FEE_ACCOUNT_PREFIX},
+    project::ProjectError,
+    static_pool::POOL_TOKEN_DECIMALS,
     utils::Sizable,
-    Project, ProjectId, ProjectStage, PROGRAM_AUTHORITY, PROJECT_PREFIX,
+    Project, ProjectId, ProjectStage, PROJECT_PREFIX,
 };
 use anchor_lang::{prelude::*, system_program};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, MintTo, Token, TokenAccount},
+    token_2022::spl_token_2022::{
+        self,
+        extension::{BaseStateWithExtensions as _, ExtensionType, StateWithExtensions},
+        instruction::AuthorityType,
+    },
+    token_interface::{
+        self, CloseAccount, Mint, MintTo, SetAuthority, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
 };
-use curve::{BuyCalculator, CalcBuy as _, CalcSell as _, CurveState, SellCalculator};
-use global::{GlobalCurvedPoolAccount, GLOBAL_ACCOUNT_PREFIX};
+use curve::{
+    BuyCalculator, CalcBuy as _, CalcSell as _, CurveConfig, CurveKind, CurveState,
+    LegacyCurveState, SellCalculator,
+};
+use global::{
+    GlobalCurvedPoolAccount, LegacyAntiSnipeCurvedPoolConfig, LegacyCurvedPoolConfig,
+    LegacySplTokenCurvedPoolConfig, LegacyWalletLimitCurvedPoolConfig, GLOBAL_ACCOUNT_PREFIX,
+};
+use stats::{ProjectStats, PROJECT_STATS_PREFIX};
 
 pub mod curve;
 pub mod global;
+pub mod stats;
 
 pub const CURVED_POOL_PREFIX: &[u8] = b"curved-pool";
+pub const CREATOR_FEE_WALLET_PREFIX: &[u8] = b"creator-fee-wallet";
 pub const DEFAULT_MIN_TRADEABLE_SOL: u64 = 1_000;
+pub const MAX_FEE_SHARE_BPS: u16 = 10_000;
 
 pub fn curved_pool_address(mint: Pubkey) -> Pubkey {
     Pubkey::find_program_address(&[CURVED_POOL_PREFIX, mint.as_ref()], &crate::ID).0
 }
 
+pub fn creator_fee_wallet_address(project: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CREATOR_FEE_WALLET_PREFIX, project.as_ref()], &crate::ID).0
+}
+
 pub fn create(ctx: Context<CreateCurvedPoolAccounts>, data: CreateCurvedPoolData) -> Result<()> {
+    // transmuter and static pool delivery move curve tokens by SPL Token only
+    if ctx.accounts.project.schema.uses_static_pool()
+        && ctx.accounts.token_program.key() != anchor_spl::token::ID
+    {
+        return err!(CurvedPoolError::StaticPoolRequiresSplToken);
+    }
     ctx.accounts.project.ensure_can_create_curved_pool()?;
+    if ctx.accounts.mint.decimals > POOL_TOKEN_DECIMALS {
+        return err!(CurvedPoolError::InvalidTokenDecimals);
+    }
+    let kind = data
+        .curve_kind_override
+        .unwrap_or(ctx.accounts.global.config.curve.kind);
+    if !kind.is_valid() {
+        return err!(CurvedPoolError::InvalidCurveKind);
+    }
+    ensure_supported_mint(&ctx.accounts.mint.to_account_info())?;
 
-    anchor_spl::token::mint_to(
+    token_interface::mint_to(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             MintTo {
@@ -37,14 +78,58 @@ pub fn create(ctx: Context<CreateCurvedPoolAccounts>, data: CreateCurvedPoolData
         ),
         ctx.accounts.global.config.curve.total_token_supply,
     )?;
-    let curve = CurveState::from_cfg(&ctx.accounts.global.config.curve);
+    let curve = CurveState::from_cfg(&CurveConfig {
+        kind,
+        ..ctx.accounts.global.config.curve
+    });
+
+    let mut config = ctx.accounts.global.config.pool;
+    config.token_program = ctx.accounts.token_program.key();
+    if let Some(max_wallet_tokens) = data.max_wallet_tokens_override {
+        config.max_wallet_tokens = Some(max_wallet_tokens);
+    }
+    if let Some(anti_snipe_slots) = data.anti_snipe_slots_override {
+        config.anti_snipe_slots = Some(anti_snipe_slots);
+    }
+    if let Some(creator_fee_share_bps) = data.creator_fee_share_bps_override {
+        config.creator_fee_share_bps = Some(creator_fee_share_bps);
+    }
+    if !config.is_creator_fee_share_valid() {
+        return err!(CurvedPoolError::InvalidCreatorFeeShare);
+    }
+    match (
+        config.creator_fee_share_bps,
+        &ctx.accounts.creator_fee_wallet,
+    ) {
+        (Some(_), Some(wallet)) => {
+            // wallet holds lamports only, so small shares must not leave it below rent exemption
+            let missing = Rent::get()?
+                .minimum_balance(0)
+                .saturating_sub(wallet.lamports());
+            if missing > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.authority.to_account_info(),
+                            to: wallet.to_account_info(),
+                        },
+                    ),
+                    missing,
+                )?;
+            }
+        }
+        (None, None) => {}
+        _ => return err!(CurvedPoolError::CreatorFeeWalletMismatch),
+    }
 
     ctx.accounts.pool.set_inner(CurvedPool {
         mint: ctx.accounts.mint.key(),
-        config: ctx.accounts.global.config.pool,
+        config,
         curve,
         status: CurvedPoolStatus::Active,
         project_id: data.project_id,
+        creation_slot: Clock::get()?.slot,
         bump: ctx.bumps.pool,
     });
 
@@ -66,11 +151,12 @@ pub fn graduate(ctx: Context<GraduateCurvedPoolAccounts>) -> Result<()> {
     ctx.accounts.project.ensure_can_graduate()?;
     emit_cpi!(ctx.accounts.project.set_stage(ProjectStage::Graduated)?);
 
-    anchor_spl::token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            anchor_spl::token::Transfer {
+            TransferChecked {
                 from: ctx.accounts.pool_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.funds_receiver_ata.to_account_info(),
                 authority: ctx.accounts.pool.to_account_info(),
             },
@@ -81,11 +167,12 @@ pub fn graduate(ctx: Context<GraduateCurvedPoolAccounts>) -> Result<()> {
             ]],
         ),
         ctx.accounts.pool_ata.amount,
+        ctx.accounts.mint.decimals,
     )?;
 
-    anchor_spl::token::close_account(CpiContext::new_with_signer(
+    token_interface::close_account(CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
-        anchor_spl::token::CloseAccount {
+        CloseAccount {
             account: ctx.accounts.pool_ata.to_account_info(),
             destination: ctx.accounts.funds_receiver.to_account_info(),
             authority: ctx.accounts.pool.to_account_info(),
@@ -110,11 +197,13 @@ pub fn graduate(ctx: Context<GraduateCurvedPoolAccounts>) -> Result<()> {
 }
 
 pub fn buy(ctx: Context<BuyFromCurvedPoolAccounts>, data: BuyFromCurvedPoolData) -> Result<()> {
-    if ctx.accounts.pool.status == CurvedPoolStatus::Closed {
-        return err!(CurvedPoolError::AlreadyClosed);
-    }
+    ctx.accounts.ensure_can_buy()?;
     let sols = data.sols;
-    let fee = ctx.accounts.fee.config.on_buy.part_of(sols);
+    let fee = ctx
+        .accounts
+        .fee
+        .on_buy(Clock::get()?.unix_timestamp)
+        .compute(sols);
     let after_fee = sols.saturating_sub(fee);
 
     let tokens = BuyCalculator::new(&ctx.accounts.pool.curve).fixed_sols(after_fee);
@@ -122,19 +211,106 @@ pub fn buy(ctx: Context<BuyFromCurvedPoolAccounts>, data: BuyFromCurvedPoolData)
     if tokens < data.min_token_output {
         return err!(CurvedPoolError::SlippageFailure);
     }
+    execute_buy(
+        ctx,
+        BuyOrder {
+            request_sols: data.sols,
+            min_token_output: data.min_token_output,
+            after_fee,
+            fee,
+            tokens,
+        },
+    )
+}
+
+/// Buys exactly `tokens`, fee is taken on top of the curve price.
+pub fn buy_exact_tokens(
+    ctx: Context<BuyFromCurvedPoolAccounts>,
+    data: BuyExactTokensData,
+) -> Result<()> {
+    ctx.accounts.ensure_can_buy()?;
+    if data.tokens >= ctx.accounts.pool.curve.virtual_token_reserves {
+        return err!(CurvedPoolError::OperationDisallowed);
+    }
+    let after_fee = BuyCalculator::new(&ctx.accounts.pool.curve).fixed_tokens(data.tokens);
+    let fee = ctx
+        .accounts
+        .fee
+        .on_buy(Clock::get()?.unix_timestamp)
+        .on_top_of(after_fee);
+    let sols = after_fee.saturating_add(fee);
+
+    if sols > data.max_sol_input {
+        return err!(CurvedPoolError::SlippageFailure);
+    }
+    execute_buy(
+        ctx,
+        BuyOrder {
+            request_sols: sols,
+            min_token_output: data.tokens,
+            after_fee,
+            fee,
+            tokens: data.tokens,
+        },
+    )
+}
+
+/// Buy with its amounts already computed, either from fixed sols or fixed tokens.
+struct BuyOrder {
+    request_sols: u64,
+    min_token_output: u64,
+    after_fee: u64,
+    fee: u64,
+    tokens: u64,
+}
+
+fn execute_buy(ctx: Context<BuyFromCurvedPoolAccounts>, order: BuyOrder) -> Result<()> {
+    let BuyOrder {
+        request_sols,
+        min_token_output,
+        after_fee,
+        fee,
+        tokens,
+    } = order;
     if !ctx.accounts.pool.buy_allowed(after_fee, tokens) {
         return err!(CurvedPoolError::OperationDisallowed);
     }
+    if !is_program_authority(
+        ctx.accounts.user.key,
+        &ctx.accounts.authority.authority_config,
+    ) && ctx
+        .accounts
+        .pool
+        .config
+        .exceeds_max_wallet(ctx.accounts.user_token_account.amount, tokens)
+    {
+        return err!(CurvedPoolError::MaxWalletExceeded);
+    }
 
     ctx.accounts.pool.curve.commit_buy(after_fee, tokens);
+    ctx.accounts
+        .fee
+        .record_volume(request_sols, Clock::get()?.unix_timestamp);
+    ctx.accounts.project_stats.record_buy(
+        ctx.accounts.project.id,
+        ctx.bumps.project_stats,
+        request_sols,
+    );
+    let (spot_price_numerator, spot_price_denominator) = ctx.accounts.pool.curve.spot_price();
     let event = CurvedPoolBuyEvent {
         project_id: ctx.accounts.project.id,
         user: ctx.accounts.user.key(),
-        request_sols: data.sols,
-        min_token_output: data.min_token_output,
+        request_sols,
+        min_token_output,
         tokens_output: tokens,
         new_virtual_token_reserves: ctx.accounts.pool.curve.virtual_token_reserves,
         new_virtual_sol_reserves: ctx.accounts.pool.curve.virtual_sol_reserves,
+
+        spot_price_numerator,
+        spot_price_denominator,
+
+        new_real_token_reserves: ctx.accounts.pool.curve.real_token_reserves,
+        new_real_sol_reserves: ctx.accounts.pool.curve.real_sol_reserves,
     };
     emit_cpi!(event);
 
@@ -147,17 +323,19 @@ pub fn buy(ctx: Context<BuyFromCurvedPoolAccounts>, data: BuyFromCurvedPoolData)
 
     let bump = &[ctx.accounts.pool.bump][..];
 
-    anchor_spl::token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            anchor_spl::token::Transfer {
+            TransferChecked {
                 from: ctx.accounts.pool_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.user_token_account.to_account_info(),
                 authority: ctx.accounts.pool.to_account_info(),
             },
             &[&[CURVED_POOL_PREFIX, ctx.accounts.mint.key().as_ref(), bump]],
         ),
         tokens,
+        ctx.accounts.mint.decimals,
     )?;
 
     system_program::transfer(
@@ -171,74 +349,369 @@ pub fn buy(ctx: Context<BuyFromCurvedPoolAccounts>, data: BuyFromCurvedPoolData)
         after_fee,
     )?;
 
+    let creator_fee = ctx.accounts.pool.config.creator_share(fee);
     take_fee(
         &ctx.accounts.system_program,
         &ctx.accounts.fee,
         &ctx.accounts.user,
-        fee,
+        fee - creator_fee,
     )?;
+    if let Some(wallet) = &ctx.accounts.creator_fee_wallet {
+        if creator_fee > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.user.to_account_info(),
+                        to: wallet.to_account_info(),
+                    },
+                ),
+                creator_fee,
+            )?;
+        }
+    }
 
     Ok(())
 }
 
 pub fn sell(ctx: Context<SellFromCurvedPoolAccounts>, data: SellFromCurvedPoolData) -> Result<()> {
-    if ctx.accounts.pool.status == CurvedPoolStatus::Closed {
-        return err!(CurvedPoolError::AlreadyClosed);
+    ctx.accounts.pool.ensure_tradeable()?;
+    execute_sell(
+        ctx,
+        SellOrder {
+            tokens: data.tokens,
+            min_sol_output: data.min_sol_output,
+        },
+    )
+}
+
+/// Sells just enough tokens to receive `target_sol`, fee is taken on top of it.
+/// Rounding of the curve may only give the user slightly more.
+pub fn sell_exact_sol(
+    ctx: Context<SellFromCurvedPoolAccounts>,
+    data: SellExactSolData,
+) -> Result<()> {
+    ctx.accounts.pool.ensure_tradeable()?;
+    let fee = ctx
+        .accounts
+        .fee
+        .on_sell(Clock::get()?.unix_timestamp)
+        .on_top_of(data.target_sol);
+    let request_sols = data.target_sol.saturating_add(fee);
+    if request_sols > ctx.accounts.pool.curve.real_sol_reserves {
+        return err!(CurvedPoolError::OperationDisallowed);
     }
+    let tokens = SellCalculator::new(&ctx.accounts.pool.curve).fixed_sols(request_sols);
 
-    let request_sols = SellCalculator::new(&ctx.accounts.pool.curve).fixed_tokens(data.tokens);
-    let fee = ctx.accounts.fee.config.on_sell.part_of(request_sols);
+    if tokens > data.max_token_input {
+        return err!(CurvedPoolError::SlippageFailure);
+    }
+    execute_sell(
+        ctx,
+        SellOrder {
+            tokens,
+            min_sol_output: data.target_sol,
+        },
+    )
+}
+
+/// Sell with its tokens already computed, either fixed or from fixed sols.
+struct SellOrder {
+    tokens: u64,
+    min_sol_output: u64,
+}
+
+fn execute_sell(ctx: Context<SellFromCurvedPoolAccounts>, order: SellOrder) -> Result<()> {
+    let SellOrder {
+        tokens,
+        min_sol_output,
+    } = order;
+    let request_sols = SellCalculator::new(&ctx.accounts.pool.curve).fixed_tokens(tokens);
+    let now = Clock::get()?.unix_timestamp;
+    let fee = ctx.accounts.fee.on_sell(now).compute(request_sols);
     let after_fee = request_sols.saturating_sub(fee);
 
-    if after_fee < data.min_sol_output {
+    if after_fee < min_sol_output {
         return err!(CurvedPoolError::SlippageFailure);
     }
-    if !ctx.accounts.pool.sell_allowed(data.tokens, request_sols) {
+    if !ctx.accounts.pool.sell_allowed(tokens, request_sols) {
         return err!(CurvedPoolError::OperationDisallowed);
     }
-    ctx.accounts
-        .pool
-        .curve
-        .commit_sell(data.tokens, request_sols);
-
-    anchor_spl::token::transfer(
+    ctx.accounts.pool.curve.commit_sell(tokens, request_sols);
+    ctx.accounts.fee.record_volume(request_sols, now);
+    ctx.accounts.project_stats.record_sell(
+        ctx.accounts.project.id,
+        ctx.bumps.project_stats,
+        request_sols,
+    );
+
+    token_interface::transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            anchor_spl::token::Transfer {
+            TransferChecked {
                 from: ctx.accounts.user_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.pool_token_account.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
-        data.tokens,
+        tokens,
+        ctx.accounts.mint.decimals,
     )?;
 
+    let creator_fee = ctx.accounts.pool.config.creator_share(fee);
     ctx.accounts.pool.sub_lamports(request_sols)?;
     ctx.accounts.user.add_lamports(after_fee)?;
-    ctx.accounts.fee.add_lamports(fee)?;
+    ctx.accounts.fee.add_lamports(fee - creator_fee)?;
+    if let Some(wallet) = &ctx.accounts.creator_fee_wallet {
+        wallet.add_lamports(creator_fee)?;
+    }
 
+    let (spot_price_numerator, spot_price_denominator) = ctx.accounts.pool.curve.spot_price();
     let event = CurvedPoolSellEvent {
         project_id: ctx.accounts.project.id,
         user: ctx.accounts.user.key(),
 
-        request_tokens: data.tokens,
-        min_sol_output: data.min_sol_output,
+        request_tokens: tokens,
+        min_sol_output,
         sols_output: after_fee,
 
         new_virtual_token_reserves: ctx.accounts.pool.curve.virtual_token_reserves,
         new_virtual_sol_reserves: ctx.accounts.pool.curve.virtual_sol_reserves,
+
+        spot_price_numerator,
+        spot_price_denominator,
+
+        new_real_token_reserves: ctx.accounts.pool.curve.real_token_reserves,
+        new_real_sol_reserves: ctx.accounts.pool.curve.real_sol_reserves,
     };
     emit_cpi!(event);
 
     Ok(())
 }
 
+/// Creator fee wallet keeps its rent exemption, everything above it goes to the project creator.
+pub fn claim_creator_fees(ctx: Context<ClaimCreatorFeesAccounts>) -> Result<()> {
+    let wallet = &ctx.accounts.creator_fee_wallet;
+    let claimable = wallet
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(0));
+    if claimable == 0 {
+        return Ok(());
+    }
+    let project = ctx.accounts.project.key();
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: wallet.to_account_info(),
+                to: ctx.accounts.creator.to_account_info(),
+            },
+            &[&[
+                CREATOR_FEE_WALLET_PREFIX,
+                project.as_ref(),
+                &[ctx.bumps.creator_fee_wallet],
+            ]],
+        ),
+        claimable,
+    )
+}
+
+/// Stops trading on the pool for incident response, until it's resumed.
+pub fn pause(ctx: Context<PauseCurvedPoolAccounts>) -> Result<()> {
+    match ctx.accounts.pool.status {
+        CurvedPoolStatus::Closed => err!(CurvedPoolError::AlreadyClosed),
+        CurvedPoolStatus::Active | CurvedPoolStatus::Paused => {
+            ctx.accounts.pool.status = CurvedPoolStatus::Paused;
+            Ok(())
+        }
+    }
+}
+
+pub fn resume(ctx: Context<PauseCurvedPoolAccounts>) -> Result<()> {
+    match ctx.accounts.pool.status {
+        CurvedPoolStatus::Closed => err!(CurvedPoolError::AlreadyClosed),
+        CurvedPoolStatus::Active | CurvedPoolStatus::Paused => {
+            ctx.accounts.pool.status = CurvedPoolStatus::Active;
+            Ok(())
+        }
+    }
+}
+
+/// Replaces config of the live pool. Curve is never touched, so reserves and price stay as is.
+/// Creator fee share can't be switched on or off, as the creator fee wallet is funded on creation only.
+pub fn update_config(
+    ctx: Context<UpdateCurvedPoolConfigAccounts>,
+    new_config: CurvedPoolConfig,
+) -> Result<()> {
+    if ctx.accounts.pool.status != CurvedPoolStatus::Active {
+        return err!(CurvedPoolError::NotActive);
+    }
+    if !new_config.is_creator_fee_share_valid() {
+        return err!(CurvedPoolError::InvalidCreatorFeeShare);
+    }
+    if new_config.creator_fee_share_bps.is_some()
+        != ctx.accounts.pool.config.creator_fee_share_bps.is_some()
+    {
+        return err!(CurvedPoolError::CreatorFeeWalletMismatch);
+    }
+    ctx.accounts.pool.config = CurvedPoolConfig {
+        token_program: ctx.accounts.pool.config.token_program,
+        ..new_config
+    };
+    Ok(())
+}
+
+/// Whole supply is minted on the pool creation, so the authority is revoked only afterwards.
+pub fn revoke_mint_authority(ctx: Context<RevokeMintAuthorityAccounts>) -> Result<()> {
+    if !matches!(
+        ctx.accounts.project.stage,
+        ProjectStage::CurvePoolActive | ProjectStage::CurvePoolClosed
+    ) {
+        return err!(CurvedPoolError::StageMismatch);
+    }
+
+    token_interface::set_authority(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                current_authority: ctx.accounts.authority.to_account_info(),
+                account_or_mint: ctx.accounts.mint.to_account_info(),
+            },
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )
+}
+
+/// Graduation closes the pool, which binds the mint to the project,
+/// so the authority is revoked once the pool is closed, right before the graduation.
+pub fn revoke_freeze_authority(ctx: Context<RevokeFreezeAuthorityAccounts>) -> Result<()> {
+    if ctx.accounts.project.stage != ProjectStage::CurvePoolClosed
+        && ctx.accounts.pool.status != CurvedPoolStatus::Closed
+    {
+        return err!(CurvedPoolError::NotClosed);
+    }
+
+    token_interface::set_authority(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                current_authority: ctx.accounts.authority.to_account_info(),
+                account_or_mint: ctx.accounts.mint.to_account_info(),
+            },
+        ),
+        AuthorityType::FreezeAccount,
+        None,
+    )
+}
+
+/// Extensions of Token-2022 mints, which don't affect transfers of the pool.
+pub const SUPPORTED_MINT_EXTENSIONS: &[ExtensionType] =
+    &[ExtensionType::MetadataPointer, ExtensionType::TokenMetadata];
+
+/// Transfers of the pool don't pass extra accounts of transfer hooks, while permanent delegate,
+/// transfer fee or confidential transfers would move, withhold or hide tokens behind the curve,
+/// so only [`SUPPORTED_MINT_EXTENSIONS`] are accepted.
+pub fn ensure_supported_mint(mint: &AccountInfo) -> Result<()> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(());
+    }
+    ensure_supported_extensions(&mint.try_borrow_data()?)
+}
+
+fn ensure_supported_extensions(mint_data: &[u8]) -> Result<()> {
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(mint_data)?;
+    let unsupported = mint
+        .get_extension_types()?
+        .into_iter()
+        .find(|extension| !SUPPORTED_MINT_EXTENSIONS.contains(extension));
+    if let Some(extension) = unsupported {
+        msg!("mint extension {:?} is not supported", extension);
+        return err!(CurvedPoolError::UnsupportedMintExtension);
+    }
+    Ok(())
+}
+
+/// Rewrites pool, created before curve kinds, pool trading limits, creator fee share
+/// or token programs were introduced, into the current layout.
+/// Such pools have no creation slot, so anti-snipe cooldown is long over for them.
+pub fn migrate_curved_pool(ctx: Context<MigrateCurvedPoolAccounts>) -> Result<()> {
+    let pool_info = ctx.accounts.pool.to_account_info();
+    let migrated: CurvedPool = {
+        let data = pool_info.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != CurvedPool::DISCRIMINATOR {
+            return err!(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+        }
+        let data = &mut &data[8..];
+        match pool_info.data_len() {
+            LegacyCurvedPool::ACCOUNT_SIZE => LegacyCurvedPool::deserialize(data)?.into(),
+            LegacyConstantCurvePool::ACCOUNT_SIZE => {
+                LegacyConstantCurvePool::deserialize(data)?.into()
+            }
+            LegacyWalletLimitCurvedPool::ACCOUNT_SIZE => {
+                LegacyWalletLimitCurvedPool::deserialize(data)?.into()
+            }
+            LegacyAntiSnipeCurvedPool::ACCOUNT_SIZE => {
+                LegacyAntiSnipeCurvedPool::deserialize(data)?.into()
+            }
+            LegacySplTokenCurvedPool::ACCOUNT_SIZE => {
+                LegacySplTokenCurvedPool::deserialize(data)?.into()
+            }
+            _ => return err!(CurvedPoolError::AlreadyMigrated),
+        }
+    };
+    if migrated.mint != ctx.accounts.mint.key() {
+        return err!(anchor_lang::error::ErrorCode::ConstraintSeeds);
+    }
+
+    let rent_minimum = Rent::get()?.minimum_balance(CurvedPool::ACCOUNT_SIZE);
+    let missing = rent_minimum.saturating_sub(pool_info.lamports());
+    if missing > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: pool_info.clone(),
+                },
+            ),
+            missing,
+        )?;
+    }
+    pool_info.realloc(CurvedPool::ACCOUNT_SIZE, false)?;
+
+    let mut data = pool_info.try_borrow_mut_data()?;
+    migrated.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// Read-only, so other programs could read the curve atomically via CPI return data.
+pub fn get_curve_state(ctx: Context<GetCurveStateAccounts>) -> Result<CurveStateView> {
+    Ok(ctx.accounts.pool.view())
+}
+
 #[derive(
     AnchorSerialize, AnchorDeserialize, Default, Clone, PartialEq, PartialOrd, Copy, Debug,
 )]
 pub struct CurvedPoolConfig {
     pub min_tradeable_sol: Option<u64>,
+    /// Pool is closed, once tokens left could be bought for at most this amount of sols.
+    /// Not to be confused with the backend graduation threshold,
+    /// which holds the closed pool back from raydium if it lacks liquidity.
     pub min_sol_to_close: Option<u64>,
+    /// Most tokens a single wallet may hold after a buy, not limited if unset.
+    /// Program authority is exempt, as it buys on behalf of the dev.
+    pub max_wallet_tokens: Option<u64>,
+    /// Buys are rejected for this many slots since the pool creation, so snipers can't
+    /// take the cheapest tokens right away. Program authority is exempt.
+    pub anti_snipe_slots: Option<u64>,
+    /// Part of every trade fee in basis points, which goes to the creator fee wallet
+    /// of the project instead of the fee account. Whole fee goes to the fee account if unset.
+    pub creator_fee_share_bps: Option<u16>,
+    /// Either SPL Token or Token-2022, owning the mint. Taken from the accounts
+    /// on pool creation and kept on config updates, so the global value is ignored.
+    pub token_program: Pubkey,
 }
 
 impl CurvedPoolConfig {
@@ -249,6 +722,26 @@ impl CurvedPoolConfig {
     pub fn min_sol_to_close(&self) -> u64 {
         self.min_sol_to_close.unwrap_or(0)
     }
+
+    pub fn exceeds_max_wallet(&self, held: u64, tokens: u64) -> bool {
+        self.max_wallet_tokens
+            .is_some_and(|max| held.saturating_add(tokens) > max)
+    }
+
+    pub fn is_creator_fee_share_valid(&self) -> bool {
+        self.creator_fee_share_bps
+            .map_or(true, |bps| bps <= MAX_FEE_SHARE_BPS)
+    }
+
+    /// Never exceeds the fee itself.
+    pub fn creator_share(&self, fee: u64) -> u64 {
+        let Some(bps) = self.creator_fee_share_bps else {
+            return 0;
+        };
+        let share = u128::from(fee) * u128::from(bps.min(MAX_FEE_SHARE_BPS))
+            / u128::from(MAX_FEE_SHARE_BPS);
+        share as u64
+    }
 }
 
 impl Sizable for CurvedPoolConfig {
@@ -256,6 +749,10 @@ impl Sizable for CurvedPoolConfig {
         Self {
             min_tradeable_sol: Some(Sizable::longest()),
             min_sol_to_close: Some(Sizable::longest()),
+            max_wallet_tokens: Some(Sizable::longest()),
+            anti_snipe_slots: Some(Sizable::longest()),
+            creator_fee_share_bps: Some(Sizable::longest()),
+            token_program: Pubkey::default(),
         }
     }
 }
@@ -268,10 +765,35 @@ pub struct CurvedPool {
     pub curve: CurveState,
     pub status: CurvedPoolStatus,
     pub project_id: ProjectId,
+    pub creation_slot: u64,
     pub bump: u8,
 }
 
 impl CurvedPool {
+    pub fn view(&self) -> CurveStateView {
+        let (spot_price_numerator, spot_price_denominator) = self.curve.spot_price();
+        CurveStateView {
+            virtual_token_reserves: self.curve.virtual_token_reserves,
+            virtual_sol_reserves: self.curve.virtual_sol_reserves,
+            real_token_reserves: self.curve.real_token_reserves,
+            real_sol_reserves: self.curve.real_sol_reserves,
+            total_token_supply: self.curve.total_token_supply,
+            spot_price_numerator,
+            spot_price_denominator,
+            token_balance: self.curve.token_balance(),
+            sol_balance: self.curve.sol_balance(),
+            status: self.status,
+        }
+    }
+
+    pub fn ensure_tradeable(&self) -> Result<()> {
+        match self.status {
+            CurvedPoolStatus::Active => Ok(()),
+            CurvedPoolStatus::Closed => err!(CurvedPoolError::AlreadyClosed),
+            CurvedPoolStatus::Paused => err!(CurvedPoolError::Paused),
+        }
+    }
+
     pub fn close_if_needed(&mut self) -> bool {
         if self.curve.token_balance() <= self.tokens_to_close() {
             self.status = CurvedPoolStatus::Closed;
@@ -295,6 +817,12 @@ impl CurvedPool {
         sols >= self.config.min_tradeable_sol() && tokens > 0
     }
 
+    pub fn in_anti_snipe_cooldown(&self, slot: u64) -> bool {
+        slot < self
+            .creation_slot
+            .saturating_add(self.config.anti_snipe_slots.unwrap_or(0))
+    }
+
     pub fn sell_allowed(&self, tokens: u64, sols: u64) -> bool {
         sols > 0 && tokens > 0
     }
@@ -313,15 +841,18 @@ impl Sizable for CurvedPool {
             status: Sizable::longest(),
             curve: Sizable::longest(),
             project_id: Sizable::longest(),
+            creation_slot: Sizable::longest(),
             bump: Sizable::longest(),
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, PartialOrd, AnchorSerialize, AnchorDeserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, AnchorSerialize, AnchorDeserialize)]
 pub enum CurvedPoolStatus {
     Active,
     Closed,
+    /// Trading is stopped by the program authority.
+    Paused,
 }
 
 impl Default for CurvedPoolStatus {
@@ -336,19 +867,129 @@ impl Sizable for CurvedPoolStatus {
     }
 }
 
-ensure_account_size!(CurvedPool, 116);
+ensure_account_size!(CurvedPool, 210);
+
+/// Layout of [`CurvedPool`] before the creation slot, with config `C` and curve `S` of the time.
+#[derive(AnchorDeserialize)]
+struct LegacyUnslottedCurvedPool<C, S> {
+    mint: Pubkey,
+    config: C,
+    curve: S,
+    status: CurvedPoolStatus,
+    project_id: ProjectId,
+    bump: u8,
+}
+
+/// Layout of [`CurvedPool`] with the creation slot, but an earlier config `C`.
+#[derive(AnchorDeserialize)]
+struct LegacySlottedCurvedPool<C> {
+    mint: Pubkey,
+    config: C,
+    curve: CurveState,
+    status: CurvedPoolStatus,
+    project_id: ProjectId,
+    creation_slot: u64,
+    bump: u8,
+}
+
+/// Before curve kinds.
+type LegacyCurvedPool = LegacyUnslottedCurvedPool<LegacyCurvedPoolConfig, LegacyCurveState>;
+/// Before per-wallet limits.
+type LegacyConstantCurvePool = LegacyUnslottedCurvedPool<LegacyCurvedPoolConfig, CurveState>;
+/// Before anti-snipe cooldown.
+type LegacyWalletLimitCurvedPool =
+    LegacyUnslottedCurvedPool<LegacyWalletLimitCurvedPoolConfig, CurveState>;
+/// Before creator fee share.
+type LegacyAntiSnipeCurvedPool = LegacySlottedCurvedPool<LegacyAntiSnipeCurvedPoolConfig>;
+/// Before the token program.
+type LegacySplTokenCurvedPool = LegacySlottedCurvedPool<LegacySplTokenCurvedPoolConfig>;
+
+impl LegacyCurvedPool {
+    const ACCOUNT_SIZE: usize = 116;
+}
+
+impl LegacyConstantCurvePool {
+    const ACCOUNT_SIZE: usize = 149;
+}
+
+impl LegacyWalletLimitCurvedPool {
+    const ACCOUNT_SIZE: usize = 158;
+}
+
+impl LegacyAntiSnipeCurvedPool {
+    const ACCOUNT_SIZE: usize = 175;
+}
+
+impl LegacySplTokenCurvedPool {
+    const ACCOUNT_SIZE: usize = 178;
+}
+
+impl<C: Into<CurvedPoolConfig>, S: Into<CurveState>> From<LegacyUnslottedCurvedPool<C, S>>
+    for CurvedPool
+{
+    fn from(legacy: LegacyUnslottedCurvedPool<C, S>) -> Self {
+        Self {
+            mint: legacy.mint,
+            config: legacy.config.into(),
+            curve: legacy.curve.into(),
+            status: legacy.status,
+            project_id: legacy.project_id,
+            creation_slot: 0,
+            bump: legacy.bump,
+        }
+    }
+}
+
+impl<C: Into<CurvedPoolConfig>> From<LegacySlottedCurvedPool<C>> for CurvedPool {
+    fn from(legacy: LegacySlottedCurvedPool<C>) -> Self {
+        Self {
+            mint: legacy.mint,
+            config: legacy.config.into(),
+            curve: legacy.curve,
+            status: legacy.status,
+            project_id: legacy.project_id,
+            creation_slot: legacy.creation_slot,
+            bump: legacy.bump,
+        }
+    }
+}
+
+/// Spot price is in lamports per token: `spot_price_numerator / spot_price_denominator`.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq)]
+pub struct CurveStateView {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub total_token_supply: u64,
+    pub spot_price_numerator: u64,
+    pub spot_price_denominator: u64,
+    pub token_balance: u64,
+    pub sol_balance: u64,
+    pub status: CurvedPoolStatus,
+}
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct CreateCurvedPoolData {
     pub project_id: ProjectId,
+    /// If set, mint must have given decimals instead of the global ones.
+    /// Must not exceed [`POOL_TOKEN_DECIMALS`].
+    pub token_decimals_override: Option<u8>,
+    /// If set, pool trades on given curve instead of the global default one.
+    pub curve_kind_override: Option<CurveKind>,
+    /// If set, replaces the global per-wallet limit for this pool.
+    pub max_wallet_tokens_override: Option<u64>,
+    /// If set, replaces the global anti-snipe cooldown for this pool.
+    pub anti_snipe_slots_override: Option<u64>,
+    /// If set, replaces the global creator fee share for this pool.
+    pub creator_fee_share_bps_override: Option<u16>,
 }
 
 #[event_cpi]
 #[derive(Accounts)]
 #[instruction(data: CreateCurvedPoolData)]
 pub struct CreateCurvedPoolAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(
         mut,
@@ -361,32 +1002,40 @@ pub struct CreateCurvedPoolAccounts<'info> {
     )]
     pub global: Box<Account<'info, GlobalCurvedPoolAccount>>,
 
+    /// Initialized right before, so Token-2022 mint could carry extensions,
+    /// see [`ensure_supported_mint`]. The whole supply is minted to the pool.
     #[account(
-        init,
-        payer = authority,
-        mint::decimals = global.config.token_decimals,
+        mut,
+        mint::decimals = data.token_decimals_override.unwrap_or(global.config.token_decimals),
         mint::authority = authority,
-        mint::freeze_authority = authority
+        mint::freeze_authority = authority,
+        mint::token_program = token_program,
+        constraint = mint.supply == 0 @ CurvedPoolError::MintAlreadyMinted,
     )]
-    pub mint: Box<Account<'info, Mint>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         init,
-        payer = authority,
+        payer = authority.authority,
         associated_token::mint = mint,
         associated_token::authority = pool,
+        associated_token::token_program = token_program,
     )]
-    pub pool_token_account: Box<Account<'info, TokenAccount>>,
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init,
-        payer = authority,
+        payer = authority.authority,
         space = CurvedPool::ACCOUNT_SIZE, seeds = [CURVED_POOL_PREFIX, mint.key().as_ref()], bump
     )]
     pub pool: Box<Account<'info, CurvedPool>>,
 
+    /// CHECK: holds lamports only, must be provided iff the pool takes a creator fee share
+    #[account(mut, seeds = [CREATOR_FEE_WALLET_PREFIX, project.key().as_ref()], bump)]
+    pub creator_fee_wallet: Option<UncheckedAccount<'info>>,
+
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
@@ -397,15 +1046,24 @@ pub struct BuyFromCurvedPoolData {
     pub min_token_output: u64,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Debug)]
+pub struct BuyExactTokensData {
+    pub project_id: ProjectId,
+    pub tokens: u64,
+    /// Fee included.
+    pub max_sol_input: u64,
+}
+
+/// Shared by all buy instructions, so only project id, which starts data of each of them, is read.
 #[event_cpi]
 #[derive(Accounts)]
-#[instruction(data: BuyFromCurvedPoolData)]
+#[instruction(project_id: ProjectId)]
 pub struct BuyFromCurvedPoolAccounts<'info> {
-    #[account(constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(
         mut,
+        address = FeeAccount::address(),
         seeds = [FEE_ACCOUNT_PREFIX], bump=fee.bump
     )]
     pub fee: Account<'info, FeeAccount>,
@@ -413,7 +1071,7 @@ pub struct BuyFromCurvedPoolAccounts<'info> {
     #[account(
         mut,
         constraint = project.id == pool.project_id,
-        seeds = [PROJECT_PREFIX, &data.project_id.to_bytes()], bump = project.bump
+        seeds = [PROJECT_PREFIX, &project_id.to_bytes()], bump = project.bump
     )]
     pub project: Account<'info, Project>,
 
@@ -421,33 +1079,63 @@ pub struct BuyFromCurvedPoolAccounts<'info> {
     pub user: Signer<'info>,
 
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         associated_token::mint = mint,
         associated_token::authority = pool,
+        associated_token::token_program = token_program,
     )]
-    pub pool_token_account: Account<'info, TokenAccount>,
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut,
-        seeds = [CURVED_POOL_PREFIX, mint.key().as_ref()], bump=pool.bump
+        seeds = [CURVED_POOL_PREFIX, mint.key().as_ref()], bump=pool.bump,
+        constraint = pool.config.creator_fee_share_bps.is_some() == creator_fee_wallet.is_some()
+            @ CurvedPoolError::CreatorFeeWalletMismatch,
     )]
     pub pool: Account<'info, CurvedPool>,
 
+    /// CHECK: holds lamports only, must be provided iff the pool takes a creator fee share
+    #[account(mut, seeds = [CREATOR_FEE_WALLET_PREFIX, project.key().as_ref()], bump)]
+    pub creator_fee_wallet: Option<UncheckedAccount<'info>>,
+
+    /// Created by the first buy of the project.
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = ProjectStats::ACCOUNT_SIZE,
+        seeds = [PROJECT_STATS_PREFIX, &project_id.to_bytes()], bump,
+    )]
+    pub project_stats: Account<'info, ProjectStats>,
+
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    #[account(address = pool.config.token_program @ CurvedPoolError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+impl BuyFromCurvedPoolAccounts<'_> {
+    fn ensure_can_buy(&self) -> Result<()> {
+        self.pool.ensure_tradeable()?;
+        if !is_program_authority(self.user.key, &self.authority.authority_config)
+            && self.pool.in_anti_snipe_cooldown(Clock::get()?.slot)
+        {
+            return err!(CurvedPoolError::AntiSnipeCooldown);
+        }
+        Ok(())
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct SellFromCurvedPoolData {
     pub project_id: ProjectId,
@@ -455,15 +1143,24 @@ pub struct SellFromCurvedPoolData {
     pub min_sol_output: u64,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Debug)]
+pub struct SellExactSolData {
+    pub project_id: ProjectId,
+    /// Fee excluded, received by the user.
+    pub target_sol: u64,
+    pub max_token_input: u64,
+}
+
+/// Shared by all sell instructions, so only project id, which starts data of each of them, is read.
 #[event_cpi]
 #[derive(Accounts)]
-#[instruction(data: SellFromCurvedPoolData)]
+#[instruction(project_id: ProjectId)]
 pub struct SellFromCurvedPoolAccounts<'info> {
-    #[account(constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(
         mut,
+        address = FeeAccount::address(),
         seeds = [FEE_ACCOUNT_PREFIX], bump=fee.bump
     )]
     pub fee: Account<'info, FeeAccount>,
@@ -471,25 +1168,28 @@ pub struct SellFromCurvedPoolAccounts<'info> {
     #[account(
         mut,
         constraint = project.id == pool.project_id,
-        seeds = [PROJECT_PREFIX, &data.project_id.to_bytes()], bump = project.bump
+        seeds = [PROJECT_PREFIX, &project_id.to_bytes()], bump = project.bump
     )]
     pub project: Account<'info, Project>,
 
     #[account(mut)]
     pub user: Signer<'info>,
     #[account(constraint = pool.mint == mint.key())]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         associated_token::mint = mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        seeds = [CURVED_POOL_PREFIX, mint.key().as_ref()], bump = pool.bump
+        seeds = [CURVED_POOL_PREFIX, mint.key().as_ref()], bump = pool.bump,
+        constraint = pool.config.creator_fee_share_bps.is_some() == creator_fee_wallet.is_some()
+            @ CurvedPoolError::CreatorFeeWalletMismatch,
     )]
     pub pool: Account<'info, CurvedPool>,
 
@@ -497,11 +1197,26 @@ pub struct SellFromCurvedPoolAccounts<'info> {
         mut,
         associated_token::mint = mint,
         associated_token::authority = pool,
+        associated_token::token_program = token_program,
     )]
-    pub pool_token_account: Account<'info, TokenAccount>,
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: holds lamports only, must be provided iff the pool takes a creator fee share
+    #[account(mut, seeds = [CREATOR_FEE_WALLET_PREFIX, project.key().as_ref()], bump)]
+    pub creator_fee_wallet: Option<UncheckedAccount<'info>>,
+
+    /// Created by a sell too, as pools, launched before the stats, may get it first.
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = ProjectStats::ACCOUNT_SIZE,
+        seeds = [PROJECT_STATS_PREFIX, &project_id.to_bytes()], bump,
+    )]
+    pub project_stats: Account<'info, ProjectStats>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    #[account(address = pool.config.token_program @ CurvedPoolError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
@@ -509,8 +1224,7 @@ pub struct SellFromCurvedPoolAccounts<'info> {
 #[derive(Accounts)]
 #[instruction(data: GraduateCurvedPoolData)]
 pub struct GraduateCurvedPoolAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(mut,
         seeds = [PROJECT_PREFIX, &data.project_id.to_bytes()], bump=project.bump
@@ -523,15 +1237,16 @@ pub struct GraduateCurvedPoolAccounts<'info> {
     )]
     pub fee: Account<'info, FeeAccount>,
 
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         init_if_needed,
         payer = funds_receiver,
         associated_token::mint = mint,
         associated_token::authority = funds_receiver,
+        associated_token::token_program = token_program,
     )]
-    pub funds_receiver_ata: Account<'info, TokenAccount>,
+    pub funds_receiver_ata: InterfaceAccount<'info, TokenAccount>,
 
     /// CHECK: only for lamports receiving
     #[account(mut)]
@@ -541,8 +1256,9 @@ pub struct GraduateCurvedPoolAccounts<'info> {
         mut,
         associated_token::mint = mint,
         associated_token::authority = pool,
+        associated_token::token_program = token_program,
     )]
-    pub pool_ata: Account<'info, TokenAccount>,
+    pub pool_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -551,10 +1267,127 @@ pub struct GraduateCurvedPoolAccounts<'info> {
     pub pool: Account<'info, CurvedPool>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    #[account(address = pool.config.token_program @ CurvedPoolError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+#[derive(Accounts)]
+pub struct ClaimCreatorFeesAccounts<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(constraint = project.creator == creator.key() @ ProjectError::NotProjectOwner)]
+    pub project: Account<'info, Project>,
+
+    #[account(mut, seeds = [CREATOR_FEE_WALLET_PREFIX, project.key().as_ref()], bump)]
+    pub creator_fee_wallet: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeMintAuthorityAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(mut, constraint = pool.mint == mint.key())]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [CURVED_POOL_PREFIX, mint.key().as_ref()], bump = pool.bump
+    )]
+    pub pool: Account<'info, CurvedPool>,
+
+    #[account(
+        seeds = [PROJECT_PREFIX, &pool.project_id.to_bytes()], bump = project.bump
+    )]
+    pub project: Account<'info, Project>,
+
+    #[account(address = pool.config.token_program @ CurvedPoolError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct PauseCurvedPoolAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(constraint = pool.mint == mint.key())]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut,
+        seeds = [CURVED_POOL_PREFIX, mint.key().as_ref()], bump = pool.bump
+    )]
+    pub pool: Account<'info, CurvedPool>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCurvedPoolConfigAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(constraint = pool.mint == mint.key())]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut,
+        seeds = [CURVED_POOL_PREFIX, mint.key().as_ref()], bump = pool.bump
+    )]
+    pub pool: Account<'info, CurvedPool>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateCurvedPoolAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    /// CHECK: only seeds the pool address
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: legacy layout can't be deserialized as [`CurvedPool`], so it's checked manually
+    #[account(mut, owner = crate::ID, seeds = [CURVED_POOL_PREFIX, mint.key().as_ref()], bump)]
+    pub pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetCurveStateAccounts<'info> {
+    #[account(constraint = pool.mint == mint.key())]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [CURVED_POOL_PREFIX, mint.key().as_ref()], bump = pool.bump
+    )]
+    pub pool: Account<'info, CurvedPool>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RevokeFreezeAuthorityData {
+    pub project_id: ProjectId,
+}
+
+/// Curve pool is closed on graduation, so the mint is bound to project by authority only.
+#[derive(Accounts)]
+#[instruction(data: RevokeFreezeAuthorityData)]
+pub struct RevokeFreezeAuthorityAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(
+        seeds = [PROJECT_PREFIX, &data.project_id.to_bytes()], bump = project.bump
+    )]
+    pub project: Account<'info, Project>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Binds the mint to the project.
+    #[account(
+        constraint = pool.project_id == project.id,
+        seeds = [CURVED_POOL_PREFIX, mint.key().as_ref()], bump = pool.bump
+    )]
+    pub pool: Account<'info, CurvedPool>,
+
+    #[account(address = pool.config.token_program @ CurvedPoolError::TokenProgramMismatch)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct GraduateCurvedPoolData {
     pub project_id: ProjectId,
@@ -585,4 +1418,214 @@ pub enum CurvedPoolError {
 
     #[msg("Pool is not graduated yet")]
     NotGraduated,
+
+    #[msg("Token decimals must not exceed native SOL precision")]
+    InvalidTokenDecimals,
+
+    #[msg("Linear curve must have non-zero slope denominator and either base price or slope")]
+    InvalidCurveKind,
+
+    #[msg("Wallet would hold more tokens than the pool allows")]
+    MaxWalletExceeded,
+
+    #[msg("Pool doesn't accept buys right after creation yet")]
+    AntiSnipeCooldown,
+
+    #[msg("Pool is paused by the program authority")]
+    Paused,
+
+    #[msg("Creator fee share must not exceed 10000 basis points")]
+    InvalidCreatorFeeShare,
+
+    #[msg("Creator fee wallet must be provided iff the pool takes a creator fee share")]
+    CreatorFeeWalletMismatch,
+
+    #[msg("Pool must be active")]
+    NotActive,
+
+    #[msg("Token program doesn't match the one of the pool")]
+    TokenProgramMismatch,
+
+    #[msg("Mint has an extension, which the pool doesn't support")]
+    UnsupportedMintExtension,
+
+    #[msg("Pool account already has the current layout")]
+    AlreadyMigrated,
+
+    #[msg("Mint must have no supply before the pool is created")]
+    MintAlreadyMinted,
+
+    #[msg("Project stage doesn't allow the operation")]
+    StageMismatch,
+
+    #[msg("Curve of the project with static pools must be of SPL Token")]
+    StaticPoolRequiresSplToken,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PROGRAM_AUTHORITY;
+
+    #[test]
+    fn test_splits_creator_share_of_the_fee() {
+        let mut config = CurvedPoolConfig::default();
+        assert_eq!(config.creator_share(1_000), 0);
+
+        config.creator_fee_share_bps = Some(2_500);
+        assert!(config.is_creator_fee_share_valid());
+        assert_eq!(config.creator_share(1_000), 250);
+        // rounded down, so the fee account never gets less than its part
+        assert_eq!(config.creator_share(3), 0);
+        assert_eq!(config.creator_share(u64::MAX), u64::MAX / 4);
+
+        config.creator_fee_share_bps = Some(MAX_FEE_SHARE_BPS);
+        assert_eq!(config.creator_share(1_000), 1_000);
+
+        config.creator_fee_share_bps = Some(MAX_FEE_SHARE_BPS + 1);
+        assert!(!config.is_creator_fee_share_valid());
+    }
+
+    #[test]
+    fn test_knows_legacy_pool_sizes() {
+        fn size(fields: impl AnchorSerialize) -> usize {
+            let mut serialized = CurvedPool::DISCRIMINATOR.to_vec();
+            fields.serialize(&mut serialized).unwrap();
+            serialized.len()
+        }
+        let pubkey = Pubkey::default();
+        let id = ProjectId::longest();
+        let status = CurvedPoolStatus::Active;
+        let curve = CurveState::longest();
+        let some = Some(u64::MAX);
+
+        let legacy_curve = [0u64; 5];
+        let config = (some, some);
+        assert_eq!(
+            size((pubkey, config, legacy_curve, status, id, 0u8)),
+            LegacyCurvedPool::ACCOUNT_SIZE
+        );
+        assert_eq!(
+            size((pubkey, config, curve, status, id, 0u8)),
+            LegacyConstantCurvePool::ACCOUNT_SIZE
+        );
+        let config = (some, some, some);
+        assert_eq!(
+            size((pubkey, config, curve, status, id, 0u8)),
+            LegacyWalletLimitCurvedPool::ACCOUNT_SIZE
+        );
+        let config = (some, some, some, some);
+        assert_eq!(
+            size((pubkey, config, curve, status, id, 0u64, 0u8)),
+            LegacyAntiSnipeCurvedPool::ACCOUNT_SIZE
+        );
+        let config = (some, some, some, some, Some(u16::MAX));
+        assert_eq!(
+            size((pubkey, config, curve, status, id, 0u64, 0u8)),
+            LegacySplTokenCurvedPool::ACCOUNT_SIZE
+        );
+    }
+
+    #[test]
+    fn test_migrates_legacy_pool() {
+        let mint = Pubkey::new_unique();
+        let mut serialized = vec![];
+        (
+            mint,
+            (Some(1_000u64), None::<u64>),
+            [10u64, 20, 30, 40, 50],
+            CurvedPoolStatus::Active,
+            ProjectId(7),
+            254u8,
+        )
+            .serialize(&mut serialized)
+            .unwrap();
+        let mut pool: CurvedPool = LegacyCurvedPool::deserialize(&mut serialized.as_slice())
+            .unwrap()
+            .into();
+        assert_eq!(pool.mint, mint);
+        assert_eq!(pool.config.min_tradeable_sol, Some(1_000));
+        assert_eq!(pool.config.token_program, anchor_spl::token::ID);
+        assert_eq!(pool.curve.kind, CurveKind::ConstantProduct);
+        assert_eq!(pool.curve.total_token_supply, 50);
+        assert_eq!(pool.project_id, ProjectId(7));
+        assert_eq!(pool.creation_slot, 0);
+        assert_eq!(pool.bump, 254);
+
+        // tokens bought before the migration are sold back without underflow
+        pool.curve.commit_sell(5, 1);
+        assert_eq!(pool.curve.tokens_sold, 0);
+    }
+
+    fn token_2022_mint(extension: ExtensionType) -> Vec<u8> {
+        use anchor_lang::solana_program::program_option::COption;
+        use spl_token_2022::extension::{
+            metadata_pointer::MetadataPointer, permanent_delegate::PermanentDelegate,
+            transfer_fee::TransferFeeConfig, transfer_hook::TransferHook,
+            BaseStateWithExtensionsMut as _, StateWithExtensionsMut,
+        };
+
+        let len =
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[extension])
+                .unwrap();
+        let mut data = vec![0; len];
+        let mut mint =
+            StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+                .unwrap();
+        match extension {
+            ExtensionType::MetadataPointer => {
+                let pointer = mint.init_extension::<MetadataPointer>(true).unwrap();
+                pointer.metadata_address = Some(Pubkey::new_unique()).try_into().unwrap();
+            }
+            ExtensionType::TransferHook => {
+                let hook = mint.init_extension::<TransferHook>(true).unwrap();
+                hook.program_id = Some(Pubkey::new_unique()).try_into().unwrap();
+            }
+            ExtensionType::PermanentDelegate => {
+                let delegate = mint.init_extension::<PermanentDelegate>(true).unwrap();
+                delegate.delegate = Some(Pubkey::new_unique()).try_into().unwrap();
+            }
+            ExtensionType::TransferFeeConfig => {
+                mint.init_extension::<TransferFeeConfig>(true).unwrap();
+            }
+            _ => unreachable!("not used by tests"),
+        }
+        mint.base = spl_token_2022::state::Mint {
+            mint_authority: COption::Some(PROGRAM_AUTHORITY),
+            supply: 0,
+            decimals: POOL_TOKEN_DECIMALS,
+            is_initialized: true,
+            freeze_authority: COption::Some(PROGRAM_AUTHORITY),
+        };
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        data
+    }
+
+    #[test]
+    fn test_accepts_token_2022_mint_with_metadata_pointer() {
+        let data = token_2022_mint(ExtensionType::MetadataPointer);
+        assert!(ensure_supported_extensions(&data).is_ok());
+
+        let mint = Mint::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(mint.decimals, POOL_TOKEN_DECIMALS);
+        assert_eq!(mint.supply, 0);
+    }
+
+    #[test]
+    fn test_rejects_token_2022_mint_with_transfer_hook() {
+        let data = token_2022_mint(ExtensionType::TransferHook);
+        assert!(ensure_supported_extensions(&data).is_err());
+    }
+
+    #[test]
+    fn test_rejects_token_2022_mint_moving_tokens_behind_the_curve() {
+        for extension in [
+            ExtensionType::PermanentDelegate,
+            ExtensionType::TransferFeeConfig,
+        ] {
+            let data = token_2022_mint(extension);
+            assert!(ensure_supported_extensions(&data).is_err());
+        }
+    }
 }
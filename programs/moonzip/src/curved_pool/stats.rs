@@ -0,0 +1,100 @@
+use crate::{ensure_account_size, events::ProjectStatsEvent, utils::Sizable, ProjectId};
+use anchor_lang::prelude::*;
+
+pub const PROJECT_STATS_PREFIX: &[u8] = b"project-stats";
+
+pub fn project_stats_address(id: &ProjectId) -> Pubkey {
+    let (address, _) =
+        Pubkey::find_program_address(&[PROJECT_STATS_PREFIX, &id.to_bytes()], &crate::ID);
+    address
+}
+
+/// View only, stats are logged, so they could be read by simulating the instruction.
+pub fn read_project_stats(ctx: Context<ReadProjectStatsAccounts>) -> Result<()> {
+    let stats = &ctx.accounts.project_stats;
+    emit!(ProjectStatsEvent {
+        project_id: stats.project_id,
+        buy_count: stats.buy_count,
+        sell_count: stats.sell_count,
+        total_sol_volume: stats.total_sol_volume,
+    });
+    Ok(())
+}
+
+/// Totals of curved pool trades, created by the first trade of the project.
+///
+/// Pools, launched before the stats were introduced, count only trades made afterwards.
+#[account]
+#[derive(Debug, Default, PartialEq)]
+pub struct ProjectStats {
+    pub project_id: ProjectId,
+    pub buy_count: u64,
+    pub sell_count: u64,
+    /// Lamports, paid for tokens or received for them, fee included.
+    pub total_sol_volume: u64,
+    pub bump: u8,
+}
+
+impl ProjectStats {
+    /// Freshly created account is zeroed, so its identity is written on every trade.
+    pub fn record_buy(&mut self, project_id: ProjectId, bump: u8, sols: u64) {
+        self.project_id = project_id;
+        self.bump = bump;
+        self.buy_count = self.buy_count.saturating_add(1);
+        self.total_sol_volume = self.total_sol_volume.saturating_add(sols);
+    }
+
+    /// See [`Self::record_buy`].
+    pub fn record_sell(&mut self, project_id: ProjectId, bump: u8, sols: u64) {
+        self.project_id = project_id;
+        self.bump = bump;
+        self.sell_count = self.sell_count.saturating_add(1);
+        self.total_sol_volume = self.total_sol_volume.saturating_add(sols);
+    }
+}
+
+impl Sizable for ProjectStats {
+    fn longest() -> Self {
+        Self {
+            project_id: Sizable::longest(),
+            buy_count: Sizable::longest(),
+            sell_count: Sizable::longest(),
+            total_sol_volume: Sizable::longest(),
+            bump: Sizable::longest(),
+        }
+    }
+}
+
+ensure_account_size!(ProjectStats, 49);
+
+#[derive(Accounts)]
+pub struct ReadProjectStatsAccounts<'info> {
+    #[account(
+        seeds = [PROJECT_STATS_PREFIX, &project_stats.project_id.to_bytes()],
+        bump = project_stats.bump
+    )]
+    pub project_stats: Account<'info, ProjectStats>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_trades() {
+        let mut stats = ProjectStats::default();
+        stats.record_buy(ProjectId(1), 255, 100);
+        stats.record_buy(ProjectId(1), 255, 50);
+        stats.record_sell(ProjectId(1), 255, u64::MAX);
+        assert_eq!(
+            stats,
+            ProjectStats {
+                project_id: ProjectId(1),
+                buy_count: 2,
+                sell_count: 1,
+                total_sol_volume: u64::MAX,
+                bump: 255,
+            }
+        );
+    }
+}
@@ -12,6 +12,60 @@ pub struct ProjectChangedEvent {
     pub to_stage: ProjectStage,
 }
 
+#[must_use]
+#[event]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct ProjectOwnershipTransferredEvent {
+    pub project_id: ProjectId,
+
+    pub from: Pubkey,
+    pub to: Pubkey,
+}
+
+/// Project account is closed along with it, so no stage change is emitted.
+#[must_use]
+#[event]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct ProjectCancelledEvent {
+    pub project_id: ProjectId,
+
+    pub creator: Pubkey,
+    pub refunded_lamports: u64,
+}
+
+#[must_use]
+#[event]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct LiquidityBurnedEvent {
+    pub project_id: ProjectId,
+
+    pub lp_mint: Pubkey,
+    pub burned_amount: u64,
+    pub slot: u64,
+}
+
+/// Logged only, as it's not tracked by the backend.
+#[must_use]
+#[event]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct TokensBurnedEvent {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub burner: Pubkey,
+}
+
+/// Logged only by the view instruction, so it's not tracked by the backend.
+#[must_use]
+#[event]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct ProjectStatsEvent {
+    pub project_id: ProjectId,
+
+    pub buy_count: u64,
+    pub sell_count: u64,
+    pub total_sol_volume: u64,
+}
+
 #[must_use]
 #[event]
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
@@ -25,6 +79,13 @@ pub struct CurvedPoolBuyEvent {
 
     pub new_virtual_token_reserves: u64,
     pub new_virtual_sol_reserves: u64,
+
+    /// Spot price after the trade, in lamports per token.
+    pub spot_price_numerator: u64,
+    pub spot_price_denominator: u64,
+
+    pub new_real_token_reserves: u64,
+    pub new_real_sol_reserves: u64,
 }
 
 impl CurvedPoolBuyEvent {
@@ -58,6 +119,13 @@ pub struct CurvedPoolSellEvent {
 
     pub new_virtual_token_reserves: u64,
     pub new_virtual_sol_reserves: u64,
+
+    /// Spot price after the trade, in lamports per token.
+    pub spot_price_numerator: u64,
+    pub spot_price_denominator: u64,
+
+    pub new_real_token_reserves: u64,
+    pub new_real_sol_reserves: u64,
 }
 
 impl CurvedPoolSellEvent {
@@ -96,6 +164,17 @@ impl StaticPoolBuyEvent {
     }
 }
 
+#[must_use]
+#[event]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct StaticPoolDeadlineExtendedEvent {
+    pub project_id: ProjectId,
+
+    /// Zero if pool had no deadline before.
+    pub old_finish_ts: u64,
+    pub new_finish_ts: u64,
+}
+
 #[must_use]
 #[event]
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
@@ -113,3 +192,40 @@ impl StaticPoolSellEvent {
         self.request_tokens - self.output_sols
     }
 }
+
+#[must_use]
+#[event]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct UpgradeProposedEvent {
+    pub upgrade_hash: [u8; 32],
+    pub proposed_at: i64,
+    pub effective_at: i64,
+    pub proposer: Pubkey,
+}
+
+#[must_use]
+#[event]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct UpgradeExecutedEvent {
+    pub upgrade_hash: [u8; 32],
+    pub proposed_at: i64,
+    pub executed_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum FeeSource {
+    /// Moved out of the fee account by the authority.
+    Extracted,
+    /// Whole balance of another account, taken into the fee account.
+    AccountTaken,
+}
+
+/// Program-wide, so protocol revenue could be tracked without project trades.
+#[must_use]
+#[event]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct FeeCollectedEvent {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub source: FeeSource,
+}
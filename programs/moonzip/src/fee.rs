@@ -1,34 +1,156 @@
-use crate::{ensure_account_size, utils::Sizable, PROGRAM_AUTHORITY};
+use crate::{
+    authority::*,
+    ensure_account_size,
+    events::{FeeCollectedEvent, FeeSource},
+    utils::Sizable,
+};
 use anchor_lang::{prelude::*, system_program};
 
 pub const FEE_ACCOUNT_PREFIX: &[u8] = b"fee";
 
+/// Trade volume, deciding the fee tier, is accumulated over this window.
+pub const VOLUME_WINDOW_SECS: i64 = 30 * 24 * 60 * 60;
+
+pub const MAX_FEE_TIERS: usize = 8;
+
+pub const MAX_FEE_RECIPIENTS: usize = 5;
+
 pub fn fee_address() -> Pubkey {
     let (address, _) = Pubkey::find_program_address(&[FEE_ACCOUNT_PREFIX], &crate::ID);
     address
 }
 
+/// Accumulated volume is kept, so that changing the config doesn't reset fee tiers.
 pub fn set_fee_config(ctx: Context<SetFeeConfigAccounts>, config: FeeConfig) -> Result<()> {
-    ctx.accounts.fee.set_inner(FeeAccount {
-        config,
-        bump: ctx.bumps.fee,
-    });
+    if !config.dynamic.is_valid() {
+        return err!(FeeError::InvalidFeeTiers);
+    }
+    if !FeeRecipient::is_valid_split(&config.recipients) {
+        return err!(FeeError::InvalidFeeRecipients);
+    }
+    let fee = &mut ctx.accounts.fee;
+    fee.config = config;
+    fee.fee_receiver = ctx.accounts.fee_receiver.key();
+    fee.bump = ctx.bumps.fee;
     Ok(())
 }
 
-pub fn extract_fee(ctx: Context<ExtractFeeAccounts>, data: ExtractFeeData) -> Result<()> {
-    ctx.accounts.fee.sub_lamports(data.amount)?;
-    ctx.accounts.receiver.add_lamports(data.amount)?;
+/// Rewrites fee account, created before fee receiver, fee rules, fee tiers or fee recipients
+/// were introduced, into the current layout. Legacy basis points are kept as [`TradeFee::Percentage`],
+/// migrated account has no fee tiers unless it had them, and no fee recipients.
+/// Given `fee_receiver` is set only for the account, which had none.
+pub fn migrate_fee_account(ctx: Context<MigrateFeeAccountAccounts>) -> Result<()> {
+    let fee_info = ctx.accounts.fee.to_account_info();
+    let legacy: TieredFeeAccount = {
+        let data = fee_info.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != FeeAccount::DISCRIMINATOR {
+            return err!(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+        }
+        match data.len() {
+            LegacyFeeAccount::ACCOUNT_SIZE => LegacyFeeAccount::deserialize(&mut &data[8..])?
+                .with_receiver(ctx.accounts.fee_receiver.key())
+                .into(),
+            StaticFeeAccount::ACCOUNT_SIZE => {
+                StaticFeeAccount::deserialize(&mut &data[8..])?.into()
+            }
+            TieredFeeAccount::ACCOUNT_SIZE => TieredFeeAccount::deserialize(&mut &data[8..])?,
+            _ => return err!(FeeError::AlreadyMigrated),
+        }
+    };
+
+    let rent_minimum = Rent::get()?.minimum_balance(FeeAccount::ACCOUNT_SIZE);
+    let missing = rent_minimum.saturating_sub(fee_info.lamports());
+    if missing > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: fee_info.clone(),
+                },
+            ),
+            missing,
+        )?;
+    }
+    fee_info.realloc(FeeAccount::ACCOUNT_SIZE, false)?;
+
+    let migrated = FeeAccount {
+        config: FeeConfig {
+            on_buy: legacy.on_buy,
+            on_sell: legacy.on_sell,
+            dynamic: legacy.dynamic,
+            recipients: vec![],
+        },
+        fee_receiver: legacy.fee_receiver,
+        bump: legacy.bump,
+        volume_30d: legacy.volume_30d,
+        volume_window_start: legacy.volume_window_start,
+    };
+    let mut data = fee_info.try_borrow_mut_data()?;
+    migrated.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// Moves all collected fee to the configured receiver, keeping fee account rent-exempt.
+pub fn sweep_fee(ctx: Context<SweepFeeAccounts>) -> Result<()> {
+    let fee_info = ctx.accounts.fee.to_account_info();
+    let rent_minimum = Rent::get()?.minimum_balance(fee_info.data_len());
+    let amount = fee_info.lamports().saturating_sub(rent_minimum);
+    if amount == 0 {
+        return err!(FeeError::NothingToSweep);
+    }
+
+    ctx.accounts.fee.sub_lamports(amount)?;
+    ctx.accounts.fee_receiver.add_lamports(amount)?;
+    Ok(())
+}
+
+/// Without fee recipients all the amount goes to `receiver`. Otherwise it's split among recipients,
+/// passed as remaining accounts in the config order, and `receiver` gets nothing.
+pub fn extract_fee<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExtractFeeAccounts<'info>>,
+    data: ExtractFeeData,
+) -> Result<()> {
+    let recipients = ctx.accounts.fee.config.recipients.clone();
+    if recipients.is_empty() {
+        ctx.accounts.fee.sub_lamports(data.amount)?;
+        ctx.accounts.receiver.add_lamports(data.amount)?;
+        emit_cpi!(FeeCollectedEvent {
+            recipient: ctx.accounts.receiver.key(),
+            amount: data.amount,
+            source: FeeSource::Extracted,
+        });
+        return Ok(());
+    }
+
+    if ctx.remaining_accounts.len() != recipients.len() {
+        return err!(FeeError::FeeRecipientMismatch);
+    }
+    let shares = FeeRecipient::split(&recipients, data.amount);
+    for ((recipient, account), share) in recipients.iter().zip(ctx.remaining_accounts).zip(shares) {
+        if account.key() != recipient.address || !account.is_writable {
+            return err!(FeeError::FeeRecipientMismatch);
+        }
+        ctx.accounts.fee.sub_lamports(share)?;
+        account.add_lamports(share)?;
+        emit_cpi!(FeeCollectedEvent {
+            recipient: recipient.address,
+            amount: share,
+            source: FeeSource::Extracted,
+        });
+    }
     Ok(())
 }
 
 pub fn take_account_as_fee(ctx: Context<TakeAccountAsFeeAccounts>) -> Result<()> {
-    ctx.accounts
-        .fee
-        .add_lamports(ctx.accounts.donor.lamports())?;
-    ctx.accounts
-        .donor
-        .sub_lamports(ctx.accounts.donor.lamports())?;
+    let amount = ctx.accounts.donor.lamports();
+    ctx.accounts.fee.add_lamports(amount)?;
+    ctx.accounts.donor.sub_lamports(amount)?;
+    emit_cpi!(FeeCollectedEvent {
+        recipient: ctx.accounts.fee.key(),
+        amount,
+        source: FeeSource::AccountTaken,
+    });
     Ok(())
 }
 
@@ -53,43 +175,66 @@ pub fn take_fee<'a, 'info>(
 
 #[derive(Accounts)]
 pub struct SetFeeConfigAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(
         init_if_needed,
-        payer = authority,
+        payer = authority.authority,
         space = FeeAccount::ACCOUNT_SIZE, seeds = [FEE_ACCOUNT_PREFIX], bump
     )]
     pub fee: Account<'info, FeeAccount>,
 
+    pub fee_receiver: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateFeeAccountAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    /// CHECK: legacy layout can't be deserialized as [`FeeAccount`], so it's checked manually
+    #[account(mut, owner = crate::ID, seeds = [FEE_ACCOUNT_PREFIX], bump)]
+    pub fee: UncheckedAccount<'info>,
+
+    pub fee_receiver: SystemAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct SweepFeeAccounts<'info> {
+    #[account(mut, seeds = [FEE_ACCOUNT_PREFIX], bump = fee.bump, has_one = fee_receiver)]
+    pub fee: Account<'info, FeeAccount>,
+
+    #[account(mut)]
+    pub fee_receiver: SystemAccount<'info>,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, PartialOrd, Debug)]
 pub struct ExtractFeeData {
     pub amount: u64,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ExtractFeeAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(mut,
         seeds = [FEE_ACCOUNT_PREFIX], bump=fee.bump
     )]
     pub fee: Account<'info, FeeAccount>,
 
-    /// CHECK: only for lamports receiving
+    /// CHECK: only for lamports receiving, unused if fee recipients are configured
     #[account(mut)]
     pub receiver: UncheckedAccount<'info>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct TakeAccountAsFeeAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(mut, seeds = [FEE_ACCOUNT_PREFIX], bump=fee.bump)]
     pub fee: Account<'info, FeeAccount>,
@@ -103,24 +248,135 @@ pub struct TakeAccountAsFeeAccounts<'info> {
 #[derive(PartialEq, PartialOrd, Debug)]
 pub struct FeeAccount {
     pub config: FeeConfig,
+    pub fee_receiver: Pubkey,
     pub bump: u8,
+    /// Lamports traded through all pools since `volume_window_start`.
+    pub volume_30d: u64,
+    pub volume_window_start: i64,
+}
+
+impl FeeAccount {
+    /// The only fee account, trade instructions accept.
+    /// Not `const`: `find_program_address` is a syscall on-chain.
+    pub fn address() -> Pubkey {
+        fee_address()
+    }
+
+    /// Volume of the current window, expired window counts as no volume at all.
+    pub fn volume_at(&self, now: i64) -> u64 {
+        if now.saturating_sub(self.volume_window_start) >= VOLUME_WINDOW_SECS {
+            return 0;
+        }
+        self.volume_30d
+    }
+
+    pub fn on_buy(&self, now: i64) -> TradeFee {
+        self.config
+            .dynamic
+            .apply(self.config.on_buy, self.volume_at(now))
+    }
+
+    pub fn on_sell(&self, now: i64) -> TradeFee {
+        self.config
+            .dynamic
+            .apply(self.config.on_sell, self.volume_at(now))
+    }
+
+    /// Accounts the trade, so that the following trades may fall into a cheaper tier.
+    pub fn record_volume(&mut self, lamports: u64, now: i64) {
+        if now.saturating_sub(self.volume_window_start) >= VOLUME_WINDOW_SECS {
+            self.volume_window_start = now;
+            self.volume_30d = 0;
+        }
+        self.volume_30d = self.volume_30d.saturating_add(lamports);
+    }
 }
 
 impl Sizable for FeeAccount {
     fn longest() -> Self {
         Self {
             config: Sizable::longest(),
+            fee_receiver: Pubkey::default(),
             bump: Sizable::longest(),
+            volume_30d: Sizable::longest(),
+            volume_window_start: Sizable::longest(),
         }
     }
 }
 
-ensure_account_size!(FeeAccount, 13);
+ensure_account_size!(FeeAccount, 337);
 
-#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, PartialOrd, Debug)]
+/// Layout of [`FeeAccount`] when only basis points fee was supported, before fee receiver.
+#[derive(AnchorDeserialize)]
+struct LegacyFeeAccount {
+    on_buy: BasisPoints,
+    on_sell: BasisPoints,
+    bump: u8,
+}
+
+impl LegacyFeeAccount {
+    const ACCOUNT_SIZE: usize = 13;
+
+    fn with_receiver(self, fee_receiver: Pubkey) -> StaticFeeAccount {
+        StaticFeeAccount {
+            on_buy: TradeFee::Percentage(self.on_buy),
+            on_sell: TradeFee::Percentage(self.on_sell),
+            fee_receiver,
+            bump: self.bump,
+        }
+    }
+}
+
+/// Layout of [`FeeAccount`] before fee tiers were introduced.
+#[derive(AnchorDeserialize)]
+struct StaticFeeAccount {
+    on_buy: TradeFee,
+    on_sell: TradeFee,
+    fee_receiver: Pubkey,
+    bump: u8,
+}
+
+impl StaticFeeAccount {
+    const ACCOUNT_SIZE: usize = 63;
+}
+
+impl From<StaticFeeAccount> for TieredFeeAccount {
+    fn from(legacy: StaticFeeAccount) -> Self {
+        Self {
+            on_buy: legacy.on_buy,
+            on_sell: legacy.on_sell,
+            dynamic: DynamicFeeConfig::default(),
+            fee_receiver: legacy.fee_receiver,
+            bump: legacy.bump,
+            volume_30d: 0,
+            volume_window_start: 0,
+        }
+    }
+}
+
+/// Layout of [`FeeAccount`] before fee recipients were introduced.
+#[derive(AnchorDeserialize)]
+struct TieredFeeAccount {
+    on_buy: TradeFee,
+    on_sell: TradeFee,
+    dynamic: DynamicFeeConfig,
+    fee_receiver: Pubkey,
+    bump: u8,
+    volume_30d: u64,
+    volume_window_start: i64,
+}
+
+impl TieredFeeAccount {
+    const ACCOUNT_SIZE: usize = 163;
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, PartialEq, PartialOrd, Debug)]
 pub struct FeeConfig {
-    pub on_buy: BasisPoints,
-    pub on_sell: BasisPoints,
+    pub on_buy: TradeFee,
+    pub on_sell: TradeFee,
+    pub dynamic: DynamicFeeConfig,
+    /// Extracted fee is split among them, empty sends it all to the receiver of the extraction.
+    pub recipients: Vec<FeeRecipient>,
 }
 
 impl Sizable for FeeConfig {
@@ -128,6 +384,176 @@ impl Sizable for FeeConfig {
         Self {
             on_buy: Sizable::longest(),
             on_sell: Sizable::longest(),
+            dynamic: Sizable::longest(),
+            recipients: vec![Sizable::longest(); MAX_FEE_RECIPIENTS],
+        }
+    }
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct FeeRecipient {
+    pub address: Pubkey,
+    pub share_bps: u16,
+}
+
+impl FeeRecipient {
+    /// Shares must sum up to the whole fee exactly, so nothing is left unassigned.
+    pub fn is_valid_split(recipients: &[FeeRecipient]) -> bool {
+        recipients.is_empty()
+            || (recipients.len() <= MAX_FEE_RECIPIENTS
+                && recipients
+                    .iter()
+                    .map(|recipient| u32::from(recipient.share_bps))
+                    .sum::<u32>()
+                    == u32::from(BasisPoints::MAX))
+    }
+
+    /// Rounding leftover goes to the last recipient, so shares always sum up to `amount`.
+    pub fn split(recipients: &[FeeRecipient], amount: u64) -> Vec<u64> {
+        let mut left = amount;
+        let mut shares: Vec<u64> = recipients
+            .iter()
+            .map(|recipient| {
+                let share = ((amount as u128) * (recipient.share_bps as u128)
+                    / (BasisPoints::MAX as u128)) as u64;
+                left -= share;
+                share
+            })
+            .collect();
+        if let Some(last) = shares.last_mut() {
+            *last += left;
+        }
+        shares
+    }
+}
+
+impl Sizable for FeeRecipient {
+    fn longest() -> Self {
+        Self {
+            address: Pubkey::default(),
+            share_bps: Sizable::longest(),
+        }
+    }
+}
+
+/// Fee tiers by the 30 days volume: once volume reaches the threshold,
+/// its basis points replace the percentage part of the trade fee.
+/// Flat part of the fee is never changed. No thresholds means no tiers.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Default, PartialEq, PartialOrd, Debug)]
+pub struct DynamicFeeConfig {
+    pub volume_thresholds: Vec<FeeTier>,
+}
+
+/// Threshold and its fee percentage. Not a tuple: IDL has no tuple types.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct FeeTier {
+    pub volume: u64,
+    pub bps: BasisPoints,
+}
+
+impl Sizable for FeeTier {
+    fn longest() -> Self {
+        Self {
+            volume: Sizable::longest(),
+            bps: Sizable::longest(),
+        }
+    }
+}
+
+impl DynamicFeeConfig {
+    /// Thresholds must be strictly ascending, so the matching tier is unambiguous.
+    pub fn is_valid(&self) -> bool {
+        self.volume_thresholds.len() <= MAX_FEE_TIERS
+            && self
+                .volume_thresholds
+                .windows(2)
+                .all(|pair| pair[0].volume < pair[1].volume)
+            && self
+                .volume_thresholds
+                .iter()
+                .all(|tier| tier.bps.0 < BasisPoints::MAX)
+    }
+
+    pub fn tier(&self, volume: u64) -> Option<BasisPoints> {
+        self.volume_thresholds
+            .iter()
+            .rev()
+            .find(|tier| volume >= tier.volume)
+            .map(|tier| tier.bps)
+    }
+
+    pub fn apply(&self, fee: TradeFee, volume: u64) -> TradeFee {
+        match self.tier(volume) {
+            Some(bps) => fee.with_percentage(bps),
+            None => fee,
+        }
+    }
+}
+
+impl Sizable for DynamicFeeConfig {
+    fn longest() -> Self {
+        Self {
+            volume_thresholds: vec![Sizable::longest(); MAX_FEE_TIERS],
+        }
+    }
+}
+
+/// Fee, taken from a single trade.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub enum TradeFee {
+    Percentage(BasisPoints),
+    /// Same amount of lamports for any trade, but never more than the trade itself.
+    Flat(u64),
+    MaxOf {
+        percentage: BasisPoints,
+        flat: u64,
+    },
+}
+
+impl TradeFee {
+    pub fn compute(&self, amount: u64) -> u64 {
+        match self {
+            TradeFee::Percentage(bps) => bps.part_of(amount),
+            TradeFee::Flat(flat) => (*flat).min(amount),
+            TradeFee::MaxOf { percentage, flat } => {
+                percentage.part_of(amount).max((*flat).min(amount))
+            }
+        }
+    }
+
+    /// Fee to add on top of `amount`, so that `amount` is left after [`TradeFee::compute`].
+    pub fn on_top_of(&self, amount: u64) -> u64 {
+        match self {
+            TradeFee::Percentage(bps) => bps.on_top_of(amount),
+            TradeFee::Flat(flat) => *flat,
+            TradeFee::MaxOf { percentage, flat } => percentage.on_top_of(amount).max(*flat),
+        }
+    }
+
+    /// Same fee with another percentage, [`TradeFee::Flat`] has none to replace.
+    pub fn with_percentage(self, bps: BasisPoints) -> Self {
+        match self {
+            TradeFee::Percentage(_) => TradeFee::Percentage(bps),
+            TradeFee::Flat(flat) => TradeFee::Flat(flat),
+            TradeFee::MaxOf { flat, .. } => TradeFee::MaxOf {
+                percentage: bps,
+                flat,
+            },
+        }
+    }
+}
+
+impl From<BasisPoints> for TradeFee {
+    fn from(bps: BasisPoints) -> Self {
+        TradeFee::Percentage(bps)
+    }
+}
+
+impl Sizable for TradeFee {
+    fn longest() -> Self {
+        TradeFee::MaxOf {
+            percentage: Sizable::longest(),
+            flat: Sizable::longest(),
         }
     }
 }
@@ -163,3 +589,232 @@ impl Sizable for BasisPoints {
         Self(Sizable::longest())
     }
 }
+
+#[error_code]
+pub enum FeeError {
+    #[msg("Fee account has no lamports above rent to sweep")]
+    NothingToSweep,
+    #[msg("Fee account already has the current layout")]
+    AlreadyMigrated,
+    #[msg("Fee tiers must be strictly ascending by volume and fit the tiers limit")]
+    InvalidFeeTiers,
+    #[msg("Fee recipient shares must sum up to 10000 basis points and fit the recipients limit")]
+    InvalidFeeRecipients,
+    #[msg("Remaining accounts must be writable fee recipients in the config order")]
+    FeeRecipientMismatch,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentage_fee() {
+        let fee = TradeFee::from(BasisPoints(100));
+        assert_eq!(fee.compute(10_000), 100);
+        assert_eq!(fee.compute(10), 1);
+    }
+
+    #[test]
+    fn test_flat_fee() {
+        let fee = TradeFee::Flat(1_000_000);
+        assert_eq!(fee.compute(1_000_000_000), 1_000_000);
+        assert_eq!(fee.compute(500), 500);
+        assert_eq!(fee.on_top_of(1_000_000_000), 1_000_000);
+    }
+
+    #[test]
+    fn test_max_of_fee() {
+        let fee = TradeFee::MaxOf {
+            percentage: BasisPoints(100),
+            flat: 1_000_000,
+        };
+        assert_eq!(fee.compute(10_000_000), 1_000_000);
+        assert_eq!(fee.compute(1_000_000_000), 10_000_000);
+
+        for amount in [10_000_000, 1_000_000_000] {
+            let total = amount + fee.on_top_of(amount);
+            assert!(total - fee.compute(total) >= amount);
+        }
+    }
+
+    #[test]
+    fn test_legacy_fee_account_size() {
+        // baseline layout: basis points config and bump
+        let legacy = ((BasisPoints(0), BasisPoints(0)), 0u8);
+        let mut serialized = FeeAccount::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), LegacyFeeAccount::ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_legacy_fee_account_keeps_basis_points() {
+        let mut serialized = vec![];
+        (BasisPoints(100), BasisPoints(200), 254u8)
+            .serialize(&mut serialized)
+            .unwrap();
+        let receiver = Pubkey::new_unique();
+        let migrated = LegacyFeeAccount::deserialize(&mut serialized.as_slice())
+            .unwrap()
+            .with_receiver(receiver);
+        assert_eq!(migrated.on_buy, TradeFee::Percentage(BasisPoints(100)));
+        assert_eq!(migrated.on_sell, TradeFee::Percentage(BasisPoints(200)));
+        assert_eq!(migrated.fee_receiver, receiver);
+        assert_eq!(migrated.bump, 254);
+    }
+
+    #[test]
+    fn test_static_fee_account_size() {
+        let longest = TradeFee::longest();
+        let legacy = (longest, longest, Pubkey::default(), 0u8);
+        let mut serialized = FeeAccount::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), StaticFeeAccount::ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_tiered_fee_account_size() {
+        let longest = TradeFee::longest();
+        let legacy = (
+            longest,
+            longest,
+            DynamicFeeConfig::longest(),
+            Pubkey::default(),
+            0u8,
+            0u64,
+            0i64,
+        );
+        let mut serialized = FeeAccount::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), TieredFeeAccount::ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_fee_split_among_recipients() {
+        let recipients = [
+            FeeRecipient {
+                address: Pubkey::new_unique(),
+                share_bps: 3333,
+            },
+            FeeRecipient {
+                address: Pubkey::new_unique(),
+                share_bps: 6667,
+            },
+        ];
+        assert!(FeeRecipient::is_valid_split(&recipients));
+        assert_eq!(FeeRecipient::split(&recipients, 10_000), vec![3333, 6667]);
+        // rounding leftover goes to the last recipient
+        assert_eq!(FeeRecipient::split(&recipients, 100), vec![33, 67]);
+        assert_eq!(FeeRecipient::split(&recipients, 1), vec![0, 1]);
+
+        assert!(FeeRecipient::is_valid_split(&[]));
+        assert!(!FeeRecipient::is_valid_split(&recipients[..1]));
+        let too_many = [FeeRecipient {
+            address: Pubkey::new_unique(),
+            share_bps: 1000,
+        }; MAX_FEE_RECIPIENTS * 2];
+        assert!(!FeeRecipient::is_valid_split(&too_many));
+    }
+
+    fn tiered_account() -> FeeAccount {
+        FeeAccount {
+            config: FeeConfig {
+                on_buy: TradeFee::Percentage(BasisPoints(100)),
+                on_sell: TradeFee::MaxOf {
+                    percentage: BasisPoints(100),
+                    flat: 1_000,
+                },
+                dynamic: DynamicFeeConfig {
+                    volume_thresholds: vec![
+                        FeeTier {
+                            volume: 1_000_000_000,
+                            bps: BasisPoints(50),
+                        },
+                        FeeTier {
+                            volume: 10_000_000_000,
+                            bps: BasisPoints(25),
+                        },
+                    ],
+                },
+                recipients: vec![],
+            },
+            fee_receiver: Pubkey::default(),
+            bump: 0,
+            volume_30d: 0,
+            volume_window_start: 0,
+        }
+    }
+
+    #[test]
+    fn test_high_volume_pays_reduced_fee() {
+        let mut account = tiered_account();
+        let trade = 100_000_000;
+        assert_eq!(account.on_buy(0).compute(trade), 1_000_000);
+
+        account.record_volume(1_000_000_000, 0);
+        assert_eq!(account.on_buy(0).compute(trade), 500_000);
+        assert_eq!(account.on_sell(0).compute(trade), 500_000);
+
+        account.record_volume(9_000_000_000, 0);
+        assert_eq!(account.on_buy(0).compute(trade), 250_000);
+        // flat part stays the same in any tier
+        assert_eq!(account.on_sell(0).compute(10_000), 1_000);
+    }
+
+    #[test]
+    fn test_volume_window_expires() {
+        let mut account = tiered_account();
+        account.record_volume(10_000_000_000, 100);
+        assert_eq!(account.on_buy(100).compute(100_000_000), 250_000);
+
+        let expired = 100 + VOLUME_WINDOW_SECS;
+        assert_eq!(account.volume_at(expired), 0);
+        assert_eq!(account.on_buy(expired).compute(100_000_000), 1_000_000);
+
+        account.record_volume(1_000_000_000, expired);
+        assert_eq!(account.volume_30d, 1_000_000_000);
+        assert_eq!(account.volume_window_start, expired);
+    }
+
+    #[test]
+    fn test_fee_tiers_validation() {
+        assert!(DynamicFeeConfig::default().is_valid());
+        assert!(tiered_account().config.dynamic.is_valid());
+
+        let descending = DynamicFeeConfig {
+            volume_thresholds: vec![
+                FeeTier {
+                    volume: 10,
+                    bps: BasisPoints(50),
+                },
+                FeeTier {
+                    volume: 5,
+                    bps: BasisPoints(25),
+                },
+            ],
+        };
+        assert!(!descending.is_valid());
+        let duplicated = DynamicFeeConfig {
+            volume_thresholds: vec![
+                FeeTier {
+                    volume: 10,
+                    bps: BasisPoints(50),
+                },
+                FeeTier {
+                    volume: 10,
+                    bps: BasisPoints(25),
+                },
+            ],
+        };
+        assert!(!duplicated.is_valid());
+        let too_many = DynamicFeeConfig {
+            volume_thresholds: (0..=MAX_FEE_TIERS as u64)
+                .map(|volume| FeeTier {
+                    volume,
+                    bps: BasisPoints(10),
+                })
+                .collect(),
+        };
+        assert!(!too_many.is_valid());
+    }
+}
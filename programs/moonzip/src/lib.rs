@@ -1,14 +1,17 @@
 use anchor_lang::prelude::*;
 use const_str_to_pubkey::str_to_pubkey;
+pub mod authority;
 pub mod common;
 pub mod curved_pool;
 pub mod events;
 pub mod fee;
+pub mod metadata;
 pub mod misc;
 pub mod project;
 pub mod pumpfun;
 pub mod static_pool;
 pub mod transmuter;
+pub mod upgrade;
 pub mod utils;
 
 declare_id!("544hmhQ5N72wv8aJFz92sgRMnDEqwmSuzGtG8T8CPgNb");
@@ -16,14 +19,18 @@ pub const PROGRAM_AUTHORITY: Pubkey = str_to_pubkey(env!("MOONZIP_AUTHORITY"));
 
 #[program]
 pub mod moonzip {
+    pub use super::authority::*;
     pub use super::curved_pool::global::*;
+    pub use super::curved_pool::stats::*;
     pub use super::curved_pool::*;
     pub use super::fee::*;
+    pub use super::metadata::*;
     pub use super::misc::*;
     pub use super::project::*;
     pub use super::pumpfun::*;
     pub use super::static_pool::*;
     pub use super::transmuter::*;
+    pub use super::upgrade::*;
     use super::*;
 
     pub fn create_project(
@@ -33,14 +40,29 @@ pub mod moonzip {
         project::create(ctx, data)
     }
 
+    pub fn cancel_project(ctx: Context<CancelProjectAccounts>) -> Result<()> {
+        project::cancel(ctx)
+    }
+
     pub fn set_fee_config(ctx: Context<SetFeeConfigAccounts>, config: FeeConfig) -> Result<()> {
         fee::set_fee_config(ctx, config)
     }
 
-    pub fn extract_fee(ctx: Context<ExtractFeeAccounts>, data: ExtractFeeData) -> Result<()> {
+    pub fn extract_fee<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExtractFeeAccounts<'info>>,
+        data: ExtractFeeData,
+    ) -> Result<()> {
         fee::extract_fee(ctx, data)
     }
 
+    pub fn sweep_fee(ctx: Context<SweepFeeAccounts>) -> Result<()> {
+        fee::sweep_fee(ctx)
+    }
+
+    pub fn migrate_fee_account(ctx: Context<MigrateFeeAccountAccounts>) -> Result<()> {
+        fee::migrate_fee_account(ctx)
+    }
+
     pub fn take_account_as_fee(ctx: Context<TakeAccountAsFeeAccounts>) -> Result<()> {
         fee::take_account_as_fee(ctx)
     }
@@ -53,6 +75,20 @@ pub mod moonzip {
         project::unlock_latch(ctx)
     }
 
+    pub fn migrate_project(
+        ctx: Context<MigrateProjectAccounts>,
+        data: MigrateProjectData,
+    ) -> Result<()> {
+        project::migrate_project(ctx, data)
+    }
+
+    pub fn force_unlock_stale_latch(
+        ctx: Context<ForceUnlockStaleLatchAccounts>,
+        data: ForceUnlockStaleLatchData,
+    ) -> Result<()> {
+        project::force_unlock_stale_latch(ctx, data)
+    }
+
     pub fn project_graduate(
         ctx: Context<GraduateProjectAccounts>,
         _data: GraduateProjectData,
@@ -60,6 +96,21 @@ pub mod moonzip {
         project::graduate(ctx)
     }
 
+    pub fn propose_transfer_ownership(
+        ctx: Context<ProposeTransferOwnershipAccounts>,
+        data: ProposeTransferOwnershipData,
+    ) -> Result<()> {
+        project::propose_transfer_ownership(ctx, data)
+    }
+
+    pub fn accept_transfer_ownership(ctx: Context<AcceptTransferOwnershipAccounts>) -> Result<()> {
+        project::accept_transfer_ownership(ctx)
+    }
+
+    pub fn cancel_transfer_ownership(ctx: Context<CancelTransferOwnershipAccounts>) -> Result<()> {
+        project::cancel_transfer_ownership(ctx)
+    }
+
     pub fn create_static_pool(
         ctx: Context<CreateStaticPoolAccounts>,
         data: CreateStaticPoolData,
@@ -67,10 +118,34 @@ pub mod moonzip {
         static_pool::create(ctx, data)
     }
 
+    pub fn extend_static_pool_deadline(
+        ctx: Context<ExtendStaticPoolDeadlineAccounts>,
+        data: ExtendStaticPoolDeadlineData,
+    ) -> Result<()> {
+        static_pool::extend_deadline(ctx, data)
+    }
+
     pub fn graduate_static_pool(ctx: Context<GraduateStaticPoolAccounts>) -> Result<()> {
         static_pool::graduate(ctx)
     }
 
+    pub fn refund_from_static_pool(
+        ctx: Context<RefundFromStaticPoolAccounts>,
+        data: RefundFromStaticPoolData,
+    ) -> Result<()> {
+        static_pool::refund(ctx, data)
+    }
+
+    pub fn migrate_static_pool(ctx: Context<MigrateStaticPoolAccounts>) -> Result<()> {
+        static_pool::migrate_static_pool(ctx)
+    }
+
+    pub fn close_static_pool_wallet_record(
+        ctx: Context<CloseStaticPoolWalletRecordAccounts>,
+    ) -> Result<()> {
+        static_pool::close_wallet_record(ctx)
+    }
+
     pub fn buy_from_static_pool(
         ctx: Context<BuyFromStaticPoolAccounts>,
         data: BuyFromStaticPoolData,
@@ -92,6 +167,13 @@ pub mod moonzip {
         curved_pool::global::set_global_config(ctx, config)
     }
 
+    pub fn set_project_creation_fee(
+        ctx: Context<SetProjectCreationFeeAccounts>,
+        data: SetProjectCreationFeeData,
+    ) -> Result<()> {
+        curved_pool::global::set_project_creation_fee(ctx, data)
+    }
+
     pub fn create_curved_pool(
         ctx: Context<CreateCurvedPoolAccounts>,
         data: CreateCurvedPoolData,
@@ -113,6 +195,13 @@ pub mod moonzip {
         curved_pool::buy(ctx, data)
     }
 
+    pub fn buy_exact_tokens(
+        ctx: Context<BuyFromCurvedPoolAccounts>,
+        data: BuyExactTokensData,
+    ) -> Result<()> {
+        curved_pool::buy_exact_tokens(ctx, data)
+    }
+
     pub fn sell_from_curved_pool(
         ctx: Context<SellFromCurvedPoolAccounts>,
         data: SellFromCurvedPoolData,
@@ -120,22 +209,107 @@ pub mod moonzip {
         curved_pool::sell(ctx, data)
     }
 
-    pub fn init_transmuter_for_curve(ctx: Context<InitTransmuterForCurveAccounts>) -> Result<()> {
-        transmuter::init_for_curve(ctx)
+    pub fn sell_exact_sol(
+        ctx: Context<SellFromCurvedPoolAccounts>,
+        data: SellExactSolData,
+    ) -> Result<()> {
+        curved_pool::sell_exact_sol(ctx, data)
+    }
+
+    pub fn pause_curved_pool(ctx: Context<PauseCurvedPoolAccounts>) -> Result<()> {
+        curved_pool::pause(ctx)
+    }
+
+    pub fn resume_curved_pool(ctx: Context<PauseCurvedPoolAccounts>) -> Result<()> {
+        curved_pool::resume(ctx)
+    }
+
+    pub fn update_curved_pool_config(
+        ctx: Context<UpdateCurvedPoolConfigAccounts>,
+        new_config: CurvedPoolConfig,
+    ) -> Result<()> {
+        curved_pool::update_config(ctx, new_config)
+    }
+
+    pub fn revoke_mint_authority(ctx: Context<RevokeMintAuthorityAccounts>) -> Result<()> {
+        curved_pool::revoke_mint_authority(ctx)
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFeesAccounts>) -> Result<()> {
+        curved_pool::claim_creator_fees(ctx)
+    }
+
+    pub fn migrate_curved_pool(ctx: Context<MigrateCurvedPoolAccounts>) -> Result<()> {
+        curved_pool::migrate_curved_pool(ctx)
+    }
+
+    pub fn get_curve_state(ctx: Context<GetCurveStateAccounts>) -> Result<CurveStateView> {
+        curved_pool::get_curve_state(ctx)
+    }
+
+    pub fn read_project_stats(ctx: Context<ReadProjectStatsAccounts>) -> Result<()> {
+        curved_pool::stats::read_project_stats(ctx)
+    }
+
+    pub fn revoke_freeze_authority(
+        ctx: Context<RevokeFreezeAuthorityAccounts>,
+        _data: RevokeFreezeAuthorityData,
+    ) -> Result<()> {
+        curved_pool::revoke_freeze_authority(ctx)
+    }
+
+    pub fn update_project_metadata(
+        ctx: Context<UpdateProjectMetadataAccounts>,
+        data: UpdateProjectMetadataData,
+    ) -> Result<()> {
+        metadata::update_project_metadata(ctx, data)
+    }
+
+    pub fn lock_project_metadata(
+        ctx: Context<UpdateProjectMetadataAccounts>,
+        _data: LockProjectMetadataData,
+    ) -> Result<()> {
+        metadata::lock_project_metadata(ctx)
+    }
+
+    pub fn init_transmuter_for_curve(
+        ctx: Context<InitTransmuterForCurveAccounts>,
+        data: InitTransmuterData,
+    ) -> Result<()> {
+        transmuter::init_for_curve(ctx, data)
     }
 
     pub fn init_transmuter_for_pumpfun_curve(
         ctx: Context<InitTransmuterForPumpfunCurveAccounts>,
+        data: InitTransmuterData,
+    ) -> Result<()> {
+        transmuter::init_for_pumpfun_curve(ctx, data)
+    }
+
+    pub fn set_transmuter_expiry(
+        ctx: Context<SetTransmuterExpiryAccounts>,
+        data: SetTransmuterExpiryData,
     ) -> Result<()> {
-        transmuter::init_for_pumpfun_curve(ctx)
+        transmuter::set_expiry(ctx, data)
+    }
+
+    pub fn migrate_transmuter(ctx: Context<MigrateTransmuterAccounts>) -> Result<()> {
+        transmuter::migrate_transmuter(ctx)
+    }
+
+    pub fn close_expired_transmuter(ctx: Context<CloseExpiredTransmuterAccounts>) -> Result<()> {
+        transmuter::close_expired(ctx)
     }
 
     pub fn transmute(ctx: Context<TransmuteAccounts>, data: TransmuteData) -> Result<()> {
         transmuter::transmute(ctx, data)
     }
 
-    pub fn transmute_idempotent(ctx: Context<TransmuteIdempotentAccounts>) -> Result<()> {
-        transmuter::transmute_idempotent(ctx)
+    pub fn transmute_idempotent(
+        ctx: Context<TransmuteIdempotentAccounts>,
+        data: TransmuteIdempotentData,
+    ) -> Result<()> {
+        transmuter::transmute_idempotent(ctx, data)
     }
 
     pub fn buy_from_pump(ctx: Context<BuyFromPumpAccounts>, data: BuyFromPumpData) -> Result<()> {
@@ -145,4 +319,39 @@ pub mod moonzip {
     pub fn burn_and_close(ctx: Context<BurnAndCloseAccounts>) -> Result<()> {
         misc::burn_and_close_accounts(ctx)
     }
+
+    pub fn emit_liquidity_burn(
+        ctx: Context<EmitLiquidityBurnAccounts>,
+        _data: EmitLiquidityBurnData,
+    ) -> Result<()> {
+        misc::emit_liquidity_burn(ctx)
+    }
+
+    pub fn propose_program_upgrade(
+        ctx: Context<ProposeProgramUpgradeAccounts>,
+        data: ProposeProgramUpgradeData,
+    ) -> Result<()> {
+        upgrade::propose_program_upgrade(ctx, data)
+    }
+
+    pub fn execute_program_upgrade(ctx: Context<ExecuteProgramUpgradeAccounts>) -> Result<()> {
+        upgrade::execute_program_upgrade(ctx)
+    }
+
+    pub fn init_authority_config(ctx: Context<InitAuthorityConfigAccounts>) -> Result<()> {
+        authority::init_authority_config(ctx)
+    }
+
+    pub fn propose_authority_rotation(
+        ctx: Context<ProposeAuthorityRotationAccounts>,
+        data: ProposeAuthorityRotationData,
+    ) -> Result<()> {
+        authority::propose_authority_rotation(ctx, data)
+    }
+
+    pub fn finalize_authority_rotation(
+        ctx: Context<FinalizeAuthorityRotationAccounts>,
+    ) -> Result<()> {
+        authority::finalize_authority_rotation(ctx)
+    }
 }
@@ -0,0 +1,138 @@
+//! Metadata of moonzip curve mints is updated by [`METADATA_AUTHORITY_PREFIX`] PDA,
+//! so it changes only the way the program allows: while the curve is live and never afterwards.
+use crate::{
+    authority::*,
+    curved_pool::{CurvedPool, CurvedPoolStatus, CURVED_POOL_PREFIX},
+    Project, ProjectId, ProjectStage, PROJECT_PREFIX,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::{
+    mpl_token_metadata::types::DataV2, update_metadata_accounts_v2, Metadata, MetadataAccount,
+    UpdateMetadataAccountsV2,
+};
+
+pub const METADATA_AUTHORITY_PREFIX: &[u8] = b"metadata-authority";
+const METADATA_PREFIX: &[u8] = b"metadata";
+
+pub fn metadata_authority_address() -> Pubkey {
+    let (address, _) = Pubkey::find_program_address(&[METADATA_AUTHORITY_PREFIX], &crate::ID);
+    address
+}
+
+/// Only the uri is replaced, name and symbol are the token identity.
+pub fn update_project_metadata(
+    ctx: Context<UpdateProjectMetadataAccounts>,
+    data: UpdateProjectMetadataData,
+) -> Result<()> {
+    if ctx.accounts.project.stage != ProjectStage::CurvePoolActive {
+        return err!(MetadataError::MetadataLocked);
+    }
+    let current = &ctx.accounts.metadata;
+    let data = DataV2 {
+        name: current.name.clone(),
+        symbol: current.symbol.clone(),
+        uri: data.uri,
+        seller_fee_basis_points: current.seller_fee_basis_points,
+        creators: current.creators.clone(),
+        collection: current.collection.clone(),
+        uses: current.uses.clone(),
+    };
+    update_metadata_accounts_v2(
+        ctx.accounts
+            .update_ctx()
+            .with_signer(&[&[METADATA_AUTHORITY_PREFIX, &[ctx.bumps.metadata_authority]]]),
+        None,
+        Some(data),
+        None,
+        None,
+    )
+}
+
+/// Makes metadata of the closed curve immutable, right before the graduation closes the pool.
+/// Metadata, which is already immutable or updated by someone else, e.g. created before
+/// [`METADATA_AUTHORITY_PREFIX`] PDA was introduced, is left as is.
+pub fn lock_project_metadata(ctx: Context<UpdateProjectMetadataAccounts>) -> Result<()> {
+    if ctx.accounts.project.stage != ProjectStage::CurvePoolClosed
+        && ctx.accounts.pool.status != CurvedPoolStatus::Closed
+    {
+        return err!(MetadataError::CurveNotClosed);
+    }
+    let metadata = &ctx.accounts.metadata;
+    if !metadata.is_mutable || metadata.update_authority != ctx.accounts.metadata_authority.key() {
+        return Ok(());
+    }
+    update_metadata_accounts_v2(
+        ctx.accounts
+            .update_ctx()
+            .with_signer(&[&[METADATA_AUTHORITY_PREFIX, &[ctx.bumps.metadata_authority]]]),
+        None,
+        None,
+        None,
+        Some(false),
+    )
+}
+
+/// Shared by both instructions, so only project id, which starts data of each of them, is read.
+#[derive(Accounts)]
+#[instruction(project_id: ProjectId)]
+pub struct UpdateProjectMetadataAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(
+        seeds = [PROJECT_PREFIX, &project_id.to_bytes()], bump = project.bump
+    )]
+    pub project: Account<'info, Project>,
+
+    /// Binds the mint to the project.
+    #[account(
+        constraint = pool.project_id == project.id,
+        seeds = [CURVED_POOL_PREFIX, pool.mint.as_ref()], bump = pool.bump
+    )]
+    pub pool: Account<'info, CurvedPool>,
+
+    #[account(
+        mut,
+        seeds = [METADATA_PREFIX, Metadata::id().as_ref(), pool.mint.as_ref()],
+        seeds::program = Metadata::id(),
+        bump,
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    /// CHECK: update authority of the metadata, signs by seeds
+    #[account(seeds = [METADATA_AUTHORITY_PREFIX], bump)]
+    pub metadata_authority: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+impl<'info> UpdateProjectMetadataAccounts<'info> {
+    fn update_ctx(&self) -> CpiContext<'_, '_, '_, 'info, UpdateMetadataAccountsV2<'info>> {
+        CpiContext::new(
+            self.token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: self.metadata.to_account_info(),
+                update_authority: self.metadata_authority.to_account_info(),
+            },
+        )
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct UpdateProjectMetadataData {
+    pub project_id: ProjectId,
+    pub uri: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LockProjectMetadataData {
+    pub project_id: ProjectId,
+}
+
+#[error_code]
+pub enum MetadataError {
+    #[msg("Metadata could be updated only while the curve pool is active")]
+    MetadataLocked,
+
+    #[msg("Metadata is locked only once the curve pool is closed")]
+    CurveNotClosed,
+}
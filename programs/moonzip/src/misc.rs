@@ -1,10 +1,18 @@
+use crate::{
+    authority::*,
+    events::{LiquidityBurnedEvent, TokensBurnedEvent},
+    project::{Project, ProjectId, PROJECT_PREFIX},
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token::{spl_token::instruction::AuthorityType, Mint, Token, TokenAccount},
 };
 
+/// Burns the whole balance of the authority, closes its token account
+/// and revokes authorities of the mint, held by the authority, so supply can't be changed anymore.
 pub fn burn_and_close_accounts(ctx: Context<BurnAndCloseAccounts>) -> Result<()> {
+    let amount = ctx.accounts.token_account.amount;
     anchor_spl::token::burn(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
@@ -14,7 +22,7 @@ pub fn burn_and_close_accounts(ctx: Context<BurnAndCloseAccounts>) -> Result<()>
                 authority: ctx.accounts.authority.to_account_info(),
             },
         ),
-        ctx.accounts.token_account.amount,
+        amount,
     )?;
 
     anchor_spl::token::close_account(CpiContext::new(
@@ -26,18 +34,46 @@ pub fn burn_and_close_accounts(ctx: Context<BurnAndCloseAccounts>) -> Result<()>
         },
     ))?;
 
+    let authority = ctx.accounts.authority.key();
+    for (current, authority_type) in [
+        (ctx.accounts.mint.mint_authority, AuthorityType::MintTokens),
+        (
+            ctx.accounts.mint.freeze_authority,
+            AuthorityType::FreezeAccount,
+        ),
+    ] {
+        if current.contains(&authority) {
+            anchor_spl::token::set_authority(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    anchor_spl::token::SetAuthority {
+                        current_authority: ctx.accounts.authority.to_account_info(),
+                        account_or_mint: ctx.accounts.mint.to_account_info(),
+                    },
+                ),
+                authority_type,
+                None,
+            )?;
+        }
+    }
+
+    emit!(TokensBurnedEvent {
+        mint: ctx.accounts.mint.key(),
+        amount,
+        burner: authority,
+    });
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct BurnAndCloseAccounts<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(mut)]
     pub mint: Account<'info, Mint>,
 
     #[account(
+        mut,
         associated_token::mint = mint,
         associated_token::authority = authority,
     )]
@@ -46,3 +82,41 @@ pub struct BurnAndCloseAccounts<'info> {
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
+
+/// Records LP tokens, which are about to be burned by `burn_and_close` in the same transaction.
+/// Must precede the burn: amount is unknown before the pool is initialized
+/// and the token account is gone after the burn.
+pub fn emit_liquidity_burn(ctx: Context<EmitLiquidityBurnAccounts>) -> Result<()> {
+    emit_cpi!(LiquidityBurnedEvent {
+        project_id: ctx.accounts.project.id,
+        lp_mint: ctx.accounts.lp_mint.key(),
+        burned_amount: ctx.accounts.lp_token_account.amount,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(data: EmitLiquidityBurnData)]
+pub struct EmitLiquidityBurnAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(
+        seeds = [PROJECT_PREFIX, &data.id.to_bytes()], bump=project.bump
+    )]
+    pub project: Account<'info, Project>,
+
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(
+        associated_token::mint = lp_mint,
+        associated_token::authority = authority,
+    )]
+    pub lp_token_account: Account<'info, TokenAccount>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EmitLiquidityBurnData {
+    pub id: ProjectId,
+}
@@ -1,21 +1,67 @@
-use crate::{ensure_account_size, events::ProjectChangedEvent, utils::Sizable, PROGRAM_AUTHORITY};
+use crate::{
+    authority::*,
+    curved_pool::global::{GlobalCurvedPoolAccount, GLOBAL_ACCOUNT_PREFIX},
+    ensure_account_size,
+    events::{ProjectCancelledEvent, ProjectChangedEvent, ProjectOwnershipTransferredEvent},
+    fee::{FeeAccount, FEE_ACCOUNT_PREFIX},
+    utils::Sizable,
+};
 use anchor_lang::{prelude::*, system_program};
 use derive_more::derive::{From, Into};
 
 pub const PROJECT_PREFIX: &[u8] = b"project";
+pub const OWNERSHIP_TRANSFER_PREFIX: &[u8] = b"transfer";
+
+/// Time new owner has to wait after proposal before accepting it.
+pub const OWNERSHIP_TRANSFER_COOLDOWN: i64 = 24 * 60 * 60;
+/// Static pools are opened one after another, e.g. whitelist round, then public one.
+pub const MAX_STATIC_POOLS: u8 = 2;
 
 pub fn project_address(id: &ProjectId) -> Pubkey {
     let (address, _) = Pubkey::find_program_address(&[b"project", &id.to_bytes()], &crate::ID);
     address
 }
 
+pub fn ownership_transfer_address(project: &Pubkey) -> Pubkey {
+    let (address, _) =
+        Pubkey::find_program_address(&[OWNERSHIP_TRANSFER_PREFIX, project.as_ref()], &crate::ID);
+    address
+}
+
+/// Creator deposit includes the creation fee, the rest is given to the authority for deployment.
 pub fn create(ctx: Context<CreateProjectAccounts>, data: CreateProjectData) -> Result<()> {
+    let creation_fee = ctx.accounts.global.config.project_creation_fee_lamports;
+    let Some(deployment_deposit) = data.creator_deposit.checked_sub(creation_fee) else {
+        msg!(
+            "creator deposit {} doesn't cover creation fee {}",
+            data.creator_deposit,
+            creation_fee
+        );
+        return err!(ProjectError::InsufficientCreationFee);
+    };
+    if creation_fee > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.fee.to_account_info(),
+                },
+            ),
+            creation_fee,
+        )?;
+    }
+
+    if data.schema.static_pool_count > MAX_STATIC_POOLS {
+        return err!(ProjectError::TooManyStaticPools);
+    }
     ctx.accounts.project.set_inner(Project {
         id: data.id,
         schema: data.schema,
         stage: ProjectStage::Created,
-        latch: ProjectLatch::new(data.creator_deposit),
+        latch: ProjectLatch::new(deployment_deposit),
         bump: ctx.bumps.project,
+        creator: ctx.accounts.creator.key(),
     });
     emit_cpi!(ctx.accounts.project.set_stage(ProjectStage::Created)?);
 
@@ -27,12 +73,51 @@ pub fn create(ctx: Context<CreateProjectAccounts>, data: CreateProjectData) -> R
                 to: ctx.accounts.authority.to_account_info(),
             },
         ),
-        data.creator_deposit,
+        deployment_deposit,
     )?;
 
     Ok(())
 }
 
+/// Gives the deployment deposit back, while nothing is deployed for the project yet.
+/// Creation fee is kept, the same as for deployed projects.
+pub fn cancel(ctx: Context<CancelProjectAccounts>) -> Result<()> {
+    let project = &ctx.accounts.project;
+    if project.stage != ProjectStage::Created {
+        msg!("project in stage {:?} is already deployed", project.stage);
+        return err!(ProjectError::AlreadyDeployed);
+    }
+    if project.latch.is_locked() {
+        return err!(ProjectError::ProjectLatchAlreadyLocked);
+    }
+
+    // whatever deployment didn't spend of the deposit
+    let refund = project.latch.project_bank;
+    if refund > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.creator.to_account_info(),
+                },
+            ),
+            refund,
+        )?;
+    }
+    // pending handoff would outlive the project otherwise
+    if ctx.accounts.proposal.owner == &crate::ID {
+        Account::<OwnershipTransferProposal>::try_from(&ctx.accounts.proposal)?
+            .close(ctx.accounts.creator.to_account_info())?;
+    }
+    emit_cpi!(ProjectCancelledEvent {
+        project_id: project.id,
+        creator: project.creator,
+        refunded_lamports: refund,
+    });
+    Ok(())
+}
+
 pub fn graduate(ctx: Context<GraduateProjectAccounts>) -> Result<()> {
     ctx.accounts.project.ensure_can_graduate()?;
     emit_cpi!(ctx.accounts.project.set_stage(ProjectStage::Graduated)?);
@@ -50,6 +135,106 @@ pub fn unlock_latch(ctx: Context<ProjectUnlockLatchAccounts>) -> Result<()> {
     Ok(())
 }
 
+/// Releases the latch, left locked by a transaction, which never reached its unlock.
+/// Bank isn't adjusted, as lamports spent by such transaction can't be attributed anymore.
+pub fn force_unlock_stale_latch(
+    ctx: Context<ForceUnlockStaleLatchAccounts>,
+    data: ForceUnlockStaleLatchData,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .project
+        .latch
+        .force_unlock(now, data.max_age_secs)?;
+    Ok(())
+}
+
+/// Rewrites project account, created before it had the creator and the latch lock time,
+/// into the current layout. Legacy projects don't know their creator, so authority provides it.
+pub fn migrate_project(
+    ctx: Context<MigrateProjectAccounts>,
+    data: MigrateProjectData,
+) -> Result<()> {
+    let project_info = ctx.accounts.project.to_account_info();
+    let legacy = {
+        let data = project_info.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != Project::DISCRIMINATOR {
+            return err!(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+        }
+        if data.len() != LegacyProject::ACCOUNT_SIZE {
+            return err!(ProjectError::AlreadyMigrated);
+        }
+        LegacyProject::deserialize(&mut &data[8..])?
+    };
+
+    let rent_minimum = Rent::get()?.minimum_balance(Project::ACCOUNT_SIZE);
+    let missing = rent_minimum.saturating_sub(project_info.lamports());
+    if missing > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: project_info.clone(),
+                },
+            ),
+            missing,
+        )?;
+    }
+    project_info.realloc(Project::ACCOUNT_SIZE, false)?;
+
+    let migrated = legacy.into_project(data.creator);
+    let mut account_data = project_info.try_borrow_mut_data()?;
+    migrated.try_serialize(&mut &mut account_data[..])?;
+    Ok(())
+}
+
+pub fn propose_transfer_ownership(
+    ctx: Context<ProposeTransferOwnershipAccounts>,
+    data: ProposeTransferOwnershipData,
+) -> Result<()> {
+    if data.new_owner == ctx.accounts.project.creator {
+        return err!(ProjectError::SameOwner);
+    }
+    ctx.accounts.proposal.set_inner(OwnershipTransferProposal {
+        project: ctx.accounts.project.key(),
+        new_owner: data.new_owner,
+        proposed_at: Clock::get()?.unix_timestamp,
+        bump: ctx.bumps.proposal,
+    });
+    Ok(())
+}
+
+pub fn accept_transfer_ownership(ctx: Context<AcceptTransferOwnershipAccounts>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let unlocks_at = ctx
+        .accounts
+        .proposal
+        .proposed_at
+        .saturating_add(OWNERSHIP_TRANSFER_COOLDOWN);
+    if now < unlocks_at {
+        msg!(
+            "ownership transfer could be accepted only after {}",
+            unlocks_at
+        );
+        return err!(ProjectError::TransferCooldownNotPassed);
+    }
+
+    let project = &mut ctx.accounts.project;
+    let event = ProjectOwnershipTransferredEvent {
+        project_id: project.id,
+        from: project.creator,
+        to: ctx.accounts.new_owner.key(),
+    };
+    project.creator = ctx.accounts.new_owner.key();
+    emit_cpi!(event);
+    Ok(())
+}
+
+pub fn cancel_transfer_ownership(_ctx: Context<CancelTransferOwnershipAccounts>) -> Result<()> {
+    Ok(())
+}
+
 #[derive(
     AnchorSerialize,
     AnchorDeserialize,
@@ -60,6 +245,7 @@ pub fn unlock_latch(ctx: Context<ProjectUnlockLatchAccounts>) -> Result<()> {
     From,
     PartialEq,
     Eq,
+    Hash,
     PartialOrd,
     Ord,
     Default,
@@ -86,6 +272,8 @@ pub struct Project {
     pub stage: ProjectStage,
     pub latch: ProjectLatch,
     pub bump: u8,
+    // appended, so accounts of the legacy layout could be migrated in place.
+    pub creator: Pubkey,
 }
 
 impl Project {
@@ -101,18 +289,48 @@ impl Project {
     }
 
     pub fn ensure_can_create_static_pool(&self) -> Result<()> {
-        if !self.schema.use_static_pool {
+        if !self.schema.uses_static_pool() {
             return err!(ProjectError::SchemaMismatch);
         }
-        if self.stage != ProjectStage::Created {
-            return err!(ProjectError::StaticPoolAlreadyExists);
+        match self.stage {
+            ProjectStage::Created => Ok(()),
+            // the first pool is graduated by authority in the same transaction.
+            ProjectStage::StaticPoolClosed if self.schema.static_pool_count > 1 => Ok(()),
+            _ => err!(ProjectError::StaticPoolAlreadyExists),
+        }
+    }
+
+    /// Stage, which project enters once the next static pool is created.
+    pub fn static_pool_active_stage(&self) -> ProjectStage {
+        if self.stage == ProjectStage::StaticPoolClosed {
+            ProjectStage::SecondStaticPoolActive
+        } else {
+            ProjectStage::StaticPoolActive
+        }
+    }
+
+    /// Stage, which project enters once the current static pool is closed.
+    pub fn static_pool_closed_stage(&self) -> ProjectStage {
+        match self.stage {
+            ProjectStage::SecondStaticPoolActive | ProjectStage::SecondStaticPoolClosed => {
+                ProjectStage::SecondStaticPoolClosed
+            }
+            _ => ProjectStage::StaticPoolClosed,
         }
-        Ok(())
+    }
+
+    fn all_static_pools_closed(&self) -> bool {
+        let last_closed = if self.schema.static_pool_count > 1 {
+            ProjectStage::SecondStaticPoolClosed
+        } else {
+            ProjectStage::StaticPoolClosed
+        };
+        self.stage == last_closed
     }
 
     pub fn ensure_can_create_curved_pool(&self) -> Result<()> {
-        if self.schema.use_static_pool {
-            if !matches!(self.stage, ProjectStage::StaticPoolClosed { .. }) {
+        if self.schema.uses_static_pool() {
+            if !self.all_static_pools_closed() {
                 return err!(ProjectError::StaticPoolNotClosed);
             }
         } else if self.stage != ProjectStage::Created {
@@ -122,11 +340,14 @@ impl Project {
     }
 
     pub fn ensure_can_graduate(&self) -> Result<()> {
-        // pumpfun also counts as graduation.
-        if self.schema.curve_pool == CurvePoolVariant::Pumpfun {
-            if self.schema.use_static_pool {
-                if self.stage != ProjectStage::StaticPoolClosed {
-                    msg!("static pool must be closed before graduating to pumpfun");
+        // pumpfun and meteora pools are created right away, which also counts as graduation.
+        if matches!(
+            self.schema.curve_pool,
+            CurvePoolVariant::Pumpfun | CurvePoolVariant::Meteora
+        ) {
+            if self.schema.uses_static_pool() {
+                if !self.all_static_pools_closed() {
+                    msg!("static pool must be closed before graduating to external pool");
                     return err!(ProjectError::NotReadyForGraduation);
                 }
             } else if self.stage != ProjectStage::Created {
@@ -151,14 +372,79 @@ impl Sizable for Project {
             stage: ProjectStage::Created,
             latch: ProjectLatch::longest(),
             bump: Sizable::longest(),
+            creator: Pubkey::default(),
+        }
+    }
+}
+
+ensure_account_size!(Project, 95);
+
+/// Layout of [`Project`] before the creator and the latch lock time were introduced.
+#[derive(AnchorDeserialize)]
+struct LegacyProject {
+    id: ProjectId,
+    schema: ProjectSchema,
+    stage: ProjectStage,
+    latch: LegacyProjectLatch,
+    bump: u8,
+}
+
+impl LegacyProject {
+    const ACCOUNT_SIZE: usize = 54;
+
+    fn into_project(self, creator: Pubkey) -> Project {
+        Project {
+            id: self.id,
+            schema: self.schema,
+            stage: self.stage,
+            latch: self.latch.into(),
+            bump: self.bump,
+            creator,
+        }
+    }
+}
+
+#[derive(AnchorDeserialize)]
+struct LegacyProjectLatch {
+    project_bank: u64,
+    lamports_before_tx: Option<u64>,
+}
+
+impl From<LegacyProjectLatch> for ProjectLatch {
+    fn from(legacy: LegacyProjectLatch) -> Self {
+        Self {
+            project_bank: legacy.project_bank,
+            lamports_before_tx: legacy.lamports_before_tx,
+            locked_at: None,
         }
     }
 }
 
-ensure_account_size!(Project, 54);
+/// Pending handoff of the project to the new owner.
+#[account]
+#[derive(Debug)]
+pub struct OwnershipTransferProposal {
+    pub project: Pubkey,
+    pub new_owner: Pubkey,
+    pub proposed_at: i64,
+    pub bump: u8,
+}
+
+impl Sizable for OwnershipTransferProposal {
+    fn longest() -> Self {
+        OwnershipTransferProposal {
+            project: Pubkey::default(),
+            new_owner: Pubkey::default(),
+            proposed_at: Sizable::longest(),
+            bump: Sizable::longest(),
+        }
+    }
+}
+
+ensure_account_size!(OwnershipTransferProposal, 81);
 
 #[derive(
-    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord,
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord,
 )]
 pub enum ProjectStage {
     Created,
@@ -170,6 +456,13 @@ pub enum ProjectStage {
     CurvePoolClosed,
 
     Graduated,
+
+    // appended, so stages of existing accounts keep their encoding.
+    SecondStaticPoolActive,
+    SecondStaticPoolClosed,
+
+    /// Static pool missed its goal by the deadline, buyers are refunded.
+    StaticPoolFailed,
 }
 
 impl Sizable for ProjectStage {
@@ -182,8 +475,7 @@ impl Sizable for ProjectStage {
 #[derive(Accounts)]
 #[instruction(data: CreateProjectData)]
 pub struct CreateProjectAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(mut)]
     pub creator: Signer<'info>,
@@ -196,6 +488,12 @@ pub struct CreateProjectAccounts<'info> {
     )]
     pub project: Account<'info, Project>,
 
+    #[account(seeds = [GLOBAL_ACCOUNT_PREFIX], bump = global.bump)]
+    pub global: Account<'info, GlobalCurvedPoolAccount>,
+
+    #[account(mut, seeds = [FEE_ACCOUNT_PREFIX], bump = fee.bump)]
+    pub fee: Account<'info, FeeAccount>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -203,6 +501,7 @@ pub struct CreateProjectAccounts<'info> {
 pub struct CreateProjectData {
     pub id: ProjectId,
     pub schema: ProjectSchema,
+    /// Includes the project creation fee.
     pub creator_deposit: u64,
 }
 
@@ -210,8 +509,7 @@ pub struct CreateProjectData {
 #[derive(Accounts)]
 #[instruction(data: GraduateProjectData)]
 pub struct GraduateProjectAccounts<'info> {
-    #[account(constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(mut,
         seeds = [PROJECT_PREFIX, &data.id.to_bytes()], bump=project.bump
@@ -219,6 +517,28 @@ pub struct GraduateProjectAccounts<'info> {
     pub project: Account<'info, Project>,
 }
 
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelProjectAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        close = creator,
+        constraint = project.creator == creator.key() @ ProjectError::NotProjectOwner,
+    )]
+    pub project: Account<'info, Project>,
+
+    /// CHECK: pending ownership transfer of the project, closed if present
+    #[account(mut, seeds = [OWNERSHIP_TRANSFER_PREFIX, project.key().as_ref()], bump)]
+    pub proposal: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct GraduateProjectData {
     pub id: ProjectId,
@@ -226,8 +546,7 @@ pub struct GraduateProjectData {
 
 #[derive(Accounts)]
 pub struct ProjectLockLatchAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(mut)]
     pub project: Account<'info, Project>,
@@ -235,16 +554,110 @@ pub struct ProjectLockLatchAccounts<'info> {
 
 #[derive(Accounts)]
 pub struct ProjectUnlockLatchAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(mut)]
+    pub project: Account<'info, Project>,
+}
+
+#[derive(Accounts)]
+pub struct ForceUnlockStaleLatchAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(mut)]
+    pub project: Account<'info, Project>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ForceUnlockStaleLatchData {
+    pub max_age_secs: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(data: MigrateProjectData)]
+pub struct MigrateProjectAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    /// CHECK: legacy layout can't be deserialized as [`Project`], so it's checked manually
+    #[account(mut, owner = crate::ID, seeds = [PROJECT_PREFIX, &data.id.to_bytes()], bump)]
+    pub project: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MigrateProjectData {
+    pub id: ProjectId,
+    pub creator: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct ProposeTransferOwnershipAccounts<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(constraint = project.creator == owner.key() @ ProjectError::NotProjectOwner)]
+    pub project: Account<'info, Project>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = OwnershipTransferProposal::ACCOUNT_SIZE,
+        seeds = [OWNERSHIP_TRANSFER_PREFIX, project.key().as_ref()], bump,
+    )]
+    pub proposal: Account<'info, OwnershipTransferProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ProposeTransferOwnershipData {
+    pub new_owner: Pubkey,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptTransferOwnershipAccounts<'info> {
+    pub new_owner: Signer<'info>,
+
+    #[account(mut)]
+    pub project: Account<'info, Project>,
+
+    /// CHECK: previous owner, receives proposal rent back
+    #[account(mut, address = project.creator)]
+    pub previous_owner: UncheckedAccount<'info>,
 
+    #[account(
+        mut,
+        close = previous_owner,
+        has_one = project,
+        has_one = new_owner @ ProjectError::NotProposedOwner,
+        seeds = [OWNERSHIP_TRANSFER_PREFIX, project.key().as_ref()], bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, OwnershipTransferProposal>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTransferOwnershipAccounts<'info> {
     #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(constraint = project.creator == owner.key() @ ProjectError::NotProjectOwner)]
     pub project: Account<'info, Project>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = project,
+        seeds = [OWNERSHIP_TRANSFER_PREFIX, project.key().as_ref()], bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, OwnershipTransferProposal>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct ProjectSchema {
-    pub use_static_pool: bool,
+    /// Up to [`MAX_STATIC_POOLS`], opened sequentially before the curve pool.
+    pub static_pool_count: u8,
     pub curve_pool: CurvePoolVariant,
     pub dev_purchase: Option<u64>,
 }
@@ -252,17 +665,25 @@ pub struct ProjectSchema {
 impl Sizable for ProjectSchema {
     fn longest() -> Self {
         ProjectSchema {
-            use_static_pool: false,
+            static_pool_count: Sizable::longest(),
             curve_pool: CurvePoolVariant::longest(),
             dev_purchase: Some(Sizable::longest()),
         }
     }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, PartialOrd)]
+impl ProjectSchema {
+    pub fn uses_static_pool(&self) -> bool {
+        self.static_pool_count > 0
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, Hash, PartialOrd)]
 pub enum CurvePoolVariant {
     Moonzip,
     Pumpfun,
+    /// Meteora Dynamic AMM pool, seeded by the authority instead of a bonding curve.
+    Meteora,
 }
 
 impl Sizable for CurvePoolVariant {
@@ -275,6 +696,8 @@ impl Sizable for CurvePoolVariant {
 pub struct ProjectLatch {
     pub project_bank: u64,
     pub lamports_before_tx: Option<u64>,
+    /// Unix timestamp of the lock, absent for unlocked latch.
+    pub locked_at: Option<i64>,
 }
 
 impl ProjectLatch {
@@ -282,14 +705,20 @@ impl ProjectLatch {
         Self {
             project_bank: bank,
             lamports_before_tx: None,
+            locked_at: None,
         }
     }
 
+    pub fn is_locked(&self) -> bool {
+        self.lamports_before_tx.is_some()
+    }
+
     pub fn lock(&mut self, authority: &Signer) -> Result<()> {
-        if self.lamports_before_tx.is_some() {
+        if self.is_locked() {
             return err!(ProjectError::ProjectLatchAlreadyLocked);
         }
         self.lamports_before_tx = Some(authority.lamports());
+        self.locked_at = Some(Clock::get()?.unix_timestamp);
         Ok(())
     }
 
@@ -297,6 +726,7 @@ impl ProjectLatch {
         let Some(before) = self.lamports_before_tx.take() else {
             return err!(ProjectError::ProjectLatchNotLocked);
         };
+        self.locked_at = None;
         let after = authority.lamports();
 
         if before > after {
@@ -315,6 +745,21 @@ impl ProjectLatch {
 
         Ok(())
     }
+
+    /// Latches, locked before `locked_at` was introduced, are considered stale right away.
+    pub fn force_unlock(&mut self, now: i64, max_age_secs: u64) -> Result<()> {
+        if !self.is_locked() {
+            return err!(ProjectError::ProjectLatchNotLocked);
+        }
+        let age = now.saturating_sub(self.locked_at.unwrap_or(0));
+        if age <= max_age_secs.try_into().unwrap_or(i64::MAX) {
+            msg!("Latch is locked for {} seconds only", age);
+            return err!(ProjectError::LatchNotStale);
+        }
+        self.lamports_before_tx = None;
+        self.locked_at = None;
+        Ok(())
+    }
 }
 
 impl Sizable for ProjectLatch {
@@ -322,6 +767,7 @@ impl Sizable for ProjectLatch {
         ProjectLatch {
             project_bank: Sizable::longest(),
             lamports_before_tx: Some(Sizable::longest()),
+            locked_at: Some(Sizable::longest()),
         }
     }
 }
@@ -354,4 +800,99 @@ pub enum ProjectError {
 
     #[msg("Project bank is overused")]
     BankOveruse,
+
+    #[msg("Signer is not the project owner")]
+    NotProjectOwner,
+
+    #[msg("Signer is not the proposed project owner")]
+    NotProposedOwner,
+
+    #[msg("Project is already owned by proposed owner")]
+    SameOwner,
+
+    #[msg("Ownership transfer cooldown has not passed yet")]
+    TransferCooldownNotPassed,
+
+    #[msg("Project schema has too many static pools")]
+    TooManyStaticPools,
+
+    #[msg("Project latch is locked too recently to be forced open")]
+    LatchNotStale,
+
+    #[msg("Creator deposit doesn't cover project creation fee")]
+    InsufficientCreationFee,
+
+    #[msg("Project is already deployed and can't be cancelled")]
+    AlreadyDeployed,
+
+    #[msg("Project account already has the current layout")]
+    AlreadyMigrated,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locked_at(ts: Option<i64>) -> ProjectLatch {
+        ProjectLatch {
+            project_bank: 0,
+            lamports_before_tx: Some(1),
+            locked_at: ts,
+        }
+    }
+
+    #[test]
+    fn test_force_unlock_stale_latch() {
+        let mut latch = locked_at(Some(1_000));
+        assert!(latch.force_unlock(1_060, 60).is_err());
+        latch.force_unlock(1_061, 60).unwrap();
+        assert!(!latch.is_locked());
+        assert_eq!(latch.locked_at, None);
+        assert!(latch.force_unlock(2_000, 60).is_err());
+    }
+
+    #[test]
+    fn test_legacy_project_account_size() {
+        let legacy = (
+            ProjectId::longest(),
+            ProjectSchema::longest(),
+            ProjectStage::longest(),
+            (0u64, Some(0u64)),
+            0u8,
+        );
+        let mut serialized = Project::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), LegacyProject::ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_legacy_locked_latch_migrates_as_stale() {
+        let legacy = LegacyProject {
+            id: ProjectId(1),
+            schema: ProjectSchema::longest(),
+            stage: ProjectStage::Created,
+            latch: LegacyProjectLatch {
+                project_bank: 100,
+                lamports_before_tx: Some(1),
+            },
+            bump: 255,
+        };
+        let mut migrated = legacy.into_project(Pubkey::new_unique());
+        assert_eq!(migrated.latch.project_bank, 100);
+        assert!(migrated.latch.is_locked());
+        assert_eq!(migrated.latch.locked_at, None);
+        migrated.latch.force_unlock(1_000, 60).unwrap();
+        assert!(!migrated.latch.is_locked());
+
+        let mut serialized = vec![];
+        migrated.try_serialize(&mut serialized).unwrap();
+        assert!(serialized.len() <= Project::ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_force_unlock_latch_without_timestamp() {
+        let mut latch = locked_at(None);
+        latch.force_unlock(1_000, 60).unwrap();
+        assert!(!latch.is_locked());
+    }
 }
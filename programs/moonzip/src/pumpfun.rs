@@ -225,6 +225,14 @@ impl<'a> SellCalculator<'a> {
         Self { curve }
     }
 
+    /// Shows how much SOL would be received for a fixed amount of tokens, after pumpfun fee
+    pub fn fixed_tokens(&self, tokens: u64) -> u64 {
+        let sols = self.curve.virtual_sol_reserves as u128 * tokens as u128
+            / (self.curve.virtual_token_reserves as u128 + tokens as u128);
+        let sols = sols as u64;
+        sols.saturating_sub(SELL_FEE.part_of(sols))
+    }
+
     /// Shows how much tokens need to be sold to get a fixed amount of SOL
     pub fn fixed_sols(&self, sols: u64) -> u64 {
         let constant = self.curve.constant();
@@ -1,10 +1,12 @@
 use crate::{
+    authority::*,
     common::PoolCloseConditions,
+    curved_pool::global::{GlobalCurvedPoolAccount, GLOBAL_ACCOUNT_PREFIX},
     ensure_account_size,
-    events::{StaticPoolBuyEvent, StaticPoolSellEvent},
-    project::{ProjectId, PROJECT_PREFIX},
+    events::{StaticPoolBuyEvent, StaticPoolDeadlineExtendedEvent, StaticPoolSellEvent},
+    project::{ProjectId, ProjectStage, PROJECT_PREFIX},
     utils::Sizable,
-    FeeAccount, Project, ProjectStage, FEE_ACCOUNT_PREFIX, PROGRAM_AUTHORITY,
+    FeeAccount, Project, FEE_ACCOUNT_PREFIX,
 };
 use anchor_lang::{prelude::*, system_program};
 use anchor_spl::{
@@ -13,13 +15,24 @@ use anchor_spl::{
 };
 
 // matches native SOL decimals so to be 1:1 with it.
-const POOL_TOKEN_DECIMALS: u8 = 9;
+pub const POOL_TOKEN_DECIMALS: u8 = 9;
 pub const STATIC_POOL_PREFIX: &[u8] = b"static-pool";
+pub const WALLET_RECORD_PREFIX: &[u8] = b"wallet-record";
+/// Deadline extension window of global configs, created before it was configurable.
+pub const DEFAULT_MAX_DEADLINE_EXTENSION_SECS: u64 = 7 * 24 * 60 * 60;
 
 pub fn static_pool_address(mint: Pubkey) -> Pubkey {
     Pubkey::find_program_address(&[STATIC_POOL_PREFIX, mint.as_ref()], &crate::ID).0
 }
 
+pub fn wallet_record_address(pool: Pubkey, wallet: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[WALLET_RECORD_PREFIX, pool.as_ref(), wallet.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
 pub fn create(ctx: Context<CreateStaticPoolAccounts>, data: CreateStaticPoolData) -> Result<()> {
     ctx.accounts.project.ensure_can_create_static_pool()?;
 
@@ -30,22 +43,59 @@ pub fn create(ctx: Context<CreateStaticPoolAccounts>, data: CreateStaticPoolData
         state: StaticPoolState::Active,
         project_id: data.project_id,
         bump: ctx.bumps.pool,
+        soft_close_ts: None,
     });
 
-    emit_cpi!(ctx
-        .accounts
-        .project
-        .set_stage(ProjectStage::StaticPoolActive)?);
+    let stage = ctx.accounts.project.static_pool_active_stage();
+    emit_cpi!(ctx.accounts.project.set_stage(stage)?);
+
+    Ok(())
+}
+
+pub fn extend_deadline(
+    ctx: Context<ExtendStaticPoolDeadlineAccounts>,
+    data: ExtendStaticPoolDeadlineData,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    // expired pool must not be revived, even if nobody has closed it yet
+    if pool.close_if_needed() || pool.state != StaticPoolState::Active {
+        return err!(StaticPoolError::AlreadyClosed);
+    }
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    let old_finish_ts = pool.config.close_conditions.finish_ts;
+    if data.new_finish_ts <= old_finish_ts.unwrap_or(0) || data.new_finish_ts <= now {
+        return err!(StaticPoolError::DeadlineNotExtended);
+    }
+    let extended_from = old_finish_ts.unwrap_or(now).max(now);
+    if data.new_finish_ts - extended_from > ctx.accounts.global.config.max_deadline_extension_secs {
+        return err!(StaticPoolError::ExtensionTooLong);
+    }
 
+    pool.config.close_conditions.finish_ts = Some(data.new_finish_ts);
+    emit_cpi!(StaticPoolDeadlineExtendedEvent {
+        project_id: data.project_id,
+        old_finish_ts: old_finish_ts.unwrap_or(0),
+        new_finish_ts: data.new_finish_ts,
+    });
     Ok(())
 }
 
+/// Failed pool is not graduated, but kept for refunds, see [`refund`].
 pub fn graduate(ctx: Context<GraduateStaticPoolAccounts>) -> Result<()> {
+    let was_failed = ctx.accounts.pool.state == StaticPoolState::Failed;
     if ctx.accounts.pool.close_if_needed() {
-        emit_cpi!(ctx
-            .accounts
-            .project
-            .set_stage(ProjectStage::StaticPoolClosed)?);
+        if ctx.accounts.pool.state == StaticPoolState::Failed {
+            if !was_failed {
+                emit_cpi!(ctx
+                    .accounts
+                    .project
+                    .set_stage(ProjectStage::StaticPoolFailed)?);
+            }
+            return Ok(());
+        }
+        let stage = ctx.accounts.project.static_pool_closed_stage();
+        emit_cpi!(ctx.accounts.project.set_stage(stage)?);
     }
 
     if ctx.accounts.pool.state != StaticPoolState::Closed {
@@ -68,10 +118,14 @@ pub fn buy(ctx: Context<BuyFromStaticPoolAccounts>, data: BuyFromStaticPoolData)
     if ctx.accounts.pool.close_if_needed() {
         return err!(StaticPoolError::AlreadyClosed);
     }
+    if ctx.accounts.pool.state == StaticPoolState::SoftClosed {
+        return err!(StaticPoolError::SoftClosed);
+    }
     ctx.accounts.pool.ensure_buy_allowed(data.sols)?;
 
     let sols = data.sols;
-    let fee = ctx.accounts.fee.config.on_buy.part_of(sols);
+    let now = Clock::get()?.unix_timestamp;
+    let fee = ctx.accounts.fee.on_buy(now).compute(sols);
     let sols_after_fee = sols.saturating_sub(fee);
 
     // If limit is set for pool, one can only retrieve up to that limit.
@@ -87,6 +141,26 @@ pub fn buy(ctx: Context<BuyFromStaticPoolAccounts>, data: BuyFromStaticPoolData)
         .collected_lamports
         .checked_add(sols_after_fee)
         .expect("invariant: lamports amount is out of bounds");
+    ctx.accounts.fee.record_volume(sols, now);
+
+    let record = &mut ctx.accounts.wallet_record;
+    if record.wallet == Pubkey::default() {
+        record.project_id = data.project_id;
+        record.wallet = ctx.accounts.user.key();
+        record.bump = ctx.bumps.wallet_record;
+    }
+    record.contributed_lamports = record.contributed_lamports.saturating_add(sols_after_fee);
+    if !is_program_authority(
+        ctx.accounts.user.key,
+        &ctx.accounts.authority.authority_config,
+    ) && ctx
+        .accounts
+        .pool
+        .config
+        .exceeds_purchase_per_wallet(record.contributed_lamports)
+    {
+        return err!(StaticPoolError::WalletCapExceeded);
+    }
 
     let event = StaticPoolBuyEvent {
         project_id: ctx.accounts.project.id,
@@ -99,10 +173,8 @@ pub fn buy(ctx: Context<BuyFromStaticPoolAccounts>, data: BuyFromStaticPoolData)
     emit_cpi!(event);
 
     if ctx.accounts.pool.close_if_needed() {
-        emit_cpi!(ctx
-            .accounts
-            .project
-            .set_stage(ProjectStage::StaticPoolClosed)?);
+        let stage = ctx.accounts.project.static_pool_closed_stage();
+        emit_cpi!(ctx.accounts.project.set_stage(stage)?);
     }
 
     let balance_to_mint = sols_after_fee.saturating_sub(ctx.accounts.pool_mint_account.amount);
@@ -164,7 +236,8 @@ pub fn sell(ctx: Context<SellToStaticPoolAccounts>, data: SellToStaticPoolData)
 
     let input = data.tokens;
     let output = data.tokens;
-    let fee = ctx.accounts.fee.config.on_sell.part_of(output);
+    let now = Clock::get()?.unix_timestamp;
+    let fee = ctx.accounts.fee.on_sell(now).compute(output);
     let output_after_fee = output.saturating_sub(fee);
 
     ctx.accounts.pool.collected_lamports = ctx
@@ -173,6 +246,7 @@ pub fn sell(ctx: Context<SellToStaticPoolAccounts>, data: SellToStaticPoolData)
         .collected_lamports
         .checked_sub(output)
         .expect("invariant: lamports amount becomes negative");
+    ctx.accounts.fee.record_volume(output, now);
 
     let event = StaticPoolSellEvent {
         project_id: ctx.accounts.project.id,
@@ -203,16 +277,133 @@ pub fn sell(ctx: Context<SellToStaticPoolAccounts>, data: SellToStaticPoolData)
     Ok(())
 }
 
+/// Burns tokens of the failed pool for the proportional part of collected lamports.
+/// Refund is reported as a sell, as it's the same for the pool balance.
+pub fn refund(
+    ctx: Context<RefundFromStaticPoolAccounts>,
+    data: RefundFromStaticPoolData,
+) -> Result<()> {
+    let was_failed = ctx.accounts.pool.state == StaticPoolState::Failed;
+    ctx.accounts.pool.close_if_needed();
+    if ctx.accounts.pool.state != StaticPoolState::Failed {
+        return err!(StaticPoolError::NotFailed);
+    }
+    if !was_failed {
+        emit_cpi!(ctx
+            .accounts
+            .project
+            .set_stage(ProjectStage::StaticPoolFailed)?);
+    }
+
+    let circulating = ctx
+        .accounts
+        .mint
+        .supply
+        .saturating_sub(ctx.accounts.pool_token_account.amount);
+    if data.tokens == 0 || data.tokens > circulating {
+        return err!(StaticPoolError::LimitViolated);
+    }
+    let refund = ctx.accounts.pool.refund_for(data.tokens, circulating);
+
+    anchor_spl::token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.user_token_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        data.tokens,
+    )?;
+
+    ctx.accounts.pool.collected_lamports -= refund;
+    emit_cpi!(StaticPoolSellEvent {
+        project_id: ctx.accounts.project.id,
+        user: ctx.accounts.user.key(),
+
+        new_collected_sols: ctx.accounts.pool.collected_lamports,
+        request_tokens: data.tokens,
+        output_sols: refund,
+    });
+
+    ctx.accounts.pool.sub_lamports(refund)?;
+    ctx.accounts.user.add_lamports(refund)?;
+
+    if data.tokens == circulating {
+        ctx.accounts
+            .pool
+            .close(ctx.accounts.authority.to_account_info())?;
+    }
+    Ok(())
+}
+
+/// Rent of the wallet record is returned to its wallet
+/// by [`close_wallet_record`], once the pool is graduated.
+pub fn close_wallet_record(_ctx: Context<CloseStaticPoolWalletRecordAccounts>) -> Result<()> {
+    Ok(())
+}
+
+/// Rewrites pool, created before per-wallet purchase caps, grace period or minimal goal
+/// were introduced, into the current layout. Migrated pool has none of them.
+pub fn migrate_static_pool(ctx: Context<MigrateStaticPoolAccounts>) -> Result<()> {
+    let pool_info = ctx.accounts.pool.to_account_info();
+    let migrated: StaticPool = {
+        let data = pool_info.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != StaticPool::DISCRIMINATOR {
+            return err!(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+        }
+        let data = &mut &data[8..];
+        match pool_info.data_len() {
+            LegacyStaticPool::ACCOUNT_SIZE => LegacyStaticPool::deserialize(data)?.into(),
+            WalletCapStaticPool::ACCOUNT_SIZE => WalletCapStaticPool::deserialize(data)?.into(),
+            GraceStaticPool::ACCOUNT_SIZE => GraceStaticPool::deserialize(data)?.into(),
+            _ => return err!(StaticPoolError::AlreadyMigrated),
+        }
+    };
+    if migrated.mint != ctx.accounts.mint.key() {
+        return err!(anchor_lang::error::ErrorCode::ConstraintSeeds);
+    }
+
+    let rent_minimum = Rent::get()?.minimum_balance(StaticPool::ACCOUNT_SIZE);
+    let missing = rent_minimum.saturating_sub(pool_info.lamports());
+    if missing > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: pool_info.clone(),
+                },
+            ),
+            missing,
+        )?;
+    }
+    pool_info.realloc(StaticPool::ACCOUNT_SIZE, false)?;
+
+    let mut data = pool_info.try_borrow_mut_data()?;
+    migrated.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, PartialEq, PartialOrd)]
 pub struct StaticPoolConfig {
     pub min_purchase_lamports: Option<u64>,
     pub close_conditions: PoolCloseConditions,
+    /// Lamports, a single wallet could contribute to the pool in total, fee excluded.
+    /// Sells don't give the allowance back.
+    pub max_purchase_per_wallet: Option<u64>,
 }
 
 impl StaticPoolConfig {
     pub fn min_purchase_lamports(&self) -> u64 {
         self.min_purchase_lamports.unwrap_or(1)
     }
+
+    pub fn exceeds_purchase_per_wallet(&self, contributed_lamports: u64) -> bool {
+        self.max_purchase_per_wallet
+            .is_some_and(|max| contributed_lamports > max)
+    }
 }
 
 impl Sizable for StaticPoolConfig {
@@ -220,10 +411,34 @@ impl Sizable for StaticPoolConfig {
         Self {
             min_purchase_lamports: Some(Sizable::longest()),
             close_conditions: Sizable::longest(),
+            max_purchase_per_wallet: Some(Sizable::longest()),
+        }
+    }
+}
+
+/// Total contribution of a single wallet to a single static pool.
+#[account]
+#[derive(Default, PartialEq, PartialOrd, Debug)]
+pub struct StaticPoolWalletRecord {
+    pub project_id: ProjectId,
+    pub wallet: Pubkey,
+    pub contributed_lamports: u64,
+    pub bump: u8,
+}
+
+impl Sizable for StaticPoolWalletRecord {
+    fn longest() -> Self {
+        Self {
+            project_id: Sizable::longest(),
+            wallet: Default::default(),
+            contributed_lamports: Sizable::longest(),
+            bump: Sizable::longest(),
         }
     }
 }
 
+ensure_account_size!(StaticPoolWalletRecord, 65);
+
 #[account]
 #[derive(Default, PartialEq, PartialOrd)]
 pub struct StaticPool {
@@ -233,19 +448,55 @@ pub struct StaticPool {
     pub collected_lamports: u64,
     pub project_id: ProjectId,
     pub bump: u8,
+    /// Set once `max_lamports` is reached with a grace period, pool is closed after it.
+    pub soft_close_ts: Option<u64>,
 }
 
 impl StaticPool {
+    /// Tells whether the pool is closed, soft-closed pool is still open for sells.
     pub fn close_if_needed(&mut self) -> bool {
-        if self.config.close_conditions.should_be_closed(
-            self.collected_lamports,
-            Clock::get().unwrap().unix_timestamp as u64,
-        ) {
+        self.close_if_needed_at(Clock::get().unwrap().unix_timestamp as u64)
+    }
+
+    /// Same as [`Self::close_if_needed`], at the given time.
+    pub fn close_if_needed_at(&mut self, now: u64) -> bool {
+        if self.state == StaticPoolState::Failed {
+            return true;
+        }
+        let conditions = &self.config.close_conditions;
+        let closed = match self.soft_close_ts {
+            Some(soft_close_ts) => now >= soft_close_ts || conditions.deadline_passed(now),
+            None if conditions.deadline_passed(now) => {
+                if conditions.goal_missed(self.collected_lamports) {
+                    self.state = StaticPoolState::Failed;
+                    return true;
+                }
+                true
+            }
+            None if conditions.limit_reached(self.collected_lamports) => {
+                match conditions.grace_period_secs {
+                    Some(grace_period_secs) => {
+                        self.soft_close_ts = Some(now.saturating_add(grace_period_secs));
+                        self.state = StaticPoolState::SoftClosed;
+                        false
+                    }
+                    None => true,
+                }
+            }
+            None => false,
+        };
+        if closed {
             self.state = StaticPoolState::Closed;
-            true
-        } else {
-            false
         }
+        closed
+    }
+
+    /// Part of collected lamports, owed for `tokens` out of `circulating` ones.
+    pub fn refund_for(&self, tokens: u64, circulating: u64) -> u64 {
+        if circulating == 0 {
+            return 0;
+        }
+        ((tokens as u128) * (self.collected_lamports as u128) / (circulating as u128)) as u64
     }
 
     pub fn ensure_buy_allowed(&self, lamports: u64) -> Result<()> {
@@ -265,14 +516,19 @@ impl Sizable for StaticPool {
             collected_lamports: Sizable::longest(),
             project_id: Sizable::longest(),
             bump: Sizable::longest(),
+            soft_close_ts: Some(Sizable::longest()),
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, PartialOrd, AnchorSerialize, AnchorDeserialize)]
+#[derive(Clone, Copy, PartialEq, PartialOrd, AnchorSerialize, AnchorDeserialize, Debug)]
 pub enum StaticPoolState {
     Active,
     Closed,
+    /// Limit is reached, but sells are accepted until `soft_close_ts`.
+    SoftClosed,
+    /// Goal is missed by the deadline, only refunds are accepted.
+    Failed,
 }
 
 impl Default for StaticPoolState {
@@ -283,11 +539,167 @@ impl Default for StaticPoolState {
 
 impl Sizable for StaticPoolState {
     fn longest() -> Self {
-        Self::Closed
+        Self::SoftClosed
+    }
+}
+
+ensure_account_size!(StaticPool, 129);
+
+/// Layout of [`PoolCloseConditions`] before the grace period.
+#[derive(AnchorDeserialize)]
+struct LegacyPoolCloseConditions {
+    max_lamports: Option<u64>,
+    finish_ts: Option<u64>,
+}
+
+impl From<LegacyPoolCloseConditions> for PoolCloseConditions {
+    fn from(legacy: LegacyPoolCloseConditions) -> Self {
+        Self {
+            max_lamports: legacy.max_lamports,
+            finish_ts: legacy.finish_ts,
+            grace_period_secs: None,
+            min_lamports: None,
+        }
+    }
+}
+
+/// Layout of [`PoolCloseConditions`] before the minimal goal.
+#[derive(AnchorDeserialize)]
+struct GracePoolCloseConditions {
+    max_lamports: Option<u64>,
+    finish_ts: Option<u64>,
+    grace_period_secs: Option<u64>,
+}
+
+impl From<GracePoolCloseConditions> for PoolCloseConditions {
+    fn from(legacy: GracePoolCloseConditions) -> Self {
+        Self {
+            max_lamports: legacy.max_lamports,
+            finish_ts: legacy.finish_ts,
+            grace_period_secs: legacy.grace_period_secs,
+            min_lamports: None,
+        }
+    }
+}
+
+/// Layout of [`StaticPool`] before per-wallet purchase caps.
+#[derive(AnchorDeserialize)]
+struct LegacyStaticPool {
+    mint: Pubkey,
+    min_purchase_lamports: Option<u64>,
+    close_conditions: LegacyPoolCloseConditions,
+    state: StaticPoolState,
+    collected_lamports: u64,
+    project_id: ProjectId,
+    bump: u8,
+}
+
+impl LegacyStaticPool {
+    const ACCOUNT_SIZE: usize = 93;
+}
+
+impl From<LegacyStaticPool> for StaticPool {
+    fn from(legacy: LegacyStaticPool) -> Self {
+        Self {
+            mint: legacy.mint,
+            config: StaticPoolConfig {
+                min_purchase_lamports: legacy.min_purchase_lamports,
+                close_conditions: legacy.close_conditions.into(),
+                max_purchase_per_wallet: None,
+            },
+            state: legacy.state,
+            collected_lamports: legacy.collected_lamports,
+            project_id: legacy.project_id,
+            bump: legacy.bump,
+            soft_close_ts: None,
+        }
+    }
+}
+
+/// Layout of [`StaticPool`] before the grace period.
+#[derive(AnchorDeserialize)]
+struct WalletCapStaticPool {
+    mint: Pubkey,
+    min_purchase_lamports: Option<u64>,
+    close_conditions: LegacyPoolCloseConditions,
+    max_purchase_per_wallet: Option<u64>,
+    state: StaticPoolState,
+    collected_lamports: u64,
+    project_id: ProjectId,
+    bump: u8,
+}
+
+impl WalletCapStaticPool {
+    const ACCOUNT_SIZE: usize = 102;
+}
+
+impl From<WalletCapStaticPool> for StaticPool {
+    fn from(legacy: WalletCapStaticPool) -> Self {
+        Self {
+            mint: legacy.mint,
+            config: StaticPoolConfig {
+                min_purchase_lamports: legacy.min_purchase_lamports,
+                close_conditions: legacy.close_conditions.into(),
+                max_purchase_per_wallet: legacy.max_purchase_per_wallet,
+            },
+            state: legacy.state,
+            collected_lamports: legacy.collected_lamports,
+            project_id: legacy.project_id,
+            bump: legacy.bump,
+            soft_close_ts: None,
+        }
+    }
+}
+
+/// Layout of [`StaticPool`] before the minimal goal.
+#[derive(AnchorDeserialize)]
+struct GraceStaticPool {
+    mint: Pubkey,
+    min_purchase_lamports: Option<u64>,
+    close_conditions: GracePoolCloseConditions,
+    max_purchase_per_wallet: Option<u64>,
+    state: StaticPoolState,
+    collected_lamports: u64,
+    project_id: ProjectId,
+    bump: u8,
+    soft_close_ts: Option<u64>,
+}
+
+impl GraceStaticPool {
+    const ACCOUNT_SIZE: usize = 120;
+}
+
+impl From<GraceStaticPool> for StaticPool {
+    fn from(legacy: GraceStaticPool) -> Self {
+        Self {
+            mint: legacy.mint,
+            config: StaticPoolConfig {
+                min_purchase_lamports: legacy.min_purchase_lamports,
+                close_conditions: legacy.close_conditions.into(),
+                max_purchase_per_wallet: legacy.max_purchase_per_wallet,
+            },
+            state: legacy.state,
+            collected_lamports: legacy.collected_lamports,
+            project_id: legacy.project_id,
+            bump: legacy.bump,
+            soft_close_ts: legacy.soft_close_ts,
+        }
     }
 }
 
-ensure_account_size!(StaticPool, 93);
+#[derive(Accounts)]
+pub struct MigrateStaticPoolAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    /// CHECK: only seeds the pool address
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: legacy layout can't be deserialized as [`StaticPool`], so it's checked manually
+    #[account(mut, owner = crate::ID, seeds = [STATIC_POOL_PREFIX, mint.key().as_ref()], bump)]
+    pub pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct CreateStaticPoolData {
@@ -299,12 +711,11 @@ pub struct CreateStaticPoolData {
 #[derive(Accounts)]
 #[instruction(data: CreateStaticPoolData)]
 pub struct CreateStaticPoolAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(
         init,
-        payer = authority,
+        payer = authority.authority,
         mint::decimals = POOL_TOKEN_DECIMALS,
         mint::authority = authority,
         mint::freeze_authority = authority
@@ -319,7 +730,7 @@ pub struct CreateStaticPoolAccounts<'info> {
 
     #[account(
         init,
-        payer = authority,
+        payer = authority.authority,
         associated_token::mint = mint,
         associated_token::authority = pool,
     )]
@@ -327,7 +738,7 @@ pub struct CreateStaticPoolAccounts<'info> {
 
     #[account(
         init,
-        payer = authority,
+        payer = authority.authority,
         space = StaticPool::ACCOUNT_SIZE, seeds = [STATIC_POOL_PREFIX, mint.key().as_ref()], bump
     )]
     pub pool: Account<'info, StaticPool>,
@@ -346,11 +757,11 @@ pub struct BuyFromStaticPoolData {
 #[derive(Accounts)]
 #[instruction(data: BuyFromStaticPoolData)]
 pub struct BuyFromStaticPoolAccounts<'info> {
-    #[account(constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(
         mut,
+        address = FeeAccount::address(),
         seeds = [FEE_ACCOUNT_PREFIX], bump=fee.bump
     )]
     pub fee: Account<'info, FeeAccount>,
@@ -388,6 +799,14 @@ pub struct BuyFromStaticPoolAccounts<'info> {
     )]
     pub pool: Account<'info, StaticPool>,
 
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StaticPoolWalletRecord::ACCOUNT_SIZE,
+        seeds = [WALLET_RECORD_PREFIX, pool.key().as_ref(), user.key().as_ref()], bump
+    )]
+    pub wallet_record: Account<'info, StaticPoolWalletRecord>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -403,11 +822,11 @@ pub struct SellToStaticPoolData {
 #[derive(Accounts)]
 #[instruction(data: SellToStaticPoolData)]
 pub struct SellToStaticPoolAccounts<'info> {
-    #[account(constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(
         mut,
+        address = FeeAccount::address(),
         seeds = [FEE_ACCOUNT_PREFIX], bump=fee.bump
     )]
     pub fee: Account<'info, FeeAccount>,
@@ -449,11 +868,38 @@ pub struct SellToStaticPoolAccounts<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ExtendStaticPoolDeadlineData {
+    pub project_id: ProjectId,
+    pub new_finish_ts: u64,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(data: ExtendStaticPoolDeadlineData)]
+pub struct ExtendStaticPoolDeadlineAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(
+        constraint = pool.project_id == project.id,
+        seeds = [PROJECT_PREFIX, &data.project_id.to_bytes()], bump = project.bump
+    )]
+    pub project: Account<'info, Project>,
+
+    #[account(
+        mut,
+        seeds = [STATIC_POOL_PREFIX, pool.mint.as_ref()], bump = pool.bump
+    )]
+    pub pool: Account<'info, StaticPool>,
+
+    #[account(seeds = [GLOBAL_ACCOUNT_PREFIX], bump = global.bump)]
+    pub global: Account<'info, GlobalCurvedPoolAccount>,
+}
+
 #[event_cpi]
 #[derive(Accounts)]
 pub struct GraduateStaticPoolAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(mut, constraint = project.id == pool.project_id)]
     pub project: Account<'info, Project>,
@@ -473,6 +919,85 @@ pub struct GraduateStaticPoolAccounts<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct RefundFromStaticPoolData {
+    pub project_id: ProjectId,
+    pub tokens: u64,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(data: RefundFromStaticPoolData)]
+pub struct RefundFromStaticPoolAccounts<'info> {
+    /// CHECK: paid rent of the pool, so receives it back after the last refund
+    #[account(mut, constraint = is_program_authority(authority.key, &authority_config))]
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: see [`is_program_authority`]
+    #[account(seeds = [AUTHORITY_CONFIG_PREFIX], bump)]
+    pub authority_config: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = pool.project_id == project.id,
+        seeds = [PROJECT_PREFIX, &data.project_id.to_bytes()], bump = project.bump
+    )]
+    pub project: Account<'info, Project>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        associated_token::mint = mint,
+        associated_token::authority = pool,
+    )]
+    pub pool_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut,
+        seeds = [STATIC_POOL_PREFIX, mint.key().as_ref()], bump = pool.bump
+    )]
+    pub pool: Account<'info, StaticPool>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStaticPoolWalletRecordAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    /// CHECK: only for pool address derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: graduated pool is closed, so only its address is checked
+    #[account(
+        seeds = [STATIC_POOL_PREFIX, mint.key().as_ref()], bump,
+        constraint = pool.data_is_empty() @ StaticPoolError::NotGraduated
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = wallet,
+        has_one = wallet,
+        seeds = [WALLET_RECORD_PREFIX, pool.key().as_ref(), wallet.key().as_ref()],
+        bump = wallet_record.bump
+    )]
+    pub wallet_record: Account<'info, StaticPoolWalletRecord>,
+
+    #[account(mut)]
+    pub wallet: SystemAccount<'info>,
+}
+
 #[error_code]
 pub enum StaticPoolError {
     #[msg("Pool limit is violated during operation")]
@@ -489,4 +1014,159 @@ pub enum StaticPoolError {
 
     #[msg("Pool is not graduated yet")]
     NotGraduated,
+
+    #[msg("New deadline must be later than both the current deadline and now")]
+    DeadlineNotExtended,
+
+    #[msg("Deadline extension exceeds the allowed window")]
+    ExtensionTooLong,
+
+    #[msg("Wallet exceeds its purchase cap in the pool")]
+    WalletCapExceeded,
+
+    #[msg("Pool has reached its limit and accepts only sells until it's closed")]
+    SoftClosed,
+
+    #[msg("Pool hasn't failed, so nothing is refunded")]
+    NotFailed,
+
+    #[msg("Pool account already has the current layout")]
+    AlreadyMigrated,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_static_pool_sizes() {
+        fn size(fields: impl AnchorSerialize) -> usize {
+            let mut serialized = StaticPool::DISCRIMINATOR.to_vec();
+            fields.serialize(&mut serialized).unwrap();
+            serialized.len()
+        }
+        let some = Some(u64::MAX);
+        let tail = (StaticPoolState::Active, 0u64, ProjectId::longest(), 0u8);
+        assert_eq!(
+            size((Pubkey::default(), some, (some, some), tail)),
+            LegacyStaticPool::ACCOUNT_SIZE
+        );
+        assert_eq!(
+            size((Pubkey::default(), some, (some, some), some, tail)),
+            WalletCapStaticPool::ACCOUNT_SIZE
+        );
+        assert_eq!(
+            size((
+                Pubkey::default(),
+                some,
+                (some, some, some),
+                some,
+                tail,
+                some
+            )),
+            GraceStaticPool::ACCOUNT_SIZE
+        );
+    }
+
+    #[test]
+    fn test_grace_static_pool_migration_keeps_soft_close() {
+        let mint = Pubkey::new_unique();
+        let mut serialized = vec![];
+        (
+            mint,
+            Some(10u64),
+            (Some(1_000u64), Some(5_000u64), Some(60u64)),
+            Some(500u64),
+            StaticPoolState::SoftClosed,
+            1_000u64,
+            ProjectId(3),
+            254u8,
+            Some(160u64),
+        )
+            .serialize(&mut serialized)
+            .unwrap();
+        let pool: StaticPool = GraceStaticPool::deserialize(&mut serialized.as_slice())
+            .unwrap()
+            .into();
+        assert_eq!(pool.mint, mint);
+        assert_eq!(pool.config.max_purchase_per_wallet, Some(500));
+        assert_eq!(pool.config.close_conditions.grace_period_secs, Some(60));
+        assert_eq!(pool.config.close_conditions.min_lamports, None);
+        assert_eq!(pool.state, StaticPoolState::SoftClosed);
+        assert_eq!(pool.soft_close_ts, Some(160));
+        assert_eq!(pool.bump, 254);
+    }
+
+    fn pool(grace_period_secs: Option<u64>) -> StaticPool {
+        StaticPool {
+            config: StaticPoolConfig {
+                close_conditions: PoolCloseConditions {
+                    max_lamports: Some(1000),
+                    finish_ts: Some(10_000),
+                    grace_period_secs,
+                    min_lamports: Some(500),
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_limit_closes_pool_without_grace() {
+        let mut pool = pool(None);
+        pool.collected_lamports = 1000;
+        assert!(pool.close_if_needed_at(100));
+        assert_eq!(pool.state, StaticPoolState::Closed);
+    }
+
+    #[test]
+    fn test_limit_soft_closes_pool_with_grace() {
+        let mut pool = pool(Some(60));
+        pool.collected_lamports = 1000;
+        assert!(!pool.close_if_needed_at(100));
+        assert_eq!(pool.state, StaticPoolState::SoftClosed);
+        assert_eq!(pool.soft_close_ts, Some(160));
+
+        // sells during grace period don't reopen the pool for buys
+        pool.collected_lamports = 400;
+        assert!(!pool.close_if_needed_at(159));
+        assert_eq!(pool.state, StaticPoolState::SoftClosed);
+
+        assert!(pool.close_if_needed_at(160));
+        assert_eq!(pool.state, StaticPoolState::Closed);
+    }
+
+    #[test]
+    fn test_deadline_cuts_grace_period() {
+        let mut pool = pool(Some(60));
+        pool.collected_lamports = 1000;
+        assert!(!pool.close_if_needed_at(9_990));
+        assert!(pool.close_if_needed_at(10_000));
+        assert_eq!(pool.state, StaticPoolState::Closed);
+    }
+
+    #[test]
+    fn test_missed_goal_fails_pool() {
+        let mut pool = pool(None);
+        pool.collected_lamports = 300;
+        assert!(!pool.close_if_needed_at(9_999));
+        assert!(pool.close_if_needed_at(10_000));
+        assert_eq!(pool.state, StaticPoolState::Failed);
+        assert!(pool.close_if_needed_at(10_001));
+        assert_eq!(pool.state, StaticPoolState::Failed);
+
+        assert_eq!(pool.refund_for(100, 300), 100);
+        pool.collected_lamports = 299;
+        assert_eq!(pool.refund_for(100, 300), 99);
+        assert_eq!(pool.refund_for(300, 300), 299);
+    }
+
+    #[test]
+    fn test_reached_goal_closes_pool() {
+        let mut pool = pool(None);
+        pool.collected_lamports = 500;
+        assert!(pool.close_if_needed_at(10_000));
+        assert_eq!(pool.state, StaticPoolState::Closed);
+    }
 }
@@ -1,13 +1,16 @@
 use crate::{
-    curved_pool::{curve::CurveState, CurvedPool, CURVED_POOL_PREFIX},
+    authority::*,
+    curved_pool::{
+        curve::{CurveState, LegacyCurveState},
+        CurvedPool, CURVED_POOL_PREFIX,
+    },
     ensure_account_size,
     moonzip::curve::CalcSell as _,
     program::Moonzip,
     pumpfun::{self, seeds::BONDING_CURVE_SEED, CurveWrapper},
     utils::Sizable,
-    PROGRAM_AUTHORITY,
 };
-use anchor_lang::{prelude::*, Bumps};
+use anchor_lang::{prelude::*, system_program, Bumps};
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::{Mint, Token, TokenAccount},
@@ -16,20 +19,26 @@ use pumpfun_cpi::BondingCurve;
 
 pub const TRANSMUTER_PREFIX: &[u8] = b"transmuter";
 
-pub fn init_for_curve(ctx: Context<InitTransmuterForCurveAccounts>) -> Result<()> {
+pub fn init_for_curve(
+    ctx: Context<InitTransmuterForCurveAccounts>,
+    data: InitTransmuterData,
+) -> Result<()> {
     let method = TransmuteMethod::CurveLimit {
         curve_snapshot: ctx.accounts.curved_pool.curve,
     };
-    base_transmuter_init(ctx, method)?;
+    base_transmuter_init(ctx, method, data)?;
 
     Ok(())
 }
 
-pub fn init_for_pumpfun_curve(ctx: Context<InitTransmuterForPumpfunCurveAccounts>) -> Result<()> {
+pub fn init_for_pumpfun_curve(
+    ctx: Context<InitTransmuterForPumpfunCurveAccounts>,
+    data: InitTransmuterData,
+) -> Result<()> {
     let method = TransmuteMethod::PumpfunCurveLimit {
         curve_snapshot: (*ctx.accounts.bonding_curve).into(),
     };
-    base_transmuter_init(ctx, method)?;
+    base_transmuter_init(ctx, method, data)?;
 
     Ok(())
 }
@@ -37,6 +46,7 @@ pub fn init_for_pumpfun_curve(ctx: Context<InitTransmuterForPumpfunCurveAccounts
 fn base_transmuter_init<'a, A: TransmuterInitAccounts<'a> + Bumps>(
     ctx: Context<A>,
     method: TransmuteMethod,
+    data: InitTransmuterData,
 ) -> Result<()> {
     let bump = ctx.accounts.transmuter_bump(&ctx.bumps);
     let base = ctx.accounts.base();
@@ -45,6 +55,7 @@ fn base_transmuter_init<'a, A: TransmuterInitAccounts<'a> + Bumps>(
         to_mint: base.to_mint.key(),
         method,
         bump,
+        expiry_ts: data.expiry_ts,
     });
 
     anchor_spl::token::transfer(
@@ -71,6 +82,13 @@ fn base_transmuter_init<'a, A: TransmuterInitAccounts<'a> + Bumps>(
 }
 
 pub fn transmute(ctx: Context<TransmuteAccounts>, data: TransmuteData) -> Result<()> {
+    require!(
+        !ctx.accounts
+            .transmuter
+            .is_expired(Clock::get()?.unix_timestamp),
+        TransmuterError::Expired
+    );
+
     anchor_spl::token::burn(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
@@ -83,7 +101,7 @@ pub fn transmute(ctx: Context<TransmuteAccounts>, data: TransmuteData) -> Result
         data.tokens,
     )?;
 
-    if ctx.accounts.user_from_token_account.amount == 0 {
+    if !data.partial && ctx.accounts.user_from_token_account.amount == 0 {
         anchor_spl::token::close_account(CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             anchor_spl::token::CloseAccount {
@@ -94,14 +112,7 @@ pub fn transmute(ctx: Context<TransmuteAccounts>, data: TransmuteData) -> Result
         ))?;
     }
 
-    let tokens = match &ctx.accounts.transmuter.method {
-        TransmuteMethod::CurveLimit { curve_snapshot } => {
-            super::curve::SellCalculator::new(curve_snapshot).fixed_sols(data.tokens)
-        }
-        TransmuteMethod::PumpfunCurveLimit { curve_snapshot } => {
-            pumpfun::SellCalculator::new(curve_snapshot).fixed_sols(data.tokens)
-        }
-    };
+    let tokens = ctx.accounts.transmuter.method.tokens_for(data.tokens);
 
     anchor_spl::token::transfer(
         CpiContext::new_with_signer(
@@ -121,6 +132,11 @@ pub fn transmute(ctx: Context<TransmuteAccounts>, data: TransmuteData) -> Result
         tokens,
     )?;
 
+    // partial transmutation is repeated, so the transmuter must stay alive
+    if data.partial {
+        return Ok(());
+    }
+
     if ctx.accounts.transmuter_to_token_account.amount == 0 {
         anchor_spl::token::close_account(CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
@@ -139,8 +155,11 @@ pub fn transmute(ctx: Context<TransmuteAccounts>, data: TransmuteData) -> Result
     Ok(())
 }
 
-/// It will transmute all available user tokens(if any)
-pub fn transmute_idempotent(ctx: Context<TransmuteIdempotentAccounts>) -> Result<()> {
+/// It will transmute all available user tokens(if any), but no more than `max_tokens`, if given.
+pub fn transmute_idempotent(
+    ctx: Context<TransmuteIdempotentAccounts>,
+    data: TransmuteIdempotentData,
+) -> Result<()> {
     if ctx.accounts.transmuter.data_is_empty() {
         msg!("transmuter is already finished");
         return Ok(());
@@ -151,12 +170,21 @@ pub fn transmute_idempotent(ctx: Context<TransmuteIdempotentAccounts>) -> Result
         return Ok(());
     }
 
-    let mint = anchor_spl::token::TokenAccount::try_deserialize(
-        &mut ctx.accounts.from_mint.data.borrow().as_ref(),
+    let transmuter =
+        Transmuter::try_deserialize(&mut ctx.accounts.transmuter.data.borrow().as_ref())?;
+    if transmuter.is_expired(Clock::get()?.unix_timestamp) {
+        // trades of the user must not be blocked by tokens, which are no longer claimable
+        msg!("transmuter is expired");
+        return Ok(());
+    }
+
+    let user_from_token_account = anchor_spl::token::TokenAccount::try_deserialize(
+        &mut ctx.accounts.user_from_token_account.data.borrow().as_ref(),
     )
     .expect("from mint token account of user is invalid");
 
-    let tokens = mint.amount;
+    let available = user_from_token_account.amount;
+    let tokens = data.max_tokens.map_or(available, |max| max.min(available));
 
     crate::cpi::transmute(
         CpiContext::new(
@@ -178,9 +206,102 @@ pub fn transmute_idempotent(ctx: Context<TransmuteIdempotentAccounts>) -> Result
                 associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
             },
         ),
-        TransmuteData { tokens },
+        TransmuteData {
+            tokens,
+            partial: data.max_tokens.is_some(),
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Rewrites transmuter, created before curve kinds or expiry were introduced,
+/// into the current layout. Transmuter without expiry keeps serving forever, until it's set.
+pub fn migrate_transmuter(ctx: Context<MigrateTransmuterAccounts>) -> Result<()> {
+    let transmuter_info = ctx.accounts.transmuter.to_account_info();
+    let legacy = {
+        let data = transmuter_info.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != Transmuter::DISCRIMINATOR {
+            return err!(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+        }
+        match data.len() {
+            LegacyTransmuter::ACCOUNT_SIZE => {
+                LegacyTransmuter::deserialize(&mut &data[8..])?.into()
+            }
+            // only pumpfun transmuters fit there, their method didn't change
+            ExpiringLegacyTransmuter::ACCOUNT_SIZE => {
+                ExpiringLegacyTransmuter::deserialize(&mut &data[8..])?
+            }
+            _ => return err!(TransmuterError::AlreadyMigrated),
+        }
+    };
+
+    let rent_minimum = Rent::get()?.minimum_balance(Transmuter::ACCOUNT_SIZE);
+    let missing = rent_minimum.saturating_sub(transmuter_info.lamports());
+    if missing > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: transmuter_info.clone(),
+                },
+            ),
+            missing,
+        )?;
+    }
+    transmuter_info.realloc(Transmuter::ACCOUNT_SIZE, false)?;
+
+    let migrated = Transmuter::from(legacy);
+    let mut data = transmuter_info.try_borrow_mut_data()?;
+    migrated.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+pub fn set_expiry(
+    ctx: Context<SetTransmuterExpiryAccounts>,
+    data: SetTransmuterExpiryData,
+) -> Result<()> {
+    ctx.accounts.transmuter.expiry_ts = data.expiry_ts;
+    Ok(())
+}
+
+/// Tokens, nobody claimed in time, are burned, then transmuter accounts return their rent.
+pub fn close_expired(ctx: Context<CloseExpiredTransmuterAccounts>) -> Result<()> {
+    let transmuter = &ctx.accounts.transmuter;
+    require!(
+        transmuter.is_expired(Clock::get()?.unix_timestamp),
+        TransmuterError::NotExpired
+    );
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        TRANSMUTER_PREFIX,
+        transmuter.from_mint.as_ref(),
+        transmuter.to_mint.as_ref(),
+        &[transmuter.bump],
+    ]];
+
+    anchor_spl::token::burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Burn {
+                from: ctx.accounts.transmuter_to_token_account.to_account_info(),
+                mint: ctx.accounts.to_mint.to_account_info(),
+                authority: ctx.accounts.transmuter.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        ctx.accounts.transmuter_to_token_account.amount,
     )?;
 
+    anchor_spl::token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        anchor_spl::token::CloseAccount {
+            account: ctx.accounts.transmuter_to_token_account.to_account_info(),
+            destination: ctx.accounts.authority.to_account_info(),
+            authority: ctx.accounts.transmuter.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
     Ok(())
 }
 
@@ -232,8 +353,7 @@ impl<'info> TransmuterInitAccounts<'info> for InitTransmuterForPumpfunCurveAccou
 
 #[derive(Accounts)]
 pub struct BaseInitTransmuterAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     pub from_mint: Account<'info, Mint>,
     pub to_mint: Account<'info, Mint>,
@@ -248,7 +368,7 @@ pub struct BaseInitTransmuterAccounts<'info> {
 
     #[account(
         init,
-        payer = authority,
+        payer = authority.authority,
         associated_token::mint = to_mint,
         associated_token::authority = transmuter,
     )]
@@ -256,7 +376,7 @@ pub struct BaseInitTransmuterAccounts<'info> {
 
     #[account(
         init,
-        payer = authority,
+        payer = authority.authority,
         space = Transmuter::ACCOUNT_SIZE,
         seeds = [TRANSMUTER_PREFIX, from_mint.key().as_ref(), to_mint.key().as_ref()], bump
     )]
@@ -267,15 +387,40 @@ pub struct BaseInitTransmuterAccounts<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+/// Amount of `from_mint` tokens to transmute.
+///
+/// Static pool tokens are 1:1 with lamports, and the curve snapshot gives curve base units
+/// for lamports, so `tokens` are priced as is, whatever decimals the curve mint has.
+///
+/// Once `partial` is set, neither user's `from_mint` account nor transmuter's `to_mint` account
+/// is closed, even if emptied, so the wallet could transmute the rest later
+/// and keep the account rent meanwhile.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct TransmuteData {
     pub tokens: u64,
+    pub partial: bool,
+}
+
+/// Transmuter without expiry serves forever.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct InitTransmuterData {
+    pub expiry_ts: Option<u64>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SetTransmuterExpiryData {
+    pub expiry_ts: Option<u64>,
+}
+
+/// Transmutation is partial, once `max_tokens` is given.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TransmuteIdempotentData {
+    pub max_tokens: Option<u64>,
 }
 
 #[derive(Accounts)]
 pub struct TransmuteAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(mut)]
     pub user: Signer<'info>,
@@ -319,8 +464,7 @@ pub struct TransmuteAccounts<'info> {
 
 #[derive(Accounts)]
 pub struct TransmuteIdempotentAccounts<'info> {
-    #[account(mut, constraint = authority.key == &PROGRAM_AUTHORITY)]
-    pub authority: Signer<'info>,
+    pub authority: ProgramAuthorityAccounts<'info>,
 
     #[account(mut)]
     pub user: Signer<'info>,
@@ -348,6 +492,50 @@ pub struct TransmuteIdempotentAccounts<'info> {
     pub moonzip_program: Program<'info, Moonzip>,
 }
 
+#[derive(Accounts)]
+pub struct SetTransmuterExpiryAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(mut)]
+    pub transmuter: Account<'info, Transmuter>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTransmuterAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    /// CHECK: legacy layout can't be deserialized as [`Transmuter`], so it's checked manually
+    #[account(mut, owner = crate::ID)]
+    pub transmuter: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpiredTransmuterAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(mut, address = transmuter.to_mint)]
+    pub to_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = to_mint,
+        associated_token::authority = transmuter,
+    )]
+    pub transmuter_to_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [TRANSMUTER_PREFIX, transmuter.from_mint.as_ref(), transmuter.to_mint.as_ref()],
+        bump = transmuter.bump
+    )]
+    pub transmuter: Account<'info, Transmuter>,
+
+    pub token_program: Program<'info, Token>,
+}
+
 #[account]
 #[derive(Debug)]
 pub struct Transmuter {
@@ -355,9 +543,18 @@ pub struct Transmuter {
     to_mint: Pubkey,
     method: TransmuteMethod,
     bump: u8,
+    /// Transmutations are refused since then, so the transmuter could be closed.
+    expiry_ts: Option<u64>,
 }
 
-ensure_account_size!(Transmuter, 114);
+ensure_account_size!(Transmuter, 156);
+
+impl Transmuter {
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expiry_ts
+            .is_some_and(|expiry_ts| now >= 0 && now as u64 >= expiry_ts)
+    }
+}
 
 impl Sizable for Transmuter {
     fn longest() -> Self {
@@ -366,6 +563,7 @@ impl Sizable for Transmuter {
             to_mint: Pubkey::default(),
             method: Sizable::longest(),
             bump: Sizable::longest(),
+            expiry_ts: Some(Sizable::longest()),
         }
     }
 }
@@ -384,6 +582,90 @@ pub enum TransmuteMethod {
     PumpfunCurveLimit { curve_snapshot: CurveWrapper },
 }
 
+impl TransmuteMethod {
+    /// `to_mint` tokens, given for `from_mint` ones.
+    pub fn tokens_for(&self, from_tokens: u64) -> u64 {
+        match self {
+            TransmuteMethod::CurveLimit { curve_snapshot } => {
+                super::curve::SellCalculator::new(curve_snapshot).fixed_sols(from_tokens)
+            }
+            TransmuteMethod::PumpfunCurveLimit { curve_snapshot } => {
+                pumpfun::SellCalculator::new(curve_snapshot).fixed_sols(from_tokens)
+            }
+        }
+    }
+}
+
+/// Layout of [`Transmuter`] before curve kinds and expiry were introduced.
+#[derive(AnchorDeserialize)]
+struct LegacyTransmuter {
+    from_mint: Pubkey,
+    to_mint: Pubkey,
+    method: LegacyTransmuteMethod,
+    bump: u8,
+}
+
+impl LegacyTransmuter {
+    const ACCOUNT_SIZE: usize = 114;
+}
+
+/// Layout of [`Transmuter`] with expiry, allocated before the size accounted for curve kinds.
+#[derive(AnchorDeserialize)]
+struct ExpiringLegacyTransmuter {
+    from_mint: Pubkey,
+    to_mint: Pubkey,
+    method: LegacyTransmuteMethod,
+    bump: u8,
+    expiry_ts: Option<u64>,
+}
+
+impl ExpiringLegacyTransmuter {
+    const ACCOUNT_SIZE: usize = 123;
+}
+
+impl From<LegacyTransmuter> for ExpiringLegacyTransmuter {
+    fn from(legacy: LegacyTransmuter) -> Self {
+        Self {
+            from_mint: legacy.from_mint,
+            to_mint: legacy.to_mint,
+            method: legacy.method,
+            bump: legacy.bump,
+            expiry_ts: None,
+        }
+    }
+}
+
+impl From<ExpiringLegacyTransmuter> for Transmuter {
+    fn from(legacy: ExpiringLegacyTransmuter) -> Self {
+        Self {
+            from_mint: legacy.from_mint,
+            to_mint: legacy.to_mint,
+            method: legacy.method.into(),
+            bump: legacy.bump,
+            expiry_ts: legacy.expiry_ts,
+        }
+    }
+}
+
+#[derive(AnchorDeserialize)]
+enum LegacyTransmuteMethod {
+    CurveLimit { curve_snapshot: LegacyCurveState },
+    PumpfunCurveLimit { curve_snapshot: CurveWrapper },
+}
+
+impl From<LegacyTransmuteMethod> for TransmuteMethod {
+    fn from(legacy: LegacyTransmuteMethod) -> Self {
+        match legacy {
+            LegacyTransmuteMethod::CurveLimit { curve_snapshot } => Self::CurveLimit {
+                curve_snapshot: curve_snapshot.into(),
+            },
+            LegacyTransmuteMethod::PumpfunCurveLimit { curve_snapshot } => {
+                Self::PumpfunCurveLimit { curve_snapshot }
+            }
+        }
+    }
+}
+
 impl Sizable for TransmuteMethod {
     fn longest() -> Self {
         Self::CurveLimit {
@@ -391,3 +673,70 @@ impl Sizable for TransmuteMethod {
         }
     }
 }
+
+#[error_code]
+pub enum TransmuterError {
+    #[msg("Transmuter is expired")]
+    Expired,
+    #[msg("Transmuter is not expired yet")]
+    NotExpired,
+    #[msg("Transmuter already has the current layout")]
+    AlreadyMigrated,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_transmuter_account_size() {
+        let legacy = (Pubkey::default(), Pubkey::default(), 0u8, [0u64; 5], 0u8);
+        let mut serialized = Transmuter::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), LegacyTransmuter::ACCOUNT_SIZE);
+
+        Some(0u64).serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), ExpiringLegacyTransmuter::ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_expiring_legacy_transmuter_keeps_expiry() {
+        let mut serialized = vec![];
+        (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1u8,
+            [1u64, 2, 3, 4, 5],
+            254u8,
+            Some(100u64),
+        )
+            .serialize(&mut serialized)
+            .unwrap();
+        let legacy = ExpiringLegacyTransmuter::deserialize(&mut serialized.as_slice()).unwrap();
+        let migrated = Transmuter::from(legacy);
+        assert_eq!(migrated.bump, 254);
+        assert_eq!(migrated.expiry_ts, Some(100));
+        let TransmuteMethod::PumpfunCurveLimit { curve_snapshot } = migrated.method else {
+            panic!("method must be kept");
+        };
+        assert_eq!(curve_snapshot.token_total_supply, 5);
+    }
+
+    #[test]
+    fn test_transmutes_static_pool_into_6_decimals_curve() {
+        use crate::curved_pool::curve::{BuyCalculator, CalcBuy as _, CurveConfig};
+
+        // curve reserves are in base units of the 6 decimals mint
+        let mut curve = CurveState::from_cfg(&CurveConfig::default());
+        let collected = 10_000_000_000;
+        let bought = BuyCalculator::new(&curve).fixed_sols(collected);
+        curve.commit_buy(collected, bought);
+
+        let method = TransmuteMethod::CurveLimit {
+            curve_snapshot: curve,
+        };
+        // buy and sell round by a base unit each
+        let transmuted = method.tokens_for(collected);
+        assert!(transmuted.abs_diff(bought) <= 1, "{transmuted} vs {bought}");
+    }
+}
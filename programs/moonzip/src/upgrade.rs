@@ -0,0 +1,222 @@
+//! Program upgrades are delayed by a timelock, so users have time to notice a pending one.
+//!
+//! Upgrade authority of the program is held by the [`upgrade_proxy_address`] PDA,
+//! so the program could be upgraded only via [`execute_program_upgrade`],
+//! after the proposal of exactly the same binary has waited for [`UPGRADE_TIMELOCK`].
+//! See the "Program upgrades" section of the README for the ceremony.
+use crate::{
+    authority::*,
+    ensure_account_size,
+    events::{UpgradeExecutedEvent, UpgradeProposedEvent},
+    utils::Sizable,
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        hash::hash,
+        program::invoke_signed,
+    },
+};
+
+pub const UPGRADE_PROPOSAL_PREFIX: &[u8] = b"upgrade_proposal";
+pub const UPGRADE_PROXY_PREFIX: &[u8] = b"upgrade_proxy";
+
+/// Minimal delay between the proposal and the upgrade.
+pub const UPGRADE_TIMELOCK: i64 = 48 * 60 * 60;
+
+pub fn upgrade_proposal_address(upgrade_hash: &[u8; 32]) -> Pubkey {
+    let (address, _) =
+        Pubkey::find_program_address(&[UPGRADE_PROPOSAL_PREFIX, upgrade_hash], &crate::ID);
+    address
+}
+
+/// Must be set as the upgrade authority of the program and of every upgrade buffer.
+pub fn upgrade_proxy_address() -> Pubkey {
+    let (address, _) = Pubkey::find_program_address(&[UPGRADE_PROXY_PREFIX], &crate::ID);
+    address
+}
+
+pub fn propose_program_upgrade(
+    ctx: Context<ProposeProgramUpgradeAccounts>,
+    data: ProposeProgramUpgradeData,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if data.effective_at < now.saturating_add(UPGRADE_TIMELOCK) {
+        msg!(
+            "upgrade could be effective not earlier than {}",
+            now.saturating_add(UPGRADE_TIMELOCK)
+        );
+        return err!(UpgradeError::TimelockTooShort);
+    }
+
+    ctx.accounts.proposal.set_inner(ProgramUpgradeProposal {
+        upgrade_hash: data.upgrade_hash,
+        proposed_at: now,
+        effective_at: data.effective_at,
+        proposer: ctx.accounts.authority.key(),
+        bump: ctx.bumps.proposal,
+    });
+    emit_cpi!(UpgradeProposedEvent {
+        upgrade_hash: data.upgrade_hash,
+        proposed_at: now,
+        effective_at: data.effective_at,
+        proposer: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
+
+/// Buffer is hashed without its loader header, so the hash matches one of the `.so` file.
+/// Event is emitted before the upgrade: upgraded program can't be invoked in the same slot.
+pub fn execute_program_upgrade(ctx: Context<ExecuteProgramUpgradeAccounts>) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+    if Clock::get()?.unix_timestamp < proposal.effective_at {
+        msg!(
+            "upgrade could be executed only after {}",
+            proposal.effective_at
+        );
+        return err!(UpgradeError::TimelockNotPassed);
+    }
+
+    let buffer_hash = {
+        let buffer = ctx.accounts.buffer.try_borrow_data()?;
+        let binary = buffer
+            .get(UpgradeableLoaderState::size_of_buffer_metadata()..)
+            .ok_or(UpgradeError::InvalidBuffer)?;
+        hash(binary).to_bytes()
+    };
+    if buffer_hash != proposal.upgrade_hash {
+        return err!(UpgradeError::HashMismatch);
+    }
+
+    emit_cpi!(UpgradeExecutedEvent {
+        upgrade_hash: proposal.upgrade_hash,
+        proposed_at: proposal.proposed_at,
+        executed_at: Clock::get()?.unix_timestamp,
+    });
+
+    let upgrade_ix = bpf_loader_upgradeable::upgrade(
+        &crate::ID,
+        ctx.accounts.buffer.key,
+        ctx.accounts.upgrade_proxy.key,
+        ctx.accounts.spill.key,
+    );
+    invoke_signed(
+        &upgrade_ix,
+        &[
+            ctx.accounts.program_data.to_account_info(),
+            ctx.accounts.program.to_account_info(),
+            ctx.accounts.buffer.to_account_info(),
+            ctx.accounts.spill.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            ctx.accounts.upgrade_proxy.to_account_info(),
+        ],
+        &[&[UPGRADE_PROXY_PREFIX, &[ctx.bumps.upgrade_proxy]]],
+    )?;
+    Ok(())
+}
+
+/// Pending upgrade, which could be executed after `effective_at`.
+#[account]
+#[derive(Debug)]
+pub struct ProgramUpgradeProposal {
+    /// SHA-256 of the new program binary.
+    pub upgrade_hash: [u8; 32],
+    pub proposed_at: i64,
+    pub effective_at: i64,
+    pub proposer: Pubkey,
+    pub bump: u8,
+}
+
+impl Sizable for ProgramUpgradeProposal {
+    fn longest() -> Self {
+        ProgramUpgradeProposal {
+            upgrade_hash: [u8::MAX; 32],
+            proposed_at: Sizable::longest(),
+            effective_at: Sizable::longest(),
+            proposer: Pubkey::default(),
+            bump: Sizable::longest(),
+        }
+    }
+}
+
+ensure_account_size!(ProgramUpgradeProposal, 89);
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(data: ProposeProgramUpgradeData)]
+pub struct ProposeProgramUpgradeAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(
+        init,
+        payer = authority.authority,
+        space = ProgramUpgradeProposal::ACCOUNT_SIZE,
+        seeds = [UPGRADE_PROPOSAL_PREFIX, &data.upgrade_hash], bump,
+    )]
+    pub proposal: Account<'info, ProgramUpgradeProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ProposeProgramUpgradeData {
+    pub upgrade_hash: [u8; 32],
+    pub effective_at: i64,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteProgramUpgradeAccounts<'info> {
+    pub authority: ProgramAuthorityAccounts<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [UPGRADE_PROPOSAL_PREFIX, &proposal.upgrade_hash], bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, ProgramUpgradeProposal>,
+
+    /// CHECK: holds upgrade authority of the program and the buffer, only signs the upgrade.
+    #[account(seeds = [UPGRADE_PROXY_PREFIX], bump)]
+    pub upgrade_proxy: UncheckedAccount<'info>,
+
+    /// CHECK: the program itself, validated by the loader.
+    #[account(mut, address = crate::ID)]
+    pub program: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the loader.
+    #[account(mut)]
+    pub program_data: UncheckedAccount<'info>,
+
+    /// CHECK: content is checked against the proposal, the rest is validated by the loader.
+    #[account(mut, owner = bpf_loader_upgradeable::ID)]
+    pub buffer: UncheckedAccount<'info>,
+
+    /// CHECK: receives lamports of the closed buffer.
+    #[account(mut)]
+    pub spill: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+
+    /// CHECK: address is checked.
+    #[account(address = bpf_loader_upgradeable::ID)]
+    pub bpf_loader_upgradeable: UncheckedAccount<'info>,
+}
+
+#[error_code]
+pub enum UpgradeError {
+    #[msg("Upgrade must be effective after the timelock")]
+    TimelockTooShort,
+
+    #[msg("Upgrade timelock has not passed yet")]
+    TimelockNotPassed,
+
+    #[msg("Upgrade buffer is malformed")]
+    InvalidBuffer,
+
+    #[msg("Upgrade buffer doesn't match the proposal")]
+    HashMismatch,
+}
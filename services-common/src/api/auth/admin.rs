@@ -0,0 +1,93 @@
+use axum::{extract::FromRequestParts, http::request::Parts};
+use http::header::AUTHORIZATION;
+use tracing::warn;
+
+use super::{Error, Rejection};
+
+const BEARER: &str = "Bearer ";
+
+pub trait AdminTokenProvider {
+    /// If absent, all admin requests are rejected.
+    fn admin_token(&self) -> Option<&str>;
+}
+
+/// Operator, authenticated by the bearer token of the admin config.
+/// Kept apart from user tokens, so no user could ever act as admin.
+#[derive(Debug, Clone, Copy)]
+pub struct AdminAuth;
+
+impl<S: Send + Sync + AdminTokenProvider> FromRequestParts<S> for AdminAuth {
+    type Rejection = Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Some(expected) = state.admin_token() else {
+            warn!("admin request is rejected, as admin token is not configured");
+            return Err(Error::InvalidAdminToken.into());
+        };
+        let supplied = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix(BEARER))
+            .ok_or(Error::InvalidHeaders)?;
+        if !constant_time_eq(supplied.as_bytes(), expected.as_bytes()) {
+            warn!("admin request with invalid token is rejected");
+            return Err(Error::InvalidAdminToken.into());
+        }
+        Ok(AdminAuth)
+    }
+}
+
+/// Doesn't short-circuit on the first mismatch, so the token can't be guessed by timing.
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0u8, |diff, (left, right)| diff | (left ^ right))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{Request, StatusCode};
+
+    struct Config(Option<&'static str>);
+
+    impl AdminTokenProvider for Config {
+        fn admin_token(&self) -> Option<&str> {
+            self.0
+        }
+    }
+
+    async fn extract(config: Config, header: Option<&str>) -> Result<AdminAuth, StatusCode> {
+        let mut request = Request::builder();
+        if let Some(header) = header {
+            request = request.header(AUTHORIZATION, header);
+        }
+        let (mut parts, ()) = request.body(()).unwrap().into_parts();
+        AdminAuth::from_request_parts(&mut parts, &config)
+            .await
+            .map_err(|(status, _)| status)
+    }
+
+    #[tokio::test]
+    async fn test_accepts_configured_token_only() {
+        assert!(extract(Config(Some("secret")), Some("Bearer secret"))
+            .await
+            .is_ok());
+        for header in [None, Some("secret"), Some("Bearer other"), Some("Bearer ")] {
+            assert_eq!(
+                extract(Config(Some("secret")), header).await.unwrap_err(),
+                StatusCode::UNAUTHORIZED
+            );
+        }
+        assert_eq!(
+            extract(Config(None), Some("Bearer secret"))
+                .await
+                .unwrap_err(),
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
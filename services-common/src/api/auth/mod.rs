@@ -31,6 +31,7 @@ use crate::TZ;
 use super::response::ApiError;
 use super::response::AppJson;
 use super::response::ErrorResponse;
+pub mod admin;
 pub mod provider;
 
 #[utoipa::path(
@@ -100,6 +101,9 @@ pub enum Error {
 
     #[error("Missing captcha")]
     MissingCaptcha,
+
+    #[error("admin token is invalid")]
+    InvalidAdminToken,
 }
 
 impl From<Error> for Rejection {
@@ -110,6 +114,7 @@ impl From<Error> for Rejection {
             Error::MalformedToken => 4032,
             Error::SignatureMismatch => 4033,
             Error::MissingCaptcha => 4034,
+            Error::InvalidAdminToken => 4035,
         };
 
         (
@@ -483,4 +488,125 @@ mod tests {
             Ok(())
         })
     }
+
+    async fn fetch_token(
+        client: &reqwest::Client,
+        base_url: &str,
+        keypair: &Keypair,
+    ) -> anyhow::Result<String> {
+        let auth_result: AuthPropose = decode_response_type_or_raw(
+            client
+                .post(format!("{base_url}/auth"))
+                .json(&AuthRequest {
+                    user: keypair.pubkey(),
+                })
+                .send()
+                .await?,
+        )
+        .await?;
+        Ok(auth_result.token)
+    }
+
+    async fn send_with_auth(
+        client: &reqwest::Client,
+        base_url: &str,
+        header: String,
+    ) -> anyhow::Result<reqwest::Response> {
+        Ok(client
+            .post(format!("{base_url}/auth_test"))
+            .header(AUTHORIZATION, header)
+            .json(&SampleRequest {
+                raw: "message".into(),
+            })
+            .send()
+            .await?)
+    }
+
+    #[test]
+    fn test_concurrent_auth_requests() -> anyhow::Result<()> {
+        const REQUESTS: usize = 50;
+
+        run_test(async move {
+            let base_url = init().await;
+            let client = reqwest::Client::new();
+
+            let mut requests = tokio::task::JoinSet::new();
+            for _ in 0..REQUESTS {
+                let client = client.clone();
+                let base_url = base_url.clone();
+                requests.spawn(async move {
+                    let keypair = Keypair::new();
+                    let token = fetch_token(&client, &base_url, &keypair).await?;
+                    let signature = keypair.sign_message(token.as_bytes());
+                    let result: SampleResponse = decode_response_type_or_raw(
+                        send_with_auth(&client, &base_url, format!("{token};{signature}")).await?,
+                    )
+                    .await?;
+                    // every token must be bound to its own user only.
+                    assert_eq!(result.user, keypair.pubkey());
+                    anyhow::Ok(())
+                });
+            }
+
+            let mut succeeded = 0;
+            while let Some(result) = requests.join_next().await {
+                result??;
+                succeeded += 1;
+            }
+            assert_eq!(succeeded, REQUESTS);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_malformed_token_format() -> anyhow::Result<()> {
+        run_test(async move {
+            let base_url = init().await;
+            let client = reqwest::Client::new();
+
+            let keypair = Keypair::new();
+            let token = fetch_token(&client, &base_url, &keypair).await?;
+            let (unsigned, _) = token.rsplit_once('.').expect("jwt has segments");
+
+            let malformed_tokens = [
+                format!("{token}.extra"),
+                format!("{unsigned}."),
+                format!("{unsigned}.%%%"),
+                "not.a.token!".to_owned(),
+                String::new(),
+            ];
+            for malformed in malformed_tokens {
+                // signed properly, so only the token itself is wrong.
+                let signature = keypair.sign_message(malformed.as_bytes());
+                let result =
+                    send_with_auth(&client, &base_url, format!("{malformed};{signature}")).await?;
+
+                assert_eq!(result.status(), 401, "token {malformed:?} is accepted");
+                assert_eq!(
+                    result.json::<ErrorResponse>().await?,
+                    ErrorResponse {
+                        code: 4032,
+                        message: "passed token is malformed".into()
+                    },
+                    "token {malformed:?}"
+                );
+            }
+
+            for malformed_header in [format!("{token};"), format!("{token};%%%"), token.clone()] {
+                let result = send_with_auth(&client, &base_url, malformed_header).await?;
+
+                assert_eq!(result.status(), 401, "result incorrect status: {result:?}");
+                assert_eq!(
+                    result.json::<ErrorResponse>().await?,
+                    ErrorResponse {
+                        code: 4031,
+                        message: "no authorization header or it is malformed".into()
+                    }
+                );
+            }
+
+            Ok(())
+        })
+    }
 }
@@ -2,6 +2,7 @@ use std::{collections::BTreeMap, io::ErrorKind};
 
 use axum::{
     extract::{multipart::MultipartError, rejection::JsonRejection, FromRequest},
+    http::HeaderMap,
     response::{IntoResponse, Response},
 };
 
@@ -57,6 +58,30 @@ pub enum ApiError {
 
     #[error("NFT doesn't belong to user")]
     NFTNotBelong2User(anyhow::Error),
+
+    /// Headers are attached to the response, so client could tell how close it's to the limit.
+    #[error("rate limit exceeded: {}", .0)]
+    RateLimited(anyhow::Error, HeaderMap),
+
+    /// Body of the request is larger than the endpoint accepts.
+    #[error("request body exceeds the {} limit: {hint}", human_size(*.max_bytes))]
+    RequestTooLarge {
+        max_bytes: usize,
+        hint: &'static str,
+    },
+}
+
+/// Limits are multiples of KB or MB, so they are printed the way they are configured.
+fn human_size(bytes: usize) -> String {
+    const KB: usize = 1024;
+    const MB: usize = 1024 * KB;
+    if bytes >= MB && bytes % MB == 0 {
+        format!("{}MB", bytes / MB)
+    } else if bytes >= KB && bytes % KB == 0 {
+        format!("{}KB", bytes / KB)
+    } else {
+        format!("{bytes}B")
+    }
 }
 
 impl ApiError {
@@ -72,6 +97,8 @@ impl ApiError {
             ApiError::NotFoundUser(_) => 13,
             ApiError::InvalidUsernameFormat(_) => 14,
             ApiError::NFTNotBelong2User(_) => 15,
+            ApiError::RateLimited(..) => 16,
+            ApiError::RequestTooLarge { .. } => 17,
         }
     }
 }
@@ -115,6 +142,7 @@ impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let code = self.code();
 
+        let mut headers = HeaderMap::new();
         let (status, message) = match self {
             ApiError::Internal(err) => {
                 tracing::error!("internal error while handling API request: {err:?}");
@@ -133,8 +161,15 @@ impl IntoResponse for ApiError {
             ApiError::NotFoundUser(err) => (StatusCode::BAD_REQUEST, err.to_string()),
             ApiError::InvalidUsernameFormat(err) => (StatusCode::BAD_REQUEST, err.to_string()),
             ApiError::NFTNotBelong2User(err) => (StatusCode::BAD_REQUEST, err.to_string()),
+            ApiError::RateLimited(err, limit_headers) => {
+                headers = limit_headers;
+                (StatusCode::TOO_MANY_REQUESTS, err.to_string())
+            }
+            err @ ApiError::RequestTooLarge { .. } => {
+                (StatusCode::PAYLOAD_TOO_LARGE, err.to_string())
+            }
         };
 
-        (status, AppJson(ErrorResponse { message, code })).into_response()
+        (status, headers, AppJson(ErrorResponse { message, code })).into_response()
     }
 }
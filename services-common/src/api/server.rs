@@ -31,6 +31,10 @@ pub struct ApiConfig {
     pub auth: AuthConfig,
 
     pub captcha: CaptchaConfig,
+
+    /// Bearer token of admin endpoints, which reject all requests if absent.
+    #[serde(default)]
+    pub admin_token: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Clone, serde_derive_default::Default)]
@@ -113,6 +117,12 @@ impl<T> auth::ConfigProvider for AppState<T> {
     }
 }
 
+impl<T> auth::admin::AdminTokenProvider for AppState<T> {
+    fn admin_token(&self) -> Option<&str> {
+        self.config.admin_token.as_deref()
+    }
+}
+
 impl<T> captcha::CaptchaProvider for AppState<T> {
     fn secret_key(&self) -> &String {
         &self.config.captcha.secret_key
@@ -126,6 +136,7 @@ impl<T> captcha::CaptchaProvider for AppState<T> {
 pub async fn serve<T: Send + Sync + 'static, O: OpenApi>(
     state: AppState<T>,
     api_router: Router<AppState<T>>,
+    admin_router: Router<AppState<T>>,
 ) -> anyhow::Result<()> {
     let service = Router::new()
         .route("/health", get(health))
@@ -160,7 +171,7 @@ pub async fn serve<T: Send + Sync + 'static, O: OpenApi>(
         .with_state(state.clone());
 
     let admin_server = Router::new()
-        .nest("/admin", Router::new())
+        .nest("/admin", admin_router)
         .with_state(state.clone());
 
     let mut set = JoinSet::new();
@@ -169,7 +180,13 @@ pub async fn serve<T: Send + Sync + 'static, O: OpenApi>(
     set.spawn(async move {
         let listener = tokio::net::TcpListener::bind(listen).await.unwrap();
         tracing::debug!("listening api on {}", listener.local_addr().unwrap());
-        axum::serve(listener, app).await.unwrap();
+        // peer address is used by rate limits of the api
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
     });
     set.spawn(async move {
         let listener = tokio::net::TcpListener::bind(admin_listen).await.unwrap();
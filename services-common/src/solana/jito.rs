@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use super::any_tx::AnyTx;
@@ -9,8 +10,9 @@ use futures_util::StreamExt as _;
 use once_cell::sync::Lazy;
 use rand::rngs::ThreadRng;
 use rand::Rng as _;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use serde_with::{serde_as, DisplayFromStr};
 use solana_sdk::commitment_config::CommitmentLevel;
 use solana_sdk::signature::Signature;
 use solana_sdk::{
@@ -18,31 +20,97 @@ use solana_sdk::{
     system_instruction::transfer,
 };
 use tokio::net::TcpStream;
+use tokio::sync::RwLock;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct JitoClientConfig {
     #[serde(default = "default_jito_base_url")]
     pub base_url: String,
+    #[serde(default)]
+    pub tip_strategy: JitoTipStrategy,
+    /// Applied on top of [`TipState::optimal_tip`], e.g. to push graduation bundles harder.
+    #[serde(default = "default_tip_multiplier")]
+    pub tip_multiplier: f64,
 }
 
 fn default_jito_base_url() -> String {
     "https://frankfurt.mainnet.block-engine.jito.wtf".into()
 }
 
+fn default_tip_multiplier() -> f64 {
+    1.0
+}
+
+/// Defines which of the jito tip accounts receives the tip.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum JitoTipStrategy {
+    #[default]
+    Random,
+    /// Only listed accounts are used, picked randomly.
+    Preferred(#[serde_as(as = "Vec<DisplayFromStr>")] Vec<Pubkey>),
+    RoundRobin,
+}
+
+impl JitoTipStrategy {
+    /// Tips sent anywhere except the jito tip accounts are simply lost.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let JitoTipStrategy::Preferred(keys) = self {
+            if keys.is_empty() {
+                bail!("preferred jito tip accounts are empty");
+            }
+            if let Some(unknown) = keys.iter().find(|key| !JITO_KEYS.contains(key)) {
+                bail!("{unknown} is not a jito tip account");
+            }
+        }
+        Ok(())
+    }
+}
+
 pub struct JitoClient {
     client: reqwest::Client,
     config: Arc<JitoClientConfig>,
+    tip_strategy: RwLock<JitoTipStrategy>,
+    round_robin_pos: AtomicUsize,
 }
 
 impl JitoClient {
     pub fn new(config: JitoClientConfig) -> Self {
         Self {
             client: reqwest::Client::new(),
+            tip_strategy: RwLock::new(config.tip_strategy.clone()),
             config: Arc::new(config),
+            round_robin_pos: AtomicUsize::default(),
         }
     }
 
+    pub async fn tip_strategy(&self) -> JitoTipStrategy {
+        self.tip_strategy.read().await.clone()
+    }
+
+    pub async fn set_tip_strategy(&self, strategy: JitoTipStrategy) -> anyhow::Result<()> {
+        strategy.validate()?;
+        *self.tip_strategy.write().await = strategy;
+        Ok(())
+    }
+
+    pub async fn tip_account(&self) -> Pubkey {
+        match &*self.tip_strategy.read().await {
+            JitoTipStrategy::Random => random_key(&JITO_KEYS),
+            JitoTipStrategy::Preferred(keys) => random_key(keys),
+            JitoTipStrategy::RoundRobin => {
+                let pos = self.round_robin_pos.fetch_add(1, Ordering::Relaxed);
+                JITO_KEYS[pos % JITO_KEYS.len()]
+            }
+        }
+    }
+
+    pub fn tip_multiplier(&self) -> f64 {
+        self.config.tip_multiplier
+    }
+
     pub async fn submit_single_tx(&self, tx: &AnyTx) -> anyhow::Result<Signature> {
         let serialized = tx.serialize_base64()?;
         let id = uuid::Uuid::new_v4().to_string();
@@ -129,6 +197,10 @@ impl JitoClient {
             .next()
             .ok_or_else(|| anyhow::anyhow!("unexpected: empty bundle statuses"))
     }
+
+    pub async fn get_bundle_signatures(&self, bundle_id: &str) -> anyhow::Result<Vec<Signature>> {
+        self.get_bundle_status(bundle_id).await?.signatures()
+    }
 }
 
 #[derive(Deserialize)]
@@ -145,6 +217,24 @@ struct InnerBundleStatusResponse {
 pub struct BundleStatus {
     pub confirmation_status: CommitmentLevel,
     pub err: Result<Option<()>, String>,
+    #[serde(default)]
+    pub slot: u64,
+    /// Base58 signatures of bundled transactions, in submission order.
+    #[serde(default)]
+    pub transactions: Vec<String>,
+}
+
+impl BundleStatus {
+    pub fn signatures(&self) -> anyhow::Result<Vec<Signature>> {
+        self.transactions
+            .iter()
+            .map(|signature| {
+                signature
+                    .parse()
+                    .with_context(|| format!("decode bundle signature {signature}"))
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -218,13 +308,17 @@ static JITO_KEYS: Lazy<Vec<Pubkey>> = Lazy::new(|| {
     .collect()
 });
 
-fn jito_tip_account() -> Pubkey {
-    JITO_KEYS[rand::thread_rng().gen_range(0..JITO_KEYS.len())]
+fn random_key(keys: &[Pubkey]) -> Pubkey {
+    keys[rand::thread_rng().gen_range(0..keys.len())]
 }
 
 impl TipState {
-    pub fn tip_ix(&self, payer: &Pubkey) -> Instruction {
-        transfer(payer, &jito_tip_account(), self.optimal_tip())
+    pub fn tip_ix(&self, payer: &Pubkey, tip_account: &Pubkey, multiplier: f64) -> Instruction {
+        transfer(payer, tip_account, self.scaled_tip(multiplier))
+    }
+
+    pub fn scaled_tip(&self, multiplier: f64) -> u64 {
+        (self.optimal_tip() as f64 * multiplier) as u64
     }
 
     pub fn optimal_tip(&self) -> u64 {
@@ -242,6 +336,46 @@ mod tests {
         tests::logger_setup,
     };
 
+    fn client(tip_strategy: JitoTipStrategy) -> JitoClient {
+        JitoClient::new(JitoClientConfig {
+            base_url: default_jito_base_url(),
+            tip_strategy,
+            tip_multiplier: default_tip_multiplier(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_rotates_all_keys() {
+        let client = client(JitoTipStrategy::RoundRobin);
+        for round in 0..2 {
+            for expected in JITO_KEYS.iter() {
+                assert_eq!(&client.tip_account().await, expected, "round {round}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preferred_uses_only_listed_keys() {
+        let preferred = vec![JITO_KEYS[1], JITO_KEYS[5]];
+        let client = client(JitoTipStrategy::Preferred(preferred.clone()));
+        for _ in 0..50 {
+            assert!(preferred.contains(&client.tip_account().await));
+        }
+
+        assert!(client
+            .set_tip_strategy(JitoTipStrategy::Preferred(vec![Pubkey::new_unique()]))
+            .await
+            .is_err());
+        assert!(client
+            .set_tip_strategy(JitoTipStrategy::Preferred(vec![]))
+            .await
+            .is_err());
+        assert_eq!(
+            client.tip_strategy().await,
+            JitoTipStrategy::Preferred(preferred)
+        );
+    }
+
     #[tokio::test]
     #[ignore = "requires jito rpc, network access"]
     async fn test_jito_watcher() -> anyhow::Result<()> {
@@ -1,4 +1,4 @@
-use super::jito::{JitoClient, JitoClientConfig};
+use super::jito::{JitoClient, JitoClientConfig, JitoTipStrategy};
 use crate::solana::helius::{HeliusClient, HeliusClientConfig};
 use crate::utils::{
     keypair::SaneKeypair,
@@ -57,6 +57,14 @@ impl SolanaPool {
         self.jito_clients.next()
     }
 
+    /// Applied to every jito client, so any of them picks tip accounts the same way.
+    pub async fn set_jito_tip_strategy(&self, strategy: JitoTipStrategy) -> anyhow::Result<()> {
+        for client in self.jito_clients.all() {
+            client.set_tip_strategy(strategy.clone()).await?;
+        }
+        Ok(())
+    }
+
     pub fn helius_client(&self) -> &HeliusClient {
         self.helius_clients.next()
     }
@@ -119,6 +127,10 @@ impl<T> Balancer<T> {
         }
     }
 
+    fn all(&self) -> &[T] {
+        &self.data
+    }
+
     fn next(&self) -> &T {
         let length = self.data.len();
         let pos = self
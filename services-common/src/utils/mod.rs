@@ -1,4 +1,4 @@
-use std::{fmt::Display, future::Future, pin::Pin, task::Poll};
+use std::{fmt::Display, pin::Pin, task::Poll};
 
 use anyhow::bail;
 use base64::Engine as _;
@@ -6,14 +6,16 @@ use futures_util::{task, Stream};
 use reqwest::Response;
 use serde::{de::DeserializeOwned, Serialize, Serializer};
 use sync_wrapper::SyncWrapper;
-use tracing::debug;
 
 pub mod keypair;
 pub mod limiter;
 pub mod period_fetch;
+pub mod retry;
 #[cfg(test)]
 pub mod tests;
 
+pub use retry::{repeat_until_ok, repeat_until_ok_with_jitter, RetryStrategy};
+
 /// Decodes type from json or return err with raw body info.
 pub async fn decode_response_type_or_raw<T: DeserializeOwned>(
     response: Response,
@@ -70,29 +72,6 @@ pub mod serde_timestamp {
     }
 }
 
-pub async fn repeat_until_ok<
-    T,
-    E: Display + Sync + Send + 'static,
-    F: Future<Output = Result<T, E>>,
-    FN: Fn() -> F,
->(
-    new_fut: FN,
-    max_repeats: u64,
-) -> anyhow::Result<T> {
-    let mut repeats = 0;
-    while repeats < max_repeats {
-        let iter_res = new_fut().await;
-        match iter_res {
-            Ok(res) => return Ok(res),
-            Err(err) => {
-                debug!("repeated future completed with err at {repeats}: {err:#}")
-            }
-        }
-        repeats += 1;
-    }
-    bail!("future iterated {max_repeats} without success")
-}
-
 pub fn serialize_tx_bs58<S>(tx: &impl Serialize, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
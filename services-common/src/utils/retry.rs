@@ -0,0 +1,209 @@
+use anyhow::bail;
+use rand::Rng as _;
+use std::{fmt::Display, future::Future, time::Duration};
+use tracing::debug;
+
+/// Delay between failed attempts of [`repeat_until_ok`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryStrategy {
+    Immediate,
+    Fixed(Duration),
+    /// Doubles after each attempt, starting from `base`, but never exceeds `max`.
+    Exponential {
+        base: Duration,
+        max: Duration,
+    },
+}
+
+impl Default for RetryStrategy {
+    fn default() -> Self {
+        Self::Exponential {
+            base: Duration::from_millis(200),
+            max: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryStrategy {
+    /// Delay after the failed attempt, attempts are counted from zero.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        match *self {
+            RetryStrategy::Immediate => Duration::ZERO,
+            RetryStrategy::Fixed(delay) => delay,
+            RetryStrategy::Exponential { base, max } => {
+                base.saturating_mul(2u32.saturating_pow(attempt)).min(max)
+            }
+        }
+    }
+}
+
+pub async fn repeat_until_ok<
+    T,
+    E: Display + Sync + Send + 'static,
+    F: Future<Output = Result<T, E>>,
+    FN: Fn() -> F,
+>(
+    new_fut: FN,
+    max_repeats: u64,
+    strategy: RetryStrategy,
+) -> anyhow::Result<T> {
+    repeat_with_delay(new_fut, max_repeats, |attempt| strategy.delay(attempt)).await
+}
+
+/// Same as [`repeat_until_ok`], but each delay is randomly changed by up to `jitter_pct`,
+/// so concurrent callers don't retry all at once.
+pub async fn repeat_until_ok_with_jitter<
+    T,
+    E: Display + Sync + Send + 'static,
+    F: Future<Output = Result<T, E>>,
+    FN: Fn() -> F,
+>(
+    new_fut: FN,
+    max_repeats: u64,
+    strategy: RetryStrategy,
+    jitter_pct: f64,
+) -> anyhow::Result<T> {
+    repeat_with_delay(new_fut, max_repeats, |attempt| {
+        with_jitter(strategy.delay(attempt), jitter_pct)
+    })
+    .await
+}
+
+async fn repeat_with_delay<
+    T,
+    E: Display + Sync + Send + 'static,
+    F: Future<Output = Result<T, E>>,
+    FN: Fn() -> F,
+>(
+    new_fut: FN,
+    max_repeats: u64,
+    delay: impl Fn(u32) -> Duration,
+) -> anyhow::Result<T> {
+    let mut repeats = 0;
+    while repeats < max_repeats {
+        let iter_res = new_fut().await;
+        match iter_res {
+            Ok(res) => return Ok(res),
+            Err(err) => {
+                debug!("repeated future completed with err at {repeats}: {err:#}")
+            }
+        }
+        repeats += 1;
+        if repeats < max_repeats {
+            let delay = delay(u32::try_from(repeats - 1).unwrap_or(u32::MAX));
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+    bail!("future iterated {max_repeats} without success")
+}
+
+/// `jitter_pct` is a fraction, e.g. `0.1` changes delay by up to 10% in both directions.
+fn with_jitter(delay: Duration, jitter_pct: f64) -> Duration {
+    let jitter_pct = jitter_pct.clamp(0.0, 1.0);
+    if jitter_pct == 0.0 || delay.is_zero() {
+        return delay;
+    }
+    let factor = rand::thread_rng().gen_range(1.0 - jitter_pct..=1.0 + jitter_pct);
+    delay.mul_f64(factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicU64, Ordering},
+        time::Instant,
+    };
+
+    #[test]
+    fn test_has_no_delay_when_immediate() {
+        for attempt in [0, 1, 10] {
+            assert_eq!(RetryStrategy::Immediate.delay(attempt), Duration::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_keeps_fixed_delay() {
+        let strategy = RetryStrategy::Fixed(Duration::from_millis(50));
+        for attempt in [0, 1, 10] {
+            assert_eq!(strategy.delay(attempt), Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    fn test_doubles_exponential_delay_up_to_max() {
+        let strategy = RetryStrategy::Exponential {
+            base: Duration::from_millis(200),
+            max: Duration::from_secs(30),
+        };
+        assert_eq!(strategy.delay(0), Duration::from_millis(200));
+        assert_eq!(strategy.delay(1), Duration::from_millis(400));
+        assert_eq!(strategy.delay(3), Duration::from_millis(1600));
+        assert_eq!(strategy.delay(8), Duration::from_secs(30));
+        assert_eq!(strategy.delay(u32::MAX), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_keeps_jitter_in_bounds() {
+        let delay = Duration::from_millis(1000);
+        for _ in 0..100 {
+            let jittered = with_jitter(delay, 0.2);
+            assert!(jittered >= Duration::from_millis(800), "{jittered:?}");
+            assert!(jittered <= Duration::from_millis(1200), "{jittered:?}");
+        }
+        assert_eq!(with_jitter(delay, 0.0), delay);
+        assert_eq!(with_jitter(Duration::ZERO, 0.5), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_respects_max_repeats() {
+        let calls = AtomicU64::new(0);
+        let result = repeat_until_ok(
+            || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>("failed")
+            },
+            3,
+            RetryStrategy::Immediate,
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_stops_on_first_success() {
+        let calls = AtomicU64::new(0);
+        let result = repeat_until_ok_with_jitter(
+            || async {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                if call < 2 {
+                    Err("failed")
+                } else {
+                    Ok(call)
+                }
+            },
+            5,
+            RetryStrategy::Fixed(Duration::from_millis(1)),
+            0.5,
+        )
+        .await;
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_waits_between_attempts() {
+        let started = Instant::now();
+        let _ = repeat_until_ok(
+            || async { Err::<(), _>("failed") },
+            3,
+            RetryStrategy::Fixed(Duration::from_millis(20)),
+        )
+        .await;
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(40), "{elapsed:?}");
+    }
+}
@@ -1,10 +1,19 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, SystemProgram } from "@coral-xyz/anchor";
 import {
+  createInitializeMetadataPointerInstruction,
+  createInitializeMint2Instruction,
+  createInitializeTransferFeeConfigInstruction,
+  ExtensionType,
   getAccount,
   getAssociatedTokenAddress,
   getAssociatedTokenAddressSync,
+  getMetadataPointerState,
+  getMint,
+  getMintLen,
   getMinimumBalanceForRentExemptAccount,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { Moonzip } from "../../target/types/moonzip";
 import {
@@ -42,9 +51,84 @@ export function getCurvedPoolAddress(pool_mint: PublicKey) {
   return poolAddress;
 }
 
+export function getCreatorFeeWalletAddress(project: PublicKey) {
+  const main_program = anchor.workspace.Moonzip as Program<Moonzip>;
+  const [walletAddress, _] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode("creator-fee-wallet"), project.toBytes()],
+    main_program.programId
+  );
+  return walletAddress;
+}
+
+/// Pool takes a mint without supply, initialized in the same transaction.
+export async function poolMintInstructions(
+  mint: Keypair,
+  tokenProgram: PublicKey,
+  extensions: ExtensionType[]
+): Promise<anchor.web3.TransactionInstruction[]> {
+  const main_program = anchor.workspace.Moonzip as Program<Moonzip>;
+  const connection = main_program.provider.connection;
+  const authority = getAuthority();
+  const space = getMintLen(extensions);
+
+  const instructions = [
+    anchor.web3.SystemProgram.createAccount({
+      fromPubkey: authority.publicKey,
+      newAccountPubkey: mint.publicKey,
+      space,
+      lamports: await connection.getMinimumBalanceForRentExemption(space),
+      programId: tokenProgram,
+    }),
+  ];
+  for (const extension of extensions) {
+    switch (extension) {
+      case ExtensionType.MetadataPointer:
+        instructions.push(
+          createInitializeMetadataPointerInstruction(
+            mint.publicKey,
+            authority.publicKey,
+            mint.publicKey,
+            tokenProgram
+          )
+        );
+        break;
+      case ExtensionType.TransferFeeConfig:
+        instructions.push(
+          createInitializeTransferFeeConfigInstruction(
+            mint.publicKey,
+            authority.publicKey,
+            authority.publicKey,
+            100,
+            BigInt(1_000_000),
+            tokenProgram
+          )
+        );
+        break;
+      default:
+        throw new Error(`extension ${extension} is not used by tests`);
+    }
+  }
+  instructions.push(
+    createInitializeMint2Instruction(
+      mint.publicKey,
+      pumpfunLikeConfig().tokenDecimals,
+      authority.publicKey,
+      authority.publicKey,
+      tokenProgram
+    )
+  );
+  return instructions;
+}
+
 export async function createCurvedPool(
   project_id: BN,
-  mint: Keypair
+  mint: Keypair,
+  curveKindOverride = null,
+  maxWalletTokensOverride = null,
+  antiSnipeSlotsOverride = null,
+  creatorFeeShareBpsOverride = null,
+  tokenProgram = TOKEN_PROGRAM_ID,
+  extensions: ExtensionType[] = []
 ): Promise<PublicKey> {
   const main_program = anchor.workspace.Moonzip as Program<Moonzip>;
   const connection = main_program.provider.connection;
@@ -64,12 +148,23 @@ export async function createCurvedPool(
     .createCurvedPool({
       config: config,
       projectId: { 0: project_id },
+      tokenDecimalsOverride: null,
+      curveKindOverride,
+      maxWalletTokensOverride,
+      antiSnipeSlotsOverride,
+      creatorFeeShareBpsOverride,
     })
     .accounts({
-      authority: authority.publicKey,
+      authority: { authority: authority.publicKey },
       mint: mint.publicKey,
       project: projectAddress,
+      creatorFeeWallet:
+        creatorFeeShareBpsOverride === null
+          ? null
+          : getCreatorFeeWalletAddress(projectAddress),
+      tokenProgram,
     })
+    .preInstructions(await poolMintInstructions(mint, tokenProgram, extensions))
     .signers([authority, mint])
     .rpc();
   await connection.confirmTransaction(signature);
@@ -99,7 +194,7 @@ describe("curved pool", () => {
 
     let randomId = new BN(Math.floor(Math.random() * 100000).toString());
     await createProject(creator, randomId, {
-      useStaticPool: false,
+      staticPoolCount: 0,
       curvePool: {
         moonzip: {},
       },
@@ -140,7 +235,7 @@ describe("curved pool", () => {
         projectId: { 0: randomId },
       })
       .accounts({
-        authority: authority.publicKey,
+        authority: { authority: authority.publicKey },
         mint: poolMint.publicKey,
         user: user.publicKey,
         project: getProjectAddress(randomId),
@@ -182,7 +277,7 @@ describe("curved pool", () => {
         minSolOutput: new BN(0),
       })
       .accounts({
-        authority: authority.publicKey,
+        authority: { authority: authority.publicKey },
         mint: poolMint.publicKey,
         user: user.publicKey,
         project: getProjectAddress(randomId),
@@ -223,4 +318,748 @@ describe("curved pool", () => {
       expectedState.totalTokenSupply.toNumber()
     );
   });
+
+  it("curve state view matches the pool", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    const poolAddress = await createCurvedPool(randomId, poolMint);
+
+    const view = await main_program.methods
+      .getCurveState()
+      .accounts({
+        mint: poolMint.publicKey,
+      })
+      .view();
+    const state = await main_program.account.curvedPool.fetch(poolAddress);
+
+    expect(view.status).to.eql({ active: {} });
+    expect(view.virtualSolReserves.toNumber()).to.eql(
+      state.curve.virtualSolReserves.toNumber()
+    );
+    expect(view.virtualTokenReserves.toNumber()).to.eql(
+      state.curve.virtualTokenReserves.toNumber()
+    );
+    expect(view.spotPriceNumerator.toNumber()).to.eql(
+      state.curve.virtualSolReserves.toNumber()
+    );
+    expect(view.spotPriceDenominator.toNumber()).to.eql(
+      state.curve.virtualTokenReserves.toNumber()
+    );
+    expect(view.tokenBalance.toNumber()).to.eql(
+      state.curve.realTokenReserves.toNumber()
+    );
+    expect(view.solBalance.toNumber()).to.eql(0);
+  });
+
+  it("linear curve override keeps price growing with sold tokens", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const user = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(user.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    const poolAddress = await createCurvedPool(randomId, poolMint, {
+      linear: {
+        basePrice: new BN(30),
+        slopeNumerator: new BN(1),
+        slopeDenominator: new BN(1_000_000_000),
+      },
+    });
+
+    const before = await main_program.methods
+      .getCurveState()
+      .accounts({ mint: poolMint.publicKey })
+      .view();
+    expect(before.spotPriceNumerator.toString()).to.eql("30000000000");
+    expect(before.spotPriceDenominator.toString()).to.eql(
+      "1000000000000000000"
+    );
+
+    const signature = await main_program.methods
+      .buyFromCurvedPool({
+        sols: new BN(LAMPORTS_PER_SOL / 10),
+        minTokenOutput: new BN(0),
+        projectId: { 0: randomId },
+      })
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: poolMint.publicKey,
+        user: user.publicKey,
+        project: getProjectAddress(randomId),
+      })
+      .signers([authority, user])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    const state = await main_program.account.curvedPool.fetch(poolAddress);
+    const after = await main_program.methods
+      .getCurveState()
+      .accounts({ mint: poolMint.publicKey })
+      .view();
+    expect(state.curve.tokensSold.toNumber()).to.be.greaterThan(0);
+    expect(after.spotPriceDenominator.toString()).to.eql(
+      before.spotPriceDenominator.toString()
+    );
+    expect(after.spotPriceNumerator.gt(before.spotPriceNumerator)).to.be.true;
+  });
+
+  it("buy above the wallet limit is rejected", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const user = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(user.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    const poolAddress = await createCurvedPool(
+      randomId,
+      poolMint,
+      null,
+      new BN("1000000000")
+    );
+    const state = await main_program.account.curvedPool.fetch(poolAddress);
+    expect(state.config.maxWalletTokens.toString()).to.eql("1000000000");
+
+    const buy = (sols: number) =>
+      main_program.methods
+        .buyFromCurvedPool({
+          sols: new BN(sols),
+          minTokenOutput: new BN(0),
+          projectId: { 0: randomId },
+        })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          mint: poolMint.publicKey,
+          user: user.publicKey,
+          project: getProjectAddress(randomId),
+        })
+        .signers([authority, user])
+        .rpc();
+
+    // ~35k token units per lamport at the start of pumpfun-like curve
+    await connection.confirmTransaction(await buy(20_000));
+    await expect(buy(20_000)).to.be.rejectedWith(/MaxWalletExceeded/);
+  });
+
+  it("buys are rejected during anti-snipe cooldown", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const user = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(user.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    const poolAddress = await createCurvedPool(
+      randomId,
+      poolMint,
+      null,
+      null,
+      new BN(1_000_000)
+    );
+    const state = await main_program.account.curvedPool.fetch(poolAddress);
+    expect(state.creationSlot.toNumber()).to.be.greaterThan(0);
+    expect(state.config.antiSnipeSlots.toNumber()).to.eql(1_000_000);
+
+    const buy = (buyer: Keypair) =>
+      main_program.methods
+        .buyFromCurvedPool({
+          sols: new BN(100_000),
+          minTokenOutput: new BN(0),
+          projectId: { 0: randomId },
+        })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          mint: poolMint.publicKey,
+          user: buyer.publicKey,
+          project: getProjectAddress(randomId),
+        })
+        .signers([authority, buyer])
+        .rpc();
+
+    await expect(buy(user)).to.be.rejectedWith(/AntiSnipeCooldown/);
+    // dev purchases go through the program authority
+    await connection.confirmTransaction(await buy(authority));
+  });
+
+  it("paused pool rejects trades until resumed", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const user = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(user.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    const poolAddress = await createCurvedPool(randomId, poolMint);
+
+    const buy = () =>
+      main_program.methods
+        .buyFromCurvedPool({
+          sols: new BN(100_000),
+          minTokenOutput: new BN(0),
+          projectId: { 0: randomId },
+        })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          mint: poolMint.publicKey,
+          user: user.publicKey,
+          project: getProjectAddress(randomId),
+        })
+        .signers([authority, user])
+        .rpc();
+
+    await expect(
+      main_program.methods
+        .pauseCurvedPool()
+        .accounts({
+          authority: { authority: user.publicKey },
+          mint: poolMint.publicKey,
+        })
+        .signers([user])
+        .rpc()
+    ).to.be.rejectedWith(/ConstraintRaw/);
+
+    let signature = await main_program.methods
+      .pauseCurvedPool()
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: poolMint.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+    await connection.confirmTransaction(signature);
+    let state = await main_program.account.curvedPool.fetch(poolAddress);
+    expect(state.status).to.eql({ paused: {} });
+    await expect(buy()).to.be.rejectedWith(/Paused/);
+
+    signature = await main_program.methods
+      .resumeCurvedPool()
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: poolMint.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+    await connection.confirmTransaction(signature);
+    state = await main_program.account.curvedPool.fetch(poolAddress);
+    expect(state.status).to.eql({ active: {} });
+    await connection.confirmTransaction(await buy());
+  });
+
+  it("live pool config is updated without touching the curve", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const user = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(user.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    const poolAddress = await createCurvedPool(randomId, poolMint);
+
+    const buy = (sols: number) =>
+      main_program.methods
+        .buyFromCurvedPool({
+          sols: new BN(sols),
+          minTokenOutput: new BN(0),
+          projectId: { 0: randomId },
+        })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          mint: poolMint.publicKey,
+          user: user.publicKey,
+          project: getProjectAddress(randomId),
+        })
+        .signers([authority, user])
+        .rpc();
+
+    // ~35k token units per lamport at the start of pumpfun-like curve
+    await connection.confirmTransaction(await buy(20_000));
+    const before = await main_program.account.curvedPool.fetch(poolAddress);
+    expect(before.config.maxWalletTokens).to.be.null;
+
+    const newConfig = {
+      ...before.config,
+      maxWalletTokens: new BN("1000000000"),
+    };
+    await expect(
+      main_program.methods
+        .updateCurvedPoolConfig(newConfig)
+        .accounts({
+          authority: { authority: user.publicKey },
+          mint: poolMint.publicKey,
+        })
+        .signers([user])
+        .rpc()
+    ).to.be.rejectedWith(/ConstraintRaw/);
+    await expect(
+      main_program.methods
+        .updateCurvedPoolConfig({ ...newConfig, creatorFeeShareBps: 1_000 })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          mint: poolMint.publicKey,
+        })
+        .signers([authority])
+        .rpc()
+    ).to.be.rejectedWith(/CreatorFeeWalletMismatch/);
+
+    let signature = await main_program.methods
+      .updateCurvedPoolConfig(newConfig)
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: poolMint.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    const after = await main_program.account.curvedPool.fetch(poolAddress);
+    expect(after.config.maxWalletTokens.toString()).to.eql("1000000000");
+    expect(after.curve).to.eql(before.curve);
+    expect(after.status).to.eql({ active: {} });
+    // already held tokens count towards the new limit
+    await expect(buy(20_000)).to.be.rejectedWith(/MaxWalletExceeded/);
+  });
+
+  it("buy exact tokens gives requested amount within sol limit", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const user = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(user.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    await createCurvedPool(randomId, poolMint);
+
+    const tokens = new BN("1000000000");
+    const buyExact = (maxSolInput: BN) =>
+      main_program.methods
+        .buyExactTokens({
+          projectId: { 0: randomId },
+          tokens,
+          maxSolInput,
+        })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          mint: poolMint.publicKey,
+          user: user.publicKey,
+          project: getProjectAddress(randomId),
+        })
+        .signers([authority, user])
+        .rpc();
+
+    // ~35k token units per lamport, so the fee included price is way above 1 lamport
+    await expect(buyExact(new BN(1))).to.be.rejectedWith(/SlippageFailure/);
+
+    const signature = await buyExact(new BN(LAMPORTS_PER_SOL / 10));
+    await connection.confirmTransaction(signature);
+    expect(
+      (await tokenBalance(poolMint.publicKey, user.publicKey)).toString()
+    ).to.eql(tokens.toString());
+  });
+
+  it("sell exact sol gives requested sols within token limit", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const user = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(user.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    await createCurvedPool(randomId, poolMint);
+
+    let signature = await main_program.methods
+      .buyFromCurvedPool({
+        projectId: { 0: randomId },
+        sols: new BN(LAMPORTS_PER_SOL / 10),
+        minTokenOutput: new BN(0),
+      })
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: poolMint.publicKey,
+        user: user.publicKey,
+        project: getProjectAddress(randomId),
+      })
+      .signers([authority, user])
+      .rpc();
+    await connection.confirmTransaction(signature);
+    const boughtTokens = await tokenBalance(poolMint.publicKey, user.publicKey);
+
+    const targetSol = new BN(LAMPORTS_PER_SOL / 100);
+    const sellExact = (maxTokenInput: BN) =>
+      main_program.methods
+        .sellExactSol({
+          projectId: { 0: randomId },
+          targetSol,
+          maxTokenInput,
+        })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          mint: poolMint.publicKey,
+          user: user.publicKey,
+          project: getProjectAddress(randomId),
+        })
+        .signers([authority, user])
+        .rpc();
+
+    await expect(sellExact(new BN(1))).to.be.rejectedWith(/SlippageFailure/);
+
+    const preSellBalance = await connection.getBalance(user.publicKey);
+    signature = await sellExact(new BN(boughtTokens));
+    await connection.confirmTransaction(signature);
+    const receivedSols =
+      (await connection.getBalance(user.publicKey)) - preSellBalance;
+    expect(receivedSols).to.gte(targetSol.toNumber());
+    // rounding of the curve gives away at most a few lamports
+    expect(receivedSols).to.lt(targetSol.toNumber() + 1000);
+    expect(
+      await tokenBalance(poolMint.publicKey, user.publicKey)
+    ).to.lt(boughtTokens);
+  });
+
+  it("creator fee share is sent to creator fee wallet and claimed", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const user = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(user.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    const project = getProjectAddress(randomId);
+    const poolAddress = await createCurvedPool(
+      randomId,
+      poolMint,
+      null,
+      null,
+      null,
+      5_000
+    );
+    const state = await main_program.account.curvedPool.fetch(poolAddress);
+    expect(state.config.creatorFeeShareBps).to.eql(5_000);
+
+    const wallet = getCreatorFeeWalletAddress(project);
+    const rentMinimum = await connection.getMinimumBalanceForRentExemption(0);
+    expect(await connection.getBalance(wallet)).to.eql(rentMinimum);
+
+    const sols = new BN(LAMPORTS_PER_SOL / 10);
+    const buy = (creatorFeeWallet: PublicKey | null) =>
+      main_program.methods
+        .buyFromCurvedPool({
+          sols,
+          minTokenOutput: new BN(0),
+          projectId: { 0: randomId },
+        })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          mint: poolMint.publicKey,
+          user: user.publicKey,
+          project,
+          creatorFeeWallet,
+        })
+        .signers([authority, user])
+        .rpc();
+
+    await expect(buy(null)).to.be.rejectedWith(/CreatorFeeWalletMismatch/);
+    await connection.confirmTransaction(await buy(wallet));
+    const creatorShare = feeAmount(sols, MZIP_FEE).div(new BN(2));
+    approxEquals(
+      new BN((await connection.getBalance(wallet)) - rentMinimum),
+      creatorShare
+    );
+
+    const preClaimBalance = await connection.getBalance(creator.publicKey);
+    await expect(
+      main_program.methods
+        .claimCreatorFees()
+        .accounts({ creator: user.publicKey, project })
+        .signers([user])
+        .rpc()
+    ).to.be.rejectedWith(/NotProjectOwner/);
+    const signature = await main_program.methods
+      .claimCreatorFees()
+      .accounts({ creator: creator.publicKey, project })
+      .signers([creator])
+      .rpc();
+    await connection.confirmTransaction(signature);
+    expect(await connection.getBalance(wallet)).to.eql(rentMinimum);
+    expect(
+      (await connection.getBalance(creator.publicKey)) - preClaimBalance
+    ).to.gt(0);
+  });
+
+  it("buy with foreign fee account is rejected", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const user = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(user.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    await createCurvedPool(randomId, poolMint);
+
+    const [fakeFee, _] = PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("fake-fee")],
+      main_program.programId
+    );
+    // anchor deserializes the account before checking its address,
+    // so unknown fee account never reaches the handler
+    await expect(
+      main_program.methods
+        .buyFromCurvedPool({
+          sols: new BN(100000),
+          minTokenOutput: new BN(0),
+          projectId: { 0: randomId },
+        })
+        .accountsPartial({
+          authority: { authority: authority.publicKey },
+          fee: fakeFee,
+          mint: poolMint.publicKey,
+          user: user.publicKey,
+          project: getProjectAddress(randomId),
+        })
+        .signers([authority, user])
+        .rpc()
+    ).to.be.rejectedWith(/AccountNotInitialized/);
+  });
+
+  it("token-2022 mint with metadata pointer is traded", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const user = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(user.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    const poolAddress = await createCurvedPool(
+      randomId,
+      poolMint,
+      null,
+      null,
+      null,
+      null,
+      TOKEN_2022_PROGRAM_ID,
+      [ExtensionType.MetadataPointer]
+    );
+
+    const mint = await getMint(
+      connection,
+      poolMint.publicKey,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(getMetadataPointerState(mint).metadataAddress).to.eql(
+      poolMint.publicKey
+    );
+    const state = await main_program.account.curvedPool.fetch(poolAddress);
+    expect(state.config.tokenProgram).to.eql(TOKEN_2022_PROGRAM_ID);
+
+    const signature = await main_program.methods
+      .buyFromCurvedPool({
+        sols: new BN(100000),
+        minTokenOutput: new BN(0),
+        projectId: { 0: randomId },
+      })
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: poolMint.publicKey,
+        user: user.publicKey,
+        project: getProjectAddress(randomId),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([authority, user])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    const userAccount = await getAccount(
+      connection,
+      getAssociatedTokenAddressSync(
+        poolMint.publicKey,
+        user.publicKey,
+        false,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(Number(userAccount.amount)).to.gt(0);
+  });
+
+  it("token-2022 mint with transfer fee is rejected", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    await expect(
+      createCurvedPool(
+        randomId,
+        poolMint,
+        null,
+        null,
+        null,
+        null,
+        TOKEN_2022_PROGRAM_ID,
+        [ExtensionType.TransferFeeConfig]
+      )
+    ).to.be.rejectedWith(/UnsupportedMintExtension/);
+  });
+
+  it("token-2022 mint is rejected for project with static pool", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 1,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    await expect(
+      createCurvedPool(
+        randomId,
+        poolMint,
+        null,
+        null,
+        null,
+        null,
+        TOKEN_2022_PROGRAM_ID,
+        [ExtensionType.MetadataPointer]
+      )
+    ).to.be.rejectedWith(/StaticPoolRequiresSplToken/);
+  });
+
+  it("authorities are revoked", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const poolMint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    await createCurvedPool(randomId, poolMint);
+
+    let mint = await getMint(connection, poolMint.publicKey);
+    expect(mint.mintAuthority).to.eql(authority.publicKey);
+
+    const signature = await main_program.methods
+      .revokeMintAuthority()
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: poolMint.publicKey,
+        project: getProjectAddress(randomId),
+      })
+      .signers([authority])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    mint = await getMint(connection, poolMint.publicKey);
+    expect(mint.mintAuthority).to.be.null;
+    expect(mint.freezeAuthority).to.eql(authority.publicKey);
+
+    await expect(
+      main_program.methods
+        .revokeFreezeAuthority({ projectId: { 0: randomId } })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          project: getProjectAddress(randomId),
+          mint: poolMint.publicKey,
+        })
+        .signers([authority])
+        .rpc()
+    ).to.be.rejectedWith(/NotClosed/);
+  });
 });
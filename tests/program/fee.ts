@@ -0,0 +1,85 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Moonzip } from "../../target/types/moonzip";
+import {
+  airdrop,
+  beforeAll,
+  feeAddress,
+  FEE_RECEIVER,
+  getAuthority,
+} from "../utils/utils";
+import { BN } from "bn.js";
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { expect } from "chai";
+import * as chai from "chai";
+import chaiAsPromised from "chai-as-promised";
+chai.use(chaiAsPromised);
+
+describe("fee", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  before(beforeAll);
+
+  const main_program = anchor.workspace.Moonzip as Program<Moonzip>;
+  const provider = main_program.provider as anchor.AnchorProvider;
+  const connection = provider.connection;
+
+  it("sweep moves everything above rent to receiver", async () => {
+    const fee = feeAddress();
+    const state = await main_program.account.feeAccount.fetch(fee);
+    expect(state.feeReceiver).to.eql(FEE_RECEIVER.publicKey);
+
+    await airdrop(fee, new BN(10 * LAMPORTS_PER_SOL));
+
+    const feeAccount = await connection.getAccountInfo(fee);
+    const rent = await connection.getMinimumBalanceForRentExemption(
+      feeAccount.data.length
+    );
+    const feeBalance = await connection.getBalance(fee);
+    const receiverBefore = await connection.getBalance(FEE_RECEIVER.publicKey);
+
+    const signature = await main_program.methods
+      .sweepFee()
+      .accounts({
+        feeReceiver: FEE_RECEIVER.publicKey,
+      })
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    expect(await connection.getBalance(fee)).to.eql(rent);
+    expect(await connection.getBalance(FEE_RECEIVER.publicKey)).to.eql(
+      receiverBefore + feeBalance - rent
+    );
+
+    await expect(
+      main_program.methods
+        .sweepFee()
+        .accounts({
+          feeReceiver: FEE_RECEIVER.publicKey,
+        })
+        .rpc()
+    ).to.be.rejectedWith(/NothingToSweep/);
+  });
+
+  it("fee tiers must be ascending by volume", async () => {
+    const authority = getAuthority();
+    await expect(
+      main_program.methods
+        .setFeeConfig({
+          onBuy: { percentage: { 0: { 0: 100 } } },
+          onSell: { percentage: { 0: { 0: 100 } } },
+          dynamic: {
+            volumeThresholds: [
+              { volume: new BN(10 * LAMPORTS_PER_SOL), bps: { 0: 50 } },
+              { volume: new BN(LAMPORTS_PER_SOL), bps: { 0: 25 } },
+            ],
+          },
+        })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          feeReceiver: FEE_RECEIVER.publicKey,
+        })
+        .signers([authority])
+        .rpc()
+    ).to.be.rejectedWith(/InvalidFeeTiers/);
+  });
+});
@@ -0,0 +1,112 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Moonzip } from "../../target/types/moonzip";
+import {
+  airdrop,
+  beforeAll,
+  expectNoATA,
+  getAuthority,
+  mintToken,
+  tokenBalance,
+} from "../utils/utils";
+import { BN } from "bn.js";
+import { Keypair, LAMPORTS_PER_SOL, SystemProgram } from "@solana/web3.js";
+import {
+  createInitializeMintInstruction,
+  getAssociatedTokenAddressSync,
+  getMinimumBalanceForRentExemptMint,
+  getMint,
+  MINT_SIZE,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import * as chai from "chai";
+import chaiAsPromised from "chai-as-promised";
+chai.use(chaiAsPromised);
+
+describe("misc", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  before(beforeAll);
+
+  const main_program = anchor.workspace.Moonzip as Program<Moonzip>;
+  const provider = main_program.provider as anchor.AnchorProvider;
+  const connection = provider.connection;
+
+  async function mintWithAuthorities(authority: Keypair): Promise<Keypair> {
+    const mint = Keypair.generate();
+    const tx = new anchor.web3.Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: provider.wallet.publicKey,
+        newAccountPubkey: mint.publicKey,
+        space: MINT_SIZE,
+        lamports: await getMinimumBalanceForRentExemptMint(connection),
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      createInitializeMintInstruction(
+        mint.publicKey,
+        6,
+        authority.publicKey,
+        authority.publicKey,
+        TOKEN_PROGRAM_ID
+      )
+    );
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint;
+  }
+
+  it("burn and close burns everything and returns rent", async () => {
+    const authority = getAuthority();
+    const mint = await mintWithAuthorities(authority);
+    const amount = new BN(1_000_000);
+    await mintToken(authority, mint.publicKey, authority.publicKey, amount);
+    expect(
+      (await tokenBalance(mint.publicKey, authority.publicKey)).toString()
+    ).to.eql(amount.toString());
+
+    const ata = getAssociatedTokenAddressSync(
+      mint.publicKey,
+      authority.publicKey
+    );
+    const ataRent = await connection.getBalance(ata);
+    const authorityBefore = await connection.getBalance(authority.publicKey);
+
+    const signature = await main_program.methods
+      .burnAndClose()
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: mint.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    await expectNoATA(mint.publicKey, authority.publicKey);
+    const mintState = await getMint(connection, mint.publicKey);
+    expect(mintState.supply.toString()).to.eql("0");
+    expect(mintState.mintAuthority).to.be.null;
+    expect(mintState.freezeAuthority).to.be.null;
+    // authority may also be the fee payer of the transaction
+    expect(await connection.getBalance(authority.publicKey)).to.be.within(
+      authorityBefore + ataRent - 10_000,
+      authorityBefore + ataRent
+    );
+  });
+
+  it("burn and close is allowed only for the program authority", async () => {
+    const owner = Keypair.generate();
+    await airdrop(owner.publicKey, new BN(LAMPORTS_PER_SOL));
+    const mint = await mintWithAuthorities(owner);
+    await mintToken(owner, mint.publicKey, owner.publicKey, new BN(1000));
+
+    await expect(
+      main_program.methods
+        .burnAndClose()
+        .accounts({
+          authority: { authority: owner.publicKey },
+          mint: mint.publicKey,
+        })
+        .signers([owner])
+        .rpc()
+    ).to.be.rejectedWith(/ConstraintRaw/);
+  });
+});
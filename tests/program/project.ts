@@ -0,0 +1,294 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Moonzip } from "../../target/types/moonzip";
+import {
+  airdrop,
+  beforeAll,
+  createProject,
+  getAuthority,
+  getProjectAddress,
+} from "../utils/utils";
+import { BN } from "bn.js";
+import { LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import * as chai from "chai";
+import chaiAsPromised from "chai-as-promised";
+chai.use(chaiAsPromised);
+
+function getTransferProposalAddress(project: PublicKey) {
+  const main_program = anchor.workspace.Moonzip as Program<Moonzip>;
+  const [address, _] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode("transfer"), project.toBytes()],
+    main_program.programId
+  );
+  return address;
+}
+
+describe("project ownership", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  before(beforeAll);
+
+  const main_program = anchor.workspace.Moonzip as Program<Moonzip>;
+  const provider = main_program.provider as anchor.AnchorProvider;
+  const connection = provider.connection;
+
+  it("transfer is proposed, rejected during cooldown and cancelled", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const newOwner = anchor.web3.Keypair.generate();
+
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(newOwner.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    const project = await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+    let state = await main_program.account.project.fetch(project);
+    expect(state.creator).to.eql(creator.publicKey);
+
+    const proposal = getTransferProposalAddress(project);
+    let signature = await main_program.methods
+      .proposeTransferOwnership({ newOwner: newOwner.publicKey })
+      .accounts({
+        owner: creator.publicKey,
+        project: project,
+      })
+      .signers([creator])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    let proposalState =
+      await main_program.account.ownershipTransferProposal.fetch(proposal);
+    expect(proposalState.newOwner).to.eql(newOwner.publicKey);
+    expect(proposalState.project).to.eql(project);
+
+    await expect(
+      main_program.methods
+        .acceptTransferOwnership()
+        .accounts({
+          newOwner: newOwner.publicKey,
+          project: project,
+          previousOwner: creator.publicKey,
+        })
+        .signers([newOwner])
+        .rpc()
+    ).to.be.rejectedWith(/TransferCooldownNotPassed/);
+
+    await expect(
+      main_program.methods
+        .cancelTransferOwnership()
+        .accounts({
+          owner: newOwner.publicKey,
+          project: project,
+        })
+        .signers([newOwner])
+        .rpc()
+    ).to.be.rejectedWith(/NotProjectOwner/);
+
+    signature = await main_program.methods
+      .cancelTransferOwnership()
+      .accounts({
+        owner: creator.publicKey,
+        project: project,
+      })
+      .signers([creator])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    expect(await connection.getAccountInfo(proposal)).to.be.null;
+    state = await main_program.account.project.fetch(project);
+    expect(state.creator).to.eql(creator.publicKey);
+  });
+
+  it("recently locked latch is not forced open", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const authority = getAuthority();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    const project = await createProject(creator, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+
+    // lock without unlock, as a transaction, which is left half-applied.
+    let signature = await main_program.methods
+      .projectLockLatch()
+      .accounts({
+        authority: { authority: authority.publicKey },
+        project: project,
+      })
+      .signers([authority])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    let state = await main_program.account.project.fetch(project);
+    expect(state.latch.lockedAt).to.not.be.null;
+
+    await expect(
+      main_program.methods
+        .forceUnlockStaleLatch({ maxAgeSecs: new BN(60 * 60) })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          project: project,
+        })
+        .signers([authority])
+        .rpc()
+    ).to.be.rejectedWith(/LatchNotStale/);
+
+    signature = await main_program.methods
+      .projectUnlockLatch()
+      .accounts({
+        authority: { authority: authority.publicKey },
+        project: project,
+      })
+      .signers([authority])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    state = await main_program.account.project.fetch(project);
+    expect(state.latch.lockedAt).to.be.null;
+  });
+
+  it("project is cancelled with deposit refunded before deployment", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const stranger = anchor.web3.Keypair.generate();
+    const authority = getAuthority();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(stranger.publicKey, new BN(LAMPORTS_PER_SOL));
+    const deposit = new BN(LAMPORTS_PER_SOL / 10);
+
+    const randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    const project = getProjectAddress(randomId);
+    let signature = await main_program.methods
+      .createProject({
+        id: { 0: randomId },
+        schema: {
+          staticPoolCount: 0,
+          curvePool: {
+            moonzip: {},
+          },
+          devPurchase: null,
+        },
+        creatorDeposit: deposit,
+      })
+      .accounts({
+        authority: { authority: authority.publicKey },
+        creator: creator.publicKey,
+        project: project,
+      })
+      .signers([authority, creator])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    await expect(
+      main_program.methods
+        .cancelProject()
+        .accounts({
+          authority: { authority: authority.publicKey },
+          creator: stranger.publicKey,
+          project: project,
+        })
+        .signers([authority, stranger])
+        .rpc()
+    ).to.be.rejectedWith(/NotProjectOwner/);
+
+    // pending transfer is closed along with the project
+    const proposal = getTransferProposalAddress(project);
+    signature = await main_program.methods
+      .proposeTransferOwnership({ newOwner: stranger.publicKey })
+      .accounts({
+        owner: creator.publicKey,
+        project: project,
+      })
+      .signers([creator])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    const balanceBefore = await connection.getBalance(creator.publicKey);
+    const projectRent = await connection.getBalance(project);
+    const proposalRent = await connection.getBalance(proposal);
+    signature = await main_program.methods
+      .cancelProject()
+      .accounts({
+        authority: { authority: authority.publicKey },
+        creator: creator.publicKey,
+        project: project,
+      })
+      .signers([authority, creator])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    expect(await connection.getAccountInfo(project)).to.be.null;
+    expect(await connection.getAccountInfo(proposal)).to.be.null;
+    // transaction fee is paid by the provider wallet
+    expect((await connection.getBalance(creator.publicKey)) - balanceBefore).to.eql(
+      deposit.toNumber() + projectRent + proposalRent
+    );
+  });
+
+  it("creation fee is charged from creator deposit", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const authority = getAuthority();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    const creationFee = new BN(LAMPORTS_PER_SOL / 100);
+    const schema = {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    };
+
+    const setFee = async (feeLamports: BN) => {
+      const signature = await main_program.methods
+        .setProjectCreationFee({ feeLamports })
+        .accounts({
+          authority: { authority: authority.publicKey },
+        })
+        .signers([authority])
+        .rpc();
+      await connection.confirmTransaction(signature);
+    };
+
+    await setFee(creationFee);
+    try {
+      let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+      await expect(createProject(creator, randomId, schema)).to.be.rejectedWith(
+        /InsufficientCreationFee/
+      );
+
+      const [feeAddress, _] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("fee")],
+        main_program.programId
+      );
+      const feeBefore = await connection.getBalance(feeAddress);
+      const signature = await main_program.methods
+        .createProject({
+          id: { 0: randomId },
+          schema: schema,
+          creatorDeposit: creationFee,
+        })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          creator: creator.publicKey,
+          project: getProjectAddress(randomId),
+        })
+        .signers([authority, creator])
+        .rpc();
+      await connection.confirmTransaction(signature);
+      expect((await connection.getBalance(feeAddress)) - feeBefore).to.eql(
+        creationFee.toNumber()
+      );
+    } finally {
+      await setFee(new BN(0));
+    }
+  });
+});
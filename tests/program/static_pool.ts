@@ -25,7 +25,7 @@ import { LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
 import { expect } from "chai";
 import * as chai from "chai";
 import chaiAsPromised from "chai-as-promised";
-import { sendTransaction, signTransaction } from "../utils/helpers";
+import { delay, sendTransaction, signTransaction } from "../utils/helpers";
 chai.use(chaiAsPromised);
 
 function getPoolAddress(mint: PublicKey) {
@@ -37,6 +37,29 @@ function getPoolAddress(mint: PublicKey) {
   return poolAddress;
 }
 
+const WALLET_RECORD_SIZE = 65;
+
+function getWalletRecordAddress(pool: PublicKey, wallet: PublicKey) {
+  const main_program = anchor.workspace.Moonzip as Program<Moonzip>;
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode("wallet-record"),
+      pool.toBytes(),
+      wallet.toBytes(),
+    ],
+    main_program.programId
+  )[0];
+}
+
+/// Buyer pays for its token account and wallet record on the first buy.
+async function getBuyerRent(): Promise<number> {
+  const connection = anchor.getProvider().connection;
+  return (
+    (await getMinimumBalanceForRentExemptAccount(connection)) +
+    (await connection.getMinimumBalanceForRentExemption(WALLET_RECORD_SIZE))
+  );
+}
+
 describe("static pool", () => {
   // Configure the client to use the local cluster.
   anchor.setProvider(anchor.AnchorProvider.env());
@@ -57,8 +80,11 @@ describe("static pool", () => {
       closeConditions: {
         finishTs: null,
         maxLamports: new BN(500),
+        gracePeriodSecs: null,
+        minLamports: null,
       },
       minPurchaseLamports: new BN(10),
+      maxPurchasePerWallet: null,
     };
 
     const firstAmountBare = new BN(200);
@@ -76,7 +102,7 @@ describe("static pool", () => {
 
     let randomId = new BN(Math.floor(Math.random() * 100000).toString());
     await createProject(creator, randomId, {
-      useStaticPool: true,
+      staticPoolCount: 1,
       curvePool: {
         moonzip: {},
       },
@@ -87,7 +113,7 @@ describe("static pool", () => {
     let signature = await main_program.methods
       .createStaticPool({ config: config, projectId: { 0: randomId } })
       .accounts({
-        authority: authority.publicKey,
+        authority: { authority: authority.publicKey },
         mint: mint.publicKey,
         project: getProjectAddress(randomId),
       })
@@ -109,15 +135,15 @@ describe("static pool", () => {
       config.minPurchaseLamports.toNumber()
     );
 
-    const tokenRent = await getMinimumBalanceForRentExemptAccount(connection);
+    const buyerRent = await getBuyerRent();
 
     await airdrop(
       firstBuyer.publicKey,
-      new BN(firstAmount.toNumber() + tokenRent)
+      new BN(firstAmount.toNumber() + buyerRent)
     );
     await airdrop(
       secondBuyer.publicKey,
-      new BN(secondAmount.toNumber() + tokenRent)
+      new BN(secondAmount.toNumber() + buyerRent)
     );
 
     console.log("starting to purchasing from static pool");
@@ -125,7 +151,7 @@ describe("static pool", () => {
     let transaction = await main_program.methods
       .buyFromStaticPool({ sols: firstAmount, projectId: { 0: randomId } })
       .accounts({
-        authority: authority.publicKey,
+        authority: { authority: authority.publicKey },
         mint: mint.publicKey,
         user: firstBuyer.publicKey,
         project: getProjectAddress(randomId),
@@ -154,7 +180,7 @@ describe("static pool", () => {
     transaction = await main_program.methods
       .buyFromStaticPool({ sols: secondAmount, projectId: { 0: randomId } })
       .accounts({
-        authority: authority.publicKey,
+        authority: { authority: authority.publicKey },
         mint: mint.publicKey,
         user: secondBuyer.publicKey,
         project: getProjectAddress(randomId),
@@ -178,7 +204,7 @@ describe("static pool", () => {
     transaction = await main_program.methods
       .graduateStaticPool()
       .accounts({
-        authority: authority.publicKey,
+        authority: { authority: authority.publicKey },
         fundsReceiver: fundsReceiver.publicKey,
         pool: poolAddress,
         project: getProjectAddress(randomId),
@@ -200,4 +226,363 @@ describe("static pool", () => {
         .toNumber()
     );
   });
+
+  it("deadline is only extended within the window", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const mint = anchor.web3.Keypair.generate();
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(authority.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 1,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+
+    const finishTs = Math.floor(Date.now() / 1000) + 60 * 60;
+    let signature = await main_program.methods
+      .createStaticPool({
+        config: {
+          closeConditions: {
+            finishTs: new BN(finishTs),
+            maxLamports: null,
+            gracePeriodSecs: null,
+            minLamports: null,
+          },
+          minPurchaseLamports: null,
+          maxPurchasePerWallet: null,
+        },
+        projectId: { 0: randomId },
+      })
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: mint.publicKey,
+        project: getProjectAddress(randomId),
+      })
+      .signers([authority, mint])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    const extend = (newFinishTs: number) =>
+      main_program.methods
+        .extendStaticPoolDeadline({
+          projectId: { 0: randomId },
+          newFinishTs: new BN(newFinishTs),
+        })
+        .accountsPartial({
+          authority: { authority: authority.publicKey },
+          project: getProjectAddress(randomId),
+          pool: getPoolAddress(mint.publicKey),
+        })
+        .signers([authority])
+        .rpc();
+
+    await expect(extend(finishTs - 60)).to.be.rejectedWith(
+      /DeadlineNotExtended/
+    );
+    await expect(extend(finishTs + 8 * 24 * 60 * 60)).to.be.rejectedWith(
+      /ExtensionTooLong/
+    );
+
+    signature = await extend(finishTs + 24 * 60 * 60);
+    await connection.confirmTransaction(signature);
+    const state = await main_program.account.staticPool.fetch(
+      getPoolAddress(mint.publicKey)
+    );
+    expect(state.config.closeConditions.finishTs.toNumber()).to.eql(
+      finishTs + 24 * 60 * 60
+    );
+  });
+
+  it("second static pool opens after the first one is graduated", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const buyer = anchor.web3.Keypair.generate();
+    const firstMint = anchor.web3.Keypair.generate();
+    const secondMint = anchor.web3.Keypair.generate();
+    const config = {
+      closeConditions: {
+        finishTs: null,
+        maxLamports: new BN(100),
+        gracePeriodSecs: null,
+        minLamports: null,
+      },
+      minPurchaseLamports: null,
+      maxPurchasePerWallet: null,
+    };
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(authority.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 2,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+
+    const createPool = (mint: anchor.web3.Keypair) =>
+      main_program.methods
+        .createStaticPool({ config: config, projectId: { 0: randomId } })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          mint: mint.publicKey,
+          project: getProjectAddress(randomId),
+        })
+        .instruction();
+
+    let transaction = new anchor.web3.Transaction().add(
+      await createPool(firstMint)
+    );
+    await signTransaction(connection, transaction, [authority, firstMint]);
+    await sendTransaction(connection, transaction);
+
+    const amount = restoreFullAmount(new BN(100));
+    const buyerRent = await getBuyerRent();
+    await airdrop(buyer.publicKey, new BN(amount.toNumber() + buyerRent));
+    transaction = await main_program.methods
+      .buyFromStaticPool({ sols: amount, projectId: { 0: randomId } })
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: firstMint.publicKey,
+        user: buyer.publicKey,
+        project: getProjectAddress(randomId),
+      })
+      .transaction();
+    await signTransaction(connection, transaction, [authority, buyer]);
+    await sendTransaction(connection, transaction);
+
+    let project = await main_program.account.project.fetch(
+      getProjectAddress(randomId)
+    );
+    expect(project.stage).to.eql({ staticPoolClosed: {} });
+
+    transaction = new anchor.web3.Transaction()
+      .add(
+        await main_program.methods
+          .graduateStaticPool()
+          .accounts({
+            authority: { authority: authority.publicKey },
+            fundsReceiver: authority.publicKey,
+            pool: getPoolAddress(firstMint.publicKey),
+            project: getProjectAddress(randomId),
+          })
+          .instruction()
+      )
+      .add(await createPool(secondMint));
+    await signTransaction(connection, transaction, [authority, secondMint]);
+    await sendTransaction(connection, transaction);
+
+    project = await main_program.account.project.fetch(
+      getProjectAddress(randomId)
+    );
+    expect(project.stage).to.eql({ secondStaticPoolActive: {} });
+    const state = await main_program.account.staticPool.fetch(
+      getPoolAddress(secondMint.publicKey)
+    );
+    expect(state.state).to.eql({ active: {} });
+  });
+
+  it("wallet cap limits purchases, record rent is returned on graduation", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const buyer = anchor.web3.Keypair.generate();
+    const mint = anchor.web3.Keypair.generate();
+    const pool = getPoolAddress(mint.publicKey);
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(authority.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(buyer.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 1,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+
+    let signature = await main_program.methods
+      .createStaticPool({
+        config: {
+          closeConditions: {
+            finishTs: null,
+            maxLamports: new BN(300),
+            gracePeriodSecs: null,
+            minLamports: null,
+          },
+          minPurchaseLamports: null,
+          maxPurchasePerWallet: new BN(300),
+        },
+        projectId: { 0: randomId },
+      })
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: mint.publicKey,
+        project: getProjectAddress(randomId),
+      })
+      .signers([authority, mint])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    const buy = async (bare: number) => {
+      const transaction = await main_program.methods
+        .buyFromStaticPool({
+          sols: restoreFullAmount(new BN(bare)),
+          projectId: { 0: randomId },
+        })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          mint: mint.publicKey,
+          user: buyer.publicKey,
+          project: getProjectAddress(randomId),
+        })
+        .transaction();
+      await signTransaction(connection, transaction, [authority, buyer]);
+      await sendTransaction(connection, transaction);
+    };
+
+    await buy(200);
+    const recordAddress = getWalletRecordAddress(pool, buyer.publicKey);
+    const record = await main_program.account.staticPoolWalletRecord.fetch(
+      recordAddress
+    );
+    expect(record.wallet).to.eql(buyer.publicKey);
+    expect(record.contributedLamports.toNumber()).to.eql(200);
+    await expect(buy(101)).to.be.rejectedWith(/WalletCapExceeded/);
+    // reaches both the wallet cap and the pool limit
+    await buy(100);
+
+    const closeRecord = () =>
+      main_program.methods
+        .closeStaticPoolWalletRecord()
+        .accounts({
+          authority: { authority: authority.publicKey },
+          mint: mint.publicKey,
+          wallet: buyer.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    await expect(closeRecord()).to.be.rejectedWith(/NotGraduated/);
+
+    signature = await main_program.methods
+      .graduateStaticPool()
+      .accounts({
+        authority: { authority: authority.publicKey },
+        fundsReceiver: authority.publicKey,
+        pool,
+        project: getProjectAddress(randomId),
+      })
+      .signers([authority])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    const recordRent = await connection.getBalance(recordAddress);
+    const buyerBefore = await connection.getBalance(buyer.publicKey);
+    signature = await closeRecord();
+    await connection.confirmTransaction(signature);
+    expect(await connection.getAccountInfo(recordAddress)).to.be.null;
+    expect(await connection.getBalance(buyer.publicKey)).to.eql(
+      buyerBefore + recordRent
+    );
+  });
+
+  it("pool, which missed its goal, refunds buyers", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const buyer = anchor.web3.Keypair.generate();
+    const mint = anchor.web3.Keypair.generate();
+    const pool = getPoolAddress(mint.publicKey);
+    await airdrop(creator.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(authority.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(buyer.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(creator, randomId, {
+      staticPoolCount: 1,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+
+    const finishTs = Math.floor(Date.now() / 1000) + 5;
+    let signature = await main_program.methods
+      .createStaticPool({
+        config: {
+          closeConditions: {
+            finishTs: new BN(finishTs),
+            maxLamports: null,
+            gracePeriodSecs: null,
+            minLamports: new BN(1000),
+          },
+          minPurchaseLamports: null,
+          maxPurchasePerWallet: null,
+        },
+        projectId: { 0: randomId },
+      })
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: mint.publicKey,
+        project: getProjectAddress(randomId),
+      })
+      .signers([authority, mint])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    const transaction = await main_program.methods
+      .buyFromStaticPool({
+        sols: restoreFullAmount(new BN(200)),
+        projectId: { 0: randomId },
+      })
+      .accounts({
+        authority: { authority: authority.publicKey },
+        mint: mint.publicKey,
+        user: buyer.publicKey,
+        project: getProjectAddress(randomId),
+      })
+      .transaction();
+    await signTransaction(connection, transaction, [authority, buyer]);
+    await sendTransaction(connection, transaction);
+    expect(await tokenBalance(mint.publicKey, buyer.publicKey)).to.eql(200);
+
+    const refund = (tokens: number) =>
+      main_program.methods
+        .refundFromStaticPool({
+          tokens: new BN(tokens),
+          projectId: { 0: randomId },
+        })
+        .accounts({
+          authority: authority.publicKey,
+          mint: mint.publicKey,
+          user: buyer.publicKey,
+          project: getProjectAddress(randomId),
+        })
+        .signers([buyer])
+        .rpc();
+    await expect(refund(200)).to.be.rejectedWith(/NotFailed/);
+
+    await delay((finishTs + 2) * 1000 - Date.now());
+    signature = await refund(50);
+    await connection.confirmTransaction(signature);
+    let state = await main_program.account.staticPool.fetch(pool);
+    expect(state.state).to.eql({ failed: {} });
+    expect(state.collectedLamports.toNumber()).to.eql(150);
+    const project = await main_program.account.project.fetch(
+      getProjectAddress(randomId)
+    );
+    expect(project.stage).to.eql({ staticPoolFailed: {} });
+
+    const buyerBefore = await connection.getBalance(buyer.publicKey);
+    signature = await refund(150);
+    await connection.confirmTransaction(signature);
+    expect(await tokenBalance(mint.publicKey, buyer.publicKey)).to.eql(0);
+    // buyer pays the transaction fee
+    expect(await connection.getBalance(buyer.publicKey)).to.be.within(
+      buyerBefore + 150 - 10_000,
+      buyerBefore + 150
+    );
+    expect(await connection.getAccountInfo(pool)).to.be.null;
+  });
 });
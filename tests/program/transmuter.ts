@@ -77,7 +77,7 @@ describe("transmuter", () => {
 
     let randomId = new BN(Math.floor(Math.random() * 100000).toString());
     await createProject(user, randomId, {
-      useStaticPool: false,
+      staticPoolCount: 0,
       curvePool: {
         moonzip: {},
       },
@@ -98,7 +98,7 @@ describe("transmuter", () => {
         projectId: { 0: randomId },
       })
       .accounts({
-        authority: authority.publicKey,
+        authority: { authority: authority.publicKey },
         user: authority.publicKey,
         mint: curveMint.publicKey,
         project: getProjectAddress(randomId),
@@ -109,10 +109,10 @@ describe("transmuter", () => {
     console.log(`bought from curved pool for authority`);
 
     signature = await main_program.methods
-      .initTransmuterForCurve()
+      .initTransmuterForCurve({ expiryTs: null })
       .accounts({
         base: {
-          authority: authority.publicKey,
+          authority: { authority: authority.publicKey },
           fromMint: fromMint.publicKey,
           toMint: curveMint.publicKey,
           donor: authority.publicKey,
@@ -135,9 +135,10 @@ describe("transmuter", () => {
     signature = await main_program.methods
       .transmute({
         tokens: userFromBalance,
+        partial: false,
       })
       .accounts({
-        authority: authority.publicKey,
+        authority: { authority: authority.publicKey },
         user: user.publicKey,
         fromMint: fromMint.publicKey,
         toMint: curveMint.publicKey,
@@ -168,7 +169,7 @@ describe("transmuter", () => {
         projectId: { 0: randomId },
       })
       .accounts({
-        authority: authority.publicKey,
+        authority: { authority: authority.publicKey },
         mint: curveMint.publicKey,
         user: user.publicKey,
         project: getProjectAddress(randomId),
@@ -188,6 +189,151 @@ describe("transmuter", () => {
     expect(addedFee).to.eql(feeAmount(userFromBalance, MZIP_FEE).toNumber());
   });
 
+  async function initCurveTransmuter(
+    user: Keypair,
+    userFromBalance: BN,
+    expiryTs: BN | null
+  ) {
+    const curveMint = anchor.web3.Keypair.generate();
+    const fromMint = anchor.web3.Keypair.generate();
+
+    await airdrop(user.publicKey, new BN(LAMPORTS_PER_SOL));
+    await airdrop(authority.publicKey, new BN(LAMPORTS_PER_SOL));
+
+    await tokenInit(authority, fromMint);
+    await mintToken(
+      authority,
+      fromMint.publicKey,
+      user.publicKey,
+      userFromBalance
+    );
+
+    let randomId = new BN(Math.floor(Math.random() * 100000).toString());
+    await createProject(user, randomId, {
+      staticPoolCount: 0,
+      curvePool: {
+        moonzip: {},
+      },
+      devPurchase: null,
+    });
+
+    const poolAddress = await createCurvedPool(randomId, curveMint);
+
+    let signature = await main_program.methods
+      .buyFromCurvedPool({
+        sols: new BN(LAMPORTS_PER_SOL),
+        minTokenOutput: new BN(0),
+        projectId: { 0: randomId },
+      })
+      .accounts({
+        authority: { authority: authority.publicKey },
+        user: authority.publicKey,
+        mint: curveMint.publicKey,
+        project: getProjectAddress(randomId),
+      })
+      .signers([authority])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    signature = await main_program.methods
+      .initTransmuterForCurve({ expiryTs })
+      .accounts({
+        base: {
+          authority: { authority: authority.publicKey },
+          fromMint: fromMint.publicKey,
+          toMint: curveMint.publicKey,
+          donor: authority.publicKey,
+        },
+        curvedPool: poolAddress,
+      })
+      .signers([authority])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    return {
+      fromMint: fromMint.publicKey,
+      curveMint: curveMint.publicKey,
+      transmuterAddress: getTransmuterAddress(
+        fromMint.publicKey,
+        curveMint.publicKey
+      ),
+    };
+  }
+
+  it("partial transmutation keeps accounts open", async () => {
+    const user = anchor.web3.Keypair.generate();
+    const userFromBalance = new BN("100000");
+    const half = userFromBalance.divn(2);
+    const { fromMint, curveMint, transmuterAddress } =
+      await initCurveTransmuter(user, userFromBalance, null);
+
+    for (const expectedLeft of [half, new BN(0)]) {
+      const signature = await main_program.methods
+        .transmute({
+          tokens: half,
+          partial: true,
+        })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          user: user.publicKey,
+          fromMint,
+          toMint: curveMint,
+        })
+        .signers([authority, user])
+        .rpc();
+      await connection.confirmTransaction(signature);
+
+      // emptied account is still there
+      expect(await tokenBalance(fromMint, user.publicKey)).to.eql(
+        expectedLeft.toNumber()
+      );
+      await main_program.account.transmuter.fetch(transmuterAddress);
+    }
+    expect(await tokenBalance(curveMint, user.publicKey)).to.gt(0);
+  });
+
+  it("expired transmuter refuses transmutation and is closed", async () => {
+    const user = anchor.web3.Keypair.generate();
+    const userFromBalance = new BN("100000");
+    const { fromMint, curveMint, transmuterAddress } =
+      await initCurveTransmuter(user, userFromBalance, new BN(1));
+
+    await expect(
+      main_program.methods
+        .transmute({
+          tokens: userFromBalance,
+          partial: false,
+        })
+        .accounts({
+          authority: { authority: authority.publicKey },
+          user: user.publicKey,
+          fromMint,
+          toMint: curveMint,
+        })
+        .signers([authority, user])
+        .rpc()
+    ).to.be.rejectedWith(/Expired/);
+
+    const authorityBefore = await connection.getBalance(authority.publicKey);
+    const signature = await main_program.methods
+      .closeExpiredTransmuter()
+      .accounts({
+        authority: { authority: authority.publicKey },
+        toMint: curveMint,
+        transmuter: transmuterAddress,
+      })
+      .signers([authority])
+      .rpc();
+    await connection.confirmTransaction(signature);
+
+    await expect(
+      main_program.account.transmuter.fetch(transmuterAddress)
+    ).to.be.rejectedWith(/Account does not exist/);
+    expect(await connection.getBalance(authority.publicKey)).to.be.gt(
+      authorityBefore
+    );
+  });
+
   it("happy path for pumpfun bonding curve", async () => {
     const user = anchor.web3.Keypair.generate();
     const curveMint = anchor.web3.Keypair.generate();
@@ -224,10 +370,10 @@ describe("transmuter", () => {
     );
 
     let signature = await main_program.methods
-      .initTransmuterForPumpfunCurve()
+      .initTransmuterForPumpfunCurve({ expiryTs: null })
       .accounts({
         base: {
-          authority: authority.publicKey,
+          authority: { authority: authority.publicKey },
           fromMint: fromMint.publicKey,
           toMint: curveMint.publicKey,
           donor: authority.publicKey,
@@ -250,9 +396,10 @@ describe("transmuter", () => {
     signature = await main_program.methods
       .transmute({
         tokens: userFromBalance,
+        partial: false,
       })
       .accounts({
-        authority: authority.publicKey,
+        authority: { authority: authority.publicKey },
         user: user.publicKey,
         fromMint: fromMint.publicKey,
         toMint: curveMint.publicKey,
@@ -8,4 +8,5 @@ for i in {0..15}; do
   solana-keygen new --no-passphrase -o $BASE_KEYS_DIR/key_$i.json &
 done
 
-migrator
+# exec, so SIGTERM reaches migrator and it could shut down gracefully
+exec migrator
@@ -183,7 +183,7 @@ export async function createProject(owner: Keypair, projectId: BN, schema) {
       creatorDeposit: new BN(0),
     })
     .accounts({
-      authority: authority.publicKey,
+      authority: { authority: authority.publicKey },
       creator: owner.publicKey,
       project: address,
     })
@@ -201,15 +201,23 @@ export function pumpfunLikeConfig() {
       initialVirtualSolReserves: new BN("30000000000"),
       initialRealTokenReserves: new BN("793100000000000"),
       totalTokenSupply: new BN("1000000000000000"),
+      kind: { constantProduct: {} },
     },
     tokenDecimals: 6,
     pool: {
       minTradeableSol: new BN(1000),
       minSolToClose: new BN(LAMPORTS_PER_SOL * 1e-5),
+      maxWalletTokens: null,
+      antiSnipeSlots: null,
+      creatorFeeShareBps: null,
     },
+    projectCreationFeeLamports: new BN(0),
+    maxDeadlineExtensionSecs: new BN(7 * 24 * 60 * 60),
   };
 }
 
+export const FEE_RECEIVER = Keypair.generate();
+
 let CONFIG_INIT = false;
 
 export async function provideGlobalConfig() {
@@ -223,7 +231,7 @@ export async function provideGlobalConfig() {
   let signature = await main_program.methods
     .setCurvedPoolGlobalConfig(pumpfunLikeConfig())
     .accounts({
-      authority: authority.publicKey,
+      authority: { authority: authority.publicKey },
     })
     .signers([authority])
     .rpc();
@@ -232,11 +240,13 @@ export async function provideGlobalConfig() {
 
   signature = await main_program.methods
     .setFeeConfig({
-      onBuy: { 0: MZIP_FEE },
-      onSell: { 0: MZIP_FEE },
+      onBuy: { percentage: { 0: { 0: MZIP_FEE } } },
+      onSell: { percentage: { 0: { 0: MZIP_FEE } } },
+      dynamic: { volumeThresholds: [] },
     })
     .accounts({
-      authority: authority.publicKey,
+      authority: { authority: authority.publicKey },
+      feeReceiver: FEE_RECEIVER.publicKey,
     })
     .signers([authority])
     .rpc();